@@ -0,0 +1,39 @@
+import { mkdirSync, writeFileSync } from "node:fs";
+import { join, relative } from "node:path";
+
+// LibreOffice's en_US dictionary, built from Kevin Atkinson's SCOWL word lists.
+// Set DICTIONARIES_REF to a tag or commit of LibreOffice/dictionaries to pin it.
+const ref = process.env.DICTIONARIES_REF || "master";
+const baseUrl = `https://raw.githubusercontent.com/LibreOffice/dictionaries/${ref}/en`;
+const files = ["en_US.aff", "en_US.dic", "README_en_US.txt"];
+
+const rootDir = process.cwd();
+const outDir = join(rootDir, "src-tauri", "dictionaries");
+
+// The real dictionary has roughly 50k stems; anything much smaller is not it
+const minimumEntries = 40000;
+
+mkdirSync(outDir, { recursive: true });
+
+for (const file of files) {
+  const url = `${baseUrl}/${file}`;
+  const response = await fetch(url);
+  if (!response.ok) {
+    console.error(`Failed to download ${url}: ${response.status} ${response.statusText}`);
+    process.exit(1);
+  }
+  const content = Buffer.from(await response.arrayBuffer());
+
+  if (file.endsWith(".dic")) {
+    // The first line of a Hunspell .dic is its entry count
+    const entries = Number.parseInt(content.toString("utf8", 0, 32).split(/\r?\n/)[0], 10);
+    if (!(entries >= minimumEntries)) {
+      console.error(`${url} lists ${entries} entries; expected at least ${minimumEntries}`);
+      process.exit(1);
+    }
+  }
+
+  const target = join(outDir, file);
+  writeFileSync(target, content);
+  console.log(`Downloaded ${relative(rootDir, target)} (${content.length} bytes)`);
+}
@@ -0,0 +1,226 @@
+//! Procedural macros for Fluxel.
+//!
+//! `#[fluxel_command]` registers a function as a Tauri command, like
+//! `#[tauri::command]`, and in profiling builds opens a `tauri_command` span for
+//! every call, named after the command and carrying a summary of its arguments.
+//! Arguments that hold credentials are recorded by name only.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, TokenTree};
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Attribute, FnArg, Ident, ItemFn, Meta, Pat, Path, Token, Type};
+
+/// Arguments Tauri injects rather than deserializing from the frontend.
+const INJECTED_TYPES: &[&str] = &[
+    "State",
+    "AppHandle",
+    "Window",
+    "WebviewWindow",
+    "Webview",
+    "Channel",
+    "Request",
+];
+
+/// Arguments whose values are never recorded, only their names. Also matched as
+/// a `_`-separated suffix, e.g. `access_token` or `github_api_key`.
+const REDACTED_ARGS: &[&str] = &[
+    "token", "api_key", "value", "password", "secret", "provider",
+];
+
+/// Arguments recorded as span fields, so the profiler can filter spans by them.
+const FIELD_ARGS: &[(&str, &str)] = &[
+    ("workspace_root", "workspace_root"),
+    ("root_path", "workspace_root"),
+    ("file_path", "file"),
+];
+
+/// Register a Tauri command and, with the `profiling` feature, record a span for
+/// each call.
+///
+/// Arguments are passed on to `#[tauri::command]`, except `skip(a, b)`, which
+/// leaves the named arguments out of the span. Injected arguments such as `State`
+/// and `AppHandle` are left out as well; arguments named like a credential
+/// (`token`, `api_key`, `value`, `password`, `secret`, `provider`, or ending in
+/// `_token` and so on) are recorded as `<redacted>`; the others are recorded with
+/// their `Debug` output, truncated. `workspace_root`, `root_path` and `file_path`
+/// strings also become the `workspace_root` and `file` fields.
+///
+/// Commands with their own `tracing::instrument` attribute placed after this one
+/// keep it instead.
+#[proc_macro_attribute]
+pub fn fluxel_command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+    let (skipped, attr) = match split_skip(attr.into()) {
+        Ok(split) => split,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    for ident in &skipped {
+        let known = func.sig.inputs.iter().any(|input| {
+            matches!(input, FnArg::Typed(arg)
+                if matches!(&*arg.pat, Pat::Ident(pat) if pat.ident == *ident))
+        });
+        if !known {
+            return syn::Error::new(ident.span(), format!("no argument named `{}`", ident))
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    let command = if attr.is_empty() {
+        quote!(#[tauri::command])
+    } else {
+        quote!(#[tauri::command(#attr)])
+    };
+    if func.attrs.iter().any(is_instrument) {
+        return quote!(#command #func).into();
+    }
+
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    let mut fields = Vec::new();
+    for input in &func.sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            continue;
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            continue;
+        };
+        let ident = &pat.ident;
+        if is_injected(&arg.ty) || skipped.contains(ident) {
+            continue;
+        }
+        let name = ident.to_string();
+        if is_redacted(&name) {
+            names.push(name);
+            values.push(quote!(crate::profiling::args::REDACTED.to_string()));
+            continue;
+        }
+        if let Some((_, field)) = FIELD_ARGS.iter().find(|(arg, _)| *arg == name) {
+            if is_string(&arg.ty) {
+                let field = syn::Ident::new(field, ident.span());
+                fields.push(quote!(#field = %#ident));
+            }
+        }
+        names.push(name);
+        values.push(quote!((&crate::profiling::args::ArgValue(&#ident)).summarize()));
+    }
+
+    let args = if names.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            args = %{
+                #[allow(unused_imports)]
+                use crate::profiling::args::{DebugArg as _, OpaqueArg as _};
+                crate::profiling::args::join(&[#((#names, #values)),*])
+            },
+        }
+    };
+
+    quote! {
+        #[cfg_attr(
+            feature = "profiling",
+            tracing::instrument(skip_all, fields(category = "tauri_command", #args #(#fields),*))
+        )]
+        #command
+        #func
+    }
+    .into()
+}
+
+/// Take `skip(...)` out of the attribute arguments, leaving the rest for
+/// `#[tauri::command]`.
+fn split_skip(
+    attr: proc_macro2::TokenStream,
+) -> syn::Result<(Vec<Ident>, proc_macro2::TokenStream)> {
+    let mut skipped = Vec::new();
+    let mut rest = Vec::new();
+    let mut entry: Vec<TokenTree> = Vec::new();
+    // A trailing separator flushes the last entry
+    let separator = TokenTree::Punct(proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone));
+    for token in attr.into_iter().chain(std::iter::once(separator)) {
+        if !matches!(&token, TokenTree::Punct(p) if p.as_char() == ',') {
+            entry.push(token);
+            continue;
+        }
+        match entry.as_slice() {
+            [] => {}
+            [TokenTree::Ident(name), TokenTree::Group(group)]
+                if name == "skip" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                let idents = syn::parse::Parser::parse2(
+                    Punctuated::<Ident, Token![,]>::parse_terminated,
+                    group.stream(),
+                )?;
+                skipped.extend(idents);
+            }
+            _ => {
+                if !rest.is_empty() {
+                    rest.push(quote!(,));
+                }
+                let tokens: proc_macro2::TokenStream = entry.iter().cloned().collect();
+                rest.push(tokens);
+            }
+        }
+        entry.clear();
+    }
+    Ok((skipped, rest.into_iter().collect()))
+}
+
+fn is_redacted(name: &str) -> bool {
+    REDACTED_ARGS.iter().any(|redacted| {
+        name == *redacted
+            || name
+                .strip_suffix(redacted)
+                .is_some_and(|prefix| prefix.ends_with('_'))
+    })
+}
+
+/// Whether the attribute is `#[instrument]` or `#[tracing::instrument]`, directly
+/// or inside `#[cfg_attr(..., ...)]`.
+fn is_instrument(attr: &Attribute) -> bool {
+    if is_instrument_path(attr.path()) {
+        return true;
+    }
+    if !attr.path().is_ident("cfg_attr") {
+        return false;
+    }
+    // The first entry is the condition; the rest are the attributes it applies
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .is_ok_and(|metas| {
+            metas
+                .iter()
+                .skip(1)
+                .any(|meta| is_instrument_path(meta.path()))
+        })
+}
+
+fn is_instrument_path(path: &Path) -> bool {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    segments == ["instrument"] || segments == ["tracing", "instrument"]
+}
+
+/// Whether the argument is supplied by Tauri, e.g. `State<'_, T>` or `AppHandle<R>`.
+fn is_injected(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_injected(&reference.elem),
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| INJECTED_TYPES.contains(&segment.ident.to_string().as_str())),
+        _ => false,
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_string(&reference.elem),
+        Type::Path(path) => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| ident == "String" || ident == "str"),
+        _ => false,
+    }
+}
@@ -0,0 +1,258 @@
+//! Declaration file symbol extraction.
+//! Lists the interfaces, type aliases, classes, functions, namespaces, enums and
+//! variables declared in a `.d.ts` file, so editors can prime completions without
+//! loading the whole file.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::{
+    Decl, DefaultDecl, ExportSpecifier, Expr, ModuleDecl, ModuleItem, Pat, Stmt, TsModuleDecl,
+    TsModuleName, TsNamespaceBody,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DtsSymbolKind {
+    Interface,
+    TypeAlias,
+    Class,
+    Function,
+    Namespace,
+    Enum,
+    Variable,
+    /// `declare module "name" { ... }`
+    AmbientModule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DtsSymbol {
+    pub name: String,
+    pub kind: DtsSymbolKind,
+    /// Dotted path of the enclosing namespaces or ambient module, e.g. `NodeJS.Process`
+    pub container: Option<String>,
+    /// Visible to importers: exported, named by `export =` or an export list,
+    /// declared in a namespace, or global because the file has no imports or exports
+    pub exported: bool,
+    /// Byte offset of the name
+    pub start: usize,
+}
+
+/// Parse a declaration file and list the symbols it declares, in source order.
+/// Overloads and merged declarations are reported once.
+pub fn extract_dts_symbols(path: &Utf8Path) -> Result<Vec<DtsSymbol>> {
+    let (_, start_pos, module) = crate::parse_module_file(path)?;
+
+    let is_script = !module
+        .body
+        .iter()
+        .any(|item| matches!(item, ModuleItem::ModuleDecl(_)));
+    let mut collector = DtsCollector {
+        start_pos,
+        symbols: Vec::new(),
+        seen: HashSet::new(),
+        exported_names: HashSet::new(),
+    };
+    collector.collect_items(&module.body, None, is_script);
+
+    let DtsCollector {
+        mut symbols,
+        exported_names,
+        ..
+    } = collector;
+    for symbol in &mut symbols {
+        if symbol.container.is_none() && exported_names.contains(&symbol.name) {
+            symbol.exported = true;
+        }
+    }
+    Ok(symbols)
+}
+
+struct DtsCollector {
+    start_pos: BytePos,
+    symbols: Vec<DtsSymbol>,
+    seen: HashSet<(Option<String>, String, DtsSymbolKind)>,
+    /// Top-level names exported separately from their declaration
+    exported_names: HashSet<String>,
+}
+
+impl DtsCollector {
+    fn push(
+        &mut self,
+        name: String,
+        kind: DtsSymbolKind,
+        container: Option<&str>,
+        exported: bool,
+        lo: BytePos,
+    ) {
+        let container = container.map(str::to_string);
+        if !self.seen.insert((container.clone(), name.clone(), kind)) {
+            return;
+        }
+        self.symbols.push(DtsSymbol {
+            name,
+            kind,
+            container,
+            exported,
+            start: (lo.0 - self.start_pos.0) as usize,
+        });
+    }
+
+    /// `visible` marks declarations without `export` as exported: top-level
+    /// declarations of a script, and namespace members.
+    fn collect_items(&mut self, items: &[ModuleItem], container: Option<&str>, visible: bool) {
+        for item in items {
+            match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => self.collect_decl(decl, container, visible),
+                ModuleItem::Stmt(_) => {}
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                    self.collect_decl(&export.decl, container, true)
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+                    let (ident, kind) = match &export.decl {
+                        DefaultDecl::Class(class) => (class.ident.as_ref(), DtsSymbolKind::Class),
+                        DefaultDecl::Fn(func) => (func.ident.as_ref(), DtsSymbolKind::Function),
+                        DefaultDecl::TsInterfaceDecl(interface) => {
+                            (Some(&interface.id), DtsSymbolKind::Interface)
+                        }
+                    };
+                    match ident {
+                        Some(ident) => {
+                            self.push(ident.sym.to_string(), kind, container, true, ident.span.lo)
+                        }
+                        None => {
+                            self.push("default".to_string(), kind, container, true, export.span.lo)
+                        }
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(named)) if named.src.is_none() => {
+                    for spec in &named.specifiers {
+                        if let ExportSpecifier::Named(named) = spec {
+                            self.exported_names.insert(crate::export_name(&named.orig));
+                        }
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(assignment)) => {
+                    if let Expr::Ident(ident) = &*assignment.expr {
+                        self.exported_names.insert(ident.sym.to_string());
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+                    if let Expr::Ident(ident) = &*export.expr {
+                        self.exported_names.insert(ident.sym.to_string());
+                    }
+                }
+                ModuleItem::ModuleDecl(_) => {}
+            }
+        }
+    }
+
+    fn collect_decl(&mut self, decl: &Decl, container: Option<&str>, exported: bool) {
+        match decl {
+            Decl::Class(class) => self.push(
+                class.ident.sym.to_string(),
+                DtsSymbolKind::Class,
+                container,
+                exported,
+                class.ident.span.lo,
+            ),
+            Decl::Fn(func) => self.push(
+                func.ident.sym.to_string(),
+                DtsSymbolKind::Function,
+                container,
+                exported,
+                func.ident.span.lo,
+            ),
+            Decl::Var(var) => {
+                for declarator in &var.decls {
+                    if let Pat::Ident(binding) = &declarator.name {
+                        self.push(
+                            binding.id.sym.to_string(),
+                            DtsSymbolKind::Variable,
+                            container,
+                            exported,
+                            binding.id.span.lo,
+                        );
+                    }
+                }
+            }
+            Decl::TsInterface(interface) => self.push(
+                interface.id.sym.to_string(),
+                DtsSymbolKind::Interface,
+                container,
+                exported,
+                interface.id.span.lo,
+            ),
+            Decl::TsTypeAlias(alias) => self.push(
+                alias.id.sym.to_string(),
+                DtsSymbolKind::TypeAlias,
+                container,
+                exported,
+                alias.id.span.lo,
+            ),
+            Decl::TsEnum(ts_enum) => self.push(
+                ts_enum.id.sym.to_string(),
+                DtsSymbolKind::Enum,
+                container,
+                exported,
+                ts_enum.id.span.lo,
+            ),
+            Decl::TsModule(ts_module) => self.collect_namespace(ts_module, container, exported),
+            _ => {}
+        }
+    }
+
+    fn collect_namespace(&mut self, decl: &TsModuleDecl, container: Option<&str>, exported: bool) {
+        // `declare global { ... }` adds to the global scope
+        if decl.global {
+            if let Some(TsNamespaceBody::TsModuleBlock(block)) = &decl.body {
+                self.collect_items(&block.body, None, true);
+            }
+            return;
+        }
+
+        let (name, kind, lo) = match &decl.id {
+            TsModuleName::Ident(ident) => (
+                ident.sym.to_string(),
+                DtsSymbolKind::Namespace,
+                ident.span.lo,
+            ),
+            TsModuleName::Str(name) => (
+                name.value.as_str().unwrap_or("").to_string(),
+                DtsSymbolKind::AmbientModule,
+                name.span.lo,
+            ),
+        };
+        // Ambient module declarations are global wherever they appear
+        let exported = exported || kind == DtsSymbolKind::AmbientModule;
+        self.push(name.clone(), kind, container, exported, lo);
+
+        let mut path = qualified(container, &name);
+        let mut body = decl.body.as_ref();
+        // `namespace A.B { ... }` nests B inside A
+        while let Some(TsNamespaceBody::TsNamespaceDecl(inner)) = body {
+            let inner_name = inner.id.sym.to_string();
+            self.push(
+                inner_name.clone(),
+                DtsSymbolKind::Namespace,
+                Some(&path),
+                true,
+                inner.id.span.lo,
+            );
+            path = qualified(Some(&path), &inner_name);
+            body = Some(&*inner.body);
+        }
+        if let Some(TsNamespaceBody::TsModuleBlock(block)) = body {
+            self.collect_items(&block.body, Some(&path), true);
+        }
+    }
+}
+
+fn qualified(container: Option<&str>, name: &str) -> String {
+    match container {
+        Some(container) => format!("{}.{}", container, name),
+        None => name.to_string(),
+    }
+}
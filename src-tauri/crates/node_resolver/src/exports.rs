@@ -0,0 +1,126 @@
+//! Export listing.
+//! Lists the names a module exports from its own declarations, with their
+//! positions, so project-wide analyses can match them against what other files
+//! import. Re-exports with a `from` clause are left to
+//! `collect_import_specifiers_native`, which reports them with their source.
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::{
+    Decl, DefaultDecl, ExportSpecifier, ModuleDecl, ModuleItem, Pat, TsModuleName,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedName {
+    /// Exported name; `default` for default exports and `export =`
+    pub name: String,
+    /// Interfaces, type aliases and `export type { ... }`, erased at compile time
+    pub type_only: bool,
+    /// Byte offset of the name, or of the statement when it has none
+    pub start: usize,
+}
+
+/// Parse a module and list the names it exports from its own declarations, in
+/// source order. CommonJS `module.exports` assignments aren't reported.
+pub fn collect_exports_native(module_path: &Utf8Path) -> Result<Vec<ExportedName>> {
+    let (_, start_pos, module) = crate::parse_module_file(module_path)?;
+
+    let mut exports = Vec::new();
+    let mut push = |name: String, type_only: bool, lo: BytePos| {
+        exports.push(ExportedName {
+            name,
+            type_only,
+            start: (lo.0 - start_pos.0) as usize,
+        });
+    };
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(decl) = item else {
+            continue;
+        };
+        match decl {
+            ModuleDecl::ExportDecl(export) => match &export.decl {
+                Decl::Class(class) => push(class.ident.sym.to_string(), false, class.ident.span.lo),
+                Decl::Fn(func) => push(func.ident.sym.to_string(), false, func.ident.span.lo),
+                Decl::Var(var) => {
+                    for declarator in &var.decls {
+                        let mut names = Vec::new();
+                        binding_names(&declarator.name, &mut names);
+                        for (name, lo) in names {
+                            push(name, false, lo);
+                        }
+                    }
+                }
+                Decl::TsInterface(interface) => {
+                    push(interface.id.sym.to_string(), true, interface.id.span.lo)
+                }
+                Decl::TsTypeAlias(alias) => push(alias.id.sym.to_string(), true, alias.id.span.lo),
+                Decl::TsEnum(ts_enum) => {
+                    push(ts_enum.id.sym.to_string(), false, ts_enum.id.span.lo)
+                }
+                Decl::TsModule(ts_module) => {
+                    if let TsModuleName::Ident(ident) = &ts_module.id {
+                        push(ident.sym.to_string(), false, ident.span.lo);
+                    }
+                }
+                _ => {}
+            },
+            ModuleDecl::ExportNamed(named) if named.src.is_none() => {
+                for spec in &named.specifiers {
+                    if let ExportSpecifier::Named(spec) = spec {
+                        let exported = spec.exported.as_ref().unwrap_or(&spec.orig);
+                        push(
+                            crate::export_name(exported),
+                            named.type_only || spec.is_type_only,
+                            spec.span.lo,
+                        );
+                    }
+                }
+            }
+            ModuleDecl::ExportDefaultDecl(export) => {
+                let type_only = matches!(export.decl, DefaultDecl::TsInterfaceDecl(_));
+                push("default".to_string(), type_only, export.span.lo);
+            }
+            ModuleDecl::ExportDefaultExpr(export) => {
+                push("default".to_string(), false, export.span.lo)
+            }
+            ModuleDecl::TsExportAssignment(assignment) => {
+                push("default".to_string(), false, assignment.span.lo)
+            }
+            _ => {}
+        }
+    }
+    Ok(exports)
+}
+
+/// Names bound by a declarator, including destructured ones
+fn binding_names(pat: &Pat, names: &mut Vec<(String, BytePos)>) {
+    match pat {
+        Pat::Ident(binding) => names.push((binding.id.sym.to_string(), binding.id.span.lo)),
+        Pat::Array(array) => {
+            for elem in array.elems.iter().flatten() {
+                binding_names(elem, names);
+            }
+        }
+        Pat::Object(object) => {
+            for prop in &object.props {
+                match prop {
+                    swc_core::ecma::ast::ObjectPatProp::KeyValue(kv) => {
+                        binding_names(&kv.value, names)
+                    }
+                    swc_core::ecma::ast::ObjectPatProp::Assign(assign) => {
+                        names.push((assign.key.sym.to_string(), assign.key.span.lo))
+                    }
+                    swc_core::ecma::ast::ObjectPatProp::Rest(rest) => {
+                        binding_names(&rest.arg, names)
+                    }
+                }
+            }
+        }
+        Pat::Assign(assign) => binding_names(&assign.left, names),
+        Pat::Rest(rest) => binding_names(&rest.arg, names),
+        _ => {}
+    }
+}
@@ -0,0 +1,218 @@
+//! Transitive dependency graph walking.
+//! Starting from an entry file, resolves and parses imports breadth-first up to a
+//! depth and file limit, collecting the files reached, the import edges between
+//! them, and the specifiers that could not be resolved.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collect_import_specifiers_native, detect_format, normalize_path, resolve_module_native,
+    ImportKind, ModuleFormat, ResolveOptions, ResolveRequest,
+};
+
+/// File extensions whose imports are followed.
+const PARSEABLE_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphOptions {
+    /// How many imports deep to follow; files at this depth are listed but not parsed
+    pub max_depth: usize,
+    /// How many files to parse at most
+    pub max_files: usize,
+    /// Follow imports inside node_modules packages instead of stopping at their entry
+    pub follow_packages: bool,
+    pub resolve: ResolveOptions,
+}
+
+impl Default for GraphOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            max_files: 500,
+            follow_packages: false,
+            resolve: ResolveOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Resolved file path, or the specifier of a Node.js built-in
+    pub id: String,
+    pub format: ModuleFormat,
+    /// Import hops from the entry file
+    pub depth: usize,
+    /// Inside node_modules or a Plug'n'Play cache
+    pub external: bool,
+    /// The file could not be read or parsed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub specifier: String,
+    pub kind: ImportKind,
+    pub type_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedImport {
+    pub importer: String,
+    pub specifier: String,
+    pub kind: ImportKind,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleGraph {
+    /// The entry file first, then in the order they were reached
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub unresolved: Vec<UnresolvedImport>,
+    /// The depth or file limit stopped the walk before every file was parsed
+    pub truncated: bool,
+}
+
+/// Walk the imports of `entry` and everything it reaches.
+pub fn walk_module_graph_native(
+    entry: &Utf8Path,
+    project_root: Option<&Utf8Path>,
+    options: &GraphOptions,
+) -> Result<ModuleGraph> {
+    let entry_id = node_id(entry);
+    let mut graph = ModuleGraph {
+        nodes: vec![GraphNode {
+            id: entry_id.clone(),
+            format: detect_format(entry),
+            depth: 0,
+            external: false,
+            error: None,
+        }],
+        edges: Vec::new(),
+        unresolved: Vec::new(),
+        truncated: false,
+    };
+    let mut index: HashMap<String, usize> = HashMap::from([(entry_id, 0)]);
+    let mut queue = VecDeque::from([0]);
+    let mut parsed = 0;
+
+    while let Some(node) = queue.pop_front() {
+        let (id, depth) = (graph.nodes[node].id.clone(), graph.nodes[node].depth);
+        if depth >= options.max_depth || parsed >= options.max_files {
+            graph.truncated = true;
+            continue;
+        }
+        parsed += 1;
+
+        let specifiers = match collect_import_specifiers_native(Utf8Path::new(&id)) {
+            Ok(specifiers) => specifiers,
+            Err(err) => {
+                // The entry itself has to be readable
+                if node == 0 {
+                    return Err(err);
+                }
+                graph.nodes[node].error = Some(err.to_string());
+                continue;
+            }
+        };
+
+        let mut seen = HashSet::new();
+        for import in specifiers {
+            if !seen.insert((import.specifier.clone(), import.kind)) {
+                continue;
+            }
+            let response = resolve_module_native(
+                ResolveRequest {
+                    specifier: import.specifier.clone(),
+                    importer: id.clone(),
+                    project_root: project_root.map(|root| root.to_string()),
+                },
+                Some(options.resolve.clone()),
+            );
+            let response = match response {
+                Ok(response) if !response.disabled => response,
+                Ok(_) => continue,
+                Err(err) => {
+                    graph.unresolved.push(UnresolvedImport {
+                        importer: id.clone(),
+                        specifier: import.specifier,
+                        kind: import.kind,
+                        warnings: vec![err.to_string()],
+                    });
+                    continue;
+                }
+            };
+
+            // Packages in Plug'n'Play archives have no path on disk
+            let archived = response.pnp.as_ref().and_then(|pnp| {
+                Some(format!(
+                    "{}/{}",
+                    pnp.zip_path.as_ref()?,
+                    pnp.inner_path.as_ref()?
+                ))
+            });
+            let target = if let Some(path) = &response.resolved_path {
+                node_id(Utf8Path::new(path))
+            } else if matches!(response.format, ModuleFormat::NodeBuiltin) {
+                import.specifier.clone()
+            } else if let Some(archived) = &archived {
+                archived.clone()
+            } else {
+                graph.unresolved.push(UnresolvedImport {
+                    importer: id.clone(),
+                    specifier: import.specifier,
+                    kind: import.kind,
+                    warnings: response.warnings,
+                });
+                continue;
+            };
+
+            let to = *index.entry(target.clone()).or_insert_with(|| {
+                let external = response.pnp.is_some()
+                    || Utf8Path::new(&target)
+                        .components()
+                        .any(|c| c.as_str() == "node_modules");
+                let followed = archived.is_none()
+                    && (options.follow_packages || !external)
+                    && is_parseable(Utf8Path::new(&target));
+                graph.nodes.push(GraphNode {
+                    id: target.clone(),
+                    format: response.format.clone(),
+                    depth: depth + 1,
+                    external,
+                    error: None,
+                });
+                if followed {
+                    queue.push_back(graph.nodes.len() - 1);
+                }
+                graph.nodes.len() - 1
+            });
+            graph.edges.push(GraphEdge {
+                from: id.clone(),
+                to: graph.nodes[to].id.clone(),
+                specifier: import.specifier,
+                kind: import.kind,
+                type_only: import.type_only,
+            });
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Normalized, forward-slash path, so a file reached through different specifiers
+/// is one node
+fn node_id(path: &Utf8Path) -> String {
+    normalize_path(path).as_str().replace('\\', "/")
+}
+
+fn is_parseable(path: &Utf8Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| PARSEABLE_EXTENSIONS.contains(&ext))
+}
@@ -2,6 +2,16 @@
 //! Provides Rust-native functions for resolving Node.js modules, discovering package typings,
 //! and analyzing module dependency graphs.
 
+mod dts;
+mod exports;
+mod graph;
+mod pnp;
+mod trace;
+mod transform;
+mod types_versions;
+mod typings;
+mod workspaces;
+
 use std::collections::HashSet;
 use std::fs;
 
@@ -9,16 +19,30 @@ use anyhow::{Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use swc_core::common::{sync::Lrc, FileName, SourceMap};
+use swc_core::common::{sync::Lrc, BytePos, FileName, SourceMap, Span};
 use swc_core::ecma::ast::EsVersion;
 use swc_core::ecma::ast::{
-    Decl, DefaultDecl, ExportDecl, ExportDefaultDecl, ExportDefaultExpr, ExportSpecifier,
-    ImportDecl, ModuleDecl, ModuleItem, Pat,
+    CallExpr, Callee, Decl, DefaultDecl, ExportAll, ExportDecl, ExportDefaultDecl,
+    ExportDefaultExpr, ExportSpecifier, Expr, ImportDecl, Lit, Module, ModuleDecl,
+    ModuleExportName, ModuleItem, NamedExport, Pat,
 };
 use swc_core::ecma::parser::{EsSyntax, Parser, StringInput, Syntax, TsSyntax};
-use swc_core::ecma::visit::Visit;
+use swc_core::ecma::visit::{Visit, VisitWith};
 use thiserror::Error;
 
+pub use dts::{extract_dts_symbols, DtsSymbol, DtsSymbolKind};
+pub use exports::{collect_exports_native, ExportedName};
+pub use graph::{
+    walk_module_graph_native, GraphEdge, GraphNode, GraphOptions, ModuleGraph, UnresolvedImport,
+};
+use pnp::PnpLookup;
+pub use pnp::PnpResolution;
+pub use trace::ResolutionStep;
+use trace::Trace;
+pub use transform::{ModuleTarget, TransformOptions};
+use types_versions::{declaration_file, TypesVersions};
+pub use typings::{bundle_typings_native, BundleOptions, TypingsBundle, TypingsFile};
+
 #[derive(Debug, Error)]
 enum ResolveError {
     #[error("specifier is empty")]
@@ -29,11 +53,26 @@ enum ResolveError {
     PackageJson(String),
 }
 
+/// Environment the resolved code runs in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Platform {
+    #[default]
+    Node,
+    /// Apply package.json `browser` field mappings and the `browser` condition,
+    /// as bundlers do
+    Browser,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolveOptions {
     pub conditions: Vec<String>,
     pub extensions: Vec<String>,
     pub prefer_cjs: bool,
+    #[serde(default)]
+    pub platform: Platform,
+    /// Record the steps taken in `ResolveResponse::trace`
+    #[serde(default)]
+    pub trace: bool,
 }
 
 impl Default for ResolveOptions {
@@ -48,6 +87,8 @@ impl Default for ResolveOptions {
                 ".cjs".to_string(),
             ],
             prefer_cjs: false,
+            platform: Platform::Node,
+            trace: false,
         }
     }
 }
@@ -64,6 +105,8 @@ pub enum ModuleFormat {
     Esm,
     CommonJs,
     TypeDefinition,
+    /// A Node.js built-in such as `fs` or `node:path`; there is no file to resolve
+    NodeBuiltin,
     Unknown,
 }
 
@@ -74,6 +117,17 @@ pub struct ResolveResponse {
     pub matched_export: Option<String>,
     pub package_json: Option<String>,
     pub warnings: Vec<String>,
+    /// Set when the package was resolved through a Yarn Plug'n'Play manifest.
+    /// Packages inside cache archives have no `resolved_path`; this locates them instead.
+    #[serde(default)]
+    pub pnp: Option<PnpResolution>,
+    /// The browser field maps the module to `false`; bundlers substitute an empty module
+    #[serde(default)]
+    pub disabled: bool,
+    /// Directories and files probed, conditions evaluated and fallbacks taken, in
+    /// order; empty unless `ResolveOptions::trace` is set
+    #[serde(default)]
+    pub trace: Vec<ResolutionStep>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,16 +144,57 @@ pub struct AnalyzeResponse {
     pub transformed: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImportKind {
+    Import,
+    ReExport,
+    DynamicImport,
+    Require,
+}
+
+/// A name brought in by an import or re-export.
+/// `export * from` is reported as a namespace binding named `*`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportBinding {
+    Default { local: String },
+    Namespace { local: String },
+    Named { imported: String, local: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSpecifier {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// Bindings of static imports and re-exports; empty for `import()`, `require()`
+    /// and side-effect-only imports. Bindings marked `type` inline are listed in
+    /// `type_bindings` instead.
+    pub bindings: Vec<ImportBinding>,
+    /// Bindings marked `type` inline, e.g. `import { type Props, render }`
+    #[serde(default)]
+    pub type_bindings: Vec<ImportBinding>,
+    /// `import type` / `export type`, erased at compile time
+    pub type_only: bool,
+    /// Byte offset of the specifier text, excluding the surrounding quotes.
+    pub start: usize,
+    /// Exclusive byte offset where the specifier text ends.
+    pub end: usize,
+}
+
 /// Resolve a module using Node semantics.
 pub fn resolve_module_native(
     req: ResolveRequest,
     options: Option<ResolveOptions>,
 ) -> Result<ResolveResponse> {
     let opts = options.unwrap_or_default();
+    let mut trace = Trace::new(opts.trace);
     let mut conditions = opts.conditions.clone();
     if opts.prefer_cjs && !conditions.iter().any(|c| c == "require") {
         conditions.insert(0, "require".to_string());
     }
+    let browser = opts.platform == Platform::Browser;
+    if browser && !conditions.iter().any(|c| c == "browser") {
+        conditions.insert(0, "browser".to_string());
+    }
     if req.specifier.trim().is_empty() {
         return Err(ResolveError::EmptySpecifier.into());
     }
@@ -108,48 +203,299 @@ pub fn resolve_module_native(
         .parent()
         .map(|p| p.to_owned())
         .ok_or(ResolveError::MissingImporter)?;
+    // Like Node, resolve from the real path of files inside packages, so symlinked
+    // packages (pnpm, workspace links) find their own dependencies
+    let importer_dir = if importer_dir
+        .components()
+        .any(|c| c.as_str() == "node_modules")
+    {
+        real_path(&importer_dir)
+    } else {
+        importer_dir
+    };
     let project_root = req.project_root.as_ref().map(Utf8PathBuf::from);
 
     let mut warnings = Vec::new();
     let mut matched_export = None;
     let mut package_json_path = None;
+    let mut pnp_resolution = None;
+
+    let mut normalized_specifier = req.specifier.replace('\\', "/");
+    let is_bare = !is_relative(&normalized_specifier)
+        && !normalized_specifier.starts_with('/')
+        && !normalized_specifier.starts_with('#');
+    // The importing package's browser field can swap or disable its dependencies
+    if browser && is_bare {
+        if let Some(scope) = find_package_scope(&importer_dir, project_root.as_deref()) {
+            let package_json = Some(scope.join("package.json").to_string());
+            let mapping = browser_module_mapping(&scope, &normalized_specifier, &opts.extensions);
+            if let Some(mapping) = &mapping {
+                trace.push(|| browser_step(mapping));
+            }
+            match mapping {
+                // Applied once, like a bundler alias
+                Some(BrowserMapping::Package(target)) => normalized_specifier = target,
+                Some(BrowserMapping::File(path)) => {
+                    return Ok(ResolveResponse {
+                        format: detect_format(&path),
+                        resolved_path: Some(path.to_string()),
+                        matched_export: None,
+                        package_json,
+                        warnings,
+                        pnp: None,
+                        disabled: false,
+                        trace: trace.into_steps(),
+                    })
+                }
+                Some(BrowserMapping::Disabled) => {
+                    return Ok(ResolveResponse {
+                        resolved_path: None,
+                        format: ModuleFormat::Unknown,
+                        matched_export: None,
+                        package_json,
+                        warnings,
+                        pnp: None,
+                        disabled: true,
+                        trace: trace.into_steps(),
+                    })
+                }
+                None => {}
+            }
+        }
+    }
+
+    // Built-ins win over node_modules, except that bundlers resolve unprefixed
+    // names to installed polyfills (`buffer`, `events`) first
+    let prefixed = normalized_specifier.starts_with("node:");
+    let builtin = is_bare && is_node_builtin(&normalized_specifier);
+    if prefixed || (builtin && !browser) {
+        if !builtin {
+            warnings.push(format!(
+                "Unknown Node.js built-in module '{}'",
+                normalized_specifier
+            ));
+        }
+        return Ok(ResolveResponse {
+            resolved_path: None,
+            format: if builtin {
+                ModuleFormat::NodeBuiltin
+            } else {
+                ModuleFormat::Unknown
+            },
+            matched_export: None,
+            package_json: None,
+            warnings,
+            pnp: None,
+            disabled: false,
+            trace: trace.into_steps(),
+        });
+    }
 
-    let normalized_specifier = req.specifier.replace('\\', "/");
     let resolved = if is_relative(&normalized_specifier) || normalized_specifier.starts_with('/') {
-        resolve_path_like(&importer_dir, &normalized_specifier, &opts.extensions)
+        resolve_path_like(
+            &importer_dir,
+            &normalized_specifier,
+            &opts.extensions,
+            &mut trace,
+        )
+    } else if normalized_specifier.starts_with('#') {
+        // Subpath imports are private to the package containing the importer
+        match find_package_scope(&importer_dir, project_root.as_deref()) {
+            Some(scope) => {
+                let pkg_json = scope.join("package.json");
+                package_json_path = Some(pkg_json.to_string());
+                let target = read_package_json(&scope).ok().and_then(|pkg| {
+                    resolve_imports(&pkg, &normalized_specifier, &conditions, &mut trace)
+                });
+                match target {
+                    Some(target) if target.starts_with("./") => {
+                        let path = scope.join(target.trim_start_matches("./"));
+                        matched_export = Some(path.to_string());
+                        resolve_with_extensions(&path, &opts.extensions, &mut trace)
+                    }
+                    // Anything else maps to a dependency, resolved from the package itself
+                    Some(target) if !target.starts_with('#') && !target.starts_with('/') => {
+                        matched_export = Some(target.clone());
+                        let dependency = resolve_module_native(
+                            ResolveRequest {
+                                specifier: target,
+                                importer: pkg_json.to_string(),
+                                project_root: req.project_root.clone(),
+                            },
+                            Some(opts.clone()),
+                        )?;
+                        warnings.extend(dependency.warnings);
+                        trace.extend(dependency.trace);
+                        dependency.resolved_path.map(Utf8PathBuf::from)
+                    }
+                    Some(target) => {
+                        warnings.push(format!(
+                            "Invalid \"imports\" target '{}' for '{}'",
+                            target, normalized_specifier
+                        ));
+                        None
+                    }
+                    None => {
+                        warnings.push(format!(
+                            "No \"imports\" entry matches '{}' in {}",
+                            normalized_specifier, pkg_json
+                        ));
+                        None
+                    }
+                }
+            }
+            None => {
+                warnings.push(format!(
+                    "No package.json found for '{}' from {:?}",
+                    normalized_specifier, importer_dir
+                ));
+                None
+            }
+        }
     } else {
         let (pkg_name, subpath) = split_package_specifier(&normalized_specifier);
-        let pkg_dir = resolve_package_dir(
-            &importer_dir,
-            project_root.as_ref().map(|v| v.as_ref()),
-            &pkg_name,
-        );
+        // Under Plug'n'Play the manifest is authoritative; node_modules is only
+        // searched for files outside its dependency tree
+        let mut pnp_handled = false;
+        let mut pkg_dir = None;
+        if let Some(manifest_path) = pnp::find_manifest(&importer_dir, project_root.as_deref()) {
+            match pnp::load_cached(&manifest_path) {
+                Ok(manifest) => match manifest.resolve(&importer_dir, &pkg_name, &subpath) {
+                    PnpLookup::Found(found) => {
+                        pnp_handled = true;
+                        trace.push(|| ResolutionStep::PackageDir {
+                            path: found.package_location.clone(),
+                            found: true,
+                        });
+                        if let Some(zip) = &found.zip_path {
+                            warnings.push(format!(
+                                "Package '{}' is stored in {}; only its location was resolved",
+                                pkg_name, zip
+                            ));
+                        } else {
+                            pkg_dir = Some(Utf8PathBuf::from(&found.package_location));
+                        }
+                        pnp_resolution = Some(found);
+                    }
+                    PnpLookup::Failed(message) => {
+                        pnp_handled = true;
+                        warnings.push(message);
+                    }
+                    PnpLookup::NotInTree => {}
+                },
+                Err(e) => warnings.push(format!(
+                    "Failed to read PnP manifest {}: {}",
+                    manifest_path, e
+                )),
+            }
+        }
+        let mut from_workspace = false;
+        if !pnp_handled {
+            pkg_dir = resolve_package_dir(
+                &importer_dir,
+                project_root.as_ref().map(|v| v.as_ref()),
+                &pkg_name,
+                &mut trace,
+            );
+            // Sibling packages of a monorepo that haven't been linked (or installed) yet
+            if pkg_dir.is_none() {
+                pkg_dir = workspaces::find_workspace_package(
+                    &importer_dir,
+                    project_root.as_deref(),
+                    &pkg_name,
+                );
+                from_workspace = pkg_dir.is_some();
+                if let Some(dir) = &pkg_dir {
+                    trace.fallback(|| {
+                        format!(
+                            "'{}' isn't installed; using the workspace package at {}",
+                            pkg_name, dir
+                        )
+                    });
+                }
+            }
+        }
         if let Some(pkg_dir) = pkg_dir {
             package_json_path = Some(pkg_dir.join("package.json").to_string());
             let pkg_json = read_package_json(&pkg_dir).ok();
             let export_target = pkg_json
                 .as_ref()
-                .and_then(|pkg| resolve_exports(pkg, &subpath, &pkg_dir, &conditions));
-            if let Some(target) = export_target.clone() {
+                .and_then(|pkg| resolve_exports(pkg, &subpath, &pkg_dir, &conditions, &mut trace));
+            let resolved = if let Some(target) = export_target.clone() {
                 matched_export = Some(target.to_string());
-                resolve_path_like(&pkg_dir, target.as_str(), &opts.extensions)
-            } else {
+                resolve_path_like(&pkg_dir, target.as_str(), &opts.extensions, &mut trace)
+            } else if subpath == "." {
                 // fallback to main/module/types/index
-                resolve_pkg_main(&pkg_dir, pkg_json.as_ref(), &opts.extensions)
+                trace.fallback(|| "No \"exports\" target; using the entry fields".to_string());
+                resolve_pkg_main(&pkg_dir, pkg_json.as_ref(), &opts.extensions, &mut trace)
+            } else {
+                trace.fallback(|| {
+                    format!("No \"exports\" target; resolving '{}' as a path", subpath)
+                });
+                resolve_path_like(&pkg_dir, &subpath, &opts.extensions, &mut trace)
+            };
+            // Workspace packages often point at build output that doesn't exist yet;
+            // fall back to their sources
+            if resolved.is_none() && from_workspace {
+                trace.fallback(|| "Workspace package isn't built; trying its sources".to_string());
+                let source = match subpath.as_str() {
+                    "." => "src/index".to_string(),
+                    sub => format!("src/{}", sub.trim_start_matches("./")),
+                };
+                resolve_path_like(&pkg_dir, &source, &opts.extensions, &mut trace)
+            } else {
+                resolved
             }
         } else {
-            warnings.push(format!(
-                "Package '{}' not found from {:?}",
-                pkg_name, importer_dir
-            ));
+            if !pnp_handled && !builtin {
+                warnings.push(format!(
+                    "Package '{}' not found from {:?}",
+                    pkg_name, importer_dir
+                ));
+            }
             None
         }
     };
 
-    let format = resolved
-        .as_ref()
-        .map(|path| detect_format(path))
-        .unwrap_or(ModuleFormat::Unknown);
+    // Files can be swapped or disabled by the browser field of the package they're in
+    let mut disabled = false;
+    let resolved = match resolved {
+        Some(path) if browser => {
+            let mapping = browser_file_mapping(&path, project_root.as_deref(), &opts.extensions);
+            if let Some(mapping) = &mapping {
+                trace.push(|| browser_step(mapping));
+            }
+            match mapping {
+                Some(BrowserMapping::File(mapped)) => Some(mapped),
+                Some(BrowserMapping::Package(target)) => {
+                    let mapped = resolve_module_native(
+                        ResolveRequest {
+                            specifier: target,
+                            importer: path.to_string(),
+                            project_root: req.project_root.clone(),
+                        },
+                        Some(opts.clone()),
+                    )?;
+                    warnings.extend(mapped.warnings);
+                    trace.extend(mapped.trace);
+                    disabled = mapped.disabled;
+                    mapped.resolved_path.map(Utf8PathBuf::from)
+                }
+                Some(BrowserMapping::Disabled) => {
+                    disabled = true;
+                    None
+                }
+                None => Some(path),
+            }
+        }
+        resolved => resolved,
+    };
+
+    let format = match &resolved {
+        Some(path) => detect_format(path),
+        None if builtin => ModuleFormat::NodeBuiltin,
+        None => ModuleFormat::Unknown,
+    };
 
     Ok(ResolveResponse {
         resolved_path: resolved.map(|p| p.to_string()),
@@ -157,6 +503,9 @@ pub fn resolve_module_native(
         matched_export,
         package_json: package_json_path,
         warnings,
+        pnp: pnp_resolution,
+        disabled,
+        trace: trace.into_steps(),
     })
 }
 
@@ -165,66 +514,124 @@ pub fn resolve_module_native(
 /// 1. Export conditions with "types" key
 /// 2. Top-level "types" / "typings" fields in package.json
 /// 3. Common fallback paths (index.d.ts, dist/index.d.ts, etc.)
+///
+/// Paths from 2 and 3 are redirected through "typesVersions" when the package has it.
 /// 4. @types/* fallback packages
 /// 5. Recursively discovers related .d.ts files in the same directory
+///
+/// With a `subpath` (or a specifier such as `lodash/merge`), only the typings of that
+/// entry point are looked up, through the same sources.
 pub fn discover_typings_native(
     package_name: &str,
     project_root: &Utf8Path,
+    subpath: Option<&str>,
 ) -> Result<TypingsResponse> {
+    let entries = typings_entries(package_name, project_root, subpath);
+
     let mut files = Vec::new();
-    let mut pkg_json_path = None;
     let mut visited_dirs: HashSet<String> = HashSet::new();
+    for entry in &entries.files {
+        files.push(entry.to_string());
+        // Recursively discover related .d.ts files in the same directory
+        if let Some(parent) = entry.parent() {
+            discover_dts_in_dir(parent, &mut files, &mut visited_dirs);
+        }
+    }
+
+    files.sort();
+    files.dedup();
+
+    Ok(TypingsResponse {
+        package_name: entries.package_name,
+        files,
+        package_json: entries.package_json,
+    })
+}
 
-    if let Some(pkg_dir) = resolve_package_dir(project_root, Some(project_root), package_name) {
+/// The entry declaration files of a package, found through sources 1-4 of
+/// `discover_typings_native`
+pub(crate) struct TypingsEntries {
+    pub(crate) package_name: String,
+    /// In the order the sources are checked; may repeat a file
+    pub(crate) files: Vec<Utf8PathBuf>,
+    pub(crate) package_json: Option<String>,
+}
+
+pub(crate) fn typings_entries(
+    package_name: &str,
+    project_root: &Utf8Path,
+    subpath: Option<&str>,
+) -> TypingsEntries {
+    let (package_name, specifier_subpath) = split_package_specifier(package_name);
+    let subpath = subpath
+        .map(|subpath| subpath.trim_start_matches("./").trim_start_matches('/'))
+        .filter(|subpath| !subpath.is_empty() && *subpath != ".")
+        .map(str::to_string)
+        .or_else(|| specifier_subpath.strip_prefix("./").map(str::to_string));
+
+    let mut files = Vec::new();
+    let mut pkg_json_path = None;
+
+    if let Some(pkg_dir) = resolve_package_dir(
+        project_root,
+        Some(project_root),
+        &package_name,
+        &mut Trace::default(),
+    ) {
         pkg_json_path = Some(pkg_dir.join("package.json").to_string());
+        let types_versions = TypesVersions::load(&pkg_dir);
+        let remap = |path: &str| {
+            types_versions
+                .as_ref()
+                .and_then(|versions| versions.remap(&pkg_dir, path))
+                .unwrap_or_else(|| pkg_dir.join(path))
+        };
 
-        if let Ok(pkg_json) = read_package_json(&pkg_dir) {
-            // 1. Check export conditions for "types" first (modern packages)
-            if let Some(exports) = pkg_json.get("exports") {
-                // Check root export
-                if let Some(types_path) = resolve_exports_types(exports, ".", &pkg_dir) {
-                    if types_path.is_file() {
-                        files.push(types_path.to_string());
-                        // Recursively discover related .d.ts files in the same directory
-                        if let Some(parent) = types_path.parent() {
-                            discover_dts_in_dir(parent, &mut files, &mut visited_dirs);
+        if let Some(subpath) = &subpath {
+            let pkg_json = read_package_json(&pkg_dir).unwrap_or(Value::Null);
+            files.extend(resolve_subpath_types(
+                &pkg_dir,
+                &pkg_json,
+                subpath,
+                types_versions.as_ref(),
+            ));
+        } else {
+            if let Ok(pkg_json) = read_package_json(&pkg_dir) {
+                // 1. Check export conditions for "types" first (modern packages)
+                if let Some(exports) = pkg_json.get("exports") {
+                    // Check root export
+                    if let Some(types_path) = resolve_exports_types(exports, ".", &pkg_dir) {
+                        if types_path.is_file() {
+                            files.push(types_path);
                         }
                     }
                 }
-            }
 
-            // 2. Check top-level "types" / "typings" fields
-            if let Some(types) = pkg_json
-                .get("types")
-                .or_else(|| pkg_json.get("typings"))
-                .and_then(|v| v.as_str())
-            {
-                let candidate = pkg_dir.join(types);
-                if candidate.is_file() {
-                    files.push(candidate.to_string());
-                    // Recursively discover related .d.ts files
-                    if let Some(parent) = candidate.parent() {
-                        discover_dts_in_dir(parent, &mut files, &mut visited_dirs);
+                // 2. Check top-level "types" / "typings" fields
+                if let Some(types) = pkg_json
+                    .get("types")
+                    .or_else(|| pkg_json.get("typings"))
+                    .and_then(|v| v.as_str())
+                {
+                    let candidate = remap(types);
+                    if candidate.is_file() {
+                        files.push(candidate);
                     }
                 }
             }
-        }
 
-        // 3. Common fallback paths
-        for candidate in [
-            "index.d.ts",
-            "index.d.mts",
-            "dist/index.d.ts",
-            "lib/index.d.ts",
-            "types/index.d.ts",
-            "build/index.d.ts",
-        ] {
-            let candidate_path = pkg_dir.join(candidate);
-            if candidate_path.is_file() {
-                files.push(candidate_path.to_string());
-                // Recursively discover related .d.ts files
-                if let Some(parent) = candidate_path.parent() {
-                    discover_dts_in_dir(parent, &mut files, &mut visited_dirs);
+            // 3. Common fallback paths
+            for candidate in [
+                "index.d.ts",
+                "index.d.mts",
+                "dist/index.d.ts",
+                "lib/index.d.ts",
+                "types/index.d.ts",
+                "build/index.d.ts",
+            ] {
+                let candidate_path = remap(candidate);
+                if candidate_path.is_file() {
+                    files.push(candidate_path);
                 }
             }
         }
@@ -235,24 +642,69 @@ pub fn discover_typings_native(
         "@types/{}",
         package_name.trim_start_matches('@').replace('/', "__")
     );
-    if let Some(types_dir) = resolve_package_dir(project_root, Some(project_root), &types_pkg) {
-        let types_index = types_dir.join("index.d.ts");
-        if types_index.is_file() {
-            files.push(types_index.to_string());
+    if let Some(types_dir) = resolve_package_dir(
+        project_root,
+        Some(project_root),
+        &types_pkg,
+        &mut Trace::default(),
+    ) {
+        let types_path = match &subpath {
+            Some(subpath) => declaration_file(&types_dir.join(strip_js_extension(subpath))),
+            None => Some(types_dir.join("index.d.ts")).filter(|index| index.is_file()),
+        };
+        if let Some(types_path) = types_path {
+            files.push(types_path);
             pkg_json_path.get_or_insert(types_dir.join("package.json").to_string());
-            // Recursively discover all .d.ts files in @types package
-            discover_dts_in_dir(&types_dir, &mut files, &mut visited_dirs);
         }
     }
 
-    files.sort();
-    files.dedup();
-
-    Ok(TypingsResponse {
-        package_name: package_name.to_string(),
+    TypingsEntries {
+        package_name,
         files,
         package_json: pkg_json_path,
-    })
+    }
+}
+
+/// The declaration file for `subpath` (e.g. `merge` or `auth`) of the package in
+/// `pkg_dir`. Packages with "exports" only expose the subpaths listed there; for
+/// the rest, the file or directory at that path is used, through "typesVersions",
+/// a nested package.json "types" field, `<subpath>.d.ts` or `<subpath>/index.d.ts`.
+fn resolve_subpath_types(
+    pkg_dir: &Utf8Path,
+    pkg_json: &Value,
+    subpath: &str,
+    types_versions: Option<&TypesVersions>,
+) -> Option<Utf8PathBuf> {
+    if let Some(exports) = pkg_json.get("exports") {
+        return resolve_exports_types(exports, subpath, pkg_dir).filter(|path| path.is_file());
+    }
+    let stem = strip_js_extension(subpath);
+    if let Some(path) = types_versions.and_then(|versions| versions.remap(pkg_dir, stem)) {
+        return Some(path);
+    }
+    let target = pkg_dir.join(stem);
+    if target.is_dir() {
+        let nested = read_package_json(&target).ok();
+        let types = nested.as_ref().and_then(|pkg| {
+            pkg.get("types")
+                .or_else(|| pkg.get("typings"))
+                .and_then(Value::as_str)
+        });
+        if let Some(path) =
+            types.and_then(|types| declaration_file(&normalize_path(&target.join(types))))
+        {
+            return Some(path);
+        }
+    }
+    declaration_file(&target)
+}
+
+/// `lodash/merge.js` is typed by `merge.d.ts`
+fn strip_js_extension(subpath: &str) -> &str {
+    [".js", ".mjs", ".cjs"]
+        .iter()
+        .find_map(|ext| subpath.strip_suffix(ext))
+        .unwrap_or(subpath)
 }
 
 /// Resolve "types" condition from exports field
@@ -261,26 +713,34 @@ fn resolve_exports_types(
     subpath: &str,
     pkg_dir: &Utf8Path,
 ) -> Option<Utf8PathBuf> {
-    // Prioritize "types" condition for type definitions
+    // Prioritize "types" condition for type definitions; the module conditions are
+    // searched for nested "types" entries
     let types_conditions = vec![
         "types".to_string(),
         "typings".to_string(),
+        "import".to_string(),
+        "require".to_string(),
+        "node".to_string(),
         "default".to_string(),
     ];
 
     let target = if subpath == "." {
-        select_export_target_with_conditions(exports, &types_conditions)
-    } else if let Some(obj) = exports.as_object() {
+        let root = match exports.as_object() {
+            Some(obj) if obj.keys().any(|key| key.starts_with('.')) => obj.get(".")?,
+            _ => exports,
+        };
+        select_export_target_with_conditions(root, &types_conditions)
+    } else {
+        // Subpaths that aren't exported can't be imported, so there's no falling back
+        // to the root entry
+        let obj = exports.as_object()?;
         let key = format!("./{}", subpath.trim_start_matches("./"));
-        if let Some(value) = obj.get(&key) {
-            select_export_target_with_conditions(value, &types_conditions)
-        } else if let Some(value) = obj.get(".") {
-            select_export_target_with_conditions(value, &types_conditions)
-        } else {
-            None
+        let (value, matched) = match_subpath_pattern(obj, &key)?;
+        let target = select_export_target_with_conditions(value, &types_conditions)?;
+        match matched {
+            Some(matched) => Some(target.replace('*', &matched)),
+            None => Some(target),
         }
-    } else {
-        None
     }?;
 
     let normalized = pkg_dir.join(target.trim_start_matches("./"));
@@ -398,8 +858,49 @@ fn discover_dts_in_dir_impl(
     }
 }
 
-/// Parse a module and return its import/export graph. Transformation is currently identity.
-pub fn analyze_module_native(module_path: &Utf8Path) -> Result<AnalyzeResponse> {
+/// Parse a module and return its import/export graph. `transformed` is the
+/// module's source, with types stripped or its module system converted when
+/// `options` asks for it.
+pub fn analyze_module_native(
+    module_path: &Utf8Path,
+    options: Option<TransformOptions>,
+) -> Result<AnalyzeResponse> {
+    let (code, _, module) = parse_module_file(module_path)?;
+
+    let mut visitor = GraphVisitor::default();
+    visitor.visit_module(&module);
+
+    let transformed = match options.filter(|options| !options.is_identity()) {
+        Some(options) => transform::transform_module(module_path, &code, &options)?,
+        None => code,
+    };
+
+    Ok(AnalyzeResponse {
+        imports: visitor.imports.into_iter().collect(),
+        exports: visitor.exports.into_iter().collect(),
+        transformed,
+    })
+}
+
+/// Parse a module and return every import, re-export, dynamic `import()` and `require()`
+/// specifier with its byte range, so callers can rewrite specifiers in place.
+pub fn collect_import_specifiers_native(module_path: &Utf8Path) -> Result<Vec<ImportSpecifier>> {
+    let (code, start_pos, module) = parse_module_file(module_path)?;
+
+    let mut visitor = SpecifierVisitor {
+        code: &code,
+        start_pos,
+        specifiers: Vec::new(),
+    };
+    visitor.visit_module(&module);
+
+    let mut specifiers = visitor.specifiers;
+    specifiers.sort_by_key(|s| s.start);
+    Ok(specifiers)
+}
+
+/// Read and parse a module, returning its source, the source file's start position and the AST.
+fn parse_module_file(module_path: &Utf8Path) -> Result<(String, BytePos, Module)> {
     let code = fs::read_to_string(module_path)
         .with_context(|| format!("Failed to read {}", module_path))?;
 
@@ -408,11 +909,28 @@ pub fn analyze_module_native(module_path: &Utf8Path) -> Result<AnalyzeResponse>
         FileName::Custom(module_path.to_string()).into(),
         code.clone(),
     );
+    let lexer = swc_core::ecma::parser::lexer::Lexer::new(
+        syntax_for(module_path),
+        EsVersion::EsNext,
+        StringInput::from(&*fm),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let module = parser
+        .parse_module()
+        .map_err(|err| anyhow::Error::msg(format!("Parse error: {:?}", err)))?;
+
+    Ok((code, fm.start_pos, module))
+}
+
+/// The parser syntax for a module, from its extension
+fn syntax_for(module_path: &Utf8Path) -> Syntax {
     let is_ts = matches!(module_path.extension(), Some("ts" | "tsx" | "mts" | "cts"));
-    let syntax = if is_ts {
+    if is_ts {
         Syntax::Typescript(TsSyntax {
             tsx: module_path.extension().map(|e| e == "tsx").unwrap_or(false),
             decorators: true,
+            dts: is_declaration_file(module_path),
             ..Default::default()
         })
     } else {
@@ -420,27 +938,169 @@ pub fn analyze_module_native(module_path: &Utf8Path) -> Result<AnalyzeResponse>
             jsx: true,
             ..Default::default()
         })
-    };
+    }
+}
 
-    let lexer = swc_core::ecma::parser::lexer::Lexer::new(
-        syntax,
-        EsVersion::EsNext,
-        StringInput::from(&*fm),
-        None,
-    );
-    let mut parser = Parser::new_from(lexer);
-    let module = parser
-        .parse_module()
-        .map_err(|err| anyhow::Error::msg(format!("Parse error: {:?}", err)))?;
+/// `.d.ts`, `.d.mts` or `.d.cts`
+fn is_declaration_file(path: &Utf8Path) -> bool {
+    let name = path.file_name().unwrap_or("");
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
 
-    let mut visitor = GraphVisitor::default();
-    visitor.visit_module(&module);
+struct SpecifierVisitor<'a> {
+    code: &'a str,
+    start_pos: BytePos,
+    specifiers: Vec<ImportSpecifier>,
+}
 
-    Ok(AnalyzeResponse {
-        imports: visitor.imports.into_iter().collect(),
-        exports: visitor.exports.into_iter().collect(),
-        transformed: code,
-    })
+impl SpecifierVisitor<'_> {
+    fn push(
+        &mut self,
+        kind: ImportKind,
+        span: Span,
+        (bindings, type_bindings): (Vec<ImportBinding>, Vec<ImportBinding>),
+        type_only: bool,
+    ) {
+        let lo = (span.lo.0 - self.start_pos.0) as usize;
+        let hi = (span.hi.0 - self.start_pos.0) as usize;
+        // The span covers the quotes; only the text between them is reported
+        let Some(raw) = self.code.get(lo..hi) else {
+            return;
+        };
+        if raw.len() < 2 || !(raw.starts_with('"') || raw.starts_with('\'')) {
+            return;
+        }
+        self.specifiers.push(ImportSpecifier {
+            specifier: raw[1..raw.len() - 1].to_string(),
+            kind,
+            bindings,
+            type_bindings,
+            type_only,
+            start: lo + 1,
+            end: hi - 1,
+        });
+    }
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(id) => id.sym.to_string(),
+        ModuleExportName::Str(s) => s.value.as_str().unwrap_or("").to_string(),
+    }
+}
+
+impl Visit for SpecifierVisitor<'_> {
+    fn visit_import_decl(&mut self, decl: &ImportDecl) {
+        let mut bindings = Vec::new();
+        let mut type_bindings = Vec::new();
+        for spec in &decl.specifiers {
+            match spec {
+                swc_core::ecma::ast::ImportSpecifier::Named(named) => {
+                    let local = named.local.sym.to_string();
+                    let binding = ImportBinding::Named {
+                        imported: named
+                            .imported
+                            .as_ref()
+                            .map(export_name)
+                            .unwrap_or_else(|| local.clone()),
+                        local,
+                    };
+                    if named.is_type_only {
+                        type_bindings.push(binding);
+                    } else {
+                        bindings.push(binding);
+                    }
+                }
+                swc_core::ecma::ast::ImportSpecifier::Default(default) => {
+                    bindings.push(ImportBinding::Default {
+                        local: default.local.sym.to_string(),
+                    })
+                }
+                swc_core::ecma::ast::ImportSpecifier::Namespace(namespace) => {
+                    bindings.push(ImportBinding::Namespace {
+                        local: namespace.local.sym.to_string(),
+                    })
+                }
+            }
+        }
+        self.push(
+            ImportKind::Import,
+            decl.src.span,
+            (bindings, type_bindings),
+            decl.type_only,
+        );
+    }
+
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        let bindings = vec![ImportBinding::Namespace {
+            local: "*".to_string(),
+        }];
+        self.push(
+            ImportKind::ReExport,
+            export.src.span,
+            (bindings, Vec::new()),
+            export.type_only,
+        );
+    }
+
+    fn visit_named_export(&mut self, export: &NamedExport) {
+        let Some(src) = &export.src else {
+            return;
+        };
+        let mut bindings = Vec::new();
+        let mut type_bindings = Vec::new();
+        for spec in &export.specifiers {
+            match spec {
+                ExportSpecifier::Named(named) => {
+                    let imported = export_name(&named.orig);
+                    let binding = ImportBinding::Named {
+                        local: named
+                            .exported
+                            .as_ref()
+                            .map(export_name)
+                            .unwrap_or_else(|| imported.clone()),
+                        imported,
+                    };
+                    if named.is_type_only {
+                        type_bindings.push(binding);
+                    } else {
+                        bindings.push(binding);
+                    }
+                }
+                ExportSpecifier::Namespace(namespace) => bindings.push(ImportBinding::Namespace {
+                    local: export_name(&namespace.name),
+                }),
+                ExportSpecifier::Default(default) => bindings.push(ImportBinding::Default {
+                    local: default.exported.sym.to_string(),
+                }),
+            }
+        }
+        self.push(
+            ImportKind::ReExport,
+            src.span,
+            (bindings, type_bindings),
+            export.type_only,
+        );
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        let kind = match &call.callee {
+            Callee::Import(_) => Some(ImportKind::DynamicImport),
+            Callee::Expr(expr) => match &**expr {
+                Expr::Ident(ident) if &*ident.sym == "require" => Some(ImportKind::Require),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let (Some(kind), Some(arg)) = (kind, call.args.first()) {
+            if let Expr::Lit(Lit::Str(s)) = &*arg.expr {
+                self.push(kind, s.span, (Vec::new(), Vec::new()), false);
+            }
+        }
+        call.visit_children_with(self);
+    }
 }
 
 #[derive(Default)]
@@ -532,7 +1192,7 @@ impl Visit for GraphVisitor {
                 _ => {}
             }
         }
-        swc_core::ecma::visit::Visit::visit_module_item(self, item);
+        item.visit_children_with(self);
     }
 }
 
@@ -565,6 +1225,90 @@ fn collect_pats(exports: &mut HashSet<String>, pat: &Pat) {
     }
 }
 
+/// Unprefixed built-in modules, as listed by `require('module').builtinModules`.
+const NODE_BUILTINS: &[&str] = &[
+    "_http_agent",
+    "_http_client",
+    "_http_common",
+    "_http_incoming",
+    "_http_outgoing",
+    "_http_server",
+    "_stream_duplex",
+    "_stream_passthrough",
+    "_stream_readable",
+    "_stream_transform",
+    "_stream_wrap",
+    "_stream_writable",
+    "_tls_common",
+    "_tls_wrap",
+    "assert",
+    "assert/strict",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "dns/promises",
+    "domain",
+    "events",
+    "fs",
+    "fs/promises",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "inspector/promises",
+    "module",
+    "net",
+    "os",
+    "path",
+    "path/posix",
+    "path/win32",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "readline/promises",
+    "repl",
+    "stream",
+    "stream/consumers",
+    "stream/promises",
+    "stream/web",
+    "string_decoder",
+    "sys",
+    "timers",
+    "timers/promises",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "util/types",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Built-ins that can only be imported with the `node:` scheme.
+const NODE_PREFIXED_BUILTINS: &[&str] = &["sea", "sqlite", "test", "test/reporters"];
+
+/// Whether `specifier` names a Node.js built-in module, with or without the
+/// `node:` prefix (`fs`, `node:fs/promises`, `node:test`).
+pub fn is_node_builtin(specifier: &str) -> bool {
+    match specifier.strip_prefix("node:") {
+        Some(name) => NODE_BUILTINS.contains(&name) || NODE_PREFIXED_BUILTINS.contains(&name),
+        None => NODE_BUILTINS.contains(&specifier),
+    }
+}
+
 fn is_relative(spec: &str) -> bool {
     spec.starts_with("./") || spec.starts_with("../")
 }
@@ -587,21 +1331,33 @@ fn split_package_specifier(spec: &str) -> (String, String) {
     }
 }
 
+/// Find `package` in the nearest `node_modules`, returning its real location.
+/// pnpm links each package to `node_modules/.pnpm/<name>@<version>[_<peers>]/node_modules/<name>`,
+/// next to links to its dependencies (and the right peer instances), so resolving
+/// onward from the real path finds them the same way Node does.
 fn resolve_package_dir(
     start: &Utf8Path,
     project_root: Option<&Utf8Path>,
     package: &str,
+    trace: &mut Trace,
 ) -> Option<Utf8PathBuf> {
+    // Walks from inside a symlinked package run over real paths
+    let real_root = project_root.map(real_path);
     let mut current = start.to_path_buf();
     loop {
         let candidate = current.join("node_modules").join(package);
-        if candidate.exists() {
-            return Some(candidate);
+        let found = candidate.exists();
+        trace.push(|| ResolutionStep::PackageDir {
+            path: candidate.to_string(),
+            found,
+        });
+        if found {
+            return Some(real_path(&candidate));
         }
-        if let Some(root) = project_root {
-            if current == root {
-                break;
-            }
+        if project_root.is_some_and(|root| current == root)
+            || real_root.as_ref().is_some_and(|root| current == *root)
+        {
+            break;
         }
         if !current.pop() {
             break;
@@ -610,10 +1366,137 @@ fn resolve_package_dir(
     None
 }
 
+/// The nearest directory at or above `start` with a package.json, without
+/// leaving the project root
+fn find_package_scope(start: &Utf8Path, project_root: Option<&Utf8Path>) -> Option<Utf8PathBuf> {
+    let mut current = start.to_path_buf();
+    loop {
+        if current.join("package.json").is_file() {
+            return Some(current);
+        }
+        if project_root.is_some_and(|root| current == root) || !current.pop() {
+            return None;
+        }
+    }
+}
+
+/// Canonical form of `path` with symlinks followed, or `path` itself if it
+/// can't be resolved
+fn real_path(path: &Utf8Path) -> Utf8PathBuf {
+    fs::canonicalize(path)
+        .ok()
+        .map(strip_verbatim_prefix)
+        .and_then(|path| Utf8PathBuf::from_path_buf(path).ok())
+        .unwrap_or_else(|| path.to_owned())
+}
+
+/// Lexically resolve `.` and `..` segments.
+fn normalize_path(path: &Utf8Path) -> Utf8PathBuf {
+    let mut out = Utf8PathBuf::new();
+    for component in path.components() {
+        match component.as_str() {
+            "." => {}
+            ".." => {
+                out.pop();
+            }
+            _ => out.push(component),
+        }
+    }
+    out
+}
+
+/// Drop the `\\?\` prefix Windows adds to canonical drive paths, which other
+/// tools and the frontend don't expect
+fn strip_verbatim_prefix(path: std::path::PathBuf) -> std::path::PathBuf {
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    match text.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => std::path::PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+/// Replacement given by an object-form package.json `browser` field.
+enum BrowserMapping {
+    /// Another module, resolved from the mapping package
+    Package(String),
+    File(Utf8PathBuf),
+    /// Mapped to `false`
+    Disabled,
+}
+
+fn browser_field(scope: &Utf8Path) -> Option<serde_json::Map<String, Value>> {
+    match read_package_json(scope).ok()?.get_mut("browser")?.take() {
+        Value::Object(map) => Some(map),
+        _ => None,
+    }
+}
+
+fn browser_mapping_target(
+    scope: &Utf8Path,
+    target: &Value,
+    extensions: &[String],
+) -> Option<BrowserMapping> {
+    match target {
+        Value::Bool(false) => Some(BrowserMapping::Disabled),
+        Value::String(target) if is_relative(target) || target.starts_with('/') => {
+            resolve_path_like(scope, target, extensions, &mut Trace::default())
+                .map(BrowserMapping::File)
+        }
+        Value::String(target) => Some(BrowserMapping::Package(target.clone())),
+        _ => None,
+    }
+}
+
+/// The browser field mapping of the package in `scope` for module `specifier`,
+/// e.g. `"fs": false` or `"node-fetch": "./fetch-shim.js"`
+fn browser_module_mapping(
+    scope: &Utf8Path,
+    specifier: &str,
+    extensions: &[String],
+) -> Option<BrowserMapping> {
+    let map = browser_field(scope)?;
+    browser_mapping_target(scope, map.get(specifier)?, extensions)
+}
+
+/// The browser field mapping for the file at `path`, from the package containing
+/// it. Keys are matched by the file they resolve to, so `./lib/node` maps
+/// `./lib/node.js` too.
+fn browser_file_mapping(
+    path: &Utf8Path,
+    project_root: Option<&Utf8Path>,
+    extensions: &[String],
+) -> Option<BrowserMapping> {
+    let scope = find_package_scope(path.parent()?, project_root)?;
+    let map = browser_field(&scope)?;
+    map.iter()
+        .filter(|(key, _)| is_relative(key) || key.starts_with('/'))
+        .find(|(key, _)| {
+            resolve_path_like(&scope, key, extensions, &mut Trace::default()).as_deref()
+                == Some(path)
+        })
+        .and_then(|(_, target)| browser_mapping_target(&scope, target, extensions))
+}
+
+/// The step recording a browser field mapping
+fn browser_step(mapping: &BrowserMapping) -> ResolutionStep {
+    let value = match mapping {
+        BrowserMapping::File(path) => path.to_string(),
+        BrowserMapping::Package(target) => target.clone(),
+        BrowserMapping::Disabled => "false".to_string(),
+    };
+    ResolutionStep::Field {
+        name: "browser".to_string(),
+        value,
+    }
+}
+
 fn resolve_path_like(
     base: &Utf8Path,
     specifier: &str,
     extensions: &[String],
+    trace: &mut Trace,
 ) -> Option<Utf8PathBuf> {
     let target = if specifier.starts_with('/') {
         Utf8PathBuf::from(specifier)
@@ -621,18 +1504,27 @@ fn resolve_path_like(
         base.join(specifier)
     };
 
-    resolve_with_extensions(&target, extensions)
+    resolve_with_extensions(&target, extensions, trace)
 }
 
-fn resolve_with_extensions(target: &Utf8Path, extensions: &[String]) -> Option<Utf8PathBuf> {
-    if target.is_file() {
+fn resolve_with_extensions(
+    target: &Utf8Path,
+    extensions: &[String],
+    trace: &mut Trace,
+) -> Option<Utf8PathBuf> {
+    let mut probe = |candidate: &Utf8Path| {
+        let found = candidate.is_file();
+        trace.file(candidate, found);
+        found
+    };
+    if probe(target) {
         return Some(target.to_owned());
     }
 
     // Try extension variations
     for ext in extensions {
         let candidate = Utf8PathBuf::from(format!("{}{}", target, ext));
-        if candidate.is_file() {
+        if probe(&candidate) {
             return Some(candidate);
         }
     }
@@ -641,7 +1533,7 @@ fn resolve_with_extensions(target: &Utf8Path, extensions: &[String]) -> Option<U
     if target.is_dir() {
         for ext in extensions {
             let candidate = target.join(format!("index{}", ext));
-            if candidate.is_file() {
+            if probe(&candidate) {
                 return Some(candidate);
             }
         }
@@ -654,28 +1546,27 @@ fn resolve_pkg_main(
     pkg_dir: &Utf8Path,
     pkg_json: Option<&Value>,
     extensions: &[String],
+    trace: &mut Trace,
 ) -> Option<Utf8PathBuf> {
     if let Some(pkg) = pkg_json {
-        if let Some(types) = pkg
-            .get("types")
-            .or_else(|| pkg.get("typings"))
-            .and_then(|v| v.as_str())
-        {
-            let candidate = pkg_dir.join(types);
-            if let Some(resolved) = resolve_with_extensions(&candidate, extensions) {
-                return Some(resolved);
-            }
-        }
-        for key in ["module", "main", "browser"] {
+        let types_key = ["types", "typings"]
+            .into_iter()
+            .find(|key| pkg.get(*key).is_some());
+        let keys = types_key.into_iter().chain(["module", "main", "browser"]);
+        for key in keys {
             if let Some(entry) = pkg.get(key).and_then(|v| v.as_str()) {
+                trace.push(|| ResolutionStep::Field {
+                    name: key.to_string(),
+                    value: entry.to_string(),
+                });
                 let candidate = pkg_dir.join(entry);
-                if let Some(resolved) = resolve_with_extensions(&candidate, extensions) {
+                if let Some(resolved) = resolve_with_extensions(&candidate, extensions, trace) {
                     return Some(resolved);
                 }
             }
         }
     }
-    resolve_with_extensions(pkg_dir, extensions)
+    resolve_with_extensions(pkg_dir, extensions, trace)
 }
 
 fn resolve_exports(
@@ -683,29 +1574,33 @@ fn resolve_exports(
     subpath: &str,
     pkg_dir: &Utf8Path,
     conditions: &[String],
+    trace: &mut Trace,
 ) -> Option<Utf8PathBuf> {
     let exports = pkg.get("exports")?;
-    let target = if subpath == "." {
-        select_export_target(exports, conditions)
-    } else if let Some(obj) = exports.as_object() {
-        let key = format!("./{}", subpath.trim_start_matches("./"));
-        if let Some(value) = obj.get(&key) {
-            select_export_target(value, conditions)
+    // `{ ".": ..., "./sub": ... }` maps subpaths; anything else describes the root export only
+    let subpath_map = exports
+        .as_object()
+        .filter(|obj| obj.keys().any(|k| k.starts_with('.')));
+    let target = if let Some(obj) = subpath_map {
+        let key = if subpath == "." {
+            ".".to_string()
         } else {
-            // simple star pattern support
-            obj.iter().find_map(|(pattern, value)| {
-                if let Some(star_pos) = pattern.find('*') {
-                    let prefix = &pattern[..star_pos];
-                    let suffix = &pattern[star_pos + 1..];
-                    if key.starts_with(prefix) && key.ends_with(suffix) {
-                        let matched = &key[prefix.len()..key.len() - suffix.len()];
-                        let mapped = select_export_target(value, conditions)?;
-                        return Some(mapped.replace('*', matched));
-                    }
-                }
-                None
-            })
+            format!("./{}", subpath.trim_start_matches("./"))
+        };
+        let entry = match_subpath_pattern(obj, &key);
+        trace.push(|| ResolutionStep::Entry {
+            field: "exports".to_string(),
+            subpath: key.clone(),
+            matched: entry.is_some(),
+        });
+        let (value, matched) = entry?;
+        let mapped = select_export_target(value, conditions, trace)?;
+        match matched {
+            Some(matched) => Some(mapped.replace('*', &matched)),
+            None => Some(mapped),
         }
+    } else if subpath == "." {
+        select_export_target(exports, conditions, trace)
     } else {
         None
     }?;
@@ -713,36 +1608,91 @@ fn resolve_exports(
     Some(normalized)
 }
 
-fn select_export_target(value: &Value, conditions: &[String]) -> Option<String> {
+/// Map a `#`-prefixed specifier through the package's `imports` field.
+/// Returns the raw target: a `./` path within the package or a dependency specifier.
+fn resolve_imports(
+    pkg: &Value,
+    specifier: &str,
+    conditions: &[String],
+    trace: &mut Trace,
+) -> Option<String> {
+    let imports = pkg.get("imports")?.as_object()?;
+    let entry = match_subpath_pattern(imports, specifier);
+    trace.push(|| ResolutionStep::Entry {
+        field: "imports".to_string(),
+        subpath: specifier.to_string(),
+        matched: entry.is_some(),
+    });
+    let (value, matched) = entry?;
+    let target = select_export_target(value, conditions, trace)?;
+    match matched {
+        Some(matched) => Some(target.replace('*', &matched)),
+        None => Some(target),
+    }
+}
+
+/// Find the `exports`/`imports` entry for `key`: an exact match, otherwise the
+/// star pattern with the longest prefix (then the longest key), as Node does.
+/// Returns the entry and the text matched by `*`.
+fn match_subpath_pattern<'a>(
+    map: &'a serde_json::Map<String, Value>,
+    key: &str,
+) -> Option<(&'a Value, Option<String>)> {
+    if !key.contains('*') {
+        if let Some(value) = map.get(key) {
+            return Some((value, None));
+        }
+    }
+    map.iter()
+        .filter_map(|(pattern, value)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let matches = !suffix.contains('*')
+                && key != prefix
+                && key.len() >= pattern.len()
+                && key.starts_with(prefix)
+                && key.ends_with(suffix);
+            matches.then(|| {
+                let matched = &key[prefix.len()..key.len() - suffix.len()];
+                (prefix.len(), pattern.len(), value, matched.to_string())
+            })
+        })
+        .max_by_key(|(prefix_len, pattern_len, ..)| (*prefix_len, *pattern_len))
+        .map(|(_, _, value, matched)| (value, Some(matched)))
+}
+
+fn select_export_target(value: &Value, conditions: &[String], trace: &mut Trace) -> Option<String> {
     match value {
         Value::String(s) => Some(s.to_string()),
         Value::Array(arr) => {
             for entry in arr {
-                if let Some(target) = select_export_target(entry, conditions) {
+                if let Some(target) = select_export_target(entry, conditions, trace) {
                     return Some(target);
                 }
             }
             None
         }
         Value::Object(map) => {
-            for condition in conditions {
-                if let Some(val) = map.get(condition) {
-                    if let Some(target) = select_export_target(val, conditions) {
-                        return Some(target);
-                    }
-                }
-            }
             // fallback to "default"
-            if let Some(default) = map.get("default") {
-                return select_export_target(default, conditions);
-            }
-            None
+            let default = (!conditions.iter().any(|c| c == "default")).then_some("default");
+            conditions
+                .iter()
+                .map(String::as_str)
+                .chain(default)
+                .find_map(|condition| {
+                    let val = map.get(condition);
+                    trace.push(|| ResolutionStep::Condition {
+                        name: condition.to_string(),
+                        matched: val.is_some(),
+                    });
+                    select_export_target(val?, conditions, trace)
+                })
         }
         _ => None,
     }
 }
 
-fn detect_format(path: &Utf8Path) -> ModuleFormat {
+/// Guess a module's format from its file name.
+pub fn detect_format(path: &Utf8Path) -> ModuleFormat {
     let path_str = path.as_str();
     if path_str.ends_with(".d.ts") || path_str.ends_with(".d.mts") || path_str.ends_with(".d.cts") {
         return ModuleFormat::TypeDefinition;
@@ -0,0 +1,350 @@
+//! Yarn Plug'n'Play support.
+//! Reads the PnP manifest (`.pnp.data.json`, or the state inlined in `.pnp.cjs`) and maps
+//! bare specifiers to package locations: unplugged directories on disk, or packages
+//! inside the zip archives of the Yarn cache.
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+const DATA_FILE: &str = ".pnp.data.json";
+const RUNTIME_FILE: &str = ".pnp.cjs";
+
+/// Where a package was found through the PnP manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnpResolution {
+    /// The manifest the package was resolved through
+    pub manifest: String,
+    pub package_name: String,
+    /// Yarn locator reference, e.g. `npm:4.17.21`
+    pub reference: String,
+    /// Package root, with `__virtual__` segments resolved to the real location
+    pub package_location: String,
+    /// The cache archive holding the package, when it isn't unplugged
+    pub zip_path: Option<String>,
+    /// Path of the requested module inside `zip_path`
+    pub inner_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum DependencyTarget {
+    Reference(String),
+    /// `[name, reference]` for aliased dependencies
+    Alias(String, String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageInformation {
+    package_location: String,
+    #[serde(default)]
+    package_dependencies: Dependencies,
+    #[serde(default)]
+    discard_from_lookup: bool,
+}
+
+/// `(name, reference)`; both are null for the top-level package
+type Locator = (Option<String>, Option<String>);
+type Dependencies = Vec<(String, Option<DependencyTarget>)>;
+type ManifestCache = Mutex<HashMap<Utf8PathBuf, (SystemTime, Arc<PnpManifest>)>>;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawManifest {
+    #[serde(default)]
+    enable_top_level_fallback: bool,
+    #[serde(default)]
+    fallback_pool: Dependencies,
+    #[serde(default)]
+    fallback_exclusion_list: Vec<(String, Vec<String>)>,
+    /// `[name, [[reference, information], ...]]` entries
+    #[allow(clippy::type_complexity)]
+    package_registry_data: Vec<(Option<String>, Vec<(Option<String>, PackageInformation)>)>,
+}
+
+/// A parsed PnP manifest.
+#[derive(Debug)]
+pub struct PnpManifest {
+    path: Utf8PathBuf,
+    /// Directory package locations are relative to
+    root: Utf8PathBuf,
+    packages: HashMap<Locator, PackageInformation>,
+    /// Package locations, longest first, for finding the package owning a file
+    locations: Vec<(Utf8PathBuf, Locator)>,
+    enable_top_level_fallback: bool,
+    fallback_pool: HashMap<String, Option<DependencyTarget>>,
+    fallback_exclusions: HashMap<String, Vec<String>>,
+}
+
+/// Outcome of looking a bare specifier up in the manifest.
+#[derive(Debug)]
+pub enum PnpLookup {
+    Found(PnpResolution),
+    /// The importer isn't part of the dependency tree; resolve it normally
+    NotInTree,
+    /// The package exists in the tree but can't be required from the importer
+    Failed(String),
+}
+
+impl PnpManifest {
+    /// Parse the manifest at `path`, either a `.pnp.data.json` or a `.pnp.cjs`.
+    pub fn load(path: &Utf8Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let json = if path.file_name() == Some(RUNTIME_FILE) {
+            extract_runtime_state(&content)
+                .ok_or_else(|| anyhow!("no inline PnP state in {}", path))?
+        } else {
+            content
+        };
+        let raw: RawManifest = serde_json::from_str(&json)?;
+        let root = path
+            .parent()
+            .map(|p| p.to_owned())
+            .ok_or_else(|| anyhow!("manifest path has no parent"))?;
+
+        let mut packages = HashMap::new();
+        let mut locations = Vec::new();
+        for (name, references) in raw.package_registry_data {
+            for (reference, info) in references {
+                let locator = (name.clone(), reference);
+                if !info.discard_from_lookup {
+                    let location = crate::normalize_path(&root.join(&info.package_location));
+                    locations.push((location, locator.clone()));
+                }
+                packages.insert(locator, info);
+            }
+        }
+        locations.sort_by_key(|(location, _)| std::cmp::Reverse(location.as_str().len()));
+
+        Ok(Self {
+            path: path.to_owned(),
+            root,
+            packages,
+            locations,
+            enable_top_level_fallback: raw.enable_top_level_fallback,
+            fallback_pool: raw.fallback_pool.into_iter().collect(),
+            fallback_exclusions: raw.fallback_exclusion_list.into_iter().collect(),
+        })
+    }
+
+    /// Resolve `package` (plus `subpath`, `.` for the package root) as required from
+    /// a file in `importer_dir`.
+    pub fn resolve(&self, importer_dir: &Utf8Path, package: &str, subpath: &str) -> PnpLookup {
+        let importer_dir = crate::normalize_path(importer_dir);
+        let Some(issuer) = self.find_locator(&importer_dir) else {
+            return PnpLookup::NotInTree;
+        };
+        let Some(issuer_info) = self.packages.get(issuer) else {
+            return PnpLookup::NotInTree;
+        };
+
+        let declared = issuer_info
+            .package_dependencies
+            .iter()
+            .find(|(name, _)| name == package)
+            .map(|(_, target)| target);
+        let target = match declared {
+            Some(Some(target)) => target,
+            Some(None) => {
+                return PnpLookup::Failed(format!(
+                    "{} tries to access '{}', a peer dependency that isn't provided by its parent",
+                    display_locator(issuer),
+                    package
+                ))
+            }
+            None => match self.fallback(issuer, package) {
+                Some(target) => target,
+                None => {
+                    return PnpLookup::Failed(format!(
+                        "{} tries to access '{}', which isn't declared in its dependencies",
+                        display_locator(issuer),
+                        package
+                    ))
+                }
+            },
+        };
+
+        let locator = match target {
+            DependencyTarget::Reference(reference) => {
+                (Some(package.to_string()), Some(reference.clone()))
+            }
+            DependencyTarget::Alias(name, reference) => {
+                (Some(name.clone()), Some(reference.clone()))
+            }
+        };
+        let Some(info) = self.packages.get(&locator) else {
+            return PnpLookup::Failed(format!(
+                "'{}' is missing from the PnP manifest",
+                display_locator(&locator)
+            ));
+        };
+
+        let location = devirtualize(&crate::normalize_path(
+            &self.root.join(&info.package_location),
+        ));
+        let (zip_path, inner_path) = match split_zip_path(&location) {
+            Some((zip, inner)) => {
+                let inner = match subpath.trim_start_matches("./") {
+                    "." | "" => inner,
+                    rest => format!("{}/{}", inner.trim_end_matches('/'), rest),
+                };
+                (Some(zip.to_string()), Some(inner))
+            }
+            None => (None, None),
+        };
+
+        PnpLookup::Found(PnpResolution {
+            manifest: self.path.to_string(),
+            package_name: locator.0.unwrap_or_default(),
+            reference: locator.1.unwrap_or_default(),
+            package_location: location.to_string(),
+            zip_path,
+            inner_path,
+        })
+    }
+
+    fn find_locator(&self, dir: &Utf8Path) -> Option<&Locator> {
+        self.locations
+            .iter()
+            .find(|(location, _)| dir.starts_with(location))
+            .map(|(_, locator)| locator)
+    }
+
+    /// Dependencies of the top-level workspace, then the fallback pool, for packages
+    /// relying on hoisting that Yarn still lets through
+    fn fallback(&self, issuer: &Locator, package: &str) -> Option<&DependencyTarget> {
+        if !self.enable_top_level_fallback {
+            return None;
+        }
+        if let (Some(name), Some(reference)) = issuer {
+            if self
+                .fallback_exclusions
+                .get(name)
+                .is_some_and(|refs| refs.contains(reference))
+            {
+                return None;
+            }
+        }
+        self.packages
+            .get(&(None, None))
+            .and_then(|top| {
+                top.package_dependencies
+                    .iter()
+                    .find(|(name, _)| name == package)
+                    .and_then(|(_, target)| target.as_ref())
+            })
+            .or_else(|| self.fallback_pool.get(package).and_then(|t| t.as_ref()))
+    }
+}
+
+/// Find the PnP manifest governing `start`, without leaving the project root.
+pub fn find_manifest(start: &Utf8Path, project_root: Option<&Utf8Path>) -> Option<Utf8PathBuf> {
+    let mut current = start.to_path_buf();
+    loop {
+        for file in [DATA_FILE, RUNTIME_FILE] {
+            let candidate = current.join(file);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        if project_root.is_some_and(|root| current == root) || !current.pop() {
+            return None;
+        }
+    }
+}
+
+/// Load the manifest at `path`, reusing the parsed copy until the file changes.
+pub fn load_cached(path: &Utf8Path) -> Result<Arc<PnpManifest>> {
+    static CACHE: OnceLock<ManifestCache> = OnceLock::new();
+    let modified = fs::metadata(path)?.modified()?;
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some((stamp, manifest)) = cache.lock().unwrap().get(path) {
+        if *stamp == modified {
+            return Ok(manifest.clone());
+        }
+    }
+    let manifest = Arc::new(PnpManifest::load(path)?);
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_owned(), (modified, manifest.clone()));
+    Ok(manifest)
+}
+
+/// The JSON of `RAW_RUNTIME_STATE`, which `.pnp.cjs` inlines as a string literal
+/// unless `pnpEnableInlining` is off.
+fn extract_runtime_state(source: &str) -> Option<String> {
+    let start = source.find("RAW_RUNTIME_STATE")?;
+    let rest = source[start..].split_once('=')?.1.trim_start();
+    let quote = rest.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+
+    let mut out = String::new();
+    let mut chars = rest[1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == quote => return Some(out),
+            '\\' => match chars.next()? {
+                // Line continuation
+                '\n' => {}
+                '\r' => {
+                    let mut peek = chars.clone();
+                    if peek.next() == Some('\n') {
+                        chars = peek;
+                    }
+                }
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                other => out.push(other),
+            },
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+/// Map `<base>/__virtual__/<hash>/<depth>/<rest>` to the real `<base>/../(depth times)/<rest>`.
+/// Yarn uses virtual paths to give each set of peer dependencies its own package instance.
+fn devirtualize(path: &Utf8Path) -> Utf8PathBuf {
+    let components: Vec<&str> = path.components().map(|c| c.as_str()).collect();
+    let Some(index) = components.iter().position(|c| *c == "__virtual__") else {
+        return path.to_owned();
+    };
+    let Some(depth) = components
+        .get(index + 2)
+        .and_then(|depth| depth.parse::<usize>().ok())
+    else {
+        return path.to_owned();
+    };
+    let mut real: Utf8PathBuf = components[..index].iter().collect();
+    for _ in 0..depth {
+        real.pop();
+    }
+    real.extend(&components[index + 3..]);
+    real
+}
+
+/// Split a path inside a zip archive into the archive and the path within it.
+fn split_zip_path(path: &Utf8Path) -> Option<(Utf8PathBuf, String)> {
+    let components: Vec<&str> = path.components().map(|c| c.as_str()).collect();
+    let index = components.iter().position(|c| c.ends_with(".zip"))?;
+    let zip: Utf8PathBuf = components[..=index].iter().collect();
+    Some((zip, components[index + 1..].join("/")))
+}
+
+fn display_locator(locator: &Locator) -> String {
+    match locator {
+        (Some(name), Some(reference)) => format!("{}@{}", name, reference),
+        _ => "The project root".to_string(),
+    }
+}
@@ -0,0 +1,72 @@
+//! Resolution tracing.
+//! With `ResolveOptions::trace` set, `resolve_module_native` records the
+//! directories and files it probes, the `exports`/`imports` entries and
+//! conditions it evaluates, and the fallbacks it takes, so users can see why a
+//! specifier resolved where it did.
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResolutionStep {
+    /// A `node_modules/<package>` directory looked for, walking up from the importer
+    PackageDir { path: String, found: bool },
+    /// A file tried as written, with an extension added, or as a directory index
+    File { path: String, found: bool },
+    /// The `exports` or `imports` entry looked up for a subpath
+    Entry {
+        field: String,
+        subpath: String,
+        matched: bool,
+    },
+    /// A condition checked against an `exports`/`imports` target, in preference
+    /// order; `matched` when the target lists it
+    Condition { name: String, matched: bool },
+    /// A package.json field read to locate the module, e.g. `main` or `browser`
+    Field { name: String, value: String },
+    /// A fallback taken because the preferred lookup didn't apply
+    Fallback { reason: String },
+}
+
+/// Steps recorded during one resolution; records nothing unless enabled.
+#[derive(Default)]
+pub(crate) struct Trace {
+    steps: Option<Vec<ResolutionStep>>,
+}
+
+impl Trace {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            steps: enabled.then(Vec::new),
+        }
+    }
+
+    /// Record a step, built only when tracing is enabled.
+    pub(crate) fn push(&mut self, step: impl FnOnce() -> ResolutionStep) {
+        if let Some(steps) = &mut self.steps {
+            steps.push(step());
+        }
+    }
+
+    pub(crate) fn file(&mut self, path: &Utf8Path, found: bool) {
+        self.push(|| ResolutionStep::File {
+            path: path.to_string(),
+            found,
+        });
+    }
+
+    pub(crate) fn fallback(&mut self, reason: impl FnOnce() -> String) {
+        self.push(|| ResolutionStep::Fallback { reason: reason() });
+    }
+
+    /// Append the steps of a nested resolution.
+    pub(crate) fn extend(&mut self, nested: Vec<ResolutionStep>) {
+        if let Some(steps) = &mut self.steps {
+            steps.extend(nested);
+        }
+    }
+
+    pub(crate) fn into_steps(self) -> Vec<ResolutionStep> {
+        self.steps.unwrap_or_default()
+    }
+}
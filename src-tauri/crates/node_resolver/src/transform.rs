@@ -0,0 +1,200 @@
+//! Module transforms.
+//! Strips TypeScript syntax and converts between ES modules and CommonJS, so the
+//! editor can preview or run a module. ES modules go through SWC's CommonJS
+//! transform. CommonJS modules are wrapped for ESM loaders instead: `require`
+//! comes from `createRequire`, `module.exports` becomes the default export, and
+//! `exports.name = ...` assignments become named exports. JSX is kept as is.
+
+use anyhow::{Context, Result};
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS};
+use swc_core::ecma::ast::{
+    AssignExpr, AssignTarget, EsVersion, Expr, Ident, MemberExpr, MemberProp, ModuleItem, Program,
+    SimpleAssignTarget,
+};
+use swc_core::ecma::codegen::{text_writer::JsWriter, Config as CodegenConfig, Emitter};
+use swc_core::ecma::parser::{lexer::Lexer, Parser, StringInput};
+use swc_core::ecma::transforms::base::{fixer::fixer, hygiene::hygiene, resolver};
+use swc_core::ecma::transforms::module::{common_js, path::Resolver};
+use swc_core::ecma::transforms::typescript::strip;
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModuleTarget {
+    Esm,
+    CommonJs,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransformOptions {
+    /// Remove type annotations, interfaces and other TypeScript-only syntax
+    #[serde(default)]
+    pub strip_types: bool,
+    /// The module system to convert to; the module's own is kept when unset
+    #[serde(default)]
+    pub module: Option<ModuleTarget>,
+}
+
+impl TransformOptions {
+    pub(crate) fn is_identity(&self) -> bool {
+        !self.strip_types && self.module.is_none()
+    }
+}
+
+/// Parse `code`, the source of `module_path`, and generate it again with
+/// `options` applied. Comments are kept.
+pub(crate) fn transform_module(
+    module_path: &Utf8Path,
+    code: &str,
+    options: &TransformOptions,
+) -> Result<String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(
+        FileName::Custom(module_path.to_string()).into(),
+        code.to_string(),
+    );
+    let comments = SingleThreadedComments::default();
+    let lexer = Lexer::new(
+        crate::syntax_for(module_path),
+        EsVersion::EsNext,
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+    let module = Parser::new_from(lexer)
+        .parse_module()
+        .map_err(|err| anyhow::Error::msg(format!("Parse error: {:?}", err)))?;
+
+    // Modules without imports or exports are treated as CommonJS
+    let is_esm = module
+        .body
+        .iter()
+        .any(|item| matches!(item, ModuleItem::ModuleDecl(_)));
+    let is_ts = matches!(module_path.extension(), Some("ts" | "tsx" | "mts" | "cts"));
+
+    GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        let mut program = Program::Module(module);
+        program.mutate(resolver(unresolved_mark, top_level_mark, is_ts));
+        if options.strip_types && is_ts {
+            program.mutate(strip(unresolved_mark, top_level_mark));
+        }
+        if options.module == Some(ModuleTarget::CommonJs) && is_esm {
+            program.mutate(common_js(
+                Resolver::Default,
+                unresolved_mark,
+                Default::default(),
+                Default::default(),
+            ));
+        }
+        program.mutate(hygiene());
+        program.mutate(fixer(Some(&comments)));
+
+        let output = emit(&cm, &comments, &program)?;
+        if options.module == Some(ModuleTarget::Esm) && !is_esm {
+            let mut exports = CommonJsExports::default();
+            program.visit_with(&mut exports);
+            return Ok(wrap_commonjs(&output, &exports));
+        }
+        Ok(output)
+    })
+}
+
+fn emit(
+    cm: &Lrc<SourceMap>,
+    comments: &SingleThreadedComments,
+    program: &Program,
+) -> Result<String> {
+    let mut buf = Vec::new();
+    {
+        let mut emitter = Emitter {
+            cfg: CodegenConfig::default().with_target(EsVersion::EsNext),
+            cm: cm.clone(),
+            comments: Some(comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter
+            .emit_program(program)
+            .context("Failed to generate code")?;
+    }
+    String::from_utf8(buf).context("Generated code is not UTF-8")
+}
+
+/// What a CommonJS module exports and which module-scoped globals it uses
+#[derive(Default)]
+struct CommonJsExports {
+    /// Names assigned through `exports.name` or `module.exports.name`
+    names: BTreeSet<String>,
+    /// Uses `__filename` or `__dirname`
+    uses_paths: bool,
+}
+
+impl Visit for CommonJsExports {
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left {
+            if let (true, MemberProp::Ident(name)) = (is_exports_object(&member.obj), &member.prop)
+            {
+                if &*name.sym != "default" {
+                    self.names.insert(name.sym.to_string());
+                }
+            }
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if matches!(&*ident.sym, "__filename" | "__dirname") {
+            self.uses_paths = true;
+        }
+    }
+}
+
+/// `exports` or `module.exports`
+fn is_exports_object(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(ident) => &*ident.sym == "exports",
+        Expr::Member(MemberExpr { obj, prop, .. }) => {
+            matches!(&**obj, Expr::Ident(module) if &*module.sym == "module")
+                && matches!(prop, MemberProp::Ident(name) if &*name.sym == "exports")
+        }
+        _ => false,
+    }
+}
+
+/// Run a CommonJS module body as an ES module
+fn wrap_commonjs(body: &str, exports: &CommonJsExports) -> String {
+    let mut out =
+        String::from("import { createRequire as __createRequire } from \"node:module\";\n");
+    if exports.uses_paths {
+        out.push_str("import { fileURLToPath as __fileURLToPath } from \"node:url\";\n");
+        out.push_str("import { dirname as __pathDirname } from \"node:path\";\n");
+    }
+    out.push_str("const require = __createRequire(import.meta.url);\n");
+    if exports.uses_paths {
+        out.push_str("const __filename = __fileURLToPath(import.meta.url);\n");
+        out.push_str("const __dirname = __pathDirname(__filename);\n");
+    }
+    out.push_str("const module = { exports: {} };\nlet exports = module.exports;\n");
+    out.push_str(body);
+    if !body.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str("export default module.exports;\n");
+
+    // Export names can be keywords, so each goes through a local binding
+    let mut specifiers = Vec::new();
+    for (index, name) in exports.names.iter().enumerate() {
+        out.push_str(&format!(
+            "const __cjs_export_{} = module.exports.{};\n",
+            index, name
+        ));
+        specifiers.push(format!("__cjs_export_{} as {}", index, name));
+    }
+    if !specifiers.is_empty() {
+        out.push_str(&format!("export {{ {} }};\n", specifiers.join(", ")));
+    }
+    out
+}
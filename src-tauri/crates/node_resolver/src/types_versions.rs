@@ -0,0 +1,143 @@
+//! `typesVersions` support.
+//! Packages can redirect their declaration files per TypeScript version, e.g.
+//! `{ "typesVersions": { ">=4.2": { "*": ["ts4.2/*"] } } }`. The first entry whose
+//! range matches the compiler version applies; its path mappings are matched like
+//! `exports` patterns.
+
+use std::fmt;
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// TypeScript version typings are discovered for
+const TYPESCRIPT_VERSION: [u64; 3] = [5, 6, 0];
+
+/// The path mappings of a package's applicable `typesVersions` entry.
+pub(crate) struct TypesVersions {
+    paths: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(rename = "typesVersions", default)]
+    types_versions: Option<OrderedEntries>,
+}
+
+/// Object entries in document order; the first matching range wins, so the
+/// sorted map `Value` would otherwise use is not enough.
+struct OrderedEntries(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedEntries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = OrderedEntries;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+impl TypesVersions {
+    /// The `typesVersions` entry of the package in `pkg_dir` that applies to the
+    /// compiler version, if any.
+    pub(crate) fn load(pkg_dir: &Utf8Path) -> Option<Self> {
+        let content = fs::read_to_string(pkg_dir.join("package.json")).ok()?;
+        let manifest: Manifest = serde_json::from_str(&content).ok()?;
+        manifest
+            .types_versions?
+            .0
+            .into_iter()
+            .find(|(range, _)| range_matches(range, TYPESCRIPT_VERSION))
+            .and_then(|(_, paths)| match paths {
+                Value::Object(paths) => Some(Self { paths }),
+                _ => None,
+            })
+    }
+
+    /// Redirect `path` (relative to the package root). Returns the first mapped
+    /// declaration file that exists, or `None` if no mapping applies.
+    pub(crate) fn remap(&self, pkg_dir: &Utf8Path, path: &str) -> Option<Utf8PathBuf> {
+        let path = path.trim_start_matches("./");
+        let (targets, matched) = crate::match_subpath_pattern(&self.paths, path)?;
+        let targets = match targets {
+            Value::Array(targets) => targets.iter().filter_map(Value::as_str).collect(),
+            Value::String(target) => vec![target.as_str()],
+            _ => Vec::new(),
+        };
+        targets.into_iter().find_map(|target| {
+            let target = match &matched {
+                Some(matched) => target.replace('*', matched),
+                None => target.to_string(),
+            };
+            declaration_file(&pkg_dir.join(target.trim_start_matches("./")))
+        })
+    }
+}
+
+/// `candidate` itself, with a `.d.ts` extension added, or its `index.d.ts`
+pub(crate) fn declaration_file(candidate: &Utf8Path) -> Option<Utf8PathBuf> {
+    if candidate.is_file() {
+        return Some(candidate.to_owned());
+    }
+    let with_extension = Utf8PathBuf::from(format!("{}.d.ts", candidate));
+    if with_extension.is_file() {
+        return Some(with_extension);
+    }
+    let index = candidate.join("index.d.ts");
+    index.is_file().then_some(index)
+}
+
+/// Whether `version` satisfies a range such as `>=4.2`, `>=3.1 <4`, `4.2 || 4.3` or `*`.
+fn range_matches(range: &str, version: [u64; 3]) -> bool {
+    range.split("||").any(|alternative| {
+        alternative
+            .split_whitespace()
+            .all(|comparator| comparator_matches(comparator, version))
+    })
+}
+
+fn comparator_matches(comparator: &str, version: [u64; 3]) -> bool {
+    if comparator == "*" {
+        return true;
+    }
+    let (op, bound) = [">=", "<=", ">", "<", "="]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("=", comparator));
+    let parts: Option<Vec<u64>> = bound
+        .trim_start_matches('v')
+        .split('.')
+        .take(3)
+        .map(|part| part.parse().ok())
+        .collect();
+    let Some(parts) = parts.filter(|parts| !parts.is_empty()) else {
+        return false;
+    };
+    let mut full = [0; 3];
+    full[..parts.len()].copy_from_slice(&parts);
+    match op {
+        ">=" => version >= full,
+        "<=" => version <= full,
+        ">" => version > full,
+        "<" => version < full,
+        // A partial version matches everything it prefixes: `4.2` is 4.2.x
+        _ => version[..parts.len()] == parts[..],
+    }
+}
@@ -0,0 +1,239 @@
+//! Typings bundles.
+//! Starting from a package's entry declaration files, follows `/// <reference path>`
+//! directives and relative imports and re-exports through the declaration files
+//! they point to, so an editor gets exactly the files a package's types are made
+//! of. Bare specifiers and `/// <reference types>` directives name other packages;
+//! those are reported rather than followed.
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleOptions {
+    /// Bundle the typings of one entry point, e.g. `auth` for `firebase/auth`
+    pub subpath: Option<String>,
+    /// Return each file's source along with its path
+    pub include_content: bool,
+    /// Also return every file's source concatenated, in bundle order
+    pub concatenate: bool,
+    /// Stop following references after this many files
+    pub max_files: usize,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            subpath: None,
+            include_content: false,
+            concatenate: false,
+            max_files: 2000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypingsFile {
+    pub path: String,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypingsBundle {
+    pub package_name: String,
+    /// The declaration file the package's types start from
+    pub entry: Option<String>,
+    /// Entry files first, then the files they reference, each once
+    pub files: Vec<TypingsFile>,
+    /// Other packages the typings import or reference, sorted
+    pub packages: Vec<String>,
+    pub package_json: Option<String>,
+    /// `max_files` was reached before every reference was followed
+    pub truncated: bool,
+    /// Every file's source, each under a `// <path>` header, with `concatenate`
+    pub bundle: Option<String>,
+}
+
+/// Collect the declaration files of a package by following references from its
+/// entry declaration files.
+pub fn bundle_typings_native(
+    package_name: &str,
+    project_root: &Utf8Path,
+    options: BundleOptions,
+) -> Result<TypingsBundle> {
+    let entries = crate::typings_entries(package_name, project_root, options.subpath.as_deref());
+
+    let mut queue: VecDeque<Utf8PathBuf> = entries.files.iter().cloned().collect();
+    let mut seen: HashSet<Utf8PathBuf> = HashSet::new();
+    let mut files = Vec::new();
+    let mut packages = BTreeSet::new();
+    let mut bundle = options.concatenate.then(String::new);
+    let mut truncated = false;
+
+    while let Some(path) = queue.pop_front() {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        if files.len() >= options.max_files {
+            truncated = true;
+            break;
+        }
+        // Dangling references are left out rather than failing the bundle
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let dependencies = scan_dependencies(&content);
+        let dir = path.parent().unwrap_or(Utf8Path::new(""));
+        for reference in &dependencies.reference_paths {
+            queue.extend(resolve_declaration(dir, reference));
+        }
+        for specifier in &dependencies.specifiers {
+            if specifier.starts_with("./") || specifier.starts_with("../") {
+                queue.extend(resolve_declaration(dir, specifier));
+            } else if !specifier.starts_with('/') && !crate::is_node_builtin(specifier) {
+                packages.insert(crate::split_package_specifier(specifier).0);
+            }
+        }
+        packages.extend(dependencies.reference_types);
+
+        if let Some(bundle) = &mut bundle {
+            bundle.push_str(&format!("// {}\n", path));
+            bundle.push_str(&content);
+            if !content.ends_with('\n') {
+                bundle.push('\n');
+            }
+        }
+        files.push(TypingsFile {
+            path: path.to_string(),
+            content: options.include_content.then_some(content),
+        });
+    }
+    packages.remove(&entries.package_name);
+
+    Ok(TypingsBundle {
+        package_name: entries.package_name,
+        entry: entries.files.first().map(|entry| entry.to_string()),
+        files,
+        packages: packages.into_iter().collect(),
+        package_json: entries.package_json,
+        truncated,
+        bundle,
+    })
+}
+
+/// What a declaration file points at
+#[derive(Debug, Default)]
+struct Dependencies {
+    /// `/// <reference path="..." />`
+    reference_paths: Vec<String>,
+    /// `/// <reference types="..." />`
+    reference_types: Vec<String>,
+    /// Module specifiers of imports, re-exports, `import("...")` types and
+    /// `require("...")` calls
+    specifiers: Vec<String>,
+}
+
+/// Scan a declaration file for references and module specifiers. Declaration
+/// files hold no executable code, so looking at what precedes each string
+/// literal is enough; `declare module "..."` names are not specifiers.
+fn scan_dependencies(source: &str) -> Dependencies {
+    let mut dependencies = Dependencies::default();
+    for line in source.lines() {
+        let Some(directive) = line.trim_start().strip_prefix("///") else {
+            continue;
+        };
+        if !directive.trim_start().starts_with("<reference") {
+            continue;
+        }
+        if let Some(path) = directive_attribute(directive, "path") {
+            dependencies.reference_paths.push(path.to_string());
+        } else if let Some(types) = directive_attribute(directive, "types") {
+            dependencies.reference_types.push(types.to_string());
+        }
+    }
+
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                let literal = &source[start + 1..i.min(bytes.len())];
+                i += 1;
+                if quote != b'`' && is_specifier_position(&source[..start]) {
+                    dependencies.specifiers.push(literal.to_string());
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    dependencies
+}
+
+/// The value of `name="..."` in a triple-slash directive
+fn directive_attribute<'a>(directive: &'a str, name: &str) -> Option<&'a str> {
+    let (_, rest) = directive.split_once(&format!("{}=", name))?;
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    rest[1..].split(quote).next()
+}
+
+/// Whether a string literal following `before` is a module specifier:
+/// `from "x"`, `import "x"`, `import("x")` or `require("x")`
+fn is_specifier_position(before: &str) -> bool {
+    let before = before.trim_end();
+    match before.strip_suffix('(') {
+        Some(call) => {
+            let call = call.trim_end();
+            ends_with_keyword(call, "import") || ends_with_keyword(call, "require")
+        }
+        None => ends_with_keyword(before, "from") || ends_with_keyword(before, "import"),
+    }
+}
+
+fn ends_with_keyword(text: &str, keyword: &str) -> bool {
+    text.strip_suffix(keyword).is_some_and(|rest| {
+        !rest
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.'))
+    })
+}
+
+/// The declaration file a relative specifier or reference path points at:
+/// `./a.js` is typed by `./a.d.ts`, `./a` by `./a.d.ts` or `./a/index.d.ts`
+fn resolve_declaration(dir: &Utf8Path, specifier: &str) -> Option<Utf8PathBuf> {
+    let target = crate::normalize_path(&dir.join(specifier));
+    if target.is_file() && matches!(target.extension(), Some("ts" | "mts" | "cts" | "tsx")) {
+        return Some(target);
+    }
+    let as_string = target.as_str();
+    for (js, dts) in [(".js", ".d.ts"), (".mjs", ".d.mts"), (".cjs", ".d.cts")] {
+        if let Some(stem) = as_string.strip_suffix(js) {
+            let candidate = Utf8PathBuf::from(format!("{}{}", stem, dts));
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    [".d.ts", ".d.mts", ".d.cts", ".ts"]
+        .iter()
+        .map(|ext| Utf8PathBuf::from(format!("{}{}", as_string, ext)))
+        .chain([target.join("index.d.ts")])
+        .find(|candidate| candidate.is_file())
+}
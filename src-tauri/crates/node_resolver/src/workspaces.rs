@@ -0,0 +1,222 @@
+//! Monorepo workspace support.
+//! Finds sibling packages declared through `package.json` "workspaces" globs or
+//! `pnpm-workspace.yaml`, and follows `workspace:` dependency aliases, so packages
+//! that were never linked into node_modules still resolve to their source.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::Value;
+
+const PNPM_WORKSPACE_FILE: &str = "pnpm-workspace.yaml";
+const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Find the directory of workspace package `name`, as imported from `start`.
+pub(crate) fn find_workspace_package(
+    start: &Utf8Path,
+    project_root: Option<&Utf8Path>,
+    name: &str,
+) -> Option<Utf8PathBuf> {
+    let name = workspace_alias(start, project_root, name).unwrap_or_else(|| name.to_string());
+    let (root, patterns) = find_workspace_root(start, project_root)?;
+    workspace_packages(&root, &patterns)
+        .into_iter()
+        .find(|(package, _)| *package == name)
+        .map(|(_, dir)| dir)
+}
+
+/// The package a `workspace:` dependency of the importing package points at.
+/// `"ui": "workspace:*"` names the package itself; `"ui": "workspace:@acme/ui@*"`
+/// aliases another one.
+fn workspace_alias(
+    start: &Utf8Path,
+    project_root: Option<&Utf8Path>,
+    name: &str,
+) -> Option<String> {
+    let scope = crate::find_package_scope(start, project_root)?;
+    let pkg = crate::read_package_json(&scope).ok()?;
+    let spec = DEPENDENCY_FIELDS
+        .iter()
+        .find_map(|field| pkg.get(field)?.get(name)?.as_str())?;
+    let target = spec.strip_prefix("workspace:")?;
+    match target.rfind('@') {
+        Some(at) if at > 0 => Some(target[..at].to_string()),
+        _ if target.starts_with('@') || target.chars().next()?.is_ascii_alphabetic() => {
+            Some(target.to_string())
+        }
+        _ => Some(name.to_string()),
+    }
+}
+
+/// The nearest directory at or above `start` declaring workspaces, with its
+/// package globs
+fn find_workspace_root(
+    start: &Utf8Path,
+    project_root: Option<&Utf8Path>,
+) -> Option<(Utf8PathBuf, Vec<String>)> {
+    let mut current = start.to_path_buf();
+    loop {
+        if let Some(patterns) = read_workspace_patterns(&current) {
+            return Some((current, patterns));
+        }
+        if project_root.is_some_and(|root| current == root) || !current.pop() {
+            return None;
+        }
+    }
+}
+
+fn read_workspace_patterns(dir: &Utf8Path) -> Option<Vec<String>> {
+    if let Ok(yaml) = fs::read_to_string(dir.join(PNPM_WORKSPACE_FILE)) {
+        return Some(parse_pnpm_workspace(&yaml));
+    }
+    let pkg = crate::read_package_json(dir).ok()?;
+    // `"workspaces": [...]`, or Yarn's `"workspaces": { "packages": [...] }`
+    let workspaces = pkg.get("workspaces")?;
+    let list = workspaces
+        .as_array()
+        .or_else(|| workspaces.get("packages")?.as_array())?;
+    Some(
+        list.iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// The `packages:` list of a pnpm-workspace.yaml. Only the block list form pnpm
+/// documents is understood, which covers the file in practice.
+fn parse_pnpm_workspace(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in yaml.lines() {
+        let content = line.split(" #").next().unwrap_or_default().trim_end();
+        if content.trim().is_empty() || content.trim_start().starts_with('#') {
+            continue;
+        }
+        if !content.starts_with(' ') && !content.starts_with('-') {
+            in_packages = content.trim() == "packages:";
+            continue;
+        }
+        if let Some(item) = content.trim().strip_prefix('-').filter(|_| in_packages) {
+            patterns.push(
+                item.trim()
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string(),
+            );
+        }
+    }
+    patterns
+}
+
+/// Names and directories of the packages matched by `patterns` under `root`.
+/// Patterns starting with `!` exclude directories.
+fn workspace_packages(root: &Utf8Path, patterns: &[String]) -> Vec<(String, Utf8PathBuf)> {
+    let (excludes, includes): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    let excludes: Vec<Vec<&str>> = excludes
+        .iter()
+        .map(|p| segments(p.trim_start_matches('!')))
+        .collect();
+
+    let mut dirs = Vec::new();
+    for pattern in includes {
+        expand(root, &segments(pattern), &mut dirs);
+    }
+    dirs.sort();
+    dirs.dedup();
+
+    dirs.into_iter()
+        .filter(|dir| {
+            let relative = dir.strip_prefix(root).unwrap_or(dir);
+            let parts: Vec<&str> = relative.components().map(|c| c.as_str()).collect();
+            !excludes.iter().any(|exclude| matches_path(exclude, &parts))
+        })
+        .filter_map(|dir| {
+            let pkg = crate::read_package_json(&dir).ok()?;
+            let name = pkg.get("name")?.as_str()?.to_string();
+            Some((name, dir))
+        })
+        .collect()
+}
+
+fn segments(pattern: &str) -> Vec<&str> {
+    pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect()
+}
+
+/// Collect directories under `dir` matching `pattern` segment by segment
+fn expand(dir: &Utf8Path, pattern: &[&str], out: &mut Vec<Utf8PathBuf>) {
+    let Some((first, rest)) = pattern.split_first() else {
+        if dir.join("package.json").is_file() {
+            out.push(dir.to_owned());
+        }
+        return;
+    };
+    if *first == "**" {
+        // Zero or more directories
+        expand(dir, rest, out);
+        for child in child_dirs(dir) {
+            expand(&child, pattern, out);
+        }
+    } else if first.contains('*') {
+        for child in child_dirs(dir) {
+            if child.file_name().is_some_and(|name| wildcard(first, name)) {
+                expand(&child, rest, out);
+            }
+        }
+    } else {
+        let child = dir.join(first);
+        if child.is_dir() {
+            expand(&child, rest, out);
+        }
+    }
+}
+
+fn child_dirs(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let Ok(entries) = dir.read_dir_utf8() else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| !matches!(entry.file_name(), "node_modules" | ".git"))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+fn matches_path(pattern: &[&str], parts: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => parts.is_empty(),
+        Some((&"**", rest)) => (0..=parts.len()).any(|skip| matches_path(rest, &parts[skip..])),
+        Some((first, rest)) => parts
+            .split_first()
+            .is_some_and(|(part, tail)| wildcard(first, part) && matches_path(rest, tail)),
+    }
+}
+
+/// Match one path segment against a pattern where `*` matches any run of characters
+fn wildcard(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=text.len())
+                .filter(|i| text.is_char_boundary(*i))
+                .any(|i| wildcard(rest, &text[i..]))
+        }
+    }
+}
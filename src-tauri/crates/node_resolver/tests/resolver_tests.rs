@@ -3,8 +3,11 @@ use std::io::Write;
 
 use camino::Utf8PathBuf;
 use fluxel_node_resolver::{
-    analyze_module_native, discover_typings_native, resolve_module_native, AnalyzeResponse,
-    ResolveOptions, ResolveRequest,
+    analyze_module_native, bundle_typings_native, collect_exports_native,
+    collect_import_specifiers_native, discover_typings_native, extract_dts_symbols,
+    is_node_builtin, resolve_module_native, walk_module_graph_native, AnalyzeResponse,
+    BundleOptions, DtsSymbolKind, GraphOptions, ImportBinding, ImportKind, ModuleFormat,
+    ModuleTarget, Platform, ResolutionStep, ResolveOptions, ResolveRequest, TransformOptions,
 };
 use tempfile::tempdir;
 
@@ -63,6 +66,428 @@ fn resolves_exports_with_conditions() {
         .ends_with("node_modules/pkg/esm.js"));
 }
 
+#[test]
+fn resolves_package_imports() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    write_file(
+        &project_root.join("package.json"),
+        r##"{
+  "name": "app",
+  "imports": {
+    "#config": {
+      "node": "./src/config.node.ts",
+      "default": "./src/config.ts"
+    },
+    "#internal/*": "./src/internal/*.ts",
+    "#internal/special/*": "./src/special/*.ts",
+    "#dep": "dep/feature",
+    "#blocked/*": null
+  }
+}"##,
+    );
+    write_file(&project_root.join("src/config.ts"), "export {};");
+    write_file(&project_root.join("src/config.node.ts"), "export {};");
+    write_file(&project_root.join("src/internal/util.ts"), "export {};");
+    write_file(&project_root.join("src/special/thing.ts"), "export {};");
+    write_file(
+        &project_root.join("node_modules/dep/package.json"),
+        r#"{ "name": "dep", "exports": { "./feature": "./feature.js" } }"#,
+    );
+    write_file(&project_root.join("node_modules/dep/feature.js"), "");
+
+    let importer = project_root.join("src/nested/index.ts");
+    write_file(&importer, "");
+    let resolve = |specifier: &str, conditions: &[&str]| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions {
+                conditions: conditions.iter().map(|c| c.to_string()).collect(),
+                ..ResolveOptions::default()
+            }),
+        )
+        .unwrap()
+    };
+
+    let config = resolve("#config", &["import", "default"]);
+    assert!(config.resolved_path.unwrap().ends_with("src/config.ts"));
+    assert!(config.package_json.unwrap().ends_with("package.json"));
+    let node_config = resolve("#config", &["node", "import", "default"]);
+    assert!(node_config
+        .resolved_path
+        .unwrap()
+        .ends_with("src/config.node.ts"));
+
+    let util = resolve("#internal/util", &["default"]);
+    assert!(util
+        .resolved_path
+        .unwrap()
+        .ends_with("src/internal/util.ts"));
+    assert!(util
+        .matched_export
+        .unwrap()
+        .ends_with("src/internal/util.ts"));
+    // The longest matching prefix wins
+    let special = resolve("#internal/special/thing", &["default"]);
+    assert!(special
+        .resolved_path
+        .unwrap()
+        .ends_with("src/special/thing.ts"));
+
+    let dep = resolve("#dep", &["default"]);
+    assert_eq!(dep.matched_export.as_deref(), Some("dep/feature"));
+    assert!(dep
+        .resolved_path
+        .unwrap()
+        .ends_with("node_modules/dep/feature.js"));
+
+    for missing in ["#blocked/x", "#unknown"] {
+        let result = resolve(missing, &["default"]);
+        assert!(result.resolved_path.is_none());
+        assert_eq!(result.warnings.len(), 1);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn resolves_through_pnpm_virtual_store() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let store = project_root.join("node_modules/.pnpm");
+    let package = |id: &str, name: &str, main: &str| {
+        let pkg_dir = store.join(id).join("node_modules").join(name);
+        write_file(
+            &pkg_dir.join("package.json"),
+            &format!(r#"{{ "name": "{}", "main": "{}" }}"#, name, main),
+        );
+        write_file(&pkg_dir.join(main), "");
+        pkg_dir
+    };
+    let app = package("app@1.0.0", "app", "index.js");
+    let react_18 = package("react@18.2.0", "react", "index.js");
+    let react_17 = package("react@17.0.2", "react", "index.js");
+    // One instance of the component library per peer react version
+    let ui_18 = package("ui@1.0.0_react@18.2.0", "ui", "index.js");
+    let ui_17 = package("ui@1.0.0_react@17.0.2", "ui", "index.js");
+    symlink(
+        &react_18,
+        store.join("ui@1.0.0_react@18.2.0/node_modules/react"),
+    )
+    .unwrap();
+    symlink(
+        &react_17,
+        store.join("ui@1.0.0_react@17.0.2/node_modules/react"),
+    )
+    .unwrap();
+    symlink(&ui_18, store.join("app@1.0.0/node_modules/ui")).unwrap();
+    symlink(&react_18, store.join("app@1.0.0/node_modules/react")).unwrap();
+    symlink(&app, project_root.join("node_modules/app")).unwrap();
+
+    let importer = project_root.join("src/index.ts");
+    write_file(&importer, "");
+    let resolve = |specifier: &str, importer: &str| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.into(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions::default()),
+        )
+        .unwrap()
+        .resolved_path
+    };
+
+    // The top-level link resolves to the real package in the store
+    let app_main = resolve("app", importer.as_str()).unwrap();
+    assert!(app_main.ends_with(".pnpm/app@1.0.0/node_modules/app/index.js"));
+    // Undeclared dependencies aren't reachable from the project
+    assert!(resolve("ui", importer.as_str()).is_none());
+
+    // The app's dependencies resolve next to it in the store, including the
+    // ui instance built for its react version
+    let ui_main = resolve("ui", &app_main).unwrap();
+    assert!(ui_main.ends_with(".pnpm/ui@1.0.0_react@18.2.0/node_modules/ui/index.js"));
+    let react = resolve("react", &ui_main).unwrap();
+    assert!(react.ends_with(".pnpm/react@18.2.0/node_modules/react/index.js"));
+
+    // Even when the importer is reached through a symlink
+    let linked_importer = project_root.join("node_modules/app/index.js");
+    assert_eq!(resolve("ui", linked_importer.as_str()).unwrap(), ui_main);
+
+    let other_ui = ui_17.join("index.js");
+    let react = resolve("react", other_ui.as_str()).unwrap();
+    assert!(react.ends_with(".pnpm/react@17.0.2/node_modules/react/index.js"));
+}
+
+#[test]
+fn resolves_through_yarn_pnp_manifest() {
+    let manifest = r#"{
+  "__info": ["This file is automatically generated."],
+  "enableTopLevelFallback": true,
+  "fallbackPool": [],
+  "fallbackExclusionList": [],
+  "packageRegistryData": [
+    [null, [[null, {
+      "packageLocation": "./",
+      "packageDependencies": [["lodash", "npm:4.17.21"], ["native", "npm:1.0.0"], ["ui", "virtual:abc#npm:1.0.0"]],
+      "linkType": "SOFT"
+    }]]],
+    ["app", [["workspace:.", {
+      "packageLocation": "./",
+      "packageDependencies": [["lodash", "npm:4.17.21"], ["native", "npm:1.0.0"], ["ui", "virtual:abc#npm:1.0.0"]],
+      "linkType": "SOFT"
+    }]]],
+    ["lodash", [["npm:4.17.21", {
+      "packageLocation": "./.yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip/node_modules/lodash/",
+      "packageDependencies": [["lodash", "npm:4.17.21"]],
+      "linkType": "HARD"
+    }]]],
+    ["native", [["npm:1.0.0", {
+      "packageLocation": "./.yarn/unplugged/native-npm-1.0.0-0123456789/node_modules/native/",
+      "packageDependencies": [["native", "npm:1.0.0"], ["peer", null]],
+      "linkType": "HARD"
+    }]]],
+    ["ui", [["virtual:abc#npm:1.0.0", {
+      "packageLocation": "./.yarn/__virtual__/ui-virtual-abc/0/cache/ui-npm-1.0.0-fedcba9876.zip/node_modules/ui/",
+      "packageDependencies": [["ui", "virtual:abc#npm:1.0.0"]],
+      "linkType": "HARD"
+    }]]]
+  ]
+}"#;
+
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    write_file(&project_root.join(".pnp.data.json"), manifest);
+    let native_dir =
+        project_root.join(".yarn/unplugged/native-npm-1.0.0-0123456789/node_modules/native");
+    write_file(
+        &native_dir.join("package.json"),
+        r#"{ "name": "native", "main": "lib/index.js" }"#,
+    );
+    write_file(&native_dir.join("lib/index.js"), "require('peer');");
+    let importer = project_root.join("src/index.ts");
+    write_file(&importer, "");
+
+    let resolve = |specifier: &str, importer: &Utf8PathBuf| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions::default()),
+        )
+        .unwrap()
+    };
+
+    // Cached packages are located inside their archive
+    let lodash = resolve("lodash/fp", &importer);
+    assert!(lodash.resolved_path.is_none());
+    let pnp = lodash.pnp.unwrap();
+    assert_eq!(pnp.reference, "npm:4.17.21");
+    assert_eq!(
+        pnp.zip_path.unwrap(),
+        project_root
+            .join(".yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip")
+            .as_str()
+    );
+    assert_eq!(pnp.inner_path.unwrap(), "node_modules/lodash/fp");
+
+    // Unplugged packages resolve on disk
+    let native = resolve("native", &importer);
+    assert_eq!(
+        native.resolved_path.unwrap(),
+        native_dir.join("lib/index.js").as_str()
+    );
+    assert!(native.pnp.unwrap().zip_path.is_none());
+
+    // Virtual instances map to the real archive
+    let ui = resolve("ui", &importer).pnp.unwrap();
+    assert_eq!(
+        ui.zip_path.unwrap(),
+        project_root
+            .join(".yarn/cache/ui-npm-1.0.0-fedcba9876.zip")
+            .as_str()
+    );
+
+    let missing_peer = resolve("peer", &native_dir.join("lib/index.js"));
+    assert!(missing_peer.resolved_path.is_none());
+    assert!(missing_peer.warnings[0].contains("peer dependency"));
+    let undeclared = resolve("left-pad", &importer);
+    assert!(undeclared.pnp.is_none());
+    assert!(undeclared.warnings[0].contains("isn't declared"));
+
+    // The same state inlined in .pnp.cjs
+    fs::remove_file(project_root.join(".pnp.data.json")).unwrap();
+    let inlined = manifest.replace('\n', "\\\n");
+    write_file(
+        &project_root.join(".pnp.cjs"),
+        &format!(
+            "#!/usr/bin/env node\n/* eslint-disable */\n\"use strict\";\n\nconst RAW_RUNTIME_STATE =\n'{}';\n",
+            inlined
+        ),
+    );
+    let native = resolve("native", &importer);
+    assert_eq!(
+        native.resolved_path.unwrap(),
+        native_dir.join("lib/index.js").as_str()
+    );
+}
+
+#[test]
+fn resolves_unlinked_workspace_packages() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    write_file(
+        &project_root.join("package.json"),
+        r#"{ "private": true, "workspaces": ["packages/*", "tools/**", "!packages/legacy"] }"#,
+    );
+    let app = project_root.join("packages/app");
+    write_file(
+        &app.join("package.json"),
+        r#"{
+  "name": "@acme/app",
+  "dependencies": { "@acme/ui": "workspace:*", "widgets": "workspace:@acme/ui@^1.0.0" }
+}"#,
+    );
+    let importer = app.join("src/main.ts");
+    write_file(&importer, "");
+    // Built output is missing, so the sources are used
+    let ui = project_root.join("packages/ui");
+    write_file(
+        &ui.join("package.json"),
+        r#"{ "name": "@acme/ui", "main": "dist/index.js" }"#,
+    );
+    write_file(&ui.join("src/index.ts"), "");
+    write_file(&ui.join("src/button.tsx"), "");
+    let lint = project_root.join("tools/config/lint");
+    write_file(
+        &lint.join("package.json"),
+        r#"{ "name": "@acme/lint", "exports": { "./rules": "./rules.js" } }"#,
+    );
+    write_file(&lint.join("rules.js"), "");
+    write_file(
+        &project_root.join("packages/legacy/package.json"),
+        r#"{ "name": "@acme/legacy", "main": "index.js" }"#,
+    );
+    write_file(&project_root.join("packages/legacy/index.js"), "");
+
+    let resolve = |specifier: &str, project_root: &Utf8PathBuf| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions::default()),
+        )
+        .unwrap()
+        .resolved_path
+    };
+
+    let ui_main = ui.join("src/index.ts").to_string();
+    assert_eq!(resolve("@acme/ui", &project_root), Some(ui_main.clone()));
+    assert_eq!(
+        resolve("@acme/ui/button", &project_root),
+        Some(ui.join("src/button.tsx").to_string())
+    );
+    // `workspace:` aliases resolve to the package they name
+    assert_eq!(resolve("widgets", &project_root), Some(ui_main.clone()));
+    assert_eq!(
+        resolve("@acme/lint/rules", &project_root),
+        Some(lint.join("rules.js").to_string())
+    );
+    assert_eq!(resolve("@acme/legacy", &project_root), None);
+
+    // pnpm declares its workspaces separately
+    write_file(&project_root.join("package.json"), r#"{ "private": true }"#);
+    write_file(
+        &project_root.join("pnpm-workspace.yaml"),
+        "# workspace layout\npackages:\n  - 'packages/*' # libraries\n  - \"!packages/legacy\"\ncatalog:\n  react: ^18.2.0\n",
+    );
+    assert_eq!(resolve("@acme/ui", &project_root), Some(ui_main));
+    assert_eq!(resolve("@acme/lint/rules", &project_root), None);
+    assert_eq!(resolve("@acme/legacy", &project_root), None);
+}
+
+#[test]
+fn traces_resolution_steps() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().canonicalize().unwrap()).unwrap();
+    let pkg_dir = project_root.join("node_modules/pkg");
+    write_file(
+        &pkg_dir.join("package.json"),
+        r#"{ "name": "pkg", "exports": { ".": { "require": "./cjs.js", "import": "./esm" } } }"#,
+    );
+    write_file(&pkg_dir.join("esm.js"), "");
+    let importer = project_root.join("src/index.ts");
+    write_file(&importer, "");
+
+    let resolve = |specifier: &str, trace: bool| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions {
+                trace,
+                ..ResolveOptions::default()
+            }),
+        )
+        .unwrap()
+    };
+    let file = |path: &str, found: bool| ResolutionStep::File {
+        path: pkg_dir.join(path).to_string(),
+        found,
+    };
+
+    assert!(resolve("pkg", false).trace.is_empty());
+    assert_eq!(
+        resolve("pkg", true).trace,
+        [
+            ResolutionStep::PackageDir {
+                path: project_root.join("src/node_modules/pkg").to_string(),
+                found: false,
+            },
+            ResolutionStep::PackageDir {
+                path: pkg_dir.to_string(),
+                found: true,
+            },
+            ResolutionStep::Entry {
+                field: "exports".into(),
+                subpath: ".".into(),
+                matched: true,
+            },
+            ResolutionStep::Condition {
+                name: "import".into(),
+                matched: true,
+            },
+            file("esm", false),
+            file("esm.ts", false),
+            file("esm.tsx", false),
+            file("esm.js", true),
+        ]
+    );
+
+    // Subpaths missing from "exports" fall back to plain paths
+    let trace = resolve("pkg/cjs.js", true).trace;
+    assert!(trace.contains(&ResolutionStep::Entry {
+        field: "exports".into(),
+        subpath: "./cjs.js".into(),
+        matched: false,
+    }));
+    assert!(matches!(trace[3], ResolutionStep::Fallback { .. }));
+    assert_eq!(trace[4], file("./cjs.js", false));
+}
+
 #[test]
 fn discovers_typings_with_types_field() {
     let dir = tempdir().unwrap();
@@ -82,12 +507,355 @@ fn discovers_typings_with_types_field() {
         "export interface Foo { value: number }",
     );
 
-    let typings = discover_typings_native("foo", &project_root).unwrap();
+    let typings = discover_typings_native("foo", &project_root, None).unwrap();
     assert_eq!(typings.package_name, "foo");
     assert_eq!(typings.files.len(), 1);
     assert!(typings.files[0].ends_with("types/index.d.ts"));
 }
 
+#[test]
+fn bundles_typings_by_following_references() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let pkg_dir = project_root.join("node_modules/lib");
+    write_file(
+        &pkg_dir.join("package.json"),
+        r#"{ "name": "lib", "types": "dist/index.d.ts" }"#,
+    );
+    write_file(
+        &pkg_dir.join("dist/index.d.ts"),
+        r#"/// <reference path="./globals.d.ts" />
+/// <reference types="node" />
+export * from "./models/user.js";
+export { helper } from "./util";
+import type { Other } from "@scope/other/sub";
+import { EventEmitter } from "node:events";
+// export * from "./commented";
+"#,
+    );
+    write_file(
+        &pkg_dir.join("dist/globals.d.ts"),
+        "declare const VERSION: string;",
+    );
+    write_file(
+        &pkg_dir.join("dist/models/user.d.ts"),
+        r#"import type { Id } from "../util"; export interface User { id: Id }"#,
+    );
+    write_file(
+        &pkg_dir.join("dist/util/index.d.ts"),
+        "export type Id = string;",
+    );
+    // Beyond the 2-level directory scan, but referenced
+    write_file(&pkg_dir.join("dist/a/b/c/deep.d.ts"), "export {};");
+    write_file(&pkg_dir.join("dist/unreferenced.d.ts"), "export {};");
+    let index = fs::read_to_string(pkg_dir.join("dist/index.d.ts")).unwrap();
+    write_file(
+        &pkg_dir.join("dist/index.d.ts"),
+        &format!("{}export * from './a/b/c/deep';\n", index),
+    );
+
+    let bundle = bundle_typings_native(
+        "lib",
+        &project_root,
+        BundleOptions {
+            include_content: true,
+            concatenate: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let files: Vec<&str> = bundle
+        .files
+        .iter()
+        .map(|file| file.path.strip_prefix(pkg_dir.as_str()).unwrap())
+        .collect();
+    assert_eq!(
+        files,
+        [
+            "/dist/index.d.ts",
+            "/dist/globals.d.ts",
+            "/dist/models/user.d.ts",
+            "/dist/util/index.d.ts",
+            "/dist/a/b/c/deep.d.ts",
+        ]
+    );
+    assert!(bundle.entry.unwrap().ends_with("dist/index.d.ts"));
+    assert_eq!(bundle.packages, ["@scope/other", "node"]);
+    assert!(!bundle.truncated);
+    assert_eq!(
+        bundle.files[3].content.as_deref(),
+        Some("export type Id = string;")
+    );
+    let concatenated = bundle.bundle.unwrap();
+    assert!(concatenated.contains("declare const VERSION: string;\n// "));
+
+    let capped = bundle_typings_native(
+        "lib",
+        &project_root,
+        BundleOptions {
+            max_files: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(capped.files.len(), 2);
+    assert!(capped.truncated);
+    assert!(capped.files[0].content.is_none() && capped.bundle.is_none());
+}
+
+#[test]
+fn discovers_typings_through_types_versions() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let pkg_dir = project_root.join("node_modules/versioned");
+    // The first matching range applies, even though "*" sorts before ">=4.2"
+    write_file(
+        &pkg_dir.join("package.json"),
+        r#"{
+  "name": "versioned",
+  "types": "index.d.ts",
+  "typesVersions": {
+    "<3.9": { "*": ["ts3.4/*"] },
+    ">=4.2 <99": { "index.d.ts": ["ts4.2/main.d.ts"], "*": ["ts4.2/*"] },
+    "*": { "*": ["legacy/*"] }
+  }
+}"#,
+    );
+    write_file(&pkg_dir.join("index.d.ts"), "export declare const old: 1;");
+    write_file(
+        &pkg_dir.join("ts4.2/main.d.ts"),
+        "export declare const v: 42;",
+    );
+    write_file(
+        &pkg_dir.join("ts3.4/index.d.ts"),
+        "export declare const v: 34;",
+    );
+    write_file(
+        &pkg_dir.join("legacy/index.d.ts"),
+        "export declare const v: 0;",
+    );
+
+    let typings = discover_typings_native("versioned", &project_root, None).unwrap();
+    assert_eq!(typings.files.len(), 1, "{:?}", typings.files);
+    assert!(typings.files[0].ends_with("ts4.2/main.d.ts"));
+
+    // Without a "types" field the implicit index.d.ts is redirected as well
+    let implicit = project_root.join("node_modules/implicit");
+    write_file(
+        &implicit.join("package.json"),
+        r#"{ "name": "implicit", "typesVersions": { "*": { "*": ["dist/types/*"] } } }"#,
+    );
+    write_file(&implicit.join("dist/types/index.d.ts"), "export {};");
+    let typings = discover_typings_native("implicit", &project_root, None).unwrap();
+    assert_eq!(typings.files.len(), 1, "{:?}", typings.files);
+    assert!(typings.files[0].ends_with("dist/types/index.d.ts"));
+}
+
+#[test]
+fn discovers_subpath_typings() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let firebase = project_root.join("node_modules/firebase");
+    write_file(
+        &firebase.join("package.json"),
+        r#"{
+  "name": "firebase",
+  "exports": {
+    ".": { "types": "./dist/index.d.ts", "default": "./dist/index.mjs" },
+    "./auth": { "import": { "types": "./auth/dist/auth.d.ts", "default": "./auth/dist/auth.mjs" } },
+    "./compat/*": { "types": "./compat/dist/*.d.ts" }
+  }
+}"#,
+    );
+    write_file(&firebase.join("dist/index.d.ts"), "export {};");
+    write_file(&firebase.join("auth/dist/auth.d.ts"), "export {};");
+    write_file(&firebase.join("compat/dist/app.d.ts"), "export {};");
+    write_file(&firebase.join("storage/index.d.ts"), "export {};");
+
+    let root = discover_typings_native("firebase", &project_root, None).unwrap();
+    assert_eq!(root.files.len(), 1, "{:?}", root.files);
+    assert!(root.files[0].ends_with("firebase/dist/index.d.ts"));
+
+    let auth = discover_typings_native("firebase/auth", &project_root, None).unwrap();
+    assert_eq!(auth.package_name, "firebase");
+    assert_eq!(auth.files.len(), 1, "{:?}", auth.files);
+    assert!(auth.files[0].ends_with("auth/dist/auth.d.ts"));
+
+    let compat = discover_typings_native("firebase", &project_root, Some("./compat/app")).unwrap();
+    assert!(compat.files[0].ends_with("compat/dist/app.d.ts"));
+
+    // Not exported, so not importable, even though the file exists
+    let storage = discover_typings_native("firebase", &project_root, Some("storage")).unwrap();
+    assert!(storage.files.is_empty(), "{:?}", storage.files);
+
+    // Without exports, the file at the subpath is used, or @types provides it
+    let lodash = project_root.join("node_modules/lodash");
+    write_file(&lodash.join("package.json"), r#"{ "name": "lodash" }"#);
+    write_file(&lodash.join("merge.js"), "module.exports = {};");
+    let types = project_root.join("node_modules/@types/lodash");
+    write_file(
+        &types.join("package.json"),
+        r#"{ "name": "@types/lodash" }"#,
+    );
+    write_file(&types.join("merge.d.ts"), "export {};");
+    let merge = discover_typings_native("lodash/merge.js", &project_root, None).unwrap();
+    assert!(
+        merge
+            .files
+            .iter()
+            .any(|f| f.ends_with("@types/lodash/merge.d.ts")),
+        "{:?}",
+        merge.files
+    );
+
+    let ui = project_root.join("node_modules/ui");
+    write_file(&ui.join("package.json"), r#"{ "name": "ui" }"#);
+    write_file(
+        &ui.join("button/package.json"),
+        r#"{ "types": "../types/button.d.ts" }"#,
+    );
+    write_file(&ui.join("types/button.d.ts"), "export {};");
+    let button = discover_typings_native("ui", &project_root, Some("button")).unwrap();
+    assert!(
+        button.files[0].ends_with("ui/types/button.d.ts"),
+        "{:?}",
+        button.files
+    );
+}
+
+#[test]
+fn applies_browser_field_mappings() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    write_file(
+        &project_root.join("package.json"),
+        r#"{ "name": "app", "browser": { "fs": false, "node-fetch": "./src/fetch-shim.js" } }"#,
+    );
+    write_file(
+        &project_root.join("src/fetch-shim.js"),
+        "export default fetch;",
+    );
+    let http = project_root.join("node_modules/http-client");
+    write_file(
+        &http.join("package.json"),
+        r#"{
+  "name": "http-client",
+  "main": "./lib/index.js",
+  "browser": { "./lib/node": "./lib/browser.js", "./lib/tls.js": false, "stream": "stream-browserify" }
+}"#,
+    );
+    write_file(&http.join("lib/index.js"), "require('./node');");
+    write_file(&http.join("lib/node.js"), "module.exports = 'node';");
+    write_file(&http.join("lib/browser.js"), "module.exports = 'browser';");
+    write_file(&http.join("lib/tls.js"), "module.exports = {};");
+    write_file(
+        &project_root.join("node_modules/stream-browserify/index.js"),
+        "module.exports = {};",
+    );
+    let importer = project_root.join("src/index.ts");
+    write_file(&importer, "");
+
+    let resolve = |specifier: &str, importer: &Utf8PathBuf, platform: Platform| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions {
+                platform,
+                ..ResolveOptions::default()
+            }),
+        )
+        .unwrap()
+    };
+
+    let fs = resolve("fs", &importer, Platform::Browser);
+    assert!(fs.disabled && fs.resolved_path.is_none());
+    let fetch = resolve("node-fetch", &importer, Platform::Browser);
+    assert!(fetch.resolved_path.unwrap().ends_with("src/fetch-shim.js"));
+
+    // Files are substituted by the package that contains them, keys without extensions too
+    let client = resolve("http-client/lib/node", &importer, Platform::Browser);
+    assert!(client.resolved_path.unwrap().ends_with("lib/browser.js"));
+    let inner = http.join("lib/index.js");
+    let relative = resolve("./node", &inner, Platform::Browser);
+    assert!(relative.resolved_path.unwrap().ends_with("lib/browser.js"));
+    let tls = resolve("./tls", &inner, Platform::Browser);
+    assert!(tls.disabled);
+    let stream = resolve("stream", &inner, Platform::Browser);
+    assert!(stream
+        .resolved_path
+        .unwrap()
+        .ends_with("stream-browserify/index.js"));
+
+    // Node resolution ignores the field
+    let node = resolve("./node", &inner, Platform::Node);
+    let node = Utf8PathBuf::from(node.resolved_path.unwrap());
+    assert!(node.ends_with("lib/node.js"), "{}", node);
+    assert!(!resolve("fs", &importer, Platform::Node).disabled);
+}
+
+#[test]
+fn detects_node_builtins() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    write_file(
+        &project_root.join("node_modules/buffer/package.json"),
+        r#"{ "name": "buffer", "main": "index.js" }"#,
+    );
+    write_file(
+        &project_root.join("node_modules/buffer/index.js"),
+        "module.exports = {};",
+    );
+    let importer = project_root.join("src/index.ts");
+    write_file(&importer, "");
+
+    let resolve = |specifier: &str, platform: Platform| {
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.into(),
+                importer: importer.to_string(),
+                project_root: Some(project_root.to_string()),
+            },
+            Some(ResolveOptions {
+                platform,
+                ..ResolveOptions::default()
+            }),
+        )
+        .unwrap()
+    };
+
+    for specifier in ["fs", "node:fs", "fs/promises", "node:test", "buffer"] {
+        let response = resolve(specifier, Platform::Node);
+        assert!(
+            matches!(response.format, ModuleFormat::NodeBuiltin),
+            "{}",
+            specifier
+        );
+        assert!(response.resolved_path.is_none());
+        assert!(response.warnings.is_empty(), "{:?}", response.warnings);
+    }
+    assert!(is_node_builtin("node:path/posix"));
+    assert!(!is_node_builtin("test"));
+    assert!(!is_node_builtin("fs-extra"));
+
+    let unknown = resolve("node:nope", Platform::Node);
+    assert!(matches!(unknown.format, ModuleFormat::Unknown));
+    assert_eq!(unknown.warnings.len(), 1);
+
+    // Bundlers prefer installed polyfills for unprefixed names
+    let polyfill = resolve("buffer", Platform::Browser);
+    assert!(polyfill.resolved_path.unwrap().ends_with("buffer/index.js"));
+    let missing = resolve("path", Platform::Browser);
+    assert!(matches!(missing.format, ModuleFormat::NodeBuiltin));
+    assert!(missing.warnings.is_empty());
+    assert!(matches!(
+        resolve("node:buffer", Platform::Browser).format,
+        ModuleFormat::NodeBuiltin
+    ));
+}
+
 #[test]
 fn analyzes_imports_and_exports() {
     let dir = tempdir().unwrap();
@@ -102,8 +870,278 @@ export default foo;
 "#,
     );
 
-    let analysis: AnalyzeResponse = analyze_module_native(&file).unwrap();
+    let analysis: AnalyzeResponse = analyze_module_native(&file, None).unwrap();
     assert!(analysis.imports.contains(&"./foo".to_string()));
     assert!(analysis.exports.contains(&"bar".to_string()));
     assert!(analysis.exports.iter().any(|e| e.contains("default")));
 }
+
+#[test]
+fn transforms_modules() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let esm = project_root.join("src/esm.ts");
+    write_file(
+        &esm,
+        r#"import { join } from "node:path";
+interface Options { base: string }
+export const resolve = (options: Options) => join(options.base, "x");
+"#,
+    );
+    let cjs = project_root.join("src/cjs.js");
+    write_file(
+        &cjs,
+        r#"const path = require("path");
+exports.base = __dirname;
+module.exports.join = (p) => path.join(exports.base, p);
+"#,
+    );
+    let transform = |file: &Utf8PathBuf, strip_types: bool, module: Option<ModuleTarget>| {
+        analyze_module_native(
+            file,
+            Some(TransformOptions {
+                strip_types,
+                module,
+            }),
+        )
+        .unwrap()
+        .transformed
+    };
+
+    let untouched = analyze_module_native(&esm, Some(TransformOptions::default())).unwrap();
+    assert!(untouched.transformed.contains("interface Options"));
+
+    let stripped = transform(&esm, true, None);
+    assert!(!stripped.contains("interface"));
+    assert!(!stripped.contains(": Options"));
+    assert!(stripped.contains("export const resolve"));
+
+    let commonjs = transform(&esm, true, Some(ModuleTarget::CommonJs));
+    assert!(commonjs.contains("require(\"node:path\")"));
+    assert!(!commonjs.contains("export const"));
+
+    let esm_wrapped = transform(&cjs, false, Some(ModuleTarget::Esm));
+    assert!(esm_wrapped.starts_with("import { createRequire as __createRequire }"));
+    assert!(esm_wrapped.contains("const __dirname = __pathDirname(__filename);"));
+    assert!(esm_wrapped.contains("export default module.exports;"));
+    assert!(esm_wrapped.contains("export { __cjs_export_0 as base, __cjs_export_1 as join };"));
+    // Already CommonJS
+    assert_eq!(
+        transform(&cjs, false, Some(ModuleTarget::CommonJs)),
+        transform(&cjs, false, None)
+    );
+}
+
+#[test]
+fn collects_import_specifier_positions() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let file = project_root.join("src/file.ts");
+    let source = r#"import foo, { type Foo } from "./foo";
+export * from '../shared';
+export { bar } from "./bar.js";
+const lazy = () => import("./lazy");
+const legacy = require('./legacy');
+"#;
+    write_file(&file, source);
+
+    let specifiers = collect_import_specifiers_native(&file).unwrap();
+    let found: Vec<(&str, ImportKind)> = specifiers
+        .iter()
+        .map(|s| (s.specifier.as_str(), s.kind))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("./foo", ImportKind::Import),
+            ("../shared", ImportKind::ReExport),
+            ("./bar.js", ImportKind::ReExport),
+            ("./lazy", ImportKind::DynamicImport),
+            ("./legacy", ImportKind::Require),
+        ]
+    );
+    for specifier in &specifiers {
+        assert_eq!(&source[specifier.start..specifier.end], specifier.specifier);
+    }
+    assert_eq!(
+        specifiers[0].bindings,
+        vec![ImportBinding::Default {
+            local: "foo".into()
+        }]
+    );
+    assert_eq!(
+        specifiers[0].type_bindings,
+        vec![ImportBinding::Named {
+            imported: "Foo".into(),
+            local: "Foo".into()
+        }]
+    );
+    assert_eq!(
+        specifiers[2].bindings,
+        vec![ImportBinding::Named {
+            imported: "bar".into(),
+            local: "bar".into()
+        }]
+    );
+    assert!(specifiers[3].bindings.is_empty());
+}
+
+#[test]
+fn walks_module_graph() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let src = project_root.join("src");
+    write_file(
+        &src.join("index.ts"),
+        r#"import { a } from "./a";
+import fs from "fs";
+import pkg from "pkg";
+import missing from "./missing";
+"#,
+    );
+    write_file(
+        &src.join("a.ts"),
+        "import { b } from './b';\nimport './index';\nexport const a = b;",
+    );
+    write_file(
+        &src.join("b.ts"),
+        "import { c } from './c';\nexport const b = c;",
+    );
+    write_file(&src.join("c.ts"), "export const c = 1;");
+    let pkg_dir = project_root.join("node_modules/pkg");
+    write_file(
+        &pkg_dir.join("package.json"),
+        r#"{ "name": "pkg", "main": "index.js" }"#,
+    );
+    write_file(&pkg_dir.join("index.js"), "export * from './inner.js';");
+    write_file(&pkg_dir.join("inner.js"), "export const inner = 1;");
+
+    let options = GraphOptions {
+        max_depth: 2,
+        ..Default::default()
+    };
+    let graph =
+        walk_module_graph_native(&src.join("index.ts"), Some(&project_root), &options).unwrap();
+
+    let name = |id: &str| id.rsplit('/').next().unwrap().to_string();
+    let nodes: Vec<(String, usize)> = graph.nodes.iter().map(|n| (name(&n.id), n.depth)).collect();
+    // b.ts is at the depth limit, so c.ts is never reached; the package is not walked into
+    assert_eq!(
+        nodes,
+        vec![
+            ("index.ts".to_string(), 0),
+            ("a.ts".to_string(), 1),
+            ("fs".to_string(), 1),
+            ("index.js".to_string(), 1),
+            ("b.ts".to_string(), 2),
+        ]
+    );
+    assert!(graph.truncated);
+    assert!(graph.nodes[3].external);
+    assert!(matches!(graph.nodes[2].format, ModuleFormat::NodeBuiltin));
+
+    // The cycle back to the entry is an edge, not a second node
+    let edges: Vec<(String, String)> = graph
+        .edges
+        .iter()
+        .map(|e| (name(&e.from), name(&e.to)))
+        .collect();
+    assert!(edges.contains(&("a.ts".to_string(), "index.ts".to_string())));
+    assert_eq!(edges.len(), 5);
+
+    assert_eq!(graph.unresolved.len(), 1);
+    assert_eq!(graph.unresolved[0].specifier, "./missing");
+}
+
+#[test]
+fn extracts_dts_symbols() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let file = project_root.join("types/index.d.ts");
+    let source = r#"import { Readable } from "stream";
+export interface Options { stream: Readable }
+export type Mode = "fast" | "safe";
+export declare function run(options: Options): void;
+export declare function run(mode: Mode): void;
+declare class Internal {}
+declare const VERSION: string;
+export { VERSION };
+export declare namespace Tools.Fs {
+    function read(path: string): string;
+    enum Level { Low, High }
+}
+declare global {
+    interface Window { tools: unknown }
+}
+declare module "plugin" {
+    export const name: string;
+}
+"#;
+    write_file(&file, source);
+
+    let symbols = extract_dts_symbols(&file).unwrap();
+    let found: Vec<(&str, DtsSymbolKind, Option<&str>, bool)> = symbols
+        .iter()
+        .map(|s| (s.name.as_str(), s.kind, s.container.as_deref(), s.exported))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Options", DtsSymbolKind::Interface, None, true),
+            ("Mode", DtsSymbolKind::TypeAlias, None, true),
+            // Overloads are listed once
+            ("run", DtsSymbolKind::Function, None, true),
+            ("Internal", DtsSymbolKind::Class, None, false),
+            ("VERSION", DtsSymbolKind::Variable, None, true),
+            ("Tools", DtsSymbolKind::Namespace, None, true),
+            ("Fs", DtsSymbolKind::Namespace, Some("Tools"), true),
+            ("read", DtsSymbolKind::Function, Some("Tools.Fs"), true),
+            ("Level", DtsSymbolKind::Enum, Some("Tools.Fs"), true),
+            ("Window", DtsSymbolKind::Interface, None, true),
+            ("plugin", DtsSymbolKind::AmbientModule, None, true),
+            ("name", DtsSymbolKind::Variable, Some("plugin"), true),
+        ]
+    );
+    assert_eq!(symbols[0].start, source.find("Options").unwrap());
+}
+
+#[test]
+fn collects_exports() {
+    let dir = tempdir().unwrap();
+    let project_root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let file = project_root.join("src/api.ts");
+    let source = r#"import { helper } from "./helper";
+export const client = helper(), { get, post: send } = client;
+export function request() {}
+export interface Options {}
+export type Method = "GET" | "POST";
+export enum Status { Ok }
+const internal = 1;
+export { internal as exposed, type Options as Settings };
+export { shared } from "./shared";
+export default request;
+"#;
+    write_file(&file, source);
+
+    let exports = collect_exports_native(&file).unwrap();
+    let found: Vec<(&str, bool)> = exports
+        .iter()
+        .map(|e| (e.name.as_str(), e.type_only))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("client", false),
+            ("get", false),
+            ("send", false),
+            ("request", false),
+            ("Options", true),
+            ("Method", true),
+            ("Status", false),
+            ("exposed", false),
+            ("Settings", true),
+            ("default", false),
+        ]
+    );
+    assert_eq!(exports[0].start, source.find("client").unwrap());
+}
@@ -0,0 +1,598 @@
+//! Asset Commands
+//!
+//! Metadata and thumbnails for binary assets shown in the explorer's asset preview.
+//! Everything here reads as little of the file as possible: image dimensions come
+//! from headers, media durations from container metadata, and only images larger
+//! than the thumbnail size are fully decoded.
+
+use base64::Engine;
+use fluxel_macros::fluxel_command;
+use image::{ImageFormat, ImageReader};
+use regex::Regex;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::services::preview_server::mime_type_for;
+
+/// Default maximum edge length of generated thumbnails
+const DEFAULT_THUMBNAIL_SIZE: u32 = 256;
+
+/// Images larger than this are never decoded for a thumbnail
+const MAX_DECODE_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Amount of data read from the end of a file when looking for trailing metadata
+const TAIL_SCAN_BYTES: u64 = 64 * 1024;
+
+// ============================================================================
+// Asset Types
+// ============================================================================
+
+/// Broad asset category, derived from the MIME type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Image,
+    Audio,
+    Video,
+    Font,
+    Binary,
+}
+
+/// Image-specific metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    /// Detected format (e.g., "png", "jpeg", "svg")
+    pub format: String,
+}
+
+/// Metadata returned by `inspect_asset`
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub mime_type: String,
+    pub kind: AssetKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageMetadata>,
+    /// Duration in seconds for audio/video, when it can be read cheaply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// PNG data URL of a downscaled preview, only for images larger than the thumbnail size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+// ============================================================================
+// Image Inspection
+// ============================================================================
+
+fn format_name(format: ImageFormat) -> String {
+    format!("{:?}", format).to_lowercase()
+}
+
+/// Read image dimensions and format from the file header
+fn read_image_metadata(path: &Path) -> Option<ImageMetadata> {
+    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    let format = reader.format()?;
+    let (width, height) = reader.into_dimensions().ok()?;
+    Some(ImageMetadata {
+        width,
+        height,
+        format: format_name(format),
+    })
+}
+
+/// Read SVG dimensions from the root element's width/height or viewBox
+fn read_svg_metadata(path: &Path) -> Option<ImageMetadata> {
+    let mut head = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(TAIL_SCAN_BYTES)
+        .read_to_end(&mut head)
+        .ok()?;
+    let text = String::from_utf8_lossy(&head);
+
+    let svg_tag = Regex::new(r"(?s)<svg\b[^>]*>").ok()?;
+    let tag = svg_tag.find(&text)?.as_str();
+
+    let attr = |name: &str| -> Option<f64> {
+        let re = Regex::new(&format!(r#"\s{}\s*=\s*["']\s*([0-9.]+)(px)?\s*["']"#, name)).ok()?;
+        re.captures(tag)?.get(1)?.as_str().parse().ok()
+    };
+
+    let (width, height) = match (attr("width"), attr("height")) {
+        (Some(w), Some(h)) => (w, h),
+        _ => {
+            let view_box = Regex::new(r#"viewBox\s*=\s*["']([^"']+)["']"#).ok()?;
+            let values: Vec<f64> = view_box
+                .captures(tag)?
+                .get(1)?
+                .as_str()
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            if values.len() != 4 {
+                return None;
+            }
+            (values[2], values[3])
+        }
+    };
+
+    Some(ImageMetadata {
+        width: width.round() as u32,
+        height: height.round() as u32,
+        format: "svg".to_string(),
+    })
+}
+
+/// Decode the image and encode a downscaled PNG as a data URL
+fn generate_thumbnail(path: &Path, max_size: u32) -> Result<String, String> {
+    let image = ImageReader::open(path)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let thumbnail = image.thumbnail(max_size, max_size);
+    let mut png = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png.into_inner())
+    ))
+}
+
+// ============================================================================
+// Media Duration
+// ============================================================================
+
+fn read_exact_at(file: &mut File, offset: u64, buf: &mut [u8]) -> Option<()> {
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(buf).ok()
+}
+
+/// WAV: data chunk size divided by the byte rate from the `fmt ` chunk
+fn wav_duration(file: &mut File, file_size: u64) -> Option<f64> {
+    let mut header = [0u8; 12];
+    read_exact_at(file, 0, &mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return None;
+    }
+
+    let mut offset = 12u64;
+    let mut byte_rate = None;
+    while offset + 8 <= file_size {
+        let mut chunk = [0u8; 8];
+        read_exact_at(file, offset, &mut chunk)?;
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        match &chunk[0..4] {
+            b"fmt " => {
+                let mut fmt = [0u8; 12];
+                read_exact_at(file, offset + 8, &mut fmt)?;
+                byte_rate = Some(u32::from_le_bytes([fmt[8], fmt[9], fmt[10], fmt[11]]));
+            }
+            b"data" => {
+                let rate = byte_rate.filter(|r| *r > 0)?;
+                // Streams written before the size is known report 0 or u32::MAX
+                let data_size = if size == 0 || size == u32::MAX as u64 {
+                    file_size.saturating_sub(offset + 8)
+                } else {
+                    size
+                };
+                return Some(data_size as f64 / rate as f64);
+            }
+            _ => {}
+        }
+        // Chunks are padded to even sizes
+        offset += 8 + size + (size & 1);
+    }
+    None
+}
+
+/// FLAC: total samples / sample rate from the STREAMINFO block
+fn flac_duration(file: &mut File) -> Option<f64> {
+    let mut header = [0u8; 4 + 4 + 34];
+    read_exact_at(file, 0, &mut header)?;
+    if &header[0..4] != b"fLaC" || header[4] & 0x7F != 0 {
+        return None;
+    }
+    let info = &header[8..];
+    let packed = u64::from_be_bytes(info[10..18].try_into().ok()?);
+    let sample_rate = packed >> 44;
+    let total_samples = packed & 0xF_FFFF_FFFF;
+    if sample_rate == 0 || total_samples == 0 {
+        return None;
+    }
+    Some(total_samples as f64 / sample_rate as f64)
+}
+
+/// Find a child box of the given type within `[start, end)`, returning its payload range
+fn find_mp4_box(file: &mut File, start: u64, end: u64, kind: &[u8; 4]) -> Option<(u64, u64)> {
+    let mut offset = start;
+    while offset + 8 <= end {
+        let mut header = [0u8; 8];
+        read_exact_at(file, offset, &mut header)?;
+        let mut size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            read_exact_at(file, offset + 8, &mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - offset;
+        }
+        if size < header_len {
+            return None;
+        }
+        if &header[4..8] == kind {
+            return Some((offset + header_len, offset + size));
+        }
+        offset += size;
+    }
+    None
+}
+
+/// MP4/MOV/M4A: duration and timescale from `moov/mvhd`
+fn mp4_duration(file: &mut File, file_size: u64) -> Option<f64> {
+    let (moov_start, moov_end) = find_mp4_box(file, 0, file_size, b"moov")?;
+    let (mvhd_start, _) = find_mp4_box(file, moov_start, moov_end, b"mvhd")?;
+
+    let mut version = [0u8; 1];
+    read_exact_at(file, mvhd_start, &mut version)?;
+    let (timescale, duration) = if version[0] == 1 {
+        let mut buf = [0u8; 12];
+        read_exact_at(file, mvhd_start + 4 + 16, &mut buf)?;
+        (
+            u32::from_be_bytes(buf[0..4].try_into().ok()?) as u64,
+            u64::from_be_bytes(buf[4..12].try_into().ok()?),
+        )
+    } else {
+        let mut buf = [0u8; 8];
+        read_exact_at(file, mvhd_start + 4 + 8, &mut buf)?;
+        (
+            u32::from_be_bytes(buf[0..4].try_into().ok()?) as u64,
+            u32::from_be_bytes(buf[4..8].try_into().ok()?) as u64,
+        )
+    };
+    if timescale == 0 {
+        return None;
+    }
+    Some(duration as f64 / timescale as f64)
+}
+
+/// Ogg (Vorbis/Opus): granule position of the last page / sample rate of the stream
+fn ogg_duration(file: &mut File, file_size: u64) -> Option<f64> {
+    let mut head = [0u8; 64];
+    read_exact_at(file, 0, &mut head)?;
+    if &head[0..4] != b"OggS" {
+        return None;
+    }
+    // Page header is 27 bytes plus the segment table
+    let payload = 27 + head[26] as usize;
+    let packet = head.get(payload..)?;
+    let (sample_rate, pre_skip) = if packet.starts_with(b"\x01vorbis") {
+        (
+            u32::from_le_bytes(packet.get(12..16)?.try_into().ok()?) as u64,
+            0,
+        )
+    } else if packet.starts_with(b"OpusHead") {
+        // Opus granule positions always count 48 kHz samples
+        (
+            48_000,
+            u16::from_le_bytes(packet.get(10..12)?.try_into().ok()?) as u64,
+        )
+    } else {
+        return None;
+    };
+    if sample_rate == 0 {
+        return None;
+    }
+
+    let tail_start = file_size.saturating_sub(TAIL_SCAN_BYTES);
+    let mut tail = vec![0u8; (file_size - tail_start) as usize];
+    read_exact_at(file, tail_start, &mut tail)?;
+    let last_page = tail.windows(4).rposition(|w| w == b"OggS")?;
+    let granule = i64::from_le_bytes(tail.get(last_page + 6..last_page + 14)?.try_into().ok()?);
+    if granule <= 0 {
+        return None;
+    }
+    Some((granule as u64).saturating_sub(pre_skip) as f64 / sample_rate as f64)
+}
+
+/// MP3: exact duration from a Xing/Info header, otherwise a constant-bitrate estimate
+fn mp3_duration(file: &mut File, file_size: u64) -> Option<f64> {
+    let mut start = 0u64;
+    let mut id3 = [0u8; 10];
+    read_exact_at(file, 0, &mut id3)?;
+    if &id3[0..3] == b"ID3" {
+        // Tag size is a 28-bit syncsafe integer
+        let size = id3[6..10]
+            .iter()
+            .fold(0u64, |acc, b| (acc << 7) | (*b as u64 & 0x7F));
+        start = 10 + size;
+    }
+
+    let mut buf = vec![0u8; 4096];
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let read = file.read(&mut buf).ok()?;
+    buf.truncate(read);
+
+    let frame = buf
+        .windows(4)
+        .position(|w| w[0] == 0xFF && (w[1] & 0xE0) == 0xE0 && (w[1] & 0x06) == 0x02)?;
+    let header = &buf[frame..frame + 4];
+
+    // version: 3 = MPEG1, 2 = MPEG2, 0 = MPEG2.5
+    let version = (header[1] >> 3) & 0x03;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+    let mono = (header[3] >> 6) == 0x03;
+
+    const MPEG1_BITRATES: [u32; 16] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0,
+    ];
+    const MPEG2_BITRATES: [u32; 16] = [
+        0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+    ];
+    const SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+
+    let base_rate = *SAMPLE_RATES.get(sample_rate_index)?;
+    let (sample_rate, bitrate, samples_per_frame, side_info) = match version {
+        3 => (
+            base_rate,
+            MPEG1_BITRATES[bitrate_index],
+            1152u64,
+            if mono { 17 } else { 32 },
+        ),
+        2 => (
+            base_rate / 2,
+            MPEG2_BITRATES[bitrate_index],
+            576,
+            if mono { 9 } else { 17 },
+        ),
+        0 => (
+            base_rate / 4,
+            MPEG2_BITRATES[bitrate_index],
+            576,
+            if mono { 9 } else { 17 },
+        ),
+        _ => return None,
+    };
+
+    let xing = frame + 4 + side_info;
+    if let Some(tag) = buf.get(xing..xing + 12) {
+        if &tag[0..4] == b"Xing" || &tag[0..4] == b"Info" {
+            let flags = u32::from_be_bytes(tag[4..8].try_into().ok()?);
+            if flags & 0x01 != 0 {
+                let frames = u32::from_be_bytes(tag[8..12].try_into().ok()?) as u64;
+                return Some((frames * samples_per_frame) as f64 / sample_rate as f64);
+            }
+        }
+    }
+
+    if bitrate == 0 {
+        return None;
+    }
+    let audio_bytes = file_size.saturating_sub(start + frame as u64);
+    Some(audio_bytes as f64 * 8.0 / (bitrate as f64 * 1000.0))
+}
+
+/// Read the duration of an audio/video file if its container allows it cheaply
+fn read_media_duration(path: &Path, file_size: u64) -> Option<f64> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut file = File::open(path).ok()?;
+
+    match ext.as_str() {
+        "wav" => wav_duration(&mut file, file_size),
+        "flac" => flac_duration(&mut file),
+        "mp4" | "m4a" | "m4v" | "mov" => mp4_duration(&mut file, file_size),
+        "ogg" | "oga" | "opus" => ogg_duration(&mut file, file_size),
+        "mp3" => mp3_duration(&mut file, file_size),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+fn inspect(path: &Path, thumbnail_size: u32) -> Result<AssetInfo, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {:?}", path));
+    }
+    let size_bytes = metadata.len();
+    let mime_type = mime_type_for(path).to_string();
+
+    let kind = match mime_type.split('/').next().unwrap_or_default() {
+        "image" => AssetKind::Image,
+        "audio" => AssetKind::Audio,
+        "video" => AssetKind::Video,
+        "font" => AssetKind::Font,
+        _ => AssetKind::Binary,
+    };
+
+    let mut info = AssetInfo {
+        path: path.to_string_lossy().replace('\\', "/"),
+        size_bytes,
+        mime_type,
+        kind,
+        image: None,
+        duration_secs: None,
+        thumbnail: None,
+    };
+
+    match kind {
+        AssetKind::Image if info.mime_type == "image/svg+xml" => {
+            info.image = read_svg_metadata(path);
+        }
+        // Unknown extensions may still be images; the header decides
+        AssetKind::Image | AssetKind::Binary => {
+            info.image = read_image_metadata(path);
+            if let Some(image) = &info.image {
+                info.kind = AssetKind::Image;
+                let oversized = image.width > thumbnail_size || image.height > thumbnail_size;
+                if oversized && size_bytes <= MAX_DECODE_BYTES {
+                    match generate_thumbnail(path, thumbnail_size) {
+                        Ok(thumbnail) => info.thumbnail = Some(thumbnail),
+                        Err(e) => println!("[Asset] Thumbnail failed for {:?}: {}", path, e),
+                    }
+                }
+            }
+        }
+        AssetKind::Audio | AssetKind::Video => {
+            info.duration_secs = read_media_duration(path, size_bytes);
+        }
+        AssetKind::Font => {}
+    }
+
+    Ok(info)
+}
+
+/// Inspect a binary asset for the explorer preview.
+///
+/// # Arguments
+/// * `path` - Absolute path of the asset
+/// * `thumbnail_size` - Maximum thumbnail edge in pixels (default: 256)
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(skip(path), fields(category = "workspace", file = %path))
+)]
+pub async fn inspect_asset(path: String, thumbnail_size: Option<u32>) -> Result<AssetInfo, String> {
+    let thumbnail_size = thumbnail_size
+        .unwrap_or(DEFAULT_THUMBNAIL_SIZE)
+        .clamp(16, 2048);
+    tauri::async_runtime::spawn_blocking(move || inspect(Path::new(&path), thumbnail_size))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn create_temp_dir(test_name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("fluxel-asset-{test_name}-{unique}"));
+        fs::create_dir_all(&path).expect("temporary directory should be created");
+        path
+    }
+
+    #[test]
+    fn inspects_png_and_generates_thumbnail() {
+        let dir = create_temp_dir("png");
+        let path = dir.join("large.png");
+        image::RgbaImage::new(600, 300)
+            .save(&path)
+            .expect("png should be written");
+
+        let info = inspect(&path, 128).expect("asset should be inspected");
+        assert_eq!(info.kind, AssetKind::Image);
+        let image = info.image.expect("image metadata");
+        assert_eq!((image.width, image.height), (600, 300));
+        assert_eq!(image.format, "png");
+        assert!(info
+            .thumbnail
+            .expect("thumbnail for oversized image")
+            .starts_with("data:image/png;base64,"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_svg_dimensions_from_view_box() {
+        let dir = create_temp_dir("svg");
+        let path = dir.join("icon.svg");
+        fs::write(
+            &path,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 16"><path d=""/></svg>"#,
+        )
+        .unwrap();
+
+        let info = inspect(&path, 128).unwrap();
+        let image = info.image.expect("svg metadata");
+        assert_eq!((image.width, image.height), (24, 16));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_wav_duration_from_header() {
+        let dir = create_temp_dir("wav");
+        let path = dir.join("tone.wav");
+
+        // 8 kHz mono 16-bit = 16000 bytes/second, two seconds of silence
+        let data_size: u32 = 32_000;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&8_000u32.to_le_bytes());
+        wav.extend_from_slice(&16_000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+        wav.resize(wav.len() + data_size as usize, 0);
+        fs::write(&path, wav).unwrap();
+
+        let info = inspect(&path, 128).unwrap();
+        assert_eq!(info.kind, AssetKind::Audio);
+        assert_eq!(info.duration_secs, Some(2.0));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_mp4_duration_from_mvhd() {
+        let dir = create_temp_dir("mp4");
+        let path = dir.join("clip.mp4");
+
+        let mut mvhd = Vec::new();
+        mvhd.extend_from_slice(&[0, 0, 0, 0]); // version 0 + flags
+        mvhd.extend_from_slice(&0u32.to_be_bytes()); // creation time
+        mvhd.extend_from_slice(&0u32.to_be_bytes()); // modification time
+        mvhd.extend_from_slice(&1_000u32.to_be_bytes()); // timescale
+        mvhd.extend_from_slice(&4_500u32.to_be_bytes()); // duration
+
+        let mut file = Vec::new();
+        file.extend_from_slice(&16u32.to_be_bytes());
+        file.extend_from_slice(b"ftypisom\0\0\0\0");
+        file.extend_from_slice(&(8 + 8 + mvhd.len() as u32).to_be_bytes());
+        file.extend_from_slice(b"moov");
+        file.extend_from_slice(&(8 + mvhd.len() as u32).to_be_bytes());
+        file.extend_from_slice(b"mvhd");
+        file.extend_from_slice(&mvhd);
+        fs::write(&path, file).unwrap();
+
+        let info = inspect(&path, 128).unwrap();
+        assert_eq!(info.kind, AssetKind::Video);
+        assert_eq!(info.duration_secs, Some(4.5));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -2,17 +2,26 @@
 //!
 //! Commands for building C# projects.
 
+use fluxel_macros::fluxel_command;
 use ignore::WalkBuilder;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
+use tauri::{AppHandle, Runtime, State};
 use tokio::process::Command;
 use tokio::sync::RwLock;
 
 use crate::languages::csharp::parser::{parse_csproj_configurations, BuildConfiguration};
+use crate::languages::diagnostics::{
+    Diagnostic, DiagnosticRange, DiagnosticSeverity, DiagnosticsState,
+};
 use crate::languages::lsp_manager::{find_project_file, find_solution_file};
+use crate::services::workspace_store::sha256_hex;
+use crate::services::wsl::{
+    shell_quote, windows_to_wsl, wsl_command, wsl_to_windows, wsl_workspace,
+};
 
 // ============================================================================
 // Build Diagnostic Types
@@ -34,6 +43,33 @@ pub struct BuildDiagnostic {
     pub code: String,
     /// Human-readable message
     pub message: String,
+    /// Stable id derived from location, code, and message, so diagnostics can
+    /// be matched between builds
+    pub id: String,
+    /// Projects that reported the diagnostic
+    pub projects: Vec<String>,
+    /// Target frameworks it was reported for, when the build is multi-targeted
+    pub target_frameworks: Vec<String>,
+}
+
+impl BuildDiagnostic {
+    /// Store form of this diagnostic; MSBuild only reports where it starts
+    pub(crate) fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            path: self.file_path.clone(),
+            range: DiagnosticRange {
+                start_line: self.line,
+                start_column: self.column,
+                end_line: self.line,
+                end_column: self.column,
+            },
+            severity: DiagnosticSeverity::parse(&self.severity),
+            message: self.message.clone(),
+            code: Some(self.code.clone()),
+            source: "msbuild".to_string(),
+            provider: "build".to_string(),
+        }
+    }
 }
 
 /// Result of a build operation with parsed diagnostics.
@@ -65,8 +101,10 @@ pub struct BuildResult {
     feature = "profiling",
     tracing::instrument(skip(output, workspace_root), fields(category = "file_io"))
 )]
-fn parse_build_diagnostics(output: &str, workspace_root: &str) -> Vec<BuildDiagnostic> {
-    let mut diagnostics = Vec::new();
+pub(crate) fn parse_build_diagnostics(output: &str, workspace_root: &str) -> Vec<BuildDiagnostic> {
+    let mut diagnostics: Vec<BuildDiagnostic> = Vec::new();
+    // Index into `diagnostics` by id
+    let mut seen: HashMap<String, usize> = HashMap::new();
 
     // Regex pattern for MSBuild diagnostic format:
     // ... (rest of the comments)
@@ -90,23 +128,45 @@ fn parse_build_diagnostics(output: &str, workspace_root: &str) -> Vec<BuildDiagn
             .unwrap_or("error")
             .to_lowercase();
         let code = caps.get(5).map(|m| m.as_str()).unwrap_or("").to_string();
-        let message = caps
-            .get(6)
-            .map(|m| m.as_str().trim())
-            .unwrap_or("")
-            .to_string();
+        let (message, project, target_framework) =
+            split_project_suffix(caps.get(6).map(|m| m.as_str().trim()).unwrap_or(""));
 
         // Normalize the file path
         let file_path = normalize_diagnostic_path(raw_path, workspace_root);
-
-        diagnostics.push(BuildDiagnostic {
-            file_path,
-            line,
-            column,
-            severity,
-            code,
-            message,
-        });
+        let id = diagnostic_id(&file_path, line, column, &severity, &code, &message);
+
+        // MSBuild reports a diagnostic once per target framework, and again
+        // in the summary at the end of the build
+        let index = match seen.get(&id) {
+            Some(&index) => index,
+            None => {
+                seen.insert(id.clone(), diagnostics.len());
+                diagnostics.push(BuildDiagnostic {
+                    file_path,
+                    line,
+                    column,
+                    severity,
+                    code,
+                    message,
+                    id,
+                    projects: Vec::new(),
+                    target_frameworks: Vec::new(),
+                });
+                diagnostics.len() - 1
+            }
+        };
+        let diagnostic = &mut diagnostics[index];
+        if let Some(project) = project {
+            let project = normalize_diagnostic_path(&project, workspace_root);
+            if !diagnostic.projects.contains(&project) {
+                diagnostic.projects.push(project);
+            }
+        }
+        if let Some(framework) = target_framework {
+            if !diagnostic.target_frameworks.contains(&framework) {
+                diagnostic.target_frameworks.push(framework);
+            }
+        }
     }
 
     #[cfg(feature = "profiling")]
@@ -115,6 +175,48 @@ fn parse_build_diagnostics(output: &str, workspace_root: &str) -> Vec<BuildDiagn
     diagnostics
 }
 
+/// Split the `[path/App.csproj::TargetFramework=net8.0]` suffix MSBuild adds to
+/// diagnostics off the message
+fn split_project_suffix(message: &str) -> (String, Option<String>, Option<String>) {
+    let suffix = message
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once(" ["));
+    let Some((text, suffix)) = suffix else {
+        return (message.to_string(), None, None);
+    };
+    let (project, framework) = match suffix.split_once("::TargetFramework=") {
+        Some((project, framework)) => (project, Some(framework.to_string())),
+        None => (suffix, None),
+    };
+    let is_project = [".csproj", ".fsproj", ".vbproj", ".sln", ".slnx", ".proj"]
+        .iter()
+        .any(|ext| project.to_lowercase().ends_with(ext));
+    if !is_project {
+        return (message.to_string(), None, None);
+    }
+    (
+        text.trim_end().to_string(),
+        Some(project.to_string()),
+        framework,
+    )
+}
+
+/// Short hash identifying a diagnostic across builds
+fn diagnostic_id(
+    file_path: &str,
+    line: u32,
+    column: u32,
+    severity: &str,
+    code: &str,
+    message: &str,
+) -> String {
+    let key = format!(
+        "{}\0{}\0{}\0{}\0{}\0{}",
+        file_path, line, column, severity, code, message
+    );
+    sha256_hex(key.as_bytes())[..16].to_string()
+}
+
 /// Normalize a path from build output to an absolute path.
 ///
 /// Handles:
@@ -122,6 +224,13 @@ fn parse_build_diagnostics(output: &str, workspace_root: &str) -> Vec<BuildDiagn
 /// - Relative paths (resolved against workspace_root)
 /// - Windows backslash normalization
 fn normalize_diagnostic_path(raw_path: &str, workspace_root: &str) -> String {
+    // Builds in a WSL-rooted workspace report paths inside the distro
+    if raw_path.starts_with('/') {
+        if let Some(workspace) = wsl_workspace(workspace_root) {
+            return wsl_to_windows(raw_path, &workspace.distro);
+        }
+    }
+
     let path = PathBuf::from(raw_path);
 
     // Check if the path is already absolute
@@ -181,6 +290,11 @@ impl ProjectConfigCache {
         cache.get(workspace_root).cloned()
     }
 
+    /// Number of workspaces with cached configurations
+    pub async fn cached_count(&self) -> usize {
+        self.cache.read().await.len()
+    }
+
     pub async fn set(&self, workspace_root: String, configs: Vec<BuildConfiguration>) {
         let mut cache = self.cache.write().await;
         cache.insert(workspace_root, configs);
@@ -203,13 +317,65 @@ fn resolve_build_target(workspace_root: &Path) -> Option<PathBuf> {
     find_solution_file(workspace_root).or_else(|| find_project_file(workspace_root))
 }
 
+/// `dotnet build` for the workspace's solution or project, run inside the
+/// distro for WSL-rooted workspaces
+pub(crate) fn dotnet_build_command(
+    workspace_root: &str,
+    configuration: Option<&str>,
+) -> Result<Command, String> {
+    let root = Path::new(workspace_root);
+    let build_target = resolve_build_target(root);
+    if let Some(target) = &build_target {
+        println!("[Tauri] Resolved explicit build target: {:?}", target);
+    } else {
+        println!("[Tauri] No solution or project file resolved, falling back to workspace root");
+    }
+
+    let mut args = vec!["build".to_string()];
+    if let Some(target) = &build_target {
+        args.push(target.to_string_lossy().to_string());
+    }
+
+    // Add configuration flag if specified
+    if let Some(config) = configuration {
+        println!("[Tauri] Using configuration: {}", config);
+        args.push("--configuration".to_string());
+        args.push(config.to_string());
+    }
+
+    // WSL-rooted workspaces build with the distro's SDK
+    let cmd = match wsl_workspace(workspace_root) {
+        Some(workspace) => {
+            let command_line =
+                std::iter::once("dotnet".to_string())
+                    .chain(args.iter().map(|arg| {
+                        shell_quote(&windows_to_wsl(arg).unwrap_or_else(|| arg.clone()))
+                    }))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            println!("[Tauri] Building inside WSL distro {}", workspace.distro);
+            Command::from(wsl_command(
+                &workspace.distro,
+                Some(&workspace.linux_root),
+                &command_line,
+            )?)
+        }
+        None => {
+            let mut cmd = Command::new("dotnet");
+            cmd.args(&args).current_dir(root);
+            cmd
+        }
+    };
+    Ok(cmd)
+}
+
 /// Get available build configurations from a C# project
 /// Uses caching to avoid repeated file system walks for the same workspace
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
     tracing::instrument(skip(workspace_root, cache), fields(category = "tauri_command", workspace_root = %workspace_root))
 )]
-#[tauri::command]
 pub async fn get_project_configurations(
     workspace_root: String,
     cache: tauri::State<'_, ProjectConfigCache>,
@@ -323,10 +489,11 @@ pub async fn get_project_configurations(
 /// - Raw build output
 /// - Parsed diagnostics (errors/warnings with file locations)
 /// - Build duration in milliseconds
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
     tracing::instrument(
-        skip(workspace_root, configuration),
+        skip(app, diagnostics_state, workspace_root, configuration),
         fields(
             category = "tauri_command",
             workspace_root = %workspace_root,
@@ -334,11 +501,12 @@ pub async fn get_project_configurations(
         )
     )
 )]
-#[tauri::command]
-pub async fn build_csharp_project(
+pub async fn build_csharp_project<R: Runtime>(
+    app: AppHandle<R>,
     workspace_root: String,
     configuration: Option<String>,
     trace_parent: Option<String>,
+    diagnostics_state: State<'_, DiagnosticsState>,
 ) -> Result<BuildResult, String> {
     let _ = trace_parent; // Suppress unused warning
     let root = PathBuf::from(&workspace_root);
@@ -351,13 +519,6 @@ pub async fn build_csharp_project(
 
     println!("[Tauri] Running dotnet build in {:?}", root);
 
-    let build_target = resolve_build_target(&root);
-    if let Some(target) = &build_target {
-        println!("[Tauri] Resolved explicit build target: {:?}", target);
-    } else {
-        println!("[Tauri] No solution or project file resolved, falling back to workspace root");
-    }
-
     #[cfg(feature = "profiling")]
     let config_str = configuration.as_deref().unwrap_or("default");
     #[cfg(feature = "profiling")]
@@ -368,18 +529,7 @@ pub async fn build_csharp_project(
     #[cfg(feature = "profiling")]
     tracing::info!("Executing dotnet build command");
 
-    let mut cmd = Command::new("dotnet");
-    cmd.arg("build").current_dir(&root);
-
-    if let Some(target) = &build_target {
-        cmd.arg(target);
-    }
-
-    // Add configuration flag if specified
-    if let Some(ref config) = configuration {
-        println!("[Tauri] Using configuration: {}", config);
-        cmd.arg("--configuration").arg(config);
-    }
+    let mut cmd = dotnet_build_command(&workspace_root, configuration.as_deref())?;
 
     let output = cmd
         .output()
@@ -420,6 +570,16 @@ pub async fn build_csharp_project(
         );
     }
 
+    // Replace the previous build's problems in the shared store
+    diagnostics_state.set_provider(
+        &app,
+        "build",
+        diagnostics
+            .iter()
+            .map(BuildDiagnostic::to_diagnostic)
+            .collect(),
+    );
+
     println!(
         "[Tauri] Build {} in {}ms with {} diagnostics",
         if success { "succeeded" } else { "failed" },
@@ -534,6 +694,49 @@ Build failed.
         assert_eq!(diagnostics[2].code, "CS0168");
     }
 
+    #[test]
+    fn test_parse_wsl_workspace_paths() {
+        let output = "/home/me/app/Program.cs(4,2): error CS1002: ; expected";
+        let diagnostics = parse_build_diagnostics(output, r"\\wsl.localhost\Ubuntu\home\me\app");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].file_path,
+            r"\\wsl.localhost\Ubuntu\home\me\app\Program.cs"
+        );
+    }
+
+    #[test]
+    fn test_group_multi_targeted_diagnostics() {
+        let output = r#"
+/project/App/Program.cs(3,9): warning CS0168: The variable 'x' is declared but never used [/project/App/App.csproj::TargetFramework=net6.0]
+/project/App/Program.cs(3,9): warning CS0168: The variable 'x' is declared but never used [/project/App/App.csproj::TargetFramework=net8.0]
+/project/Lib/Lib.cs(1,1): error CS1002: ; expected [/project/Lib/Lib.csproj]
+Build FAILED.
+/project/App/Program.cs(3,9): warning CS0168: The variable 'x' is declared but never used [/project/App/App.csproj::TargetFramework=net6.0]
+/project/Lib/Lib.cs(1,1): error CS1002: ; expected [/project/Lib/Lib.csproj]
+"#;
+        let diagnostics = parse_build_diagnostics(output, "/project");
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].message,
+            "The variable 'x' is declared but never used"
+        );
+        assert_eq!(diagnostics[0].target_frameworks, vec!["net6.0", "net8.0"]);
+        assert_eq!(diagnostics[0].projects.len(), 1);
+        assert!(diagnostics[0].projects[0].ends_with("App.csproj"));
+        assert!(diagnostics[1].target_frameworks.is_empty());
+        assert_ne!(diagnostics[0].id, diagnostics[1].id);
+
+        // Ids do not depend on which frameworks or projects reported it
+        let single = parse_build_diagnostics(
+            "/project/Lib/Lib.cs(1,1): error CS1002: ; expected",
+            "/project",
+        );
+        assert_eq!(single[0].id, diagnostics[1].id);
+    }
+
     #[test]
     fn test_parse_no_diagnostics() {
         let output = "Build succeeded.\n    0 Warning(s)\n    0 Error(s)";
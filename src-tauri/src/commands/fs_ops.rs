@@ -0,0 +1,493 @@
+//! File System Commands
+//!
+//! File operations that go beyond the fs plugin, such as new files populated
+//! from file templates, moves that keep JS/TS import specifiers pointing at the
+//! moved files, duplicates named the way the platform's file manager names
+//! them, and the path forms the explorer's "Copy Relative Path" and "Copy POSIX
+//! Path" actions put on the clipboard, for drive paths and WSL shares alike.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::file_templates::render_file_template_for;
+use crate::services::import_rewrite::{apply_import_edits, plan_import_updates, FileImportEdits};
+use crate::services::wsl::{windows_to_wsl, wsl_workspace};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::async_runtime::spawn_blocking;
+use tauri::State;
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameResult {
+    pub from: String,
+    pub to: String,
+    /// Import rewrites, addressed by each file's path after the move
+    pub import_edits: Vec<FileImportEdits>,
+    /// Whether `import_edits` were written to disk
+    pub edits_applied: bool,
+}
+
+/// Rename or move a file or directory, optionally rewriting imports that point at it
+///
+/// # Arguments
+/// * `from` - Existing file or directory
+/// * `to` - New path; missing parent directories are created
+/// * `workspace_root` - Root scanned for imports (required when `update_imports` is set)
+/// * `update_imports` - Compute import specifier edits for the move (default: false)
+/// * `apply_edits` - Write the edits to disk instead of only returning them (default: false)
+/// * `force` - Proceed even if moved or rewritten files have unsaved editor changes
+#[fluxel_command]
+pub async fn rename_path(
+    from: String,
+    to: String,
+    workspace_root: Option<String>,
+    update_imports: Option<bool>,
+    apply_edits: Option<bool>,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<RenameResult, String> {
+    let dirty_files = dirty_files.inner().clone();
+    spawn_blocking(move || {
+        let source = PathBuf::from(&from);
+        let destination = PathBuf::from(&to);
+        if !source.exists() {
+            return Err(format!("Path does not exist: {}", from));
+        }
+        if destination.exists() {
+            return Err(format!("Destination already exists: {}", to));
+        }
+        if source.is_dir() && destination.starts_with(&source) {
+            return Err(format!("Cannot move {} into itself", from));
+        }
+
+        // Specifiers are resolved against the tree as it is before the move
+        let import_edits = if update_imports.unwrap_or(false) {
+            let root = workspace_root
+                .as_deref()
+                .ok_or("workspace_root is required to update imports")?;
+            plan_import_updates(Path::new(root), &source, &destination)
+        } else {
+            Vec::new()
+        };
+
+        let edits_applied = apply_edits.unwrap_or(false) && !import_edits.is_empty();
+        let mut affected = vec![source.clone()];
+        if edits_applied {
+            // Edits are addressed by post-move paths; guard the files as they are now
+            affected.extend(import_edits.iter().map(|edit| {
+                let path = PathBuf::from(&edit.file_path);
+                match path.strip_prefix(&destination) {
+                    Ok(rest) => source.join(rest),
+                    Err(_) => path,
+                }
+            }));
+        }
+        dirty_files.guard("rename_path", &affected, force.unwrap_or(false))?;
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::rename(&source, &destination)
+            .map_err(|e| format!("Failed to move {} to {}: {}", from, to, e))?;
+
+        if edits_applied {
+            let files = apply_import_edits(&import_edits)?;
+            println!("[FsOps] Updated imports in {} files", files);
+        }
+
+        Ok(RenameResult {
+            from,
+            to,
+            import_edits,
+            edits_applied,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedFile {
+    pub path: String,
+    /// Pattern of the file template the file was populated from
+    pub template: Option<String>,
+}
+
+/// Create a new file, optionally populated from the matching file template
+///
+/// # Arguments
+/// * `path` - File to create; fails if it already exists
+/// * `workspace_root` - Workspace for workspace templates, globs and namespaces
+/// * `content` - Initial content; takes precedence over templates
+/// * `apply_template` - Populate the file from its template when no content is given (default: false)
+#[fluxel_command]
+pub async fn create_file(
+    path: String,
+    workspace_root: Option<String>,
+    content: Option<String>,
+    apply_template: Option<bool>,
+) -> Result<CreatedFile, String> {
+    spawn_blocking(move || {
+        let file_path = PathBuf::from(&path);
+        let rendered = match (&content, apply_template.unwrap_or(false)) {
+            (None, true) => {
+                render_file_template_for(&file_path, workspace_root.as_deref().map(Path::new))?
+            }
+            _ => None,
+        };
+        let text = content
+            .or_else(|| rendered.as_ref().map(|r| r.content.clone()))
+            .unwrap_or_default();
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::AlreadyExists => format!("File already exists: {}", path),
+                _ => format!("Failed to create {}: {}", path, e),
+            })?;
+        file.write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+        let template = rendered.map(|r| r.pattern);
+        if let Some(pattern) = &template {
+            println!("[FsOps] Created {} from template '{}'", path, pattern);
+        }
+        Ok(CreatedFile { path, template })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Give up looking for a free copy name after this many copies
+const MAX_COPIES: u32 = 10_000;
+
+/// How `duplicate_path` names copies, after each platform's file manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyNaming {
+    /// Finder: `name copy.txt`, `name copy 2.txt`
+    Macos,
+    /// Explorer: `name - Copy.txt`, `name - Copy (2).txt`
+    Windows,
+    /// GNOME Files: `name (copy).txt`, `name (copy 2).txt`
+    Linux,
+}
+
+impl CopyNaming {
+    fn native() -> Self {
+        if cfg!(target_os = "windows") {
+            CopyNaming::Windows
+        } else if cfg!(target_os = "macos") {
+            CopyNaming::Macos
+        } else {
+            CopyNaming::Linux
+        }
+    }
+
+    /// Name of the `n`th copy, counting from 1
+    fn copy_name(self, stem: &str, extension: Option<&str>, n: u32) -> String {
+        let stem = match (self, n) {
+            (CopyNaming::Macos, 1) => format!("{} copy", stem),
+            (CopyNaming::Macos, n) => format!("{} copy {}", stem, n),
+            (CopyNaming::Windows, 1) => format!("{} - Copy", stem),
+            (CopyNaming::Windows, n) => format!("{} - Copy ({})", stem, n),
+            (CopyNaming::Linux, 1) => format!("{} (copy)", stem),
+            (CopyNaming::Linux, n) => format!("{} (copy {})", stem, n),
+        };
+        match extension {
+            Some(extension) => format!("{}.{}", stem, extension),
+            None => stem,
+        }
+    }
+
+    /// `stem` without this style's copy suffix, so a copy of a copy gets the
+    /// next number rather than a second suffix
+    fn original_stem(self, stem: &str) -> &str {
+        let (single, numbered_prefix, numbered_suffix) = match self {
+            CopyNaming::Macos => (" copy", " copy ", ""),
+            CopyNaming::Windows => (" - Copy", " - Copy (", ")"),
+            CopyNaming::Linux => (" (copy)", " (copy ", ")"),
+        };
+        let numbered = || {
+            let (base, number) = stem
+                .strip_suffix(numbered_suffix)?
+                .rsplit_once(numbered_prefix)?;
+            (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(base)
+        };
+        stem.strip_suffix(single)
+            .or_else(numbered)
+            .filter(|base| !base.is_empty())
+            .unwrap_or(stem)
+    }
+}
+
+/// Split a file name into stem and extension; directories and dotfiles such as
+/// `.env` have no extension
+fn split_name(name: &str, is_dir: bool) -> (&str, Option<&str>) {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 && !is_dir => (&name[..dot], Some(&name[dot + 1..])),
+        _ => (name, None),
+    }
+}
+
+/// Copy `source` next to itself under the first free copy name
+fn duplicate(source: &Path, naming: CopyNaming) -> Result<PathBuf, String> {
+    let parent = source
+        .parent()
+        .ok_or_else(|| format!("Cannot duplicate {}", source.display()))?;
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Cannot duplicate {}", source.display()))?;
+    let is_dir = source.is_dir();
+    let (stem, extension) = split_name(&name, is_dir);
+    let stem = naming.original_stem(stem);
+
+    for n in 1..=MAX_COPIES {
+        let candidate = parent.join(naming.copy_name(stem, extension, n));
+        // Creating the copy claims the name, so a concurrent duplicate can't
+        // pick it as well
+        let claimed = if is_dir {
+            fs::create_dir(&candidate)
+        } else {
+            fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&candidate)
+                .map(|_| ())
+        };
+        match claimed {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create {}: {}", candidate.display(), e)),
+        }
+
+        let copied = if is_dir {
+            copy_dir_contents(source, &candidate)
+        } else {
+            fs::copy(source, &candidate).map(|_| ())
+        };
+        if let Err(e) = copied {
+            let _ = if is_dir {
+                fs::remove_dir_all(&candidate)
+            } else {
+                fs::remove_file(&candidate)
+            };
+            return Err(format!(
+                "Failed to copy {} to {}: {}",
+                source.display(),
+                candidate.display(),
+                e
+            ));
+        }
+        return Ok(candidate);
+    }
+    Err(format!("No free copy name for {}", source.display()))
+}
+
+/// Copy everything inside `source` into the existing directory `dest`.
+/// Symlinks are recreated on Unix; elsewhere linked files are copied and
+/// linked folders skipped.
+fn copy_dir_contents(source: &Path, dest: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(source).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .map_err(io::Error::other)?;
+        let target = dest.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+            #[cfg(not(unix))]
+            if entry.path().is_file() {
+                fs::copy(entry.path(), &target)?;
+            }
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Duplicate a file or directory next to itself and return the copy's path
+///
+/// # Arguments
+/// * `path` - File or directory to duplicate
+/// * `naming` - Copy naming convention (default: the current platform's)
+#[fluxel_command]
+pub async fn duplicate_path(path: String, naming: Option<CopyNaming>) -> Result<String, String> {
+    spawn_blocking(move || {
+        let source = PathBuf::from(&path);
+        if !source.exists() {
+            return Err(format!("Path does not exist: {}", path));
+        }
+        let copy = duplicate(&source, naming.unwrap_or_else(CopyNaming::native))?;
+        println!("[FsOps] Duplicated {} to {}", path, copy.display());
+        Ok(copy.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Separators used by `copy_relative_path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathStyle {
+    /// `/` inside WSL workspaces and on Unix, `\` for other Windows paths
+    Native,
+    Posix,
+    Windows,
+}
+
+/// `path` in POSIX form: drive paths as `/mnt/<drive>/...`, WSL shares as
+/// the path inside the distro, anything else with `/` separators
+fn posix_path(path: &str) -> String {
+    let posix = windows_to_wsl(path).unwrap_or_else(|| path.replace('\\', "/"));
+    match posix.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// `path` relative to `root`, with `/` separators. Both are compared in POSIX
+/// form, so a Windows path and the WSL form of its root still match; drive
+/// paths compare case-insensitively, like Windows does.
+fn relative_path(path: &str, root: &str) -> Option<String> {
+    let path = posix_path(path);
+    let root = posix_path(root);
+    let is_drive_path = root.starts_with("/mnt/") && path.starts_with("/mnt/");
+    let rest = if is_drive_path {
+        path.get(..root.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(&root))
+            .map(|_| &path[root.len()..])
+    } else {
+        path.strip_prefix(root.as_str())
+    }?;
+    if rest.is_empty() {
+        return Some(".".to_string());
+    }
+    // `/src/app` must not count as inside `/src/ap`
+    if root == "/" {
+        return Some(rest.to_string());
+    }
+    rest.strip_prefix('/').map(str::to_string)
+}
+
+/// Text for the explorer's "Copy Relative Path" action
+///
+/// # Arguments
+/// * `path` - File or directory inside the workspace
+/// * `workspace_root` - Workspace root, in Windows, WSL share or POSIX form
+/// * `style` - Separators to use (default: native for the workspace)
+#[fluxel_command]
+pub fn copy_relative_path(
+    path: String,
+    workspace_root: String,
+    style: Option<PathStyle>,
+) -> Result<String, String> {
+    let relative = relative_path(&path, &workspace_root)
+        .ok_or_else(|| format!("{} is not inside {}", path, workspace_root))?;
+    let windows_separators = match style.unwrap_or(PathStyle::Native) {
+        PathStyle::Posix => false,
+        PathStyle::Windows => true,
+        PathStyle::Native => {
+            cfg!(target_os = "windows") && wsl_workspace(&workspace_root).is_none()
+        }
+    };
+    Ok(if windows_separators {
+        relative.replace('/', "\\")
+    } else {
+        relative
+    })
+}
+
+/// Text for the explorer's "Copy POSIX Path" action: drive paths become
+/// `/mnt/<drive>/...` and WSL shares the path inside the distro
+#[fluxel_command]
+pub fn copy_posix_path(path: String) -> String {
+    posix_path(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_with_platform_names_and_copies_path_forms() {
+        assert_eq!(
+            CopyNaming::Macos.copy_name("notes", Some("txt"), 2),
+            "notes copy 2.txt"
+        );
+        assert_eq!(
+            CopyNaming::Windows.copy_name("notes", Some("txt"), 2),
+            "notes - Copy (2).txt"
+        );
+        assert_eq!(CopyNaming::Linux.copy_name("src", None, 1), "src (copy)");
+        assert_eq!(CopyNaming::Windows.original_stem("a - Copy (3)"), "a");
+        assert_eq!(CopyNaming::Macos.original_stem("copy"), "copy");
+        assert_eq!(split_name(".env", false), (".env", None));
+        assert_eq!(split_name("app.test.ts", false), ("app.test", Some("ts")));
+
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-fs-ops-{unique}"));
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("notes.txt"), "hello").unwrap();
+        fs::write(root.join("src/nested/a.ts"), "export {};").unwrap();
+
+        let first = duplicate(&root.join("notes.txt"), CopyNaming::Macos).unwrap();
+        assert_eq!(first, root.join("notes copy.txt"));
+        assert_eq!(fs::read_to_string(&first).unwrap(), "hello");
+        let second = duplicate(&first, CopyNaming::Macos).unwrap();
+        assert_eq!(second, root.join("notes copy 2.txt"));
+
+        let dir = duplicate(&root.join("src"), CopyNaming::Windows).unwrap();
+        assert_eq!(dir, root.join("src - Copy"));
+        assert_eq!(
+            fs::read_to_string(dir.join("nested/a.ts")).unwrap(),
+            "export {};"
+        );
+        fs::remove_dir_all(&root).ok();
+
+        assert_eq!(
+            relative_path(r"C:\Work\App\src\main.ts", "/mnt/c/work/app").as_deref(),
+            Some("src/main.ts")
+        );
+        assert_eq!(
+            relative_path(r"\\wsl.localhost\Ubuntu\home\me\app\lib", "/home/me/app").as_deref(),
+            Some("lib")
+        );
+        assert_eq!(relative_path("/home/me/app2/x", "/home/me/app"), None);
+        assert_eq!(
+            relative_path("/home/me/app/", "/home/me/app").as_deref(),
+            Some(".")
+        );
+        assert_eq!(
+            copy_relative_path(
+                "/home/me/app/src/a.ts".to_string(),
+                "/home/me/app".to_string(),
+                Some(PathStyle::Windows)
+            )
+            .unwrap(),
+            r"src\a.ts"
+        );
+        assert_eq!(copy_posix_path(r"D:\data\".to_string()), "/mnt/d/data");
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Commands for application launch state and initialization.
 
+use fluxel_macros::fluxel_command;
 use serde::Serialize;
 use std::sync::Mutex;
 use tauri::State;
@@ -31,22 +32,22 @@ impl Default for LaunchState {
 }
 
 /// A simple greeting command for testing
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
     tracing::instrument(skip(name), fields(category = "workspace"))
 )]
-#[tauri::command]
 pub fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
 /// Get the launch info passed via CLI arguments (e.g., from context menu)
 /// Returns workspace_path (always a directory) and optionally file_path (when user right-clicked a file)
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
     tracing::instrument(skip(state), fields(category = "workspace"))
 )]
-#[tauri::command]
 pub fn get_launch_path(state: State<LaunchState>) -> Option<LaunchInfo> {
     let mut info = state.0.lock().unwrap();
     info.take()
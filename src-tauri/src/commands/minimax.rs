@@ -2,16 +2,59 @@
 //!
 //! This module proxies MiniMax calls through the Tauri backend so the frontend can use
 //! the model without browser CORS limitations.
+//!
+//! Optional sampling parameters (stop sequences, `top_p`, seed, penalties) are
+//! checked against a per-model capability table and rejected with an error
+//! when the model does not accept them, rather than sent and silently ignored.
 
+use fluxel_macros::fluxel_command;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
+use crate::services::ai_metrics::{estimate_tokens, AiRequestSpan};
+
 const DEFAULT_MINIMAX_BASE_URL: &str = "https://api.minimaxi.chat";
 const MINIMAX_CHAT_PATH: &str = "/v1/text/chatcompletion_v2";
 const DEFAULT_MODEL: &str = "MiniMax-M2.7";
 const DEFAULT_MAX_TOKENS: u32 = 4096;
 const MINIMAX_MAX_OUTPUT_TOKENS: u32 = 196_608;
+const MAX_STOP_SEQUENCES: usize = 4;
+
+/// Sampling parameters beyond `temperature` and `max_tokens` that a family of
+/// models accepts, matched by model name prefix
+struct ModelCapabilities {
+    prefix: &'static str,
+    stop: bool,
+    top_p: bool,
+    seed: bool,
+    penalties: bool,
+}
+
+/// The last entry matches every model and holds the conservative defaults
+const MODEL_CAPABILITIES: &[ModelCapabilities] = &[
+    ModelCapabilities {
+        prefix: "MiniMax-",
+        stop: false,
+        top_p: true,
+        seed: false,
+        penalties: false,
+    },
+    ModelCapabilities {
+        prefix: "abab",
+        stop: false,
+        top_p: true,
+        seed: false,
+        penalties: false,
+    },
+    ModelCapabilities {
+        prefix: "",
+        stop: false,
+        top_p: true,
+        seed: false,
+        penalties: false,
+    },
+];
 
 /// Stream chunk sent to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,12 +110,41 @@ pub struct MinimaxToolCall {
     pub id: Option<String>,
 }
 
+/// Image reference inside a content part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimaxImageUrl {
+    /// `data:` URL or remote URL
+    pub url: String,
+}
+
+/// One part of a multi-part message, used to send images to vision models.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MinimaxContentPart {
+    Text { text: String },
+    ImageUrl { image_url: MinimaxImageUrl },
+}
+
+/// Message content: plain text, or text and image parts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MinimaxContent {
+    Text(String),
+    Parts(Vec<MinimaxContentPart>),
+}
+
+impl From<String> for MinimaxContent {
+    fn from(text: String) -> Self {
+        MinimaxContent::Text(text)
+    }
+}
+
 /// Message structure for MiniMax API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinimaxMessage {
     pub role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<String>,
+    pub content: Option<MinimaxContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<MinimaxToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -81,6 +153,84 @@ pub struct MinimaxMessage {
     pub name: Option<String>,
 }
 
+/// Optional sampling parameters, passed through when the model supports them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SamplingParameters {
+    /// Sequences that end generation when produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+}
+
+impl SamplingParameters {
+    /// Reject parameters `model` does not accept and values out of range
+    fn validate(&self, model: &str) -> Result<(), String> {
+        let capabilities = MODEL_CAPABILITIES
+            .iter()
+            .find(|entry| model.starts_with(entry.prefix))
+            .expect("the last capability entry matches every model");
+        let unsupported: Vec<&str> = [
+            ("stop", self.stop.is_some(), capabilities.stop),
+            ("top_p", self.top_p.is_some(), capabilities.top_p),
+            ("seed", self.seed.is_some(), capabilities.seed),
+            (
+                "frequency_penalty",
+                self.frequency_penalty.is_some(),
+                capabilities.penalties,
+            ),
+            (
+                "presence_penalty",
+                self.presence_penalty.is_some(),
+                capabilities.penalties,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, given, supported)| *given && !supported)
+        .map(|(name, ..)| name)
+        .collect();
+        if !unsupported.is_empty() {
+            return Err(format!(
+                "{} does not support {}",
+                model,
+                unsupported.join(", ")
+            ));
+        }
+
+        if let Some(top_p) = self.top_p {
+            if !(top_p > 0.0 && top_p <= 1.0) {
+                return Err(format!("top_p must be in (0, 1], got {top_p}"));
+            }
+        }
+        for (name, penalty) in [
+            ("frequency_penalty", self.frequency_penalty),
+            ("presence_penalty", self.presence_penalty),
+        ] {
+            if let Some(penalty) = penalty.filter(|p| !(-2.0..=2.0).contains(p)) {
+                return Err(format!("{name} must be in [-2, 2], got {penalty}"));
+            }
+        }
+        if let Some(stop) = &self.stop {
+            if stop.len() > MAX_STOP_SEQUENCES {
+                return Err(format!(
+                    "At most {MAX_STOP_SEQUENCES} stop sequences are allowed, got {}",
+                    stop.len()
+                ));
+            }
+            if stop.iter().any(|sequence| sequence.is_empty()) {
+                return Err("Stop sequences must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Request structure for MiniMax API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinimaxRequest {
@@ -92,6 +242,11 @@ pub struct MinimaxRequest {
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<MinimaxToolDefinition>>,
+    /// JSON Schema the reply must follow; sent as a `json_schema` response format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub sampling: SamplingParameters,
 }
 
 #[derive(Debug, Serialize)]
@@ -109,11 +264,25 @@ struct MinimaxChatRequest {
     parallel_tool_calls: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "reasoning_split")]
     reasoning_split: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(flatten)]
+    sampling: SamplingParameters,
 }
 
 #[derive(Debug, Deserialize)]
 struct MinimaxChatResponse {
     choices: Vec<MinimaxChoice>,
+    #[serde(default)]
+    usage: Option<MinimaxUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinimaxUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u64>,
+    #[serde(default)]
+    completion_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -211,6 +380,16 @@ fn resolve_endpoint(api_base: Option<&str>) -> String {
     }
 }
 
+/// OpenAI-style `response_format` for a JSON Schema
+fn response_format(schema: Option<serde_json::Value>) -> Option<serde_json::Value> {
+    schema.map(|schema| {
+        serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": "response", "schema": schema, "strict": true },
+        })
+    })
+}
+
 fn parse_tool_arguments(arguments: &str) -> serde_json::Value {
     if arguments.trim().is_empty() {
         serde_json::json!({})
@@ -384,7 +563,7 @@ fn process_minimax_stream_line(
 }
 
 /// Non-streaming chat completion.
-#[tauri::command]
+#[fluxel_command]
 pub async fn minimax_chat(
     api_key: String,
     request: MinimaxRequest,
@@ -393,8 +572,10 @@ pub async fn minimax_chat(
     let endpoint = resolve_endpoint(api_base.as_deref());
     let model = normalize_model(request.model);
     let max_tokens = normalize_max_tokens(request.max_tokens);
+    request.sampling.validate(&model)?;
 
     let tools = request.tools;
+    let response_format = response_format(request.response_schema);
     let tool_choice = tools.as_ref().map(|_| "auto".to_string());
     let parallel_tool_calls = tools.as_ref().map(|_| true);
 
@@ -408,7 +589,10 @@ pub async fn minimax_chat(
         tool_choice,
         parallel_tool_calls,
         reasoning_split: Some(true),
+        response_format,
+        sampling: request.sampling,
     };
+    let span = AiRequestSpan::start("minimax", &body.model, prompt_tokens(&body.messages));
 
     let response = reqwest::Client::new()
         .post(&endpoint)
@@ -435,6 +619,7 @@ pub async fn minimax_chat(
     let parsed: MinimaxChatResponse =
         serde_json::from_str(&text).map_err(|e| format!("Failed to parse response: {e}"))?;
 
+    let usage = parsed.usage;
     let message = parsed
         .choices
         .into_iter()
@@ -442,11 +627,21 @@ pub async fn minimax_chat(
         .map(|choice| choice.message)
         .ok_or_else(|| "MiniMax response missing choices[0].message".to_string())?;
 
+    if let Some(tokens) = usage.as_ref().and_then(|u| u.prompt_tokens) {
+        span.record_input_tokens(tokens);
+    }
+    span.finish(Some(
+        usage.and_then(|u| u.completion_tokens).unwrap_or_else(|| {
+            estimate_tokens(message.content.as_deref().unwrap_or_default())
+                + estimate_tokens(message.reasoning_content.as_deref().unwrap_or_default())
+        }),
+    ));
+
     serde_json::to_value(message).map_err(|e| format!("Failed to serialize response: {e}"))
 }
 
 /// Streaming chat completion - emits events to frontend.
-#[tauri::command]
+#[fluxel_command]
 pub async fn minimax_chat_stream(
     window: tauri::Window,
     api_key: String,
@@ -459,8 +654,13 @@ pub async fn minimax_chat_stream(
 
     let model = normalize_model(request.model);
     let max_tokens = normalize_max_tokens(request.max_tokens);
+    if let Err(message) = request.sampling.validate(&model) {
+        emit_stream_error(&window, &event_name, message.clone());
+        return Err(message);
+    }
 
     let tools = request.tools;
+    let response_format = response_format(request.response_schema);
     let tool_choice = tools.as_ref().map(|_| "auto".to_string());
     let parallel_tool_calls = tools.as_ref().map(|_| true);
 
@@ -474,7 +674,10 @@ pub async fn minimax_chat_stream(
         tool_choice,
         parallel_tool_calls,
         reasoning_split: Some(true),
+        response_format,
+        sampling: request.sampling,
     };
+    let mut span = AiRequestSpan::start("minimax", &body.model, prompt_tokens(&body.messages));
 
     let response = match reqwest::Client::new()
         .post(&endpoint)
@@ -513,6 +716,7 @@ pub async fn minimax_chat_stream(
     let mut saw_content_delta = false;
 
     let mut emit_delta = |chunk: StreamChunk| -> Result<(), String> {
+        span.first_token();
         window
             .emit(&event_name, chunk)
             .map_err(|e| format!("Failed to emit stream event: {e}"))
@@ -590,11 +794,33 @@ pub async fn minimax_chat_stream(
         })?;
     }
 
-    emit_delta(StreamChunk::Done)
+    let result = emit_delta(StreamChunk::Done);
+    span.finish(Some(
+        estimate_tokens(&content) + estimate_tokens(&reasoning),
+    ));
+    result
+}
+
+/// Estimated prompt size of `messages`, counting text content only
+fn prompt_tokens(messages: &[MinimaxMessage]) -> u64 {
+    messages
+        .iter()
+        .filter_map(|message| message.content.as_ref())
+        .map(|content| match content {
+            MinimaxContent::Text(text) => estimate_tokens(text),
+            MinimaxContent::Parts(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    MinimaxContentPart::Text { text } => estimate_tokens(text),
+                    MinimaxContentPart::ImageUrl { .. } => 0,
+                })
+                .sum(),
+        })
+        .sum()
 }
 
 /// Health check for MiniMax API.
-#[tauri::command]
+#[fluxel_command]
 pub async fn minimax_health_check(
     api_key: String,
     api_base: Option<String>,
@@ -605,7 +831,7 @@ pub async fn minimax_health_check(
         model: DEFAULT_MODEL.to_string(),
         messages: vec![MinimaxMessage {
             role: "user".to_string(),
-            content: Some("Hi".to_string()),
+            content: Some("Hi".to_string().into()),
             tool_calls: None,
             tool_call_id: None,
             name: None,
@@ -617,6 +843,8 @@ pub async fn minimax_health_check(
         tool_choice: None,
         parallel_tool_calls: None,
         reasoning_split: Some(true),
+        response_format: None,
+        sampling: SamplingParameters::default(),
     };
 
     let response = reqwest::Client::new()
@@ -651,6 +879,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sampling_parameters_are_checked_against_the_model() {
+        let request: MinimaxRequest = serde_json::from_value(serde_json::json!({
+            "model": "MiniMax-M2.7",
+            "messages": [],
+            "top_p": 0.9,
+        }))
+        .unwrap();
+        assert!(request.sampling.validate(&request.model).is_ok());
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["top_p"], serde_json::json!(0.9f32));
+        assert!(body.get("seed").is_none() && body.get("stop").is_none());
+
+        let unsupported = SamplingParameters {
+            stop: Some(vec!["END".to_string()]),
+            seed: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(
+            unsupported.validate("MiniMax-M2.7").unwrap_err(),
+            "MiniMax-M2.7 does not support stop, seed"
+        );
+        let out_of_range = SamplingParameters {
+            top_p: Some(1.5),
+            ..Default::default()
+        };
+        assert!(out_of_range.validate("custom-model").is_err());
+    }
+
     #[test]
     fn parse_tool_arguments_falls_back_to_empty_object() {
         assert_eq!(parse_tool_arguments(""), serde_json::json!({}));
@@ -5,11 +5,15 @@
 //! ## Structure
 //!
 //! - `workspace` - Directory listing, file search operations
+//! - `fs_ops` - New files from file templates, file moves with import specifier updates, duplicates, and copyable path forms
+//! - `asset` - Binary asset metadata and thumbnails
 //! - `build` - C# project build commands
 //! - `launch` - Application launch state and initialization
 //! - `minimax` - MiniMax API proxy commands
 
+pub mod asset;
 pub mod build;
+pub mod fs_ops;
 pub mod launch;
 pub mod minimax;
 pub mod terminal;
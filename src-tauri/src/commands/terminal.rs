@@ -1,12 +1,34 @@
+use crate::services::secrets::{contains_secret_reference, secret_env, SecretEnv, ShellSyntax};
+use crate::services::ssh_hosts::ssh_command;
+use crate::services::terminal_output::{stream_output, OutputLine, OutputOptions, StyledSegment};
+use crate::services::wsl::{windows_to_wsl, wsl_command, wsl_workspace};
 use crate::services::ProcessManager;
-use std::io::{BufRead, BufReader};
+use crate::services::TerminalHistoryState;
+use fluxel_macros::fluxel_command;
+use std::path::Path;
 use std::process::{Command, Stdio};
 use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 
 #[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 struct TerminalOutput {
     pid: u32,
     data: String,
+    /// Replaces the previous line of the same stream (a redrawn progress line)
+    replace: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    segments: Option<Vec<StyledSegment>>,
+}
+
+impl TerminalOutput {
+    fn new(pid: u32, line: OutputLine) -> Self {
+        Self {
+            pid,
+            data: line.text,
+            replace: line.replace,
+            segments: line.segments,
+        }
+    }
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -15,14 +37,53 @@ struct TerminalExit {
     code: Option<i32>,
 }
 
-#[tauri::command]
+/// Command that runs `command_line` through the system shell, so shell
+/// built-ins (like 'dir', 'echo') work and PATH resolution applies
+pub(crate) fn shell_command(command_line: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command_line]);
+        cmd
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command_line]);
+        cmd
+    }
+}
+
+/// Like `shell_command`, with the secrets the command line refers to set in
+/// the child's environment. On Windows, delayed expansion is turned on so
+/// their `!NAME!` references are expanded after `cmd` has parsed the line.
+pub(crate) fn secret_shell_command(secrets: &SecretEnv) -> Command {
+    let mut cmd = if cfg!(windows) && !secrets.vars.is_empty() {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/V:ON", "/C", &secrets.command_line]);
+        cmd
+    } else {
+        shell_command(&secrets.command_line)
+    };
+    cmd.envs(secrets.vars.iter().map(|(name, value)| (name, value)));
+    cmd
+}
+
+#[fluxel_command]
+#[allow(clippy::too_many_arguments)]
 pub fn execute_shell_command<R: Runtime>(
     app: AppHandle<R>,
     command: String,
     args: Vec<String>,
     cwd: Option<String>,
-    state: State<'_, ProcessManager>,
+    workspace_root: Option<String>,
+    output: Option<OutputOptions>,
+    wsl_distro: Option<String>,
+    ssh_host: Option<String>,
 ) -> Result<u32, String> {
+    let output = output.unwrap_or_default();
+
     // Build the full command string
     let full_command = if args.is_empty() {
         command
@@ -30,26 +91,85 @@ pub fn execute_shell_command<R: Runtime>(
         format!("{} {}", command, args.join(" "))
     };
 
-    // Use the system shell to execute commands
-    // This allows running shell built-ins (like 'dir', 'echo') and uses PATH resolution
-    #[cfg(target_os = "windows")]
-    let mut cmd = {
-        let mut c = Command::new("cmd");
-        c.args(["/C", &full_command]);
-        c
+    // History keeps the command as typed, so secret values never reach it
+    let history_command = full_command.clone();
+
+    // Terminals opened in a distro, and any terminal in a WSL-rooted
+    // workspace, run inside WSL from the matching Linux directory
+    let wsl = match wsl_distro {
+        Some(distro) => Some((distro, cwd.as_deref().and_then(windows_to_wsl))),
+        None => cwd
+            .as_deref()
+            .and_then(wsl_workspace)
+            .map(|workspace| (workspace.distro, Some(workspace.linux_root))),
     };
 
-    #[cfg(not(target_os = "windows"))]
-    let mut cmd = {
-        let mut c = Command::new("sh");
-        c.args(["-c", &full_command]);
-        c
+    // ${secret:NAME} references become environment variables of the child, so
+    // plaintext never round-trips through the UI, the shell parser, or `ps`.
+    // The environment doesn't reach SSH sessions, so they can't use secrets.
+    if ssh_host.is_some() && contains_secret_reference(&full_command) {
+        return Err("Secret references are not supported in SSH terminals".to_string());
+    }
+    let syntax = if wsl.is_some() {
+        ShellSyntax::Posix
+    } else {
+        ShellSyntax::native()
+    };
+    let secrets = match workspace_root.as_deref() {
+        Some(root) => secret_env(&full_command, Path::new(root), syntax)?,
+        None => SecretEnv {
+            command_line: full_command,
+            vars: Vec::new(),
+        },
     };
 
-    if let Some(dir) = cwd {
-        cmd.current_dir(dir);
-    }
+    // Remote terminals run in the host's login directory; killing the local
+    // ssh client closes the session
+    let cmd = match (&ssh_host, &wsl) {
+        (Some(host), _) => ssh_command(host, &secrets.command_line)?,
+        (None, Some((distro, linux_cwd))) => {
+            let mut cmd = wsl_command(distro, linux_cwd.as_deref(), &secrets.command_line)?;
+            if !secrets.vars.is_empty() {
+                // WSLENV lists the Windows variables shared with the distro
+                let shared = std::env::var("WSLENV")
+                    .ok()
+                    .filter(|existing| !existing.is_empty())
+                    .into_iter()
+                    .chain(secrets.vars.iter().map(|(name, _)| name.clone()))
+                    .collect::<Vec<_>>()
+                    .join(":");
+                cmd.envs(secrets.vars.iter().map(|(name, value)| (name, value)))
+                    .env("WSLENV", shared);
+            }
+            cmd
+        }
+        (None, None) => {
+            let mut cmd = secret_shell_command(&secrets);
+            if let Some(dir) = &cwd {
+                cmd.current_dir(dir);
+            }
+            cmd
+        }
+    };
 
+    let history = workspace_root
+        .or_else(|| cwd.clone())
+        .map(|root| (root, history_command, cwd));
+    spawn_streaming(&app, cmd, output, history)
+}
+
+/// Spawn `cmd` as a terminal process: output is streamed as
+/// `terminal://output` and `terminal://stderr` events, the exit as
+/// `terminal://exit`, and the process is tracked by the `ProcessManager`.
+///
+/// `history` is the workspace root, command line, and cwd recorded in the
+/// terminal history.
+pub(crate) fn spawn_streaming<R: Runtime>(
+    app: &AppHandle<R>,
+    mut cmd: Command,
+    output: OutputOptions,
+    history: Option<(String, String, Option<String>)>,
+) -> Result<u32, String> {
     // Configure pipes for streaming
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
@@ -68,7 +188,7 @@ pub fn execute_shell_command<R: Runtime>(
     let pid = child.id();
 
     // Register PID
-    state.register(pid);
+    app.state::<ProcessManager>().register(pid);
 
     let stdout = child.stdout.take().ok_or("Failed to open stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to open stderr")?;
@@ -76,28 +196,34 @@ pub fn execute_shell_command<R: Runtime>(
     // Spawn thread for stdout
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        for l in reader.lines().map_while(Result::ok) {
-            let _ = app_clone.emit("terminal://output", TerminalOutput { pid, data: l });
-        }
+        stream_output(stdout, output, |line| {
+            let _ = app_clone.emit("terminal://output", TerminalOutput::new(pid, line));
+        });
     });
 
     // Spawn thread for stderr
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for l in reader.lines().map_while(Result::ok) {
-            // We emit to same event or different?
-            // BuildPanel.tsx expects 'error' type for stderr.
-            // But let's use a distinct event or just include type in payload.
-            // For now, let's use a "terminal://stderr" event to be explicit.
-            let _ = app_clone.emit("terminal://stderr", TerminalOutput { pid, data: l });
-        }
+        // BuildPanel.tsx shows stderr as 'error' entries, so it gets its own event
+        stream_output(stderr, output, |line| {
+            let _ = app_clone.emit("terminal://stderr", TerminalOutput::new(pid, line));
+        });
     });
 
     // Spawn thread to wait for exit
     let app_clone = app.clone();
     std::thread::spawn(move || {
+        let history = history.and_then(|(root, command, cwd)| {
+            let state = app_clone.try_state::<TerminalHistoryState>()?;
+            match state.start(Path::new(&root), &command, cwd.as_deref(), pid) {
+                Ok(id) => Some((state.inner().clone(), root, id)),
+                Err(e) => {
+                    println!("[Terminal] Failed to record command history: {}", e);
+                    None
+                }
+            }
+        });
+
         let result = child.wait();
 
         let code = match result {
@@ -110,13 +236,19 @@ pub fn execute_shell_command<R: Runtime>(
             pm.unregister(pid);
         }
 
+        if let Some((state, root, id)) = history {
+            if let Err(e) = state.finish(Path::new(&root), &id, code) {
+                println!("[Terminal] Failed to record command exit: {}", e);
+            }
+        }
+
         let _ = app_clone.emit("terminal://exit", TerminalExit { pid, code });
     });
 
     Ok(pid)
 }
 
-#[tauri::command]
+#[fluxel_command]
 pub fn kill_shell_process(pid: u32, state: State<'_, ProcessManager>) -> Result<(), String> {
     state.kill_pid(pid);
     Ok(())
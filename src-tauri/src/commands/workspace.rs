@@ -2,9 +2,14 @@
 //!
 //! Commands for directory listing and file search operations.
 
+use crate::services::io_accounting::IoAccounting;
+use crate::services::path_filter::PathFilterState;
+use crate::services::search_index::{is_binary_file, SearchIndexState};
+use fluxel_macros::fluxel_command;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
@@ -26,6 +31,11 @@ impl GitignoreCache {
         }
     }
 
+    /// Number of workspaces with a cached matcher
+    pub async fn cached_count(&self) -> usize {
+        self.cache.read().await.len()
+    }
+
     /// Get a cached gitignore matcher for a workspace, or build and cache a new one.
     #[cfg_attr(
         feature = "profiling",
@@ -88,6 +98,8 @@ impl GitignoreCache {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchMatch {
     pub file_path: String,
+    /// Search root the file was found under
+    pub root: String,
     pub line_number: usize,
     pub line_content: String,
     pub match_start: usize,
@@ -113,11 +125,18 @@ pub struct DirEntry {
 /// List the immediate children of a directory without blocking the UI thread.
 /// Runs on a blocking thread pool and applies .gitignore rules (from the provided workspace root) when available.
 /// Uses cached gitignore matchers for improved performance on repeated calls.
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(path, workspace_root, cache), fields(category = "workspace"))
+    tracing::instrument(
+        skip(path, workspace_root, cache),
+        fields(
+            category = "workspace",
+            workspace_root = workspace_root.as_deref().unwrap_or_default(),
+            files_read = tracing::field::Empty
+        )
+    )
 )]
-#[tauri::command]
 pub async fn list_directory_entries(
     path: String,
     workspace_root: Option<String>,
@@ -164,6 +183,7 @@ pub async fn list_directory_entries(
         }
     };
 
+    let io = IoAccounting::current();
     let entries = {
         let blocking_future = spawn_blocking(move || -> Result<Vec<DirEntry>, String> {
             #[cfg(feature = "profiling")]
@@ -194,6 +214,7 @@ pub async fn list_directory_entries(
                     .map_err(|e| format!("Failed to read dir: {e}"))?
                     .collect()
             };
+            io.entries_listed(dir_entries.len() as u64);
 
             #[cfg(feature = "profiling")]
             let _process_span = tracing::span!(
@@ -311,15 +332,89 @@ pub async fn list_directory_entries(
     Ok(entries)
 }
 
+/// A folder of a multi-root workspace with its own include/exclude globs.
+/// Globs are relative to `path`; a glob without `/` matches at any depth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRoot {
+    pub path: String,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Compile search globs. `*.ts` becomes `**/*.ts` and `src` also matches `src/**`,
+/// mirroring the search panel's "files to include" semantics.
+fn build_search_globs(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern
+            .trim()
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        if pattern.is_empty() {
+            continue;
+        }
+        let pattern = if pattern.contains('/') {
+            pattern.to_string()
+        } else {
+            format!("**/{}", pattern)
+        };
+        for glob in [pattern.clone(), format!("{}/**", pattern)] {
+            let glob = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| format!("Invalid glob '{}': {}", pattern, e))?;
+            builder.add(glob);
+        }
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to build globs: {}", e))
+}
+
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(query, root_path), fields(category = "search"))
+    tracing::instrument(
+        skip(query, root_path, roots, index, filters),
+        fields(
+            category = "search",
+            workspace_root = root_path.as_deref().unwrap_or_default(),
+            bytes_read = tracing::field::Empty,
+            files_read = tracing::field::Empty
+        )
+    )
 )]
-#[tauri::command]
 pub fn search_files(
     query: String,
-    root_path: String,
+    root_path: Option<String>,
+    roots: Option<Vec<SearchRoot>>,
+    max_results: Option<usize>,
+    index: tauri::State<'_, SearchIndexState>,
+    filters: tauri::State<'_, PathFilterState>,
+) -> Result<SearchResult, String> {
+    run_search(
+        query,
+        root_path,
+        roots,
+        max_results,
+        Some(index.inner()),
+        filters.inner(),
+    )
+}
+
+fn run_search(
+    query: String,
+    root_path: Option<String>,
+    roots: Option<Vec<SearchRoot>>,
     max_results: Option<usize>,
+    index: Option<&SearchIndexState>,
+    filters: &PathFilterState,
 ) -> Result<SearchResult, String> {
     if query.is_empty() {
         return Ok(SearchResult {
@@ -329,57 +424,136 @@ pub fn search_files(
         });
     }
 
-    let root = PathBuf::from(&root_path);
+    // `roots` supersedes the single `root_path` used by single-folder workspaces
+    let roots = match (roots.filter(|r| !r.is_empty()), root_path) {
+        (Some(roots), _) => roots,
+        (None, Some(path)) => vec![SearchRoot {
+            path,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }],
+        (None, None) => return Err("No search root provided".to_string()),
+    };
+
+    let max_results = max_results.unwrap_or(1000);
+    let mut matches = Vec::new();
+    let mut total_files_searched = 0;
+    // Nested roots would otherwise report the same file twice
+    let mut seen_files = HashSet::new();
+    let io = IoAccounting::current();
+
+    for search_root in &roots {
+        if matches.len() >= max_results {
+            break;
+        }
+        total_files_searched += search_root_files(
+            &query,
+            search_root,
+            max_results,
+            index,
+            filters,
+            &mut seen_files,
+            &mut matches,
+            &io,
+        )?;
+    }
+
+    Ok(SearchResult {
+        total_matches: matches.len(),
+        total_files_searched,
+        matches,
+    })
+}
+
+/// Search one root, appending to `matches`. Returns the number of files searched.
+#[allow(clippy::too_many_arguments)]
+fn search_root_files(
+    query: &str,
+    search_root: &SearchRoot,
+    max_results: usize,
+    index: Option<&SearchIndexState>,
+    filters: &PathFilterState,
+    seen_files: &mut HashSet<PathBuf>,
+    matches: &mut Vec<SearchMatch>,
+    io: &IoAccounting,
+) -> Result<usize, String> {
+    let root = PathBuf::from(&search_root.path);
     if !root.exists() || !root.is_dir() {
         return Err(format!(
             "Root path does not exist or is not a directory: {}",
-            root_path
+            search_root.path
         ));
     }
 
-    let max_results = max_results.unwrap_or(1000);
-    let mut matches = Vec::new();
+    let include = build_search_globs(&search_root.include)?;
+    let exclude = build_search_globs(&search_root.exclude)?;
+    let root_attribution = search_root.path.replace('\\', "/");
     let mut total_files_searched = 0;
     let query_lower = query.to_lowercase();
 
-    // Build gitignore matcher
-    let mut builder = ignore::WalkBuilder::new(&root);
-    builder.hidden(false); // Don't skip hidden files by default
-    builder.git_ignore(true); // Respect .gitignore
-    builder.git_exclude(true); // Respect .git/info/exclude
-    builder.require_git(false); // Work even without git repo
+    // Each root honors its own ignore files and workspace exclusions; the root's
+    // exclude globs prune directories instead of walking into them
+    let filter = filters.filter_for(&root);
+    let walk_root = root.clone();
+    let root_exclude = exclude.clone();
+    let builder = filter.walk_builder_with(&root, move |entry| {
+        let Some(exclude) = &root_exclude else {
+            return true;
+        };
+        entry
+            .path()
+            .strip_prefix(&walk_root)
+            .map(|relative| relative.as_os_str().is_empty() || !exclude.is_match(relative))
+            .unwrap_or(true)
+    });
+
+    // The trigram index, when enabled, already applied the ignore rules and narrows
+    // the files to those containing every trigram of the query
+    let files: Box<dyn Iterator<Item = PathBuf>> =
+        match index.and_then(|index| index.candidates(&root, query)) {
+            Some(candidates) => Box::new(candidates.into_iter()),
+            // Walk directory respecting gitignore, skipping entries we can't read
+            None => Box::new(
+                builder
+                    .build()
+                    .filter_map(|result| result.ok())
+                    .map(|entry| entry.into_path()),
+            ),
+        };
 
-    // Walk directory respecting gitignore
-    for result in builder.build() {
+    for path in files {
         if matches.len() >= max_results {
             break;
         }
 
-        let entry = match result {
-            Ok(entry) => entry,
-            Err(_) => continue, // Skip entries we can't read
-        };
-
-        let path = entry.path();
+        let path = path.as_path();
 
         // Skip directories
         if path.is_dir() {
             continue;
         }
 
-        // Skip binary files (basic check)
-        if let Some(ext) = path.extension() {
-            let ext_str = ext.to_string_lossy().to_lowercase();
-            let binary_exts = [
-                "png", "jpg", "jpeg", "gif", "ico", "svg", "woff", "woff2", "ttf", "eot", "pdf",
-                "zip", "tar", "gz", "7z", "rar", "exe", "dll", "so", "dylib", "bin", "dat", "db",
-                "sqlite",
-            ];
-            if binary_exts.contains(&ext_str.as_str()) {
+        let relative = path.strip_prefix(&root).unwrap_or(path);
+        if let Some(include) = &include {
+            if !include.is_match(relative) {
+                continue;
+            }
+        }
+        if let Some(exclude) = &exclude {
+            if exclude.is_match(relative) {
                 continue;
             }
         }
 
+        if !seen_files.insert(path.to_path_buf()) {
+            continue;
+        }
+
+        // Skip binary files (basic check)
+        if is_binary_file(path) {
+            continue;
+        }
+
         total_files_searched += 1;
 
         // Read file and search for matches
@@ -387,6 +561,7 @@ pub fn search_files(
             Ok(f) => f,
             Err(_) => continue, // Skip files we can't read
         };
+        io.file_read(0);
 
         let reader = BufReader::new(file);
 
@@ -401,6 +576,7 @@ pub fn search_files(
                 Ok(l) => l,
                 Err(_) => continue, // Skip lines we can't read
             };
+            io.bytes_read(line.len() as u64 + 1);
 
             // Case-insensitive search
             if let Some(pos) = line.to_lowercase().find(&query_lower) {
@@ -408,6 +584,7 @@ pub fn search_files(
 
                 matches.push(SearchMatch {
                     file_path: path.to_string_lossy().replace('\\', "/"),
+                    root: root_attribution.clone(),
                     line_number,
                     line_content: line.clone(),
                     match_start: pos,
@@ -417,9 +594,72 @@ pub fn search_files(
         }
     }
 
-    Ok(SearchResult {
-        total_matches: matches.len(),
-        total_files_searched,
-        matches,
-    })
+    Ok(total_files_searched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_multiple_roots_with_per_root_globs() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-workspace-search-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let write = |rel: &str, content: &str| {
+            let path = dir.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("app/src/main.ts", "const needle = 1;");
+        write("app/src/main.css", "/* needle */");
+        write("app/generated/api.ts", "needle");
+        write("lib/.gitignore", "dist/\n");
+        write("lib/index.js", "export const NEEDLE = 2;");
+        write("lib/dist/index.js", "needle");
+
+        let root = |name: &str, include: &[&str], exclude: &[&str]| SearchRoot {
+            path: dir.join(name).to_string_lossy().to_string(),
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+        };
+        let result = run_search(
+            "needle".to_string(),
+            None,
+            Some(vec![
+                root("app", &["*.ts"], &["generated"]),
+                root("lib", &[], &[]),
+                // Overlapping roots must not report a file twice
+                root("lib", &[], &[]),
+            ]),
+            None,
+            None,
+            &PathFilterState::new(),
+        )
+        .unwrap();
+
+        let mut found: Vec<(String, String)> = result
+            .matches
+            .iter()
+            .map(|m| {
+                let root = m.root.rsplit('/').next().unwrap().to_string();
+                let file = m.file_path.strip_prefix(&m.root).unwrap().to_string();
+                (root, file)
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("app".to_string(), "/src/main.ts".to_string()),
+                ("lib".to_string(), "/index.js".to_string()),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,355 @@
+//! Completion Proxy
+//!
+//! Language servers like csharp-ls answer completion requests with every symbol
+//! in scope (often 10k+ items), which is too much to push through IPC on every
+//! keystroke. The proxy requests the list once per word, caches it per document,
+//! and fuzzy-filters and ranks it against the typed prefix in Rust, returning only
+//! the top items. Documentation and other details are fetched per item on demand
+//! with `completionItem/resolve`.
+
+use crate::languages::lsp_manager::{path_to_uri, request, LSPState};
+use crate::languages::lsp_requests::owning_server;
+use crate::languages::workspace_edit::LspPosition;
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Items returned when the caller doesn't ask for a specific number
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedCompletion {
+    /// Index into the cached list, for `lsp_completion_resolve`
+    pub index: usize,
+    pub score: i64,
+    /// The server's `CompletionItem`, unchanged
+    pub item: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionResult {
+    /// Identifies the cached list the indices refer to
+    pub generation: u64,
+    pub items: Vec<RankedCompletion>,
+    /// Items matching the prefix, before truncation to the limit
+    pub total: usize,
+    /// The server's list was incomplete or cut to the limit, so more typing may
+    /// match items not in this list; request again instead of filtering client-side
+    pub is_incomplete: bool,
+}
+
+/// A server completion list for one word of one document
+struct CachedList {
+    generation: u64,
+    line: u32,
+    /// UTF-16 character where the word being completed starts
+    word_start: u32,
+    incomplete: bool,
+    items: Vec<Value>,
+}
+
+/// Last completion list per document
+#[derive(Clone, Default)]
+pub struct CompletionState {
+    lists: Arc<Mutex<HashMap<String, CachedList>>>,
+    generation: Arc<Mutex<u64>>,
+}
+
+impl CompletionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next_generation(&self) -> u64 {
+        let mut generation = self.generation.lock().unwrap();
+        *generation += 1;
+        *generation
+    }
+}
+
+/// Whether the character at `index` of `candidate` starts a word (start, after a
+/// separator, or a camelCase hump)
+fn is_word_start(candidate: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let previous = candidate[index - 1];
+    let current = candidate[index];
+    !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase())
+}
+
+/// Score `candidate` against `pattern`, case-insensitively; `None` if the
+/// pattern isn't a subsequence. Prefix, word-start, consecutive, and exact-case
+/// matches score higher; gaps score lower.
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0i64;
+    let mut position = 0usize;
+    let mut previous_match: Option<usize> = None;
+
+    for pattern_char in pattern.chars() {
+        let lower = pattern_char.to_lowercase().next().unwrap_or(pattern_char);
+        let index = (position..candidate.len())
+            .find(|i| candidate[*i].to_lowercase().next().unwrap_or(candidate[*i]) == lower)?;
+
+        score += 1;
+        if index == 0 {
+            score += 8;
+        } else if is_word_start(&candidate, index) {
+            score += 5;
+        }
+        if candidate[index] == pattern_char {
+            score += 1;
+        }
+        match previous_match {
+            Some(previous) if index == previous + 1 => score += 4,
+            Some(previous) => score -= (index - previous - 1).min(5) as i64,
+            None => score -= index.min(5) as i64,
+        }
+        previous_match = Some(index);
+        position = index + 1;
+    }
+    if !pattern.is_empty() {
+        // Shorter candidates are closer matches
+        score -= ((candidate.len() - pattern.chars().count()) / 4).min(5) as i64;
+    }
+    Some(score)
+}
+
+/// Text an item is matched against
+fn filter_text(item: &Value) -> &str {
+    item.get("filterText")
+        .and_then(Value::as_str)
+        .or_else(|| item.get("label").and_then(Value::as_str))
+        .unwrap_or("")
+}
+
+/// Filter and rank `items` against `prefix`, best first
+fn rank(items: &[Value], prefix: &str) -> Vec<(usize, i64)> {
+    let mut ranked: Vec<(usize, i64)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| Some((index, fuzzy_score(prefix, filter_text(item))?)))
+        .collect();
+    let sort_text = |index: usize| {
+        let item = &items[index];
+        item.get("sortText")
+            .and_then(Value::as_str)
+            .unwrap_or_else(|| filter_text(item))
+    };
+    let preselected =
+        |index: usize| items[index].get("preselect").and_then(Value::as_bool) == Some(true);
+    ranked.sort_by(|a, b| {
+        (Reverse(a.1), !preselected(a.0), sort_text(a.0)).cmp(&(
+            Reverse(b.1),
+            !preselected(b.0),
+            sort_text(b.0),
+        ))
+    });
+    ranked
+}
+
+/// The first `limit` ranked items, with their indices into `items`
+fn top_items(items: &[Value], ranked: &[(usize, i64)], limit: usize) -> Vec<RankedCompletion> {
+    ranked
+        .iter()
+        .take(limit)
+        .map(|(index, score)| RankedCompletion {
+            index: *index,
+            score: *score,
+            item: items[*index].clone(),
+        })
+        .collect()
+}
+
+/// Items and completeness of a `textDocument/completion` result
+fn completion_items(result: Value) -> (Vec<Value>, bool) {
+    match result {
+        Value::Array(items) => (items, false),
+        Value::Object(mut list) => {
+            let incomplete = list
+                .get("isIncomplete")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            match list.remove("items") {
+                Some(Value::Array(items)) => (items, incomplete),
+                _ => (Vec::new(), incomplete),
+            }
+        }
+        _ => (Vec::new(), false),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Completions at a position, filtered and ranked against the typed prefix
+///
+/// The server is only asked again when the cursor moves to another word, a
+/// trigger character is typed, or its last list was incomplete.
+///
+/// # Arguments
+/// * `path` - File to complete in; must be open in the server
+/// * `position` - Cursor position (0-based line, UTF-16 character)
+/// * `prefix` - Word characters before the cursor that the items must match
+/// * `trigger_character` - Character that triggered completion, e.g. `.`
+/// * `limit` - Maximum number of items to return (default: 100)
+#[fluxel_command]
+pub async fn lsp_completion(
+    state: State<'_, LSPState>,
+    completion: State<'_, CompletionState>,
+    path: String,
+    position: LspPosition,
+    prefix: String,
+    trigger_character: Option<String>,
+    limit: Option<usize>,
+) -> Result<CompletionResult, String> {
+    let prefix_units = prefix.encode_utf16().count() as u32;
+    let word_start = position.character.saturating_sub(prefix_units);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let cached = {
+        let lists = completion.lists.lock().unwrap();
+        lists.get(&path).and_then(|list| {
+            let reusable = trigger_character.is_none()
+                && !list.incomplete
+                && list.line == position.line
+                && list.word_start == word_start;
+            reusable.then(|| {
+                let ranked = rank(&list.items, &prefix);
+                let items = top_items(&list.items, &ranked, limit);
+                (list.generation, ranked.len(), items, false)
+            })
+        })
+    };
+
+    let (generation, total, items, incomplete) = match cached {
+        Some(cached) => cached,
+        None => {
+            let server = owning_server(&state, &path)?;
+            let context = match &trigger_character {
+                Some(character) => json!({ "triggerKind": 2, "triggerCharacter": character }),
+                None => json!({ "triggerKind": 1 }),
+            };
+            let params = json!({
+                "textDocument": { "uri": path_to_uri(&path) },
+                "position": position,
+                "context": context,
+            });
+            let (server_items, incomplete) =
+                completion_items(request(server, "textDocument/completion", params).await?);
+
+            let ranked = rank(&server_items, &prefix);
+            let items = top_items(&server_items, &ranked, limit);
+            let generation = completion.next_generation();
+            completion.lists.lock().unwrap().insert(
+                path.clone(),
+                CachedList {
+                    generation,
+                    line: position.line,
+                    word_start,
+                    incomplete,
+                    items: server_items,
+                },
+            );
+            (generation, ranked.len(), items, incomplete)
+        }
+    };
+
+    Ok(CompletionResult {
+        generation,
+        is_incomplete: incomplete || total > items.len(),
+        total,
+        items,
+    })
+}
+
+/// Fetch the details of a completion item (documentation, edits) on demand
+///
+/// # Arguments
+/// * `path` - File the completion list belongs to
+/// * `generation` - `generation` of the list the item came from
+/// * `index` - `index` of the item
+#[fluxel_command]
+pub async fn lsp_completion_resolve(
+    state: State<'_, LSPState>,
+    completion: State<'_, CompletionState>,
+    path: String,
+    generation: u64,
+    index: usize,
+) -> Result<Value, String> {
+    let item = {
+        let lists = completion.lists.lock().unwrap();
+        let list = lists
+            .get(&path)
+            .filter(|list| list.generation == generation)
+            .ok_or("Completion list is out of date")?;
+        list.items
+            .get(index)
+            .cloned()
+            .ok_or("Unknown completion item")?
+    };
+
+    let server = owning_server(&state, &path)?;
+    let resolved = request(server, "completionItem/resolve", item).await?;
+
+    // Later filtering returns the resolved item, so details aren't fetched twice
+    let mut lists = completion.lists.lock().unwrap();
+    if let Some(list) = lists
+        .get_mut(&path)
+        .filter(|list| list.generation == generation)
+    {
+        list.items[index] = resolved.clone();
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_prefix_and_word_start_matches_first() {
+        let items: Vec<Value> = [
+            ("ToString", "1"),
+            ("GetHashCode", "2"),
+            ("GetType", "3"),
+            ("getter", "4"),
+            ("Equals", "5"),
+        ]
+        .iter()
+        .map(|(label, sort)| json!({ "label": label, "sortText": sort }))
+        .collect();
+
+        let labels = |prefix: &str| -> Vec<String> {
+            rank(&items, prefix)
+                .iter()
+                .map(|(index, _)| items[*index]["label"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(labels("GetT"), vec!["GetType", "getter"]);
+        assert_eq!(labels("ghc"), vec!["GetHashCode"]);
+        assert_eq!(labels("get")[..2], ["getter", "GetType"]);
+        // Without a prefix the server's sort order is kept
+        assert_eq!(labels("").len(), 5);
+        assert_eq!(labels("")[0], "ToString");
+        assert!(fuzzy_score("xyz", "ToString").is_none());
+    }
+
+    #[test]
+    fn reads_list_and_array_results() {
+        let (items, incomplete) =
+            completion_items(json!({ "isIncomplete": true, "items": [{ "label": "a" }] }));
+        assert_eq!((items.len(), incomplete), (1, true));
+        let (items, incomplete) = completion_items(json!([{ "label": "a" }, { "label": "b" }]));
+        assert_eq!((items.len(), incomplete), (2, false));
+        assert!(completion_items(Value::Null).0.is_empty());
+    }
+}
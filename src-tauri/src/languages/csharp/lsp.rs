@@ -3,29 +3,41 @@
 //! This module provides Tauri commands for managing the C# language server (csharp-ls).
 //! It uses the generic LSPManager from the lsp_manager module.
 
+use fluxel_macros::fluxel_command;
 use std::path::PathBuf;
 
+use crate::languages::csharp::solutions;
 use crate::languages::lsp_manager::{
     check_csharp_ls_installed, get_path_with_dotnet_tools, install_csharp_ls, LSPServerConfig,
     LSPState,
 };
+use crate::languages::watched_files;
+use crate::services::FsWatcherService;
 
 /// Start the C# language server (csharp-ls)
 ///
 /// This command will:
 /// 1. Check if csharp-ls is installed, and install it if not
-/// 2. Find a .sln or .csproj file in the workspace
-/// 3. Start the language server with appropriate configuration
-#[tauri::command]
+/// 2. Start the language server with appropriate configuration
+/// 3. Watch the workspace for changes to the solution it loads
+///
+/// # Arguments
+/// * `workspace_root` - Workspace folder, used as the working directory
+/// * `configuration` - Build configuration (e.g. Debug) for MSBuild evaluation
+/// * `solution` - Solution or project chosen for the workspace; changes to it
+///   are reported as `csharp://solution-changed`
+#[fluxel_command]
 pub async fn start_csharp_ls(
     state: tauri::State<'_, LSPState>,
+    watcher: tauri::State<'_, FsWatcherService>,
     window: tauri::Window,
     workspace_root: Option<String>,
     configuration: Option<String>,
+    solution: Option<String>,
 ) -> Result<(), String> {
     println!(
-        "[Tauri:csharp] start_csharp_ls called with workspace: {:?}, configuration: {:?}",
-        workspace_root, configuration
+        "[Tauri:csharp] start_csharp_ls called with workspace: {:?}, configuration: {:?}, solution: {:?}",
+        workspace_root, configuration, solution
     );
 
     // Check if csharp-ls is installed
@@ -96,17 +108,31 @@ pub async fn start_csharp_ls(
         command: "csharp-ls".to_string(),
         args,
         env,
-        working_dir,
+        working_dir: working_dir.clone(),
         event_name: "lsp-message".to_string(),
     };
 
     // Start the language server
     let mut manager = state.manager.lock().await;
-    manager.start_with_config(window, config).await
+    manager.start_with_config(window.clone(), config).await?;
+
+    // Forward changes to files the server registers watchers for
+    if let Some(root) = working_dir {
+        let relay = watched_files::start_relay(
+            &watcher,
+            state.manager.clone(),
+            manager.watch_registrations(),
+            &root,
+        )?;
+        manager.attach_task(relay);
+        let solution_watch = solutions::start_solution_watch(&watcher, window, &root, solution)?;
+        manager.attach_task(solution_watch);
+    }
+    Ok(())
 }
 
 /// Stop the C# language server
-#[tauri::command]
+#[fluxel_command]
 pub async fn stop_csharp_ls(state: tauri::State<'_, LSPState>) -> Result<(), String> {
     println!("[Tauri:csharp] stop_csharp_ls called");
     let mut manager = state.manager.lock().await;
@@ -114,7 +140,7 @@ pub async fn stop_csharp_ls(state: tauri::State<'_, LSPState>) -> Result<(), Str
 }
 
 /// Send an LSP message to the C# language server
-#[tauri::command]
+#[fluxel_command]
 pub async fn send_lsp_message(
     state: tauri::State<'_, LSPState>,
     message: String,
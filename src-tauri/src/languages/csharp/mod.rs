@@ -3,6 +3,8 @@
 //! This module provides C# language support including:
 //! - LSP integration (csharp-ls)
 //! - Project file parsing (.csproj)
+//! - Solution selection and change detection
 
 pub mod lsp;
 pub mod parser;
+pub mod solutions;
@@ -0,0 +1,192 @@
+//! C# Solution Selection
+//!
+//! Workspaces may contain several solutions. The frontend lists them, remembers
+//! the chosen one per workspace, and passes it to `start_csharp_ls`. While the
+//! server runs, a watcher reports `csharp://solution-changed` when the chosen
+//! solution changes or the set of solutions/projects on disk does (e.g. after a
+//! branch switch), so the frontend can restart the server.
+
+use crate::languages::lsp_manager::find_solution_candidates;
+use crate::services::fs_watcher::{FsChangeKind, FsWatcherService};
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::task::JoinHandle;
+
+pub const SOLUTION_CHANGED_EVENT: &str = "csharp://solution-changed";
+
+/// Branch switches touch many files at once; wait for them to settle
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionChanged {
+    pub workspace_root: String,
+    /// Solution and project files that changed, normalized to forward slashes
+    pub paths: Vec<String>,
+}
+
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Whether a change to `path` affects the loaded solution: the chosen solution
+/// was edited or removed, or a solution/project was added or removed. Build
+/// output below `bin`/`obj` is ignored.
+fn is_solution_change(path: &str, kind: FsChangeKind, solution: Option<&str>) -> bool {
+    if path.split('/').any(|part| part == "bin" || part == "obj") {
+        return false;
+    }
+    let Some(extension) = Path::new(path).extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    let is_solution = matches!(extension, "sln" | "slnx");
+    if !is_solution && extension != "csproj" {
+        return false;
+    }
+    // Renames arrive as modifications of a path that no longer exists
+    let exists = Path::new(path).exists();
+    match kind {
+        FsChangeKind::Created | FsChangeKind::Removed => true,
+        FsChangeKind::Modified | FsChangeKind::Other if !exists => true,
+        FsChangeKind::Modified | FsChangeKind::Other => {
+            is_solution && solution.is_none_or(|solution| solution == path)
+        }
+    }
+}
+
+/// Watch `root` for solution changes until aborted
+pub fn start_solution_watch(
+    watcher: &FsWatcherService,
+    window: tauri::Window,
+    root: &Path,
+    solution: Option<String>,
+) -> Result<JoinHandle<()>, String> {
+    let mut subscription = watcher.subscribe(root)?;
+    let workspace_root = normalize(root);
+    let solution = solution.map(|solution| normalize(Path::new(&solution)));
+
+    Ok(tokio::spawn(async move {
+        while let Some(first) = subscription.recv().await {
+            let mut changed = BTreeSet::new();
+            let mut pending = Some(first);
+            while let Some(event) = pending {
+                changed.extend(
+                    event
+                        .paths
+                        .into_iter()
+                        .filter(|path| is_solution_change(path, event.kind, solution.as_deref())),
+                );
+                // Keep waiting only once something relevant happened
+                pending = if changed.is_empty() {
+                    None
+                } else {
+                    tokio::time::timeout(DEBOUNCE, subscription.recv())
+                        .await
+                        .ok()
+                        .flatten()
+                };
+            }
+            if changed.is_empty() {
+                continue;
+            }
+
+            println!(
+                "[Tauri:csharp] Solution files changed, notifying frontend: {:?}",
+                changed
+            );
+            let _ = window.emit(
+                SOLUTION_CHANGED_EVENT,
+                SolutionChanged {
+                    workspace_root: workspace_root.clone(),
+                    paths: changed.into_iter().collect(),
+                },
+            );
+        }
+    }))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the solutions in a workspace, or its projects when it has none
+///
+/// # Arguments
+/// * `workspace_root` - Workspace to search (three levels deep)
+#[fluxel_command]
+pub async fn list_csharp_solutions(workspace_root: String) -> Result<Vec<String>, String> {
+    let root = PathBuf::from(workspace_root);
+    if !root.is_dir() {
+        return Err(format!("Workspace not found: {}", root.display()));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        find_solution_candidates(&root)
+            .iter()
+            .map(|path| normalize(path))
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn detects_solution_changes() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-solutions-{}", nanos));
+        fs::create_dir_all(&root).unwrap();
+        let app = normalize(&root.join("App.sln"));
+        let tests = normalize(&root.join("Tests.sln"));
+        let project = normalize(&root.join("App/App.csproj"));
+        fs::write(&app, "").unwrap();
+        fs::write(&tests, "").unwrap();
+        let selected = Some(app.as_str());
+
+        assert!(is_solution_change(&app, FsChangeKind::Modified, selected));
+        assert!(!is_solution_change(
+            &tests,
+            FsChangeKind::Modified,
+            selected
+        ));
+        assert!(is_solution_change(&tests, FsChangeKind::Modified, None));
+        assert!(is_solution_change(&tests, FsChangeKind::Removed, selected));
+        assert!(is_solution_change(
+            &project,
+            FsChangeKind::Created,
+            selected
+        ));
+        // A project renamed away shows up as a modification of a missing file
+        assert!(is_solution_change(
+            &project,
+            FsChangeKind::Modified,
+            selected
+        ));
+
+        let generated = normalize(&root.join("App/obj/App.csproj"));
+        assert!(!is_solution_change(
+            &generated,
+            FsChangeKind::Created,
+            selected
+        ));
+        let source = normalize(&root.join("App/Program.cs"));
+        assert!(!is_solution_change(
+            &source,
+            FsChangeKind::Created,
+            selected
+        ));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
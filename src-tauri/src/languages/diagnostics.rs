@@ -0,0 +1,520 @@
+//! Diagnostics Store
+//!
+//! Collects diagnostics from every producer — `textDocument/publishDiagnostics`
+//! from running language servers, build output, and linters run by the frontend —
+//! into one store the problems panel reads from.
+//!
+//! Each producer ("provider", e.g. `lsp:csharp-ls` or `build`) owns its entries
+//! and replaces them wholesale, so stale results never linger. Queries group
+//! diagnostics by file and drop duplicates reported by more than one provider
+//! (a compiler error seen by both the language server and the build), keeping the
+//! language server's copy since it has the precise range.
+//!
+//! Every change is announced as `diagnostics://changed`.
+
+use crate::languages::lsp_manager::uri_to_path;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Runtime, State};
+
+/// Event emitted after any provider's diagnostics change
+pub const DIAGNOSTICS_CHANGED_EVENT: &str = "diagnostics://changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl DiagnosticSeverity {
+    /// LSP `DiagnosticSeverity` (1 = error … 4 = hint); servers may omit it
+    fn from_lsp(value: Option<u64>) -> Self {
+        match value {
+            Some(2) => DiagnosticSeverity::Warning,
+            Some(3) => DiagnosticSeverity::Info,
+            Some(4) => DiagnosticSeverity::Hint,
+            _ => DiagnosticSeverity::Error,
+        }
+    }
+
+    /// Severity names used by build and lint tools
+    pub fn parse(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "warning" | "warn" => DiagnosticSeverity::Warning,
+            "info" | "information" | "note" => DiagnosticSeverity::Info,
+            "hint" | "suggestion" => DiagnosticSeverity::Hint,
+            _ => DiagnosticSeverity::Error,
+        }
+    }
+}
+
+/// 1-based, end-exclusive range (Monaco marker convention)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticRange {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub path: String,
+    pub range: DiagnosticRange,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Tool that reported it, e.g. `csharp-ls`, `msbuild`, or `eslint`
+    #[serde(default)]
+    pub source: String,
+    /// Store slot it came from, e.g. `lsp:csharp-ls` or `build`
+    #[serde(default)]
+    pub provider: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiagnosticsFilter {
+    /// Least severe level to include (`"warning"` includes errors and warnings)
+    pub severity: Option<DiagnosticSeverity>,
+    /// Provider (`build`, `lsp:csharp-ls`, or a provider prefix like `lsp`) or tool name
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiagnostics {
+    pub path: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsChanged {
+    pub provider: String,
+    /// Files whose diagnostics from `provider` changed
+    pub paths: Vec<String>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Comparable form of a path: forward slashes, and case-insensitive on Windows
+fn path_key(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if cfg!(windows) {
+        path.to_lowercase()
+    } else {
+        path
+    }
+}
+
+#[derive(Default)]
+struct DiagnosticsStore {
+    /// provider → path key → diagnostics
+    providers: HashMap<String, HashMap<String, Vec<Diagnostic>>>,
+}
+
+impl DiagnosticsStore {
+    /// Replace `provider`'s diagnostics for `path`; returns whether anything changed
+    fn set_file(&mut self, provider: &str, path: &str, diagnostics: Vec<Diagnostic>) -> bool {
+        let files = self.providers.entry(provider.to_string()).or_default();
+        let key = path_key(path);
+        if diagnostics.is_empty() {
+            files.remove(&key).is_some()
+        } else {
+            files.insert(key, diagnostics);
+            true
+        }
+    }
+
+    /// Replace all of `provider`'s diagnostics; returns the paths that changed
+    fn set_provider(&mut self, provider: &str, diagnostics: Vec<Diagnostic>) -> Vec<String> {
+        let mut files: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        let mut paths: BTreeMap<String, String> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            let key = path_key(&diagnostic.path);
+            paths.insert(key.clone(), diagnostic.path.clone());
+            files.entry(key).or_default().push(diagnostic);
+        }
+        let previous = self
+            .providers
+            .insert(provider.to_string(), files)
+            .unwrap_or_default();
+        for (key, old) in previous {
+            let path = old.first().map(|d| d.path.clone()).unwrap_or_default();
+            paths.entry(key).or_insert(path);
+        }
+        paths.into_values().collect()
+    }
+
+    fn query(&self, filter: &DiagnosticsFilter) -> Vec<FileDiagnostics> {
+        let source = filter.source.as_deref();
+        let matches_source = |provider: &str, diagnostic: &Diagnostic| match source {
+            None => true,
+            Some(source) => {
+                provider == source
+                    || provider.starts_with(&format!("{}:", source))
+                    || diagnostic.source.eq_ignore_ascii_case(source)
+            }
+        };
+
+        // Language servers first, so their precise ranges win deduplication
+        let mut providers: Vec<&String> = self.providers.keys().collect();
+        providers.sort_by_key(|provider| (!provider.starts_with("lsp:"), provider.as_str()));
+
+        let mut files: BTreeMap<String, FileDiagnostics> = BTreeMap::new();
+        let mut seen: HashSet<(String, u32, u32, DiagnosticSeverity, String)> = HashSet::new();
+        for provider in providers {
+            for (key, diagnostics) in &self.providers[provider] {
+                for diagnostic in diagnostics {
+                    if filter.severity.is_some_and(|min| diagnostic.severity > min)
+                        || !matches_source(provider, diagnostic)
+                    {
+                        continue;
+                    }
+                    let identity = (
+                        key.clone(),
+                        diagnostic.range.start_line,
+                        diagnostic.range.start_column,
+                        diagnostic.severity,
+                        diagnostic.message.trim().to_string(),
+                    );
+                    if !seen.insert(identity) {
+                        continue;
+                    }
+                    let file = files.entry(key.clone()).or_insert_with(|| FileDiagnostics {
+                        path: diagnostic.path.clone(),
+                        errors: 0,
+                        warnings: 0,
+                        diagnostics: Vec::new(),
+                    });
+                    match diagnostic.severity {
+                        DiagnosticSeverity::Error => file.errors += 1,
+                        DiagnosticSeverity::Warning => file.warnings += 1,
+                        _ => {}
+                    }
+                    file.diagnostics.push(diagnostic.clone());
+                }
+            }
+        }
+
+        let mut files: Vec<FileDiagnostics> = files.into_values().collect();
+        for file in &mut files {
+            file.diagnostics
+                .sort_by_key(|d| (d.range.start_line, d.range.start_column, d.severity));
+        }
+        files
+    }
+
+    fn totals(&self) -> (usize, usize) {
+        self.query(&DiagnosticsFilter::default())
+            .iter()
+            .fold((0, 0), |(errors, warnings), file| {
+                (errors + file.errors, warnings + file.warnings)
+            })
+    }
+}
+
+/// Shared diagnostics store
+#[derive(Clone, Default)]
+pub struct DiagnosticsState {
+    store: Arc<Mutex<DiagnosticsStore>>,
+}
+
+impl DiagnosticsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn changed<R: Runtime>(&self, emitter: &impl Emitter<R>, provider: &str, paths: Vec<String>) {
+        if paths.is_empty() {
+            return;
+        }
+        let (errors, warnings) = self.store.lock().unwrap().totals();
+        let _ = emitter.emit(
+            DIAGNOSTICS_CHANGED_EVENT,
+            DiagnosticsChanged {
+                provider: provider.to_string(),
+                paths,
+                errors,
+                warnings,
+            },
+        );
+    }
+
+    /// Replace `provider`'s diagnostics for one file (an empty list clears them)
+    pub fn set_file<R: Runtime>(
+        &self,
+        emitter: &impl Emitter<R>,
+        provider: &str,
+        path: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        let changed = self
+            .store
+            .lock()
+            .unwrap()
+            .set_file(provider, path, diagnostics);
+        if changed {
+            self.changed(emitter, provider, vec![path.to_string()]);
+        }
+    }
+
+    /// Replace all of `provider`'s diagnostics, e.g. with the results of a new build
+    pub fn set_provider<R: Runtime>(
+        &self,
+        emitter: &impl Emitter<R>,
+        provider: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        let paths = self
+            .store
+            .lock()
+            .unwrap()
+            .set_provider(provider, diagnostics);
+        self.changed(emitter, provider, paths);
+    }
+
+    /// Ingest an LSP message from `server`, if it is `textDocument/publishDiagnostics`
+    pub fn ingest_lsp_message<R: Runtime>(
+        &self,
+        emitter: &impl Emitter<R>,
+        server: &str,
+        message: &Value,
+    ) {
+        if message.get("method").and_then(Value::as_str) != Some("textDocument/publishDiagnostics")
+        {
+            return;
+        }
+        let Some(params) = message.get("params") else {
+            return;
+        };
+        let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+            return;
+        };
+        let path = uri_to_path(uri);
+        let diagnostics = params
+            .get("diagnostics")
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| lsp_diagnostic(item, &path, server))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.set_file(emitter, &format!("lsp:{}", server), &path, diagnostics);
+    }
+}
+
+/// Convert an LSP `Diagnostic` (0-based positions) to the store's form
+fn lsp_diagnostic(item: &Value, path: &str, server: &str) -> Option<Diagnostic> {
+    let range = item.get("range")?;
+    let position = |key: &str| {
+        let position = range.get(key)?;
+        Some((
+            position.get("line")?.as_u64()? as u32 + 1,
+            position.get("character")?.as_u64()? as u32 + 1,
+        ))
+    };
+    let (start_line, start_column) = position("start")?;
+    let (end_line, end_column) = position("end").unwrap_or((start_line, start_column));
+    let code = match item.get("code") {
+        Some(Value::String(code)) => Some(code.clone()),
+        Some(Value::Number(code)) => Some(code.to_string()),
+        _ => None,
+    };
+    Some(Diagnostic {
+        path: path.to_string(),
+        range: DiagnosticRange {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        },
+        severity: DiagnosticSeverity::from_lsp(item.get("severity").and_then(Value::as_u64)),
+        message: item.get("message")?.as_str()?.to_string(),
+        code,
+        source: item
+            .get("source")
+            .and_then(Value::as_str)
+            .unwrap_or(server)
+            .to_string(),
+        provider: format!("lsp:{}", server),
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// All diagnostics, grouped by file and deduplicated across providers
+///
+/// # Arguments
+/// * `filter` - `severity` to include only that level and worse; `source` to
+///   include only one provider or tool
+#[fluxel_command]
+pub fn get_all_diagnostics(
+    filter: Option<DiagnosticsFilter>,
+    state: State<'_, DiagnosticsState>,
+) -> Vec<FileDiagnostics> {
+    state
+        .store
+        .lock()
+        .unwrap()
+        .query(&filter.unwrap_or_default())
+}
+
+/// Publish diagnostics produced by the frontend (e.g. a linter or a language
+/// service running in the editor)
+///
+/// # Arguments
+/// * `provider` - Slot the diagnostics belong to, e.g. `lint:eslint`
+/// * `path` - File the diagnostics belong to; without it, all of the provider's
+///   diagnostics are replaced
+/// * `diagnostics` - New diagnostics (an empty list clears them)
+#[fluxel_command]
+pub fn publish_diagnostics<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    provider: String,
+    path: Option<String>,
+    diagnostics: Vec<Diagnostic>,
+    state: State<'_, DiagnosticsState>,
+) {
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|mut diagnostic| {
+            diagnostic.provider = provider.clone();
+            if let Some(path) = &path {
+                diagnostic.path = path.clone();
+            }
+            diagnostic
+        })
+        .collect();
+    match path {
+        Some(path) => state.set_file(&app, &provider, &path, diagnostics),
+        None => state.set_provider(&app, &provider, diagnostics),
+    }
+}
+
+/// Remove every diagnostic from `provider`
+#[fluxel_command]
+pub fn clear_diagnostics<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    provider: String,
+    state: State<'_, DiagnosticsState>,
+) {
+    state.set_provider(&app, &provider, Vec::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(
+        path: &str,
+        line: u32,
+        severity: DiagnosticSeverity,
+        provider: &str,
+    ) -> Diagnostic {
+        Diagnostic {
+            path: path.to_string(),
+            range: DiagnosticRange {
+                start_line: line,
+                start_column: 5,
+                end_line: line,
+                end_column: 5,
+            },
+            severity,
+            message: "; expected".to_string(),
+            code: Some("CS1002".to_string()),
+            source: "msbuild".to_string(),
+            provider: provider.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_deduplicates_and_filters() {
+        let mut store = DiagnosticsStore::default();
+        let message = serde_json::json!({
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": "file:///repo/My%20App/Program.cs",
+                "diagnostics": [{
+                    "range": {
+                        "start": { "line": 9, "character": 4 },
+                        "end": { "line": 9, "character": 10 }
+                    },
+                    "severity": 1,
+                    "code": "CS1002",
+                    "message": "; expected"
+                }]
+            }
+        });
+        let params = &message["params"];
+        let path = uri_to_path(params["uri"].as_str().unwrap());
+        assert_eq!(path, "/repo/My App/Program.cs");
+        let lsp = lsp_diagnostic(&params["diagnostics"][0], &path, "csharp-ls").unwrap();
+        assert_eq!((lsp.range.start_line, lsp.range.end_column), (10, 11));
+        assert!(store.set_file("lsp:csharp-ls", &path, vec![lsp]));
+
+        // The build reports the same error plus a warning elsewhere
+        let build = vec![
+            diagnostic(&path, 10, DiagnosticSeverity::Error, "build"),
+            diagnostic(
+                "/repo/My App/Util.cs",
+                3,
+                DiagnosticSeverity::Warning,
+                "build",
+            ),
+        ];
+        assert_eq!(store.set_provider("build", build).len(), 2);
+
+        let all = store.query(&DiagnosticsFilter::default());
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].path, "/repo/My App/Program.cs");
+        assert_eq!(all[0].diagnostics.len(), 1);
+        assert_eq!(all[0].diagnostics[0].provider, "lsp:csharp-ls");
+        assert_eq!(store.totals(), (1, 1));
+
+        let errors_only = DiagnosticsFilter {
+            severity: Some(DiagnosticSeverity::Error),
+            source: None,
+        };
+        assert_eq!(store.query(&errors_only).len(), 1);
+        let build_only = DiagnosticsFilter {
+            severity: None,
+            source: Some("build".to_string()),
+        };
+        let build = store.query(&build_only);
+        assert_eq!(build.len(), 2);
+        assert_eq!(build[0].diagnostics[0].provider, "build");
+
+        // A new build with no problems clears the old results
+        let cleared = store.set_provider("build", Vec::new());
+        assert_eq!(cleared.len(), 2);
+        assert_eq!(store.query(&build_only).len(), 0);
+        assert!(store.set_file("lsp:csharp-ls", &path, Vec::new()));
+        assert!(store.query(&DiagnosticsFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn converts_windows_file_uris() {
+        assert_eq!(uri_to_path("file:///C:/src/a%2Bb.cs"), "C:\\src\\a+b.cs");
+        assert_eq!(
+            DiagnosticSeverity::parse("warning"),
+            DiagnosticSeverity::Warning
+        );
+    }
+}
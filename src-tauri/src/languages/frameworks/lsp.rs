@@ -0,0 +1,111 @@
+//! Framework Language Server Commands
+//!
+//! Tauri commands for starting, stopping, and messaging the Vue, Svelte, and
+//! Astro language servers. Each server emits its messages on its own
+//! `lsp-message:<language>` event so several can run alongside csharp-ls.
+
+use fluxel_macros::fluxel_command;
+use std::path::PathBuf;
+
+use crate::languages::frameworks::servers::{self, FrameworkServerInfo};
+use crate::languages::lsp_manager::{LSPServerConfig, LSPState};
+use crate::languages::watched_files;
+use crate::services::FsWatcherService;
+
+fn workspace_dir(workspace_root: &str) -> Result<PathBuf, String> {
+    let root = PathBuf::from(workspace_root);
+    if root.is_dir() {
+        Ok(root)
+    } else {
+        Err(format!("Workspace not found: {}", workspace_root))
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Find the language server and TypeScript SDK for a framework
+///
+/// # Arguments
+/// * `language` - `vue`, `svelte`, or `astro`
+/// * `workspace_root` - Project whose `node_modules` is searched first
+#[fluxel_command]
+pub async fn resolve_framework_ls(
+    language: String,
+    workspace_root: String,
+) -> Result<FrameworkServerInfo, String> {
+    let root = workspace_dir(&workspace_root)?;
+    tauri::async_runtime::spawn_blocking(move || servers::resolve(&language, &root))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Start the language server for a framework
+///
+/// # Arguments
+/// * `language` - `vue`, `svelte`, or `astro`
+/// * `workspace_root` - Workspace folder, used as the working directory
+#[fluxel_command]
+pub async fn start_framework_ls(
+    state: tauri::State<'_, LSPState>,
+    watcher: tauri::State<'_, FsWatcherService>,
+    window: tauri::Window,
+    language: String,
+    workspace_root: String,
+) -> Result<FrameworkServerInfo, String> {
+    let root = workspace_dir(&workspace_root)?;
+    let info = {
+        let (language, root) = (language.clone(), root.clone());
+        tauri::async_runtime::spawn_blocking(move || servers::resolve(&language, &root))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    println!(
+        "[Frameworks] Starting {} language server from {:?}: {}",
+        language, info.source, info.command
+    );
+
+    let config = LSPServerConfig {
+        command: info.command.clone(),
+        args: vec!["--stdio".to_string()],
+        env: Vec::new(),
+        working_dir: Some(root.clone()),
+        event_name: format!("lsp-message:{}", language),
+    };
+
+    let server = state.framework(&language)?;
+    let mut manager = server.lock().await;
+    manager.start_with_config(window, config).await?;
+
+    let relay = watched_files::start_relay(
+        &watcher,
+        server.clone(),
+        manager.watch_registrations(),
+        &root,
+    )?;
+    manager.attach_task(relay);
+    Ok(info)
+}
+
+/// Stop the language server for a framework
+#[fluxel_command]
+pub async fn stop_framework_ls(
+    state: tauri::State<'_, LSPState>,
+    language: String,
+) -> Result<(), String> {
+    println!("[Frameworks] Stopping {} language server", language);
+    let mut manager = state.framework(&language)?.lock().await;
+    manager.stop().await
+}
+
+/// Send an LSP message to a framework language server
+#[fluxel_command]
+pub async fn send_framework_lsp_message(
+    state: tauri::State<'_, LSPState>,
+    language: String,
+    message: String,
+) -> Result<(), String> {
+    let mut manager = state.framework(&language)?.lock().await;
+    manager.send_message(message).await
+}
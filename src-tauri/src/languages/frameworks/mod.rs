@@ -0,0 +1,13 @@
+//! Web Framework Language Support Module
+//!
+//! This module launches the language servers for framework single-file
+//! components:
+//! - Vue (`@vue/language-server`)
+//! - Svelte (`svelte-language-server`)
+//! - Astro (`@astrojs/language-server`)
+//!
+//! Servers are resolved from the project's `node_modules` first so they match
+//! the framework version in use, then from the PATH.
+
+pub mod lsp;
+pub mod servers;
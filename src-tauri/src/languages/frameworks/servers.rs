@@ -0,0 +1,218 @@
+//! Framework Language Server Resolution
+//!
+//! Finds the server binary and the TypeScript SDK each framework server needs.
+//! The Vue and Astro servers embed TypeScript through its server library and
+//! must be told where it lives; the project's own `typescript` is preferred so
+//! diagnostics match `tsc`, with the copy installed next to the server as a
+//! fallback.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// A framework language server Fluxel knows how to launch
+pub struct FrameworkServer {
+    /// Language id, as used by Monaco
+    pub language: &'static str,
+    /// npm package providing the server
+    pub package: &'static str,
+    /// Executable the package installs
+    pub binary: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+pub const FRAMEWORK_SERVERS: &[FrameworkServer] = &[
+    FrameworkServer {
+        language: "vue",
+        package: "@vue/language-server",
+        binary: "vue-language-server",
+        extensions: &["vue"],
+    },
+    FrameworkServer {
+        language: "svelte",
+        package: "svelte-language-server",
+        binary: "svelteserver",
+        extensions: &["svelte"],
+    },
+    FrameworkServer {
+        language: "astro",
+        package: "@astrojs/language-server",
+        binary: "astro-ls",
+        extensions: &["astro"],
+    },
+];
+
+/// The framework server for a language id
+pub fn framework_server(language: &str) -> Result<&'static FrameworkServer, String> {
+    FRAMEWORK_SERVERS
+        .iter()
+        .find(|server| server.language == language)
+        .ok_or_else(|| format!("No framework language server for {}", language))
+}
+
+/// The framework server handling files with `extension`
+pub fn framework_for_extension(extension: &str) -> Option<&'static FrameworkServer> {
+    FRAMEWORK_SERVERS
+        .iter()
+        .find(|server| server.extensions.contains(&extension))
+}
+
+/// Where a server binary was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerSource {
+    Project,
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkServerInfo {
+    pub language: String,
+    pub command: String,
+    pub source: ServerSource,
+    /// `lib` directory of the TypeScript the server should load
+    pub typescript_sdk: Option<String>,
+    /// Options for the `initialize` request; user settings are merged on top
+    pub initialization_options: Value,
+}
+
+fn executable_names(binary: &str) -> Vec<String> {
+    if cfg!(windows) {
+        vec![format!("{}.cmd", binary), format!("{}.exe", binary)]
+    } else {
+        vec![binary.to_string()]
+    }
+}
+
+/// The server binary from the project's `node_modules/.bin`, then the PATH
+fn find_binary(root: &Path, binary: &str) -> Option<(PathBuf, ServerSource)> {
+    let names = executable_names(binary);
+    let local_bin = root.join("node_modules").join(".bin");
+    if let Some(path) = names
+        .iter()
+        .map(|name| local_bin.join(name))
+        .find(|path| path.is_file())
+    {
+        return Some((path, ServerSource::Project));
+    }
+
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+        .map(|path| (path, ServerSource::Global))
+}
+
+fn is_typescript_lib(dir: &Path) -> bool {
+    dir.join("tsserverlibrary.js").is_file() || dir.join("typescript.js").is_file()
+}
+
+/// The project's TypeScript, or the one installed with the server package
+fn find_typescript_sdk(root: &Path, package: &str, binary: &Path) -> Option<PathBuf> {
+    let project = root.join("node_modules").join("typescript").join("lib");
+    if is_typescript_lib(&project) {
+        return Some(project);
+    }
+
+    // Global installs put the binary (or a link to it) next to or under the
+    // prefix holding `node_modules`
+    let binary = binary
+        .canonicalize()
+        .unwrap_or_else(|_| binary.to_path_buf());
+    binary.ancestors().skip(1).find_map(|dir| {
+        let modules = [
+            dir.join("node_modules"),
+            dir.join("lib").join("node_modules"),
+        ];
+        modules.iter().find_map(|modules| {
+            [
+                modules.join(package).join("node_modules"),
+                modules.to_path_buf(),
+            ]
+            .into_iter()
+            .map(|modules| modules.join("typescript").join("lib"))
+            .find(|lib| is_typescript_lib(lib))
+        })
+    })
+}
+
+/// Initialization options carrying the TypeScript setup each server expects
+fn initialization_options(language: &str, typescript_sdk: Option<&str>) -> Value {
+    match language {
+        // Without a tsserver running the Vue TypeScript plugin, the server
+        // must handle script blocks itself
+        "vue" => json!({
+            "typescript": { "tsdk": typescript_sdk },
+            "vue": { "hybridMode": false },
+        }),
+        "astro" => json!({ "typescript": { "tsdk": typescript_sdk } }),
+        // svelteserver loads TypeScript from the workspace on its own
+        _ => json!({}),
+    }
+}
+
+/// Resolve the server for `language` in the workspace at `root`
+pub fn resolve(language: &str, root: &Path) -> Result<FrameworkServerInfo, String> {
+    let server = framework_server(language)?;
+    let (command, source) = find_binary(root, server.binary).ok_or_else(|| {
+        format!(
+            "{} not found. Install it in the project (npm install -D {}) or globally (npm install -g {})",
+            server.binary, server.package, server.package
+        )
+    })?;
+    let typescript_sdk = find_typescript_sdk(root, server.package, &command)
+        .map(|lib| lib.to_string_lossy().replace('\\', "/"));
+    if typescript_sdk.is_none() && language != "svelte" {
+        println!(
+            "[Frameworks] No TypeScript found for {}; script diagnostics may be missing",
+            server.binary
+        );
+    }
+
+    Ok(FrameworkServerInfo {
+        language: language.to_string(),
+        command: command.to_string_lossy().to_string(),
+        source,
+        initialization_options: initialization_options(language, typescript_sdk.as_deref()),
+        typescript_sdk,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn resolves_servers_from_project_node_modules() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-frameworks-{}", nanos));
+        let bin = root.join("node_modules").join(".bin");
+        let typescript = root.join("node_modules").join("typescript").join("lib");
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(&typescript).unwrap();
+        for name in executable_names("vue-language-server") {
+            fs::write(bin.join(name), "").unwrap();
+        }
+        fs::write(typescript.join("tsserverlibrary.js"), "").unwrap();
+
+        let info = resolve("vue", &root).unwrap();
+        assert_eq!(info.source, ServerSource::Project);
+        assert!(info.command.contains("vue-language-server"));
+        let tsdk = typescript.to_string_lossy().replace('\\', "/");
+        assert_eq!(info.typescript_sdk.as_deref(), Some(tsdk.as_str()));
+        assert_eq!(info.initialization_options["typescript"]["tsdk"], tsdk);
+        assert_eq!(info.initialization_options["vue"]["hybridMode"], false);
+
+        assert_eq!(framework_for_extension("astro").unwrap().binary, "astro-ls");
+        assert!(framework_for_extension("tsx").is_none());
+        assert!(resolve("elm", &root).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -4,16 +4,24 @@
 //! any LSP-compliant language server. Language-specific implementations
 //! (like C#) should use this manager and provide their own configuration.
 
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
-use tauri::Emitter;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
 use walkdir::WalkDir;
 
+use crate::languages::diagnostics::DiagnosticsState;
+use crate::languages::frameworks::servers::FRAMEWORK_SERVERS;
+use crate::languages::lsp_metrics::{RequestTracker, ServerInfo};
+use crate::languages::watched_files::WatchRegistrations;
+
 /// Configuration for starting a language server
 #[derive(Debug, Clone)]
 pub struct LSPServerConfig {
@@ -41,12 +49,33 @@ impl Default for LSPServerConfig {
     }
 }
 
+/// Prefix of ids of requests sent by the backend itself. The frontend client uses
+/// numeric ids, so string ids with this prefix never collide with its requests.
+const BACKEND_REQUEST_PREFIX: &str = "fluxel-";
+
+/// How long a backend request waits for the server's response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type PendingRequests =
+    Arc<std::sync::Mutex<HashMap<String, oneshot::Sender<Result<Value, String>>>>>;
+
 /// LSP Manager handles the lifecycle and communication with a language server process
 pub struct LSPManager {
     process: Option<Child>,
     stdin_handle: Option<tokio::process::ChildStdin>,
     /// Name of the language server (for logging purposes)
     server_name: String,
+    /// Backend requests awaiting a response, by id
+    pending: PendingRequests,
+    next_request_id: u64,
+    /// File watchers the server registered
+    watch_registrations: WatchRegistrations,
+    /// Task relaying file changes to the server
+    /// Tasks that live as long as the server, aborted when it stops
+    background_tasks: Vec<JoinHandle<()>>,
+    /// Latencies of requests sent to the server
+    tracker: RequestTracker,
+    started: Option<Instant>,
 }
 
 impl LSPManager {
@@ -55,11 +84,42 @@ impl LSPManager {
             process: None,
             stdin_handle: None,
             server_name: server_name.to_string(),
+            pending: PendingRequests::default(),
+            next_request_id: 0,
+            watch_registrations: WatchRegistrations::default(),
+            background_tasks: Vec::new(),
+            tracker: RequestTracker::default(),
+            started: None,
+        }
+    }
+
+    /// Name of the language server
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    /// File watchers the server registered, for the watched files relay
+    pub fn watch_registrations(&self) -> WatchRegistrations {
+        self.watch_registrations.clone()
+    }
+
+    /// Keep a task (e.g. the watched files relay) running until the server stops
+    pub fn attach_task(&mut self, task: JoinHandle<()>) {
+        self.background_tasks.push(task);
+    }
+
+    /// Identity and request tracker of the server, for metrics
+    pub fn info(&self) -> ServerInfo {
+        ServerInfo {
+            server: self.server_name.clone(),
+            running: self.is_running(),
+            pid: self.process.as_ref().and_then(Child::id),
+            started: self.started,
+            tracker: self.tracker.clone(),
         }
     }
 
     /// Check if the language server process is running
-    #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
         self.process.is_some()
     }
@@ -126,6 +186,7 @@ impl LSPManager {
 
         self.stdin_handle = Some(stdin);
         self.process = Some(child);
+        self.started = Some(Instant::now());
 
         println!(
             "[LSPManager:{}] Language server started successfully",
@@ -137,8 +198,20 @@ impl LSPManager {
 
         // Spawn task to read stdout
         let server_name_stdout = server_name.clone();
+        let pending = self.pending.clone();
+        let watch_registrations = self.watch_registrations.clone();
+        let tracker = self.tracker.clone();
         tokio::spawn(async move {
-            Self::handle_stdout(stdout, window.clone(), &event_name, &server_name_stdout).await;
+            Self::handle_stdout(
+                stdout,
+                window.clone(),
+                &event_name,
+                &server_name_stdout,
+                pending,
+                watch_registrations,
+                tracker,
+            )
+            .await;
         });
 
         // Spawn task to read stderr
@@ -158,6 +231,13 @@ impl LSPManager {
             self.server_name
         );
 
+        for task in self.background_tasks.drain(..) {
+            task.abort();
+        }
+        self.watch_registrations.clear();
+        self.tracker.clear_in_flight();
+        self.started = None;
+
         if let Some(mut process) = self.process.take() {
             // Kill the process forcefully to ensure cleanup
             if let Err(e) = process.kill().await {
@@ -204,10 +284,32 @@ impl LSPManager {
         Ok(())
     }
 
+    /// Wait up to `timeout` for the server process to exit on its own, e.g. after
+    /// an `exit` notification. Returns whether it did.
+    async fn wait_for_exit(&mut self, timeout: Duration) -> bool {
+        let Some(process) = self.process.as_mut() else {
+            return true;
+        };
+        match tokio::time::timeout(timeout, process.wait()).await {
+            Ok(Ok(status)) => {
+                println!(
+                    "[LSPManager:{}] Language server exited with status: {:?}",
+                    self.server_name, status
+                );
+                // Already reaped, so `stop` only has to clean up
+                self.process = None;
+                self.stdin_handle = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Send an LSP message to the language server
     #[cfg_attr(feature = "profiling", tracing::instrument(skip(self, message), fields(category = "lsp", server = %self.server_name)))]
     pub async fn send_message(&mut self, message: String) -> Result<(), String> {
         if let Some(stdin) = &mut self.stdin_handle {
+            self.tracker.track_outgoing(&message);
             let content_length = message.len();
             let header = format!("Content-Length: {}\r\n\r\n", content_length);
             let full_message = format!("{}{}", header, message);
@@ -228,12 +330,58 @@ impl LSPManager {
         }
     }
 
+    /// Send a request on behalf of the backend. Its response is delivered to the
+    /// returned receiver instead of the frontend.
+    pub async fn send_request(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<(String, oneshot::Receiver<Result<Value, String>>), String> {
+        self.next_request_id += 1;
+        let id = format!("{}{}", BACKEND_REQUEST_PREFIX, self.next_request_id);
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), sender);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = self.send_message(message.to_string()).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        Ok((id, receiver))
+    }
+
+    /// Resolve a pending backend request if `message` is its response. Returns
+    /// whether the message was consumed.
+    fn take_backend_response(pending: &PendingRequests, message: &Value) -> bool {
+        let Some(id) = message.get("id").and_then(Value::as_str) else {
+            return false;
+        };
+        if !id.starts_with(BACKEND_REQUEST_PREFIX) || message.get("method").is_some() {
+            return false;
+        }
+        if let Some(sender) = pending.lock().unwrap().remove(id) {
+            let result = match message.get("error") {
+                Some(error) => Err(error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Language server request failed")
+                    .to_string()),
+                None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+            };
+            let _ = sender.send(result);
+        }
+        true
+    }
+
     /// Handle stdout from the language server
     async fn handle_stdout(
         stdout: tokio::process::ChildStdout,
         window: tauri::Window,
         event_name: &str,
         server_name: &str,
+        pending: PendingRequests,
+        watch_registrations: WatchRegistrations,
+        tracker: RequestTracker,
     ) {
         let mut reader = BufReader::new(stdout);
         let mut content_length: usize = 0;
@@ -266,6 +414,33 @@ impl LSPManager {
                     Ok(_) => {
                         // Parse and emit the LSP message to frontend
                         if let Ok(json) = serde_json::from_slice::<Value>(&buffer) {
+                            if let Some(completed) = tracker.complete(&json) {
+                                #[cfg(feature = "profiling")]
+                                if let Some(profiler) =
+                                    window.try_state::<crate::profiling::FluxelProfiler>()
+                                {
+                                    profiler.record_measured_span(
+                                        completed.method.clone(),
+                                        "lsp",
+                                        crate::profiling::SpanCategory::LspRequest,
+                                        completed.latency,
+                                        vec![
+                                            ("server".to_string(), server_name.to_string()),
+                                            ("failed".to_string(), completed.failed.to_string()),
+                                        ],
+                                    );
+                                }
+                                #[cfg(not(feature = "profiling"))]
+                                let _ = completed;
+                            }
+                            if Self::take_backend_response(&pending, &json) {
+                                content_length = 0;
+                                continue;
+                            }
+                            if let Some(diagnostics) = window.try_state::<DiagnosticsState>() {
+                                diagnostics.ingest_lsp_message(&window, server_name, &json);
+                            }
+                            watch_registrations.ingest_message(&json);
                             let _ = window.emit(event_name, json);
                         }
                     }
@@ -280,6 +455,13 @@ impl LSPManager {
             }
         }
 
+        // Nothing will answer requests still waiting; dropping them fails the callers
+        pending.lock().unwrap().clear();
+        tracker.clear_in_flight();
+        // Diagnostics from a server that exited are stale
+        if let Some(diagnostics) = window.try_state::<DiagnosticsState>() {
+            diagnostics.set_provider(&window, &format!("lsp:{}", server_name), Vec::new());
+        }
         println!("[LSPManager:{}] stdout closed", server_name);
     }
 
@@ -296,6 +478,118 @@ impl LSPManager {
     }
 }
 
+/// Send a request to the server behind `manager` and wait for its result. The
+/// manager is only locked while sending, so the frontend's traffic isn't blocked.
+pub async fn request(
+    manager: &Mutex<LSPManager>,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    let (id, receiver) = {
+        let mut manager = manager.lock().await;
+        if !manager.is_running() {
+            return Err(format!("{} is not running", manager.server_name()));
+        }
+        manager.send_request(method, params).await?
+    };
+
+    match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Language server stopped before responding".to_string()),
+        Err(_) => {
+            let mut manager = manager.lock().await;
+            manager.pending.lock().unwrap().remove(&id);
+            let cancel =
+                json!({ "jsonrpc": "2.0", "method": "$/cancelRequest", "params": { "id": id } });
+            let _ = manager.send_message(cancel.to_string()).await;
+            Err(format!("{} timed out", method))
+        }
+    }
+}
+
+/// Shut the server behind `manager` down the way LSP asks for: a `shutdown`
+/// request, then an `exit` notification. The process is killed if it doesn't
+/// answer or exit within `timeout` each.
+pub async fn shutdown(manager: &Mutex<LSPManager>, timeout: Duration) {
+    let receiver = {
+        let mut manager = manager.lock().await;
+        if !manager.is_running() {
+            return;
+        }
+        manager.send_request("shutdown", Value::Null).await.ok()
+    };
+    let acknowledged = match receiver {
+        Some((_, receiver)) => {
+            matches!(tokio::time::timeout(timeout, receiver).await, Ok(Ok(Ok(_))))
+        }
+        None => false,
+    };
+
+    let mut manager = manager.lock().await;
+    if acknowledged {
+        let exit = json!({ "jsonrpc": "2.0", "method": "exit" });
+        if manager.send_message(exit.to_string()).await.is_ok() {
+            manager.wait_for_exit(timeout).await;
+        }
+    }
+    let _ = manager.stop().await;
+}
+
+// =============================================================================
+// URI Helpers
+// =============================================================================
+
+/// Filesystem path of a `file://` URI
+pub fn uri_to_path(uri: &str) -> String {
+    let Some(rest) = uri.strip_prefix("file://") else {
+        return uri.to_string();
+    };
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = |b: u8| (b as char).to_digit(16);
+        match (bytes[i], bytes.get(i + 1), bytes.get(i + 2)) {
+            (b'%', Some(&hi), Some(&lo)) if hex(hi).is_some() && hex(lo).is_some() => {
+                decoded.push((hex(hi).unwrap() * 16 + hex(lo).unwrap()) as u8);
+                i += 3;
+            }
+            (byte, _, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&decoded).to_string();
+
+    // file:///C:/dir → C:\dir
+    let is_drive = |p: &str| p.len() >= 3 && p.as_bytes()[0] == b'/' && p.as_bytes()[2] == b':';
+    if is_drive(&path) {
+        path[1..].replace('/', "\\")
+    } else {
+        path
+    }
+}
+
+/// `file://` URI of a filesystem path
+pub fn path_to_uri(path: &str) -> String {
+    let mut path = path.replace('\\', "/");
+    if !path.starts_with('/') {
+        // C:/dir → /C:/dir
+        path.insert(0, '/');
+    }
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
 // =============================================================================
 // C# Language Server Specific Helpers
 // =============================================================================
@@ -331,6 +625,34 @@ pub fn find_project_file(workspace_root: &Path) -> Option<PathBuf> {
         .map(|entry| entry.into_path())
 }
 
+/// All solution files within the workspace root, or all .csproj files when
+/// there is no solution, sorted by path
+pub fn find_solution_candidates(workspace_root: &Path) -> Vec<PathBuf> {
+    let files_with = |extensions: &[&str]| {
+        let mut files: Vec<PathBuf> = WalkDir::new(workspace_root)
+            .max_depth(3)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.contains(&ext))
+            })
+            .map(|entry| entry.into_path())
+            .collect();
+        files.sort();
+        files
+    };
+    let solutions = files_with(&["sln", "slnx"]);
+    if solutions.is_empty() {
+        files_with(&["csproj"])
+    } else {
+        solutions
+    }
+}
+
 /// Resolve the dotnet tool directory for the current platform.
 pub fn dotnet_tool_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|home| home.join(".dotnet").join("tools"))
@@ -398,19 +720,40 @@ pub async fn install_csharp_ls() -> Result<(), String> {
 /// Global state for managing language server instances
 pub struct LSPState {
     pub manager: Arc<Mutex<LSPManager>>,
+    /// Framework servers (Vue, Svelte, Astro), by language id
+    pub frameworks: HashMap<&'static str, Arc<Mutex<LSPManager>>>,
 }
 
 impl LSPState {
     pub fn new() -> Self {
         Self {
             manager: Arc::new(Mutex::new(LSPManager::new("csharp-ls"))),
+            frameworks: FRAMEWORK_SERVERS
+                .iter()
+                .map(|server| {
+                    let manager = LSPManager::new(server.binary);
+                    (server.language, Arc::new(Mutex::new(manager)))
+                })
+                .collect(),
         }
     }
+
+    /// The manager of a framework server
+    pub fn framework(&self, language: &str) -> Result<&Arc<Mutex<LSPManager>>, String> {
+        self.frameworks
+            .get(language)
+            .ok_or_else(|| format!("No framework language server for {}", language))
+    }
+
+    /// Every language server manager
+    pub fn servers(&self) -> impl Iterator<Item = &Arc<Mutex<LSPManager>>> {
+        std::iter::once(&self.manager).chain(self.frameworks.values())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::find_solution_file;
+    use super::{find_solution_file, path_to_uri, uri_to_path};
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -438,4 +781,12 @@ mod tests {
 
         fs::remove_dir_all(workspace).expect("temporary workspace should be removed");
     }
+
+    #[test]
+    fn converts_paths_to_uris_and_back() {
+        let uri = path_to_uri("/repo/My App/a#b.cs");
+        assert_eq!(uri, "file:///repo/My%20App/a%23b.cs");
+        assert_eq!(uri_to_path(&uri), "/repo/My App/a#b.cs");
+        assert_eq!(path_to_uri("C:\\src\\a.cs"), "file:///C:/src/a.cs");
+    }
 }
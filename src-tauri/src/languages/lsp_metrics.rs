@@ -0,0 +1,283 @@
+//! Language Server Metrics
+//!
+//! Request counts and latencies per method, pending request depth, and process
+//! memory for each language server, so a slow server can be told apart from a
+//! slow editor. Requests from both the frontend and the backend are timed as
+//! they pass through the `LSPManager`; with the `profiling` feature each
+//! completed request is also recorded as an `LspRequest` span.
+
+use crate::languages::lsp_manager::LSPState;
+use crate::services::process_manager::process_memory_bytes;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// Latencies kept per method for percentiles
+const RECENT_LATENCIES: usize = 100;
+
+/// The parts of an outgoing message needed to track it; params are skipped
+#[derive(Deserialize)]
+struct Envelope {
+    id: Option<Value>,
+    method: Option<String>,
+}
+
+#[derive(Default)]
+struct MethodStats {
+    count: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    /// Method and send time of requests awaiting a response, by id
+    in_flight: HashMap<String, (String, Instant)>,
+    methods: HashMap<String, MethodStats>,
+}
+
+/// A request that received its response
+// Only read when spans are recorded for the profiler
+#[cfg_attr(not(feature = "profiling"), allow(dead_code))]
+pub struct CompletedRequest {
+    pub method: String,
+    pub latency: Duration,
+    pub failed: bool,
+}
+
+/// Times the requests sent to one language server
+#[derive(Clone, Default)]
+pub struct RequestTracker {
+    inner: Arc<Mutex<TrackerInner>>,
+}
+
+impl RequestTracker {
+    /// Start timing `message` if it's a request
+    pub fn track_outgoing(&self, message: &str) {
+        let Ok(Envelope {
+            id: Some(id),
+            method: Some(method),
+        }) = serde_json::from_str::<Envelope>(message)
+        else {
+            return;
+        };
+        if method == "$/cancelRequest" {
+            return;
+        }
+        self.inner
+            .lock()
+            .unwrap()
+            .in_flight
+            .insert(id.to_string(), (method, Instant::now()));
+    }
+
+    /// Record the latency of the request `message` responds to
+    pub fn complete(&self, message: &Value) -> Option<CompletedRequest> {
+        if message.get("method").is_some() {
+            return None;
+        }
+        let id = message.get("id")?.to_string();
+        let mut inner = self.inner.lock().unwrap();
+        let (method, sent) = inner.in_flight.remove(&id)?;
+        let latency = sent.elapsed();
+        let failed = message.get("error").is_some();
+
+        let stats = inner.methods.entry(method.clone()).or_default();
+        stats.count += 1;
+        stats.errors += failed as u64;
+        stats.total += latency;
+        stats.max = stats.max.max(latency);
+        if stats.recent.len() == RECENT_LATENCIES {
+            stats.recent.pop_front();
+        }
+        stats.recent.push_back(latency);
+
+        Some(CompletedRequest {
+            method,
+            latency,
+            failed,
+        })
+    }
+
+    /// Forget requests that will never be answered, e.g. after the server stops
+    pub fn clear_in_flight(&self) {
+        self.inner.lock().unwrap().in_flight.clear();
+    }
+
+    fn snapshot(&self) -> (Vec<MethodMetrics>, usize, Option<f64>) {
+        let inner = self.inner.lock().unwrap();
+        let mut methods: Vec<MethodMetrics> = inner
+            .methods
+            .iter()
+            .map(|(method, stats)| {
+                let mut recent: Vec<Duration> = stats.recent.iter().copied().collect();
+                recent.sort();
+                let percentile = |p: f64| {
+                    let index = ((recent.len() as f64 - 1.0) * p).round() as usize;
+                    recent.get(index).copied().map(millis).unwrap_or(0.0)
+                };
+                MethodMetrics {
+                    method: method.clone(),
+                    count: stats.count,
+                    errors: stats.errors,
+                    average_ms: millis(stats.total) / stats.count.max(1) as f64,
+                    p50_ms: percentile(0.5),
+                    p95_ms: percentile(0.95),
+                    max_ms: millis(stats.max),
+                    total_ms: millis(stats.total),
+                }
+            })
+            .collect();
+        // Where the server spent the most time first
+        methods.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+
+        let oldest_pending_ms = inner
+            .in_flight
+            .values()
+            .map(|(_, sent)| millis(sent.elapsed()))
+            .reduce(f64::max);
+        (methods, inner.in_flight.len(), oldest_pending_ms)
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodMetrics {
+    pub method: String,
+    pub count: u64,
+    /// Requests answered with an error (including cancellations)
+    pub errors: u64,
+    pub average_ms: f64,
+    /// Percentiles over the last 100 requests
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LspServerMetrics {
+    pub server: String,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub uptime_ms: Option<f64>,
+    /// Resident memory of the server process
+    pub memory_bytes: Option<u64>,
+    pub total_requests: u64,
+    /// Requests sent and not yet answered
+    pub pending_requests: usize,
+    /// Age of the oldest unanswered request
+    pub oldest_pending_ms: Option<f64>,
+    /// Per-method statistics, by total time spent
+    pub methods: Vec<MethodMetrics>,
+}
+
+/// What a manager reports about itself; memory is read separately so the
+/// manager isn't locked while the OS is queried
+pub struct ServerInfo {
+    pub server: String,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub started: Option<Instant>,
+    pub tracker: RequestTracker,
+}
+
+impl ServerInfo {
+    fn metrics(self) -> LspServerMetrics {
+        let (methods, pending_requests, oldest_pending_ms) = self.tracker.snapshot();
+        LspServerMetrics {
+            memory_bytes: self.pid.and_then(process_memory_bytes),
+            uptime_ms: self.started.map(|started| millis(started.elapsed())),
+            total_requests: methods.iter().map(|method| method.count).sum(),
+            server: self.server,
+            running: self.running,
+            pid: self.pid,
+            pending_requests,
+            oldest_pending_ms,
+            methods,
+        }
+    }
+}
+
+/// Metrics of every language server in `state`
+pub async fn collect_server_metrics(state: &LSPState) -> Result<Vec<LspServerMetrics>, String> {
+    let mut servers = Vec::new();
+    for manager in state.servers() {
+        servers.push(manager.lock().await.info());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        servers.into_iter().map(ServerInfo::metrics).collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Request statistics, pending depth, and memory of each language server
+#[fluxel_command]
+pub async fn get_lsp_server_metrics(
+    state: State<'_, LSPState>,
+) -> Result<Vec<LspServerMetrics>, String> {
+    collect_server_metrics(&state).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn times_requests_by_method() {
+        let tracker = RequestTracker::default();
+        let request = |id: Value, method: &str| {
+            json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": {} }).to_string()
+        };
+        tracker.track_outgoing(&request(json!(1), "textDocument/hover"));
+        tracker.track_outgoing(&request(json!("fluxel-1"), "textDocument/hover"));
+        tracker.track_outgoing(&request(json!(2), "textDocument/completion"));
+        // Notifications and responses to the server aren't timed
+        tracker.track_outgoing(r#"{"jsonrpc":"2.0","method":"textDocument/didOpen"}"#);
+        tracker.track_outgoing(r#"{"jsonrpc":"2.0","id":7,"result":null}"#);
+
+        let completed = tracker
+            .complete(&json!({ "id": 1, "result": null }))
+            .unwrap();
+        assert_eq!(completed.method, "textDocument/hover");
+        assert!(!completed.failed);
+        let completed = tracker
+            .complete(&json!({ "id": "fluxel-1", "error": { "code": -32800 } }))
+            .unwrap();
+        assert!(completed.failed);
+        // Server-to-client requests and unknown ids
+        assert!(tracker
+            .complete(&json!({ "id": 2, "method": "workspace/configuration" }))
+            .is_none());
+        assert!(tracker
+            .complete(&json!({ "id": 9, "result": null }))
+            .is_none());
+
+        let (methods, pending, oldest) = tracker.snapshot();
+        assert_eq!(pending, 1);
+        assert!(oldest.is_some());
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].method, "textDocument/hover");
+        assert_eq!((methods[0].count, methods[0].errors), (2, 1));
+
+        tracker.clear_in_flight();
+        assert_eq!(tracker.snapshot().1, 0);
+    }
+}
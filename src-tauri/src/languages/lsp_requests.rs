@@ -0,0 +1,546 @@
+//! Backend LSP Requests
+//!
+//! Requests the backend sends to a language server itself (rather than relaying
+//! the frontend's traffic), routed to the server that owns the file or language.
+
+use crate::languages::frameworks::servers::framework_for_extension;
+use crate::languages::lsp_manager::{path_to_uri, request, LSPManager, LSPState};
+use crate::languages::workspace_edit::{
+    apply_workspace_edit_to_disk, LspPosition, LspRange, WorkspaceEdit, WorkspaceEditResult,
+};
+use crate::services::markdown::sanitize_markdown;
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+use tauri::State;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolRenameResult {
+    /// Every file the rename changed or moved, so open buffers can be refreshed
+    pub touched_files: Vec<String>,
+    pub edit: WorkspaceEditResult,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hover {
+    /// Sanitized markdown
+    pub contents: String,
+    /// Range the hover applies to, if the server gave one
+    pub range: Option<LspRange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureParameter {
+    pub label: String,
+    /// UTF-16 offsets of the parameter in the signature label, for highlighting
+    pub label_range: Option<[u32; 2]>,
+    pub documentation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Signature {
+    pub label: String,
+    pub documentation: Option<String>,
+    pub parameters: Vec<SignatureParameter>,
+    /// Overrides the help's `activeParameter` for this signature
+    pub active_parameter: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureHelp {
+    pub signatures: Vec<Signature>,
+    pub active_signature: u32,
+    pub active_parameter: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    pub position: LspPosition,
+    /// Label text; label parts are joined
+    pub label: String,
+    /// 1 = type, 2 = parameter
+    pub kind: Option<u8>,
+    /// Sanitized markdown
+    pub tooltip: Option<String>,
+    pub padding_left: bool,
+    pub padding_right: bool,
+}
+
+/// The language server for a language id (as used by Monaco)
+fn language_server<'a>(
+    state: &'a LSPState,
+    language: &str,
+) -> Result<&'a Mutex<LSPManager>, String> {
+    match language {
+        "csharp" => Ok(&state.manager),
+        _ => state
+            .framework(language)
+            .map(|manager| manager.as_ref())
+            .map_err(|_| format!("No language server for {}", language)),
+    }
+}
+
+/// The language server that handles `path`, by file extension
+pub(crate) fn owning_server<'a>(
+    state: &'a LSPState,
+    path: &str,
+) -> Result<&'a Mutex<LSPManager>, String> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "cs" | "csx" => language_server(state, "csharp"),
+        extension => match framework_for_extension(extension) {
+            Some(server) => language_server(state, server.language),
+            None => Err(format!("No language server handles {}", path)),
+        },
+    }
+}
+
+// ============================================================================
+// Content Normalization
+// ============================================================================
+
+/// Backslash-escape markdown punctuation so plain text renders as written
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if "\\`*_{}[]()<>#+-.!|~".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Markdown for any of the content shapes servers send: a `MarkedString`
+/// (a string, or `{ language, value }`), an array of them, or `MarkupContent`
+/// (`{ kind: "markdown" | "plaintext", value }`)
+fn markup_to_markdown(content: &Value) -> String {
+    match content {
+        Value::String(markdown) => markdown.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(markup_to_markdown)
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n"),
+        Value::Object(object) => {
+            let value = object.get("value").and_then(Value::as_str).unwrap_or("");
+            if let Some(language) = object.get("language").and_then(Value::as_str) {
+                format!("```{}\n{}\n```", language, value)
+            } else if object.get("kind").and_then(Value::as_str) == Some("plaintext") {
+                escape_markdown(value)
+            } else {
+                value.to_string()
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// Normalized, sanitized markdown for `content`, or `None` if it is empty
+fn documentation(content: Option<&Value>) -> Result<Option<String>, String> {
+    let markdown = content.map(markup_to_markdown).unwrap_or_default();
+    if markdown.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(sanitize_markdown(&markdown)?.trim_end().to_string()))
+}
+
+fn normalize_hover(result: &Value) -> Result<Option<Hover>, String> {
+    let Some(contents) = documentation(result.get("contents"))? else {
+        return Ok(None);
+    };
+    let range = result
+        .get("range")
+        .and_then(|range| serde_json::from_value(range.clone()).ok());
+    Ok(Some(Hover { contents, range }))
+}
+
+/// Text of a parameter label, which is either a string or UTF-16 offsets into
+/// the signature label
+fn parameter_label(signature_label: &str, label: &Value) -> (String, Option<[u32; 2]>) {
+    if let Some(label) = label.as_str() {
+        return (label.to_string(), None);
+    }
+    let offsets = label.as_array().and_then(|pair| {
+        Some([
+            pair.first()?.as_u64()? as u32,
+            pair.get(1)?.as_u64()? as u32,
+        ])
+    });
+    let Some([start, end]) = offsets else {
+        return (String::new(), None);
+    };
+    let units: Vec<u16> = signature_label.encode_utf16().collect();
+    let text = units
+        .get(start as usize..(end as usize).min(units.len()))
+        .map(String::from_utf16_lossy)
+        .unwrap_or_default();
+    (text, Some([start, end]))
+}
+
+fn normalize_signature_help(result: &Value) -> Result<Option<SignatureHelp>, String> {
+    let mut signatures = Vec::new();
+    for signature in result
+        .get("signatures")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let label = signature
+            .get("label")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        let mut parameters = Vec::new();
+        for parameter in signature
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let (text, label_range) =
+                parameter_label(&label, parameter.get("label").unwrap_or(&Value::Null));
+            parameters.push(SignatureParameter {
+                label: text,
+                label_range,
+                documentation: documentation(parameter.get("documentation"))?,
+            });
+        }
+        signatures.push(Signature {
+            documentation: documentation(signature.get("documentation"))?,
+            label,
+            parameters,
+            active_parameter: signature
+                .get("activeParameter")
+                .and_then(Value::as_u64)
+                .map(|index| index as u32),
+        });
+    }
+    if signatures.is_empty() {
+        return Ok(None);
+    }
+
+    let active_signature = result
+        .get("activeSignature")
+        .and_then(Value::as_u64)
+        .map(|index| (index as usize).min(signatures.len() - 1) as u32)
+        .unwrap_or(0);
+    Ok(Some(SignatureHelp {
+        signatures,
+        active_signature,
+        active_parameter: result
+            .get("activeParameter")
+            .and_then(Value::as_u64)
+            .map(|index| index as u32),
+    }))
+}
+
+fn normalize_inlay_hint(hint: &Value) -> Result<Option<InlayHint>, String> {
+    let Some(position) = hint
+        .get("position")
+        .and_then(|position| serde_json::from_value(position.clone()).ok())
+    else {
+        return Ok(None);
+    };
+    let label = match hint.get("label") {
+        Some(Value::String(label)) => label.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|part| part.get("value").and_then(Value::as_str))
+            .collect(),
+        _ => String::new(),
+    };
+    if label.is_empty() {
+        return Ok(None);
+    }
+    let flag = |key: &str| hint.get(key).and_then(Value::as_bool).unwrap_or(false);
+    Ok(Some(InlayHint {
+        position,
+        label,
+        kind: hint
+            .get("kind")
+            .and_then(Value::as_u64)
+            .map(|kind| kind as u8),
+        tooltip: documentation(hint.get("tooltip"))?,
+        padding_left: flag("paddingLeft"),
+        padding_right: flag("paddingRight"),
+    }))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Request code actions (quick fixes and refactorings) for a range of a file
+///
+/// Returns the server's `Command | CodeAction` list as-is. Actions carrying an
+/// `edit` can be applied with `apply_workspace_edit`.
+///
+/// # Arguments
+/// * `path` - File the range is in; must be open in the server
+/// * `range` - LSP range (0-based lines, UTF-16 characters)
+/// * `diagnostics` - LSP diagnostics overlapping the range, to get fixes for them
+/// * `only` - Code action kinds to request, e.g. `["quickfix"]`
+#[fluxel_command]
+pub async fn lsp_code_actions(
+    state: State<'_, LSPState>,
+    path: String,
+    range: LspRange,
+    diagnostics: Option<Vec<Value>>,
+    only: Option<Vec<String>>,
+) -> Result<Vec<Value>, String> {
+    let server = owning_server(&state, &path)?;
+    let mut context = json!({ "diagnostics": diagnostics.unwrap_or_default() });
+    if let Some(only) = only {
+        context["only"] = json!(only);
+    }
+    let params = json!({
+        "textDocument": { "uri": path_to_uri(&path) },
+        "range": range,
+        "context": context,
+    });
+
+    match request(server, "textDocument/codeAction", params).await? {
+        Value::Array(actions) => Ok(actions),
+        // `null` means no actions
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Rename the symbol at a position across the workspace
+///
+/// Runs the `textDocument/rename` round trip and applies the resulting edit to
+/// files on disk (all or nothing, see `apply_workspace_edit`).
+///
+/// # Arguments
+/// * `language` - Language id of the file, e.g. `csharp`
+/// * `file` - File containing the symbol; must be open in the server
+/// * `position` - LSP position of the symbol (0-based line, UTF-16 character)
+/// * `new_name` - New name for the symbol
+/// * `open_files` - Files open in the editor; their edits are returned for the
+///   editor to apply instead of being written to disk
+#[fluxel_command]
+pub async fn lsp_rename(
+    state: State<'_, LSPState>,
+    language: String,
+    file: String,
+    position: LspPosition,
+    new_name: String,
+    open_files: Option<Vec<String>>,
+) -> Result<SymbolRenameResult, String> {
+    if new_name.trim().is_empty() {
+        return Err("New name cannot be empty".to_string());
+    }
+    let server = language_server(&state, &language)?;
+    let params = json!({
+        "textDocument": { "uri": path_to_uri(&file) },
+        "position": position,
+        "newName": new_name,
+    });
+    let result = request(server, "textDocument/rename", params).await?;
+    if result.is_null() {
+        return Err("Nothing to rename at this position".to_string());
+    }
+    let edit: WorkspaceEdit =
+        serde_json::from_value(result).map_err(|e| format!("Invalid rename edit: {}", e))?;
+
+    let edit = tauri::async_runtime::spawn_blocking(move || {
+        apply_workspace_edit_to_disk(edit, &open_files.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut touched: BTreeSet<String> = BTreeSet::new();
+    for change in &edit.applied {
+        touched.insert(change.path.clone());
+        touched.extend(change.new_path.clone());
+    }
+    touched.extend(edit.open_file_edits.iter().map(|file| file.path.clone()));
+    println!(
+        "[LSP] Renamed symbol to {} in {} file(s)",
+        new_name,
+        touched.len()
+    );
+
+    Ok(SymbolRenameResult {
+        touched_files: touched.into_iter().collect(),
+        edit,
+    })
+}
+
+/// Hover information at a position, as sanitized markdown
+///
+/// # Arguments
+/// * `path` - File to query; must be open in the server
+/// * `position` - LSP position (0-based line, UTF-16 character)
+#[fluxel_command]
+pub async fn lsp_hover(
+    state: State<'_, LSPState>,
+    path: String,
+    position: LspPosition,
+) -> Result<Option<Hover>, String> {
+    let server = owning_server(&state, &path)?;
+    let params = json!({
+        "textDocument": { "uri": path_to_uri(&path) },
+        "position": position,
+    });
+    let result = request(server, "textDocument/hover", params).await?;
+    if result.is_null() {
+        return Ok(None);
+    }
+    normalize_hover(&result)
+}
+
+/// Signature help for the call at a position, with sanitized documentation
+///
+/// # Arguments
+/// * `path` - File to query; must be open in the server
+/// * `position` - LSP position (0-based line, UTF-16 character)
+/// * `trigger_character` - Character that triggered the request, e.g. `(` or `,`
+#[fluxel_command]
+pub async fn lsp_signature_help(
+    state: State<'_, LSPState>,
+    path: String,
+    position: LspPosition,
+    trigger_character: Option<String>,
+) -> Result<Option<SignatureHelp>, String> {
+    let server = owning_server(&state, &path)?;
+    let context = match &trigger_character {
+        Some(character) => {
+            json!({ "triggerKind": 2, "triggerCharacter": character, "isRetrigger": false })
+        }
+        None => json!({ "triggerKind": 1, "isRetrigger": false }),
+    };
+    let params = json!({
+        "textDocument": { "uri": path_to_uri(&path) },
+        "position": position,
+        "context": context,
+    });
+    let result = request(server, "textDocument/signatureHelp", params).await?;
+    if result.is_null() {
+        return Ok(None);
+    }
+    normalize_signature_help(&result)
+}
+
+/// Inlay hints (inferred types, parameter names) within a range of a file
+///
+/// # Arguments
+/// * `path` - File to get hints for; must be open in the server
+/// * `range` - Visible range, usually the viewport
+#[fluxel_command]
+pub async fn lsp_inlay_hints(
+    state: State<'_, LSPState>,
+    path: String,
+    range: LspRange,
+) -> Result<Vec<InlayHint>, String> {
+    let server = owning_server(&state, &path)?;
+    let params = json!({
+        "textDocument": { "uri": path_to_uri(&path) },
+        "range": range,
+    });
+    let result = request(server, "textDocument/inlayHint", params).await?;
+    let mut hints = Vec::new();
+    for hint in result.as_array().into_iter().flatten() {
+        hints.extend(normalize_inlay_hint(hint)?);
+    }
+    Ok(hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_hover_content_shapes() {
+        let marked = json!({
+            "contents": [
+                { "language": "csharp", "value": "void Main()" },
+                "Entry <script>x()</script> point"
+            ]
+        });
+        let hover = normalize_hover(&marked).unwrap().unwrap();
+        assert!(hover.contents.contains("void Main()"));
+        assert!(hover.contents.contains("---"));
+        assert!(!hover.contents.contains("<script>"));
+
+        let plain = json!({ "contents": { "kind": "plaintext", "value": "a *b*" } });
+        assert_eq!(
+            normalize_hover(&plain).unwrap().unwrap().contents,
+            "a \\*b\\*"
+        );
+        assert!(normalize_hover(&json!({ "contents": "" }))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn normalizes_signature_parameters() {
+        let help = json!({
+            "signatures": [{
+                "label": "Add(int a, int b)",
+                "documentation": { "kind": "markdown", "value": "Adds **two** numbers" },
+                "parameters": [
+                    { "label": [4, 9] },
+                    { "label": "int b", "documentation": "second" }
+                ]
+            }],
+            "activeSignature": 3,
+            "activeParameter": 1
+        });
+        let help = normalize_signature_help(&help).unwrap().unwrap();
+        assert_eq!(help.active_signature, 0);
+        assert_eq!(help.active_parameter, Some(1));
+        let signature = &help.signatures[0];
+        assert_eq!(
+            signature.documentation.as_deref(),
+            Some("Adds **two** numbers")
+        );
+        assert_eq!(signature.parameters[0].label, "int a");
+        assert_eq!(signature.parameters[0].label_range, Some([4, 9]));
+        assert_eq!(
+            signature.parameters[1].documentation.as_deref(),
+            Some("second")
+        );
+    }
+
+    #[test]
+    fn normalizes_inlay_hint_labels() {
+        let parts = json!({
+            "position": { "line": 3, "character": 12 },
+            "label": [{ "value": ": " }, { "value": "List<int>", "location": {} }],
+            "kind": 1,
+            "paddingLeft": true,
+        });
+        let hint = normalize_inlay_hint(&parts).unwrap().unwrap();
+        assert_eq!(hint.label, ": List<int>");
+        assert_eq!(
+            (hint.kind, hint.padding_left, hint.padding_right),
+            (Some(1), true, false)
+        );
+
+        let plain = json!({
+            "position": { "line": 0, "character": 4 },
+            "label": "count:",
+            "tooltip": { "kind": "markdown", "value": "Parameter **count**" },
+        });
+        let hint = normalize_inlay_hint(&plain).unwrap().unwrap();
+        assert_eq!(hint.tooltip.as_deref(), Some("Parameter **count**"));
+        assert!(normalize_inlay_hint(&json!({ "label": "x" }))
+            .unwrap()
+            .is_none());
+    }
+}
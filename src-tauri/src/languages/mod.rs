@@ -6,7 +6,16 @@
 //! ## Structure
 //!
 //! - `lsp_manager` - Generic LSP manager for process lifecycle and communication
+//! - `completion` - Completion proxy that filters and ranks large server lists in Rust
+//! - `diagnostics` - Diagnostics store shared by language servers, builds, and linters
+//! - `lsp_metrics` - Per-server request latencies, pending depth, and memory
+//! - `lsp_requests` - Requests the backend sends to language servers (code actions, rename, hover, inlay hints)
+//! - `semantic_tokens` - Semantic tokens with delta requests resolved against a per-document cache
+//! - `watched_files` - Relays file changes to servers that registered file watchers
+//! - `workspace_edit` - Applying LSP workspace edits to files on disk
 //! - `csharp` - C# language support (csharp-ls, project parsing)
+//! - `frameworks` - Vue, Svelte, and Astro language servers
+//! - `schemas` - Built-in JSON, YAML, and TOML validation and completion against known schemas
 //!
 //! ## Adding New Languages
 //!
@@ -16,8 +25,21 @@
 //! 3. Export the commands from the new module
 //! 4. Register the commands in `lib.rs`
 
+pub mod completion;
 pub mod csharp;
+pub mod diagnostics;
+pub mod frameworks;
 pub mod lsp_manager;
+pub mod lsp_metrics;
+pub mod lsp_requests;
+pub mod schemas;
+pub mod semantic_tokens;
+pub mod watched_files;
+pub mod workspace_edit;
 
 // Re-export commonly used types
+pub use completion::CompletionState;
+pub use diagnostics::DiagnosticsState;
 pub use lsp_manager::LSPState;
+pub use schemas::catalog::SchemaState;
+pub use semantic_tokens::SemanticTokensState;
@@ -0,0 +1,370 @@
+//! Schema Catalog
+//!
+//! Maps well-known config files to their published schemas and loads them.
+//! Downloads are kept in memory and in `~/.fluxel/cache/schemas/`, so a schema
+//! is fetched at most once a week and the last copy keeps working offline.
+
+use crate::languages::schemas::document::{Format, Node, NodeValue};
+use crate::services::workspace_store::sha256_hex;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Downloaded schemas younger than this are used without fetching again
+const CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// After a failed download, don't try the same URL again for this long
+const RETRY_AFTER_SECS: u64 = 5 * 60;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A file name pattern and the schema for matching files
+struct SchemaAssociation {
+    /// Globs matched against the end of the file path
+    patterns: &'static [&'static str],
+    url: &'static str,
+}
+
+const SCHEMA_ASSOCIATIONS: &[SchemaAssociation] = &[
+    SchemaAssociation {
+        patterns: &["package.json"],
+        url: "https://json.schemastore.org/package.json",
+    },
+    SchemaAssociation {
+        patterns: &["tsconfig.json", "tsconfig.*.json", "tsconfig-*.json"],
+        url: "https://json.schemastore.org/tsconfig.json",
+    },
+    SchemaAssociation {
+        patterns: &["jsconfig.json", "jsconfig.*.json"],
+        url: "https://json.schemastore.org/jsconfig.json",
+    },
+    SchemaAssociation {
+        patterns: &["tauri.conf.json", "tauri.*.conf.json"],
+        url: "https://schema.tauri.app/config/2",
+    },
+    SchemaAssociation {
+        patterns: &[".github/workflows/*.yml", ".github/workflows/*.yaml"],
+        url: "https://json.schemastore.org/github-workflow.json",
+    },
+    SchemaAssociation {
+        patterns: &["action.yml", "action.yaml"],
+        url: "https://json.schemastore.org/github-action.json",
+    },
+    SchemaAssociation {
+        patterns: &[".github/dependabot.yml", ".github/dependabot.yaml"],
+        url: "https://json.schemastore.org/dependabot-2.0.json",
+    },
+    SchemaAssociation {
+        patterns: &[
+            "compose.yml",
+            "compose.yaml",
+            "docker-compose*.yml",
+            "docker-compose*.yaml",
+        ],
+        url: "https://raw.githubusercontent.com/compose-spec/compose-spec/master/schema/compose-spec.json",
+    },
+    SchemaAssociation {
+        patterns: &["Cargo.toml"],
+        url: "https://json.schemastore.org/cargo.json",
+    },
+    SchemaAssociation {
+        patterns: &["rustfmt.toml", ".rustfmt.toml"],
+        url: "https://json.schemastore.org/rustfmt.json",
+    },
+    SchemaAssociation {
+        patterns: &["pyproject.toml"],
+        url: "https://json.schemastore.org/pyproject.json",
+    },
+    SchemaAssociation {
+        patterns: &[".eslintrc", ".eslintrc.json"],
+        url: "https://json.schemastore.org/eslintrc.json",
+    },
+    SchemaAssociation {
+        patterns: &[".prettierrc", ".prettierrc.json"],
+        url: "https://json.schemastore.org/prettierrc.json",
+    },
+    SchemaAssociation {
+        patterns: &[".babelrc", "babel.config.json"],
+        url: "https://json.schemastore.org/babelrc.json",
+    },
+];
+
+/// Compiled association globs, with the association each one belongs to
+fn association_globs() -> &'static (GlobSet, Vec<usize>) {
+    static GLOBS: OnceLock<(GlobSet, Vec<usize>)> = OnceLock::new();
+    GLOBS.get_or_init(|| {
+        let mut builder = GlobSetBuilder::new();
+        let mut owners = Vec::new();
+        for (index, association) in SCHEMA_ASSOCIATIONS.iter().enumerate() {
+            for pattern in association.patterns {
+                let glob = GlobBuilder::new(&format!("**/{}", pattern))
+                    .literal_separator(true)
+                    .build()
+                    .expect("invalid schema glob");
+                builder.add(glob);
+                owners.push(index);
+            }
+        }
+        (builder.build().expect("invalid schema globs"), owners)
+    })
+}
+
+/// Schema URL associated with a file by name
+fn associated_schema(path: &str) -> Option<&'static str> {
+    let (globs, owners) = association_globs();
+    let normalized = path.replace('\\', "/");
+    globs
+        .matches(&normalized)
+        .first()
+        .map(|&index| SCHEMA_ASSOCIATIONS[owners[index]].url)
+}
+
+/// Schema named in the document itself: a JSON `$schema` property, a
+/// `# yaml-language-server: $schema=` modeline, or a Taplo `#:schema` comment
+fn inline_schema(format: Format, text: &str, document: Option<&Node>) -> Option<String> {
+    let reference = match format {
+        Format::Json => match document {
+            Some(document) => document.get("$schema").and_then(|node| match &node.value {
+                NodeValue::String(value) => Some(value.clone()),
+                _ => None,
+            }),
+            // Mid-edit documents often don't parse; find the property by hand
+            None => {
+                let after = &text[text.find("\"$schema\"")? + 9..];
+                let value = after.trim_start().strip_prefix(':')?.trim_start();
+                let value = value.strip_prefix('"')?;
+                Some(value[..value.find('"')?].to_string())
+            }
+        },
+        Format::Yaml => text.lines().find_map(|line| {
+            line.trim_start()
+                .strip_prefix('#')?
+                .trim_start()
+                .strip_prefix("yaml-language-server:")?
+                .trim_start()
+                .strip_prefix("$schema=")
+                .map(|url| url.trim().to_string())
+        }),
+        Format::Toml => text.lines().find_map(|line| {
+            line.trim_start()
+                .strip_prefix("#:schema")
+                .map(|url| url.trim().to_string())
+        }),
+    };
+    reference.filter(|reference| !reference.is_empty())
+}
+
+/// Where the schema for a document comes from: a URL, or a path for schemas
+/// referenced relative to the document
+pub fn schema_for(
+    path: &str,
+    format: Format,
+    text: &str,
+    document: Option<&Node>,
+) -> Option<String> {
+    match inline_schema(format, text, document) {
+        Some(reference) if reference.contains("://") => Some(reference),
+        Some(reference) => {
+            let base = Path::new(path).parent().unwrap_or(Path::new(""));
+            Some(base.join(reference).to_string_lossy().to_string())
+        }
+        None => associated_schema(path).map(str::to_string),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedSchema {
+    url: String,
+    fetched_at: u64,
+    schema: Value,
+}
+
+#[derive(Default)]
+struct SchemaCache {
+    loaded: HashMap<String, Arc<Value>>,
+    /// URL -> when its last download failed
+    failed: HashMap<String, u64>,
+}
+
+/// Loaded schemas, shared by validation and completion
+#[derive(Clone, Default)]
+pub struct SchemaState {
+    cache: Arc<Mutex<SchemaCache>>,
+    client: reqwest::Client,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".fluxel").join("cache").join("schemas"))
+}
+
+fn cache_file(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{}.json", sha256_hex(url.as_bytes())))
+}
+
+fn load_cached(path: &Path) -> Option<CachedSchema> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save_cached(path: &Path, cached: &CachedSchema) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+    let json = serde_json::to_string(cached).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write schema cache: {}", e))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl SchemaState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the schema at `source`, a URL or a local path
+    pub async fn load(&self, source: &str) -> Result<Arc<Value>, String> {
+        if let Some(schema) = self.cache.lock().unwrap().loaded.get(source) {
+            return Ok(schema.clone());
+        }
+
+        let schema = if source.starts_with("http://") || source.starts_with("https://") {
+            self.load_remote(source).await?
+        } else {
+            // Local schemas are read fresh each time so edits show up
+            let content = std::fs::read_to_string(source)
+                .map_err(|e| format!("Failed to read schema {}: {}", source, e))?;
+            return serde_json::from_str(&content)
+                .map(Arc::new)
+                .map_err(|e| format!("Invalid schema {}: {}", source, e));
+        };
+
+        let schema = Arc::new(schema);
+        self.cache
+            .lock()
+            .unwrap()
+            .loaded
+            .insert(source.to_string(), schema.clone());
+        Ok(schema)
+    }
+
+    async fn load_remote(&self, url: &str) -> Result<Value, String> {
+        let now = now_secs();
+        let path = cache_dir().map(|dir| cache_file(&dir, url));
+        let cached = path.as_deref().and_then(load_cached);
+        if let Some(cached) = &cached {
+            if now.saturating_sub(cached.fetched_at) <= CACHE_TTL_SECS {
+                return Ok(cached.schema.clone());
+            }
+        }
+
+        let recently_failed = self
+            .cache
+            .lock()
+            .unwrap()
+            .failed
+            .get(url)
+            .is_some_and(|&failed_at| now.saturating_sub(failed_at) < RETRY_AFTER_SECS);
+        let fetched = if recently_failed {
+            Err(format!("Schema {} is unavailable", url))
+        } else {
+            self.fetch(url).await
+        };
+
+        match fetched {
+            Ok(schema) => {
+                println!("[Schemas] Downloaded {}", url);
+                if let Some(path) = &path {
+                    let cached = CachedSchema {
+                        url: url.to_string(),
+                        fetched_at: now,
+                        schema: schema.clone(),
+                    };
+                    if let Err(e) = save_cached(path, &cached) {
+                        println!("[Schemas] {}", e);
+                    }
+                }
+                Ok(schema)
+            }
+            Err(e) => {
+                if !recently_failed {
+                    println!("[Schemas] {}", e);
+                    self.cache
+                        .lock()
+                        .unwrap()
+                        .failed
+                        .insert(url.to_string(), now);
+                }
+                // An outdated schema beats none
+                cached.map(|cached| cached.schema).ok_or(e)
+            }
+        }
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Value, String> {
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", "Fluxel")
+            .timeout(FETCH_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("Schema request failed for {}: {}", url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Schema request for {} returned {}", url, status));
+        }
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read schema {}: {}", url, e))?;
+        serde_json::from_str(text.trim_start_matches('\u{feff}'))
+            .map_err(|e| format!("Invalid schema {}: {}", url, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::schemas::document::parse;
+
+    #[test]
+    fn finds_schemas_by_name_and_inline_reference() {
+        let url = |path: &str| schema_for(path, Format::from_path(path).unwrap(), "", None);
+        assert_eq!(
+            url("/repo/src-tauri/tauri.conf.json").as_deref(),
+            Some("https://schema.tauri.app/config/2")
+        );
+        assert_eq!(
+            url("C:\\repo\\.github\\workflows\\ci.yml").as_deref(),
+            Some("https://json.schemastore.org/github-workflow.json")
+        );
+        assert_eq!(
+            url("/repo/tsconfig.node.json").as_deref(),
+            Some("https://json.schemastore.org/tsconfig.json")
+        );
+        assert_eq!(url("/repo/ci.yml"), None);
+
+        let text = r#"{ "$schema": "./schema.json" }"#;
+        let document = parse(Format::Json, text).unwrap();
+        let local = schema_for("/repo/package.json", Format::Json, text, Some(&document)).unwrap();
+        assert_eq!(Path::new(&local), Path::new("/repo").join("./schema.json"));
+
+        let text = "# yaml-language-server: $schema=https://example.com/s.json\nname: x\n";
+        assert_eq!(
+            schema_for("/repo/ci.yml", Format::Yaml, text, None).as_deref(),
+            Some("https://example.com/s.json")
+        );
+    }
+}
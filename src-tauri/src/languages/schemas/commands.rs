@@ -0,0 +1,169 @@
+//! Schema Service Commands
+//!
+//! Tauri commands validating JSON, YAML, and TOML documents against their
+//! schemas and completing keys. Validation results are also published to the
+//! diagnostics store under the `schema` provider.
+
+use crate::languages::diagnostics::{
+    Diagnostic, DiagnosticRange, DiagnosticSeverity, DiagnosticsState,
+};
+use crate::languages::schemas::catalog::{schema_for, SchemaState};
+use crate::languages::schemas::completion::{complete, key_context, SchemaCompletion};
+use crate::languages::schemas::document::{parse, Format, LineIndex};
+use crate::languages::schemas::validator::Validator;
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::ops::Range;
+use tauri::{Runtime, State};
+
+const PROVIDER: &str = "schema";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCompletionList {
+    pub items: Vec<SchemaCompletion>,
+    /// Text the items replace
+    pub range: DiagnosticRange,
+}
+
+fn to_range(index: &LineIndex, range: &Range<usize>) -> DiagnosticRange {
+    let (start_line, start_column) = index.position(range.start);
+    let (end_line, end_column) = index.position(range.end);
+    DiagnosticRange {
+        start_line: start_line + 1,
+        start_column: start_column + 1,
+        end_line: end_line + 1,
+        // Empty ranges still get a visible squiggle
+        end_column: end_column + 1 + u32::from(range.is_empty()),
+    }
+}
+
+/// Parse and validate `content`, returning diagnostics for it
+async fn check_document(
+    state: &SchemaState,
+    path: &str,
+    format: Format,
+    content: String,
+) -> Result<Vec<Diagnostic>, String> {
+    let diagnostic = |range: DiagnosticRange, severity, message: String| Diagnostic {
+        path: path.to_string(),
+        range,
+        severity,
+        message,
+        code: None,
+        source: format.name().to_string(),
+        provider: PROVIDER.to_string(),
+    };
+
+    let (content, parsed) = tauri::async_runtime::spawn_blocking(move || {
+        let parsed = parse(format, &content);
+        (content, parsed)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let document = match parsed {
+        Ok(document) => document,
+        Err(error) => {
+            let index = LineIndex::new(&content);
+            return Ok(vec![diagnostic(
+                to_range(&index, &error.range),
+                DiagnosticSeverity::Error,
+                error.message,
+            )]);
+        }
+    };
+
+    let Some(source) = schema_for(path, format, &content, Some(&document)) else {
+        return Ok(Vec::new());
+    };
+    let schema = match state.load(&source).await {
+        Ok(schema) => schema,
+        Err(e) => {
+            println!("[Schemas] No schema for {}: {}", path, e);
+            return Ok(Vec::new());
+        }
+    };
+
+    let errors = tauri::async_runtime::spawn_blocking(move || {
+        let errors = Validator::new(&schema).validate(&document);
+        (content, errors)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let (content, errors) = errors;
+    let index = LineIndex::new(&content);
+    Ok(errors
+        .into_iter()
+        .map(|error| {
+            diagnostic(
+                to_range(&index, &error.range),
+                DiagnosticSeverity::Warning,
+                error.message,
+            )
+        })
+        .collect())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Validate a JSON, YAML, or TOML document against its schema
+///
+/// The schema comes from the document (`$schema`, a `yaml-language-server`
+/// modeline, or a `#:schema` comment) or from the file name for well-known
+/// files. Other files get syntax errors only.
+///
+/// # Arguments
+/// * `path` - File path, used to pick the format and schema
+/// * `content` - Current editor content
+#[fluxel_command]
+pub async fn schema_validate<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, SchemaState>,
+    diagnostics: State<'_, DiagnosticsState>,
+    path: String,
+    content: String,
+) -> Result<Vec<Diagnostic>, String> {
+    let Some(format) = Format::from_path(&path) else {
+        return Ok(Vec::new());
+    };
+    let results = check_document(&state, &path, format, content).await?;
+    diagnostics.set_file(&app, PROVIDER, &path, results.clone());
+    Ok(results)
+}
+
+/// Complete object keys from the document's schema
+///
+/// # Arguments
+/// * `path` - File path, used to pick the format and schema
+/// * `content` - Current editor content
+/// * `line_number` - 1-based cursor line
+/// * `column` - 1-based cursor column, in UTF-16 code units
+#[fluxel_command]
+pub async fn schema_complete(
+    state: State<'_, SchemaState>,
+    path: String,
+    content: String,
+    line_number: u32,
+    column: u32,
+) -> Result<Option<SchemaCompletionList>, String> {
+    let Some(format) = Format::from_path(&path) else {
+        return Ok(None);
+    };
+    let index = LineIndex::new(&content);
+    let cursor = index.offset(line_number.saturating_sub(1), column.saturating_sub(1));
+    let Some(context) = key_context(format, &content, cursor) else {
+        return Ok(None);
+    };
+
+    let document = parse(format, &content).ok();
+    let Some(source) = schema_for(&path, format, &content, document.as_ref()) else {
+        return Ok(None);
+    };
+    let schema = state.load(&source).await?;
+    Ok(Some(SchemaCompletionList {
+        items: complete(format, &schema, &context),
+        range: to_range(&index, &context.replace),
+    }))
+}
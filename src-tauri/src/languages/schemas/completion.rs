@@ -0,0 +1,694 @@
+//! Schema Key Completion
+//!
+//! Works out which object the cursor is in from the text before it (the
+//! document is usually invalid mid-edit, so this doesn't rely on a full parse)
+//! and offers the properties the schema allows there.
+
+use crate::languages::schemas::document::{parse, yaml_lines, Format, Node, NodeValue};
+use crate::languages::schemas::validator::Validator;
+use serde::Serialize;
+use serde_json::Value;
+use std::ops::Range;
+
+/// Composition expanded this deep when collecting candidate schemas
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index,
+}
+
+/// Where a key is being typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyContext {
+    /// Path from the document root to the object holding the key
+    pub path: Vec<PathSegment>,
+    /// Text the completion replaces (the partial key, quotes included)
+    pub replace: Range<usize>,
+    /// Keys already present in the object
+    pub existing: Vec<String>,
+    /// Whether the completion is for a TOML `[table]` header
+    pub table_header: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCompletion {
+    pub label: String,
+    /// Value type, e.g. `string` or `object`
+    pub detail: Option<String>,
+    pub documentation: Option<String>,
+    /// Snippet inserting the key and a placeholder value
+    pub insert_text: String,
+    pub required: bool,
+    pub deprecated: bool,
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '$' | '@' | '.' | '/')
+}
+
+fn word_start(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| is_key_char(c) && c != '.')
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(offset)
+}
+
+/// Keys of the object at `path` in a document that parsed despite the edit
+fn keys_at(document: &Node, path: &[PathSegment], cursor: usize) -> Vec<String> {
+    let mut node = document;
+    for segment in path {
+        node = match (segment, &node.value) {
+            (PathSegment::Key(key), NodeValue::Object(_)) => match node.get(key) {
+                Some(child) => child,
+                None => return Vec::new(),
+            },
+            // The item holding the cursor
+            (PathSegment::Index, NodeValue::Array(items)) => match items
+                .iter()
+                .find(|item| item.range.start <= cursor && cursor <= item.range.end)
+            {
+                Some(item) => item,
+                None => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        };
+    }
+    match &node.value {
+        NodeValue::Object(properties) => properties
+            .iter()
+            .filter(|property| {
+                !(property.key_range.start <= cursor && cursor <= property.key_range.end)
+            })
+            .map(|property| property.key.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Cursor Context
+// ============================================================================
+
+enum JsonFrame {
+    Object {
+        key: Option<String>,
+        keys: Vec<String>,
+        expecting_key: bool,
+    },
+    Array,
+}
+
+fn json_context(text: &str, cursor: usize) -> Option<KeyContext> {
+    let bytes = text.as_bytes();
+    let mut stack: Vec<JsonFrame> = Vec::new();
+    let mut path: Vec<PathSegment> = Vec::new();
+    let mut pos = 0;
+    let mut open_string: Option<usize> = None;
+
+    while pos < cursor {
+        match bytes[pos] {
+            b'"' => {
+                let start = pos;
+                pos += 1;
+                while pos < cursor && bytes[pos] != b'"' && bytes[pos] != b'\n' {
+                    pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+                if pos >= cursor {
+                    open_string = Some(start);
+                    break;
+                }
+                if let Some(JsonFrame::Object {
+                    key,
+                    keys,
+                    expecting_key: true,
+                }) = stack.last_mut()
+                {
+                    let name = text[start + 1..pos].to_string();
+                    keys.push(name.clone());
+                    *key = Some(name);
+                }
+                pos += 1;
+            }
+            b'/' if text[pos..].starts_with("//") => {
+                pos = text[pos..]
+                    .find('\n')
+                    .map(|i| pos + i)
+                    .unwrap_or(text.len());
+            }
+            b'/' if text[pos..].starts_with("/*") => {
+                pos = text[pos + 2..]
+                    .find("*/")
+                    .map(|i| pos + i + 4)
+                    .unwrap_or(text.len());
+            }
+            open @ (b'{' | b'[') => {
+                match stack.last() {
+                    Some(JsonFrame::Object { key, .. }) => {
+                        path.push(PathSegment::Key(key.clone().unwrap_or_default()))
+                    }
+                    Some(JsonFrame::Array) => path.push(PathSegment::Index),
+                    None => {}
+                }
+                stack.push(if open == b'{' {
+                    JsonFrame::Object {
+                        key: None,
+                        keys: Vec::new(),
+                        expecting_key: true,
+                    }
+                } else {
+                    JsonFrame::Array
+                });
+                pos += 1;
+            }
+            b'}' | b']' => {
+                stack.pop();
+                path.pop();
+                pos += 1;
+            }
+            b':' => {
+                if let Some(JsonFrame::Object { expecting_key, .. }) = stack.last_mut() {
+                    *expecting_key = false;
+                }
+                pos += 1;
+            }
+            b',' => {
+                if let Some(JsonFrame::Object { expecting_key, .. }) = stack.last_mut() {
+                    *expecting_key = true;
+                }
+                pos += 1;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    let Some(JsonFrame::Object {
+        keys,
+        expecting_key: true,
+        ..
+    }) = stack.last()
+    else {
+        return None;
+    };
+    let start = match open_string {
+        Some(start) => start,
+        None => {
+            let start = word_start(text, cursor);
+            // Only bare words may precede the cursor outside a string
+            if !text[start..cursor].chars().all(is_key_char) {
+                return None;
+            }
+            start
+        }
+    };
+    // Swallow the rest of a key that's already there, closing quote included
+    let rest = &text[cursor..];
+    let tail = rest
+        .char_indices()
+        .find(|&(_, c)| !is_key_char(c))
+        .map(|(i, c)| if c == '"' { i + 1 } else { i })
+        .unwrap_or(rest.len());
+    Some(KeyContext {
+        path,
+        replace: start..cursor + tail,
+        existing: keys.clone(),
+        table_header: false,
+    })
+}
+
+fn yaml_context(text: &str, cursor: usize) -> Option<KeyContext> {
+    let line_start = text[..cursor].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let before = &text[line_start..cursor];
+    let indent = before.len() - before.trim_start_matches(' ').len();
+    let mut content = &before[indent..];
+    let mut key_col = indent;
+    let dash = content == "-" || content.starts_with("- ");
+    if dash {
+        let after_dash = content[1..].trim_start();
+        key_col = cursor - line_start - after_dash.len();
+        content = after_dash;
+    }
+    // A key is being typed only before any colon, quote, or flow syntax
+    if !content.chars().all(is_key_char) {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let mut col = key_col;
+    if dash {
+        path.push(PathSegment::Index);
+        col = indent;
+    }
+    let lines = yaml_lines(&text[..line_start]);
+    let mut existing = Vec::new();
+    // A new sequence item has no siblings above it
+    let mut in_block = !dash;
+    for line in lines.iter().rev() {
+        if in_block {
+            if line.key_col == key_col {
+                if let Some((key, _)) = &line.key {
+                    existing.push(key.clone());
+                }
+            }
+            in_block = line.key_col >= key_col && !(line.dash && line.col < key_col);
+        }
+        if line.key_col < col && line.inline_value.is_none() {
+            if let Some((key, _)) = &line.key {
+                path.push(PathSegment::Key(key.clone()));
+                col = line.key_col;
+            }
+        }
+        if line.dash && line.col < col {
+            path.push(PathSegment::Index);
+            col = line.col;
+        }
+    }
+    path.reverse();
+
+    // Siblings after the cursor line
+    let line_end = text[cursor..]
+        .find('\n')
+        .map(|i| cursor + i + 1)
+        .unwrap_or(text.len());
+    for line in yaml_lines(&text[line_end..]) {
+        if line.key_col < key_col || (line.dash && line.col < key_col) {
+            break;
+        }
+        if line.key_col == key_col && !line.dash {
+            if let Some((key, _)) = &line.key {
+                existing.push(key.clone());
+            }
+        }
+    }
+
+    let start = cursor - content.len();
+    let rest = &text[cursor..];
+    let tail = rest
+        .char_indices()
+        .find(|&(_, c)| !is_key_char(c))
+        .map(|(i, _)| i)
+        .unwrap_or(rest.len());
+    Some(KeyContext {
+        path,
+        replace: start..cursor + tail,
+        existing,
+        table_header: false,
+    })
+}
+
+/// Path segments of a dotted TOML key; quoted parts are unquoted
+fn toml_key_parts(key: &str) -> Vec<String> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn toml_context(text: &str, cursor: usize) -> Option<KeyContext> {
+    let line_start = text[..cursor].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let before = text[line_start..cursor].trim_start();
+
+    let (header, typed) = if let Some(header) = before.strip_prefix('[') {
+        (true, header.trim_start_matches('['))
+    } else {
+        (false, before)
+    };
+    if !typed
+        .chars()
+        .all(|c| is_key_char(c) || c == ' ' || c == '"')
+    {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let mut existing = Vec::new();
+    if !header {
+        // The table this line belongs to, and the keys already in it
+        for line in text[..line_start].lines().rev() {
+            let line = line.trim();
+            if let Some(table) = line.strip_prefix("[[") {
+                path.extend(
+                    toml_key_parts(table.trim_end_matches(']'))
+                        .into_iter()
+                        .map(PathSegment::Key),
+                );
+                path.push(PathSegment::Index);
+                break;
+            }
+            if let Some(table) = line.strip_prefix('[') {
+                path.extend(
+                    toml_key_parts(table.trim_end_matches(']'))
+                        .into_iter()
+                        .map(PathSegment::Key),
+                );
+                break;
+            }
+            if let Some((key, _)) = line.split_once('=') {
+                if !line.starts_with('#') {
+                    existing.extend(toml_key_parts(key).into_iter().take(1));
+                }
+            }
+        }
+    }
+
+    // `a.b.c` is typing `c` inside `a.b`
+    let dotted = typed.rfind('.').map(|i| &typed[..i]).unwrap_or("");
+    path.extend(toml_key_parts(dotted).into_iter().map(PathSegment::Key));
+    if !dotted.is_empty() {
+        existing.clear();
+    }
+
+    let start = word_start(text, cursor);
+    let rest = &text[cursor..];
+    let tail = rest
+        .char_indices()
+        .find(|&(_, c)| !is_key_char(c) || c == '.')
+        .map(|(i, _)| i)
+        .unwrap_or(rest.len());
+    Some(KeyContext {
+        path,
+        replace: start..cursor + tail,
+        existing,
+        table_header: header,
+    })
+}
+
+/// Where a key is being typed at `cursor`, if anywhere
+pub fn key_context(format: Format, text: &str, cursor: usize) -> Option<KeyContext> {
+    let cursor = cursor.min(text.len());
+    if !text.is_char_boundary(cursor) {
+        return None;
+    }
+    let mut context = match format {
+        Format::Json => json_context(text, cursor)?,
+        Format::Yaml => yaml_context(text, cursor)?,
+        Format::Toml => toml_context(text, cursor)?,
+    };
+    if !context.table_header {
+        if let Ok(document) = parse(format, text) {
+            context
+                .existing
+                .extend(keys_at(&document, &context.path, cursor));
+        }
+    }
+    Some(context)
+}
+
+// ============================================================================
+// Schema Lookup
+// ============================================================================
+
+/// Add `schema` and everything it composes to `out`
+fn expand<'a>(
+    validator: &Validator<'a>,
+    schema: &'a Value,
+    out: &mut Vec<&'a Value>,
+    depth: usize,
+) {
+    if depth > MAX_EXPANSION_DEPTH {
+        return;
+    }
+    let schema = validator.resolve(schema);
+    if !schema.is_object() || out.iter().any(|seen| std::ptr::eq(*seen, schema)) {
+        return;
+    }
+    out.push(schema);
+    for keyword in ["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(alternatives)) = schema.get(keyword) {
+            for alternative in alternatives {
+                expand(validator, alternative, out, depth + 1);
+            }
+        }
+    }
+    for keyword in ["then", "else"] {
+        if let Some(branch) = schema.get(keyword) {
+            expand(validator, branch, out, depth + 1);
+        }
+    }
+}
+
+/// Schemas that may apply at `path`
+fn schemas_at<'a>(
+    validator: &Validator<'a>,
+    root: &'a Value,
+    path: &[PathSegment],
+) -> Vec<&'a Value> {
+    let mut current = Vec::new();
+    expand(validator, root, &mut current, 0);
+    for segment in path {
+        let mut next = Vec::new();
+        for schema in &current {
+            match segment {
+                PathSegment::Key(key) => {
+                    if let Some(child) = schema.get("properties").and_then(|p| p.get(key)) {
+                        expand(validator, child, &mut next, 0);
+                        continue;
+                    }
+                    let mut matched = false;
+                    if let Some(patterns) =
+                        schema.get("patternProperties").and_then(Value::as_object)
+                    {
+                        for (pattern, child) in patterns {
+                            if regex::Regex::new(pattern).is_ok_and(|regex| regex.is_match(key)) {
+                                matched = true;
+                                expand(validator, child, &mut next, 0);
+                            }
+                        }
+                    }
+                    if !matched {
+                        if let Some(additional @ Value::Object(_)) =
+                            schema.get("additionalProperties")
+                        {
+                            expand(validator, additional, &mut next, 0);
+                        }
+                    }
+                }
+                PathSegment::Index => {
+                    match schema.get("items") {
+                        Some(Value::Array(items)) => items
+                            .iter()
+                            .for_each(|item| expand(validator, item, &mut next, 0)),
+                        Some(item) => expand(validator, item, &mut next, 0),
+                        None => {}
+                    }
+                    if let Some(Value::Array(items)) = schema.get("prefixItems") {
+                        items
+                            .iter()
+                            .for_each(|item| expand(validator, item, &mut next, 0));
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn schema_type(schema: &Value) -> Option<String> {
+    match schema.get("type") {
+        Some(Value::String(name)) => Some(name.clone()),
+        Some(Value::Array(names)) => Some(
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" | "),
+        ),
+        _ if schema.get("enum").is_some() => Some("enum".to_string()),
+        _ if schema.get("properties").is_some() => Some("object".to_string()),
+        _ => None,
+    }
+}
+
+/// Snippet for a key and a placeholder for its value
+fn insert_text(format: Format, key: &str, schema: &Value, table_header: bool) -> String {
+    let kind = schema_type(schema).unwrap_or_default();
+    let kind = kind.split(" | ").next().unwrap_or_default();
+    let default = schema
+        .get("default")
+        .or_else(|| schema.get("enum").and_then(|values| values.get(0)));
+    let default_text = |fallback: &str| match default {
+        Some(Value::String(value)) => value.replace(['$', '}'], ""),
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+        _ => fallback.to_string(),
+    };
+    match format {
+        Format::Json => {
+            let value = match kind {
+                "string" | "enum"
+                    if !matches!(default, Some(Value::Number(_) | Value::Bool(_))) =>
+                {
+                    format!("\"${{1:{}}}\"", default_text(""))
+                }
+                "object" => "{$1}".to_string(),
+                "array" => "[$1]".to_string(),
+                "boolean" => format!("${{1:{}}}", default_text("false")),
+                "number" | "integer" => format!("${{1:{}}}", default_text("0")),
+                _ => "$1".to_string(),
+            };
+            format!("\"{}\": {}", key, value)
+        }
+        Format::Yaml => match kind {
+            "object" => format!("{}:\n  $1", key),
+            "array" => format!("{}:\n  - $1", key),
+            _ => format!("{}: ${{1:{}}}", key, default_text("")),
+        },
+        Format::Toml => {
+            let key = if key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            {
+                key.to_string()
+            } else {
+                format!("\"{}\"", key)
+            };
+            if table_header {
+                return key;
+            }
+            match kind {
+                "string" | "enum"
+                    if !matches!(default, Some(Value::Number(_) | Value::Bool(_))) =>
+                {
+                    format!("{} = \"${{1:{}}}\"", key, default_text(""))
+                }
+                "object" => format!("{} = {{ $1 }}", key),
+                "array" => format!("{} = [$1]", key),
+                "boolean" => format!("{} = ${{1:{}}}", key, default_text("false")),
+                "number" | "integer" => format!("{} = ${{1:{}}}", key, default_text("0")),
+                _ => format!("{} = $1", key),
+            }
+        }
+    }
+}
+
+/// Properties the schema allows at `context` that aren't there yet
+pub fn complete(format: Format, schema: &Value, context: &KeyContext) -> Vec<SchemaCompletion> {
+    let validator = Validator::new(schema);
+    let mut completions: Vec<SchemaCompletion> = Vec::new();
+    for candidate in schemas_at(&validator, schema, &context.path) {
+        let Some(properties) = candidate.get("properties").and_then(Value::as_object) else {
+            continue;
+        };
+        let required: Vec<&str> = candidate
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for (key, property) in properties {
+            if context.existing.contains(key)
+                || completions
+                    .iter()
+                    .any(|completion| completion.label == *key)
+            {
+                continue;
+            }
+            let property = validator.resolve(property);
+            if property.get("doNotSuggest") == Some(&Value::Bool(true)) {
+                continue;
+            }
+            // Only tables make sense in a TOML header
+            if context.table_header
+                && !matches!(schema_type(property).as_deref(), Some("object" | "array"))
+            {
+                continue;
+            }
+            completions.push(SchemaCompletion {
+                label: key.clone(),
+                detail: schema_type(property),
+                documentation: property
+                    .get("markdownDescription")
+                    .or_else(|| property.get("description"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                insert_text: insert_text(format, key, property, context.table_header),
+                required: required.contains(&key.as_str()),
+                deprecated: property.get("deprecated") == Some(&Value::Bool(true))
+                    || property.get("deprecationMessage").is_some(),
+            });
+        }
+    }
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(format: Format, text: &str) -> KeyContext {
+        let cursor = text.find('|').unwrap();
+        let text = text.replace('|', "");
+        key_context(format, &text, cursor).unwrap()
+    }
+
+    fn key(name: &str) -> PathSegment {
+        PathSegment::Key(name.to_string())
+    }
+
+    #[test]
+    fn finds_the_object_at_the_cursor() {
+        let json = context(
+            Format::Json,
+            "{\"scripts\": {\"dev\": \"vite\"}, \"compilerOptions\": {\"st|\"}}",
+        );
+        assert_eq!(json.path, vec![key("compilerOptions")]);
+        assert_eq!(json.existing, Vec::<String>::new());
+
+        let yaml = context(
+            Format::Yaml,
+            "jobs:\n  build:\n    steps:\n      - uses: actions/checkout@v4\n        wi|\n",
+        );
+        assert_eq!(
+            yaml.path,
+            vec![key("jobs"), key("build"), key("steps"), PathSegment::Index]
+        );
+        assert_eq!(yaml.existing, vec!["uses".to_string()]);
+
+        let toml = context(
+            Format::Toml,
+            "[package]\nname = \"app\"\nver|\n\n[dependencies]\n",
+        );
+        assert_eq!(toml.path, vec![key("package")]);
+        assert!(toml.existing.contains(&"name".to_string()));
+
+        let header = context(Format::Toml, "[package]\n[pro|");
+        assert!(header.table_header);
+        assert!(header.path.is_empty());
+
+        assert!(key_context(Format::Json, "{\"a\": 1}", 6).is_none());
+    }
+
+    #[test]
+    fn offers_missing_properties_with_snippets() {
+        let schema = json!({
+            "properties": {
+                "package": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string", "description": "Package name" },
+                        "version": { "$ref": "#/definitions/version" },
+                        "publish": { "type": "boolean" }
+                    }
+                }
+            },
+            "definitions": { "version": { "type": "string", "default": "0.1.0" } }
+        });
+        let context = context(Format::Toml, "[package]\nname = \"app\"\n|\n");
+        let completions = complete(Format::Toml, &schema, &context);
+        let snippet = |label: &str| {
+            completions
+                .iter()
+                .find(|c| c.label == label)
+                .map(|c| c.insert_text.as_str())
+        };
+        assert_eq!(completions.len(), 2);
+        assert_eq!(snippet("version"), Some("version = \"${1:0.1.0}\""));
+        assert_eq!(snippet("publish"), Some("publish = ${1:false}"));
+    }
+}
@@ -0,0 +1,841 @@
+//! Documents With Source Ranges
+//!
+//! JSON, YAML, and TOML parsed into one tree whose nodes carry byte ranges, so
+//! schema errors can point at the offending key or value. JSON is parsed here
+//! (comments and trailing commas allowed, as in tsconfig), TOML through
+//! `toml`'s spans, and YAML through `serde_yaml` with ranges recovered from the
+//! indentation of each line.
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use toml::Spanned;
+
+/// Nesting deeper than this is rejected rather than risking the stack
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let name = Path::new(path)
+            .file_name()?
+            .to_string_lossy()
+            .to_lowercase();
+        let extension = name.rsplit('.').next().unwrap_or_default();
+        match extension {
+            "json" | "jsonc" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            // Dotfiles that are JSON without saying so
+            _ if matches!(name.as_str(), ".prettierrc" | ".babelrc" | ".eslintrc") => {
+                Some(Format::Json)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeValue {
+    Null,
+    Bool(bool),
+    Number { value: f64, integer: bool },
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<Property>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub key: String,
+    pub key_range: Range<usize>,
+    pub value: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub value: NodeValue,
+    pub range: Range<usize>,
+}
+
+impl Node {
+    fn new(value: NodeValue) -> Self {
+        Self { value, range: 0..0 }
+    }
+
+    /// The property `key` of an object node
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match &self.value {
+            NodeValue::Object(properties) => properties
+                .iter()
+                .find(|property| property.key == key)
+                .map(|property| &property.value),
+            _ => None,
+        }
+    }
+
+    /// Comparable JSON form, for `enum` and `const`
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        match &self.value {
+            NodeValue::Null => Value::Null,
+            NodeValue::Bool(value) => Value::Bool(*value),
+            NodeValue::Number { value, integer } => {
+                if *integer && value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+                    Value::from(*value as i64)
+                } else {
+                    serde_json::Number::from_f64(*value)
+                        .map(Value::Number)
+                        .unwrap_or(Value::Null)
+                }
+            }
+            NodeValue::String(value) => Value::String(value.clone()),
+            NodeValue::Array(items) => Value::Array(items.iter().map(Node::to_json).collect()),
+            NodeValue::Object(properties) => Value::Object(
+                properties
+                    .iter()
+                    .map(|property| (property.key.clone(), property.value.to_json()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub range: Range<usize>,
+}
+
+/// Parse `text`; whitespace-only documents parse as `null`
+pub fn parse(format: Format, text: &str) -> Result<Node, ParseError> {
+    if text.trim().is_empty() {
+        return Ok(Node {
+            value: NodeValue::Null,
+            range: 0..text.len(),
+        });
+    }
+    match format {
+        Format::Json => parse_json(text),
+        Format::Yaml => parse_yaml(text),
+        Format::Toml => parse_toml(text),
+    }
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let end = self.text[self.pos..]
+            .chars()
+            .next()
+            .map(|c| self.pos + c.len_utf8())
+            .unwrap_or(self.pos);
+        ParseError {
+            message: message.to_string(),
+            range: self.pos..end,
+        }
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') if self.text[self.pos..].starts_with("//") => {
+                    self.pos = self.text[self.pos..]
+                        .find('\n')
+                        .map(|offset| self.pos + offset)
+                        .unwrap_or(self.text.len());
+                }
+                Some(b'/') if self.text[self.pos..].starts_with("/*") => {
+                    match self.text[self.pos + 2..].find("*/") {
+                        Some(offset) => self.pos += offset + 4,
+                        None => return Err(self.error("Unterminated comment")),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Node, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("Document is nested too deeply"));
+        }
+        let start = self.pos;
+        let value = match self.peek() {
+            Some(b'{') => self.object(depth)?,
+            Some(b'[') => self.array(depth)?,
+            Some(b'"') => NodeValue::String(self.string()?),
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(b't') => self.literal("true", NodeValue::Bool(true))?,
+            Some(b'f') => self.literal("false", NodeValue::Bool(false))?,
+            Some(b'n') => self.literal("null", NodeValue::Null)?,
+            Some(_) => return Err(self.error("Value expected")),
+            None => return Err(self.error("Unexpected end of file")),
+        };
+        Ok(Node {
+            value,
+            range: start..self.pos,
+        })
+    }
+
+    fn literal(&mut self, word: &str, value: NodeValue) -> Result<NodeValue, ParseError> {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("Value expected"))
+        }
+    }
+
+    fn number(&mut self) -> Result<NodeValue, ParseError> {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.pos += 1;
+        }
+        let raw = &self.text[start..self.pos];
+        match raw.parse::<f64>() {
+            Ok(value) => Ok(NodeValue::Number {
+                value,
+                integer: !raw.contains(['.', 'e', 'E']),
+            }),
+            Err(_) => {
+                self.pos = start;
+                Err(self.error("Invalid number"))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.text[self.pos..].chars().next() else {
+                self.pos = start;
+                return Err(self.error("Unterminated string"));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(value),
+                '\n' => {
+                    self.pos = start;
+                    return Err(self.error("Unterminated string"));
+                }
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("Unterminated string"))?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => value.push('"'),
+                        b'\\' => value.push('\\'),
+                        b'/' => value.push('/'),
+                        b'b' => value.push('\u{8}'),
+                        b'f' => value.push('\u{c}'),
+                        b'n' => value.push('\n'),
+                        b'r' => value.push('\r'),
+                        b't' => value.push('\t'),
+                        b'u' => value.push(self.unicode_escape()?),
+                        _ => {
+                            self.pos -= 2;
+                            return Err(self.error("Invalid escape character"));
+                        }
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| self.error("Invalid unicode escape"))?;
+        let code =
+            u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.hex4()?;
+        if (0xD800..0xDC00).contains(&high) && self.text[self.pos..].starts_with("\\u") {
+            self.pos += 2;
+            let low = self.hex4()?;
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+            return Ok(char::from_u32(code).unwrap_or('\u{FFFD}'));
+        }
+        Ok(char::from_u32(high).unwrap_or('\u{FFFD}'))
+    }
+
+    fn object(&mut self, depth: usize) -> Result<NodeValue, ParseError> {
+        self.pos += 1;
+        let mut properties = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(NodeValue::Object(properties));
+                }
+                Some(b'"') => {}
+                _ => return Err(self.error("Property name expected")),
+            }
+            let key_start = self.pos;
+            let key = self.string()?;
+            let key_range = key_start..self.pos;
+            self.skip_trivia()?;
+            if self.peek() != Some(b':') {
+                return Err(self.error("Colon expected"));
+            }
+            self.pos += 1;
+            self.skip_trivia()?;
+            let value = self.value(depth + 1)?;
+            properties.push(Property {
+                key,
+                key_range,
+                value,
+            });
+            self.skip_trivia()?;
+            match self.peek() {
+                // A trailing comma is tolerated, as tsconfig allows
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("Comma expected")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<NodeValue, ParseError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(NodeValue::Array(items));
+            }
+            items.push(self.value(depth + 1)?);
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("Comma expected")),
+            }
+        }
+    }
+}
+
+fn parse_json(text: &str) -> Result<Node, ParseError> {
+    let mut parser = JsonParser { text, pos: 0 };
+    // A byte order mark isn't content
+    if text.starts_with('\u{feff}') {
+        parser.pos = 3;
+    }
+    parser.skip_trivia()?;
+    let node = parser.value(0)?;
+    parser.skip_trivia()?;
+    if parser.pos < text.len() {
+        return Err(parser.error("End of file expected"));
+    }
+    Ok(node)
+}
+
+// ============================================================================
+// TOML
+// ============================================================================
+
+/// Field name under which `toml` hands out datetimes
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// A TOML value whose children carry their spans
+struct TomlNode(Node);
+
+fn spanned_node(spanned: Spanned<TomlNode>) -> Node {
+    let range = spanned.span();
+    let mut node = spanned.into_inner().0;
+    node.range = range;
+    node
+}
+
+impl<'de> Deserialize<'de> for TomlNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TomlVisitor)
+    }
+}
+
+struct TomlVisitor;
+
+impl<'de> Visitor<'de> for TomlVisitor {
+    type Value = TomlNode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a TOML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<TomlNode, E> {
+        Ok(TomlNode(Node::new(NodeValue::Bool(value))))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<TomlNode, E> {
+        Ok(TomlNode(Node::new(NodeValue::Number {
+            value: value as f64,
+            integer: true,
+        })))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<TomlNode, E> {
+        self.visit_i64(value as i64)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<TomlNode, E> {
+        Ok(TomlNode(Node::new(NodeValue::Number {
+            value,
+            integer: false,
+        })))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<TomlNode, E> {
+        Ok(TomlNode(Node::new(NodeValue::String(value.to_string()))))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TomlNode, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element::<Spanned<TomlNode>>()? {
+            items.push(spanned_node(item));
+        }
+        Ok(TomlNode(Node::new(NodeValue::Array(items))))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TomlNode, A::Error> {
+        let mut properties = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == TOML_DATETIME_FIELD {
+                let datetime = map.next_value::<String>()?;
+                return Ok(TomlNode(Node::new(NodeValue::String(datetime))));
+            }
+            let value = spanned_node(map.next_value::<Spanned<TomlNode>>()?);
+            properties.push(Property {
+                key,
+                // Refined from the source text once parsing is done
+                key_range: value.range.clone(),
+                value,
+            });
+        }
+        Ok(TomlNode(Node::new(NodeValue::Object(properties))))
+    }
+}
+
+/// Point key ranges at the key text preceding each value, falling back to the
+/// value when the key can't be found (e.g. for table headers)
+fn locate_toml_keys(text: &str, node: &mut Node) {
+    match &mut node.value {
+        NodeValue::Object(properties) => {
+            for property in properties {
+                let start = property.value.range.start.min(text.len());
+                let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                let window = &text[line_start..start];
+                if let Some(offset) = window.rfind(property.key.as_str()) {
+                    let key_start = line_start + offset;
+                    property.key_range = key_start..key_start + property.key.len();
+                }
+                locate_toml_keys(text, &mut property.value);
+            }
+        }
+        NodeValue::Array(items) => items
+            .iter_mut()
+            .for_each(|item| locate_toml_keys(text, item)),
+        _ => {}
+    }
+}
+
+fn parse_toml(text: &str) -> Result<Node, ParseError> {
+    match toml::from_str::<TomlNode>(text) {
+        Ok(TomlNode(mut node)) => {
+            node.range = 0..text.len();
+            locate_toml_keys(text, &mut node);
+            Ok(node)
+        }
+        Err(e) => Err(ParseError {
+            message: e.message().to_string(),
+            range: e.span().unwrap_or(0..0),
+        }),
+    }
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+/// What matters about one line of YAML for locating nodes
+#[derive(Debug, Clone)]
+pub(crate) struct YamlLine {
+    pub start: usize,
+    pub end: usize,
+    /// Column of the first character (the dash, for sequence items)
+    pub col: usize,
+    pub dash: bool,
+    /// Column where the content after any dash begins
+    pub key_col: usize,
+    pub key: Option<(String, Range<usize>)>,
+    /// Byte range of an inline value after the key or dash
+    pub inline_value: Option<Range<usize>>,
+}
+
+/// Offset of the `:` ending a mapping key on this line, outside quotes
+fn key_colon(content: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in content.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if i == 0 => quote = Some(c),
+            (None, '#') if i == 0 || content[..i].ends_with(' ') => return None,
+            (None, ':') => {
+                let rest = &content[i + 1..];
+                if rest.is_empty() || rest.starts_with([' ', '\t']) {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// End of `range` in `text` without a trailing comment or whitespace
+fn trim_comment(text: &str, range: Range<usize>) -> Range<usize> {
+    let slice = &text[range.clone()];
+    let without_comment = match slice.find(" #") {
+        Some(offset) if !slice.starts_with(['"', '\'']) => &slice[..offset],
+        _ => slice,
+    };
+    range.start..range.start + without_comment.trim_end().len()
+}
+
+pub(crate) fn yaml_lines(text: &str) -> Vec<YamlLine> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for raw in text.split_inclusive('\n') {
+        let end = start + raw.trim_end_matches(['\n', '\r']).len();
+        let line = &text[start..end];
+        let col = line.len() - line.trim_start_matches(' ').len();
+        let content = &line[col..];
+        if content.is_empty() || content.starts_with('#') || content == "---" {
+            start += raw.len();
+            continue;
+        }
+
+        let dash = content == "-" || content.starts_with("- ");
+        let key_col = if dash {
+            col + 1 + (content.len() - 1 - content[1..].trim_start().len())
+        } else {
+            col
+        };
+        let content = &line[key_col.min(line.len())..];
+        let content_start = start + key_col.min(line.len());
+        let key = key_colon(content).map(|colon| {
+            let raw_key = content[..colon].trim_end();
+            let name = raw_key.trim_matches(['"', '\'']).to_string();
+            (name, content_start..content_start + raw_key.len())
+        });
+        let value_start = match &key {
+            Some((_, range)) => {
+                let after = range.end + 1;
+                after + (text[after..end].len() - text[after..end].trim_start().len())
+            }
+            None => content_start,
+        };
+        let inline_value = (value_start < end)
+            .then(|| trim_comment(text, value_start..end))
+            .filter(|range| !range.is_empty());
+
+        lines.push(YamlLine {
+            start,
+            end,
+            col,
+            dash,
+            key_col,
+            key,
+            inline_value,
+        });
+        start += raw.len();
+    }
+    lines
+}
+
+fn yaml_node(value: serde_yaml::Value) -> Node {
+    use serde_yaml::Value;
+    Node::new(match value {
+        Value::Null => NodeValue::Null,
+        Value::Bool(value) => NodeValue::Bool(value),
+        Value::Number(number) => NodeValue::Number {
+            value: number.as_f64().unwrap_or_default(),
+            integer: number.is_i64() || number.is_u64(),
+        },
+        Value::String(value) => NodeValue::String(value),
+        Value::Sequence(items) => NodeValue::Array(items.into_iter().map(yaml_node).collect()),
+        Value::Mapping(mapping) => NodeValue::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| Property {
+                    key: match key {
+                        Value::String(key) => key,
+                        other => serde_yaml::to_string(&other)
+                            .unwrap_or_default()
+                            .trim()
+                            .to_string(),
+                    },
+                    key_range: 0..0,
+                    value: yaml_node(value),
+                })
+                .collect(),
+        ),
+        Value::Tagged(tagged) => return yaml_node(tagged.value),
+    })
+}
+
+/// End of the block starting at `lines[index]` whose content sits at `col`:
+/// the index of the first later line that isn't nested under it
+fn block_end(lines: &[YamlLine], index: usize, col: usize, limit: usize) -> usize {
+    (index + 1..limit)
+        .find(|&i| lines[i].col < col || (lines[i].col == col && !lines[i].dash))
+        .unwrap_or(limit)
+}
+
+/// Assign ranges to `node`, which spans `lines[first..limit]`
+fn locate_yaml(lines: &[YamlLine], node: &mut Node, first: usize, limit: usize) {
+    if first >= limit {
+        return;
+    }
+    node.range = lines[first].start..lines[limit - 1].end;
+    match &mut node.value {
+        NodeValue::Object(properties) => {
+            let col = lines[first].key_col;
+            let mut cursor = first;
+            for property in properties.iter_mut() {
+                let Some(index) = (cursor..limit).find(|&i| {
+                    lines[i].key_col == col
+                        && lines[i]
+                            .key
+                            .as_ref()
+                            .is_some_and(|(key, _)| *key == property.key)
+                }) else {
+                    // Flow mappings and unusual layouts keep the parent's range
+                    property.key_range = node.range.clone();
+                    property.value.range = node.range.clone();
+                    continue;
+                };
+                cursor = index + 1;
+                let line = &lines[index];
+                property.key_range = line.key.as_ref().map(|(_, range)| range.clone()).unwrap();
+                match &line.inline_value {
+                    Some(range) => property.value.range = range.clone(),
+                    None => {
+                        let end = block_end(lines, index, col, limit);
+                        locate_yaml(lines, &mut property.value, index + 1, end);
+                    }
+                }
+            }
+        }
+        NodeValue::Array(items) => {
+            let col = lines[first].col;
+            let starts: Vec<usize> = (first..limit)
+                .filter(|&i| lines[i].dash && lines[i].col == col)
+                .collect();
+            for (item, &start) in items.iter_mut().zip(&starts) {
+                let end = (start + 1..limit)
+                    .find(|&i| lines[i].col <= col)
+                    .unwrap_or(limit);
+                let line = &lines[start];
+                match (&line.key, &line.inline_value) {
+                    // `- key: value` starts a mapping on the dash line
+                    (Some(_), _) => locate_yaml(lines, item, start, end),
+                    (None, Some(range)) => item.range = range.clone(),
+                    (None, None) => locate_yaml(lines, item, start + 1, end),
+                }
+            }
+        }
+        _ => {
+            if let Some(range) = &lines[first].inline_value {
+                node.range = range.clone();
+            }
+        }
+    }
+}
+
+fn parse_yaml(text: &str) -> Result<Node, ParseError> {
+    let value = serde_yaml::from_str::<serde_yaml::Value>(text).map_err(|e| {
+        let start = e.location().map(|location| location.index()).unwrap_or(0);
+        let start = start.min(text.len());
+        let end = text[start..]
+            .chars()
+            .next()
+            .map(|c| start + c.len_utf8())
+            .unwrap_or(start);
+        ParseError {
+            message: e.to_string(),
+            range: start..end,
+        }
+    })?;
+    let mut node = yaml_node(value);
+    let lines = yaml_lines(text);
+    locate_yaml(&lines, &mut node, 0, lines.len());
+    Ok(node)
+}
+
+// ============================================================================
+// Positions
+// ============================================================================
+
+/// Converts between byte offsets and zero-based line/UTF-16 column positions
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> (u32, u32) {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let column = self
+            .text
+            .get(start..offset)
+            .unwrap_or_default()
+            .encode_utf16()
+            .count();
+        (line as u32, column as u32)
+    }
+
+    pub fn offset(&self, line: u32, character: u32) -> usize {
+        let Some(&start) = self.line_starts.get(line as usize) else {
+            return self.text.len();
+        };
+        let end = self
+            .line_starts
+            .get(line as usize + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        let mut units = 0;
+        for (i, c) in self.text[start..end].char_indices() {
+            if units >= character as usize || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_text<'a>(text: &'a str, node: &Node, key: &str) -> &'a str {
+        let NodeValue::Object(properties) = &node.value else {
+            panic!("not an object");
+        };
+        let property = properties.iter().find(|p| p.key == key).unwrap();
+        &text[property.key_range.clone()]
+    }
+
+    #[test]
+    fn parses_jsonc_with_ranges() {
+        let text = "{\n  // comment\n  \"name\": \"app\",\n  \"list\": [1, 2.5,],\n}";
+        let node = parse(Format::Json, text).unwrap();
+        assert_eq!(key_text(text, &node, "name"), "\"name\"");
+        let list = node.get("list").unwrap();
+        assert_eq!(&text[list.range.clone()], "[1, 2.5,]");
+        assert_eq!(list.to_json(), serde_json::json!([1, 2.5]));
+
+        let error = parse(Format::Json, "{\"a\": 1 \"b\": 2}").unwrap_err();
+        assert_eq!(error.message, "Comma expected");
+        assert_eq!(error.range, 8..9);
+    }
+
+    #[test]
+    fn locates_yaml_nodes_from_indentation() {
+        let text = "name: CI\non:\n  push:\njobs:\n  build:\n    steps:\n    - uses: actions/checkout@v4\n    - run: cargo test # tests\n";
+        let node = parse(Format::Yaml, text).unwrap();
+        let steps = node
+            .get("jobs")
+            .and_then(|jobs| jobs.get("build"))
+            .and_then(|build| build.get("steps"))
+            .unwrap();
+        let NodeValue::Array(items) = &steps.value else {
+            panic!("steps should be a sequence");
+        };
+        assert_eq!(key_text(text, &items[1], "run"), "run");
+        assert_eq!(
+            &text[items[1].get("run").unwrap().range.clone()],
+            "cargo test"
+        );
+        assert_eq!(&text[node.get("name").unwrap().range.clone()], "CI");
+    }
+
+    #[test]
+    fn parses_toml_with_spans() {
+        let text = "[package]\nname = \"app\"\nversion = 1\nedition = 2021-01-01\n";
+        let node = parse(Format::Toml, text).unwrap();
+        let package = node.get("package").unwrap();
+        assert_eq!(key_text(text, package, "version"), "version");
+        assert_eq!(&text[package.get("name").unwrap().range.clone()], "\"app\"");
+        // TOML datetimes are validated as strings
+        assert!(matches!(
+            package.get("edition").unwrap().value,
+            NodeValue::String(_)
+        ));
+
+        assert!(parse(Format::Toml, "[package\nname = 1").is_err());
+    }
+
+    #[test]
+    fn converts_offsets_and_positions() {
+        let index = LineIndex::new("ab\n\u{1F600}x\n");
+        assert_eq!(index.position(3), (1, 0));
+        assert_eq!(index.position(7), (1, 2));
+        assert_eq!(index.offset(1, 2), 7);
+        assert_eq!(index.offset(5, 0), 9);
+    }
+}
@@ -0,0 +1,15 @@
+//! Schema Language Services
+//!
+//! Built-in validation and key completion for JSON, YAML, and TOML files with
+//! a known schema, without an external language server:
+//! - `document` - Parsing into a tree with source ranges
+//! - `validator` - JSON Schema validation
+//! - `completion` - Cursor context and key suggestions
+//! - `catalog` - Schema associations (package.json, tsconfig, tauri.conf.json,
+//!   GitHub workflows, ...) and the download cache
+
+pub mod catalog;
+pub mod commands;
+pub mod completion;
+pub mod document;
+pub mod validator;
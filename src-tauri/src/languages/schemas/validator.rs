@@ -0,0 +1,632 @@
+//! JSON Schema Validation
+//!
+//! Validates a parsed document against a JSON Schema, reporting errors at the
+//! key or value they concern. Covers the keywords the common config schemas
+//! rely on; unknown keywords, `format`, and remote `$ref`s are ignored rather
+//! than guessed at, so a gap means a missed error, never a false one.
+
+use crate::languages::schemas::document::{Node, NodeValue};
+use regex::Regex;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// `$ref` chains deeper than this are treated as valid, to survive cycles
+const MAX_DEPTH: usize = 64;
+
+/// Enum values listed in an error message
+const MAX_LISTED_VALUES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    pub message: String,
+    pub range: Range<usize>,
+}
+
+pub struct Validator<'a> {
+    root: &'a Value,
+    patterns: RefCell<HashMap<String, Option<Regex>>>,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(root: &'a Value) -> Self {
+        Self {
+            root,
+            patterns: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn validate(&self, node: &Node) -> Vec<SchemaError> {
+        let mut errors = Vec::new();
+        // Errors about the document as a whole go on its first character
+        let start = node.range.start;
+        self.check(self.root, node, &(start..start + 1), &mut errors, 0);
+        errors
+    }
+
+    /// Follow a local `$ref` to the schema it names
+    pub fn resolve(&self, schema: &'a Value) -> &'a Value {
+        let mut schema = schema;
+        for _ in 0..MAX_DEPTH {
+            match schema.get("$ref").and_then(Value::as_str) {
+                Some(reference) => match self.lookup(reference) {
+                    Some(target) => schema = target,
+                    None => break,
+                },
+                None => break,
+            }
+        }
+        schema
+    }
+
+    fn lookup(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        if pointer.is_empty() {
+            return Some(self.root);
+        }
+        self.root.pointer(pointer)
+    }
+
+    fn pattern_matches(&self, pattern: &str, value: &str) -> Option<bool> {
+        let mut patterns = self.patterns.borrow_mut();
+        // ECMAScript-only syntax (lookaround etc.) won't compile; such patterns
+        // are skipped
+        let regex = patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok());
+        regex.as_ref().map(|regex| regex.is_match(value))
+    }
+
+    /// Whether the node's type is one `schema` (after `$ref`) allows,
+    /// ignoring everything else
+    fn type_matches(&self, schema: &'a Value, node: &Node) -> bool {
+        declared_type_matches(self.resolve(schema), node)
+    }
+
+    fn check(
+        &self,
+        schema: &'a Value,
+        node: &Node,
+        anchor: &Range<usize>,
+        errors: &mut Vec<SchemaError>,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                errors.push(error("Value is not allowed.", &node.range));
+                return;
+            }
+            Value::Object(_) => schema,
+            _ => return,
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(target) = self.lookup(reference) {
+                self.check(target, node, anchor, errors, depth + 1);
+            }
+        }
+
+        if let Some(message) = deprecation(schema) {
+            errors.push(error(&message, anchor));
+        }
+
+        if !declared_type_matches(schema, node) {
+            let expected = match schema.get("type") {
+                Some(Value::Array(names)) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                Some(Value::String(name)) => name.clone(),
+                _ => String::new(),
+            };
+            errors.push(error(
+                &format!("Incorrect type. Expected \"{}\".", expected),
+                &node.range,
+            ));
+            return;
+        }
+
+        self.check_value(schema, node, errors);
+        match &node.value {
+            NodeValue::String(value) => self.check_string(schema, value, node, errors),
+            NodeValue::Number { value, .. } => check_number(schema, *value, node, errors),
+            NodeValue::Array(items) => self.check_array(schema, items, node, errors, depth),
+            NodeValue::Object(_) => self.check_object(schema, node, anchor, errors, depth),
+            _ => {}
+        }
+        self.check_composition(schema, node, anchor, errors, depth);
+    }
+
+    fn check_value(&self, schema: &Value, node: &Node, errors: &mut Vec<SchemaError>) {
+        if let Some(constant) = schema.get("const") {
+            if node.to_json() != *constant {
+                errors.push(error(&format!("Value must be {}.", constant), &node.range));
+            }
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            let value = node.to_json();
+            if !values.contains(&value) {
+                let mut listed: Vec<String> = values
+                    .iter()
+                    .take(MAX_LISTED_VALUES)
+                    .map(Value::to_string)
+                    .collect();
+                if values.len() > MAX_LISTED_VALUES {
+                    listed.push("…".to_string());
+                }
+                errors.push(error(
+                    &format!(
+                        "Value is not accepted. Valid values: {}.",
+                        listed.join(", ")
+                    ),
+                    &node.range,
+                ));
+            }
+        }
+    }
+
+    fn check_string(
+        &self,
+        schema: &Value,
+        value: &str,
+        node: &Node,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        let length = value.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                errors.push(error(
+                    &format!("String is shorter than the minimum length of {}.", min),
+                    &node.range,
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                errors.push(error(
+                    &format!("String is longer than the maximum length of {}.", max),
+                    &node.range,
+                ));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if self.pattern_matches(pattern, value) == Some(false) {
+                let message = schema
+                    .get("patternErrorMessage")
+                    .or_else(|| schema.get("errorMessage"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| {
+                        format!("String does not match the pattern of \"{}\".", pattern)
+                    });
+                errors.push(error(&message, &node.range));
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &'a Value,
+        items: &[Node],
+        node: &Node,
+        errors: &mut Vec<SchemaError>,
+        depth: usize,
+    ) {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                errors.push(error(
+                    &format!("Array has too few items. Expected {} or more.", min),
+                    &node.range,
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                errors.push(error(
+                    &format!("Array has too many items. Expected {} or fewer.", max),
+                    &node.range,
+                ));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let values: Vec<Value> = items.iter().map(Node::to_json).collect();
+            for (index, item) in items.iter().enumerate() {
+                if values[..index].contains(&values[index]) {
+                    errors.push(error("Array has duplicate items.", &item.range));
+                }
+            }
+        }
+
+        // Draft 2020-12 `prefixItems`, or a draft-07 `items` array, for tuples
+        let tuple = schema
+            .get("prefixItems")
+            .or_else(|| schema.get("items").filter(|items| items.is_array()))
+            .and_then(Value::as_array);
+        let rest = match tuple {
+            Some(_) if schema.get("prefixItems").is_some() => schema.get("items"),
+            Some(_) => schema.get("additionalItems"),
+            None => schema.get("items"),
+        };
+        let prefix = tuple.map(Vec::len).unwrap_or(0);
+        for (index, item) in items.iter().enumerate() {
+            let item_schema = if index < prefix {
+                tuple.and_then(|tuple| tuple.get(index))
+            } else {
+                rest
+            };
+            if let Some(item_schema) = item_schema {
+                self.check(item_schema, item, &item.range, errors, depth + 1);
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &'a Value,
+        node: &Node,
+        anchor: &Range<usize>,
+        errors: &mut Vec<SchemaError>,
+        depth: usize,
+    ) {
+        let NodeValue::Object(properties) = &node.value else {
+            return;
+        };
+        let declared = schema.get("properties").and_then(Value::as_object);
+        let patterns = schema.get("patternProperties").and_then(Value::as_object);
+
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !properties.iter().any(|property| property.key == name) {
+                    errors.push(error(&format!("Missing property \"{}\".", name), anchor));
+                }
+            }
+        }
+
+        for property in properties {
+            let mut matched = false;
+            if let Some(property_schema) = declared.and_then(|declared| declared.get(&property.key))
+            {
+                matched = true;
+                self.check(
+                    property_schema,
+                    &property.value,
+                    &property.key_range,
+                    errors,
+                    depth + 1,
+                );
+            }
+            for (pattern, property_schema) in patterns.into_iter().flatten() {
+                if self.pattern_matches(pattern, &property.key) == Some(true) {
+                    matched = true;
+                    self.check(
+                        property_schema,
+                        &property.value,
+                        &property.key_range,
+                        errors,
+                        depth + 1,
+                    );
+                }
+            }
+            if matched {
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => errors.push(error(
+                    &format!("Property {} is not allowed.", property.key),
+                    &property.key_range,
+                )),
+                Some(additional @ Value::Object(_)) => self.check(
+                    additional,
+                    &property.value,
+                    &property.key_range,
+                    errors,
+                    depth + 1,
+                ),
+                _ => {}
+            }
+        }
+
+        let count = properties.len() as u64;
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                errors.push(error(
+                    &format!(
+                        "Object has fewer properties than the required number of {}.",
+                        min
+                    ),
+                    anchor,
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                errors.push(error(
+                    &format!("Object has more properties than the limit of {}.", max),
+                    anchor,
+                ));
+            }
+        }
+    }
+
+    fn check_composition(
+        &self,
+        schema: &'a Value,
+        node: &Node,
+        anchor: &Range<usize>,
+        errors: &mut Vec<SchemaError>,
+        depth: usize,
+    ) {
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub_schema in all {
+                self.check(sub_schema, node, anchor, errors, depth + 1);
+            }
+        }
+
+        // `oneOf` is checked like `anyOf`: many published schemas have
+        // overlapping alternatives, and "matches more than one" is noise
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(alternatives)) = schema.get(keyword) {
+                self.check_alternatives(alternatives, node, anchor, errors, depth);
+            }
+        }
+
+        if let Some(not) = schema.get("not") {
+            let mut not_errors = Vec::new();
+            self.check(not, node, anchor, &mut not_errors, depth + 1);
+            if not_errors.is_empty() {
+                errors.push(error("Matches a schema that is not allowed.", &node.range));
+            }
+        }
+
+        if let Some(condition) = schema.get("if") {
+            let mut condition_errors = Vec::new();
+            self.check(condition, node, anchor, &mut condition_errors, depth + 1);
+            let branch = if condition_errors.is_empty() {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.check(branch, node, anchor, errors, depth + 1);
+            }
+        }
+    }
+
+    /// Report the errors of the closest alternative when none matches, so the
+    /// user sees what to fix rather than a bare "no match"
+    fn check_alternatives(
+        &self,
+        alternatives: &'a [Value],
+        node: &Node,
+        anchor: &Range<usize>,
+        errors: &mut Vec<SchemaError>,
+        depth: usize,
+    ) {
+        let mut best: Option<(bool, Vec<SchemaError>)> = None;
+        for alternative in alternatives {
+            let type_matches = self.type_matches(alternative, node);
+            let mut alternative_errors = Vec::new();
+            self.check(
+                alternative,
+                node,
+                anchor,
+                &mut alternative_errors,
+                depth + 1,
+            );
+            if alternative_errors.is_empty() {
+                return;
+            }
+            let better = match &best {
+                None => true,
+                Some((best_type_matches, best_errors)) => {
+                    (type_matches, std::cmp::Reverse(alternative_errors.len()))
+                        > (*best_type_matches, std::cmp::Reverse(best_errors.len()))
+                }
+            };
+            if better {
+                best = Some((type_matches, alternative_errors));
+            }
+        }
+
+        match best {
+            Some((true, best_errors)) => errors.extend(best_errors),
+            Some((false, _)) => {
+                let mut expected: Vec<&str> = alternatives
+                    .iter()
+                    .filter_map(|alternative| self.resolve(alternative).get("type"))
+                    .flat_map(|types| match types {
+                        Value::String(name) => vec![name.as_str()],
+                        Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                        _ => Vec::new(),
+                    })
+                    .collect();
+                expected.dedup();
+                errors.push(error(
+                    &format!("Incorrect type. Expected one of {}.", expected.join(", ")),
+                    &node.range,
+                ));
+            }
+            None => {}
+        }
+    }
+}
+
+fn error(message: &str, range: &Range<usize>) -> SchemaError {
+    SchemaError {
+        message: message.to_string(),
+        range: range.clone(),
+    }
+}
+
+fn type_is(name: &str, node: &Node) -> bool {
+    match (name, &node.value) {
+        ("null", NodeValue::Null)
+        | ("boolean", NodeValue::Bool(_))
+        | ("string", NodeValue::String(_))
+        | ("array", NodeValue::Array(_))
+        | ("object", NodeValue::Object(_))
+        | ("number", NodeValue::Number { .. }) => true,
+        ("integer", NodeValue::Number { value, .. }) => value.fract() == 0.0,
+        _ => false,
+    }
+}
+
+/// Whether the node's type is one `schema` itself declares
+fn declared_type_matches(schema: &Value, node: &Node) -> bool {
+    match schema.get("type") {
+        Some(Value::String(name)) => type_is(name, node),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| type_is(name, node)),
+        _ => true,
+    }
+}
+
+/// The message for a deprecated property, if `schema` marks it so
+fn deprecation(schema: &Value) -> Option<String> {
+    if let Some(message) = schema.get("deprecationMessage").and_then(Value::as_str) {
+        return Some(message.to_string());
+    }
+    (schema.get("deprecated") == Some(&Value::Bool(true)))
+        .then(|| "Value is deprecated.".to_string())
+}
+
+fn check_number(schema: &Value, value: f64, node: &Node, errors: &mut Vec<SchemaError>) {
+    let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    // Draft 4 spells exclusive bounds as booleans next to minimum/maximum
+    let exclusive = |keyword: &str| schema.get(keyword) == Some(&Value::Bool(true));
+
+    if let Some(min) = bound("minimum") {
+        if value < min || (exclusive("exclusiveMinimum") && value == min) {
+            errors.push(error(
+                &format!("Value is below the minimum of {}.", min),
+                &node.range,
+            ));
+        }
+    }
+    if let Some(max) = bound("maximum") {
+        if value > max || (exclusive("exclusiveMaximum") && value == max) {
+            errors.push(error(
+                &format!("Value is above the maximum of {}.", max),
+                &node.range,
+            ));
+        }
+    }
+    if let Some(min) = bound("exclusiveMinimum") {
+        if value <= min {
+            errors.push(error(
+                &format!("Value must be greater than {}.", min),
+                &node.range,
+            ));
+        }
+    }
+    if let Some(max) = bound("exclusiveMaximum") {
+        if value >= max {
+            errors.push(error(
+                &format!("Value must be less than {}.", max),
+                &node.range,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::schemas::document::{parse, Format};
+    use serde_json::json;
+
+    fn messages(schema: &Value, format: Format, text: &str) -> Vec<(String, String)> {
+        let node = parse(format, text).unwrap();
+        Validator::new(schema)
+            .validate(&node)
+            .into_iter()
+            .map(|error| (error.message, text[error.range].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_errors_at_keys_and_values() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string", "pattern": "^[a-z-]+$" },
+                "private": { "type": "boolean" },
+                "engines": { "$ref": "#/definitions/engines" },
+                "old": { "deprecationMessage": "Use new instead." }
+            },
+            "additionalProperties": false,
+            "definitions": {
+                "engines": { "type": "object", "additionalProperties": { "type": "string" } }
+            }
+        });
+        let text = r#"{"name": "App", "private": "yes", "engines": {"node": 20}, "old": 1, "extra": true}"#;
+        assert_eq!(
+            messages(&schema, Format::Json, text),
+            vec![
+                (
+                    "String does not match the pattern of \"^[a-z-]+$\".".to_string(),
+                    "\"App\"".to_string()
+                ),
+                (
+                    "Incorrect type. Expected \"boolean\".".to_string(),
+                    "\"yes\"".to_string()
+                ),
+                (
+                    "Incorrect type. Expected \"string\".".to_string(),
+                    "20".to_string()
+                ),
+                ("Use new instead.".to_string(), "\"old\"".to_string()),
+                (
+                    "Property extra is not allowed.".to_string(),
+                    "\"extra\"".to_string()
+                ),
+            ]
+        );
+
+        let missing = messages(&schema, Format::Json, "{}");
+        assert_eq!(missing[0].0, "Missing property \"name\".");
+    }
+
+    #[test]
+    fn picks_the_closest_alternative() {
+        let schema = json!({
+            "properties": {
+                "runs-on": {
+                    "oneOf": [
+                        { "type": "string" },
+                        { "type": "object", "properties": { "group": { "type": "string" } } }
+                    ]
+                },
+                "level": { "enum": ["low", "high"] }
+            }
+        });
+        let text = "runs-on:\n  group: 3\nlevel: mid\n";
+        assert_eq!(
+            messages(&schema, Format::Yaml, text),
+            vec![
+                (
+                    "Incorrect type. Expected \"string\".".to_string(),
+                    "3".to_string()
+                ),
+                (
+                    "Value is not accepted. Valid values: \"low\", \"high\".".to_string(),
+                    "mid".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            messages(&schema, Format::Yaml, "runs-on: [1]\n")[0].0,
+            "Incorrect type. Expected one of string, object."
+        );
+        assert!(messages(&schema, Format::Yaml, "runs-on: ubuntu-latest\n").is_empty());
+    }
+}
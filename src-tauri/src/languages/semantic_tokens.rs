@@ -0,0 +1,182 @@
+//! Semantic Tokens
+//!
+//! Proxies `textDocument/semanticTokens/full` and `.../full/delta`. The last
+//! token result of each document is cached so that after the first request only
+//! edits travel from the server; they're applied here and the editor always
+//! receives the complete token array.
+
+use crate::languages::lsp_manager::{path_to_uri, request, LSPManager, LSPState};
+use crate::languages::lsp_requests::owning_server;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokens {
+    /// Complete token array in the LSP encoding (5 integers per token, positions
+    /// relative to the previous token), as Monaco expects it
+    pub data: Vec<u32>,
+    pub result_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SemanticTokensEdit {
+    start: usize,
+    delete_count: usize,
+    #[serde(default)]
+    data: Vec<u32>,
+}
+
+/// Last token result per document
+#[derive(Clone, Default)]
+pub struct SemanticTokensState {
+    documents: Arc<Mutex<HashMap<String, SemanticTokens>>>,
+}
+
+impl SemanticTokensState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Apply delta edits to the previous token array. Edits index into the previous
+/// array, so they're applied from the back.
+fn apply_edits(previous: &[u32], mut edits: Vec<SemanticTokensEdit>) -> Result<Vec<u32>, String> {
+    edits.sort_by_key(|edit| edit.start);
+    let mut data = previous.to_vec();
+    let mut limit = previous.len();
+    for edit in edits.into_iter().rev() {
+        let end = edit.start + edit.delete_count;
+        if end > limit {
+            return Err("Semantic token edits overlap or exceed the previous result".to_string());
+        }
+        data.splice(edit.start..end, edit.data);
+        limit = edit.start;
+    }
+    Ok(data)
+}
+
+fn parse_tokens(result: &Value) -> SemanticTokens {
+    SemanticTokens {
+        data: result
+            .get("data")
+            .and_then(|data| serde_json::from_value(data.clone()).ok())
+            .unwrap_or_default(),
+        result_id: result
+            .get("resultId")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+async fn full_tokens(
+    server: &tokio::sync::Mutex<LSPManager>,
+    uri: &str,
+) -> Result<SemanticTokens, String> {
+    let params = json!({ "textDocument": { "uri": uri } });
+    let result = request(server, "textDocument/semanticTokens/full", params).await?;
+    Ok(parse_tokens(&result))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Semantic tokens for a whole file, using a delta request when a previous
+/// result is cached
+///
+/// # Arguments
+/// * `path` - File to tokenize; must be open in the server
+#[fluxel_command]
+pub async fn lsp_semantic_tokens(
+    state: State<'_, LSPState>,
+    tokens_state: State<'_, SemanticTokensState>,
+    path: String,
+) -> Result<SemanticTokens, String> {
+    let server = owning_server(&state, &path)?;
+    let uri = path_to_uri(&path);
+    let previous = tokens_state
+        .documents
+        .lock()
+        .unwrap()
+        .get(&path)
+        .filter(|tokens| tokens.result_id.is_some())
+        .cloned();
+
+    let tokens = match previous {
+        Some(previous) => {
+            let params = json!({
+                "textDocument": { "uri": uri },
+                "previousResultId": previous.result_id,
+            });
+            let delta = request(server, "textDocument/semanticTokens/full/delta", params)
+                .await
+                .ok()
+                .and_then(|result| match result.get("edits") {
+                    Some(edits) => {
+                        let edits = serde_json::from_value(edits.clone()).ok()?;
+                        Some(SemanticTokens {
+                            data: apply_edits(&previous.data, edits).ok()?,
+                            result_id: result
+                                .get("resultId")
+                                .and_then(Value::as_str)
+                                .map(str::to_string),
+                        })
+                    }
+                    // Servers may answer a delta request with full tokens
+                    None if result.get("data").is_some() => Some(parse_tokens(&result)),
+                    None => None,
+                });
+            match delta {
+                Some(tokens) => tokens,
+                None => full_tokens(server, &uri).await?,
+            }
+        }
+        None => full_tokens(server, &uri).await?,
+    };
+
+    tokens_state
+        .documents
+        .lock()
+        .unwrap()
+        .insert(path, tokens.clone());
+    Ok(tokens)
+}
+
+/// Forget the cached tokens of a closed file
+#[fluxel_command]
+pub fn lsp_semantic_tokens_release(tokens_state: State<'_, SemanticTokensState>, path: String) {
+    tokens_state.documents.lock().unwrap().remove(&path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: usize, delete_count: usize, data: &[u32]) -> SemanticTokensEdit {
+        SemanticTokensEdit {
+            start,
+            delete_count,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn applies_delta_edits_against_previous_result() {
+        let previous = vec![0, 0, 5, 1, 0, 1, 2, 3, 2, 0, 0, 4, 6, 3, 0];
+        // Replace the second token and append one, listed out of order
+        let edits = vec![edit(15, 0, &[2, 0, 1, 4, 0]), edit(5, 5, &[1, 2, 4, 2, 1])];
+        assert_eq!(
+            apply_edits(&previous, edits).unwrap(),
+            vec![0, 0, 5, 1, 0, 1, 2, 4, 2, 1, 0, 4, 6, 3, 0, 2, 0, 1, 4, 0]
+        );
+
+        assert!(apply_edits(&previous, vec![edit(10, 10, &[])]).is_err());
+        assert!(apply_edits(&previous, vec![edit(0, 10, &[]), edit(5, 5, &[])]).is_err());
+    }
+}
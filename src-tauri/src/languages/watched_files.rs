@@ -0,0 +1,345 @@
+//! Watched Files Relay
+//!
+//! Language servers ask to be told about file changes they didn't make (project
+//! files, generated sources, files edited outside Fluxel) by registering
+//! `workspace/didChangeWatchedFiles` watchers with `client/registerCapability`.
+//! The registrations are recorded as they pass through the LSP manager, and a
+//! relay task forwards matching changes from the shared file system watcher as
+//! `workspace/didChangeWatchedFiles` notifications.
+
+use crate::languages::lsp_manager::{path_to_uri, uri_to_path, LSPManager};
+use crate::services::fs_watcher::{FsChangeEvent, FsChangeKind, FsWatcherService};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+const WATCHED_FILES_METHOD: &str = "workspace/didChangeWatchedFiles";
+
+/// Changes arriving within this window are sent as one notification
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// `FileChangeType` values
+const CREATED: u8 = 1;
+const CHANGED: u8 = 2;
+const DELETED: u8 = 3;
+
+/// `WatchKind` bits; watchers without a kind get all three
+const WATCH_CREATE: u8 = 1;
+const WATCH_CHANGE: u8 = 2;
+const WATCH_DELETE: u8 = 4;
+
+/// One `FileSystemWatcher` from a registration
+struct FileWatcher {
+    glob: GlobMatcher,
+    /// Base directory of a `RelativePattern`; plain patterns match the path
+    /// relative to the workspace root, or the absolute path
+    base: Option<PathBuf>,
+    kind: u8,
+}
+
+impl FileWatcher {
+    fn parse(watcher: &Value) -> Option<Self> {
+        let pattern = watcher.get("globPattern")?;
+        let (glob, base) = match pattern {
+            Value::String(glob) => (glob.as_str(), None),
+            Value::Object(relative) => {
+                // baseUri is a URI or a WorkspaceFolder
+                let base = relative.get("baseUri")?;
+                let uri = base
+                    .as_str()
+                    .or_else(|| base.get("uri").and_then(Value::as_str))?;
+                let glob = relative.get("pattern").and_then(Value::as_str)?;
+                (glob, Some(PathBuf::from(uri_to_path(uri))))
+            }
+            _ => return None,
+        };
+        let glob = match GlobBuilder::new(glob).literal_separator(true).build() {
+            Ok(glob) => glob.compile_matcher(),
+            Err(e) => {
+                println!("[WatchedFiles] Ignoring invalid glob '{}': {}", glob, e);
+                return None;
+            }
+        };
+        let kind = watcher
+            .get("kind")
+            .and_then(Value::as_u64)
+            .map(|kind| kind as u8)
+            .unwrap_or(WATCH_CREATE | WATCH_CHANGE | WATCH_DELETE);
+        Some(Self { glob, base, kind })
+    }
+
+    fn matches(&self, root: &Path, path: &Path, change: u8) -> bool {
+        let wanted = match change {
+            CREATED => WATCH_CREATE,
+            CHANGED => WATCH_CHANGE,
+            _ => WATCH_DELETE,
+        };
+        if self.kind & wanted == 0 {
+            return false;
+        }
+        match &self.base {
+            Some(base) => path
+                .strip_prefix(base)
+                .is_ok_and(|relative| self.glob.is_match(relative)),
+            None => {
+                self.glob.is_match(path)
+                    || path
+                        .strip_prefix(root)
+                        .is_ok_and(|relative| self.glob.is_match(relative))
+            }
+        }
+    }
+}
+
+/// File watchers a language server has registered, by registration id
+#[derive(Clone, Default)]
+pub struct WatchRegistrations {
+    registrations: Arc<std::sync::Mutex<HashMap<String, Vec<FileWatcher>>>>,
+}
+
+impl WatchRegistrations {
+    /// Record watcher (un)registrations in a message from the server. Other
+    /// messages are ignored; the frontend still answers the request.
+    pub fn ingest_message(&self, message: &Value) {
+        let method = message.get("method").and_then(Value::as_str);
+        if !matches!(
+            method,
+            Some("client/registerCapability" | "client/unregisterCapability")
+        ) {
+            return;
+        }
+        let params = message.get("params");
+        let mut registrations = self.registrations.lock().unwrap();
+        match method {
+            Some("client/registerCapability") => {
+                let entries = params
+                    .and_then(|params| params.get("registrations"))
+                    .and_then(Value::as_array);
+                for entry in entries.into_iter().flatten() {
+                    if entry.get("method").and_then(Value::as_str) != Some(WATCHED_FILES_METHOD) {
+                        continue;
+                    }
+                    let Some(id) = entry.get("id").and_then(Value::as_str) else {
+                        continue;
+                    };
+                    let watchers: Vec<FileWatcher> = entry
+                        .pointer("/registerOptions/watchers")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(FileWatcher::parse)
+                        .collect();
+                    println!(
+                        "[WatchedFiles] Registered {} watcher(s) as {}",
+                        watchers.len(),
+                        id
+                    );
+                    registrations.insert(id.to_string(), watchers);
+                }
+            }
+            Some("client/unregisterCapability") => {
+                // The protocol spells the field "unregisterations"
+                let entries = params
+                    .and_then(|params| params.get("unregisterations"))
+                    .and_then(Value::as_array);
+                for entry in entries.into_iter().flatten() {
+                    if let Some(id) = entry.get("id").and_then(Value::as_str) {
+                        registrations.remove(id);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn clear(&self) {
+        self.registrations.lock().unwrap().clear();
+    }
+
+    /// `FileEvent`s for the changes any registered watcher is interested in
+    fn file_events(&self, root: &Path, changes: &BTreeMap<String, u8>) -> Vec<Value> {
+        let registrations = self.registrations.lock().unwrap();
+        changes
+            .iter()
+            .filter(|(path, change)| {
+                registrations
+                    .values()
+                    .flatten()
+                    .any(|watcher| watcher.matches(root, Path::new(path), **change))
+            })
+            .map(|(path, change)| json!({ "uri": path_to_uri(path), "type": change }))
+            .collect()
+    }
+}
+
+/// Fold a watcher event into the pending changes, by path
+fn collect_changes(event: FsChangeEvent, changes: &mut BTreeMap<String, u8>) {
+    for path in event.paths {
+        if path.contains("/.git/") {
+            continue;
+        }
+        let change = match event.kind {
+            FsChangeKind::Created => CREATED,
+            FsChangeKind::Removed => DELETED,
+            // Renames arrive as modifications of both the old and the new path
+            FsChangeKind::Modified | FsChangeKind::Other if !Path::new(&path).exists() => DELETED,
+            FsChangeKind::Modified | FsChangeKind::Other => CHANGED,
+        };
+        match changes.get(&path) {
+            // A file created and then written is still new to the server
+            Some(&CREATED) if change == CHANGED => {}
+            _ => {
+                changes.insert(path, change);
+            }
+        }
+    }
+}
+
+/// Forward changes below `root` that match the server's registered watchers
+/// until the returned task is aborted
+pub fn start_relay(
+    watcher: &FsWatcherService,
+    manager: Arc<Mutex<LSPManager>>,
+    registrations: WatchRegistrations,
+    root: &Path,
+) -> Result<JoinHandle<()>, String> {
+    let mut subscription = watcher.subscribe(root)?;
+    let root = subscription.root().to_path_buf();
+
+    Ok(tokio::spawn(async move {
+        while let Some(first) = subscription.recv().await {
+            let mut changes = BTreeMap::new();
+            let mut pending = Some(first);
+            while let Some(event) = pending {
+                collect_changes(event, &mut changes);
+                pending = tokio::time::timeout(DEBOUNCE, subscription.recv())
+                    .await
+                    .ok()
+                    .flatten();
+            }
+
+            let events = registrations.file_events(&root, &changes);
+            if events.is_empty() {
+                continue;
+            }
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": WATCHED_FILES_METHOD,
+                "params": { "changes": events },
+            });
+            let mut manager = manager.lock().await;
+            if !manager.is_running() {
+                break;
+            }
+            if let Err(e) = manager.send_message(notification.to_string()).await {
+                println!(
+                    "[WatchedFiles] Failed to notify {}: {}",
+                    manager.server_name(),
+                    e
+                );
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registrations(watchers: Value) -> WatchRegistrations {
+        let registrations = WatchRegistrations::default();
+        registrations.ingest_message(&json!({
+            "id": 1,
+            "method": "client/registerCapability",
+            "params": { "registrations": [{
+                "id": "watch-1",
+                "method": WATCHED_FILES_METHOD,
+                "registerOptions": { "watchers": watchers },
+            }]},
+        }));
+        registrations
+    }
+
+    #[test]
+    fn matches_registered_patterns_and_kinds() {
+        let root = Path::new("/repo");
+        let registrations = registrations(json!([
+            { "globPattern": "**/*.{csproj,props}" },
+            { "globPattern": { "baseUri": "file:///repo/src", "pattern": "*.cs" }, "kind": 4 },
+        ]));
+
+        let changes: BTreeMap<String, u8> = [
+            ("/repo/App/App.csproj", CHANGED),
+            ("/repo/Directory.Build.props", CREATED),
+            ("/repo/src/Program.cs", CHANGED),
+            ("/repo/src/Old.cs", DELETED),
+            ("/repo/src/nested/Deep.cs", DELETED),
+            ("/repo/README.md", CHANGED),
+        ]
+        .into_iter()
+        .map(|(path, change)| (path.to_string(), change))
+        .collect();
+
+        let uris: Vec<String> = registrations
+            .file_events(root, &changes)
+            .iter()
+            .map(|event| event["uri"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "file:///repo/App/App.csproj",
+                "file:///repo/Directory.Build.props",
+                "file:///repo/src/Old.cs",
+            ]
+        );
+
+        registrations.ingest_message(&json!({
+            "id": 2,
+            "method": "client/unregisterCapability",
+            "params": { "unregisterations": [{ "id": "watch-1", "method": WATCHED_FILES_METHOD }] },
+        }));
+        assert!(registrations.file_events(root, &changes).is_empty());
+    }
+
+    #[test]
+    fn keeps_created_files_created() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-watched-files-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let root = dir.to_string_lossy().replace('\\', "/");
+        let created = format!("{}/New.cs", root);
+        let renamed = format!("{}/Renamed.cs", root);
+        std::fs::write(&created, "class New {}").unwrap();
+
+        let mut changes = BTreeMap::new();
+        let event = |kind, path: &str| FsChangeEvent {
+            kind,
+            paths: vec![path.to_string()],
+        };
+        collect_changes(event(FsChangeKind::Created, &created), &mut changes);
+        collect_changes(event(FsChangeKind::Modified, &created), &mut changes);
+        collect_changes(event(FsChangeKind::Modified, &renamed), &mut changes);
+        collect_changes(
+            event(FsChangeKind::Modified, &format!("{}/.git/index", root)),
+            &mut changes,
+        );
+        // Modified paths that no longer exist were renamed away
+        assert_eq!(
+            changes.into_iter().collect::<Vec<_>>(),
+            vec![(created, CREATED), (renamed, DELETED)]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
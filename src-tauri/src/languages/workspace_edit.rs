@@ -0,0 +1,575 @@
+//! Workspace Edits
+//!
+//! Applies LSP `WorkspaceEdit`s (from code actions, renames, and refactorings) to
+//! files on disk. Files open in the editor are left to the editor, so its undo
+//! stack and unsaved changes stay intact; their edits are handed back instead.
+//!
+//! Changes are applied in order and all-or-nothing: if one fails, everything
+//! already done is rolled back. Deleted files are moved aside until the edit
+//! completes so they can be restored.
+
+use crate::languages::lsp_manager::uri_to_path;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspPosition {
+    pub line: u32,
+    /// UTF-16 code units into the line
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LspRange {
+    pub start: LspPosition,
+    pub end: LspPosition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: LspRange,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceOptions {
+    pub overwrite: bool,
+    pub ignore_if_exists: bool,
+    pub recursive: bool,
+    pub ignore_if_not_exists: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResourceOperation {
+    #[serde(rename_all = "camelCase")]
+    Create {
+        uri: String,
+        #[serde(default)]
+        options: ResourceOptions,
+    },
+    #[serde(rename_all = "camelCase")]
+    Rename {
+        old_uri: String,
+        new_uri: String,
+        #[serde(default)]
+        options: ResourceOptions,
+    },
+    #[serde(rename_all = "camelCase")]
+    Delete {
+        uri: String,
+        #[serde(default)]
+        options: ResourceOptions,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentEdit {
+    pub text_document: TextDocumentIdentifier,
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum DocumentChange {
+    Resource(ResourceOperation),
+    Edit(TextDocumentEdit),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkspaceEdit {
+    pub changes: Option<HashMap<String, Vec<TextEdit>>>,
+    /// Takes precedence over `changes`, as in the LSP spec
+    pub document_changes: Option<Vec<DocumentChange>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedChange {
+    /// `"edit"`, `"create"`, `"rename"`, or `"delete"`
+    pub kind: &'static str,
+    pub path: String,
+    /// New path of a renamed file
+    pub new_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedChange {
+    pub kind: &'static str,
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenFileEdits {
+    pub path: String,
+    pub edits: Vec<TextEdit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEditResult {
+    /// Whether every change was applied (or left to the editor)
+    pub success: bool,
+    pub applied: Vec<AppliedChange>,
+    /// The change that failed; everything applied before it was rolled back
+    pub failed: Vec<FailedChange>,
+    /// Text edits for files open in the editor, for the editor to apply
+    pub open_file_edits: Vec<OpenFileEdits>,
+}
+
+enum Operation {
+    Edit(PathBuf, Vec<TextEdit>),
+    Create(PathBuf, ResourceOptions),
+    Rename(PathBuf, PathBuf, ResourceOptions),
+    Delete(PathBuf, ResourceOptions),
+}
+
+impl Operation {
+    fn kind(&self) -> &'static str {
+        match self {
+            Operation::Edit(..) => "edit",
+            Operation::Create(..) => "create",
+            Operation::Rename(..) => "rename",
+            Operation::Delete(..) => "delete",
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            Operation::Edit(path, _)
+            | Operation::Create(path, _)
+            | Operation::Rename(path, _, _)
+            | Operation::Delete(path, _) => path,
+        }
+    }
+}
+
+/// What to do to undo one applied step
+enum Undo {
+    /// Restore the previous contents, or remove the file if it didn't exist
+    Write(PathBuf, Option<Vec<u8>>),
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// Move a deleted (or overwritten) path back from where it was set aside
+    Restore {
+        original: PathBuf,
+        aside: PathBuf,
+    },
+}
+
+fn operations(edit: WorkspaceEdit) -> Vec<Operation> {
+    let path = |uri: &str| PathBuf::from(uri_to_path(uri));
+    if let Some(changes) = edit.document_changes {
+        return changes
+            .into_iter()
+            .map(|change| match change {
+                DocumentChange::Edit(edit) => {
+                    Operation::Edit(path(&edit.text_document.uri), edit.edits)
+                }
+                DocumentChange::Resource(ResourceOperation::Create { uri, options }) => {
+                    Operation::Create(path(&uri), options)
+                }
+                DocumentChange::Resource(ResourceOperation::Rename {
+                    old_uri,
+                    new_uri,
+                    options,
+                }) => Operation::Rename(path(&old_uri), path(&new_uri), options),
+                DocumentChange::Resource(ResourceOperation::Delete { uri, options }) => {
+                    Operation::Delete(path(&uri), options)
+                }
+            })
+            .collect();
+    }
+    let mut changes: Vec<(String, Vec<TextEdit>)> =
+        edit.changes.unwrap_or_default().into_iter().collect();
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+        .into_iter()
+        .map(|(uri, edits)| Operation::Edit(path(&uri), edits))
+        .collect()
+}
+
+/// Byte offset of an LSP position in `text`, clamped to the line and the text
+fn offset_of(text: &str, line_starts: &[usize], position: LspPosition) -> usize {
+    let Some(&start) = line_starts.get(position.line as usize) else {
+        return text.len();
+    };
+    let line = &text[start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let mut units = 0;
+    for (index, ch) in line.char_indices() {
+        if units >= position.character {
+            return start + index;
+        }
+        units += ch.len_utf16() as u32;
+    }
+    start + line.len()
+}
+
+/// Apply LSP text edits to `text`. Edits must not overlap; edits at the same
+/// position are inserted in the order given.
+pub fn apply_text_edits(text: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut line_starts = vec![0];
+    line_starts.extend(text.match_indices('\n').map(|(index, _)| index + 1));
+
+    let mut spans: Vec<(usize, usize, &str)> = edits
+        .iter()
+        .map(|edit| {
+            let start = offset_of(text, &line_starts, edit.range.start);
+            let end = offset_of(text, &line_starts, edit.range.end);
+            (start, end.max(start), edit.new_text.as_str())
+        })
+        .collect();
+    spans.sort_by_key(|(start, end, _)| (*start, *end));
+    for pair in spans.windows(2) {
+        if pair[1].0 < pair[0].1 {
+            return Err("Text edits overlap".to_string());
+        }
+    }
+
+    let mut result = text.to_string();
+    for (start, end, new_text) in spans.into_iter().rev() {
+        result.replace_range(start..end, new_text);
+    }
+    Ok(result)
+}
+
+/// A sibling path to set `path` aside at while the edit is in progress
+fn aside_path(path: &Path, step: usize) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{}.fluxel-edit-{}-{}",
+        name,
+        std::process::id(),
+        step
+    ))
+}
+
+fn apply_operation(
+    operation: &Operation,
+    step: usize,
+    undo: &mut Vec<Undo>,
+    applied: &mut Vec<AppliedChange>,
+) -> Result<(), String> {
+    let display = |path: &Path| path.to_string_lossy().to_string();
+    match operation {
+        Operation::Edit(path, edits) => {
+            let original = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+            let text = String::from_utf8(original.clone())
+                .map_err(|_| "File is not valid UTF-8".to_string())?;
+            let updated = apply_text_edits(&text, edits)?;
+            fs::write(path, updated).map_err(|e| format!("Failed to write file: {}", e))?;
+            undo.push(Undo::Write(path.clone(), Some(original)));
+        }
+        Operation::Create(path, options) => {
+            if path.exists() {
+                if !options.overwrite {
+                    if options.ignore_if_exists {
+                        return Ok(());
+                    }
+                    return Err("File already exists".to_string());
+                }
+                let aside = aside_path(path, step);
+                fs::rename(path, &aside).map_err(|e| format!("Failed to replace file: {}", e))?;
+                undo.push(Undo::Restore {
+                    original: path.clone(),
+                    aside,
+                });
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::write(path, "").map_err(|e| format!("Failed to create file: {}", e))?;
+            undo.push(Undo::Write(path.clone(), None));
+        }
+        Operation::Rename(from, to, options) => {
+            if !from.exists() {
+                return Err("Path does not exist".to_string());
+            }
+            if to.exists() {
+                if !options.overwrite {
+                    if options.ignore_if_exists {
+                        return Ok(());
+                    }
+                    return Err(format!("{} already exists", to.display()));
+                }
+                let aside = aside_path(to, step);
+                fs::rename(to, &aside).map_err(|e| format!("Failed to replace target: {}", e))?;
+                undo.push(Undo::Restore {
+                    original: to.clone(),
+                    aside,
+                });
+            }
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::rename(from, to).map_err(|e| format!("Failed to rename: {}", e))?;
+            undo.push(Undo::Rename {
+                from: from.clone(),
+                to: to.clone(),
+            });
+            applied.push(AppliedChange {
+                kind: "rename",
+                path: display(from),
+                new_path: Some(display(to)),
+            });
+            return Ok(());
+        }
+        Operation::Delete(path, options) => {
+            if !path.exists() {
+                if options.ignore_if_not_exists {
+                    return Ok(());
+                }
+                return Err("Path does not exist".to_string());
+            }
+            let is_empty_dir = fs::read_dir(path)
+                .map(|mut entries| entries.next().is_none())
+                .unwrap_or(true);
+            if path.is_dir() && !options.recursive && !is_empty_dir {
+                return Err("Directory is not empty".to_string());
+            }
+            let aside = aside_path(path, step);
+            fs::rename(path, &aside).map_err(|e| format!("Failed to delete: {}", e))?;
+            undo.push(Undo::Restore {
+                original: path.clone(),
+                aside,
+            });
+        }
+    }
+    applied.push(AppliedChange {
+        kind: operation.kind(),
+        path: display(operation.path()),
+        new_path: None,
+    });
+    Ok(())
+}
+
+fn roll_back(undo: Vec<Undo>) {
+    for step in undo.into_iter().rev() {
+        let result = match &step {
+            Undo::Write(path, Some(original)) => fs::write(path, original),
+            Undo::Write(path, None) => fs::remove_file(path),
+            Undo::Rename { from, to } => fs::rename(to, from),
+            Undo::Restore { original, aside } => fs::rename(aside, original),
+        };
+        if let Err(e) = result {
+            println!("[WorkspaceEdit] Failed to roll back a change: {}", e);
+        }
+    }
+}
+
+/// Remove paths set aside for rollback once the whole edit has succeeded
+fn discard_backups(undo: Vec<Undo>) {
+    for step in undo {
+        if let Undo::Restore { aside, .. } = step {
+            let _ = if aside.is_dir() {
+                fs::remove_dir_all(&aside)
+            } else {
+                fs::remove_file(&aside)
+            };
+        }
+    }
+}
+
+/// Apply `edit` on disk, except text edits to `open_files`, which are returned
+pub fn apply_workspace_edit_to_disk(
+    edit: WorkspaceEdit,
+    open_files: &[String],
+) -> WorkspaceEditResult {
+    let key = |path: &Path| {
+        let path = path.to_string_lossy().replace('\\', "/");
+        if cfg!(windows) {
+            path.to_lowercase()
+        } else {
+            path
+        }
+    };
+    let open: HashSet<String> = open_files.iter().map(|p| key(Path::new(p))).collect();
+
+    let mut applied = Vec::new();
+    let mut open_file_edits: Vec<OpenFileEdits> = Vec::new();
+    let mut undo = Vec::new();
+    for (step, operation) in operations(edit).into_iter().enumerate() {
+        if let Operation::Edit(path, edits) = &operation {
+            if open.contains(&key(path)) {
+                open_file_edits.push(OpenFileEdits {
+                    path: path.to_string_lossy().to_string(),
+                    edits: edits.clone(),
+                });
+                continue;
+            }
+        }
+        if let Err(error) = apply_operation(&operation, step, &mut undo, &mut applied) {
+            roll_back(undo);
+            return WorkspaceEditResult {
+                success: false,
+                applied: Vec::new(),
+                failed: vec![FailedChange {
+                    kind: operation.kind(),
+                    path: operation.path().to_string_lossy().to_string(),
+                    error,
+                }],
+                open_file_edits: Vec::new(),
+            };
+        }
+    }
+    discard_backups(undo);
+
+    WorkspaceEditResult {
+        success: true,
+        applied,
+        failed: Vec::new(),
+        open_file_edits,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Apply an LSP `WorkspaceEdit` to files on disk, all or nothing
+///
+/// # Arguments
+/// * `edit` - The workspace edit (`changes` or `documentChanges`, including
+///   create/rename/delete operations)
+/// * `open_files` - Files open in the editor; their text edits are returned in
+///   `openFileEdits` for the editor to apply instead of being written to disk
+#[fluxel_command]
+pub async fn apply_workspace_edit(
+    edit: WorkspaceEdit,
+    open_files: Option<Vec<String>>,
+) -> Result<WorkspaceEditResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = apply_workspace_edit_to_disk(edit, &open_files.unwrap_or_default());
+        println!(
+            "[WorkspaceEdit] Applied {} change(s), {} failed, {} left to the editor",
+            result.applied.len(),
+            result.failed.len(),
+            result.open_file_edits.len()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::lsp_manager::path_to_uri;
+
+    fn text_edit(line: u32, start: u32, end: u32, text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "range": {
+                "start": { "line": line, "character": start },
+                "end": { "line": line, "character": end }
+            },
+            "newText": text
+        })
+    }
+
+    #[test]
+    fn applies_text_edits_in_utf16_units() {
+        let edits: Vec<TextEdit> = serde_json::from_value(serde_json::json!([
+            text_edit(0, 3, 6, "bar"),
+            text_edit(1, 0, 0, "// ")
+        ]))
+        .unwrap();
+        // The emoji is two UTF-16 units
+        assert_eq!(
+            apply_text_edits("😀 foo();\r\nnext\n", &edits).unwrap(),
+            "😀 bar();\r\n// next\n"
+        );
+        let overlapping: Vec<TextEdit> = serde_json::from_value(serde_json::json!([
+            text_edit(0, 0, 4, "a"),
+            text_edit(0, 2, 5, "b")
+        ]))
+        .unwrap();
+        assert!(apply_text_edits("abcdef", &overlapping).is_err());
+    }
+
+    #[test]
+    fn applies_document_changes_atomically() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-workspace-edit-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let uri = |name: &str| path_to_uri(&dir.join(name).to_string_lossy());
+        fs::write(dir.join("a.cs"), "class A {}\n").unwrap();
+        fs::write(dir.join("open.cs"), "A a;\n").unwrap();
+        fs::write(dir.join("old.cs"), "// old\n").unwrap();
+
+        let edit: WorkspaceEdit = serde_json::from_value(serde_json::json!({
+            "documentChanges": [
+                { "textDocument": { "uri": uri("a.cs"), "version": 1 }, "edits": [text_edit(0, 6, 7, "B")] },
+                { "textDocument": { "uri": uri("open.cs"), "version": 1 }, "edits": [text_edit(0, 0, 1, "B")] },
+                { "kind": "rename", "oldUri": uri("a.cs"), "newUri": uri("b/B.cs") },
+                { "kind": "create", "uri": uri("new file.cs") },
+                { "kind": "delete", "uri": uri("old.cs") }
+            ]
+        }))
+        .unwrap();
+        let open = vec![dir.join("open.cs").to_string_lossy().to_string()];
+        let result = apply_workspace_edit_to_disk(edit, &open);
+        assert!(result.success, "{:?}", result.failed);
+        assert_eq!(result.applied.len(), 4);
+        assert_eq!(result.open_file_edits.len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("b/B.cs")).unwrap(),
+            "class B {}\n"
+        );
+        assert_eq!(fs::read_to_string(dir.join("open.cs")).unwrap(), "A a;\n");
+        assert!(dir.join("new file.cs").exists());
+        assert!(!dir.join("old.cs").exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+        // The rename target exists, so the earlier edit and delete are undone
+        let edit: WorkspaceEdit = serde_json::from_value(serde_json::json!({
+            "documentChanges": [
+                { "textDocument": { "uri": uri("b/B.cs"), "version": 2 }, "edits": [text_edit(0, 0, 5, "struct")] },
+                { "kind": "delete", "uri": uri("new file.cs") },
+                { "kind": "rename", "oldUri": uri("b/B.cs"), "newUri": uri("open.cs") }
+            ]
+        }))
+        .unwrap();
+        let result = apply_workspace_edit_to_disk(edit, &[]);
+        assert!(!result.success);
+        assert_eq!(result.failed[0].kind, "rename");
+        assert!(result.applied.is_empty());
+        assert_eq!(
+            fs::read_to_string(dir.join("b/B.cs")).unwrap(),
+            "class B {}\n"
+        );
+        assert!(dir.join("new file.cs").exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -6,36 +6,74 @@ mod profiling;
 mod services;
 
 use commands::{GitignoreCache, LaunchState, ProjectConfigCache};
-use languages::LSPState;
-use services::ProcessManager;
+use languages::{CompletionState, DiagnosticsState, LSPState, SchemaState, SemanticTokensState};
+use services::{
+    AiAuditState, AiMetricsState, AuthorIdentityState, CodeHostState, DirtyFilesState,
+    FsWatcherService, HighlightState, InlineCompletionState, LocalHistoryState,
+    ModuleGraphWatchState, PathFilterState, PreviewServerState, ProcessManager, SearchHistoryState,
+    SearchIndexState, SessionState, SpellCheckState, TerminalHistoryState, TsProjectState,
+    WatchBuildState, WorkspaceSecretsState,
+};
 
 use std::path::PathBuf;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    // Installed before app state is built so the startup session covers it
+    #[cfg(feature = "profiling")]
+    let profiler = profiling::init();
+    #[cfg(feature = "profiling")]
+    let state_init_span = tracing::span!(tracing::Level::INFO, "state_init").entered();
+
+    // Shared so watcher events honor the same exclusions as search and indexing
+    let path_filters = PathFilterState::new();
+
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .manage(LSPState::new())
+        .manage(DiagnosticsState::new())
+        .manage(CompletionState::new())
+        .manage(SemanticTokensState::new())
+        .manage(SchemaState::new())
         .manage(LaunchState::new())
         .manage(ProcessManager::new())
         .manage(ProjectConfigCache::new())
         .manage(GitignoreCache::new())
-        .setup(|app| {
+        .manage(path_filters.clone())
+        .manage(FsWatcherService::new(path_filters))
+        .manage(ModuleGraphWatchState::new())
+        .manage(WatchBuildState::new())
+        .manage(PreviewServerState::new())
+        .manage(WorkspaceSecretsState::new())
+        .manage(SpellCheckState::new())
+        .manage(HighlightState::new())
+        .manage(TsProjectState::new())
+        .manage(SearchHistoryState::new())
+        .manage(TerminalHistoryState::new())
+        .manage(SearchIndexState::new())
+        .manage(DirtyFilesState::new())
+        .manage(LocalHistoryState::new())
+        .manage(CodeHostState::new())
+        .manage(AuthorIdentityState::new())
+        .manage(InlineCompletionState::new())
+        .manage(AiAuditState::new())
+        .manage(AiMetricsState::new())
+        .manage(SessionState::begin());
+
+    #[cfg(feature = "profiling")]
+    drop(state_init_span);
+
+    builder
+        .setup(move |app| {
             #[cfg(feature = "profiling")]
             let _setup_span = tracing::span!(tracing::Level::INFO, "tauri_setup").entered();
 
-            // Initialize profiling subsystem (feature-gated)
             #[cfg(feature = "profiling")]
-            {
-                let _profiler_span =
-                    tracing::span!(tracing::Level::INFO, "profiler_init").entered();
-                let profiler = profiling::init();
-                app.manage(profiler);
-            }
+            app.manage(profiler);
 
             // Check for CLI args (e.g. context menu launch)
             #[cfg(feature = "profiling")]
@@ -82,19 +120,103 @@ pub fn run() {
             // Workspace Commands
             commands::workspace::list_directory_entries,
             commands::workspace::search_files,
+            services::open_with::reveal_in_file_manager,
+            services::open_with::list_open_with_handlers,
+            services::open_with::open_with_handler,
+            services::scaffold::list_templates,
+            services::scaffold::create_project,
+            services::search_history::record_search,
+            services::search_history::list_recent_searches,
+            services::search_history::clear_search_history,
+            services::search_history::save_search,
+            services::search_history::list_saved_searches,
+            services::search_history::delete_saved_search,
+            services::search_index::enable_search_index,
+            services::search_index::disable_search_index,
+            services::search_index::get_search_index_status,
+            services::path_filter::set_path_filter_config,
+            services::shutdown::get_previous_session,
+            // File System Commands
+            commands::fs_ops::create_file,
+            commands::fs_ops::rename_path,
+            commands::fs_ops::duplicate_path,
+            commands::fs_ops::copy_relative_path,
+            commands::fs_ops::copy_posix_path,
+            services::file_guard::set_dirty_files,
+            services::file_guard::check_dirty_files,
+            services::file_templates::list_file_templates,
+            services::file_templates::save_file_templates,
+            services::file_templates::render_file_template,
+            services::local_history::record_local_history,
+            services::local_history::list_local_history,
+            services::local_history::read_local_history_version,
+            services::local_history::restore_local_history_version,
+            // Asset Commands
+            commands::asset::inspect_asset,
             // Build Commands
             commands::build::get_project_configurations,
             commands::build::build_csharp_project,
+            services::watch_build::watch_build,
+            services::watch_build::unwatch_build,
             // LSP Commands (from languages module)
             languages::csharp::lsp::start_csharp_ls,
             languages::csharp::lsp::send_lsp_message,
             languages::csharp::lsp::stop_csharp_ls,
+            languages::csharp::solutions::list_csharp_solutions,
+            languages::frameworks::lsp::resolve_framework_ls,
+            languages::frameworks::lsp::start_framework_ls,
+            languages::frameworks::lsp::stop_framework_ls,
+            languages::frameworks::lsp::send_framework_lsp_message,
+            languages::schemas::commands::schema_validate,
+            languages::schemas::commands::schema_complete,
+            languages::completion::lsp_completion,
+            languages::completion::lsp_completion_resolve,
+            languages::diagnostics::get_all_diagnostics,
+            languages::diagnostics::publish_diagnostics,
+            languages::diagnostics::clear_diagnostics,
+            languages::lsp_metrics::get_lsp_server_metrics,
+            languages::lsp_requests::lsp_code_actions,
+            languages::lsp_requests::lsp_rename,
+            languages::lsp_requests::lsp_hover,
+            languages::lsp_requests::lsp_signature_help,
+            languages::lsp_requests::lsp_inlay_hints,
+            languages::semantic_tokens::lsp_semantic_tokens,
+            languages::semantic_tokens::lsp_semantic_tokens_release,
+            languages::workspace_edit::apply_workspace_edit,
             // Node Resolution (from services module)
             services::node_resolver::resolve_node_module,
             services::node_resolver::discover_package_typings,
+            services::node_resolver::bundle_typings,
             services::node_resolver::analyze_module_graph,
+            services::node_resolver::walk_module_graph,
+            services::node_resolver::extract_declaration_symbols,
+            services::module_graph::watch_module_graph,
+            services::module_graph::unwatch_module_graph,
+            services::module_graph::export_module_graph,
+            services::import_cost::get_import_costs,
+            services::tree_shaking::analyze_tree_shaking_report,
+            services::unused_exports::analyze_unused_exports,
+            services::terminal_history::get_terminal_history,
+            services::terminal_links::detect_terminal_links,
+            services::ts_projects::get_ts_project_references,
+            services::ts_projects::find_ts_project_for_file,
             // Project Detection
             services::project_detector::detect_project_profile,
+            services::runnables::list_runnables,
+            services::run_configs::list_run_configurations,
+            services::run_configs::save_run_configurations,
+            services::run_configs::launch_run_configuration,
+            services::benchmarks::discover_benchmarks,
+            services::benchmarks::run_benchmarks,
+            services::benchmarks::get_benchmark_history,
+            services::project_clean::clean_project,
+            // WSL
+            services::workspace_archive::estimate_workspace_archive,
+            services::workspace_archive::export_workspace_archive,
+            services::wsl::list_wsl_distros,
+            services::wsl::translate_wsl_path,
+            // SSH
+            services::ssh_hosts::list_ssh_hosts,
             // Batch File Operations (for efficient type loading)
             services::batch_file_reader::batch_read_files,
             services::batch_file_reader::batch_discover_typings,
@@ -104,8 +226,40 @@ pub fn run() {
             services::git::git_commit,
             services::git::git_push,
             services::git::git_pull,
+            services::git::git_unshallow,
             services::git::git_read_file_at_head,
             services::git::git_discard_changes,
+            services::git::git_reflog,
+            services::git::git_recover,
+            services::git::git_clone,
+            services::git_bisect::git_bisect_start,
+            services::git_bisect::git_bisect_mark,
+            services::git_bisect::git_bisect_status,
+            services::git_bisect::git_bisect_reset,
+            services::git_diff::git_diff_refs,
+            services::git_patch::git_create_patch,
+            services::git_patch::git_apply_patch,
+            services::git_rebase::git_rebase_plan,
+            services::git_rebase::git_rebase_execute,
+            services::git_rebase::git_rebase_continue,
+            services::git_rebase::git_rebase_skip,
+            services::git_rebase::git_rebase_abort,
+            services::git_rebase::git_rebase_status,
+            services::git_sparse::git_sparse_checkout_set,
+            services::git_sparse::git_sparse_checkout_list,
+            services::git_sparse::git_sparse_checkout_disable,
+            services::repo_stats::git_repo_stats,
+            services::commit_message::validate_commit_message,
+            services::commit_message::suggest_commit_message,
+            // Code Host Commands
+            services::code_host::get_code_host,
+            services::code_host::list_pull_requests,
+            services::code_host::get_pull_request_checks,
+            services::code_host::create_pull_request,
+            services::code_host::get_pull_request_comments,
+            services::code_host::list_issues,
+            services::code_host::get_issue,
+            services::author_identity::resolve_author_identities,
             // Profiling Commands (feature-gated)
             #[cfg(feature = "profiling")]
             profiling::commands::profiler_set_enabled,
@@ -127,7 +281,16 @@ pub fn run() {
             profiling::commands::profiler_record_frontend_spans_batch,
             #[cfg(feature = "profiling")]
             profiling::commands::profiler_export,
+            #[cfg(feature = "profiling")]
+            profiling::commands::profiler_export_flamegraph,
+            #[cfg(feature = "profiling")]
+            profiling::commands::profiler_set_sampling,
+            #[cfg(feature = "profiling")]
+            profiling::commands::profiler_finish_startup,
+            #[cfg(feature = "profiling")]
+            profiling::commands::profiler_get_startup_report,
             // Process Manager Commands
+            services::health::get_health_snapshot,
             services::process_manager::register_child_process,
             services::process_manager::unregister_child_process,
             services::process_manager::kill_all_child_processes,
@@ -138,20 +301,63 @@ pub fn run() {
             services::plugin_loader::discover_community_plugins,
             services::plugin_loader::get_community_plugins_path,
             services::plugin_loader::validate_plugin_directory,
+            // Preview Server Commands
+            services::preview_server::start_preview_server,
+            services::preview_server::stop_preview_server,
+            services::preview_server::list_preview_servers,
+            // Snippet Commands
+            services::snippets::list_snippets,
+            services::snippets::get_user_snippets_path,
+            services::snippets::expand_snippet,
+            // Workspace Secret Commands
+            services::secrets::list_workspace_secrets,
+            services::secrets::set_workspace_secret,
+            services::secrets::get_workspace_secret,
+            services::secrets::delete_workspace_secret,
+            // Spell Check Commands
+            services::spellcheck::check_text,
+            services::spellcheck::add_to_dictionary,
+            services::spellcheck::list_spell_languages,
+            // Markdown Commands
+            services::markdown::render_markdown,
+            // Syntax Highlighting Commands
+            services::highlight::highlight_snippet,
+            services::highlight::list_highlight_themes,
+            // EditorConfig Commands
+            services::editorconfig::resolve_editorconfig,
+            // Folding Commands
+            services::folding::compute_folding_ranges,
+            // Dependency Audit Commands
+            services::dependency_audit::audit_licenses,
+            services::vulnerability_audit::audit_vulnerabilities,
+            services::lockfile::parse_lockfile,
+            services::package_info::get_package_info,
             // MiniMax API Proxy Commands
             commands::minimax::minimax_chat,
             commands::minimax::minimax_chat_stream,
-            commands::minimax::minimax_health_check
+            commands::minimax::minimax_health_check,
+            services::inline_completion::ai_complete_inline,
+            services::ai_audit::get_ai_audit_enabled,
+            services::ai_audit::set_ai_audit_enabled,
+            services::ai_audit::record_ai_audit_entry,
+            services::ai_audit::query_ai_audit_log,
+            services::ai_audit::purge_ai_audit_log,
+            services::ai_context::assemble_ai_context,
+            services::ai_edits::preview_ai_edits,
+            services::ai_edits::apply_ai_edits,
+            services::ai_metrics::record_ai_metrics,
+            services::ai_metrics::ai_get_metrics,
+            services::ai_images::prepare_ai_image,
+            services::structured_output::ai_structured_chat,
+            services::grammar_check::ai_grammar_check
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
-            // Handle app exit to clean up child processes
+            // Shut subsystems down before the process exits
             if let tauri::RunEvent::Exit = event {
-                println!("[Tauri] Application exiting, cleaning up child processes...");
-                if let Some(pm) = app_handle.try_state::<ProcessManager>() {
-                    pm.kill_all();
-                }
+                println!("[Tauri] Application exiting, shutting down...");
+                tauri::async_runtime::block_on(services::shutdown::shutdown(app_handle));
             }
         });
 }
@@ -0,0 +1,163 @@
+//! Argument summaries for command spans.
+//!
+//! `#[fluxel_command]` records every deserialized argument of a command on its
+//! span. Arguments are shown with their `Debug` output when they have one, cut
+//! off after a few dozen characters so large payloads such as file contents are
+//! never formatted in full. Arguments named like credentials are recorded as
+//! [`REDACTED`] instead, and `#[fluxel_command(skip(..))]` leaves arguments out.
+
+use std::fmt::{self, Debug, Write};
+
+/// Longest summary of a single argument, in bytes.
+const MAX_ARG_LEN: usize = 64;
+
+/// Summary of an argument whose value must not be recorded.
+pub const REDACTED: &str = "<redacted>";
+
+/// A command argument being summarized.
+///
+/// `(&ArgValue(&value)).summarize()` picks [`DebugArg`] when the value
+/// implements `Debug`, and [`OpaqueArg`] otherwise.
+pub struct ArgValue<'a, T>(pub &'a T);
+
+pub trait DebugArg {
+    fn summarize(&self) -> String;
+}
+
+impl<T: Debug> DebugArg for ArgValue<'_, T> {
+    fn summarize(&self) -> String {
+        let mut out = Truncated::default();
+        // Formatting stops with an error once the limit is reached
+        let _ = write!(out, "{:?}", self.0);
+        if out.truncated {
+            out.text.push('…');
+        }
+        out.text
+    }
+}
+
+pub trait OpaqueArg {
+    #[allow(dead_code)] // Only picked for arguments without `Debug`
+    fn summarize(&self) -> String;
+}
+
+impl<T> OpaqueArg for &ArgValue<'_, T> {
+    fn summarize(&self) -> String {
+        "..".to_string()
+    }
+}
+
+/// Join summaries as `name=value` pairs.
+pub fn join(args: &[(&str, String)]) -> String {
+    args.iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Default)]
+struct Truncated {
+    text: String,
+    truncated: bool,
+}
+
+impl Write for Truncated {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = MAX_ARG_LEN - self.text.len();
+        if s.len() <= room {
+            self.text.push_str(s);
+            return Ok(());
+        }
+        let mut end = room;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.text.push_str(&s[..end]);
+        self.truncated = true;
+        Err(fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiling::FluxelProfiler;
+    use fluxel_macros::fluxel_command;
+    use tracing_subscriber::prelude::*;
+
+    struct NoDebug;
+
+    #[test]
+    fn test_summarize_args() {
+        let root = "/work/a".to_string();
+        let content = "x".repeat(10_000);
+        let summary = join(&[
+            ("root", ArgValue(&root).summarize()),
+            ("limit", ArgValue(&Some(20)).summarize()),
+            ("opaque", (&ArgValue(&NoDebug)).summarize()),
+            ("content", ArgValue(&content).summarize()),
+        ]);
+        let expected = format!(
+            "root=\"/work/a\", limit=Some(20), opaque=.., content=\"{}…",
+            "x".repeat(MAX_ARG_LEN - 1)
+        );
+        assert_eq!(summary, expected);
+    }
+
+    #[fluxel_command]
+    async fn connect_host(
+        host: String,
+        token: String,
+        api_key: Option<String>,
+        access_token: String,
+    ) -> Result<(), String> {
+        let _ = (host, token, api_key, access_token);
+        Ok(())
+    }
+
+    #[fluxel_command(skip(payload))]
+    async fn upload_payload(name: String, payload: String) -> Result<(), String> {
+        let _ = (name, payload);
+        Ok(())
+    }
+
+    #[test]
+    fn test_secret_args_never_recorded() {
+        let profiler = FluxelProfiler::new(100);
+        let subscriber = tracing_subscriber::registry().with(profiler.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            futures::executor::block_on(async {
+                connect_host(
+                    "github.com".to_string(),
+                    "ghp_1111".to_string(),
+                    Some("sk-2222".to_string()),
+                    "tok-3333".to_string(),
+                )
+                .await
+                .unwrap();
+                upload_payload("notes".to_string(), "blob-4444".to_string())
+                    .await
+                    .unwrap();
+            });
+        });
+
+        let spans = profiler.recent_spans(10);
+        let args = |name: &str| {
+            let span = spans.iter().find(|s| s.name == name).unwrap();
+            let (_, args) = span.fields.iter().find(|(k, _)| k == "args").unwrap();
+            args.clone()
+        };
+        assert_eq!(
+            args("connect_host"),
+            "host=\"github.com\", token=<redacted>, api_key=<redacted>, access_token=<redacted>"
+        );
+        assert_eq!(args("upload_payload"), "name=\"notes\"");
+        for span in &spans {
+            for (_, value) in &span.fields {
+                for secret in ["1111", "2222", "3333", "4444"] {
+                    assert!(!value.contains(secret), "{} leaked {}", span.name, value);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,18 @@
 //! Attribution engine for analyzing span trees and answering "Why was this slow?"
 //!
-//! Provides latency attribution by category, critical path analysis, and hotspot detection.
+//! Provides latency attribution by category, critical path analysis, hotspot detection
+//! by time and by I/O volume, and detection of spans that blocked the IPC thread or an
+//! async worker.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::profiling::buffer::{SpanCategory, SpanSummary};
 
+/// Longest a span may hold the IPC thread or an async worker in one stretch
+/// before it is reported as blocking.
+const BLOCKING_THRESHOLD_MS: f64 = 10.0;
+
 /// Breakdown of time spent in a specific category.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,6 +58,207 @@ pub struct AttributionReport {
     pub hotspots: Vec<SpanSummary>,
     /// Hierarchical tree structure for flame graph visualization.
     pub tree: Option<SpanTreeNode>,
+    /// Time and tokens spent in AI requests, if the operation made any.
+    pub ai_usage: Option<AiUsageSummary>,
+    /// Top spans by bytes read and written, then by files touched.
+    pub io_hotspots: Vec<SpanIo>,
+    /// Spans that held the IPC thread or an async worker longer than the
+    /// blocking threshold in one stretch, longest first.
+    pub blocking_spans: Vec<BlockingSpan>,
+}
+
+/// A span that blocked a thread other commands depend on, typically synchronous
+/// I/O in an async command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockingSpan {
+    pub span: SpanSummary,
+    /// Name of the blocked thread.
+    pub thread: String,
+    /// `ipc` or `async_worker`.
+    pub thread_kind: String,
+    /// Longest stretch the span held the thread; for async spans, the longest poll.
+    pub longest_poll_ms: f64,
+    /// Total time the span held a thread.
+    pub busy_ms: f64,
+}
+
+impl BlockingSpan {
+    /// The span, if it blocked the IPC thread or an async worker for longer
+    /// than `threshold_ms`.
+    fn from_span(span: &SpanSummary, threshold_ms: f64) -> Option<Self> {
+        let field = |key: &str| {
+            span.fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        let thread_kind = field("thread_kind")?;
+        if !matches!(thread_kind, "ipc" | "async_worker") {
+            return None;
+        }
+        let longest_poll_ms = field("longest_poll_ms")?.parse::<f64>().ok()?;
+        if longest_poll_ms <= threshold_ms {
+            return None;
+        }
+        Some(Self {
+            span: span.clone(),
+            thread: field("thread").unwrap_or_default().to_string(),
+            thread_kind: thread_kind.to_string(),
+            longest_poll_ms,
+            busy_ms: field("busy_ms")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(longest_poll_ms),
+        })
+    }
+}
+
+/// I/O recorded on a span through its `bytes_*` / `files_*` fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanIo {
+    pub span: SpanSummary,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    pub files_read: u64,
+    pub files_written: u64,
+    /// Self time, to compare with the I/O volume.
+    pub self_time_ms: f64,
+    /// Bytes per second over the span's duration; low throughput on a slow
+    /// span points at CPU rather than disk.
+    pub throughput_bytes_per_sec: f64,
+}
+
+impl SpanIo {
+    /// The span's I/O, or `None` if it recorded none.
+    fn from_span(span: &SpanSummary, self_time_ms: f64) -> Option<Self> {
+        let count = |key: &str| {
+            span.fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| v.parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        let io = Self {
+            span: span.clone(),
+            bytes_read: count("bytes_read"),
+            bytes_written: count("bytes_written"),
+            files_read: count("files_read"),
+            files_written: count("files_written"),
+            self_time_ms,
+            throughput_bytes_per_sec: 0.0,
+        };
+        if io.total_bytes() == 0 && io.total_files() == 0 {
+            return None;
+        }
+        Some(Self {
+            throughput_bytes_per_sec: if span.duration_ms > 0.0 {
+                io.total_bytes() as f64 / (span.duration_ms / 1000.0)
+            } else {
+                0.0
+            },
+            ..io
+        })
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.bytes_read + self.bytes_written
+    }
+
+    fn total_files(&self) -> u64 {
+        self.files_read + self.files_written
+    }
+}
+
+/// Time and token usage of one provider/model within an operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiModelUsage {
+    pub provider: String,
+    pub model: String,
+    pub request_count: usize,
+    pub total_time_ms: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// How much of an operation's latency and token cost went to AI requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiUsageSummary {
+    pub request_count: usize,
+    /// Wall-clock time spent waiting on models.
+    pub total_time_ms: f64,
+    /// Share of the operation's total time.
+    pub percentage: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Per provider/model breakdown, slowest first.
+    pub models: Vec<AiModelUsage>,
+}
+
+impl AiUsageSummary {
+    /// Summarize the `AiRequest` spans among `spans`, or `None` if there are none.
+    /// Token counts come from the `input_tokens` / `output_tokens` span fields.
+    pub fn from_spans<'a>(
+        spans: impl IntoIterator<Item = &'a SpanSummary>,
+        total_time_ms: f64,
+    ) -> Option<Self> {
+        let mut by_model: HashMap<(String, String), AiModelUsage> = HashMap::new();
+        for span in spans {
+            if span.category != SpanCategory::AiRequest {
+                continue;
+            }
+            let field = |key: &str| {
+                span.fields
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.as_str())
+            };
+            let provider = field("provider").unwrap_or("unknown").to_string();
+            let model = field("model").unwrap_or_default().to_string();
+            let tokens = |key: &str| field(key).and_then(|v| v.parse::<u64>().ok());
+
+            let entry = by_model
+                .entry((provider.clone(), model.clone()))
+                .or_insert_with(|| AiModelUsage {
+                    provider,
+                    model,
+                    request_count: 0,
+                    total_time_ms: 0.0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                });
+            entry.request_count += 1;
+            entry.total_time_ms += span.duration_ms;
+            entry.input_tokens += tokens("input_tokens").unwrap_or(0);
+            entry.output_tokens += tokens("output_tokens").unwrap_or(0);
+        }
+        if by_model.is_empty() {
+            return None;
+        }
+
+        let mut models: Vec<_> = by_model.into_values().collect();
+        models.sort_by(|a, b| {
+            b.total_time_ms
+                .partial_cmp(&a.total_time_ms)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let total = models.iter().map(|m| m.total_time_ms).sum::<f64>();
+
+        Some(Self {
+            request_count: models.iter().map(|m| m.request_count).sum(),
+            total_time_ms: total,
+            percentage: if total_time_ms > 0.0 {
+                (total / total_time_ms * 100.0).min(100.0)
+            } else {
+                0.0
+            },
+            input_tokens: models.iter().map(|m| m.input_tokens).sum(),
+            output_tokens: models.iter().map(|m| m.output_tokens).sum(),
+            models,
+        })
+    }
 }
 
 /// Engine for computing attribution reports.
@@ -102,6 +309,24 @@ impl AttributionEngine {
         // Build hierarchical tree for flame graph
         let tree = Self::build_tree_node(&root, &children_map, &self_times, 0);
 
+        let ai_usage = AiUsageSummary::from_spans(spans, total_time_ms);
+
+        // Rank by I/O volume (top 10)
+        let mut io_hotspots: Vec<SpanIo> = spans
+            .iter()
+            .filter_map(|s| SpanIo::from_span(s, *self_times.get(&s.id).unwrap_or(&0.0)))
+            .collect();
+        io_hotspots.sort_by(|a, b| {
+            (b.total_bytes(), b.total_files()).cmp(&(a.total_bytes(), a.total_files()))
+        });
+        io_hotspots.truncate(10);
+
+        let mut blocking_spans: Vec<BlockingSpan> = spans
+            .iter()
+            .filter_map(|s| BlockingSpan::from_span(s, BLOCKING_THRESHOLD_MS))
+            .collect();
+        blocking_spans.sort_by(|a, b| b.longest_poll_ms.total_cmp(&a.longest_poll_ms));
+
         AttributionReport {
             root_span: root,
             total_time_ms,
@@ -109,6 +334,9 @@ impl AttributionEngine {
             critical_path,
             hotspots,
             tree: Some(tree),
+            ai_usage,
+            io_hotspots,
+            blocking_spans,
         }
     }
 
@@ -472,6 +700,112 @@ mod comprehensive_tests {
         let tree = report.tree.unwrap();
         assert_eq!(tree.children.len(), 10);
     }
+
+    #[test]
+    fn test_ai_usage_attribution() {
+        let root = make_span("1", None, SpanCategory::TauriCommand, 0.0, 1000.0, "root");
+        let ai_request = |id: &str, model: &str, start: f64, duration: f64, tokens: &str| {
+            let mut span = make_span(
+                id,
+                Some("1"),
+                SpanCategory::AiRequest,
+                start,
+                duration,
+                "ai_request",
+            );
+            span.fields = vec![
+                ("provider".to_string(), "ollama".to_string()),
+                ("model".to_string(), model.to_string()),
+                ("input_tokens".to_string(), "100".to_string()),
+                ("output_tokens".to_string(), tokens.to_string()),
+            ];
+            span
+        };
+        let spans = vec![
+            root.clone(),
+            ai_request("2", "qwen", 0.0, 300.0, "40"),
+            ai_request("3", "qwen", 300.0, 200.0, "10"),
+            ai_request("4", "llama", 500.0, 100.0, "5"),
+            make_span("5", Some("1"), SpanCategory::FileIo, 600.0, 50.0, "read"),
+        ];
+
+        let usage = AttributionEngine::analyze(root, &spans).ai_usage.unwrap();
+        assert_eq!(usage.request_count, 3);
+        assert_eq!(usage.total_time_ms, 600.0);
+        assert_eq!(usage.percentage, 60.0);
+        assert_eq!((usage.input_tokens, usage.output_tokens), (300, 55));
+        assert_eq!(usage.models[0].model, "qwen");
+        assert_eq!(usage.models[0].output_tokens, 50);
+
+        let quiet = make_span("9", None, SpanCategory::FileIo, 0.0, 10.0, "quiet");
+        assert!(AttributionEngine::analyze(quiet.clone(), &[quiet])
+            .ai_usage
+            .is_none());
+    }
+
+    #[test]
+    fn test_io_hotspots() {
+        let root = make_span("1", None, SpanCategory::TauriCommand, 0.0, 500.0, "root");
+        let io_span = |id: &str, duration: f64, fields: &[(&str, &str)]| {
+            let mut span = make_span(id, Some("1"), SpanCategory::FileIo, 0.0, duration, "io");
+            span.fields = fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            span
+        };
+        let spans = vec![
+            root.clone(),
+            // Slow but little data: CPU-bound
+            io_span("2", 300.0, &[("bytes_read", "1000"), ("files_read", "1")]),
+            io_span(
+                "3",
+                100.0,
+                &[("bytes_read", "4000000"), ("bytes_written", "1000000")],
+            ),
+            io_span("4", 50.0, &[("files_read", "120")]),
+            io_span("5", 10.0, &[("category", "file_io")]),
+        ];
+
+        let io = AttributionEngine::analyze(root, &spans).io_hotspots;
+        let ids: Vec<&str> = io.iter().map(|s| s.span.id.as_str()).collect();
+        assert_eq!(ids, ["3", "2", "4"]);
+        assert_eq!(io[0].bytes_written, 1_000_000);
+        assert_eq!(io[0].throughput_bytes_per_sec, 50_000_000.0);
+        assert_eq!(io[1].throughput_bytes_per_sec, 1000.0 / 0.3);
+        assert_eq!(io[2].files_read, 120);
+    }
+
+    #[test]
+    fn test_blocking_spans() {
+        let root = make_span("1", None, SpanCategory::TauriCommand, 0.0, 500.0, "root");
+        let ran_on = |id: &str, kind: &str, longest_poll: &str| {
+            let mut span = make_span(id, Some("1"), SpanCategory::FileIo, 0.0, 100.0, "io");
+            span.fields = vec![
+                ("thread".to_string(), format!("{}-thread", kind)),
+                ("thread_kind".to_string(), kind.to_string()),
+                ("busy_ms".to_string(), "90.000".to_string()),
+                ("longest_poll_ms".to_string(), longest_poll.to_string()),
+            ];
+            span
+        };
+        let spans = vec![
+            root.clone(),
+            ran_on("2", "async_worker", "40.000"),
+            ran_on("3", "ipc", "80.000"),
+            // Waiting is expected on the blocking pool
+            ran_on("4", "blocking", "90.000"),
+            // Short polls
+            ran_on("5", "async_worker", "2.000"),
+        ];
+
+        let blocking = AttributionEngine::analyze(root, &spans).blocking_spans;
+        let ids: Vec<&str> = blocking.iter().map(|s| s.span.id.as_str()).collect();
+        assert_eq!(ids, ["3", "2"]);
+        assert_eq!(blocking[0].thread, "ipc-thread");
+        assert_eq!(blocking[1].thread_kind, "async_worker");
+        assert_eq!(blocking[1].busy_ms, 90.0);
+    }
 }
 
 #[cfg(test)]
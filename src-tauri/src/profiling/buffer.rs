@@ -4,7 +4,7 @@
 //! When capacity is reached, oldest entries are automatically dropped.
 
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 
 /// Unique identifier for a span, derived from tracing's span ID.
@@ -28,6 +28,8 @@ pub enum SpanCategory {
     FrontendNetwork,
     /// Generic backend operation
     BackendOperation,
+    /// Requests to AI providers; provider, model and token counts are span fields
+    AiRequest,
     Other,
 }
 
@@ -47,6 +49,7 @@ impl SpanCategory {
                     "frontend_interaction" => SpanCategory::FrontendInteraction,
                     "frontend_network" => SpanCategory::FrontendNetwork,
                     "tauri_command" => SpanCategory::TauriCommand,
+                    "ai" => SpanCategory::AiRequest,
                     _ => SpanCategory::Other,
                 };
             }
@@ -142,6 +145,71 @@ impl SpanSummary {
     }
 }
 
+/// Restricts span queries to one workspace and/or file, for multi-window traces.
+///
+/// Spans match on their own `workspace_root` / `file` fields or, failing that,
+/// those of their nearest ancestor, so helper spans follow the command that ran them.
+#[derive(Debug, Clone, Default)]
+pub struct SpanFilter {
+    /// Workspace root path.
+    pub workspace: Option<String>,
+    /// File path, absolute or relative to the workspace.
+    pub file: Option<String>,
+}
+
+impl SpanFilter {
+    /// Whether the filter lets every span through.
+    pub fn is_empty(&self) -> bool {
+        self.workspace.is_none() && self.file.is_none()
+    }
+
+    fn matches(&self, span: &CompletedSpan, spans: &HashMap<SpanId, &CompletedSpan>) -> bool {
+        let workspace_matches = self.workspace.as_deref().is_none_or(|workspace| {
+            inherited_field(span, "workspace_root", spans)
+                .is_some_and(|root| same_path(&normalize_path(root), &normalize_path(workspace)))
+        });
+        let file_matches = self.file.as_deref().is_none_or(|file| {
+            inherited_field(span, "file", spans).is_some_and(|path| {
+                let (path, file) = (normalize_path(path), normalize_path(file));
+                same_path(&path, &file)
+                    || path.len() > file.len()
+                        && path.ends_with(&file)
+                        && path[..path.len() - file.len()].ends_with('/')
+            })
+        });
+        workspace_matches && file_matches
+    }
+}
+
+/// Value of `key` on the span or its nearest ancestor that has it.
+fn inherited_field<'a>(
+    span: &'a CompletedSpan,
+    key: &str,
+    spans: &HashMap<SpanId, &'a CompletedSpan>,
+) -> Option<&'a str> {
+    let mut current = span;
+    // Bounded in case frontend-provided parent IDs form a cycle
+    for _ in 0..64 {
+        if let Some((_, value)) = current.fields.iter().find(|(k, _)| k == key) {
+            return Some(value);
+        }
+        current = spans.get(&current.parent_id?)?;
+    }
+    None
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
 /// Bounded ring buffer for span storage.
 ///
 /// Thread-safe wrapper around VecDeque with automatic eviction
@@ -190,6 +258,24 @@ impl RingBuffer {
             .collect()
     }
 
+    /// Get the most recent N spans that pass `filter`.
+    pub fn recent_filtered(&self, limit: usize, filter: &SpanFilter) -> Vec<SpanSummary> {
+        if filter.is_empty() {
+            return self.recent(limit);
+        }
+        let reference = self.reference_time.unwrap_or_else(Instant::now);
+        let spans: HashMap<SpanId, &CompletedSpan> =
+            self.data.iter().map(|span| (span.id, span)).collect();
+
+        self.data
+            .iter()
+            .rev()
+            .filter(|span| filter.matches(span, &spans))
+            .take(limit)
+            .map(|span| SpanSummary::from_completed(span, reference))
+            .collect()
+    }
+
     /// Get all spans that are descendants of the given root span.
     pub fn find_tree(&self, root_id: SpanId) -> Vec<&CompletedSpan> {
         let mut tree = Vec::new();
@@ -281,6 +367,60 @@ mod tests {
         assert_eq!(recent[2].name, "span_2");
     }
 
+    #[test]
+    fn test_filter_by_workspace_and_file() {
+        let mut buffer = RingBuffer::new(10);
+        let now = Instant::now();
+        let mut push = |id: SpanId, parent_id: Option<SpanId>, field: Option<(&str, &str)>| {
+            buffer.push(CompletedSpan {
+                id,
+                parent_id,
+                name: format!("span_{}", id),
+                target: "test".to_string(),
+                category: SpanCategory::Other,
+                start_time: now,
+                end_time: now,
+                duration_ns: 1000,
+                fields: field
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .into_iter()
+                    .collect(),
+            });
+        };
+        push(1, None, Some(("workspace_root", "/work/a")));
+        push(2, Some(1), Some(("file", "/work/a/src/main.rs")));
+        push(3, Some(2), None);
+        push(4, None, Some(("workspace_root", "C:\\work\\b\\")));
+        push(5, None, None);
+
+        let ids = |filter: SpanFilter, limit: usize| -> Vec<String> {
+            buffer
+                .recent_filtered(limit, &filter)
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+        let workspace = |w: &str| SpanFilter {
+            workspace: Some(w.to_string()),
+            file: None,
+        };
+
+        // Descendants inherit the workspace of the command span
+        assert_eq!(ids(workspace("/work/a/"), 10), ["3", "2", "1"]);
+        assert_eq!(ids(workspace("/work/a"), 2), ["3", "2"]);
+        assert_eq!(ids(workspace("C:/work/b"), 10), ["4"]);
+        assert_eq!(ids(SpanFilter::default(), 10).len(), 5);
+
+        // Files match by full path or by a workspace-relative suffix
+        let file = |f: &str| SpanFilter {
+            workspace: Some("/work/a".to_string()),
+            file: Some(f.to_string()),
+        };
+        assert_eq!(ids(file("src/main.rs"), 10), ["3", "2"]);
+        assert_eq!(ids(file("/work/a/src/main.rs"), 10), ["3", "2"]);
+        assert!(ids(file("ain.rs"), 10).is_empty());
+    }
+
     #[test]
     fn test_category_inference() {
         assert_eq!(
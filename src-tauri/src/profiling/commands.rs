@@ -2,8 +2,9 @@
 //!
 //! Exposes commands to the frontend:
 //! - `profiler_set_enabled` - Enable/disable span collection
+//! - `profiler_set_sampling` - Configure span sampling for long sessions
 //! - `profiler_get_status` - Get profiler status
-//! - `profiler_get_recent_spans` - Get recent span summaries
+//! - `profiler_get_recent_spans` - Get recent span summaries, optionally per workspace/file
 //! - `profiler_get_attribution` - Get attribution report for a span tree
 //! - `profiler_clear` - Clear all stored spans
 //! - `profiler_start_session` - Start a named profiling session
@@ -11,13 +12,19 @@
 //! - `profiler_record_frontend_span` - Record a span from the frontend
 //! - `profiler_record_frontend_spans_batch` - Record multiple spans (batched)
 //! - `profiler_export` - Export spans as JSON or Chrome Trace format
+//! - `profiler_export_flamegraph` - Write a span tree as a flamegraph SVG
+//! - `profiler_finish_startup` - Finalize the startup session when the frontend is ready
+//! - `profiler_get_startup_report` - Get the cold start breakdown
 
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
 use crate::profiling::attribution::{AttributionEngine, AttributionReport};
-use crate::profiling::buffer::{SpanCategory, SpanId, SpanSummary};
+use crate::profiling::buffer::{SpanCategory, SpanFilter, SpanId, SpanSummary};
+use crate::profiling::flamegraph;
+use crate::profiling::sampling::{SamplingConfig, SamplingStats};
 use crate::profiling::sessions::SessionReport;
+use crate::profiling::startup::StartupReport;
 use crate::profiling::FluxelProfiler;
 
 /// Profiler status response.
@@ -32,6 +39,10 @@ pub struct ProfilerStatus {
     pub buffer_capacity: usize,
     /// Active session ID, if any.
     pub active_session_id: Option<String>,
+    /// Span sampling configuration.
+    pub sampling: SamplingConfig,
+    /// Sampling counters since the last clear.
+    pub sampling_stats: SamplingStats,
 }
 
 /// Frontend span input for recording.
@@ -74,6 +85,24 @@ pub fn profiler_set_enabled(state: State<'_, FluxelProfiler>, enabled: bool) {
     );
 }
 
+/// Configure span sampling.
+///
+/// When enabled, 1 in `sampleRate` span trees is recorded; spans slower than
+/// `slowThresholdMs` or that recorded an error are always kept.
+#[tauri::command]
+pub fn profiler_set_sampling(state: State<'_, FluxelProfiler>, config: SamplingConfig) {
+    state.set_sampling(config);
+    let config = state.sampling();
+    if config.enabled {
+        println!(
+            "[Profiling] Sampling 1 in {} span trees (always keeping spans >= {}ms)",
+            config.sample_rate, config.slow_threshold_ms
+        );
+    } else {
+        println!("[Profiling] Sampling disabled");
+    }
+}
+
 /// Get the current profiler status.
 #[tauri::command]
 pub fn profiler_get_status(state: State<'_, FluxelProfiler>) -> ProfilerStatus {
@@ -82,6 +111,8 @@ pub fn profiler_get_status(state: State<'_, FluxelProfiler>) -> ProfilerStatus {
         span_count: state.span_count(),
         buffer_capacity: state.capacity(),
         active_session_id: state.active_session_id(),
+        sampling: state.sampling(),
+        sampling_stats: state.sampling_stats(),
     }
 }
 
@@ -89,12 +120,16 @@ pub fn profiler_get_status(state: State<'_, FluxelProfiler>) -> ProfilerStatus {
 ///
 /// # Arguments
 /// * `limit` - Maximum number of spans to return (default: 100)
+/// * `workspace` - Only spans run for this workspace root
+/// * `file` - Only spans run for this file (absolute or workspace-relative)
 #[tauri::command]
 pub fn profiler_get_recent_spans(
     state: State<'_, FluxelProfiler>,
     limit: Option<usize>,
+    workspace: Option<String>,
+    file: Option<String>,
 ) -> Vec<SpanSummary> {
-    state.recent_spans(limit.unwrap_or(100))
+    state.filtered_spans(limit.unwrap_or(100), &SpanFilter { workspace, file })
 }
 
 /// Get an attribution report for a span tree.
@@ -105,6 +140,14 @@ pub fn profiler_get_recent_spans(
 pub fn profiler_get_attribution(
     state: State<'_, FluxelProfiler>,
     root_span_id: String,
+) -> Result<AttributionReport, String> {
+    attribution_report(&state, &root_span_id)
+}
+
+/// Build the attribution report for the span tree under `root_span_id`.
+fn attribution_report(
+    state: &FluxelProfiler,
+    root_span_id: &str,
 ) -> Result<AttributionReport, String> {
     // Parse span ID
     let root_id: SpanId = root_span_id
@@ -163,16 +206,42 @@ pub fn profiler_start_session(state: State<'_, FluxelProfiler>, name: String) ->
 ///
 /// # Arguments
 /// * `session_id` - The session ID returned from `profiler_start_session`
+/// * `workspace` - Only aggregate spans run for this workspace root
+/// * `file` - Only aggregate spans run for this file
 #[tauri::command]
 pub fn profiler_end_session(
     state: State<'_, FluxelProfiler>,
     session_id: String,
+    workspace: Option<String>,
+    file: Option<String>,
 ) -> Result<SessionReport, String> {
     state
-        .end_session(&session_id)
+        .end_session(&session_id, &SpanFilter { workspace, file })
         .ok_or_else(|| format!("Session not found or already ended: {}", session_id))
 }
 
+// =============================================================================
+// Startup Commands
+// =============================================================================
+
+/// Finalize the startup session. Called by the frontend once it is ready; later
+/// calls return the first report.
+#[tauri::command]
+pub fn profiler_finish_startup(state: State<'_, FluxelProfiler>) -> Option<StartupReport> {
+    let first = state.startup_report().is_none();
+    let report = state.finish_startup();
+    if let (true, Some(report)) = (first, &report) {
+        println!("[Profiling] Startup finished in {:.1}ms", report.total_ms);
+    }
+    report
+}
+
+/// Get the cold start breakdown, once the frontend has signaled ready.
+#[tauri::command]
+pub fn profiler_get_startup_report(state: State<'_, FluxelProfiler>) -> Option<StartupReport> {
+    state.startup_report()
+}
+
 // =============================================================================
 // Frontend Span Recording
 // =============================================================================
@@ -213,6 +282,7 @@ fn record_frontend_span_internal(state: &FluxelProfiler, span: FrontendSpanInput
         "lsp_request" => SpanCategory::LspRequest,
         "search" => SpanCategory::Search,
         "workspace" => SpanCategory::Workspace,
+        "ai_request" => SpanCategory::AiRequest,
         _ => SpanCategory::Other,
     };
 
@@ -253,3 +323,25 @@ pub fn profiler_export(
         }
     }
 }
+
+/// Render the span tree under a root span as a flamegraph SVG and write it to `path`.
+///
+/// # Arguments
+/// * `root_span_id` - The ID of the root span to render
+/// * `path` - Destination file for the SVG
+#[tauri::command]
+pub fn profiler_export_flamegraph(
+    state: State<'_, FluxelProfiler>,
+    root_span_id: String,
+    path: String,
+) -> Result<(), String> {
+    let report = attribution_report(&state, &root_span_id)?;
+    let svg = flamegraph::render_svg(&report)
+        .ok_or_else(|| format!("No span tree for span: {}", root_span_id))?;
+    std::fs::write(&path, svg).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    println!(
+        "[Profiling] Exported flamegraph for span {} to {}",
+        root_span_id, path
+    );
+    Ok(())
+}
@@ -0,0 +1,279 @@
+//! Flamegraph SVG export for attribution reports.
+//!
+//! Renders a span tree as a standalone, inferno-style SVG that can be attached to
+//! issues. Frames are stacked root-at-bottom and colored by category like the
+//! in-app flame graph. A frame's width is its self time plus the widths of its
+//! children, so concurrent children never overflow their parent.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::profiling::attribution::{AttributionReport, SpanTreeNode};
+use crate::profiling::buffer::SpanCategory;
+
+const IMAGE_WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: f64 = 16.0;
+const PADDING: f64 = 10.0;
+/// Space above the frames for the title and legend
+const HEADER_HEIGHT: f64 = 56.0;
+const FONT_SIZE: f64 = 12.0;
+/// Approximate glyph width used to fit labels
+const CHAR_WIDTH: f64 = 7.0;
+/// Frames narrower than this are dropped, as inferno does
+const MIN_FRAME_WIDTH: f64 = 0.1;
+
+/// A laid-out frame.
+struct Frame<'a> {
+    node: &'a SpanTreeNode,
+    x: f64,
+    width: f64,
+    depth: usize,
+}
+
+/// Render the report's span tree as an SVG document.
+pub fn render_svg(report: &AttributionReport) -> Option<String> {
+    let root = report.tree.as_ref()?;
+    let total = layout_width(root);
+    let scale = if total > 0.0 {
+        (IMAGE_WIDTH - 2.0 * PADDING) / total
+    } else {
+        0.0
+    };
+
+    let mut frames = Vec::new();
+    layout(root, PADDING, 0, scale, &mut frames);
+    let max_depth = frames.iter().map(|f| f.depth).max().unwrap_or(0);
+    let height = HEADER_HEIGHT + (max_depth + 1) as f64 * FRAME_HEIGHT + PADDING;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Verdana, sans-serif" font-size="{f}">"#,
+        w = IMAGE_WIDTH,
+        h = height,
+        f = FONT_SIZE
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect x="0" y="0" width="100%" height="100%" fill="#f8f8f8"/>"##
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="24" text-anchor="middle" font-size="17">{} ({:.2}ms)</text>"#,
+        IMAGE_WIDTH / 2.0,
+        escape(&report.root_span.name),
+        report.total_time_ms
+    );
+
+    // Legend of the categories present
+    let categories: BTreeMap<&str, SpanCategory> = frames
+        .iter()
+        .map(|f| (category_name(f.node.span.category), f.node.span.category))
+        .collect();
+    let mut x = PADDING;
+    for (name, category) in categories {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="36" width="10" height="10" fill="{}"/><text x="{:.1}" y="45">{}</text>"#,
+            x,
+            color(category, 0.5),
+            x + 14.0,
+            name
+        );
+        x += 14.0 + name.len() as f64 * CHAR_WIDTH + 16.0;
+    }
+
+    for frame in &frames {
+        let span = &frame.node.span;
+        let y = height - PADDING - (frame.depth + 1) as f64 * FRAME_HEIGHT;
+        let self_ratio = if span.duration_ms > 0.0 {
+            frame.node.self_time_ms / span.duration_ms
+        } else {
+            0.0
+        };
+        let _ = writeln!(svg, "<g>");
+        let _ = writeln!(
+            svg,
+            "<title>{} ({}, {:.2}ms total, {:.2}ms self)</title>",
+            escape(&span.name),
+            category_name(span.category),
+            span.duration_ms,
+            frame.node.self_time_ms
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.2}" y="{:.1}" width="{:.2}" height="{:.1}" fill="{}" rx="2" ry="2"/>"#,
+            frame.x,
+            y,
+            frame.width,
+            FRAME_HEIGHT - 1.0,
+            color(span.category, self_ratio)
+        );
+        if let Some(label) = fit_label(&span.name, frame.width) {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.1}">{}</text>"#,
+                frame.x + 3.0,
+                y + FRAME_HEIGHT - 4.5,
+                escape(&label)
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Self time plus the widths of the children, in milliseconds
+fn layout_width(node: &SpanTreeNode) -> f64 {
+    node.self_time_ms + node.children.iter().map(layout_width).sum::<f64>()
+}
+
+fn layout<'a>(
+    node: &'a SpanTreeNode,
+    x: f64,
+    depth: usize,
+    scale: f64,
+    frames: &mut Vec<Frame<'a>>,
+) {
+    let width = layout_width(node) * scale;
+    if width < MIN_FRAME_WIDTH {
+        return;
+    }
+    frames.push(Frame {
+        node,
+        x,
+        width,
+        depth,
+    });
+    let mut child_x = x;
+    for child in &node.children {
+        layout(child, child_x, depth + 1, scale, frames);
+        child_x += layout_width(child) * scale;
+    }
+}
+
+/// `name` shortened to fit in `width` pixels, or `None` if there's no room
+fn fit_label(name: &str, width: f64) -> Option<String> {
+    let available = ((width - 6.0) / CHAR_WIDTH).floor() as usize;
+    let count = name.chars().count();
+    if count <= available {
+        Some(name.to_string())
+    } else if available >= 3 {
+        Some(format!(
+            "{}..",
+            name.chars().take(available - 2).collect::<String>()
+        ))
+    } else {
+        None
+    }
+}
+
+fn category_name(category: SpanCategory) -> &'static str {
+    match category {
+        SpanCategory::TauriCommand => "tauri_command",
+        SpanCategory::FileIo => "file_io",
+        SpanCategory::GitOperation => "git_operation",
+        SpanCategory::LspRequest => "lsp_request",
+        SpanCategory::Search => "search",
+        SpanCategory::Workspace => "workspace",
+        SpanCategory::FrontendRender => "frontend_render",
+        SpanCategory::FrontendInteraction => "frontend_interaction",
+        SpanCategory::FrontendNetwork => "frontend_network",
+        SpanCategory::BackendOperation => "backend_operation",
+        SpanCategory::AiRequest => "ai_request",
+        SpanCategory::Other => "other",
+    }
+}
+
+/// Category hue, saturated and darkened by the share of self time (matches
+/// `FlameGraphView`)
+fn color(category: SpanCategory, self_ratio: f64) -> String {
+    let hue = match category {
+        SpanCategory::FrontendRender => 220,
+        SpanCategory::FrontendInteraction => 280,
+        SpanCategory::FrontendNetwork => 140,
+        SpanCategory::TauriCommand => 30,
+        SpanCategory::BackendOperation => 180,
+        SpanCategory::FileIo => 50,
+        SpanCategory::GitOperation => 320,
+        SpanCategory::LspRequest => 260,
+        SpanCategory::AiRequest => 350,
+        _ => 0,
+    };
+    let saturation = 50.0 + self_ratio * 40.0;
+    let lightness = 65.0 - self_ratio * 15.0;
+    format!("hsl({}, {:.0}%, {:.0}%)", hue, saturation, lightness)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiling::attribution::AttributionEngine;
+    use crate::profiling::buffer::SpanSummary;
+
+    fn span(
+        id: &str,
+        parent: Option<&str>,
+        category: SpanCategory,
+        start: f64,
+        duration: f64,
+    ) -> SpanSummary {
+        SpanSummary {
+            id: id.to_string(),
+            parent_id: parent.map(str::to_string),
+            name: format!("span<{}>", id),
+            target: "test".to_string(),
+            category,
+            start_time_ms: start,
+            duration_ms: duration,
+            fields: vec![],
+        }
+    }
+
+    #[test]
+    fn renders_frames_with_self_time_widths() {
+        // Two concurrent 80ms children under a 100ms root: by wall clock they'd
+        // overflow it, by self-time layout the root is 20 + 80 + 80 wide
+        let spans = vec![
+            span("1", None, SpanCategory::TauriCommand, 0.0, 100.0),
+            span("2", Some("1"), SpanCategory::GitOperation, 0.0, 80.0),
+            span("3", Some("1"), SpanCategory::AiRequest, 10.0, 80.0),
+        ];
+        let report = AttributionEngine::analyze(spans[0].clone(), &spans);
+        let svg = render_svg(&report).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("span&lt;1&gt; (100.00ms)"));
+        assert!(svg.contains("<title>span&lt;3&gt; (ai_request, 80.00ms total, 80.00ms self)"));
+        assert!(svg.contains("hsl(350,"));
+        assert!(svg.contains(">git_operation</text>"));
+
+        let root = report.tree.as_ref().unwrap();
+        assert_eq!(layout_width(root), 160.0);
+        let mut frames = Vec::new();
+        layout(root, PADDING, 0, 1.0, &mut frames);
+        let geometry: Vec<(f64, f64, usize)> =
+            frames.iter().map(|f| (f.x, f.width, f.depth)).collect();
+        assert_eq!(
+            geometry,
+            vec![(10.0, 160.0, 0), (10.0, 80.0, 1), (90.0, 80.0, 1)]
+        );
+
+        assert_eq!(
+            fit_label("render_tree", 200.0).as_deref(),
+            Some("render_tree")
+        );
+        assert_eq!(fit_label("render_tree", 55.0).as_deref(), Some("rende.."));
+        assert_eq!(fit_label("render_tree", 20.0), None);
+    }
+}
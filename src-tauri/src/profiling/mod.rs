@@ -8,20 +8,32 @@
 //! - `subscriber`: Custom tracing Layer that captures span lifecycle events
 //! - `buffer`: Bounded ring buffer for span storage (no heap allocation on push)
 //! - `attribution`: Latency analysis and "Why was this slow?" reports
+//! - `flamegraph`: Standalone flamegraph SVG rendering of span trees
+//! - `sampling`: 1-in-N span sampling that always keeps slow and failing spans
 //! - `sessions`: Session management for before/after comparisons and export
+//! - `startup`: Cold start breakdown, finalized when the frontend is ready
+//! - `threads`: Which thread spans ran on: IPC, async worker, or blocking pool
+//! - `args`: Argument summaries for the spans `#[fluxel_command]` opens
 //! - `commands`: Tauri commands exposing profiler data to the frontend
 //!
 //! # Usage
 //!
 //! ```rust,ignore
-//! // In lib.rs setup
+//! // At the start of lib.rs `run()`, before app state is built, so the startup
+//! // session covers it
 //! #[cfg(feature = "profiling")]
-//! {
-//!     let profiler = profiling::init();
-//!     app.manage(profiler);
-//! }
+//! let profiler = profiling::init();
+//!
+//! // In setup
+//! #[cfg(feature = "profiling")]
+//! app.manage(profiler);
 //! ```
+//!
+//! The frontend calls `profiler_finish_startup` once it is ready to finalize the
+//! cold start report.
 
+#[cfg(feature = "profiling")]
+pub mod args;
 #[cfg(feature = "profiling")]
 mod attribution;
 #[cfg(feature = "profiling")]
@@ -29,10 +41,20 @@ mod buffer;
 #[cfg(feature = "profiling")]
 pub mod commands;
 #[cfg(feature = "profiling")]
+mod flamegraph;
+#[cfg(feature = "profiling")]
+mod sampling;
+#[cfg(feature = "profiling")]
 mod sessions;
 #[cfg(feature = "profiling")]
+mod startup;
+#[cfg(feature = "profiling")]
 mod subscriber;
+#[cfg(feature = "profiling")]
+mod threads;
 
+#[cfg(feature = "profiling")]
+pub use buffer::{SpanCategory, SpanFilter, SpanSummary};
 #[cfg(feature = "profiling")]
 pub use subscriber::FluxelProfiler;
 
@@ -40,10 +62,13 @@ pub use subscriber::FluxelProfiler;
 use tracing_subscriber::prelude::*;
 
 /// Initialize the profiling subscriber and install it as the global default.
+/// Call it on the main thread, before Tauri starts its async runtime.
 /// Returns the profiler instance for Tauri state management.
 #[cfg(feature = "profiling")]
 pub fn init() -> FluxelProfiler {
     let profiler = FluxelProfiler::new(10_000); // 10k span capacity
+    profiler.begin_startup();
+    threads::install_runtime();
 
     // Clone for the layer (profiler is Arc-wrapped internally)
     let layer = profiler.clone();
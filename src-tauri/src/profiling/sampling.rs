@@ -0,0 +1,191 @@
+//! Span sampling to bound profiler overhead in long sessions.
+//!
+//! When enabled, only 1 in `sample_rate` root spans is recorded together with
+//! its descendants, so sampled trees stay complete. Spans at or above the slow
+//! threshold, and spans that recorded an error, are always kept; since a parent
+//! lasts at least as long as its synchronous children, a slow span's ancestors
+//! are kept with it, and errors mark every span in their scope.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// Sampling configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingConfig {
+    /// Whether sampling is applied; when off every span is recorded.
+    pub enabled: bool,
+    /// Record 1 in N root spans below the slow threshold.
+    pub sample_rate: u32,
+    /// Spans at least this long are always recorded.
+    pub slow_threshold_ms: f64,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 10,
+            slow_threshold_ms: 16.0,
+        }
+    }
+}
+
+/// Counters describing what sampling did since the last clear.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingStats {
+    /// Spans dropped because they were neither sampled, slow, nor errors.
+    pub dropped_spans: u64,
+    /// Unsampled spans kept because they were slow.
+    pub kept_slow: u64,
+    /// Unsampled spans kept because they recorded an error.
+    pub kept_error: u64,
+}
+
+/// Why a closed span is being recorded or dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleDecision {
+    Keep,
+    KeepSlow,
+    KeepError,
+    Drop,
+}
+
+/// Sampling state shared by the profiler layer.
+pub struct Sampler {
+    config: RwLock<SamplingConfig>,
+    roots_seen: AtomicU64,
+    dropped_spans: AtomicU64,
+    kept_slow: AtomicU64,
+    kept_error: AtomicU64,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self {
+            config: RwLock::new(SamplingConfig::default()),
+            roots_seen: AtomicU64::new(0),
+            dropped_spans: AtomicU64::new(0),
+            kept_slow: AtomicU64::new(0),
+            kept_error: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> SamplingConfig {
+        *self.config.read().unwrap()
+    }
+
+    /// Replace the configuration; a rate of 0 is treated as 1 (record everything).
+    pub fn set_config(&self, mut config: SamplingConfig) {
+        config.sample_rate = config.sample_rate.max(1);
+        config.slow_threshold_ms = config.slow_threshold_ms.max(0.0);
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Whether a new root span (and its descendants) is sampled.
+    pub fn sample_root(&self) -> bool {
+        let config = self.config();
+        if !config.enabled || config.sample_rate <= 1 {
+            return true;
+        }
+        self.roots_seen
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(config.sample_rate as u64)
+    }
+
+    /// Decide whether a closed span is recorded, updating the counters.
+    pub fn decide(&self, sampled: bool, error: bool, duration_ms: f64) -> SampleDecision {
+        let decision = if sampled || !self.config().enabled {
+            SampleDecision::Keep
+        } else if error {
+            SampleDecision::KeepError
+        } else if duration_ms >= self.config().slow_threshold_ms {
+            SampleDecision::KeepSlow
+        } else {
+            SampleDecision::Drop
+        };
+        let counter = match decision {
+            SampleDecision::Keep => None,
+            SampleDecision::KeepSlow => Some(&self.kept_slow),
+            SampleDecision::KeepError => Some(&self.kept_error),
+            SampleDecision::Drop => Some(&self.dropped_spans),
+        };
+        if let Some(counter) = counter {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        decision
+    }
+
+    pub fn stats(&self) -> SamplingStats {
+        SamplingStats {
+            dropped_spans: self.dropped_spans.load(Ordering::Relaxed),
+            kept_slow: self.kept_slow.load(Ordering::Relaxed),
+            kept_error: self.kept_error.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        self.roots_seen.store(0, Ordering::Relaxed);
+        self.dropped_spans.store(0, Ordering::Relaxed);
+        self.kept_slow.store(0, Ordering::Relaxed);
+        self.kept_error.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiling::FluxelProfiler;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_sampling_keeps_whole_trees_slow_and_error_spans() {
+        let profiler = FluxelProfiler::new(1000);
+        profiler.set_sampling(SamplingConfig {
+            enabled: true,
+            sample_rate: 4,
+            slow_threshold_ms: 5.0,
+        });
+        let subscriber = tracing_subscriber::registry().with(profiler.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..9 {
+                let root = tracing::info_span!("root");
+                let _entered = root.enter();
+                tracing::info_span!("child").in_scope(|| {});
+            }
+            tracing::info_span!("slow").in_scope(|| {
+                std::thread::sleep(std::time::Duration::from_millis(8));
+            });
+            tracing::info_span!("failing").in_scope(|| {
+                tracing::info_span!("inner").in_scope(|| {
+                    tracing::error!("request failed");
+                });
+            });
+        });
+
+        let spans = profiler.recent_spans(100);
+        let count = |name: &str| spans.iter().filter(|s| s.name == name).count();
+        // Roots 1, 5 and 9 are sampled, each with its child
+        assert_eq!(count("root"), 3);
+        assert_eq!(count("child"), 3);
+        for child in spans.iter().filter(|s| s.name == "child") {
+            let parent = child.parent_id.as_ref().unwrap();
+            assert!(spans.iter().any(|s| &s.id == parent && s.name == "root"));
+        }
+        // Unsampled, but slow or failing (with the failing span's parent)
+        assert_eq!(count("slow"), 1);
+        assert_eq!(count("failing"), 1);
+        assert_eq!(count("inner"), 1);
+
+        let stats = profiler.sampling_stats();
+        assert_eq!(stats.dropped_spans, 12);
+        assert_eq!(stats.kept_slow, 1);
+        assert_eq!(stats.kept_error, 2);
+
+        profiler.clear();
+        assert_eq!(profiler.sampling_stats(), SamplingStats::default());
+    }
+}
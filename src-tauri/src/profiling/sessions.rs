@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
+use super::attribution::AiUsageSummary;
 use super::buffer::{SpanCategory, SpanSummary};
 
 /// Counter for generating session IDs.
@@ -42,6 +43,8 @@ pub struct SessionReport {
     pub top_spans: Vec<SpanSummary>,
     /// Total duration of all spans (may exceed session duration due to overlap).
     pub total_span_time_ms: f64,
+    /// Time and tokens spent in AI requests during the session, if any.
+    pub ai_usage: Option<AiUsageSummary>,
 }
 
 /// Category breakdown within a session.
@@ -163,6 +166,9 @@ impl SessionManager {
             })
             .collect();
 
+        let ai_usage =
+            AiUsageSummary::from_spans(session_spans.iter().copied(), end_time_ms - start_time_ms);
+
         // Get top spans by duration
         let mut top_spans: Vec<_> = session_spans.into_iter().cloned().collect();
         top_spans.sort_by(|a, b| {
@@ -183,6 +189,7 @@ impl SessionManager {
             breakdowns,
             top_spans,
             total_span_time_ms: total_span_time,
+            ai_usage,
         })
     }
 
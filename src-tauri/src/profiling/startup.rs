@@ -0,0 +1,156 @@
+//! Cold start breakdown.
+//!
+//! A dedicated "startup" session is opened as soon as the profiler is installed,
+//! before app state is built, and finalized when the frontend signals it is ready.
+//! It is kept apart from user sessions so it never shows up as the active one.
+//! The report times the setup path, state initialization, and the first workspace
+//! scan and language server start, when they happened before the app was ready.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::profiling::buffer::SpanSummary;
+use crate::profiling::sessions::{SessionManager, SessionReport};
+
+/// Startup phases and the spans that time them; the first span of each name counts.
+const PHASES: &[(&str, &str)] = &[
+    ("state_init", "state_init"),
+    ("tauri_setup", "tauri_setup"),
+    ("first_workspace_scan", "list_directory_entries"),
+    ("first_lsp_start", "start_with_config"),
+];
+
+/// Cold start report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    /// Time from profiler installation until the frontend signaled ready.
+    pub total_ms: f64,
+    /// Phases that were recorded, in the order they started.
+    pub phases: Vec<StartupPhase>,
+    /// Aggregates over every span recorded during startup.
+    pub session: SessionReport,
+}
+
+/// A timed part of startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPhase {
+    pub name: String,
+    /// The span that timed the phase, for attribution.
+    pub span_id: String,
+    /// Start time relative to the beginning of startup.
+    pub start_ms: f64,
+    pub duration_ms: f64,
+}
+
+/// Tracks the startup session until it is finalized.
+pub struct StartupTracker {
+    sessions: SessionManager,
+    session_id: String,
+    started_at: Instant,
+    report: Option<StartupReport>,
+}
+
+impl StartupTracker {
+    pub fn new() -> Self {
+        let mut sessions = SessionManager::new();
+        let session_id = sessions.start_session("startup".to_string(), 0);
+        Self {
+            sessions,
+            session_id,
+            started_at: Instant::now(),
+            report: None,
+        }
+    }
+
+    /// The report, once startup has been finalized.
+    pub fn report(&self) -> Option<&StartupReport> {
+        self.report.as_ref()
+    }
+
+    /// Finalize startup from the spans in the buffer. Later calls return the
+    /// first report.
+    pub fn finish(&mut self, spans: &[SpanSummary], reference: Instant) -> Option<&StartupReport> {
+        if self.report.is_none() {
+            let total_ms = self.started_at.elapsed().as_secs_f64() * 1000.0;
+            let session = self
+                .sessions
+                .end_session(&self.session_id, spans, reference)?;
+            let start_ms = session.session.start_time_ms;
+            let end_ms = session.session.end_time_ms.unwrap_or(f64::MAX);
+            let startup_spans: Vec<&SpanSummary> = spans
+                .iter()
+                .filter(|s| s.start_time_ms >= start_ms && s.start_time_ms <= end_ms)
+                .collect();
+            self.report = Some(StartupReport {
+                total_ms,
+                phases: phases(&startup_spans, start_ms),
+                session,
+            });
+        }
+        self.report.as_ref()
+    }
+}
+
+/// The first span of each phase, relative to `start_ms`.
+fn phases(spans: &[&SpanSummary], start_ms: f64) -> Vec<StartupPhase> {
+    let mut phases: Vec<StartupPhase> = PHASES
+        .iter()
+        .filter_map(|(phase, span_name)| {
+            let first = spans
+                .iter()
+                .filter(|s| s.name == *span_name)
+                .min_by(|a, b| a.start_time_ms.total_cmp(&b.start_time_ms))?;
+            Some(StartupPhase {
+                name: phase.to_string(),
+                span_id: first.id.clone(),
+                start_ms: first.start_time_ms - start_ms,
+                duration_ms: first.duration_ms,
+            })
+        })
+        .collect();
+    phases.sort_by(|a, b| a.start_ms.total_cmp(&b.start_ms));
+    phases
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::profiling::buffer::SpanCategory;
+    use crate::profiling::FluxelProfiler;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_startup_report_times_first_phases() {
+        let profiler = FluxelProfiler::new(100);
+        profiler.begin_startup();
+        let subscriber = tracing_subscriber::registry().with(profiler.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("state_init").in_scope(|| {});
+            tracing::info_span!("tauri_setup").in_scope(|| {});
+            tracing::info_span!("list_directory_entries").in_scope(|| {
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            });
+            tracing::info_span!("list_directory_entries").in_scope(|| {});
+        });
+        assert!(profiler.startup_report().is_none());
+
+        let report = profiler.finish_startup().unwrap();
+        let names: Vec<&str> = report.phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["state_init", "tauri_setup", "first_workspace_scan"]);
+        // The first scan is the one that counts
+        assert!(report.phases[2].duration_ms >= 2.0);
+        assert_eq!(report.session.session.name, "startup");
+        assert_eq!(report.session.session.span_count, 4);
+        assert!(report.total_ms >= report.phases[2].start_ms);
+        // Not a user session
+        assert!(profiler.active_session_id().is_none());
+
+        // Spans after ready don't change the report
+        profiler.record_frontend_span(99, "late".into(), SpanCategory::Other, 1.0, None, vec![]);
+        let again = profiler.finish_startup().unwrap();
+        assert_eq!(again.session.session.span_count, 4);
+        assert!(profiler.startup_report().is_some());
+    }
+}
@@ -9,12 +9,17 @@ use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use tracing::span::{Attributes, Id, Record};
-use tracing::{Event, Subscriber};
+use tracing::{Event, Level, Subscriber};
 use tracing_subscriber::layer::{Context, Layer};
 use tracing_subscriber::registry::LookupSpan;
 
-use crate::profiling::buffer::{CompletedSpan, RingBuffer, SpanCategory, SpanId, SpanSummary};
+use crate::profiling::buffer::{
+    CompletedSpan, RingBuffer, SpanCategory, SpanFilter, SpanId, SpanSummary,
+};
+use crate::profiling::sampling::{SampleDecision, Sampler, SamplingConfig, SamplingStats};
 use crate::profiling::sessions::{SessionManager, SessionReport};
+use crate::profiling::startup::{StartupReport, StartupTracker};
+use crate::profiling::threads::{self, ThreadInfo};
 
 /// In-flight span data stored in the registry.
 struct SpanData {
@@ -22,6 +27,19 @@ struct SpanData {
     target: String,
     start_time: Instant,
     fields: Vec<(String, String)>,
+    /// Part of a sampled tree; unsampled spans are kept only if slow or failing.
+    sampled: bool,
+    /// An error was recorded in this span or one of its descendants.
+    error: bool,
+    /// When the span was last entered, while it is entered.
+    entered_at: Option<Instant>,
+    /// Total time the span was entered.
+    busy_ns: u64,
+    /// Longest single stretch the span was entered; for async spans, the
+    /// longest poll.
+    longest_poll_ns: u64,
+    /// The thread the longest stretch ran on.
+    thread: Option<ThreadInfo>,
 }
 
 /// FluxelProfiler captures tracing spans and stores them in a bounded buffer.
@@ -46,6 +64,10 @@ struct ProfilerInner {
     id_map: RwLock<HashMap<u64, SpanId>>,
     /// In-flight span data.
     span_data: RwLock<HashMap<SpanId, SpanData>>,
+    /// Span sampling configuration and counters.
+    sampler: Sampler,
+    /// Cold start session, once begun.
+    startup: RwLock<Option<StartupTracker>>,
 }
 
 impl FluxelProfiler {
@@ -59,6 +81,8 @@ impl FluxelProfiler {
                 next_id: AtomicU64::new(1),
                 id_map: RwLock::new(HashMap::new()),
                 span_data: RwLock::new(HashMap::new()),
+                sampler: Sampler::new(),
+                startup: RwLock::new(None),
             }),
         }
     }
@@ -73,6 +97,21 @@ impl FluxelProfiler {
         self.inner.enabled.load(Ordering::Relaxed)
     }
 
+    /// Configure span sampling.
+    pub fn set_sampling(&self, config: SamplingConfig) {
+        self.inner.sampler.set_config(config);
+    }
+
+    /// Get the sampling configuration.
+    pub fn sampling(&self) -> SamplingConfig {
+        self.inner.sampler.config()
+    }
+
+    /// Get the sampling counters.
+    pub fn sampling_stats(&self) -> SamplingStats {
+        self.inner.sampler.stats()
+    }
+
     /// Get the number of spans currently stored.
     pub fn span_count(&self) -> usize {
         self.inner.buffer.read().unwrap().len()
@@ -88,6 +127,15 @@ impl FluxelProfiler {
         self.inner.buffer.read().unwrap().recent(limit)
     }
 
+    /// Get recent spans of one workspace and/or file.
+    pub fn filtered_spans(&self, limit: usize, filter: &SpanFilter) -> Vec<SpanSummary> {
+        self.inner
+            .buffer
+            .read()
+            .unwrap()
+            .recent_filtered(limit, filter)
+    }
+
     /// Get a span tree for attribution.
     pub fn get_span_tree(&self, root_id: SpanId) -> Vec<SpanSummary> {
         let buffer = self.inner.buffer.read().unwrap();
@@ -105,6 +153,7 @@ impl FluxelProfiler {
         self.inner.buffer.write().unwrap().clear();
         self.inner.id_map.write().unwrap().clear();
         self.inner.span_data.write().unwrap().clear();
+        self.inner.sampler.reset_stats();
     }
 
     // =========================================================================
@@ -121,11 +170,12 @@ impl FluxelProfiler {
             .start_session(name, span_count)
     }
 
-    /// End a profiling session and get the report.
-    pub fn end_session(&self, session_id: &str) -> Option<SessionReport> {
+    /// End a profiling session and get the report, aggregated over the spans
+    /// that pass `filter`.
+    pub fn end_session(&self, session_id: &str, filter: &SpanFilter) -> Option<SessionReport> {
         let buffer = self.inner.buffer.read().unwrap();
         let reference = buffer.reference_time().unwrap_or_else(Instant::now);
-        let spans = buffer.recent(buffer.len());
+        let spans = buffer.recent_filtered(buffer.len(), filter);
         drop(buffer);
 
         self.inner
@@ -135,6 +185,39 @@ impl FluxelProfiler {
             .end_session(session_id, &spans, reference)
     }
 
+    /// Begin the startup session. Called once the profiler is installed.
+    pub fn begin_startup(&self) {
+        *self.inner.startup.write().unwrap() = Some(StartupTracker::new());
+    }
+
+    /// Finalize the startup session when the frontend is ready, returning the
+    /// report. Later calls return the same report.
+    pub fn finish_startup(&self) -> Option<StartupReport> {
+        let buffer = self.inner.buffer.read().unwrap();
+        let reference = buffer.reference_time().unwrap_or_else(Instant::now);
+        let spans = buffer.recent(buffer.len());
+        drop(buffer);
+
+        self.inner
+            .startup
+            .write()
+            .unwrap()
+            .as_mut()?
+            .finish(&spans, reference)
+            .cloned()
+    }
+
+    /// The startup report, if startup has been finalized.
+    pub fn startup_report(&self) -> Option<StartupReport> {
+        self.inner
+            .startup
+            .read()
+            .unwrap()
+            .as_ref()?
+            .report()
+            .cloned()
+    }
+
     /// Check if a session is active.
     #[allow(dead_code)] // Public API method for future use
     pub fn is_session_active(&self, session_id: &str) -> bool {
@@ -205,6 +288,36 @@ impl FluxelProfiler {
         self.inner.buffer.write().unwrap().push(completed);
     }
 
+    /// Record a backend operation that was timed outside of tracing, such as a
+    /// language server request that completes on another task.
+    pub fn record_measured_span(
+        &self,
+        name: String,
+        target: &str,
+        category: SpanCategory,
+        duration: std::time::Duration,
+        fields: Vec<(String, String)>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let now = Instant::now();
+        let completed = CompletedSpan {
+            id: self.next_span_id(),
+            parent_id: None,
+            name,
+            target: target.to_string(),
+            category,
+            start_time: now - duration,
+            end_time: now,
+            duration_ns: duration.as_nanos() as u64,
+            fields,
+        };
+
+        self.inner.buffer.write().unwrap().push(completed);
+    }
+
     /// Get the buffer's reference time for relative timestamps.
     #[allow(dead_code)] // Public API method for future use
     pub fn reference_time(&self) -> Option<Instant> {
@@ -239,6 +352,17 @@ impl FluxelProfiler {
         new_id
     }
 
+    /// Mark in-flight spans as having recorded an error, so sampling keeps them.
+    fn mark_error(&self, ids: impl Iterator<Item = Id>) {
+        let ids: Vec<SpanId> = ids.filter_map(|id| self.get_id(&id)).collect();
+        let mut span_data = self.inner.span_data.write().unwrap();
+        for id in ids {
+            if let Some(data) = span_data.get_mut(&id) {
+                data.error = true;
+            }
+        }
+    }
+
     /// Get our ID for a tracing span ID.
     fn get_id(&self, tracing_id: &Id) -> Option<SpanId> {
         self.inner
@@ -292,7 +416,7 @@ impl<S> Layer<S> for FluxelProfiler
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
-    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
         if !self.is_enabled() {
             return;
         }
@@ -303,18 +427,45 @@ where
         let mut visitor = FieldVisitor::new();
         attrs.record(&mut visitor);
 
+        // Children follow their parent's sampling decision so trees stay whole
+        let parent_sampled = ctx
+            .span(id)
+            .and_then(|span| span.parent())
+            .and_then(|parent| self.get_id(&parent.id()))
+            .and_then(|parent| {
+                self.inner
+                    .span_data
+                    .read()
+                    .unwrap()
+                    .get(&parent)
+                    .map(|data| data.sampled)
+            });
+        let sampled = parent_sampled.unwrap_or_else(|| self.inner.sampler.sample_root());
+        let error = visitor.fields.iter().any(|(key, _)| key == "error");
+
         // Store span data
         let data = SpanData {
             name: attrs.metadata().name().to_string(),
             target: attrs.metadata().target().to_string(),
             start_time: Instant::now(),
             fields: visitor.fields,
+            sampled,
+            error,
+            entered_at: None,
+            busy_ns: 0,
+            longest_poll_ns: 0,
+            thread: None,
         };
 
         self.inner.span_data.write().unwrap().insert(our_id, data);
+        if error {
+            if let Some(span) = ctx.span(id) {
+                self.mark_error(span.scope().skip(1).map(|s| s.id()));
+            }
+        }
     }
 
-    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
         if !self.is_enabled() {
             return;
         }
@@ -322,24 +473,66 @@ where
         if let Some(our_id) = self.get_id(id) {
             let mut visitor = FieldVisitor::new();
             values.record(&mut visitor);
+            if visitor.fields.iter().any(|(key, _)| key == "error") {
+                if let Some(span) = ctx.span(id) {
+                    self.mark_error(span.scope().map(|s| s.id()));
+                }
+            }
 
             if let Some(data) = self.inner.span_data.write().unwrap().get_mut(&our_id) {
-                data.fields.extend(visitor.fields);
+                // Recording a field again replaces its value
+                for (key, value) in visitor.fields {
+                    match data.fields.iter_mut().find(|(k, _)| *k == key) {
+                        Some(field) => field.1 = value,
+                        None => data.fields.push((key, value)),
+                    }
+                }
             }
         }
     }
 
-    fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, S>) {
-        // Events are not captured in this implementation.
-        // Could be extended to capture events within spans.
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Events are not captured in this implementation; error events only mark
+        // the spans they happen in, so sampling keeps them
+        if !self.is_enabled() || *event.metadata().level() != Level::ERROR {
+            return;
+        }
+        if let Some(scope) = ctx.event_scope(event) {
+            self.mark_error(scope.map(|span| span.id()));
+        }
     }
 
-    fn on_enter(&self, _id: &Id, _ctx: Context<'_, S>) {
-        // Enter timing is captured on new_span
+    fn on_enter(&self, id: &Id, _ctx: Context<'_, S>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if let Some(our_id) = self.get_id(id) {
+            if let Some(data) = self.inner.span_data.write().unwrap().get_mut(&our_id) {
+                data.entered_at = Some(Instant::now());
+            }
+        }
     }
 
-    fn on_exit(&self, _id: &Id, _ctx: Context<'_, S>) {
-        // Exit is handled on close
+    fn on_exit(&self, id: &Id, _ctx: Context<'_, S>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        // Each stretch between enter and exit ran uninterrupted on this thread;
+        // for async spans it is one poll
+        if let Some(our_id) = self.get_id(id) {
+            if let Some(data) = self.inner.span_data.write().unwrap().get_mut(&our_id) {
+                if let Some(entered_at) = data.entered_at.take() {
+                    let stretch = entered_at.elapsed().as_nanos() as u64;
+                    data.busy_ns += stretch;
+                    if data.thread.is_none() || stretch > data.longest_poll_ns {
+                        data.longest_poll_ns = stretch;
+                        data.thread = Some(threads::current());
+                    }
+                }
+            }
+        }
     }
 
     fn on_close(&self, id: Id, ctx: Context<'_, S>) {
@@ -385,9 +578,34 @@ where
         let end_time = Instant::now();
         let duration_ns = end_time.duration_since(data.start_time).as_nanos() as u64;
 
+        let decision =
+            self.inner
+                .sampler
+                .decide(data.sampled, data.error, duration_ns as f64 / 1_000_000.0);
+        if decision == SampleDecision::Drop {
+            self.inner.id_map.write().unwrap().remove(&id.into_u64());
+            return;
+        }
+
         // Infer category
         let category = SpanCategory::from_span(&data.name, &data.target, &data.fields);
 
+        let mut fields = data.fields;
+        if let Some(thread) = data.thread {
+            fields.extend([
+                ("thread".to_string(), thread.name),
+                ("thread_kind".to_string(), thread.kind.as_str().to_string()),
+                (
+                    "busy_ms".to_string(),
+                    format!("{:.3}", data.busy_ns as f64 / 1_000_000.0),
+                ),
+                (
+                    "longest_poll_ms".to_string(),
+                    format!("{:.3}", data.longest_poll_ns as f64 / 1_000_000.0),
+                ),
+            ]);
+        }
+
         // Create completed span
         let completed = CompletedSpan {
             id: our_id,
@@ -398,7 +616,7 @@ where
             start_time: data.start_time,
             end_time,
             duration_ns,
-            fields: data.fields,
+            fields,
         };
 
         // Push to buffer
@@ -0,0 +1,131 @@
+//! Thread attribution for spans.
+//!
+//! Tokio's async workers and blocking pool threads are the same pool threads, so
+//! they can't be told apart by name or spawn order. Profiling builds hand Tauri a
+//! runtime of their own that numbers its threads and marks a thread as a worker
+//! when it parks, which only workers do. Together with the main thread, which
+//! runs the event loop, handles IPC and executes synchronous commands, this tells
+//! spans that ran where waiting is expected (the blocking pool) from spans that
+//! stalled a thread other commands need.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, ThreadId};
+
+const THREAD_PREFIX: &str = "tokio-";
+
+/// The thread that installed the profiler, i.e. the main thread.
+static IPC_THREAD: OnceLock<ThreadId> = OnceLock::new();
+/// Kept alive for the lifetime of the app; Tauri only holds a handle.
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+thread_local! {
+    /// Set once the thread has parked as an async worker.
+    static WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What a thread is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadKind {
+    /// The main thread, which handles IPC and runs synchronous commands.
+    Ipc,
+    /// A tokio worker polling async commands and tasks.
+    AsyncWorker,
+    /// A tokio blocking pool thread (`spawn_blocking`). A worker that has not
+    /// parked yet, right after startup, also counts as one.
+    Blocking,
+    Other,
+}
+
+impl ThreadKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ipc => "ipc",
+            Self::AsyncWorker => "async_worker",
+            Self::Blocking => "blocking",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// The thread a span ran on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadInfo {
+    pub kind: ThreadKind,
+    /// Thread name, e.g. `tokio-3`, or `thread-<id>` for unnamed threads.
+    pub name: String,
+}
+
+/// Describe the calling thread.
+pub fn current() -> ThreadInfo {
+    let thread = thread::current();
+    let kind = match thread.name() {
+        _ if IPC_THREAD.get() == Some(&thread.id()) => ThreadKind::Ipc,
+        _ if WORKER.get() => ThreadKind::AsyncWorker,
+        Some(name) if name.starts_with(THREAD_PREFIX) => ThreadKind::Blocking,
+        _ => ThreadKind::Other,
+    };
+    let name = match thread.name() {
+        Some(name) => name.to_string(),
+        None => {
+            let id = format!("{:?}", thread.id());
+            format!(
+                "thread-{}",
+                id.trim_start_matches("ThreadId(").trim_end_matches(')')
+            )
+        }
+    };
+    ThreadInfo { kind, name }
+}
+
+/// Record the calling thread as the IPC thread and run Tauri's async runtime on
+/// threads whose role can be told. Must be called before Tauri starts its runtime.
+pub fn install_runtime() {
+    let _ = IPC_THREAD.set(thread::current().id());
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let runtime = build_runtime(workers).expect("Failed to build async runtime");
+    tauri::async_runtime::set(runtime.handle().clone());
+    let _ = RUNTIME.set(runtime);
+}
+
+/// A multi-threaded runtime like Tauri's default one, with numbered threads that
+/// mark themselves as workers.
+fn build_runtime(workers: usize) -> std::io::Result<tokio::runtime::Runtime> {
+    let spawned = AtomicUsize::new(0);
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(workers)
+        .enable_all()
+        .thread_name_fn(move || {
+            format!(
+                "{}{}",
+                THREAD_PREFIX,
+                spawned.fetch_add(1, Ordering::Relaxed)
+            )
+        })
+        // Only workers park; the blocking pool waits on its own condition variable
+        .on_thread_park(|| WORKER.set(true))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_threads_are_told_apart() {
+        let runtime = build_runtime(2).unwrap();
+        let (worker, blocking) = runtime.block_on(async {
+            // Let the idle workers park
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            let worker = tokio::spawn(async { current().kind }).await.unwrap();
+            let blocking = tokio::task::spawn_blocking(|| current().kind)
+                .await
+                .unwrap();
+            (worker, blocking)
+        });
+        assert_eq!(worker, ThreadKind::AsyncWorker);
+        assert_eq!(blocking, ThreadKind::Blocking);
+        assert_eq!(current().kind, ThreadKind::Other);
+    }
+}
@@ -0,0 +1,704 @@
+//! AI Context Assembly
+//!
+//! Picks the code most relevant to a cursor position for an AI prompt, so the
+//! webview does not have to read and rank files itself. Candidates come from
+//! the code around the cursor, definitions of symbols used near it (found with
+//! the search index, or in the file's imports when no index is enabled), the
+//! focus file's resolved imports, and files with uncommitted changes. Blocks
+//! are added in that order until the token budget runs out.
+
+use crate::services::folding;
+use crate::services::import_rewrite::{normalize, RESOLVE_EXTENSIONS, SOURCE_EXTENSIONS};
+use crate::services::search_index::{is_binary_file, SearchIndexState};
+use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
+use fluxel_node_resolver::{
+    collect_import_specifiers_native, resolve_module_native, ResolveOptions, ResolveRequest,
+};
+use git2::{DiffOptions, Repository};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+use tauri::State;
+
+const DEFAULT_BUDGET_TOKENS: usize = 4000;
+/// Lines either side of the cursor when no small enclosing block is found
+const CURSOR_WINDOW: usize = 40;
+/// Enclosing blocks up to this size are used whole as the focus block
+const MAX_ENCLOSING_LINES: usize = 120;
+/// Identifiers near the cursor whose definitions are looked up
+const MAX_SYMBOLS: usize = 8;
+/// Files checked for each identifier's definition
+const MAX_SYMBOL_CANDIDATES: usize = 50;
+const MAX_DEFINITION_LINES: usize = 40;
+const MAX_IMPORTS: usize = 12;
+/// Lines of an import's outline
+const MAX_OUTLINE_LINES: usize = 40;
+const MAX_CHANGED_FILES: usize = 10;
+const MAX_DIFF_CHARS: usize = 4000;
+/// Files larger than this are not read for context
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+/// Smallest remainder worth filling with a truncated block
+const MIN_BLOCK_TOKENS: usize = 48;
+
+/// Skipped when looking up identifiers; shorter words never match
+const KEYWORDS: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "from",
+    "function",
+    "impl",
+    "import",
+    "interface",
+    "match",
+    "null",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "self",
+    "static",
+    "string",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "true",
+    "type",
+    "typeof",
+    "undefined",
+    "using",
+    "void",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContextBlockKind {
+    /// Code around the cursor
+    Focus,
+    /// Definition of an identifier used near the cursor
+    Symbol,
+    /// Declarations of a module the focus file imports
+    Import,
+    /// Uncommitted changes to another file
+    Change,
+}
+
+/// Cursor position; 1-based like the editor's
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextCursor {
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBlock {
+    pub kind: ContextBlockKind,
+    /// Path relative to the workspace root
+    pub path: String,
+    /// First line of `content` (1-based); `None` for diffs and outlines
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+    /// Symbol a `symbol` block defines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub content: String,
+    /// Estimated tokens of `content`
+    pub tokens: usize,
+    /// Cut short to fit the budget
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiContext {
+    pub blocks: Vec<ContextBlock>,
+    pub total_tokens: usize,
+    pub budget_tokens: usize,
+    /// Candidate blocks left out for lack of budget
+    pub omitted: usize,
+    /// The blocks formatted as a prompt section
+    pub rendered: String,
+}
+
+/// Rough token count; close enough to budget prompts
+fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]{3,}\b").unwrap())
+}
+
+fn outline_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^\s*(?:export\b|(?:(?:public|internal|pub(?:\([^)]*\))?|abstract|sealed|static|partial|async)\s+)*(?:class|interface|type|enum|struct|trait|fn|function|def|record)\b)",
+        )
+        .unwrap()
+    })
+}
+
+/// Matches a line declaring `name`
+fn definition_regex(name: &str) -> Regex {
+    Regex::new(&format!(
+        r"^\s*(?:(?:export|default|declare|public|private|protected|internal|static|sealed|partial|abstract|readonly|async|pub(?:\([^)]*\))?)\s+)*(?:class|interface|type|enum|struct|trait|fn|function|def|const|let|var|record)\s+{}\b",
+        regex::escape(name)
+    ))
+    .unwrap()
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn read_source(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() || meta.len() > MAX_FILE_SIZE || is_binary_file(path) {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// Lines `start..=end` (1-based) of `lines`
+fn line_range(lines: &[&str], start: usize, end: usize) -> String {
+    lines[start - 1..end.min(lines.len())].join("\n")
+}
+
+/// Folding ranges stop before a block's closing line; take it along
+fn with_closing_line(lines: &[&str], end: usize) -> usize {
+    match lines.get(end) {
+        Some(next) if next.trim_start().starts_with(['}', ')', ']']) => end + 1,
+        _ => end,
+    }
+}
+
+// ============================================================================
+// Candidates
+// ============================================================================
+
+/// The outermost enclosing code block of the cursor that is small enough,
+/// usually the surrounding function, otherwise a window around the cursor
+fn focus_block(root: &Path, path: &Path, content: &str, cursor: ContextCursor) -> ContextBlock {
+    let lines: Vec<&str> = content.lines().collect();
+    let line = cursor.line.clamp(1, lines.len().max(1));
+    let enclosing = folding::compute(&path.to_string_lossy(), content)
+        .into_iter()
+        .filter(|range| range.kind.is_none())
+        .filter(|range| range.start_line <= line && line <= range.end_line)
+        .filter(|range| range.end_line - range.start_line < MAX_ENCLOSING_LINES)
+        .max_by_key(|range| range.end_line - range.start_line);
+    let (start, end) = match enclosing {
+        Some(range) => (range.start_line, with_closing_line(&lines, range.end_line)),
+        None => (
+            line.saturating_sub(CURSOR_WINDOW).max(1),
+            (line + CURSOR_WINDOW).min(lines.len().max(1)),
+        ),
+    };
+    let content = if lines.is_empty() {
+        String::new()
+    } else {
+        line_range(&lines, start, end)
+    };
+    ContextBlock {
+        kind: ContextBlockKind::Focus,
+        path: relative_path(root, path),
+        start_line: Some(start),
+        end_line: Some(end.min(lines.len())),
+        symbol: None,
+        tokens: estimate_tokens(&content),
+        content,
+        truncated: false,
+    }
+}
+
+/// Local modules the focus file imports, for JavaScript and TypeScript files
+fn resolved_imports(root: &Path, path: &Path) -> Vec<PathBuf> {
+    let is_source = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+    if !is_source {
+        return Vec::new();
+    }
+    let Ok(specifiers) = collect_import_specifiers_native(&Utf8PathBuf::from(
+        path.to_string_lossy().replace('\\', "/"),
+    )) else {
+        return Vec::new();
+    };
+    let options = ResolveOptions {
+        extensions: RESOLVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    specifiers
+        .into_iter()
+        .filter_map(|specifier| {
+            resolve_module_native(
+                ResolveRequest {
+                    specifier: specifier.specifier,
+                    importer: path.to_string_lossy().to_string(),
+                    project_root: Some(root.to_string_lossy().to_string()),
+                },
+                Some(options.clone()),
+            )
+            .ok()?
+            .resolved_path
+        })
+        .map(|resolved| normalize(Path::new(&resolved)))
+        .filter(|resolved| {
+            resolved.starts_with(root)
+                && !resolved
+                    .components()
+                    .any(|c| c.as_os_str() == "node_modules")
+        })
+        .filter(|resolved| seen.insert(resolved.clone()))
+        .take(MAX_IMPORTS)
+        .collect()
+}
+
+/// Declaration lines of an imported module, or its head when none are found
+fn import_block(root: &Path, path: &Path) -> Option<ContextBlock> {
+    let content = read_source(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let outline: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| outline_regex().is_match(line))
+        .take(MAX_OUTLINE_LINES)
+        .collect();
+    let (text, start_line, end_line) = if outline.is_empty() {
+        let end = lines.len().min(MAX_OUTLINE_LINES);
+        (lines[..end].join("\n"), Some(1), Some(end))
+    } else {
+        (outline.join("\n"), None, None)
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some(ContextBlock {
+        kind: ContextBlockKind::Import,
+        path: relative_path(root, path),
+        start_line,
+        end_line,
+        symbol: None,
+        tokens: estimate_tokens(&text),
+        content: text,
+        truncated: false,
+    })
+}
+
+/// Identifiers used near the cursor but not declared in the focus file,
+/// closest to the cursor first
+fn nearby_identifiers(content: &str, cursor: ContextCursor) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let line = cursor.line.clamp(1, lines.len().max(1));
+    let start = line.saturating_sub(CURSOR_WINDOW / 2).max(1);
+    let end = (line + CURSOR_WINDOW / 2).min(lines.len());
+
+    let mut distance: HashMap<&str, usize> = HashMap::new();
+    for (index, text) in lines.iter().enumerate().take(end).skip(start - 1) {
+        for found in identifier_regex().find_iter(text) {
+            let name = found.as_str();
+            if KEYWORDS.contains(&name) {
+                continue;
+            }
+            let d = (index + 1).abs_diff(line);
+            distance
+                .entry(name)
+                .and_modify(|best| *best = (*best).min(d))
+                .or_insert(d);
+        }
+    }
+
+    let mut names: Vec<(&str, usize)> = distance
+        .into_iter()
+        .filter(|(name, _)| {
+            let pattern = definition_regex(name);
+            !content.lines().any(|line| pattern.is_match(line))
+        })
+        .collect();
+    names.sort_by_key(|&(name, distance)| (distance, name));
+    names
+        .into_iter()
+        .take(MAX_SYMBOLS)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// The declaration of `name` in `path`, through the end of its block
+fn definition_block(root: &Path, path: &Path, name: &str) -> Option<ContextBlock> {
+    let content = read_source(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let pattern = definition_regex(name);
+    let start = lines.iter().position(|line| pattern.is_match(line))? + 1;
+    let end = folding::compute(&path.to_string_lossy(), &content)
+        .into_iter()
+        .find(|range| range.start_line == start && range.kind.is_none())
+        .map(|range| with_closing_line(&lines, range.end_line))
+        .unwrap_or(start)
+        .min(start + MAX_DEFINITION_LINES - 1);
+    let text = line_range(&lines, start, end);
+    Some(ContextBlock {
+        kind: ContextBlockKind::Symbol,
+        path: relative_path(root, path),
+        start_line: Some(start),
+        end_line: Some(end.min(lines.len())),
+        symbol: Some(name.to_string()),
+        tokens: estimate_tokens(&text),
+        content: text,
+        truncated: false,
+    })
+}
+
+/// Diffs of changed files other than the focus file, most recently modified first
+fn change_blocks(root: &Path, focus: &Path) -> Vec<ContextBlock> {
+    let Ok(repo) = Repository::discover(root) else {
+        return Vec::new();
+    };
+    let Some(workdir) = repo.workdir().map(Path::to_path_buf) else {
+        return Vec::new();
+    };
+    let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).show_untracked_content(true);
+    let Ok(diff) = repo.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut options)) else {
+        return Vec::new();
+    };
+
+    let mut changes: Vec<(SystemTime, ContextBlock)> = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let Ok(Some(mut patch)) = git2::Patch::from_diff(&diff, index) else {
+            continue;
+        };
+        let Some(relative) = patch.delta().new_file().path().map(Path::to_path_buf) else {
+            continue;
+        };
+        let absolute = workdir.join(&relative);
+        if absolute == focus || !absolute.starts_with(root) || is_binary_file(&absolute) {
+            continue;
+        }
+        let Ok(buf) = patch.to_buf() else {
+            continue;
+        };
+        let mut text = String::from_utf8_lossy(&buf).into_owned();
+        if text.len() > MAX_DIFF_CHARS {
+            let cut = (0..=MAX_DIFF_CHARS)
+                .rev()
+                .find(|&i| text.is_char_boundary(i))
+                .unwrap_or(0);
+            text.truncate(cut);
+        }
+        let modified = std::fs::metadata(&absolute)
+            .and_then(|meta| meta.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        changes.push((
+            modified,
+            ContextBlock {
+                kind: ContextBlockKind::Change,
+                path: relative_path(root, &absolute),
+                start_line: None,
+                end_line: None,
+                symbol: None,
+                tokens: estimate_tokens(&text),
+                content: text,
+                truncated: false,
+            },
+        ));
+    }
+    changes.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    changes
+        .into_iter()
+        .take(MAX_CHANGED_FILES)
+        .map(|(_, block)| block)
+        .collect()
+}
+
+// ============================================================================
+// Budget
+// ============================================================================
+
+/// Keep blocks in order while they fit, cutting the first one that does not
+/// to whole lines when enough budget is left. Returns the kept blocks and how
+/// many were left out.
+fn fit_to_budget(candidates: Vec<ContextBlock>, budget: usize) -> (Vec<ContextBlock>, usize) {
+    let mut remaining = budget;
+    let mut blocks = Vec::new();
+    let mut omitted = 0;
+    for mut block in candidates {
+        if block.tokens <= remaining {
+            remaining -= block.tokens;
+            blocks.push(block);
+            continue;
+        }
+        if remaining < MIN_BLOCK_TOKENS {
+            omitted += 1;
+            continue;
+        }
+        let mut kept = String::new();
+        let mut line_count = 0;
+        for line in block.content.lines() {
+            if estimate_tokens(&kept) + estimate_tokens(line) + 1 > remaining {
+                break;
+            }
+            if line_count > 0 {
+                kept.push('\n');
+            }
+            kept.push_str(line);
+            line_count += 1;
+        }
+        if line_count == 0 {
+            omitted += 1;
+            continue;
+        }
+        if let (Some(start), Some(_)) = (block.start_line, block.end_line) {
+            block.end_line = Some(start + line_count - 1);
+        }
+        block.tokens = estimate_tokens(&kept);
+        block.content = kept;
+        block.truncated = true;
+        remaining = remaining.saturating_sub(block.tokens);
+        blocks.push(block);
+    }
+    (blocks, omitted)
+}
+
+fn render(blocks: &[ContextBlock]) -> String {
+    blocks
+        .iter()
+        .map(|block| {
+            let location = match (block.start_line, block.end_line) {
+                (Some(start), Some(end)) => format!("{} (lines {}-{})", block.path, start, end),
+                _ => block.path.clone(),
+            };
+            let title = match (block.kind, &block.symbol) {
+                (ContextBlockKind::Focus, _) => format!("Current file: {}", location),
+                (ContextBlockKind::Symbol, Some(symbol)) => {
+                    format!("Definition of `{}`: {}", symbol, location)
+                }
+                (ContextBlockKind::Symbol, None) => format!("Definition: {}", location),
+                (ContextBlockKind::Import, _) => format!("Imported module: {}", location),
+                (ContextBlockKind::Change, _) => format!("Uncommitted changes: {}", location),
+            };
+            let fence = if block.kind == ContextBlockKind::Change {
+                "```diff"
+            } else {
+                "```"
+            };
+            format!("{}\n{}\n{}\n```", title, fence, block.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn assemble(
+    root: &Path,
+    focus: &Path,
+    content: &str,
+    cursor: ContextCursor,
+    budget: usize,
+    imports: &[PathBuf],
+    search_index: &SearchIndexState,
+) -> AiContext {
+    let mut candidates = vec![focus_block(root, focus, content, cursor)];
+
+    let mut defined = BTreeSet::new();
+    for name in nearby_identifiers(content, cursor) {
+        let files = search_index
+            .candidates(root, &name)
+            .unwrap_or_else(|| imports.to_vec());
+        let block = files
+            .iter()
+            .filter(|path| path.as_path() != focus)
+            .take(MAX_SYMBOL_CANDIDATES)
+            .find_map(|path| definition_block(root, path, &name));
+        if let Some(block) = block {
+            defined.insert(block.path.clone());
+            candidates.push(block);
+        }
+    }
+
+    // Modules whose definitions are already in a symbol block add little more
+    candidates.extend(
+        imports
+            .iter()
+            .filter_map(|path| import_block(root, path))
+            .filter(|block| !defined.contains(&block.path)),
+    );
+    candidates.extend(change_blocks(root, focus));
+
+    let (blocks, omitted) = fit_to_budget(candidates, budget);
+    AiContext {
+        total_tokens: blocks.iter().map(|block| block.tokens).sum(),
+        budget_tokens: budget,
+        omitted,
+        rendered: render(&blocks),
+        blocks,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Gather the code most relevant to a cursor position for an AI prompt
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the focus file belongs to
+/// * `focus_file` - File being edited
+/// * `cursor` - 1-based cursor position
+/// * `budget_tokens` - Upper bound on the returned context (default 4000)
+/// * `content` - Unsaved editor content; the file is read from disk when omitted
+#[fluxel_command]
+pub async fn assemble_ai_context(
+    workspace_root: String,
+    focus_file: String,
+    cursor: ContextCursor,
+    budget_tokens: Option<usize>,
+    content: Option<String>,
+    search_index: State<'_, SearchIndexState>,
+) -> Result<AiContext, String> {
+    let root = normalize(Path::new(&workspace_root));
+    let focus = normalize(Path::new(&focus_file));
+    let budget = budget_tokens.unwrap_or(DEFAULT_BUDGET_TOKENS);
+    let search_index = search_index.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let content = match content {
+            Some(content) => content,
+            None => std::fs::read_to_string(&focus)
+                .map_err(|e| format!("Failed to read {}: {}", focus.display(), e))?,
+        };
+        let imports = resolved_imports(&root, &focus);
+        let context = assemble(
+            &root,
+            &focus,
+            &content,
+            cursor,
+            budget,
+            &imports,
+            &search_index,
+        );
+        println!(
+            "[AiContext] {} blocks, {} tokens ({} omitted) for {}",
+            context.blocks.len(),
+            context.total_tokens,
+            context.omitted,
+            focus.display()
+        );
+        Ok(context)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn assembles_focus_symbols_and_imports_within_budget() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-ai-context-{}", nanos));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/math.ts"),
+            "export interface Vector {\n  x: number;\n  y: number;\n}\n\n\
+             export function addVectors(a: Vector, b: Vector): Vector {\n  \
+             return { x: a.x + b.x, y: a.y + b.y };\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/util.ts"), "export const setup = 1;\n").unwrap();
+        let main = "import { addVectors } from './math';\nimport './util';\n\n\
+                    function total(items) {\n  let sum = { x: 0, y: 0 };\n  \
+                    for (const item of items) {\n    sum = addVectors(sum, item);\n  }\n  \
+                    return sum;\n}\n";
+        fs::write(root.join("src/main.ts"), main).unwrap();
+        let root = normalize(&root);
+        let focus = root.join("src/main.ts");
+        let cursor = ContextCursor { line: 7 };
+
+        assert!(nearby_identifiers(main, cursor).contains(&"addVectors".to_string()));
+        assert!(!nearby_identifiers(main, cursor).contains(&"total".to_string()));
+
+        let imports = [root.join("src/math.ts"), root.join("src/util.ts")];
+        let context = assemble(
+            &root,
+            &focus,
+            main,
+            cursor,
+            1000,
+            &imports,
+            &SearchIndexState::new(),
+        );
+        let kinds: Vec<(ContextBlockKind, &str)> = context
+            .blocks
+            .iter()
+            .map(|block| (block.kind, block.path.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ContextBlockKind::Focus, "src/main.ts"),
+                (ContextBlockKind::Symbol, "src/math.ts"),
+                (ContextBlockKind::Import, "src/util.ts"),
+            ]
+        );
+        assert_eq!(context.blocks[0].start_line, Some(4));
+        assert_eq!(context.blocks[0].end_line, Some(10));
+        assert_eq!(context.blocks[1].symbol.as_deref(), Some("addVectors"));
+        assert!(context.blocks[1].content.ends_with("}"));
+        assert!(context
+            .rendered
+            .contains("Definition of `addVectors`: src/math.ts"));
+
+        let long = ContextBlock {
+            kind: ContextBlockKind::Change,
+            path: "a".to_string(),
+            start_line: None,
+            end_line: None,
+            symbol: None,
+            content: "line of text here\n".repeat(100),
+            tokens: estimate_tokens(&"line of text here\n".repeat(100)),
+            truncated: false,
+        };
+        let (blocks, omitted) = fit_to_budget(vec![long.clone(), long], 100);
+        assert_eq!(omitted, 1);
+        assert!(blocks[0].truncated);
+        assert!(blocks[0].tokens <= 100);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,696 @@
+//! AI Edits
+//!
+//! Validates and applies file edits proposed by an AI model, given either as
+//! search/replace blocks or as unified diffs. Edits are planned against the
+//! current file contents first; a preview returns the resulting diff per file,
+//! and applying writes every file or none: new contents are staged next to
+//! their targets and swapped in, and anything already swapped is restored if
+//! a later file fails.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::import_rewrite::normalize;
+use crate::services::workspace_store::sha256_hex;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// A proposed change to one or more files
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AiFileEdit {
+    /// Replace each block's `search` text, which must occur exactly once. An
+    /// empty `search` on a missing file creates it with `replace`.
+    SearchReplace {
+        path: String,
+        blocks: Vec<SearchReplaceBlock>,
+    },
+    /// A unified diff for one or more files, paths relative to the workspace
+    UnifiedDiff { diff: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchReplaceBlock {
+    pub search: String,
+    pub replace: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiEditStatus {
+    Modified,
+    Created,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiEditFilePreview {
+    /// Path relative to the workspace root
+    pub path: String,
+    pub status: AiEditStatus,
+    /// Unified diff from the current contents to the edited ones
+    pub diff: String,
+    pub additions: usize,
+    pub deletions: usize,
+    /// SHA-256 of the current contents; pass back to `apply_ai_edits` to
+    /// refuse files that changed after the preview
+    pub original_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiEditError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiEditPreview {
+    /// Whether every edit applies to the current contents
+    pub applicable: bool,
+    pub files: Vec<AiEditFilePreview>,
+    pub errors: Vec<AiEditError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiEditApplyResult {
+    /// Whether every file was written; nothing is written otherwise
+    pub success: bool,
+    pub files: Vec<AiEditFilePreview>,
+    pub errors: Vec<AiEditError>,
+}
+
+/// Contents of one file before and after the planned edits; `None` when the
+/// file does not exist
+struct PlannedFile {
+    original: Option<String>,
+    updated: Option<String>,
+}
+
+type Plan = BTreeMap<PathBuf, PlannedFile>;
+
+fn content_hash(text: &str) -> String {
+    sha256_hex(text.as_bytes())
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Absolute path of `path` inside `root`; edits may not leave the workspace
+fn resolve_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Edit has no path".to_string());
+    }
+    let resolved = normalize(&root.join(path));
+    if !resolved.starts_with(root) {
+        return Err(format!("{} is outside the workspace", path));
+    }
+    Ok(resolved)
+}
+
+/// The planned state of `path`, reading it on first use
+fn planned<'a>(plan: &'a mut Plan, path: &Path) -> Result<&'a mut PlannedFile, String> {
+    if !plan.contains_key(path) {
+        let original = match fs::read(path) {
+            Ok(bytes) => {
+                Some(String::from_utf8(bytes).map_err(|_| "File is not valid UTF-8".to_string())?)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Failed to read file: {}", e)),
+        };
+        plan.insert(
+            path.to_path_buf(),
+            PlannedFile {
+                updated: original.clone(),
+                original,
+            },
+        );
+    }
+    Ok(plan.get_mut(path).unwrap())
+}
+
+/// Match the file's line endings so LF text from the model finds CRLF files
+fn with_line_endings(text: &str, crlf: bool) -> String {
+    if crlf && !text.contains("\r\n") {
+        text.replace('\n', "\r\n")
+    } else {
+        text.to_string()
+    }
+}
+
+// ============================================================================
+// Search/Replace
+// ============================================================================
+
+fn apply_search_replace(content: &str, blocks: &[SearchReplaceBlock]) -> Result<String, String> {
+    let crlf = content.contains("\r\n");
+    let mut content = content.to_string();
+    for (index, block) in blocks.iter().enumerate() {
+        let search = with_line_endings(&block.search, crlf);
+        let replace = with_line_endings(&block.replace, crlf);
+        if search.is_empty() {
+            return Err(format!("Block {} has an empty search", index + 1));
+        }
+        let matches: Vec<usize> = content.match_indices(&search).map(|(i, _)| i).collect();
+        match matches.as_slice() {
+            [start] => content.replace_range(*start..*start + search.len(), &replace),
+            [] => {
+                return Err(format!(
+                    "Block {} no longer matches the file; its search text was not found",
+                    index + 1
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "Block {} matches {} places; its search text needs more context",
+                    index + 1,
+                    matches.len()
+                ))
+            }
+        }
+    }
+    Ok(content)
+}
+
+// ============================================================================
+// Unified Diffs
+// ============================================================================
+
+#[derive(Debug, Default)]
+struct Hunk {
+    old_start: usize,
+    /// Lines with their `' '`, `'-'`, or `'+'` marker
+    lines: Vec<(char, String)>,
+}
+
+#[derive(Debug, Default)]
+struct FilePatch {
+    /// `None` for `/dev/null`
+    old_path: Option<String>,
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+fn diff_path(header: &str) -> Option<String> {
+    let path = header.split('\t').next().unwrap_or("").trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Old start line from a `@@ -12,5 +12,6 @@` header
+fn hunk_start(header: &str) -> Option<usize> {
+    let old = header.strip_prefix("@@ -")?.split([' ', ',']).next()?;
+    old.parse().ok()
+}
+
+fn parse_unified_diff(diff: &str) -> Result<Vec<FilePatch>, String> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut lines = diff.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(old) = line.strip_prefix("--- ") {
+            let new = lines
+                .next()
+                .and_then(|next| next.strip_prefix("+++ "))
+                .ok_or("Diff has a `---` line without a `+++` line")?;
+            patches.push(FilePatch {
+                old_path: diff_path(old),
+                new_path: diff_path(new),
+                hunks: Vec::new(),
+            });
+        } else if line.starts_with("@@") {
+            let patch = patches
+                .last_mut()
+                .ok_or("Diff has a hunk before any file header")?;
+            let mut hunk = Hunk {
+                old_start: hunk_start(line).unwrap_or(1),
+                lines: Vec::new(),
+            };
+            while let Some(&next) = lines.peek() {
+                // A removed line can start with `--- ` too; a file header is
+                // followed by `+++ `
+                let starts_file = next.starts_with("--- ") && {
+                    let mut ahead = lines.clone();
+                    ahead.next();
+                    ahead.next().is_some_and(|after| after.starts_with("+++ "))
+                };
+                if next.starts_with("@@") || starts_file {
+                    break;
+                }
+                let next = lines.next().unwrap_or_default();
+                match next.chars().next() {
+                    Some(marker @ (' ' | '-' | '+')) => {
+                        hunk.lines.push((marker, next[1..].to_string()))
+                    }
+                    // Models often drop the space before empty context lines
+                    None => hunk.lines.push((' ', String::new())),
+                    // `\ No newline at end of file` and stray text
+                    _ => {}
+                }
+            }
+            patch.hunks.push(hunk);
+        }
+    }
+    if patches.is_empty() {
+        return Err("Diff has no file headers".to_string());
+    }
+    Ok(patches)
+}
+
+/// Line index where `needle` occurs in `haystack`, nearest to `expected`
+fn find_lines(haystack: &[&str], needle: &[&str], expected: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(expected.min(haystack.len()));
+    }
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    let matches_at = |start: usize| {
+        haystack[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(a, b)| a.trim_end_matches('\r') == b.trim_end_matches('\r'))
+    };
+    let last = haystack.len() - needle.len();
+    let expected = expected.min(last);
+    (0..=last)
+        .flat_map(|distance| {
+            let before = expected.checked_sub(distance);
+            let after =
+                (distance > 0 && expected + distance <= last).then_some(expected + distance);
+            before.into_iter().chain(after)
+        })
+        .find(|&start| matches_at(start))
+}
+
+fn apply_hunks(content: &str, hunks: &[Hunk]) -> Result<String, String> {
+    let crlf = content.contains("\r\n");
+    let trailing_newline = content.is_empty() || content.ends_with('\n');
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect();
+
+    let mut offset: isize = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter(|(marker, _)| *marker != '+')
+            .map(|(_, text)| text.as_str())
+            .collect();
+        let new: Vec<String> = hunk
+            .lines
+            .iter()
+            .filter(|(marker, _)| *marker != '-')
+            .map(|(_, text)| text.clone())
+            .collect();
+        let expected = (hunk.old_start.saturating_sub(1) as isize + offset).max(0) as usize;
+        let current: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let start = find_lines(&current, &old, expected).ok_or_else(|| {
+            format!(
+                "Hunk {} no longer applies; its lines were not found near line {}",
+                index + 1,
+                hunk.old_start
+            )
+        })?;
+        offset += new.len() as isize - old.len() as isize;
+        lines.splice(start..start + old.len(), new);
+    }
+
+    let separator = if crlf { "\r\n" } else { "\n" };
+    let mut result = lines.join(separator);
+    if trailing_newline && !result.is_empty() {
+        result.push_str(separator);
+    }
+    Ok(result)
+}
+
+fn plan_diff(root: &Path, diff: &str, plan: &mut Plan) -> Result<(), AiEditError> {
+    let error = |path: &str, message: String| AiEditError {
+        path: path.to_string(),
+        message,
+    };
+    let patches = parse_unified_diff(diff).map_err(|e| error("", e))?;
+    for patch in patches {
+        let name = patch
+            .new_path
+            .clone()
+            .or_else(|| patch.old_path.clone())
+            .unwrap_or_default();
+        let path = resolve_path(root, &name).map_err(|e| error(&name, e))?;
+        let file = planned(plan, &path).map_err(|e| error(&name, e))?;
+        match (&patch.old_path, &patch.new_path, &file.updated) {
+            (None, Some(_), Some(_)) => {
+                return Err(error(&name, "File already exists".to_string()));
+            }
+            (Some(_), _, None) => {
+                return Err(error(&name, "File does not exist".to_string()));
+            }
+            _ => {}
+        }
+        let current = file.updated.clone().unwrap_or_default();
+        let updated = apply_hunks(&current, &patch.hunks).map_err(|e| error(&name, e))?;
+        file.updated = patch.new_path.is_some().then_some(updated);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Planning and Preview
+// ============================================================================
+
+/// Apply every edit in memory. Returns the plan and the edits that failed.
+fn plan_edits(root: &Path, edits: &[AiFileEdit]) -> (Plan, Vec<AiEditError>) {
+    let mut plan = Plan::new();
+    let mut errors = Vec::new();
+    for edit in edits {
+        match edit {
+            AiFileEdit::SearchReplace { path, blocks } => {
+                let result = resolve_path(root, path).and_then(|resolved| {
+                    let file = planned(&mut plan, &resolved)?;
+                    file.updated = Some(match &file.updated {
+                        Some(content) => apply_search_replace(content, blocks)?,
+                        None if blocks.iter().all(|b| b.search.is_empty()) => {
+                            blocks.iter().map(|b| b.replace.as_str()).collect()
+                        }
+                        None => return Err("File does not exist".to_string()),
+                    });
+                    Ok(())
+                });
+                if let Err(message) = result {
+                    errors.push(AiEditError {
+                        path: path.clone(),
+                        message,
+                    });
+                }
+            }
+            AiFileEdit::UnifiedDiff { diff } => {
+                if let Err(error) = plan_diff(root, diff, &mut plan) {
+                    errors.push(error);
+                }
+            }
+        }
+    }
+    plan.retain(|_, file| file.original != file.updated);
+    (plan, errors)
+}
+
+/// Unified diff between two versions of a file, with line counts
+fn render_diff(path: &str, original: &str, updated: &str) -> (String, usize, usize) {
+    let old_path = format!("a/{}", path);
+    let new_path = format!("b/{}", path);
+    let patch = git2::Patch::from_buffers(
+        original.as_bytes(),
+        Some(Path::new(&old_path)),
+        updated.as_bytes(),
+        Some(Path::new(&new_path)),
+        None,
+    );
+    let Ok(mut patch) = patch else {
+        return (String::new(), 0, 0);
+    };
+    let (_, additions, deletions) = patch.line_stats().unwrap_or((0, 0, 0));
+    let text = patch
+        .to_buf()
+        .map(|buf| String::from_utf8_lossy(&buf).into_owned())
+        .unwrap_or_default();
+    (text, additions, deletions)
+}
+
+fn previews(root: &Path, plan: &Plan) -> Vec<AiEditFilePreview> {
+    plan.iter()
+        .map(|(path, file)| {
+            let path = relative(root, path);
+            let original = file.original.as_deref().unwrap_or("");
+            let updated = file.updated.as_deref().unwrap_or("");
+            let (diff, additions, deletions) = render_diff(&path, original, updated);
+            AiEditFilePreview {
+                status: match (&file.original, &file.updated) {
+                    (None, _) => AiEditStatus::Created,
+                    (_, None) => AiEditStatus::Deleted,
+                    _ => AiEditStatus::Modified,
+                },
+                path,
+                diff,
+                additions,
+                deletions,
+                original_hash: file.original.as_deref().map(content_hash),
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Atomic Write
+// ============================================================================
+
+fn staging_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.fluxel-ai-{}", name, std::process::id()))
+}
+
+/// Write every planned file or none of them
+fn write_plan(plan: &Plan) -> Result<(), String> {
+    // Stage new contents beside their targets so the swap is a rename
+    let mut staged: Vec<(&PathBuf, PathBuf)> = Vec::new();
+    let cleanup = |staged: &[(&PathBuf, PathBuf)]| {
+        for (_, staging) in staged {
+            let _ = fs::remove_file(staging);
+        }
+    };
+    for (path, file) in plan {
+        let Some(updated) = &file.updated else {
+            continue;
+        };
+        let staging = staging_path(path);
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&staging, updated));
+        if let Err(e) = result {
+            cleanup(&staged);
+            return Err(format!("Failed to stage {}: {}", path.display(), e));
+        }
+        staged.push((path, staging));
+    }
+
+    // Swap in order; on failure restore the originals of what was swapped
+    let mut done: Vec<&PathBuf> = Vec::new();
+    let mut staged_iter = staged.iter();
+    for (path, file) in plan {
+        let result = match &file.updated {
+            Some(_) => {
+                let (_, staging) = staged_iter.next().expect("staged file");
+                fs::rename(staging, path)
+            }
+            None => fs::remove_file(path),
+        };
+        if let Err(e) = result {
+            for path in done.into_iter().rev() {
+                let restored = match &plan[path].original {
+                    Some(original) => fs::write(path, original),
+                    None => fs::remove_file(path),
+                };
+                if let Err(e) = restored {
+                    println!("[AiEdits] Failed to restore {}: {}", path.display(), e);
+                }
+            }
+            cleanup(&staged);
+            return Err(format!("Failed to write {}: {}", path.display(), e));
+        }
+        done.push(path);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check AI-proposed edits against the current files and return the diffs
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the edited paths are relative to
+/// * `edits` - Search/replace blocks and unified diffs
+#[fluxel_command]
+pub async fn preview_ai_edits(
+    workspace_root: String,
+    edits: Vec<AiFileEdit>,
+) -> Result<AiEditPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = normalize(Path::new(&workspace_root));
+        let (plan, errors) = plan_edits(&root, &edits);
+        Ok(AiEditPreview {
+            applicable: errors.is_empty(),
+            files: previews(&root, &plan),
+            errors,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Apply AI-proposed edits to disk, all or nothing
+///
+/// Edits are re-validated against the current contents. Nothing is written
+/// if any edit no longer applies, a file changed since the preview, or a file
+/// has unsaved editor changes (unless `force` is set).
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the edited paths are relative to
+/// * `edits` - Search/replace blocks and unified diffs
+/// * `expected_hashes` - `originalHash` per relative path from the preview
+/// * `force` - Write even if files have unsaved editor changes
+#[fluxel_command]
+pub async fn apply_ai_edits(
+    workspace_root: String,
+    edits: Vec<AiFileEdit>,
+    expected_hashes: Option<HashMap<String, String>>,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<AiEditApplyResult, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = normalize(Path::new(&workspace_root));
+        let (plan, mut errors) = plan_edits(&root, &edits);
+
+        for (path, file) in &plan {
+            let relative = relative(&root, path);
+            let expected = expected_hashes.as_ref().and_then(|h| h.get(&relative));
+            if let Some(expected) = expected {
+                if file.original.as_deref().map(content_hash).as_ref() != Some(expected) {
+                    errors.push(AiEditError {
+                        path: relative,
+                        message: "File changed since the preview".to_string(),
+                    });
+                }
+            }
+        }
+
+        let files = previews(&root, &plan);
+        if !errors.is_empty() {
+            return Ok(AiEditApplyResult {
+                success: false,
+                files,
+                errors,
+            });
+        }
+
+        let affected: Vec<PathBuf> = plan.keys().cloned().collect();
+        dirty_files.guard("apply_ai_edits", &affected, force.unwrap_or(false))?;
+        write_plan(&plan)?;
+        println!("[AiEdits] Applied edits to {} file(s)", files.len());
+        Ok(AiEditApplyResult {
+            success: true,
+            files,
+            errors,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn plans_and_writes_edits_all_or_nothing() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = normalize(&std::env::temp_dir().join(format!("fluxel-ai-edits-{}", nanos)));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "fn one() -> u32 {\r\n    1\r\n}\r\n\r\nfn two() -> u32 {\r\n    2\r\n}\r\n",
+        )
+        .unwrap();
+        fs::write(root.join("notes.txt"), "a\nb\nc\nd\n").unwrap();
+
+        let edits = vec![
+            AiFileEdit::SearchReplace {
+                path: "src/lib.rs".to_string(),
+                blocks: vec![SearchReplaceBlock {
+                    search: "fn two() -> u32 {\n    2\n}".to_string(),
+                    replace: "fn two() -> u32 {\n    one() + 1\n}".to_string(),
+                }],
+            },
+            AiFileEdit::UnifiedDiff {
+                diff: "--- a/notes.txt\n+++ b/notes.txt\n@@ -3,2 +3,2 @@\n c\n-d\n+D\n\
+                       --- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1 @@\n+pub fn new() {}\n"
+                    .to_string(),
+            },
+        ];
+        let (plan, errors) = plan_edits(&root, &edits);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let files = previews(&root, &plan);
+        let summary: Vec<(&str, AiEditStatus, usize, usize)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.status, f.additions, f.deletions))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("notes.txt", AiEditStatus::Modified, 1, 1),
+                ("src/lib.rs", AiEditStatus::Modified, 1, 1),
+                ("src/new.rs", AiEditStatus::Created, 1, 0),
+            ]
+        );
+
+        write_plan(&plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn one() -> u32 {\r\n    1\r\n}\r\n\r\nfn two() -> u32 {\r\n    one() + 1\r\n}\r\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("notes.txt")).unwrap(),
+            "a\nb\nc\nD\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/new.rs")).unwrap(),
+            "pub fn new() {}\n"
+        );
+
+        // Applying again no longer matches, and an ambiguous block is refused
+        let (_, errors) = plan_edits(&root, &edits);
+        assert_eq!(errors.len(), 2);
+        let ambiguous = [SearchReplaceBlock {
+            search: "u32".to_string(),
+            replace: "u64".to_string(),
+        }];
+        assert!(apply_search_replace("u32 u32", &ambiguous)
+            .unwrap_err()
+            .contains("matches 2 places"));
+        let escape = AiFileEdit::SearchReplace {
+            path: "../outside.txt".to_string(),
+            blocks: ambiguous.to_vec(),
+        };
+        assert!(plan_edits(&root, &[escape]).1[0]
+            .message
+            .contains("outside the workspace"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+//! AI Image Inputs
+//!
+//! Prepares workspace images for vision-capable AI providers. Images already
+//! within a provider's limits are sent as-is; larger ones are downscaled and
+//! re-encoded (PNG when they have transparency, JPEG otherwise) until they fit.
+
+use base64::Engine;
+use fluxel_macros::fluxel_command;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use serde::Serialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::services::import_rewrite::normalize;
+
+/// Files larger than this are never decoded
+const MAX_SOURCE_BYTES: u64 = 64 * 1024 * 1024;
+const JPEG_QUALITY: u8 = 85;
+/// Each retry shrinks the image by this factor when the encoding is too large
+const SHRINK_FACTOR: f64 = 0.75;
+const MAX_SHRINK_ATTEMPTS: usize = 6;
+
+/// Size limits of a provider's image inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Longest edge in pixels
+    pub max_edge: u32,
+    /// Encoded size before base64
+    pub max_bytes: usize,
+}
+
+/// Limits for `provider`; unknown providers get conservative defaults
+pub fn limits_for(provider: &str) -> ImageLimits {
+    match provider {
+        // Local vision models tile or resize internally; smaller inputs keep requests fast
+        "ollama" => ImageLimits {
+            max_edge: 1344,
+            max_bytes: 4 * 1024 * 1024,
+        },
+        "minimax" => ImageLimits {
+            max_edge: 2048,
+            max_bytes: 5 * 1024 * 1024,
+        },
+        _ => ImageLimits {
+            max_edge: 1568,
+            max_bytes: 4 * 1024 * 1024,
+        },
+    }
+}
+
+/// An image ready to be sent as a base64 content block
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiImage {
+    pub path: String,
+    pub mime_type: String,
+    /// Base64 of the encoded image, without a data URL prefix
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+    /// Encoded size before base64
+    pub bytes: usize,
+    /// Whether the image was downscaled or re-encoded
+    pub converted: bool,
+}
+
+fn resolve_image_path(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let root = normalize(root);
+    let resolved = normalize(&root.join(path.trim()));
+    if !resolved.starts_with(&root) {
+        return Err(format!("{} is outside the workspace", path));
+    }
+    Ok(resolved)
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, String> {
+    let mut buffer = Cursor::new(Vec::new());
+    match format {
+        ImageFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, JPEG_QUALITY))
+        }
+        _ => image.write_to(&mut buffer, format),
+    }
+    .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer.into_inner())
+}
+
+fn mime_type(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Jpeg => "image/jpeg",
+        _ => "image/png",
+    }
+}
+
+/// Read `path` and fit it to `limits`
+pub fn prepare_image(root: &Path, path: &str, limits: ImageLimits) -> Result<AiImage, String> {
+    let resolved = resolve_image_path(root, path)?;
+    if resolved
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+    {
+        return Err("SVG images are not supported; export a PNG instead".to_string());
+    }
+    let size = fs::metadata(&resolved)
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .len();
+    if size > MAX_SOURCE_BYTES {
+        return Err(format!("Image is too large ({} bytes)", size));
+    }
+
+    let bytes = fs::read(&resolved).map_err(|e| format!("Failed to read image: {}", e))?;
+    let reader = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    let format = reader
+        .format()
+        .ok_or_else(|| "Unrecognized image format".to_string())?;
+    let image = reader
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (original_width, original_height) = (image.width(), image.height());
+    let image_result =
+        |data: Vec<u8>, format: ImageFormat, image: &DynamicImage, converted| AiImage {
+            path: path.to_string(),
+            mime_type: mime_type(format).to_string(),
+            bytes: data.len(),
+            data: base64::engine::general_purpose::STANDARD.encode(&data),
+            width: image.width(),
+            height: image.height(),
+            original_width,
+            original_height,
+            converted,
+        };
+
+    // PNG and JPEG within the limits are accepted by every vision provider as-is
+    let fits_edge = original_width.max(original_height) <= limits.max_edge;
+    if fits_edge
+        && bytes.len() <= limits.max_bytes
+        && matches!(format, ImageFormat::Png | ImageFormat::Jpeg)
+    {
+        return Ok(image_result(bytes, format, &image, false));
+    }
+
+    let target = if image.color().has_alpha() {
+        ImageFormat::Png
+    } else {
+        ImageFormat::Jpeg
+    };
+    let mut scaled = if fits_edge {
+        image
+    } else {
+        image.resize(limits.max_edge, limits.max_edge, FilterType::Lanczos3)
+    };
+    for attempt in 0..=MAX_SHRINK_ATTEMPTS {
+        // Large transparent images fall back to JPEG rather than shrinking forever
+        let format = if attempt >= 2 {
+            ImageFormat::Jpeg
+        } else {
+            target
+        };
+        let data = encode(&scaled, format)?;
+        if data.len() <= limits.max_bytes {
+            return Ok(image_result(data, format, &scaled, true));
+        }
+        let edge = (scaled.width().max(scaled.height()) as f64 * SHRINK_FACTOR) as u32;
+        scaled = scaled.resize(edge.max(1), edge.max(1), FilterType::Triangle);
+    }
+    Err("Image could not be reduced below the provider's size limit".to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Read a workspace image and fit it to a provider's vision input limits
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the image must live in
+/// * `path` - Image path, relative to the workspace root or absolute
+/// * `provider` - Provider the image is for (`ollama`, `minimax`); picks the limits
+#[fluxel_command]
+pub async fn prepare_ai_image(
+    workspace_root: String,
+    path: String,
+    provider: Option<String>,
+) -> Result<AiImage, String> {
+    let limits = limits_for(provider.as_deref().unwrap_or_default());
+    tauri::async_runtime::spawn_blocking(move || {
+        prepare_image(Path::new(&workspace_root), &path, limits)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    #[test]
+    fn fits_images_to_provider_limits() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-ai-images-{}", nanos));
+        fs::create_dir_all(&root).unwrap();
+
+        // Small PNGs are passed through untouched
+        RgbaImage::from_pixel(10, 10, Rgba([255, 0, 0, 128]))
+            .save(root.join("icon.png"))
+            .unwrap();
+        let icon = prepare_image(&root, "icon.png", limits_for("ollama")).unwrap();
+        assert!(!icon.converted);
+        assert_eq!((icon.mime_type.as_str(), icon.width), ("image/png", 10));
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD
+                .decode(&icon.data)
+                .unwrap(),
+            fs::read(root.join("icon.png")).unwrap()
+        );
+
+        // Wide opaque screenshots are downscaled to JPEG
+        RgbImage::from_fn(3000, 1000, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, 0])
+        })
+        .save(root.join("screenshot.png"))
+        .unwrap();
+        let shot = prepare_image(&root, "screenshot.png", limits_for("ollama")).unwrap();
+        assert!(shot.converted);
+        assert_eq!(shot.mime_type, "image/jpeg");
+        assert_eq!((shot.width, shot.height), (1344, 448));
+        assert_eq!((shot.original_width, shot.original_height), (3000, 1000));
+
+        // Tight byte limits shrink further
+        let tiny = ImageLimits {
+            max_edge: 2048,
+            max_bytes: 20 * 1024,
+        };
+        let small = prepare_image(&root, "screenshot.png", tiny).unwrap();
+        assert!(small.bytes <= tiny.max_bytes);
+        assert!(small.width < 3000);
+
+        assert!(prepare_image(&root, "../outside.png", limits_for("ollama")).is_err());
+        fs::write(root.join("diagram.svg"), "<svg/>").unwrap();
+        assert!(prepare_image(&root, "diagram.svg", limits_for("ollama")).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,361 @@
+//! AI Request Metrics
+//!
+//! Time to first token, throughput, and total duration of recent AI requests,
+//! grouped by provider and model so providers can be compared. Requests made
+//! by the backend are recorded directly; streams run by the frontend report a
+//! sample once they finish. Samples live in memory only.
+//!
+//! With the `profiling` feature, backend requests also emit `ai_request` spans
+//! so attribution reports and session exports show the model's share of a
+//! workflow's latency and token cost.
+
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// Samples kept for aggregation
+const MAX_SAMPLES: usize = 500;
+/// Samples returned individually, newest first
+const RECENT_SAMPLES: usize = 50;
+
+/// One finished AI request as reported by its caller
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiRequestSample {
+    pub provider: String,
+    pub model: String,
+    /// What the request was for, e.g. "chat" or "inlineCompletion"
+    pub feature: String,
+    /// Unset when nothing was received, e.g. for failed requests
+    pub time_to_first_token_ms: Option<f64>,
+    pub duration_ms: f64,
+    pub output_tokens: u64,
+    pub success: bool,
+}
+
+impl AiRequestSample {
+    /// A non-streamed request, where the first token arrives with the response
+    pub fn completed(
+        provider: &str,
+        model: &str,
+        feature: &str,
+        duration: Duration,
+        output: Option<&str>,
+    ) -> Self {
+        let duration_ms = millis(duration);
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            feature: feature.to_string(),
+            time_to_first_token_ms: output.map(|_| duration_ms),
+            duration_ms,
+            output_tokens: output.map(estimate_tokens).unwrap_or(0),
+            success: output.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiMetricSample {
+    /// Unix milliseconds when the request finished
+    pub timestamp: u64,
+    pub provider: String,
+    pub model: String,
+    pub feature: String,
+    pub time_to_first_token_ms: Option<f64>,
+    pub duration_ms: f64,
+    pub output_tokens: u64,
+    /// Output tokens per second after the first token
+    pub tokens_per_second: Option<f64>,
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiProviderMetrics {
+    pub provider: String,
+    pub model: String,
+    pub count: u64,
+    pub errors: u64,
+    pub average_ttft_ms: Option<f64>,
+    pub p50_ttft_ms: Option<f64>,
+    pub p95_ttft_ms: Option<f64>,
+    pub average_duration_ms: f64,
+    pub average_tokens_per_second: Option<f64>,
+    pub output_tokens: u64,
+    pub last_used: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiMetrics {
+    /// Per provider/model aggregates, most used first
+    pub providers: Vec<AiProviderMetrics>,
+    pub recent: Vec<AiMetricSample>,
+}
+
+/// Recent AI request samples, shared by the backend AI features
+#[derive(Clone, Default)]
+pub struct AiMetricsState {
+    samples: Arc<Mutex<VecDeque<AiMetricSample>>>,
+}
+
+impl AiMetricsState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, sample: AiRequestSample) {
+        let generation_ms = sample
+            .time_to_first_token_ms
+            .map(|ttft| sample.duration_ms - ttft);
+        let tokens_per_second = match generation_ms {
+            // Non-streamed responses arrive at once; rate them over the whole request
+            Some(ms) if ms <= 0.0 && sample.duration_ms > 0.0 => {
+                Some(sample.output_tokens as f64 * 1000.0 / sample.duration_ms)
+            }
+            Some(ms) if ms > 0.0 => Some(sample.output_tokens as f64 * 1000.0 / ms),
+            _ => None,
+        }
+        .filter(|_| sample.success && sample.output_tokens > 0);
+
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(AiMetricSample {
+            timestamp: now_millis(),
+            provider: sample.provider,
+            model: sample.model,
+            feature: sample.feature,
+            time_to_first_token_ms: sample.time_to_first_token_ms,
+            duration_ms: sample.duration_ms,
+            output_tokens: sample.output_tokens,
+            tokens_per_second,
+            success: sample.success,
+        });
+    }
+
+    fn snapshot(&self) -> AiMetrics {
+        let samples = self.samples.lock().unwrap();
+        let mut groups: HashMap<(&str, &str), Vec<&AiMetricSample>> = HashMap::new();
+        for sample in samples.iter() {
+            groups
+                .entry((&sample.provider, &sample.model))
+                .or_default()
+                .push(sample);
+        }
+
+        let mut providers: Vec<AiProviderMetrics> = groups
+            .into_iter()
+            .map(|((provider, model), group)| aggregate(provider, model, &group))
+            .collect();
+        providers.sort_by(|a, b| b.count.cmp(&a.count).then(b.last_used.cmp(&a.last_used)));
+
+        AiMetrics {
+            providers,
+            recent: samples.iter().rev().take(RECENT_SAMPLES).cloned().collect(),
+        }
+    }
+}
+
+/// Profiler span covering one AI provider request, from `start` until `finish`.
+/// A no-op unless the `profiling` feature is enabled.
+pub struct AiRequestSpan {
+    #[cfg(feature = "profiling")]
+    span: tracing::Span,
+    #[cfg(feature = "profiling")]
+    started: std::time::Instant,
+    #[cfg(feature = "profiling")]
+    first_token_seen: bool,
+}
+
+impl AiRequestSpan {
+    /// `input_tokens` is an estimate until the provider reports usage
+    pub fn start(provider: &str, model: &str, input_tokens: u64) -> Self {
+        #[cfg(not(feature = "profiling"))]
+        let _ = (provider, model, input_tokens);
+
+        Self {
+            #[cfg(feature = "profiling")]
+            span: tracing::info_span!(
+                "ai_request",
+                category = "ai",
+                provider = %provider,
+                model = %model,
+                input_tokens,
+                output_tokens = tracing::field::Empty,
+                ttft_ms = tracing::field::Empty,
+                success = tracing::field::Empty,
+            ),
+            #[cfg(feature = "profiling")]
+            started: std::time::Instant::now(),
+            #[cfg(feature = "profiling")]
+            first_token_seen: false,
+        }
+    }
+
+    /// Note the first streamed output; later calls are ignored
+    pub fn first_token(&mut self) {
+        #[cfg(feature = "profiling")]
+        if !self.first_token_seen {
+            self.first_token_seen = true;
+            self.span
+                .record("ttft_ms", self.started.elapsed().as_millis() as u64);
+        }
+    }
+
+    /// Replace the estimated input tokens with the provider's count
+    pub fn record_input_tokens(&self, tokens: u64) {
+        #[cfg(feature = "profiling")]
+        self.span.record("input_tokens", tokens);
+        #[cfg(not(feature = "profiling"))]
+        let _ = tokens;
+    }
+
+    /// Close the span; `output_tokens` is `None` for failed requests
+    pub fn finish(self, output_tokens: Option<u64>) {
+        #[cfg(feature = "profiling")]
+        {
+            self.span.record("success", output_tokens.is_some());
+            if let Some(tokens) = output_tokens {
+                self.span.record("output_tokens", tokens);
+            }
+        }
+        #[cfg(not(feature = "profiling"))]
+        let _ = output_tokens;
+    }
+}
+
+fn aggregate(provider: &str, model: &str, group: &[&AiMetricSample]) -> AiProviderMetrics {
+    let mut ttfts: Vec<f64> = group
+        .iter()
+        .filter_map(|s| s.time_to_first_token_ms)
+        .collect();
+    ttfts.sort_by(f64::total_cmp);
+    let percentile = |p: f64| {
+        let index = ((ttfts.len() as f64 - 1.0) * p).round() as usize;
+        ttfts.get(index).copied()
+    };
+    let rates: Vec<f64> = group.iter().filter_map(|s| s.tokens_per_second).collect();
+
+    AiProviderMetrics {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        count: group.len() as u64,
+        errors: group.iter().filter(|s| !s.success).count() as u64,
+        average_ttft_ms: average(&ttfts),
+        p50_ttft_ms: percentile(0.5),
+        p95_ttft_ms: percentile(0.95),
+        average_duration_ms: group.iter().map(|s| s.duration_ms).sum::<f64>()
+            / group.len().max(1) as f64,
+        average_tokens_per_second: average(&rates),
+        output_tokens: group.iter().map(|s| s.output_tokens).sum(),
+        last_used: group.iter().map(|s| s.timestamp).max().unwrap_or(0),
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Rough token count for providers that don't report usage
+pub fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(4) as u64
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Record a request the frontend ran itself, such as a streamed chat turn
+///
+/// # Arguments
+/// * `sample` - Timing and token counts of the finished request
+#[fluxel_command]
+pub fn record_ai_metrics(sample: AiRequestSample, state: State<'_, AiMetricsState>) {
+    state.record(sample);
+}
+
+/// Per provider/model latency and throughput, plus the most recent requests
+#[fluxel_command]
+pub fn ai_get_metrics(state: State<'_, AiMetricsState>) -> AiMetrics {
+    state.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_samples_by_provider_and_model() {
+        let state = AiMetricsState::new();
+        let stream = |model: &str, ttft: f64, duration: f64, tokens: u64| AiRequestSample {
+            provider: "ollama".to_string(),
+            model: model.to_string(),
+            feature: "chat".to_string(),
+            time_to_first_token_ms: Some(ttft),
+            duration_ms: duration,
+            output_tokens: tokens,
+            success: true,
+        };
+        state.record(stream("qwen", 100.0, 1100.0, 50));
+        state.record(stream("qwen", 300.0, 2300.0, 50));
+        state.record(AiRequestSample::completed(
+            "minimax",
+            "MiniMax-M2.7",
+            "commitMessage",
+            Duration::from_millis(500),
+            Some("feat: add metrics"),
+        ));
+        state.record(AiRequestSample::completed(
+            "minimax",
+            "MiniMax-M2.7",
+            "commitMessage",
+            Duration::from_millis(3000),
+            None,
+        ));
+
+        let metrics = state.snapshot();
+        assert_eq!(metrics.recent.len(), 4);
+        assert!(!metrics.recent[0].success);
+        assert_eq!(metrics.providers.len(), 2);
+
+        let qwen = metrics
+            .providers
+            .iter()
+            .find(|p| p.model == "qwen")
+            .unwrap();
+        assert_eq!((qwen.count, qwen.errors, qwen.output_tokens), (2, 0, 100));
+        assert_eq!(qwen.average_ttft_ms, Some(200.0));
+        assert_eq!(qwen.p50_ttft_ms, Some(300.0));
+        assert_eq!(qwen.average_duration_ms, 1700.0);
+        // 50 tokens/s over the first 1000ms, 25 tokens/s over the next 2000ms
+        assert_eq!(qwen.average_tokens_per_second, Some(37.5));
+
+        let minimax = metrics
+            .providers
+            .iter()
+            .find(|p| p.provider == "minimax")
+            .unwrap();
+        assert_eq!((minimax.count, minimax.errors), (2, 1));
+        assert_eq!(minimax.average_ttft_ms, Some(500.0));
+        // 5 estimated tokens delivered in 500ms
+        assert_eq!(minimax.average_tokens_per_second, Some(10.0));
+    }
+}
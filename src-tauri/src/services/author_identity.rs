@@ -0,0 +1,549 @@
+//! Author Identity Service
+//!
+//! Maps commit author emails to display names and avatars for blame annotations
+//! and the history panel. A whole batch of authors is resolved per call so the
+//! webview never makes one request per commit.
+//!
+//! Resolution order: GitHub noreply addresses (no network), the cache in
+//! `~/.fluxel/authors.json`, the code host behind `origin` (the commit's author
+//! on GitHub, user search on GitLab), then a Gravatar URL. Host answers are
+//! cached for a week; Gravatar fallbacks for a day, so they are upgraded once a
+//! token is configured. Lookups that fail on the network are not cached.
+
+use crate::services::code_host::{self, HostClient, HostKind};
+use crate::services::workspace_store::sha256_hex;
+use fluxel_macros::fluxel_command;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const CACHE_VERSION: u32 = 1;
+/// Lifetime of identities the code host confirmed
+const HOST_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Lifetime of Gravatar fallbacks
+const FALLBACK_TTL_SECS: u64 = 24 * 60 * 60;
+/// Cached identities kept; the ones expiring soonest are dropped first
+const MAX_CACHED_AUTHORS: usize = 5000;
+/// Code host lookups in flight at once
+const LOOKUP_CONCURRENCY: usize = 4;
+/// Avatar size requested from Gravatar, in pixels
+const AVATAR_SIZE: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentitySource {
+    GitHub,
+    GitLab,
+    Gravatar,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorIdentity {
+    /// Lowercased email the identity was resolved for
+    pub email: String,
+    /// Display name (from the host profile, else the commit's author name)
+    pub name: Option<String>,
+    /// Code host username, if known
+    pub login: Option<String>,
+    pub avatar_url: String,
+    pub profile_url: Option<String>,
+    pub source: IdentitySource,
+}
+
+/// One author to resolve
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorQuery {
+    pub email: String,
+    /// Author name recorded in the commit
+    pub name: Option<String>,
+    /// A commit by this author; lets GitHub resolve emails that aren't public
+    pub commit: Option<String>,
+}
+
+// ============================================================================
+// Local Resolution
+// ============================================================================
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Gravatar URL for `email` (Gravatar accepts SHA-256 hashes of the normalized address)
+pub fn gravatar_url(email: &str) -> String {
+    let hash = sha256_hex(normalize_email(email).as_bytes());
+    format!(
+        "https://www.gravatar.com/avatar/{}?d=identicon&s={}",
+        hash, AVATAR_SIZE
+    )
+}
+
+/// Identity encoded in a GitHub noreply address: `123+login@users.noreply.github.com`
+/// or the older `login@users.noreply.github.com`
+fn github_noreply_identity(email: &str) -> Option<AuthorIdentity> {
+    let local = email.strip_suffix("@users.noreply.github.com")?;
+    let (id, login) = match local.split_once('+') {
+        Some((id, login)) if id.chars().all(|c| c.is_ascii_digit()) => (Some(id), login),
+        _ => (None, local),
+    };
+    if login.is_empty() {
+        return None;
+    }
+    let avatar_url = match id {
+        Some(id) => format!("https://avatars.githubusercontent.com/u/{}?v=4", id),
+        None => format!("https://github.com/{}.png", login),
+    };
+    Some(AuthorIdentity {
+        email: email.to_string(),
+        name: None,
+        login: Some(login.to_string()),
+        avatar_url,
+        profile_url: Some(format!("https://github.com/{}", login)),
+        source: IdentitySource::GitHub,
+    })
+}
+
+fn gravatar_identity(query: &AuthorQuery) -> AuthorIdentity {
+    AuthorIdentity {
+        email: normalize_email(&query.email),
+        name: query.name.clone(),
+        login: None,
+        avatar_url: gravatar_url(&query.email),
+        profile_url: None,
+        source: IdentitySource::Gravatar,
+    }
+}
+
+/// Percent-encode a query parameter value
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// ============================================================================
+// Code Host Lookups
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct GhAccount {
+    login: String,
+    avatar_url: String,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCommit {
+    author: Option<GhAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhUserSearch {
+    items: Vec<GhAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlUser {
+    username: String,
+    name: String,
+    avatar_url: Option<String>,
+    web_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlAvatar {
+    avatar_url: String,
+}
+
+/// Ask the code host who `query` is. `Ok(None)` means the host doesn't know.
+async fn lookup_on_host(
+    client: &HostClient,
+    query: &AuthorQuery,
+) -> Result<Option<AuthorIdentity>, String> {
+    let email = normalize_email(&query.email);
+    match client.remote.kind {
+        HostKind::GitHub => {
+            // The commit API links the author even when their email is private
+            let account = match &query.commit {
+                Some(commit) => {
+                    let commit: GhCommit = client.get(&format!("/commits/{}", commit)).await?;
+                    commit.author
+                }
+                // User search is heavily rate limited without a token
+                None if client.has_token() => {
+                    let search: GhUserSearch = client
+                        .get_api(&format!(
+                            "/search/users?q={}+in:email&per_page=1",
+                            encode_query_value(&email)
+                        ))
+                        .await?;
+                    search.items.into_iter().next()
+                }
+                None => None,
+            };
+            Ok(account.map(|account| AuthorIdentity {
+                email,
+                name: query.name.clone(),
+                login: Some(account.login),
+                avatar_url: account.avatar_url,
+                profile_url: Some(account.html_url),
+                source: IdentitySource::GitHub,
+            }))
+        }
+        HostKind::GitLab => {
+            if client.has_token() {
+                let users: Vec<GlUser> = client
+                    .get_api(&format!(
+                        "/users?search={}&per_page=1",
+                        encode_query_value(&email)
+                    ))
+                    .await?;
+                if let Some(user) = users.into_iter().next() {
+                    return Ok(Some(AuthorIdentity {
+                        avatar_url: user.avatar_url.unwrap_or_else(|| gravatar_url(&email)),
+                        email,
+                        name: Some(user.name),
+                        login: Some(user.username),
+                        profile_url: Some(user.web_url),
+                        source: IdentitySource::GitLab,
+                    }));
+                }
+            }
+            // Public endpoint; returns the GitLab avatar or the instance's Gravatar
+            let avatar: GlAvatar = client
+                .get_api(&format!(
+                    "/avatar?email={}&size={}",
+                    encode_query_value(&email),
+                    AVATAR_SIZE
+                ))
+                .await?;
+            Ok(Some(AuthorIdentity {
+                email,
+                name: query.name.clone(),
+                login: None,
+                avatar_url: avatar.avatar_url,
+                profile_url: None,
+                source: IdentitySource::GitLab,
+            }))
+        }
+    }
+}
+
+// ============================================================================
+// Identity Cache
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedIdentity {
+    identity: AuthorIdentity,
+    /// Unix seconds
+    expires_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IdentityCacheFile {
+    version: u32,
+    /// Keyed by `host|email`, so the same email can map to accounts on different hosts
+    entries: BTreeMap<String, CachedIdentity>,
+}
+
+impl IdentityCacheFile {
+    fn get(&self, key: &str, now: u64) -> Option<&AuthorIdentity> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| &entry.identity)
+    }
+
+    fn insert(&mut self, key: String, identity: AuthorIdentity, now: u64) {
+        let ttl = match identity.source {
+            IdentitySource::Gravatar => FALLBACK_TTL_SECS,
+            _ => HOST_TTL_SECS,
+        };
+        self.entries.insert(
+            key,
+            CachedIdentity {
+                identity,
+                expires_at: now + ttl,
+            },
+        );
+    }
+
+    fn prune(&mut self, now: u64) {
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        let excess = self.entries.len().saturating_sub(MAX_CACHED_AUTHORS);
+        if excess > 0 {
+            let mut by_expiry: Vec<(u64, String)> = self
+                .entries
+                .iter()
+                .map(|(key, entry)| (entry.expires_at, key.clone()))
+                .collect();
+            by_expiry.sort_unstable();
+            for (_, key) in by_expiry.into_iter().take(excess) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+fn cache_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".fluxel").join("authors.json"))
+}
+
+fn load_cache(path: &PathBuf) -> IdentityCacheFile {
+    let empty = IdentityCacheFile {
+        version: CACHE_VERSION,
+        ..Default::default()
+    };
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return empty;
+    };
+    match serde_json::from_str::<IdentityCacheFile>(&content) {
+        Ok(file) if file.version == CACHE_VERSION => file,
+        _ => empty,
+    }
+}
+
+fn save_cache(path: &PathBuf, file: &IdentityCacheFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let json = serde_json::to_string(file).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Serializes access to the identity cache file
+#[derive(Clone, Default)]
+pub struct AuthorIdentityState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl AuthorIdentityState {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    async fn with_cache<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&PathBuf, &mut IdentityCacheFile) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let lock = Arc::clone(&self.lock);
+        tauri::async_runtime::spawn_blocking(move || {
+            let _guard = lock.lock().map_err(|e| e.to_string())?;
+            let path = cache_path()?;
+            let mut file = load_cache(&path);
+            f(&path, &mut file)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Resolve display names and avatars for a batch of commit authors
+///
+/// # Arguments
+/// * `root_path` - Repository whose `origin` host is asked (optional; Gravatar only without it)
+/// * `authors` - Authors to resolve; duplicate emails are looked up once
+/// * `token` - Code host token to use instead of the stored one
+///
+/// # Returns
+/// Identities keyed by lowercased email
+#[fluxel_command]
+pub async fn resolve_author_identities(
+    root_path: Option<String>,
+    authors: Vec<AuthorQuery>,
+    token: Option<String>,
+    state: State<'_, AuthorIdentityState>,
+) -> Result<BTreeMap<String, AuthorIdentity>, String> {
+    let state = state.inner().clone();
+
+    // A repository without a recognizable host still gets Gravatar avatars
+    let client = match root_path {
+        Some(root_path) => match code_host::load_context(root_path, token).await {
+            Ok(context) => Some(HostClient::new(&context)?),
+            Err(e) => {
+                println!("[AuthorIdentity] No code host lookups: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let host = client
+        .as_ref()
+        .map(|client| client.remote.host.clone())
+        .unwrap_or_else(|| "-".to_string());
+
+    let mut unique: BTreeMap<String, AuthorQuery> = BTreeMap::new();
+    for query in authors {
+        let email = normalize_email(&query.email);
+        if email.is_empty() {
+            continue;
+        }
+        let entry = unique.entry(email).or_insert_with(|| query.clone());
+        if entry.commit.is_none() {
+            entry.commit = query.commit;
+        }
+        if entry.name.is_none() {
+            entry.name = query.name;
+        }
+    }
+
+    let mut resolved = BTreeMap::new();
+    let mut pending = Vec::new();
+    let cached_host = host.clone();
+    let cached = state
+        .with_cache(move |_, file| {
+            let now = now_secs();
+            Ok(unique
+                .into_iter()
+                .map(|(email, query)| {
+                    let hit = file
+                        .get(&format!("{}|{}", cached_host, email), now)
+                        .cloned();
+                    (email, query, hit)
+                })
+                .collect::<Vec<_>>())
+        })
+        .await?;
+    for (email, query, hit) in cached {
+        if let Some(identity) = github_noreply_identity(&email) {
+            resolved.insert(email, identity);
+        } else if let Some(identity) = hit {
+            resolved.insert(email, identity);
+        } else {
+            pending.push((email, query));
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(resolved);
+    }
+
+    // (email, identity, cacheable)
+    let looked_up: Vec<(String, AuthorIdentity, bool)> = match &client {
+        Some(client) => {
+            futures::stream::iter(pending)
+                .map(|(email, query)| async move {
+                    match lookup_on_host(client, &query).await {
+                        Ok(Some(identity)) => (email, identity, true),
+                        Ok(None) => (email, gravatar_identity(&query), true),
+                        Err(e) => {
+                            println!("[AuthorIdentity] Lookup for {} failed: {}", email, e);
+                            (email, gravatar_identity(&query), false)
+                        }
+                    }
+                })
+                .buffer_unordered(LOOKUP_CONCURRENCY)
+                .collect()
+                .await
+        }
+        None => pending
+            .into_iter()
+            .map(|(email, query)| (email, gravatar_identity(&query), true))
+            .collect(),
+    };
+
+    let to_cache: Vec<(String, AuthorIdentity)> = looked_up
+        .iter()
+        .filter(|(_, _, cacheable)| *cacheable)
+        .map(|(email, identity, _)| (format!("{}|{}", host, email), identity.clone()))
+        .collect();
+    if !to_cache.is_empty() {
+        state
+            .with_cache(move |path, file| {
+                let now = now_secs();
+                for (key, identity) in to_cache {
+                    file.insert(key, identity, now);
+                }
+                file.prune(now);
+                save_cache(path, file)
+            })
+            .await?;
+    }
+
+    resolved.extend(
+        looked_up
+            .into_iter()
+            .map(|(email, identity, _)| (email, identity)),
+    );
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_noreply_addresses_and_gravatar_fallbacks() {
+        let identity = github_noreply_identity("1234+octocat@users.noreply.github.com").unwrap();
+        assert_eq!(identity.login.as_deref(), Some("octocat"));
+        assert_eq!(
+            identity.avatar_url,
+            "https://avatars.githubusercontent.com/u/1234?v=4"
+        );
+        let legacy = github_noreply_identity("octocat@users.noreply.github.com").unwrap();
+        assert_eq!(legacy.avatar_url, "https://github.com/octocat.png");
+        assert!(github_noreply_identity("octocat@example.com").is_none());
+
+        assert_eq!(
+            gravatar_url(" Dev@Example.com "),
+            gravatar_url("dev@example.com")
+        );
+        assert!(gravatar_url("dev@example.com").contains("d=identicon"));
+        assert_eq!(encode_query_value("a+b@c.io"), "a%2Bb%40c.io");
+    }
+
+    #[test]
+    fn expires_fallbacks_before_host_identities() {
+        let query = AuthorQuery {
+            email: "dev@example.com".to_string(),
+            name: Some("Dev".to_string()),
+            commit: None,
+        };
+        let mut file = IdentityCacheFile::default();
+        file.insert(
+            "-|dev@example.com".to_string(),
+            gravatar_identity(&query),
+            0,
+        );
+        let mut host = gravatar_identity(&query);
+        host.source = IdentitySource::GitHub;
+        file.insert("github.com|dev@example.com".to_string(), host, 0);
+
+        let later = FALLBACK_TTL_SECS + 1;
+        assert!(file.get("-|dev@example.com", later).is_none());
+        assert!(file.get("github.com|dev@example.com", later).is_some());
+
+        file.prune(later);
+        assert_eq!(file.entries.len(), 1);
+    }
+}
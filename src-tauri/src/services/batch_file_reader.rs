@@ -3,7 +3,9 @@
 //! Provides efficient batch file reading operations for the TypeScript type loader.
 //! Uses parallel I/O via Tokio to minimize latency when loading many type files.
 
+use crate::services::io_accounting::IoAccounting;
 use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
 use fluxel_node_resolver::{discover_typings_native, TypingsResponse};
 use futures::future::join_all;
 use std::collections::HashMap;
@@ -12,13 +14,29 @@ use tokio::fs;
 /// Read multiple files in parallel and return their contents.
 /// Returns a map of path -> content for successfully read files.
 /// Files that fail to read are silently skipped.
-#[tauri::command]
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(
+        skip(paths),
+        fields(
+            category = "file_io",
+            bytes_read = tracing::field::Empty,
+            files_read = tracing::field::Empty
+        )
+    )
+)]
 pub async fn batch_read_files(paths: Vec<String>) -> Result<HashMap<String, String>, String> {
+    let io = IoAccounting::current();
     let tasks: Vec<_> = paths
         .into_iter()
-        .map(|path| async move {
-            let content = fs::read_to_string(&path).await.ok()?;
-            Some((path, content))
+        .map(|path| {
+            let io = &io;
+            async move {
+                let content = fs::read_to_string(&path).await.ok()?;
+                io.file_read(content.len() as u64);
+                Some((path, content))
+            }
         })
         .collect();
 
@@ -30,7 +48,7 @@ pub async fn batch_read_files(paths: Vec<String>) -> Result<HashMap<String, Stri
 
 /// Batch discover typings for multiple packages in parallel.
 /// More efficient than calling discover_package_typings N times via IPC.
-#[tauri::command]
+#[fluxel_command]
 pub async fn batch_discover_typings(
     package_names: Vec<String>,
     project_root: String,
@@ -40,7 +58,7 @@ pub async fn batch_discover_typings(
     // Discover typings for each package (this is synchronous but fast)
     let results: Vec<TypingsResponse> = package_names
         .iter()
-        .filter_map(|name| discover_typings_native(name, &root).ok())
+        .filter_map(|name| discover_typings_native(name, &root, None).ok())
         .collect();
 
     Ok(results)
@@ -48,7 +66,7 @@ pub async fn batch_discover_typings(
 
 /// Get the count of type files that would be loaded for given packages.
 /// Useful for progress indication.
-#[tauri::command]
+#[fluxel_command]
 pub async fn count_package_type_files(
     package_names: Vec<String>,
     project_root: String,
@@ -57,7 +75,7 @@ pub async fn count_package_type_files(
 
     let total: usize = package_names
         .iter()
-        .filter_map(|name| discover_typings_native(name, &root).ok())
+        .filter_map(|name| discover_typings_native(name, &root, None).ok())
         .map(|res| res.files.len())
         .sum();
 
@@ -0,0 +1,692 @@
+//! Benchmark Runner
+//!
+//! Finds and runs benchmarks for BenchmarkDotNet projects, `cargo bench`
+//! (libtest and Criterion output), and `vitest bench`. Results are normalized
+//! to name, mean, standard deviation, and allocations, kept per workspace in
+//! `~/.fluxel/benchmarks/<workspace-hash>.json`, and compared with the
+//! previous run of the same suite.
+
+use crate::services::project_detector::detect_node_info;
+use crate::services::runnables::{dotnet_projects, package_runner};
+use crate::services::workspace_store::{WorkspaceFile, WorkspaceStore};
+use fluxel_macros::fluxel_command;
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+
+const STORE_VERSION: u32 = 1;
+/// Runs kept per suite; older runs are dropped
+const MAX_RUNS_PER_SUITE: usize = 50;
+/// How deep benchmark sources are searched for
+const SEARCH_DEPTH: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchmarkFramework {
+    BenchmarkDotNet,
+    Cargo,
+    Vitest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkSuite {
+    /// Stable identifier, e.g. `dotnet:Benchmarks/Benchmarks.csproj`
+    pub id: String,
+    pub framework: BenchmarkFramework,
+    pub label: String,
+    /// Project file for BenchmarkDotNet suites
+    pub project: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub mean_ns: f64,
+    pub stddev_ns: Option<f64>,
+    /// Bytes allocated per operation, where the framework measures it
+    pub allocated_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRun {
+    pub suite_id: String,
+    /// Start time (unix milliseconds)
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub results: Vec<BenchmarkResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkDelta {
+    pub name: String,
+    pub mean_ns: f64,
+    /// Mean in the previous run; `None` for new benchmarks
+    pub previous_mean_ns: Option<f64>,
+    /// Relative change of the mean; positive is slower
+    pub change_percent: Option<f64>,
+    pub allocated_bytes: Option<u64>,
+    pub previous_allocated_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub success: bool,
+    pub run: BenchmarkRun,
+    pub deltas: Vec<BenchmarkDelta>,
+    /// Raw output of the benchmark process
+    pub output: String,
+}
+
+// ============================================================================
+// Discovery
+// ============================================================================
+
+fn relative(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn has_vitest(root: &Path) -> bool {
+    let Some(package) = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return false;
+    };
+    ["dependencies", "devDependencies"]
+        .iter()
+        .any(|field| package[field].get("vitest").is_some())
+}
+
+fn discover(root: &Path) -> Vec<BenchmarkSuite> {
+    let mut suites: Vec<BenchmarkSuite> = dotnet_projects(root)
+        .into_iter()
+        .filter(|project| {
+            std::fs::read_to_string(project)
+                .is_ok_and(|content| content.contains("\"BenchmarkDotNet\""))
+        })
+        .map(|project| {
+            let relative_path = relative(&project, root);
+            BenchmarkSuite {
+                id: format!("dotnet:{}", relative_path),
+                framework: BenchmarkFramework::BenchmarkDotNet,
+                label: project
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or(relative_path),
+                project: Some(project.to_string_lossy().to_string()),
+            }
+        })
+        .collect();
+
+    let mut has_cargo_benches = false;
+    let mut has_vitest_benches = false;
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(SEARCH_DEPTH))
+        .filter_entry(|entry| {
+            !matches!(
+                entry.file_name().to_str(),
+                Some("node_modules" | "target" | "bin" | "obj")
+            )
+        })
+        .build();
+    for entry in walker.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy();
+        if path.is_dir() && name == "benches" {
+            has_cargo_benches |= path
+                .parent()
+                .is_some_and(|dir| dir.join("Cargo.toml").is_file());
+        } else if name.contains(".bench.") {
+            has_vitest_benches = true;
+        }
+    }
+
+    if has_cargo_benches && root.join("Cargo.toml").is_file() {
+        suites.push(BenchmarkSuite {
+            id: "cargo".to_string(),
+            framework: BenchmarkFramework::Cargo,
+            label: "cargo bench".to_string(),
+            project: None,
+        });
+    }
+    if has_vitest_benches && has_vitest(root) {
+        suites.push(BenchmarkSuite {
+            id: "vitest".to_string(),
+            framework: BenchmarkFramework::Vitest,
+            label: "vitest bench".to_string(),
+            project: None,
+        });
+    }
+    suites
+}
+
+// ============================================================================
+// Output Parsing
+// ============================================================================
+
+/// Nanoseconds in a duration such as `4.123 us` or `1,234 ns`
+fn parse_duration_ns(value: &str, unit: &str) -> Option<f64> {
+    let value: f64 = value.replace(',', "").parse().ok()?;
+    let scale = match unit {
+        "ps" => 0.001,
+        "ns" => 1.0,
+        "us" | "μs" | "µs" => 1_000.0,
+        "ms" => 1_000_000.0,
+        "s" => 1_000_000_000.0,
+        _ => return None,
+    };
+    Some(value * scale)
+}
+
+/// Bytes in a BenchmarkDotNet allocation column; `-` means nothing allocated
+fn parse_bytes(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text == "-" {
+        return Some(0);
+    }
+    let (value, unit) = text.split_once(' ')?;
+    let value: f64 = value.replace(',', "").parse().ok()?;
+    let scale = match unit {
+        "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * scale).round() as u64)
+}
+
+fn parse_duration_cell(text: &str) -> Option<f64> {
+    let (value, unit) = text.trim().split_once(' ')?;
+    parse_duration_ns(value, unit.trim())
+}
+
+/// Summary tables BenchmarkDotNet prints at the end of a run
+fn parse_benchmark_dotnet(output: &str) -> Vec<BenchmarkResult> {
+    let mut results = Vec::new();
+    let mut header: Option<Vec<String>> = None;
+
+    for line in output.lines().map(str::trim) {
+        if !line.starts_with('|') {
+            header = None;
+            continue;
+        }
+        let cells: Vec<String> = line
+            .trim_matches('|')
+            .split('|')
+            .map(|cell| cell.trim().to_string())
+            .collect();
+        if cells.iter().any(|cell| cell == "Method") && cells.iter().any(|cell| cell == "Mean") {
+            header = Some(cells);
+            continue;
+        }
+        let Some(columns) = &header else {
+            continue;
+        };
+        if cells
+            .iter()
+            .all(|cell| cell.chars().all(|c| matches!(c, '-' | ':')))
+        {
+            continue;
+        }
+
+        let cell = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .and_then(|index| cells.get(index))
+                .map(String::as_str)
+        };
+        let Some(mean_ns) = cell("Mean").and_then(parse_duration_cell) else {
+            continue;
+        };
+        // Parameter columns sit between the method and the statistics
+        let method_index = columns.iter().position(|c| c == "Method").unwrap_or(0);
+        let mean_index = columns.iter().position(|c| c == "Mean").unwrap_or(0);
+        let parameters: Vec<String> = (method_index + 1..mean_index)
+            .filter_map(|index| Some(format!("{}={}", columns[index], cells.get(index)?)))
+            .collect();
+        let method = cell("Method").unwrap_or_default();
+        let name = if parameters.is_empty() {
+            method.to_string()
+        } else {
+            format!("{}({})", method, parameters.join(", "))
+        };
+
+        results.push(BenchmarkResult {
+            name,
+            mean_ns,
+            stddev_ns: cell("StdDev").and_then(parse_duration_cell),
+            allocated_bytes: cell("Allocated").and_then(parse_bytes),
+        });
+    }
+    results
+}
+
+fn libtest_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^test (\S+)\s+\.\.\. bench:\s+([\d,\.]+) ns/iter \(\+/- ([\d,\.]+)\)")
+            .expect("Failed to compile libtest bench regex")
+    })
+}
+
+fn criterion_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(.*?)\s*time:\s+\[\S+ \S+ (\S+) (\S+) \S+ \S+\]")
+            .expect("Failed to compile criterion regex")
+    })
+}
+
+/// libtest `bench:` lines and Criterion `time:` estimates
+fn parse_cargo_bench(output: &str) -> Vec<BenchmarkResult> {
+    let mut results = Vec::new();
+    let mut previous_line = "";
+    for line in output.lines() {
+        if let Some(caps) = libtest_regex().captures(line) {
+            results.push(BenchmarkResult {
+                name: caps[1].to_string(),
+                mean_ns: parse_duration_ns(&caps[2], "ns").unwrap_or_default(),
+                stddev_ns: parse_duration_ns(&caps[3], "ns"),
+                allocated_bytes: None,
+            });
+        } else if let Some(caps) = criterion_regex().captures(line) {
+            // Criterion puts long names on a line of their own
+            let name = match caps[1].trim() {
+                "" => previous_line.trim(),
+                name => name,
+            };
+            if let Some(mean_ns) = parse_duration_ns(&caps[2], &caps[3]) {
+                results.push(BenchmarkResult {
+                    name: name.to_string(),
+                    mean_ns,
+                    stddev_ns: None,
+                    allocated_bytes: None,
+                });
+            }
+        }
+        if !line.trim().is_empty() {
+            previous_line = line;
+        }
+    }
+    results
+}
+
+#[derive(Deserialize)]
+struct VitestReport {
+    #[serde(default)]
+    files: Vec<VitestFile>,
+}
+
+#[derive(Deserialize)]
+struct VitestFile {
+    #[serde(default)]
+    groups: Vec<VitestGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VitestGroup {
+    full_name: String,
+    #[serde(default)]
+    benchmarks: Vec<VitestBenchmark>,
+}
+
+/// Times are in milliseconds
+#[derive(Deserialize)]
+struct VitestBenchmark {
+    name: String,
+    mean: f64,
+    sd: Option<f64>,
+}
+
+/// The report `vitest bench --outputJson` writes
+fn parse_vitest_report(json: &str) -> Result<Vec<BenchmarkResult>, String> {
+    let report: VitestReport =
+        serde_json::from_str(json).map_err(|e| format!("Invalid vitest report: {}", e))?;
+    Ok(report
+        .files
+        .into_iter()
+        .flat_map(|file| file.groups)
+        .flat_map(|group| {
+            let prefix = group.full_name;
+            group
+                .benchmarks
+                .into_iter()
+                .map(move |bench| BenchmarkResult {
+                    name: format!("{} > {}", prefix, bench.name),
+                    mean_ns: bench.mean * 1_000_000.0,
+                    stddev_ns: bench.sd.map(|sd| sd * 1_000_000.0),
+                    allocated_bytes: None,
+                })
+        })
+        .collect())
+}
+
+// ============================================================================
+// History Store
+// ============================================================================
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    version: u32,
+    workspace: String,
+    /// Oldest first
+    runs: Vec<BenchmarkRun>,
+}
+
+impl WorkspaceFile for HistoryFile {
+    const VERSION: u32 = STORE_VERSION;
+    const LABEL: &'static str = "benchmark history";
+
+    fn empty(workspace: String) -> Self {
+        Self {
+            version: STORE_VERSION,
+            workspace,
+            ..Default::default()
+        }
+    }
+}
+
+type HistoryStore = WorkspaceStore<HistoryFile>;
+
+fn push_run(file: &mut HistoryFile, run: BenchmarkRun) {
+    let suite_id = run.suite_id.clone();
+    file.runs.push(run);
+    let suite_runs = file.runs.iter().filter(|r| r.suite_id == suite_id).count();
+    if suite_runs > MAX_RUNS_PER_SUITE {
+        if let Some(oldest) = file.runs.iter().position(|r| r.suite_id == suite_id) {
+            file.runs.remove(oldest);
+        }
+    }
+}
+
+/// Compare `results` with those of `previous`
+fn deltas(results: &[BenchmarkResult], previous: Option<&BenchmarkRun>) -> Vec<BenchmarkDelta> {
+    let previous: HashMap<&str, &BenchmarkResult> = previous
+        .map(|run| {
+            run.results
+                .iter()
+                .map(|result| (result.name.as_str(), result))
+                .collect()
+        })
+        .unwrap_or_default();
+    results
+        .iter()
+        .map(|result| {
+            let before = previous.get(result.name.as_str());
+            BenchmarkDelta {
+                name: result.name.clone(),
+                mean_ns: result.mean_ns,
+                previous_mean_ns: before.map(|b| b.mean_ns),
+                change_percent: before
+                    .filter(|b| b.mean_ns > 0.0)
+                    .map(|b| (result.mean_ns - b.mean_ns) / b.mean_ns * 100.0),
+                allocated_bytes: result.allocated_bytes,
+                previous_allocated_bytes: before.and_then(|b| b.allocated_bytes),
+            }
+        })
+        .collect()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Running
+// ============================================================================
+
+/// Run `suite` and parse its results, with the process output
+async fn run_suite(
+    root: &Path,
+    suite: &BenchmarkSuite,
+    filter: Option<&str>,
+) -> Result<(bool, Vec<BenchmarkResult>, String), String> {
+    let report_path = std::env::temp_dir().join(format!("fluxel-bench-{}.json", now_millis()));
+    let mut cmd = match suite.framework {
+        BenchmarkFramework::BenchmarkDotNet => {
+            let project = suite.project.as_deref().ok_or("Suite has no project")?;
+            let mut cmd = Command::new("dotnet");
+            cmd.args(["run", "-c", "Release", "--project", project, "--"])
+                .args(["--filter", filter.unwrap_or("*")]);
+            cmd
+        }
+        BenchmarkFramework::Cargo => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["bench", "--color", "never"]);
+            if let Some(filter) = filter {
+                cmd.args(["--", filter]);
+            }
+            cmd
+        }
+        BenchmarkFramework::Vitest => {
+            let node = detect_node_info(root);
+            let (runner, runner_args) = package_runner(node.package_manager.as_ref());
+            // Windows package managers are `.cmd` shims
+            let program = if cfg!(windows) && runner != "bunx" {
+                format!("{}.cmd", runner)
+            } else {
+                runner.to_string()
+            };
+            let mut cmd = Command::new(program);
+            cmd.args(runner_args)
+                .args(["vitest", "bench", "--run", "--outputJson"])
+                .arg(&report_path);
+            if let Some(filter) = filter {
+                cmd.args(["-t", filter]);
+            }
+            cmd
+        }
+    };
+    cmd.current_dir(root);
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        cmd.creation_flags(0x08000000);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", suite.label, e))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let results = match suite.framework {
+        BenchmarkFramework::BenchmarkDotNet => parse_benchmark_dotnet(&text),
+        BenchmarkFramework::Cargo => parse_cargo_bench(&text),
+        BenchmarkFramework::Vitest => {
+            let report = std::fs::read_to_string(&report_path);
+            let _ = std::fs::remove_file(&report_path);
+            match report {
+                Ok(json) => parse_vitest_report(&json)?,
+                Err(_) => Vec::new(),
+            }
+        }
+    };
+    Ok((output.status.success(), results, text))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the benchmark suites in a workspace
+///
+/// # Arguments
+/// * `workspace_root` - Workspace to scan
+#[fluxel_command]
+pub async fn discover_benchmarks(workspace_root: String) -> Result<Vec<BenchmarkSuite>, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err(format!(
+            "Workspace root is not a directory or does not exist: {}",
+            workspace_root
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || discover(&root))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run a benchmark suite, record its results, and compare them with the
+/// previous run
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the suite belongs to
+/// * `suite_id` - Suite to run, from `discover_benchmarks`
+/// * `filter` - Only run benchmarks matching this pattern
+#[fluxel_command]
+pub async fn run_benchmarks(
+    workspace_root: String,
+    suite_id: String,
+    filter: Option<String>,
+) -> Result<BenchmarkReport, String> {
+    let root = PathBuf::from(&workspace_root);
+    let scan_root = root.clone();
+    let suite = tauri::async_runtime::spawn_blocking(move || discover(&scan_root))
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|suite| suite.id == suite_id)
+        .ok_or_else(|| format!("Benchmark suite not found: {}", suite_id))?;
+
+    println!("[Benchmarks] Running {}", suite.label);
+    let started_at = now_millis();
+    let start = Instant::now();
+    let (success, results, output) = run_suite(&root, &suite, filter.as_deref()).await?;
+    let run = BenchmarkRun {
+        suite_id: suite.id.clone(),
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        results,
+    };
+
+    let store = HistoryStore::for_user("benchmarks")?;
+    let mut history = store.load(&root)?;
+    let previous = history
+        .runs
+        .iter()
+        .rev()
+        .find(|previous| previous.suite_id == run.suite_id);
+    let deltas = deltas(&run.results, previous);
+    // Runs that produced nothing (build errors, no matches) would only hide
+    // the last real baseline
+    if !run.results.is_empty() {
+        push_run(&mut history, run.clone());
+        store.save(&root, &history)?;
+    }
+    println!(
+        "[Benchmarks] {} finished with {} results",
+        suite.label,
+        run.results.len()
+    );
+
+    Ok(BenchmarkReport {
+        success,
+        run,
+        deltas,
+        output,
+    })
+}
+
+/// Recorded runs of a workspace, most recent first
+///
+/// # Arguments
+/// * `workspace_root` - Workspace whose history is read
+/// * `suite_id` - Only runs of this suite
+#[fluxel_command]
+pub async fn get_benchmark_history(
+    workspace_root: String,
+    suite_id: Option<String>,
+) -> Result<Vec<BenchmarkRun>, String> {
+    let history = HistoryStore::for_user("benchmarks")?.load(Path::new(&workspace_root))?;
+    Ok(history
+        .runs
+        .into_iter()
+        .rev()
+        .filter(|run| suite_id.as_ref().is_none_or(|id| &run.suite_id == id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_benchmark_output() {
+        let dotnet = "\
+| Method | N    | Mean     | Error     | StdDev    | Gen0   | Allocated |
+|------- |----- |---------:|----------:|----------:|-------:|----------:|
+| Sha256 | 1000 | 4.123 μs | 0.0812 μs | 0.0760 μs | 0.0153 |   1.17 KB |
+| Md5    | 1000 | 2,001 ns |  10.2 ns  |   9.5 ns  |      - |         - |
+";
+        let results = parse_benchmark_dotnet(dotnet);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Sha256(N=1000)");
+        assert!((results[0].mean_ns - 4123.0).abs() < 1e-6);
+        assert!((results[0].stddev_ns.unwrap() - 76.0).abs() < 1e-6);
+        assert_eq!(results[0].allocated_bytes, Some(1198));
+        assert_eq!(results[1].mean_ns, 2001.0);
+        assert_eq!(results[1].allocated_bytes, Some(0));
+
+        let cargo = "\
+test bench_add      ... bench:       1,234 ns/iter (+/- 56)
+fib 20                  time:   [26.029 µs 26.251 µs 26.505 µs]
+group/a_very_long_benchmark_name
+                        time:   [1.0 ms 1.5 ms 2.0 ms]
+";
+        let results = parse_cargo_bench(cargo);
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["bench_add", "fib 20", "group/a_very_long_benchmark_name"]
+        );
+        assert_eq!(results[0].mean_ns, 1234.0);
+        assert_eq!(results[0].stddev_ns, Some(56.0));
+        assert!((results[1].mean_ns - 26251.0).abs() < 1e-6);
+        assert_eq!(results[2].mean_ns, 1_500_000.0);
+
+        let vitest = r#"{ "files": [{ "filepath": "/w/sort.bench.ts", "groups": [
+            { "fullName": "sort.bench.ts > sorting", "benchmarks": [
+                { "name": "native", "mean": 0.5, "sd": 0.01, "hz": 2000 } ] } ] }] }"#;
+        let results = parse_vitest_report(vitest).unwrap();
+        assert_eq!(results[0].name, "sort.bench.ts > sorting > native");
+        assert_eq!(results[0].mean_ns, 500_000.0);
+
+        let previous = BenchmarkRun {
+            suite_id: "vitest".to_string(),
+            started_at: 0,
+            duration_ms: 0,
+            results: vec![BenchmarkResult {
+                mean_ns: 400_000.0,
+                ..results[0].clone()
+            }],
+        };
+        let compared = deltas(&results, Some(&previous));
+        assert_eq!(compared[0].previous_mean_ns, Some(400_000.0));
+        assert!((compared[0].change_percent.unwrap() - 25.0).abs() < 1e-9);
+        assert_eq!(deltas(&results, None)[0].change_percent, None);
+    }
+}
@@ -0,0 +1,1625 @@
+//! Code Host Service
+//!
+//! Talks to the GitHub or GitLab instance behind a repository's `origin` remote:
+//! pull/merge requests, their status checks, review comments mapped onto local
+//! files, and opening a pull request for the current branch.
+//!
+//! Remotes on `github.com` and `gitlab.com` are detected from the URL; any other
+//! host has to be opted in with `git config fluxel.codeHost`. Tokens come from
+//! the command argument, then the workspace secret `GITHUB_TOKEN`/`GITLAB_TOKEN`
+//! and the usual environment variables for the public hosts, or the
+//! `CODE_HOST_TOKEN` workspace secret for a self-hosted instance, so a token
+//! meant for github.com is never sent anywhere else. Merge requests are exposed
+//! under the same pull request types so the UI doesn't need to care which host
+//! it is talking to.
+//!
+//! Issues are cached per repository in `~/.fluxel/code-host/<repo-hash>.json`,
+//! so `#123` autocomplete and issue hover cards answer from disk and keep
+//! working offline; the cache is refreshed when it is older than its TTL.
+
+use crate::services::secrets::read_workspace_secret;
+use crate::services::workspace_store::sha256_hex;
+use fluxel_macros::fluxel_command;
+use git2::{BranchType, Repository};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// Pull requests fetched per list request
+const LIST_PAGE_SIZE: usize = 50;
+/// Comments, checks, and discussions fetched per request
+const DETAIL_PAGE_SIZE: usize = 100;
+
+const ISSUE_CACHE_VERSION: u32 = 1;
+/// Age after which the cached issue list is refetched
+const ISSUE_LIST_TTL_SECS: u64 = 5 * 60;
+/// Age after which a single cached issue is refetched
+const ISSUE_TTL_SECS: u64 = 10 * 60;
+/// Issues kept per repository; the least recently fetched are dropped
+const MAX_CACHED_ISSUES: usize = 1000;
+/// Issues returned by `list_issues` unless a limit is given
+const DEFAULT_ISSUE_LIMIT: usize = 20;
+/// Workspace secret holding the token of a self-hosted instance
+const SELF_HOSTED_TOKEN_SECRET: &str = "CODE_HOST_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostKind {
+    GitHub,
+    GitLab,
+}
+
+impl HostKind {
+    fn label(self) -> &'static str {
+        match self {
+            HostKind::GitHub => "GitHub",
+            HostKind::GitLab => "GitLab",
+        }
+    }
+}
+
+/// Repository on a code host, as derived from a remote URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRepo {
+    pub kind: HostKind,
+    /// Host name without user or port, e.g. `github.com`
+    pub host: String,
+    /// `owner/name`, or `group/subgroup/name` on GitLab
+    pub path: String,
+}
+
+impl RemoteRepo {
+    /// Whether the remote is on github.com or gitlab.com itself
+    fn is_public_host(&self) -> bool {
+        match self.kind {
+            HostKind::GitHub => self.host == "github.com",
+            HostKind::GitLab => self.host == "gitlab.com",
+        }
+    }
+
+    /// Workspace secret and environment variables consulted for a token. The
+    /// shared tokens are only offered to the public hosts; a self-hosted instance
+    /// gets its own secret and never sees the environment.
+    fn token_sources(&self) -> (&'static str, &'static [&'static str]) {
+        match self.kind {
+            _ if !self.is_public_host() => (SELF_HOSTED_TOKEN_SECRET, &[]),
+            HostKind::GitHub => ("GITHUB_TOKEN", &["GITHUB_TOKEN", "GH_TOKEN"]),
+            HostKind::GitLab => ("GITLAB_TOKEN", &["GITLAB_TOKEN"]),
+        }
+    }
+
+    fn api_base(&self) -> String {
+        match self.kind {
+            HostKind::GitHub if self.host == "github.com" => "https://api.github.com".to_string(),
+            HostKind::GitHub => format!("https://{}/api/v3", self.host),
+            HostKind::GitLab => format!("https://{}/api/v4", self.host),
+        }
+    }
+
+    fn web_url(&self) -> String {
+        format!("https://{}/{}", self.host, self.path)
+    }
+
+    /// API path of the repository (GitLab addresses projects by URL-encoded path)
+    fn api_repo_path(&self) -> String {
+        match self.kind {
+            HostKind::GitHub => format!("/repos/{}", self.path),
+            HostKind::GitLab => format!("/projects/{}", self.path.replace('/', "%2F")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeHostInfo {
+    pub kind: HostKind,
+    pub host: String,
+    pub path: String,
+    pub web_url: String,
+    /// Whether a token was found for the host
+    pub authenticated: bool,
+    /// Current branch, if HEAD is not detached
+    pub branch: Option<String>,
+}
+
+/// A GitHub pull request or GitLab merge request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    /// PR number on GitHub, MR iid on GitLab
+    pub number: u64,
+    pub title: String,
+    /// `"open"`, `"closed"`, or `"merged"`
+    pub state: String,
+    pub draft: bool,
+    pub author: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub head_sha: Option<String>,
+    pub url: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckState {
+    Pending,
+    Success,
+    Failure,
+    Neutral,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCheck {
+    pub name: String,
+    pub state: CheckState,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestChecks {
+    pub sha: String,
+    /// Failure if any check failed, pending if any is still running, else success
+    pub state: CheckState,
+    pub checks: Vec<StatusCheck>,
+}
+
+/// A review comment anchored to a line of a file in the pull request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    pub id: u64,
+    /// Id of the comment that started the thread, for replies
+    pub thread_id: Option<u64>,
+    pub author: String,
+    pub body: String,
+    /// Repository-relative path
+    pub path: String,
+    /// Absolute path of the file in the local work tree
+    pub file_path: String,
+    /// 1-based line the comment is attached to
+    pub line: Option<u32>,
+    /// First line of a multi-line comment
+    pub start_line: Option<u32>,
+    /// `"new"` for lines of the changed file, `"old"` for removed lines
+    pub side: &'static str,
+    /// The commented code has changed since; `line` is where it used to be
+    pub outdated: bool,
+    pub created_at: String,
+    pub url: String,
+}
+
+/// A GitHub or GitLab issue. GitHub pull requests share the issue number space,
+/// so they are included and flagged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    /// `"open"` or `"closed"`
+    pub state: String,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub body: Option<String>,
+    pub comments: u32,
+    pub is_pull_request: bool,
+    pub url: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// ============================================================================
+// Remote Detection
+// ============================================================================
+
+/// Split a remote URL into host and repository path.
+/// Handles `https://`, `ssh://`, and scp-style `git@host:owner/repo.git` forms.
+pub fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            let host = host.split(':').next()?;
+            (host, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || !path.contains('/') {
+        return None;
+    }
+    Some((host.to_ascii_lowercase(), path.to_string()))
+}
+
+/// Detect the code host of `url`. Only `github.com` and `gitlab.com` are
+/// recognized by name; other hosts need `configured` (`git config fluxel.codeHost`).
+pub fn detect_remote(url: &str, configured: Option<&str>) -> Option<RemoteRepo> {
+    let (host, path) = parse_remote_url(url)?;
+    let kind = match configured.map(|value| value.trim().to_ascii_lowercase()) {
+        Some(value) if value == "github" => HostKind::GitHub,
+        Some(value) if value == "gitlab" => HostKind::GitLab,
+        _ if host == "github.com" => HostKind::GitHub,
+        _ if host == "gitlab.com" => HostKind::GitLab,
+        _ => return None,
+    };
+    Some(RemoteRepo { kind, host, path })
+}
+
+fn resolve_token(
+    workspace_root: &Path,
+    remote: &RemoteRepo,
+    explicit: Option<String>,
+) -> Option<String> {
+    if let Some(token) = explicit.filter(|token| !token.trim().is_empty()) {
+        return Some(token);
+    }
+    let (secret, env_vars) = remote.token_sources();
+    match read_workspace_secret(workspace_root, secret) {
+        Ok(Some(token)) if !token.is_empty() => return Some(token),
+        Ok(_) => {}
+        Err(e) => println!(
+            "[CodeHost] Failed to read workspace secret {}: {}",
+            secret, e
+        ),
+    }
+    env_vars
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|token| !token.is_empty())
+}
+
+/// Everything a command needs from the local repository
+pub(crate) struct HostContext {
+    pub(crate) remote: RemoteRepo,
+    pub(crate) token: Option<String>,
+    workdir: PathBuf,
+    branch: Option<String>,
+}
+
+fn open_context(root_path: &str, token: Option<String>) -> Result<HostContext, String> {
+    let repo = Repository::open(root_path).map_err(|e| e.to_string())?;
+    let origin = repo
+        .find_remote("origin")
+        .map_err(|_| "Repository has no 'origin' remote".to_string())?;
+    let url = origin
+        .url()
+        .ok_or("The 'origin' remote URL is not valid UTF-8")?;
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_string("fluxel.codeHost").ok());
+    let remote = detect_remote(url, configured.as_deref()).ok_or_else(|| {
+        format!(
+            "Could not detect a GitHub or GitLab host for '{}' (set git config fluxel.codeHost)",
+            url
+        )
+    })?;
+
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory")?
+        .to_path_buf();
+    let branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+    let token = resolve_token(&workdir, &remote, token);
+
+    Ok(HostContext {
+        remote,
+        token,
+        workdir,
+        branch,
+    })
+}
+
+pub(crate) async fn load_context(
+    root_path: String,
+    token: Option<String>,
+) -> Result<HostContext, String> {
+    tauri::async_runtime::spawn_blocking(move || open_context(&root_path, token))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// API Client
+// ============================================================================
+
+pub(crate) struct HostClient {
+    http: reqwest::Client,
+    pub(crate) remote: RemoteRepo,
+    token: Option<String>,
+}
+
+impl HostClient {
+    pub(crate) fn new(context: &HostContext) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("Fluxel/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            http,
+            remote: context.remote.clone(),
+            token: context.token.clone(),
+        })
+    }
+
+    /// Request against the repository, e.g. `repo_request(GET, "/pulls")`
+    fn repo_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.api_request(method, &format!("{}{}", self.remote.api_repo_path(), path))
+    }
+
+    /// Request relative to the API root, e.g. `api_request(GET, "/user")`
+    fn api_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self
+            .http
+            .request(method, format!("{}{}", self.remote.api_base(), path));
+        match (self.remote.kind, &self.token) {
+            (HostKind::GitHub, Some(token)) => builder
+                .header("Accept", "application/vnd.github+json")
+                .bearer_auth(token),
+            (HostKind::GitHub, None) => builder.header("Accept", "application/vnd.github+json"),
+            (HostKind::GitLab, Some(token)) => builder.header("PRIVATE-TOKEN", token),
+            (HostKind::GitLab, None) => builder,
+        }
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.send(self.repo_request(Method::GET, path)).await
+    }
+
+    pub(crate) async fn get_api<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.send(self.api_request(Method::GET, path)).await
+    }
+
+    pub(crate) fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let label = self.remote.kind.label();
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| format!("{label} request failed: {e}"))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read {label} response: {e}"))?;
+        if !status.is_success() {
+            let hint = if self.token.is_none() && matches!(status.as_u16(), 401 | 403 | 404) {
+                format!(
+                    " (no token configured; set the {} workspace secret)",
+                    self.remote.token_sources().0
+                )
+            } else {
+                String::new()
+            };
+            return Err(format!(
+                "{label} error {status}: {}{hint}",
+                api_error_message(&text)
+            ));
+        }
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse {label} response: {e}"))
+    }
+
+    fn require_token(&self) -> Result<(), String> {
+        if self.token.is_some() {
+            return Ok(());
+        }
+        Err(format!(
+            "A {} token is required; set the {} workspace secret",
+            self.remote.kind.label(),
+            self.remote.token_sources().0
+        ))
+    }
+}
+
+/// The `message` of an API error body, or the body itself
+fn api_error_message(body: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(value) => match value.get("message") {
+            Some(serde_json::Value::String(message)) => message.clone(),
+            Some(other) => other.to_string(),
+            None => body.trim().to_string(),
+        },
+        Err(_) => body.trim().to_string(),
+    }
+}
+
+// ============================================================================
+// API Payloads
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    #[serde(alias = "username")]
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRepository {
+    default_branch: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhBranchRef {
+    #[serde(rename = "ref")]
+    name: String,
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhPullRequest {
+    number: u64,
+    title: String,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    html_url: String,
+    user: ApiUser,
+    head: GhBranchRef,
+    base: GhBranchRef,
+    updated_at: String,
+    merged_at: Option<String>,
+}
+
+impl From<GhPullRequest> for PullRequest {
+    fn from(pr: GhPullRequest) -> Self {
+        let state = if pr.merged_at.is_some() {
+            "merged".to_string()
+        } else {
+            pr.state
+        };
+        PullRequest {
+            number: pr.number,
+            title: pr.title,
+            state,
+            draft: pr.draft,
+            author: pr.user.login,
+            source_branch: pr.head.name,
+            target_branch: pr.base.name,
+            head_sha: Some(pr.head.sha),
+            url: pr.html_url,
+            updated_at: pr.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GlMergeRequest {
+    iid: u64,
+    title: String,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    web_url: String,
+    author: ApiUser,
+    source_branch: String,
+    target_branch: String,
+    sha: Option<String>,
+    updated_at: String,
+}
+
+impl From<GlMergeRequest> for PullRequest {
+    fn from(mr: GlMergeRequest) -> Self {
+        let state = match mr.state.as_str() {
+            "opened" => "open",
+            "merged" => "merged",
+            _ => "closed",
+        };
+        PullRequest {
+            number: mr.iid,
+            title: mr.title,
+            state: state.to_string(),
+            draft: mr.draft,
+            author: mr.author.login,
+            source_branch: mr.source_branch,
+            target_branch: mr.target_branch,
+            head_sha: mr.sha,
+            url: mr.web_url,
+            updated_at: mr.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCheckRuns {
+    check_runs: Vec<GhCheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCheckRunOutput {
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+    output: Option<GhCheckRunOutput>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCombinedStatus {
+    statuses: Vec<GhCommitStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhCommitStatus {
+    context: String,
+    state: String,
+    description: Option<String>,
+    target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlCommitStatus {
+    name: String,
+    status: String,
+    description: Option<String>,
+    target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhReviewComment {
+    id: u64,
+    in_reply_to_id: Option<u64>,
+    user: ApiUser,
+    body: String,
+    path: String,
+    line: Option<u32>,
+    original_line: Option<u32>,
+    start_line: Option<u32>,
+    side: Option<String>,
+    created_at: String,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlDiscussion {
+    notes: Vec<GlNote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlNote {
+    id: u64,
+    body: String,
+    author: ApiUser,
+    created_at: String,
+    #[serde(default)]
+    system: bool,
+    position: Option<GlPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlPosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<u32>,
+    old_line: Option<u32>,
+}
+
+// ============================================================================
+// Normalization
+// ============================================================================
+
+fn github_check_state(status: &str, conclusion: Option<&str>) -> CheckState {
+    if status != "completed" {
+        return CheckState::Pending;
+    }
+    match conclusion {
+        Some("success") => CheckState::Success,
+        Some("neutral") => CheckState::Neutral,
+        Some("skipped") => CheckState::Skipped,
+        _ => CheckState::Failure,
+    }
+}
+
+fn commit_status_state(state: &str) -> CheckState {
+    match state {
+        "success" => CheckState::Success,
+        "failure" | "failed" | "error" | "canceled" => CheckState::Failure,
+        "skipped" => CheckState::Skipped,
+        "manual" => CheckState::Neutral,
+        _ => CheckState::Pending,
+    }
+}
+
+fn overall_state(checks: &[StatusCheck]) -> CheckState {
+    if checks
+        .iter()
+        .any(|check| check.state == CheckState::Failure)
+    {
+        CheckState::Failure
+    } else if checks
+        .iter()
+        .any(|check| check.state == CheckState::Pending)
+    {
+        CheckState::Pending
+    } else if checks.is_empty() {
+        CheckState::Neutral
+    } else {
+        CheckState::Success
+    }
+}
+
+fn local_path(workdir: &Path, path: &str) -> String {
+    workdir.join(path).to_string_lossy().to_string()
+}
+
+fn github_review_comments(comments: Vec<GhReviewComment>, workdir: &Path) -> Vec<ReviewComment> {
+    comments
+        .into_iter()
+        .map(|comment| ReviewComment {
+            id: comment.id,
+            thread_id: comment.in_reply_to_id,
+            author: comment.user.login,
+            body: comment.body,
+            file_path: local_path(workdir, &comment.path),
+            path: comment.path,
+            outdated: comment.line.is_none(),
+            line: comment.line.or(comment.original_line),
+            start_line: comment.start_line,
+            side: if comment.side.as_deref() == Some("LEFT") {
+                "old"
+            } else {
+                "new"
+            },
+            created_at: comment.created_at,
+            url: comment.html_url,
+        })
+        .collect()
+}
+
+fn gitlab_review_comments(
+    discussions: Vec<GlDiscussion>,
+    workdir: &Path,
+    merge_request_url: &str,
+) -> Vec<ReviewComment> {
+    let mut comments = Vec::new();
+    for discussion in discussions {
+        let thread_id = discussion.notes.first().map(|note| note.id);
+        for note in discussion.notes {
+            if note.system {
+                continue;
+            }
+            // Notes without a position are general discussion, not inline comments
+            let Some(position) = note.position else {
+                continue;
+            };
+            let (path, line, side) = match (position.new_line, position.old_line) {
+                (Some(line), _) => (position.new_path, line, "new"),
+                (None, Some(line)) => (position.old_path, line, "old"),
+                (None, None) => continue,
+            };
+            let Some(path) = path else {
+                continue;
+            };
+            comments.push(ReviewComment {
+                id: note.id,
+                thread_id: thread_id.filter(|id| *id != note.id),
+                author: note.author.login,
+                body: note.body,
+                file_path: local_path(workdir, &path),
+                path,
+                line: Some(line),
+                start_line: None,
+                side,
+                outdated: false,
+                created_at: note.created_at,
+                url: format!("{}#note_{}", merge_request_url, note.id),
+            });
+        }
+    }
+    comments
+}
+
+#[derive(Debug, Deserialize)]
+struct GhLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhIssue {
+    number: u64,
+    title: String,
+    state: String,
+    body: Option<String>,
+    user: ApiUser,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    #[serde(default)]
+    comments: u32,
+    html_url: String,
+    created_at: String,
+    updated_at: String,
+    pull_request: Option<serde_json::Value>,
+}
+
+impl From<GhIssue> for Issue {
+    fn from(issue: GhIssue) -> Self {
+        Issue {
+            number: issue.number,
+            title: issue.title,
+            state: issue.state,
+            author: issue.user.login,
+            labels: issue.labels.into_iter().map(|label| label.name).collect(),
+            body: issue.body,
+            comments: issue.comments,
+            is_pull_request: issue.pull_request.is_some(),
+            url: issue.html_url,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GlIssue {
+    iid: u64,
+    title: String,
+    state: String,
+    description: Option<String>,
+    author: ApiUser,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    user_notes_count: u32,
+    web_url: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<GlIssue> for Issue {
+    fn from(issue: GlIssue) -> Self {
+        Issue {
+            number: issue.iid,
+            title: issue.title,
+            state: if issue.state == "opened" {
+                "open".to_string()
+            } else {
+                issue.state
+            },
+            author: issue.author.login,
+            labels: issue.labels,
+            body: issue.description,
+            comments: issue.user_notes_count,
+            is_pull_request: false,
+            url: issue.web_url,
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+async fn fetch_recent_issues(client: &HostClient) -> Result<Vec<Issue>, String> {
+    match client.remote.kind {
+        HostKind::GitHub => {
+            let issues: Vec<GhIssue> = client
+                .get(&format!(
+                    "/issues?state=all&sort=updated&direction=desc&per_page={}",
+                    DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            Ok(issues.into_iter().map(Issue::from).collect())
+        }
+        HostKind::GitLab => {
+            let issues: Vec<GlIssue> = client
+                .get(&format!(
+                    "/issues?state=all&order_by=updated_at&sort=desc&per_page={}",
+                    DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            Ok(issues.into_iter().map(Issue::from).collect())
+        }
+    }
+}
+
+async fn fetch_issue(client: &HostClient, number: u64) -> Result<Issue, String> {
+    match client.remote.kind {
+        HostKind::GitHub => {
+            let issue: GhIssue = client.get(&format!("/issues/{}", number)).await?;
+            Ok(issue.into())
+        }
+        HostKind::GitLab => {
+            let issue: GlIssue = client.get(&format!("/issues/{}", number)).await?;
+            Ok(issue.into())
+        }
+    }
+}
+
+// ============================================================================
+// Issue Cache
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedIssue {
+    issue: Issue,
+    /// Unix seconds
+    fetched_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueCacheFile {
+    version: u32,
+    /// `host/path` of the repository
+    repository: String,
+    /// When the recent-issues list was last fetched (unix seconds)
+    list_fetched_at: u64,
+    issues: BTreeMap<u64, CachedIssue>,
+}
+
+impl IssueCacheFile {
+    fn merge(&mut self, issues: Vec<Issue>, now: u64) {
+        for issue in issues {
+            self.issues.insert(
+                issue.number,
+                CachedIssue {
+                    issue,
+                    fetched_at: now,
+                },
+            );
+        }
+        let excess = self.issues.len().saturating_sub(MAX_CACHED_ISSUES);
+        if excess > 0 {
+            let mut by_age: Vec<(u64, u64)> = self
+                .issues
+                .values()
+                .map(|cached| (cached.fetched_at, cached.issue.number))
+                .collect();
+            by_age.sort_unstable();
+            for (_, number) in by_age.into_iter().take(excess) {
+                self.issues.remove(&number);
+            }
+        }
+    }
+}
+
+struct IssueStore {
+    store_dir: PathBuf,
+}
+
+impl IssueStore {
+    fn for_user() -> Result<Self, String> {
+        let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+        Ok(Self {
+            store_dir: home.join(".fluxel").join("code-host"),
+        })
+    }
+
+    fn file_for(&self, remote: &RemoteRepo) -> (PathBuf, String) {
+        let repository = format!("{}/{}", remote.host, remote.path);
+        let name = sha256_hex(repository.as_bytes());
+        (self.store_dir.join(format!("{}.json", name)), repository)
+    }
+
+    /// Load the cache; an unreadable or outdated cache starts over empty
+    fn load(&self, remote: &RemoteRepo) -> IssueCacheFile {
+        let (path, repository) = self.file_for(remote);
+        let empty = IssueCacheFile {
+            version: ISSUE_CACHE_VERSION,
+            repository,
+            ..Default::default()
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return empty;
+        };
+        match serde_json::from_str::<IssueCacheFile>(&content) {
+            Ok(file) if file.version == ISSUE_CACHE_VERSION => file,
+            Ok(_) => empty,
+            Err(e) => {
+                println!("[CodeHost] Ignoring corrupt issue cache {:?}: {}", path, e);
+                empty
+            }
+        }
+    }
+
+    fn save(&self, remote: &RemoteRepo, file: &IssueCacheFile) -> Result<(), String> {
+        let (path, _) = self.file_for(remote);
+        std::fs::create_dir_all(&self.store_dir)
+            .map_err(|e| format!("Failed to create issue cache directory: {}", e))?;
+        let json = serde_json::to_string(file).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+}
+
+/// Serializes read-modify-write cycles on issue cache files
+#[derive(Clone, Default)]
+pub struct CodeHostState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl CodeHostState {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    async fn with_cache<T: Send + 'static>(
+        &self,
+        remote: &RemoteRepo,
+        f: impl FnOnce(&IssueStore, &mut IssueCacheFile) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let lock = Arc::clone(&self.lock);
+        let remote = remote.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _guard = lock.lock().map_err(|e| e.to_string())?;
+            let store = IssueStore::for_user()?;
+            let mut file = store.load(&remote);
+            f(&store, &mut file)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Merge freshly fetched issues into the cache
+    async fn store_issues(
+        &self,
+        remote: &RemoteRepo,
+        issues: Vec<Issue>,
+        is_list: bool,
+    ) -> Result<IssueCacheFile, String> {
+        let remote_for_save = remote.clone();
+        self.with_cache(remote, move |store, file| {
+            let now = now_secs();
+            file.merge(issues, now);
+            if is_list {
+                file.list_fetched_at = now;
+            }
+            store.save(&remote_for_save, file)?;
+            Ok(std::mem::take(file))
+        })
+        .await
+    }
+}
+
+/// Cached issues matching `query`: `#12`/`12` match by number prefix, anything else
+/// matches titles containing every word. Exact number hits come first, then the
+/// most recently updated.
+fn filter_issues<'a>(
+    issues: impl Iterator<Item = &'a Issue>,
+    query: &str,
+    limit: usize,
+) -> Vec<Issue> {
+    let query = query.trim().trim_start_matches('#').to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let numeric = !query.is_empty() && query.chars().all(|c| c.is_ascii_digit());
+
+    let mut matches: Vec<&Issue> = issues
+        .filter(|issue| {
+            if numeric {
+                issue.number.to_string().starts_with(&query)
+            } else {
+                let title = issue.title.to_lowercase();
+                words.iter().all(|word| title.contains(word))
+            }
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        let exact_a = numeric && a.number.to_string() == query;
+        let exact_b = numeric && b.number.to_string() == query;
+        exact_b
+            .cmp(&exact_a)
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+    });
+    matches.into_iter().take(limit).cloned().collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Describe the code host behind the repository's `origin` remote
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn get_code_host(
+    root_path: String,
+    token: Option<String>,
+) -> Result<CodeHostInfo, String> {
+    let context = load_context(root_path, token).await?;
+    Ok(CodeHostInfo {
+        kind: context.remote.kind,
+        host: context.remote.host.clone(),
+        path: context.remote.path.clone(),
+        web_url: context.remote.web_url(),
+        authenticated: context.token.is_some(),
+        branch: context.branch,
+    })
+}
+
+/// List pull requests (merge requests on GitLab) for the repository
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `state` - `"open"` (default), `"closed"`, `"merged"`, or `"all"`
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn list_pull_requests(
+    root_path: String,
+    state: Option<String>,
+    token: Option<String>,
+) -> Result<Vec<PullRequest>, String> {
+    let context = load_context(root_path, token).await?;
+    let client = HostClient::new(&context)?;
+    let state = state.unwrap_or_else(|| "open".to_string());
+
+    match context.remote.kind {
+        HostKind::GitHub => {
+            // GitHub has no merged filter; merged PRs are closed ones with merged_at
+            let query_state = match state.as_str() {
+                "open" | "all" => state.as_str(),
+                "closed" | "merged" => "closed",
+                other => return Err(format!("Unknown pull request state '{}'", other)),
+            };
+            let pulls: Vec<GhPullRequest> = client
+                .get(&format!(
+                    "/pulls?state={}&sort=updated&direction=desc&per_page={}",
+                    query_state, LIST_PAGE_SIZE
+                ))
+                .await?;
+            Ok(pulls
+                .into_iter()
+                .map(PullRequest::from)
+                .filter(|pr| state == "all" || pr.state == state)
+                .collect())
+        }
+        HostKind::GitLab => {
+            let query_state = match state.as_str() {
+                "open" => "opened",
+                "closed" | "merged" | "all" => state.as_str(),
+                other => return Err(format!("Unknown pull request state '{}'", other)),
+            };
+            let requests: Vec<GlMergeRequest> = client
+                .get(&format!(
+                    "/merge_requests?state={}&order_by=updated_at&per_page={}",
+                    query_state, LIST_PAGE_SIZE
+                ))
+                .await?;
+            Ok(requests.into_iter().map(PullRequest::from).collect())
+        }
+    }
+}
+
+/// Fetch the CI checks and commit statuses of a pull request's head commit
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `number` - Pull request number (merge request iid on GitLab)
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn get_pull_request_checks(
+    root_path: String,
+    number: u64,
+    token: Option<String>,
+) -> Result<PullRequestChecks, String> {
+    let context = load_context(root_path, token).await?;
+    let client = HostClient::new(&context)?;
+
+    let (sha, checks) = match context.remote.kind {
+        HostKind::GitHub => {
+            let pr: GhPullRequest = client.get(&format!("/pulls/{}", number)).await?;
+            let sha = pr.head.sha;
+            let runs: GhCheckRuns = client
+                .get(&format!(
+                    "/commits/{}/check-runs?per_page={}",
+                    sha, DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            let statuses: GhCombinedStatus = client
+                .get(&format!(
+                    "/commits/{}/status?per_page={}",
+                    sha, DETAIL_PAGE_SIZE
+                ))
+                .await?;
+
+            let mut checks: Vec<StatusCheck> = runs
+                .check_runs
+                .into_iter()
+                .map(|run| StatusCheck {
+                    state: github_check_state(&run.status, run.conclusion.as_deref()),
+                    name: run.name,
+                    description: run.output.and_then(|output| output.title),
+                    url: run.html_url,
+                })
+                .collect();
+            checks.extend(statuses.statuses.into_iter().map(|status| StatusCheck {
+                state: commit_status_state(&status.state),
+                name: status.context,
+                description: status.description,
+                url: status.target_url,
+            }));
+            (sha, checks)
+        }
+        HostKind::GitLab => {
+            let mr: GlMergeRequest = client.get(&format!("/merge_requests/{}", number)).await?;
+            let sha = mr
+                .sha
+                .ok_or_else(|| format!("Merge request !{} has no commits", number))?;
+            let statuses: Vec<GlCommitStatus> = client
+                .get(&format!(
+                    "/repository/commits/{}/statuses?per_page={}",
+                    sha, DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            let checks = statuses
+                .into_iter()
+                .map(|status| StatusCheck {
+                    state: commit_status_state(&status.status),
+                    name: status.name,
+                    description: status.description,
+                    url: status.target_url,
+                })
+                .collect();
+            (sha, checks)
+        }
+    };
+
+    Ok(PullRequestChecks {
+        state: overall_state(&checks),
+        sha,
+        checks,
+    })
+}
+
+/// Open a pull request (merge request on GitLab) from the current branch.
+/// The branch must already be pushed; its upstream name is used as the source.
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `title` - Pull request title
+/// * `body` - Description (markdown)
+/// * `base` - Target branch (default: the repository's default branch)
+/// * `draft` - Open as a draft
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn create_pull_request(
+    root_path: String,
+    title: String,
+    body: Option<String>,
+    base: Option<String>,
+    draft: Option<bool>,
+    token: Option<String>,
+) -> Result<PullRequest, String> {
+    if title.trim().is_empty() {
+        return Err("Pull request title cannot be empty".to_string());
+    }
+
+    let source_root = root_path.clone();
+    let source = tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        let repo = Repository::open(&source_root).map_err(|e| e.to_string())?;
+        let head = repo.head().map_err(|e| e.to_string())?;
+        if !head.is_branch() {
+            return Err("Cannot create a pull request from a detached HEAD".to_string());
+        }
+        let name = head.shorthand().ok_or("Branch name is not valid UTF-8")?;
+        let branch = repo
+            .find_branch(name, BranchType::Local)
+            .map_err(|e| e.to_string())?;
+        let upstream = branch.upstream().map_err(|_| {
+            format!(
+                "Branch '{}' has no upstream; push it before creating a pull request",
+                name
+            )
+        })?;
+        let upstream_name = upstream
+            .name()
+            .map_err(|e| e.to_string())?
+            .ok_or("Upstream branch name is not valid UTF-8")?;
+        // `origin/feature/x` -> `feature/x`
+        Ok(upstream_name
+            .split_once('/')
+            .map(|(_, branch)| branch)
+            .unwrap_or(upstream_name)
+            .to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let context = load_context(root_path, token).await?;
+    let client = HostClient::new(&context)?;
+    client.require_token()?;
+
+    let base = match base.filter(|base| !base.is_empty()) {
+        Some(base) => base,
+        None => {
+            let repository: ApiRepository = client.get("").await?;
+            repository
+                .default_branch
+                .ok_or("Could not determine the repository's default branch")?
+        }
+    };
+    if base == source {
+        return Err(format!("Source and target branch are both '{}'", base));
+    }
+
+    let draft = draft.unwrap_or(false);
+    let body = body.unwrap_or_default();
+    println!(
+        "[CodeHost] Creating pull request {} -> {} on {}",
+        source,
+        base,
+        context.remote.web_url()
+    );
+
+    match context.remote.kind {
+        HostKind::GitHub => {
+            let request = client
+                .repo_request(Method::POST, "/pulls")
+                .json(&serde_json::json!({
+                    "title": title,
+                    "body": body,
+                    "head": source,
+                    "base": base,
+                    "draft": draft,
+                }));
+            let pr: GhPullRequest = client.send(request).await?;
+            Ok(pr.into())
+        }
+        HostKind::GitLab => {
+            // GitLab marks drafts by title prefix
+            let title = if draft && !title.starts_with("Draft:") {
+                format!("Draft: {}", title)
+            } else {
+                title
+            };
+            let request =
+                client
+                    .repo_request(Method::POST, "/merge_requests")
+                    .json(&serde_json::json!({
+                        "title": title,
+                        "description": body,
+                        "source_branch": source,
+                        "target_branch": base,
+                    }));
+            let mr: GlMergeRequest = client.send(request).await?;
+            Ok(mr.into())
+        }
+    }
+}
+
+/// Fetch inline review comments of a pull request, mapped to local files
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `number` - Pull request number (merge request iid on GitLab)
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn get_pull_request_comments(
+    root_path: String,
+    number: u64,
+    token: Option<String>,
+) -> Result<Vec<ReviewComment>, String> {
+    let context = load_context(root_path, token).await?;
+    let client = HostClient::new(&context)?;
+
+    match context.remote.kind {
+        HostKind::GitHub => {
+            let comments: Vec<GhReviewComment> = client
+                .get(&format!(
+                    "/pulls/{}/comments?per_page={}",
+                    number, DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            Ok(github_review_comments(comments, &context.workdir))
+        }
+        HostKind::GitLab => {
+            let discussions: Vec<GlDiscussion> = client
+                .get(&format!(
+                    "/merge_requests/{}/discussions?per_page={}",
+                    number, DETAIL_PAGE_SIZE
+                ))
+                .await?;
+            let url = format!("{}/-/merge_requests/{}", context.remote.web_url(), number);
+            Ok(gitlab_review_comments(discussions, &context.workdir, &url))
+        }
+    }
+}
+
+/// Search the repository's issues for `#123` autocomplete. Answers from the local
+/// cache, refetching recently updated issues when the cache is stale.
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `query` - Issue number (with or without `#`) or words of the title
+/// * `limit` - Maximum results (default: 20)
+/// * `refresh` - Refetch even if the cache is fresh
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn list_issues(
+    root_path: String,
+    query: Option<String>,
+    limit: Option<usize>,
+    refresh: Option<bool>,
+    token: Option<String>,
+    cache: State<'_, CodeHostState>,
+) -> Result<Vec<Issue>, String> {
+    let cache = cache.inner().clone();
+    let context = load_context(root_path, token).await?;
+    let remote = context.remote.clone();
+
+    let mut file = cache
+        .with_cache(&remote, |_, file| Ok(std::mem::take(file)))
+        .await?;
+    let stale = now_secs().saturating_sub(file.list_fetched_at) > ISSUE_LIST_TTL_SECS;
+    if refresh.unwrap_or(false) || stale {
+        let client = HostClient::new(&context)?;
+        match fetch_recent_issues(&client).await {
+            Ok(issues) => file = cache.store_issues(&remote, issues, true).await?,
+            // Autocomplete should keep working offline
+            Err(e) if !file.issues.is_empty() => {
+                println!("[CodeHost] Using cached issues: {}", e)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filter_issues(
+        file.issues.values().map(|cached| &cached.issue),
+        query.as_deref().unwrap_or(""),
+        limit.unwrap_or(DEFAULT_ISSUE_LIMIT),
+    ))
+}
+
+/// Get a single issue, e.g. for a hover card on an issue link. Served from the
+/// cache while fresh, and from a stale cache entry if the host can't be reached.
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `number` - Issue number (iid on GitLab)
+/// * `refresh` - Refetch even if the cached entry is fresh
+/// * `token` - Token to use instead of the stored one
+#[fluxel_command]
+pub async fn get_issue(
+    root_path: String,
+    number: u64,
+    refresh: Option<bool>,
+    token: Option<String>,
+    cache: State<'_, CodeHostState>,
+) -> Result<Issue, String> {
+    let cache = cache.inner().clone();
+    let context = load_context(root_path, token).await?;
+    let remote = context.remote.clone();
+
+    let cached = cache
+        .with_cache(&remote, move |_, file| Ok(file.issues.remove(&number)))
+        .await?;
+    if let Some(cached) = &cached {
+        let fresh = now_secs().saturating_sub(cached.fetched_at) <= ISSUE_TTL_SECS;
+        if fresh && !refresh.unwrap_or(false) {
+            return Ok(cached.issue.clone());
+        }
+    }
+
+    let client = HostClient::new(&context)?;
+    match fetch_issue(&client, number).await {
+        Ok(issue) => {
+            cache
+                .store_issues(&remote, vec![issue.clone()], false)
+                .await?;
+            Ok(issue)
+        }
+        Err(e) => match cached {
+            Some(cached) => {
+                println!("[CodeHost] Using cached issue #{}: {}", number, e);
+                Ok(cached.issue)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_hosts_from_remote_urls() {
+        assert_eq!(
+            parse_remote_url("https://github.com/ifBars/Fluxel.git"),
+            Some(("github.com".to_string(), "ifBars/Fluxel".to_string()))
+        );
+        assert_eq!(
+            parse_remote_url("git@github.com:ifBars/Fluxel.git"),
+            Some(("github.com".to_string(), "ifBars/Fluxel".to_string()))
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@gitlab.example.com:2222/group/sub/app/"),
+            Some((
+                "gitlab.example.com".to_string(),
+                "group/sub/app".to_string()
+            ))
+        );
+        assert_eq!(parse_remote_url("/srv/git/app.git"), None);
+
+        let github = detect_remote("https://user:pw@github.com/a/b", None).unwrap();
+        assert_eq!(github.kind, HostKind::GitHub);
+        assert_eq!(github.api_base(), "https://api.github.com");
+        assert_eq!(github.api_repo_path(), "/repos/a/b");
+
+        let gitlab = detect_remote("git@gitlab.com:group/sub/app.git", None).unwrap();
+        assert_eq!(gitlab.kind, HostKind::GitLab);
+        assert_eq!(gitlab.api_repo_path(), "/projects/group%2Fsub%2Fapp");
+
+        assert!(detect_remote("git@git.corp.net:team/app.git", None).is_none());
+        let configured = detect_remote("git@git.corp.net:team/app.git", Some("GitHub")).unwrap();
+        assert_eq!(configured.api_base(), "https://git.corp.net/api/v3");
+    }
+
+    #[test]
+    fn only_public_hosts_get_shared_tokens() {
+        assert_eq!(
+            detect_remote("https://github.com/a/b", None)
+                .unwrap()
+                .token_sources(),
+            ("GITHUB_TOKEN", &["GITHUB_TOKEN", "GH_TOKEN"][..])
+        );
+        assert_eq!(
+            detect_remote("git@gitlab.com:a/b.git", None)
+                .unwrap()
+                .token_sources(),
+            ("GITLAB_TOKEN", &["GITLAB_TOKEN"][..])
+        );
+
+        // Look-alike hosts are not detected by name
+        assert!(detect_remote("https://github.evil.example/a/b", None).is_none());
+        assert!(detect_remote("git@gitlab.example.com:a/b.git", None).is_none());
+
+        // and an opted-in self-hosted instance never sees the shared tokens
+        let configured = detect_remote("https://github.evil.example/a/b", Some("github")).unwrap();
+        assert_eq!(
+            configured.token_sources(),
+            (SELF_HOSTED_TOKEN_SECRET, &[][..])
+        );
+        let workspace = std::env::temp_dir().join("fluxel-code-host-no-secrets");
+        std::env::set_var("GH_TOKEN", "ghp_shared");
+        assert_eq!(resolve_token(&workspace, &configured, None), None);
+        assert_eq!(
+            resolve_token(&workspace, &configured, Some("own".to_string())),
+            Some("own".to_string())
+        );
+        std::env::remove_var("GH_TOKEN");
+    }
+
+    fn issue(number: u64, title: &str, updated_at: &str) -> Issue {
+        Issue {
+            number,
+            title: title.to_string(),
+            state: "open".to_string(),
+            author: "alice".to_string(),
+            labels: Vec::new(),
+            body: None,
+            comments: 0,
+            is_pull_request: false,
+            url: format!("https://github.com/a/b/issues/{}", number),
+            created_at: updated_at.to_string(),
+            updated_at: updated_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn filters_and_caps_cached_issues() {
+        let issues = vec![
+            issue(12, "Crash when opening large files", "2024-01-03T00:00:00Z"),
+            issue(120, "Search ignores .gitignore", "2024-01-05T00:00:00Z"),
+            issue(7, "Large file warning is wrong", "2024-01-04T00:00:00Z"),
+        ];
+
+        let numbers = |found: Vec<Issue>| found.iter().map(|i| i.number).collect::<Vec<_>>();
+        assert_eq!(
+            numbers(filter_issues(issues.iter(), "#12", 10)),
+            vec![12, 120]
+        );
+        assert_eq!(
+            numbers(filter_issues(issues.iter(), "large FILE", 10)),
+            vec![7, 12]
+        );
+        assert_eq!(numbers(filter_issues(issues.iter(), "", 2)), vec![120, 7]);
+
+        let mut file = IssueCacheFile::default();
+        file.merge(issues, 1);
+        let overflow: Vec<Issue> = (1000..1000 + MAX_CACHED_ISSUES as u64)
+            .map(|n| issue(n, "bulk", "2024-02-01T00:00:00Z"))
+            .collect();
+        file.merge(overflow, 2);
+        assert_eq!(file.issues.len(), MAX_CACHED_ISSUES);
+        assert!(!file.issues.contains_key(&12));
+        assert!(file.issues.contains_key(&1000));
+    }
+
+    #[test]
+    fn maps_review_comments_to_local_files() {
+        let workdir = Path::new("/work/app");
+        let github: Vec<GhReviewComment> = serde_json::from_value(serde_json::json!([
+            {
+                "id": 1, "in_reply_to_id": null, "user": { "login": "alice" },
+                "body": "nit", "path": "src/main.ts", "line": 12, "original_line": 10,
+                "start_line": null, "side": "RIGHT",
+                "created_at": "2024-01-01T00:00:00Z", "html_url": "https://github.com/a/b/pull/1#r1"
+            },
+            {
+                "id": 2, "in_reply_to_id": 1, "user": { "login": "bob" },
+                "body": "old", "path": "src/main.ts", "line": null, "original_line": 7,
+                "start_line": null, "side": "LEFT",
+                "created_at": "2024-01-02T00:00:00Z", "html_url": "https://github.com/a/b/pull/1#r2"
+            }
+        ]))
+        .unwrap();
+        let comments = github_review_comments(github, workdir);
+        assert_eq!(comments[0].line, Some(12));
+        assert!(!comments[0].outdated);
+        assert_eq!(
+            Path::new(&comments[0].file_path),
+            workdir.join("src/main.ts")
+        );
+        assert_eq!(comments[1].line, Some(7));
+        assert!(comments[1].outdated);
+        assert_eq!(comments[1].side, "old");
+        assert_eq!(comments[1].thread_id, Some(1));
+
+        let gitlab: Vec<GlDiscussion> = serde_json::from_value(serde_json::json!([
+            { "notes": [
+                { "id": 5, "body": "general", "author": { "username": "carol" },
+                  "created_at": "2024-01-01T00:00:00Z", "system": false, "position": null }
+            ] },
+            { "notes": [
+                { "id": 6, "body": "why?", "author": { "username": "carol" },
+                  "created_at": "2024-01-01T00:00:00Z", "system": false,
+                  "position": { "new_path": "lib/a.rs", "old_path": "lib/a.rs", "new_line": null, "old_line": 3 } },
+                { "id": 7, "body": "because", "author": { "username": "dave" },
+                  "created_at": "2024-01-01T00:00:00Z", "system": false,
+                  "position": { "new_path": "lib/a.rs", "old_path": "lib/a.rs", "new_line": null, "old_line": 3 } }
+            ] }
+        ]))
+        .unwrap();
+        let comments =
+            gitlab_review_comments(gitlab, workdir, "https://gitlab.com/g/a/-/merge_requests/4");
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author, "carol");
+        assert_eq!(comments[0].side, "old");
+        assert_eq!(comments[0].thread_id, None);
+        assert_eq!(comments[1].thread_id, Some(6));
+        assert_eq!(
+            comments[1].url,
+            "https://gitlab.com/g/a/-/merge_requests/4#note_7"
+        );
+    }
+}
@@ -0,0 +1,661 @@
+//! Commit Message Service
+//!
+//! Validates commit messages against the Conventional Commits format and drafts
+//! messages from the staged changes, either from the file list and line stats or,
+//! when an API key is supplied, by asking the MiniMax gateway to summarize the diff.
+
+use crate::commands::minimax::{minimax_chat, MinimaxMessage, MinimaxRequest, SamplingParameters};
+use crate::services::ai_audit::{AiAuditRecord, AiAuditState};
+use crate::services::ai_metrics::{AiMetricsState, AiRequestSample};
+use fluxel_macros::fluxel_command;
+use git2::{Delta, DiffOptions, Repository};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+use tauri::State;
+
+const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+/// Files listed in a drafted body before eliding the rest
+const MAX_BODY_FILES: usize = 20;
+/// Patch characters sent to the AI gateway
+const MAX_PROMPT_DIFF: usize = 12_000;
+
+fn header_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^(?P<type>[A-Za-z]+)(?:\((?P<scope>[^()\r\n]*)\))?(?P<bang>!)?: (?P<subject>.*)$",
+        )
+        .unwrap()
+    })
+}
+
+/// Rules a message is checked against; every field has a conventional default
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitConvention {
+    pub types: Vec<String>,
+    /// Allowed scopes; empty allows any
+    pub scopes: Vec<String>,
+    pub require_scope: bool,
+    /// Maximum length of the whole header line
+    pub max_subject_length: usize,
+    pub max_body_line_length: usize,
+}
+
+impl Default for CommitConvention {
+    fn default() -> Self {
+        Self {
+            types: DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+            scopes: Vec::new(),
+            require_scope: false,
+            max_subject_length: 72,
+            max_body_line_length: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitIssue {
+    /// Stable rule id, e.g. `"type-enum"` or `"subject-max-length"`
+    pub rule: &'static str,
+    pub severity: IssueSeverity,
+    /// 1-based line of the message the issue is on
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitHeader {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub scope: Option<String>,
+    /// `!` after the type/scope or a `BREAKING CHANGE:` footer
+    pub breaking: bool,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitValidation {
+    /// No error-severity issues
+    pub valid: bool,
+    pub header: Option<CommitHeader>,
+    pub issues: Vec<CommitIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedFile {
+    pub path: String,
+    /// `added`, `modified`, `deleted`, `renamed`, ...
+    pub status: &'static str,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSuggestion {
+    pub message: String,
+    /// `"heuristic"` or `"ai"`
+    pub source: &'static str,
+    pub files: Vec<StagedFile>,
+    pub additions: usize,
+    pub deletions: usize,
+    pub validation: CommitValidation,
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+pub fn validate(message: &str, convention: &CommitConvention) -> CommitValidation {
+    // Git drops comment lines from the final message; keep 1-based line numbers
+    let lines: Vec<(usize, &str)> = message
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with('#'))
+        .map(|(index, line)| (index + 1, line))
+        .collect();
+    let mut issues = Vec::new();
+    let mut issue = |rule, severity, line, message: String| {
+        issues.push(CommitIssue {
+            rule,
+            severity,
+            line,
+            message,
+        })
+    };
+
+    let (header_number, header_line) = lines.first().copied().unwrap_or((1, ""));
+    let header_line = header_line.trim_end();
+    if header_line.trim().is_empty() {
+        issue(
+            "header-empty",
+            IssueSeverity::Error,
+            header_number,
+            "Commit message is empty".to_string(),
+        );
+        return finish(None, issues);
+    }
+
+    let header = header_regex()
+        .captures(header_line)
+        .map(|caps| CommitHeader {
+            kind: caps["type"].to_string(),
+            scope: caps
+                .name("scope")
+                .map(|s| s.as_str().trim().to_string())
+                .filter(|s| !s.is_empty()),
+            breaking: caps.name("bang").is_some()
+                || lines.iter().any(|(_, line)| {
+                    line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+                }),
+            subject: caps["subject"].trim().to_string(),
+        });
+    let Some(header) = header else {
+        issue(
+            "header-format",
+            IssueSeverity::Error,
+            header_number,
+            "Header must look like `type(scope): subject`".to_string(),
+        );
+        return finish(None, issues);
+    };
+
+    if !convention.types.contains(&header.kind) {
+        issue(
+            "type-enum",
+            IssueSeverity::Error,
+            header_number,
+            format!(
+                "Unknown type `{}`; expected one of: {}",
+                header.kind,
+                convention.types.join(", ")
+            ),
+        );
+    }
+    match &header.scope {
+        None if convention.require_scope => issue(
+            "scope-empty",
+            IssueSeverity::Error,
+            header_number,
+            "A scope is required".to_string(),
+        ),
+        Some(scope)
+            if !convention.scopes.is_empty() && !convention.scopes.iter().any(|s| s == scope) =>
+        {
+            issue(
+                "scope-enum",
+                IssueSeverity::Error,
+                header_number,
+                format!(
+                    "Unknown scope `{}`; expected one of: {}",
+                    scope,
+                    convention.scopes.join(", ")
+                ),
+            )
+        }
+        _ => {}
+    }
+    if header.subject.is_empty() {
+        issue(
+            "subject-empty",
+            IssueSeverity::Error,
+            header_number,
+            "Subject must not be empty".to_string(),
+        );
+    } else if header.subject.ends_with('.') {
+        issue(
+            "subject-full-stop",
+            IssueSeverity::Warning,
+            header_number,
+            "Subject should not end with a period".to_string(),
+        );
+    }
+    let header_length = header_line.chars().count();
+    if header_length > convention.max_subject_length {
+        issue(
+            "subject-max-length",
+            IssueSeverity::Error,
+            header_number,
+            format!(
+                "Header is {} characters; the limit is {}",
+                header_length, convention.max_subject_length
+            ),
+        );
+    }
+
+    if let Some(&(number, _)) = lines.get(1).filter(|(_, line)| !line.trim().is_empty()) {
+        issue(
+            "body-leading-blank",
+            IssueSeverity::Error,
+            number,
+            "Separate the header from the body with a blank line".to_string(),
+        );
+    }
+    for &(number, line) in lines.iter().skip(1) {
+        let length = line.chars().count();
+        // Long URLs can't be wrapped
+        if length > convention.max_body_line_length && !line.contains("://") {
+            issue(
+                "body-max-line-length",
+                IssueSeverity::Warning,
+                number,
+                format!(
+                    "Line is {} characters; wrap at {}",
+                    length, convention.max_body_line_length
+                ),
+            );
+        }
+    }
+
+    finish(Some(header), issues)
+}
+
+fn finish(header: Option<CommitHeader>, issues: Vec<CommitIssue>) -> CommitValidation {
+    CommitValidation {
+        valid: !issues.iter().any(|i| i.severity == IssueSeverity::Error),
+        header,
+        issues,
+    }
+}
+
+// ============================================================================
+// Suggestion
+// ============================================================================
+
+/// Staged changes (index against HEAD) with per-file line stats, plus the patch text
+fn staged_changes(repo: &Repository) -> Result<(Vec<StagedFile>, String), String> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let mut options = DiffOptions::new();
+    let diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    let mut files = Vec::new();
+    for (i, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let (additions, deletions) = git2::Patch::from_diff(&diff, i)
+            .ok()
+            .flatten()
+            .and_then(|patch| patch.line_stats().ok())
+            .map(|(_, additions, deletions)| (additions, deletions))
+            .unwrap_or((0, 0));
+        files.push(StagedFile {
+            path,
+            status: match delta.status() {
+                Delta::Added => "added",
+                Delta::Deleted => "deleted",
+                Delta::Renamed => "renamed",
+                Delta::Copied => "copied",
+                Delta::Typechange => "typechange",
+                _ => "modified",
+            },
+            additions,
+            deletions,
+        });
+    }
+
+    let mut patch = String::new();
+    let _ = diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if patch.len() < MAX_PROMPT_DIFF {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+        }
+        true
+    });
+    Ok((files, patch))
+}
+
+/// Draft a conventional message from the staged file list alone
+pub fn draft_message(files: &[StagedFile]) -> String {
+    let kind = infer_type(files);
+    let scope = infer_scope(files);
+    let subject = match files {
+        [file] => {
+            let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+            let verb = match file.status {
+                "added" => "add",
+                "deleted" => "remove",
+                "renamed" => "rename",
+                _ => "update",
+            };
+            format!("{} {}", verb, name)
+        }
+        _ => format!("update {} files", files.len()),
+    };
+    let header = match scope.filter(|scope| scope != kind) {
+        Some(scope) => format!("{}({}): {}", kind, scope, subject),
+        None => format!("{}: {}", kind, subject),
+    };
+    if files.len() < 2 {
+        return header;
+    }
+
+    let mut body: Vec<String> = files
+        .iter()
+        .take(MAX_BODY_FILES)
+        .map(|f| {
+            format!(
+                "- {} {} (+{} -{})",
+                f.status, f.path, f.additions, f.deletions
+            )
+        })
+        .collect();
+    if files.len() > MAX_BODY_FILES {
+        body.push(format!("- and {} more", files.len() - MAX_BODY_FILES));
+    }
+    format!("{}\n\n{}", header, body.join("\n"))
+}
+
+fn infer_type(files: &[StagedFile]) -> &'static str {
+    let all = |predicate: fn(&str) -> bool| files.iter().all(|f| predicate(&f.path));
+    if files.is_empty() {
+        "chore"
+    } else if all(|p| p.ends_with(".md") || p.ends_with(".mdx") || p.starts_with("docs/")) {
+        "docs"
+    } else if all(|p| {
+        p.contains("/tests/")
+            || p.starts_with("tests/")
+            || p.contains("/__tests__/")
+            || p.contains(".test.")
+            || p.contains(".spec.")
+            || p.ends_with("_test.go")
+    }) {
+        "test"
+    } else if all(|p| {
+        p.starts_with(".github/") || p.starts_with(".gitlab-ci") || p.starts_with(".circleci/")
+    }) {
+        "ci"
+    } else if all(|p| {
+        let name = p.rsplit('/').next().unwrap_or(p);
+        matches!(
+            name,
+            "package.json"
+                | "package-lock.json"
+                | "yarn.lock"
+                | "pnpm-lock.yaml"
+                | "bun.lockb"
+                | "Cargo.toml"
+                | "Cargo.lock"
+                | "tsconfig.json"
+        ) || name.starts_with("vite.config")
+            || name.starts_with("webpack.config")
+    }) {
+        "build"
+    } else if files.iter().all(|f| f.status == "added") {
+        "feat"
+    } else {
+        "chore"
+    }
+}
+
+/// The directory shared by every file, skipping generic `src`/`lib` segments
+fn infer_scope(files: &[StagedFile]) -> Option<String> {
+    let dirs: Vec<Vec<&str>> = files
+        .iter()
+        .map(|f| {
+            let mut parts: Vec<&str> = f.path.split('/').collect();
+            parts.pop();
+            parts
+        })
+        .collect();
+    let first = dirs.first()?;
+    let common = (0..first.len())
+        .take_while(|&i| dirs.iter().all(|d| d.get(i) == first.get(i)))
+        .count();
+    first[..common]
+        .iter()
+        .rev()
+        .find(|segment| !matches!(**segment, "src" | "lib" | "app"))
+        .map(|segment| segment.to_string())
+}
+
+async fn ai_message(
+    files: &[StagedFile],
+    patch: &str,
+    api_key: String,
+    api_base: Option<String>,
+    model: Option<String>,
+) -> Result<String, String> {
+    let file_list: Vec<String> = files
+        .iter()
+        .map(|f| {
+            format!(
+                "{} {} (+{} -{})",
+                f.status, f.path, f.additions, f.deletions
+            )
+        })
+        .collect();
+    let message = |role: &str, content: String| MinimaxMessage {
+        role: role.to_string(),
+        content: Some(content.into()),
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+    };
+    let request = MinimaxRequest {
+        model: model.unwrap_or_default(),
+        messages: vec![
+            message(
+                "system",
+                "Write a git commit message in the Conventional Commits format for the staged \
+                 changes. Use `type(scope): subject` with an imperative subject under 72 \
+                 characters, then a blank line and a short body wrapped at 72 columns if the \
+                 change needs explanation. Reply with the message only."
+                    .to_string(),
+            ),
+            message(
+                "user",
+                format!("Files:\n{}\n\nDiff:\n{}", file_list.join("\n"), patch),
+            ),
+        ],
+        max_tokens: Some(512),
+        temperature: Some(0.2),
+        tools: None,
+        response_schema: None,
+        sampling: SamplingParameters::default(),
+    };
+    let response = minimax_chat(api_key, request, api_base).await?;
+    let content = response
+        .get("content")
+        .and_then(|c| c.as_str())
+        .map(|c| c.trim().trim_matches('`').trim().to_string())
+        .filter(|c| !c.is_empty())
+        .ok_or("AI gateway returned an empty message")?;
+    Ok(content)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check a commit message against Conventional Commits rules
+///
+/// # Arguments
+/// * `message` - Full commit message
+/// * `convention` - Overrides for the allowed types/scopes and length limits
+#[fluxel_command]
+pub async fn validate_commit_message(
+    message: String,
+    convention: Option<CommitConvention>,
+) -> Result<CommitValidation, String> {
+    Ok(validate(&message, &convention.unwrap_or_default()))
+}
+
+/// Draft a commit message for the staged changes
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `api_key` - MiniMax API key; when set, the draft is written by the model
+/// * `api_base` - Optional MiniMax endpoint override
+/// * `model` - Optional model override
+#[fluxel_command]
+pub async fn suggest_commit_message(
+    root_path: String,
+    api_key: Option<String>,
+    api_base: Option<String>,
+    model: Option<String>,
+    audit: State<'_, AiAuditState>,
+    metrics: State<'_, AiMetricsState>,
+) -> Result<CommitSuggestion, String> {
+    let workspace_root = root_path.clone();
+    let (files, patch) = tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(Path::new(&root_path)).map_err(|e| e.to_string())?;
+        staged_changes(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if files.is_empty() {
+        return Err("No staged changes".to_string());
+    }
+
+    let (message, source) = match api_key.filter(|key| !key.trim().is_empty()) {
+        Some(key) => {
+            let audit = audit.inner().clone();
+            let record = AiAuditRecord {
+                provider: "minimax".to_string(),
+                model: model.clone().unwrap_or_default(),
+                feature: "commitMessage".to_string(),
+                files: files.iter().map(|f| f.path.clone()).collect(),
+                snippets: vec![patch.clone()],
+                ..Default::default()
+            };
+            let recorded = tauri::async_runtime::spawn_blocking(move || {
+                audit.record(Path::new(&workspace_root), record)
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+            if let Err(e) = recorded {
+                println!("[CommitMessage] Failed to record AI audit entry: {}", e);
+            }
+
+            let started = Instant::now();
+            let result = ai_message(&files, &patch, key, api_base, model.clone()).await;
+            metrics.record(AiRequestSample::completed(
+                "minimax",
+                model.as_deref().unwrap_or_default(),
+                "commitMessage",
+                started.elapsed(),
+                result.as_deref().ok(),
+            ));
+            match result {
+                Ok(message) => (message, "ai"),
+                Err(e) => {
+                    println!("[CommitMessage] AI draft failed, using heuristic: {}", e);
+                    (draft_message(&files), "heuristic")
+                }
+            }
+        }
+        None => (draft_message(&files), "heuristic"),
+    };
+
+    Ok(CommitSuggestion {
+        validation: validate(&message, &CommitConvention::default()),
+        additions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        message,
+        source,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(validation: &CommitValidation) -> Vec<&'static str> {
+        validation.issues.iter().map(|i| i.rule).collect()
+    }
+
+    #[test]
+    fn validates_conventional_commit_rules() {
+        let convention = CommitConvention::default();
+
+        let ok = validate(
+            "feat(search)!: add trigram index\n\nBody text.\n# comment line ignored",
+            &convention,
+        );
+        assert!(ok.valid, "{:?}", ok.issues);
+        let header = ok.header.unwrap();
+        assert_eq!(header.kind, "feat");
+        assert_eq!(header.scope.as_deref(), Some("search"));
+        assert!(header.breaking);
+
+        assert_eq!(
+            rules(&validate("Add stuff", &convention)),
+            vec!["header-format"]
+        );
+        assert_eq!(
+            rules(&validate("feature: add stuff.\nbody", &convention)),
+            vec!["type-enum", "subject-full-stop", "body-leading-blank"]
+        );
+        let long = format!("fix: {}", "x".repeat(80));
+        assert_eq!(
+            rules(&validate(&long, &convention)),
+            vec!["subject-max-length"]
+        );
+
+        let strict = CommitConvention {
+            scopes: vec!["git".to_string()],
+            require_scope: true,
+            max_body_line_length: 20,
+            ..Default::default()
+        };
+        assert_eq!(rules(&validate("fix: a", &strict)), vec!["scope-empty"]);
+        let result = validate(
+            "fix(ui): a\n\nthis body line is longer than twenty\nhttps://example.com/a/very/long/url",
+            &strict,
+        );
+        assert_eq!(rules(&result), vec!["scope-enum", "body-max-line-length"]);
+        assert_eq!(result.issues[1].line, 3);
+    }
+
+    #[test]
+    fn drafts_message_from_staged_files() {
+        let file = |path: &str, status| StagedFile {
+            path: path.to_string(),
+            status,
+            additions: 3,
+            deletions: 1,
+        };
+        assert_eq!(
+            draft_message(&[file("src-tauri/src/services/git.rs", "modified")]),
+            "chore(services): update git.rs"
+        );
+        assert_eq!(
+            draft_message(&[file("docs/setup.md", "added")]),
+            "docs: add setup.md"
+        );
+        let message = draft_message(&[
+            file("src/components/search/Panel.tsx", "added"),
+            file("src/components/search/History.tsx", "added"),
+        ]);
+        assert!(message.starts_with("feat(search): update 2 files\n\n- added "));
+        assert!(validate(&message, &CommitConvention::default()).valid);
+    }
+}
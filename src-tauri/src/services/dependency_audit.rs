@@ -0,0 +1,1163 @@
+//! Dependency Audit Service
+//!
+//! Collects the dependencies declared by a workspace's manifests (`package.json`,
+//! `*.csproj`, `Cargo.toml`), looks up the installed copy of each package, and
+//! builds a license report grouped by license with copyleft flags — useful when
+//! preparing a release.
+//!
+//! Installed packages are read from `node_modules`, the NuGet global packages
+//! folder, and the Cargo registry source cache. Nothing is fetched from the network.
+
+use fluxel_macros::fluxel_command;
+use ignore::WalkBuilder;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Manifests nested deeper than this are ignored
+const MAX_MANIFEST_DEPTH: usize = 8;
+
+/// Only the head of a license file is needed to recognize it
+const LICENSE_SNIFF_BYTES: usize = 4096;
+
+/// Package ecosystem a dependency belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Npm,
+    Nuget,
+    Cargo,
+}
+
+impl Ecosystem {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::Nuget => "nuget",
+            Ecosystem::Cargo => "cargo",
+        }
+    }
+}
+
+/// A dependency as declared in a manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeclaredDependency {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    /// Version requirement as written in the manifest, if any
+    pub requirement: Option<String>,
+    /// Manifest path relative to the workspace root
+    pub manifest: String,
+    /// Only needed for development (devDependencies, dev-dependencies)
+    pub dev: bool,
+}
+
+/// How restrictive a license is for redistribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LicenseCategory {
+    Permissive,
+    Unknown,
+    WeakCopyleft,
+    StrongCopyleft,
+}
+
+impl LicenseCategory {
+    pub fn is_copyleft(self) -> bool {
+        matches!(
+            self,
+            LicenseCategory::WeakCopyleft | LicenseCategory::StrongCopyleft
+        )
+    }
+}
+
+/// A declared dependency with its installed version and license
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditedDependency {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    pub requirement: Option<String>,
+    /// Version of the installed copy, if one was found
+    pub installed_version: Option<String>,
+    /// SPDX expression (or the raw license string when it isn't SPDX)
+    pub license: Option<String>,
+    /// Where the license came from: "manifest", "license-file", or "url"
+    pub license_source: Option<String>,
+    pub category: LicenseCategory,
+    pub dev: bool,
+    /// Manifests that declare this dependency
+    pub manifests: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseGroup {
+    /// License expression, or "UNKNOWN"
+    pub license: String,
+    pub category: LicenseCategory,
+    pub packages: Vec<AuditedDependency>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseReport {
+    pub root: String,
+    pub manifests: Vec<String>,
+    /// Groups ordered most restrictive first
+    pub groups: Vec<LicenseGroup>,
+    pub total: usize,
+    pub copyleft_count: usize,
+    pub unknown_count: usize,
+    /// Human-readable flags for copyleft and unlicensed packages
+    pub flagged: Vec<String>,
+}
+
+// ============================================================================
+// Manifest Discovery
+// ============================================================================
+
+/// Find dependency manifests under `root`, skipping installed packages and build output
+pub fn find_manifests(root: &Path) -> Vec<PathBuf> {
+    let mut manifests: Vec<PathBuf> = WalkBuilder::new(root)
+        .max_depth(Some(MAX_MANIFEST_DEPTH))
+        .follow_links(false)
+        .git_ignore(true)
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !matches!(
+                    name.as_ref(),
+                    "node_modules" | "target" | "bin" | "obj" | ".git"
+                )
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name == "package.json" || name == "Cargo.toml" || name.ends_with(".csproj")
+        })
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+fn relative_display(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Collect all dependencies declared by the workspace's manifests
+pub fn collect_declared_dependencies(root: &Path) -> Vec<DeclaredDependency> {
+    let central_versions = read_central_package_versions(root);
+    let mut dependencies = Vec::new();
+
+    for manifest in find_manifests(root) {
+        let Ok(content) = std::fs::read_to_string(&manifest) else {
+            continue;
+        };
+        let display = relative_display(root, &manifest);
+        let name = manifest.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        let parsed = if name == "package.json" {
+            parse_package_json(&content)
+        } else if name == "Cargo.toml" {
+            parse_cargo_toml(&content)
+        } else {
+            parse_csproj(&content, &central_versions)
+        };
+
+        match parsed {
+            Ok(entries) => dependencies.extend(entries.into_iter().map(
+                |(name, ecosystem, requirement, dev)| DeclaredDependency {
+                    name,
+                    ecosystem,
+                    requirement,
+                    manifest: display.clone(),
+                    dev,
+                },
+            )),
+            Err(e) => println!("[DependencyAudit] Skipping {}: {}", display, e),
+        }
+    }
+
+    dependencies
+}
+
+type ParsedEntry = (String, Ecosystem, Option<String>, bool);
+
+fn parse_package_json(content: &str) -> Result<Vec<ParsedEntry>, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid package.json: {}", e))?;
+    let mut entries = Vec::new();
+
+    for (section, dev) in [
+        ("dependencies", false),
+        ("optionalDependencies", false),
+        ("devDependencies", true),
+    ] {
+        let Some(deps) = json.get(section).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        for (name, spec) in deps {
+            let spec = spec.as_str().unwrap_or("");
+            // Local packages are part of the project, not third-party code
+            if ["workspace:", "file:", "link:", "portal:"]
+                .iter()
+                .any(|prefix| spec.starts_with(prefix))
+            {
+                continue;
+            }
+            entries.push((
+                name.clone(),
+                Ecosystem::Npm,
+                (!spec.is_empty()).then(|| spec.to_string()),
+                dev,
+            ));
+        }
+    }
+
+    Ok(entries)
+}
+
+fn parse_cargo_toml(content: &str) -> Result<Vec<ParsedEntry>, String> {
+    let doc: toml::Table =
+        toml::from_str(content).map_err(|e| format!("Invalid Cargo.toml: {}", e))?;
+    let mut entries = Vec::new();
+
+    let mut push_section = |table: Option<&toml::Value>, dev: bool| {
+        let Some(table) = table.and_then(|t| t.as_table()) else {
+            return;
+        };
+        for (key, spec) in table {
+            let (name, requirement) = match spec {
+                toml::Value::String(version) => (key.clone(), Some(version.clone())),
+                toml::Value::Table(t) => {
+                    // Path and git dependencies aren't installed from a registry
+                    if t.contains_key("path") || t.contains_key("git") {
+                        continue;
+                    }
+                    // `workspace = true` inherits from [workspace.dependencies]
+                    if t.get("workspace").and_then(|v| v.as_bool()) == Some(true) {
+                        continue;
+                    }
+                    let name = t
+                        .get("package")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(key)
+                        .to_string();
+                    let version = t.get("version").and_then(|v| v.as_str()).map(String::from);
+                    (name, version)
+                }
+                _ => continue,
+            };
+            entries.push((name, Ecosystem::Cargo, requirement, dev));
+        }
+    };
+
+    push_section(doc.get("dependencies"), false);
+    push_section(doc.get("build-dependencies"), false);
+    push_section(doc.get("dev-dependencies"), true);
+    if let Some(workspace) = doc.get("workspace").and_then(|v| v.as_table()) {
+        push_section(workspace.get("dependencies"), false);
+    }
+    if let Some(targets) = doc.get("target").and_then(|v| v.as_table()) {
+        for target in targets.values() {
+            push_section(target.get("dependencies"), false);
+            push_section(target.get("build-dependencies"), false);
+            push_section(target.get("dev-dependencies"), true);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Matches `<PackageReference .../>` and `<PackageReference ...>...</PackageReference>`
+fn package_reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?s)<(PackageReference|PackageVersion)\b([^>]*?)(?:/>|>(.*?)</(?:PackageReference|PackageVersion)>)",
+        )
+        .unwrap()
+    })
+}
+
+fn xml_attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!(r#"\b{}\s*=\s*"([^"]*)""#, name);
+    Regex::new(&pattern)
+        .ok()?
+        .captures(attributes)
+        .map(|c| c[1].trim().to_string())
+}
+
+fn xml_element(body: &str, name: &str) -> Option<String> {
+    let pattern = format!(r"(?s)<{0}\b[^>]*>(.*?)</{0}>", name);
+    Regex::new(&pattern)
+        .ok()?
+        .captures(body)
+        .map(|c| c[1].trim().to_string())
+}
+
+/// Versions from `Directory.Packages.props` (central package management), keyed by
+/// lowercase package id
+fn read_central_package_versions(root: &Path) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+    let Ok(content) = std::fs::read_to_string(root.join("Directory.Packages.props")) else {
+        return versions;
+    };
+    for caps in package_reference_regex().captures_iter(&content) {
+        if &caps[1] != "PackageVersion" {
+            continue;
+        }
+        let attributes = &caps[2];
+        let body = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+        if let (Some(id), Some(version)) = (
+            xml_attribute(attributes, "Include"),
+            xml_attribute(attributes, "Version").or_else(|| xml_element(body, "Version")),
+        ) {
+            versions.insert(id.to_lowercase(), version);
+        }
+    }
+    versions
+}
+
+fn parse_csproj(
+    content: &str,
+    central_versions: &HashMap<String, String>,
+) -> Result<Vec<ParsedEntry>, String> {
+    let mut entries = Vec::new();
+
+    for caps in package_reference_regex().captures_iter(content) {
+        if &caps[1] != "PackageReference" {
+            continue;
+        }
+        let attributes = &caps[2];
+        let body = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+        let Some(id) = xml_attribute(attributes, "Include") else {
+            continue;
+        };
+        let version = xml_attribute(attributes, "Version")
+            .or_else(|| xml_element(body, "Version"))
+            .or_else(|| central_versions.get(&id.to_lowercase()).cloned());
+        // PrivateAssets="all" marks build-time-only packages (analyzers, SourceLink)
+        let private_assets = xml_attribute(attributes, "PrivateAssets")
+            .or_else(|| xml_element(body, "PrivateAssets"))
+            .is_some_and(|v| v.eq_ignore_ascii_case("all"));
+        entries.push((id, Ecosystem::Nuget, version, private_assets));
+    }
+
+    Ok(entries)
+}
+
+// ============================================================================
+// Installed Package Lookup
+// ============================================================================
+
+/// Locations of the installed package caches
+#[derive(Debug, Clone, Default)]
+pub struct PackageCaches {
+    /// NuGet global packages folder (`~/.nuget/packages`)
+    pub nuget_packages: Option<PathBuf>,
+    /// Cargo registry sources (`~/.cargo/registry/src`)
+    pub cargo_registry_src: Option<PathBuf>,
+}
+
+impl PackageCaches {
+    pub fn for_user() -> Self {
+        let home = dirs::home_dir();
+        let nuget_packages = std::env::var_os("NUGET_PACKAGES")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".nuget").join("packages")));
+        let cargo_registry_src = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".cargo")))
+            .map(|cargo| cargo.join("registry").join("src"));
+        Self {
+            nuget_packages,
+            cargo_registry_src,
+        }
+    }
+}
+
+/// What was learned about an installed package
+#[derive(Debug, Default)]
+struct InstalledPackage {
+    version: Option<String>,
+    license: Option<String>,
+    license_source: Option<&'static str>,
+}
+
+/// Find `node_modules/<name>` from `start` up to `root` (handles hoisting)
+pub(crate) fn find_node_module(start: &Path, root: &Path, name: &str) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(current) = dir {
+        let candidate = current.join("node_modules").join(name);
+        if candidate.join("package.json").is_file() {
+            return Some(candidate);
+        }
+        if current == root {
+            break;
+        }
+        dir = current.parent();
+    }
+    None
+}
+
+fn lookup_npm(manifest_dir: &Path, root: &Path, name: &str) -> Option<InstalledPackage> {
+    let package_dir = find_node_module(manifest_dir, root, name)?;
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(package_dir.join("package.json")).ok()?)
+            .ok()?;
+
+    let (license, license_source) = npm_license(&json, &package_dir).unzip();
+    Some(InstalledPackage {
+        version: json
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        license,
+        license_source,
+    })
+}
+
+/// License of an installed npm package: the one its package.json declares, or
+/// the one recognized from its license file, with where it was found
+pub(crate) fn npm_license(
+    json: &serde_json::Value,
+    package_dir: &Path,
+) -> Option<(String, &'static str)> {
+    // "license": "MIT" | { "type": "MIT" }; legacy "licenses": [{ "type": "MIT" }]
+    let license = match json.get("license") {
+        Some(serde_json::Value::String(s)) => Some(s.clone()),
+        Some(serde_json::Value::Object(o)) => {
+            o.get("type").and_then(|v| v.as_str()).map(String::from)
+        }
+        _ => json.get("licenses").and_then(|v| v.as_array()).map(|list| {
+            list.iter()
+                .filter_map(|l| {
+                    l.get("type")
+                        .and_then(|t| t.as_str())
+                        .or_else(|| l.as_str())
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ")
+        }),
+    }
+    .filter(|l| !l.trim().is_empty());
+
+    // "SEE LICENSE IN <file>" defers to a file in the package
+    match license {
+        Some(l) if !l.to_ascii_uppercase().starts_with("SEE LICENSE") => Some((l, "manifest")),
+        _ => sniff_license_dir(package_dir).map(|l| (l, "license-file")),
+    }
+}
+
+/// Pick the installed version directory: the exact requirement when present,
+/// otherwise the highest version installed
+fn pick_version_dir(parent: &Path, requirement: Option<&str>) -> Option<PathBuf> {
+    if let Some(requirement) = requirement {
+        let exact = parent.join(requirement.trim().to_lowercase());
+        if exact.is_dir() {
+            return Some(exact);
+        }
+    }
+    let mut versions: Vec<PathBuf> = std::fs::read_dir(parent)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    versions.sort_by(|a, b| {
+        compare_versions(
+            &a.file_name().unwrap_or_default().to_string_lossy(),
+            &b.file_name().unwrap_or_default().to_string_lossy(),
+        )
+    });
+    versions.pop()
+}
+
+/// Compare dotted versions numerically, falling back to string order per segment
+pub(crate) fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.split(['.', '-', '+'])
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+    };
+    let (a, b) = (split(a), split(b));
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != std::cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+fn lookup_nuget(
+    caches: &PackageCaches,
+    id: &str,
+    requirement: Option<&str>,
+) -> Option<InstalledPackage> {
+    let package_root = caches.nuget_packages.as_ref()?.join(id.to_lowercase());
+    let version_dir = pick_version_dir(&package_root, requirement)?;
+    let version = version_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string());
+
+    let nuspec = std::fs::read_dir(&version_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.extension().is_some_and(|ext| ext == "nuspec"))
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .unwrap_or_default();
+
+    let mut installed = InstalledPackage {
+        version,
+        ..Default::default()
+    };
+
+    static LICENSE_RE: OnceLock<Regex> = OnceLock::new();
+    let license_re =
+        LICENSE_RE.get_or_init(|| Regex::new(r#"(?s)<license\b([^>]*)>(.*?)</license>"#).unwrap());
+
+    if let Some(caps) = license_re.captures(&nuspec) {
+        let kind = xml_attribute(&caps[1], "type").unwrap_or_default();
+        let value = caps[2].trim();
+        if kind == "file" {
+            installed.license = sniff_license_file(&version_dir.join(value))
+                .or_else(|| sniff_license_dir(&version_dir));
+            installed.license_source = installed.license.as_ref().map(|_| "license-file");
+        } else if !value.is_empty() {
+            installed.license = Some(value.to_string());
+            installed.license_source = Some("manifest");
+        }
+    } else if let Some(url) = xml_element(&nuspec, "licenseUrl") {
+        installed.license = license_from_url(&url);
+        installed.license_source = installed.license.as_ref().map(|_| "url");
+    }
+
+    if installed.license.is_none() {
+        installed.license = sniff_license_dir(&version_dir);
+        installed.license_source = installed.license.as_ref().map(|_| "license-file");
+    }
+
+    Some(installed)
+}
+
+/// Legacy NuGet packages point at well-known license URLs instead of an SPDX id
+fn license_from_url(url: &str) -> Option<String> {
+    let lower = url.to_ascii_lowercase();
+    let known = [
+        ("licenses.nuget.org/", ""),
+        ("opensource.org/licenses/mit", "MIT"),
+        ("apache.org/licenses/license-2.0", "Apache-2.0"),
+        ("opensource.org/licenses/apache-2.0", "Apache-2.0"),
+        ("opensource.org/licenses/bsd-3-clause", "BSD-3-Clause"),
+        ("opensource.org/licenses/bsd-2-clause", "BSD-2-Clause"),
+        ("gnu.org/licenses/gpl", "GPL-3.0"),
+        ("gnu.org/licenses/lgpl", "LGPL-3.0"),
+        ("mozilla.org/mpl/2.0", "MPL-2.0"),
+    ];
+    for (needle, spdx) in known {
+        if let Some(pos) = lower.find(needle) {
+            // licenses.nuget.org/<expression>
+            if spdx.is_empty() {
+                let expression = &url[pos + needle.len()..];
+                return (!expression.is_empty()).then(|| expression.replace("%20", " "));
+            }
+            return Some(spdx.to_string());
+        }
+    }
+    None
+}
+
+/// Resolved versions from the Cargo.lock nearest to the manifest
+fn cargo_lock_versions(manifest_dir: &Path, root: &Path) -> HashMap<String, Vec<String>> {
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    let mut dir = Some(manifest_dir);
+    while let Some(current) = dir {
+        let lock = current.join("Cargo.lock");
+        if let Ok(content) = std::fs::read_to_string(&lock) {
+            if let Ok(doc) = toml::from_str::<toml::Table>(&content) {
+                for package in doc
+                    .get("package")
+                    .and_then(|p| p.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    if let (Some(name), Some(version)) = (
+                        package.get("name").and_then(|v| v.as_str()),
+                        package.get("version").and_then(|v| v.as_str()),
+                    ) {
+                        versions
+                            .entry(name.to_string())
+                            .or_default()
+                            .push(version.to_string());
+                    }
+                }
+            }
+            break;
+        }
+        if current == root {
+            break;
+        }
+        dir = current.parent();
+    }
+    versions
+}
+
+fn lookup_cargo(
+    caches: &PackageCaches,
+    name: &str,
+    locked_versions: Option<&Vec<String>>,
+) -> Option<InstalledPackage> {
+    let registry_src = caches.cargo_registry_src.as_ref()?;
+    let mut candidates: Vec<String> = locked_versions.cloned().unwrap_or_default();
+    candidates.sort_by(|a, b| compare_versions(a, b));
+    let version = candidates.pop()?;
+
+    let mut installed = InstalledPackage {
+        version: Some(version.clone()),
+        ..Default::default()
+    };
+
+    let package_dir = std::fs::read_dir(registry_src)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().join(format!("{}-{}", name, version)))
+        .find(|p| p.join("Cargo.toml").is_file());
+
+    let Some(package_dir) = package_dir else {
+        return Some(installed);
+    };
+
+    let manifest = std::fs::read_to_string(package_dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|c| toml::from_str::<toml::Table>(&c).ok());
+    let package = manifest
+        .as_ref()
+        .and_then(|m| m.get("package"))
+        .and_then(|p| p.as_table());
+
+    if let Some(license) = package
+        .and_then(|p| p.get("license"))
+        .and_then(|v| v.as_str())
+    {
+        installed.license = Some(license.to_string());
+        installed.license_source = Some("manifest");
+    } else {
+        let license_file = package
+            .and_then(|p| p.get("license-file"))
+            .and_then(|v| v.as_str())
+            .map(|f| package_dir.join(f));
+        installed.license = license_file
+            .and_then(|f| sniff_license_file(&f))
+            .or_else(|| sniff_license_dir(&package_dir));
+        installed.license_source = installed.license.as_ref().map(|_| "license-file");
+    }
+
+    Some(installed)
+}
+
+// ============================================================================
+// License Recognition
+// ============================================================================
+
+/// Recognize the license of the first LICENSE/LICENCE/COPYING file in a directory
+fn sniff_license_dir(dir: &Path) -> Option<String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_ascii_uppercase())
+                .unwrap_or_default();
+            p.is_file()
+                && (name.starts_with("LICENSE")
+                    || name.starts_with("LICENCE")
+                    || name.starts_with("COPYING"))
+        })
+        .collect();
+    files.sort();
+    files.iter().find_map(|f| sniff_license_file(f))
+}
+
+fn sniff_license_file(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(LICENSE_SNIFF_BYTES)]);
+    identify_license_text(&head).map(String::from)
+}
+
+/// Identify common license texts by their distinctive phrases
+fn identify_license_text(text: &str) -> Option<&'static str> {
+    let upper = text.to_ascii_uppercase();
+    let normalized = upper.split_whitespace().collect::<Vec<_>>().join(" ");
+    let has = |needle: &str| normalized.contains(needle);
+    let v3 = has("VERSION 3");
+
+    let id = if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        "AGPL-3.0"
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") {
+        if v3 {
+            "LGPL-3.0"
+        } else {
+            "LGPL-2.1"
+        }
+    } else if has("GNU GENERAL PUBLIC LICENSE") {
+        if v3 {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        }
+    } else if has("MOZILLA PUBLIC LICENSE") {
+        "MPL-2.0"
+    } else if has("ECLIPSE PUBLIC LICENSE") {
+        if has("VERSION 2.0") {
+            "EPL-2.0"
+        } else {
+            "EPL-1.0"
+        }
+    } else if has("APACHE LICENSE") && has("VERSION 2.0") {
+        "Apache-2.0"
+    } else if has("PERMISSION IS HEREBY GRANTED, FREE OF CHARGE") {
+        "MIT"
+    } else if has("PERMISSION TO USE, COPY, MODIFY, AND/OR DISTRIBUTE") {
+        "ISC"
+    } else if has("REDISTRIBUTION AND USE IN SOURCE AND BINARY FORMS") {
+        if has("NEITHER THE NAME") || has("ENDORSE OR PROMOTE") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if has("THIS IS FREE AND UNENCUMBERED SOFTWARE") {
+        "Unlicense"
+    } else if has("BOOST SOFTWARE LICENSE") {
+        "BSL-1.0"
+    } else {
+        return None;
+    };
+    Some(id)
+}
+
+fn classify_license_id(id: &str) -> LicenseCategory {
+    let id = id
+        .trim()
+        .trim_end_matches('+')
+        .trim_end_matches("-or-later")
+        .trim_end_matches("-only")
+        .to_ascii_uppercase();
+
+    const STRONG: &[&str] = &["AGPL", "GPL", "SSPL", "OSL", "EUPL", "CC-BY-SA", "RPL"];
+    const WEAK: &[&str] = &["LGPL", "MPL", "EPL", "CDDL", "CPL", "MS-RL", "APSL"];
+    const PERMISSIVE: &[&str] = &[
+        "MIT",
+        "ISC",
+        "BSD",
+        "0BSD",
+        "APACHE",
+        "UNLICENSE",
+        "ZLIB",
+        "CC0",
+        "CC-BY-",
+        "BSL-1.0",
+        "PYTHON",
+        "PSF",
+        "MS-PL",
+        "POSTGRESQL",
+        "WTFPL",
+        "BLUEOAK",
+        "UNICODE",
+        "X11",
+        "ARTISTIC-2.0",
+        "PUBLIC DOMAIN",
+    ];
+
+    // LGPL must be checked before GPL since it shares the suffix
+    if WEAK.iter().any(|p| id.starts_with(p)) {
+        LicenseCategory::WeakCopyleft
+    } else if STRONG.iter().any(|p| id.starts_with(p)) {
+        LicenseCategory::StrongCopyleft
+    } else if PERMISSIVE.iter().any(|p| id.starts_with(p)) {
+        LicenseCategory::Permissive
+    } else {
+        LicenseCategory::Unknown
+    }
+}
+
+/// Classify an SPDX expression. `OR` lets the consumer choose the least
+/// restrictive option; `AND` requires honoring the most restrictive one.
+pub fn classify_license(expression: &str) -> LicenseCategory {
+    let cleaned = expression.replace(['(', ')'], " ");
+    // npm/cargo legacy separators ("MIT/Apache-2.0")
+    let cleaned = cleaned.replace('/', " OR ");
+    let alternatives: Vec<LicenseCategory> = cleaned
+        .split(" OR ")
+        .flat_map(|alt| alt.split(" or "))
+        .map(|alternative| {
+            alternative
+                .split(" AND ")
+                .flat_map(|part| part.split(" and "))
+                .filter_map(|part| {
+                    // "Apache-2.0 WITH LLVM-exception": the exception only adds permissions
+                    let id = part.split(" WITH ").next().unwrap_or("").trim();
+                    (!id.is_empty()).then(|| classify_license_id(id))
+                })
+                .max()
+                .unwrap_or(LicenseCategory::Unknown)
+        })
+        .collect();
+
+    alternatives
+        .into_iter()
+        .min()
+        .unwrap_or(LicenseCategory::Unknown)
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+pub fn audit_licenses_in(root: &Path, caches: &PackageCaches, include_dev: bool) -> LicenseReport {
+    let declared = collect_declared_dependencies(root);
+    let manifests: Vec<String> = {
+        let mut list: Vec<String> = declared.iter().map(|d| d.manifest.clone()).collect();
+        list.sort();
+        list.dedup();
+        list
+    };
+
+    let mut lock_cache: HashMap<PathBuf, HashMap<String, Vec<String>>> = HashMap::new();
+    // (ecosystem, name, version) -> audited entry
+    let mut audited: BTreeMap<(Ecosystem, String, String), AuditedDependency> = BTreeMap::new();
+
+    for dependency in declared {
+        if dependency.dev && !include_dev {
+            continue;
+        }
+        let manifest_dir = root
+            .join(&dependency.manifest)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| root.to_path_buf());
+
+        let installed = match dependency.ecosystem {
+            Ecosystem::Npm => lookup_npm(&manifest_dir, root, &dependency.name),
+            Ecosystem::Nuget => {
+                lookup_nuget(caches, &dependency.name, dependency.requirement.as_deref())
+            }
+            Ecosystem::Cargo => {
+                let locked = lock_cache
+                    .entry(manifest_dir.clone())
+                    .or_insert_with(|| cargo_lock_versions(&manifest_dir, root));
+                lookup_cargo(caches, &dependency.name, locked.get(&dependency.name))
+            }
+        }
+        .unwrap_or_default();
+
+        let version_key = installed
+            .version
+            .clone()
+            .or_else(|| dependency.requirement.clone())
+            .unwrap_or_default();
+        let key = (dependency.ecosystem, dependency.name.clone(), version_key);
+
+        let entry = audited.entry(key).or_insert_with(|| AuditedDependency {
+            name: dependency.name.clone(),
+            ecosystem: dependency.ecosystem,
+            requirement: dependency.requirement.clone(),
+            installed_version: installed.version.clone(),
+            category: installed
+                .license
+                .as_deref()
+                .map(classify_license)
+                .unwrap_or(LicenseCategory::Unknown),
+            license: installed.license.clone(),
+            license_source: installed.license_source.map(String::from),
+            dev: dependency.dev,
+            manifests: Vec::new(),
+        });
+        // A package is only dev-only if every manifest declares it as such
+        entry.dev &= dependency.dev;
+        if !entry.manifests.contains(&dependency.manifest) {
+            entry.manifests.push(dependency.manifest);
+        }
+    }
+
+    let total = audited.len();
+    let mut groups: BTreeMap<(std::cmp::Reverse<LicenseCategory>, String), Vec<AuditedDependency>> =
+        BTreeMap::new();
+    let mut flagged = Vec::new();
+    let mut copyleft_count = 0;
+    let mut unknown_count = 0;
+
+    for dependency in audited.into_values() {
+        let label = dependency
+            .license
+            .clone()
+            .unwrap_or_else(|| "UNKNOWN".to_string());
+        let version = dependency
+            .installed_version
+            .as_deref()
+            .or(dependency.requirement.as_deref())
+            .unwrap_or("?");
+
+        if dependency.category.is_copyleft() {
+            copyleft_count += 1;
+            let strength = if dependency.category == LicenseCategory::StrongCopyleft {
+                "strong copyleft"
+            } else {
+                "weak copyleft"
+            };
+            flagged.push(format!(
+                "{}:{}@{} is licensed under {} ({})",
+                dependency.ecosystem.as_str(),
+                dependency.name,
+                version,
+                label,
+                strength
+            ));
+        } else if dependency.category == LicenseCategory::Unknown {
+            unknown_count += 1;
+            let reason = if dependency.installed_version.is_none() {
+                "not installed"
+            } else if dependency.license.is_none() {
+                "no license found"
+            } else {
+                "unrecognized license"
+            };
+            flagged.push(format!(
+                "{}:{}@{} has an unknown license ({})",
+                dependency.ecosystem.as_str(),
+                dependency.name,
+                version,
+                reason
+            ));
+        }
+
+        groups
+            .entry((std::cmp::Reverse(dependency.category), label))
+            .or_default()
+            .push(dependency);
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(
+            |((std::cmp::Reverse(category), license), packages)| LicenseGroup {
+                license,
+                category,
+                packages,
+            },
+        )
+        .collect();
+
+    LicenseReport {
+        root: root.to_string_lossy().to_string(),
+        manifests,
+        groups,
+        total,
+        copyleft_count,
+        unknown_count,
+        flagged,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Build a license report for the dependencies of the open workspace.
+///
+/// # Arguments
+/// * `workspace_root` - Workspace root directory
+/// * `include_dev` - Include development-only dependencies (default: false)
+#[fluxel_command]
+pub async fn audit_licenses(
+    workspace_root: String,
+    include_dev: Option<bool>,
+) -> Result<LicenseReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&workspace_root);
+        if !root.is_dir() {
+            return Err(format!("Workspace root not found: {}", workspace_root));
+        }
+        let report = audit_licenses_in(
+            &root,
+            &PackageCaches::for_user(),
+            include_dev.unwrap_or(false),
+        );
+        println!(
+            "[DependencyAudit] {} packages audited, {} copyleft, {} unknown",
+            report.total, report.copyleft_count, report.unknown_count
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_root(test_name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root =
+            std::env::temp_dir().join(format!("fluxel-dependency-audit-{test_name}-{unique}"));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn classifies_spdx_expressions() {
+        assert_eq!(classify_license("MIT"), LicenseCategory::Permissive);
+        assert_eq!(
+            classify_license("GPL-3.0-or-later"),
+            LicenseCategory::StrongCopyleft
+        );
+        assert_eq!(classify_license("LGPL-2.1"), LicenseCategory::WeakCopyleft);
+        assert_eq!(
+            classify_license("(MIT OR GPL-3.0)"),
+            LicenseCategory::Permissive
+        );
+        assert_eq!(
+            classify_license("MIT AND MPL-2.0"),
+            LicenseCategory::WeakCopyleft
+        );
+        assert_eq!(
+            classify_license("MIT/Apache-2.0"),
+            LicenseCategory::Permissive
+        );
+        assert_eq!(
+            classify_license("Apache-2.0 WITH LLVM-exception"),
+            LicenseCategory::Permissive
+        );
+        assert_eq!(classify_license("Proprietary"), LicenseCategory::Unknown);
+    }
+
+    #[test]
+    fn parses_csproj_package_references() {
+        let central = HashMap::from([("central.pkg".to_string(), "3.0.0".to_string())]);
+        let entries = parse_csproj(
+            r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+    <PackageReference Include="Central.Pkg" />
+    <PackageReference Include="Microsoft.SourceLink.GitHub">
+      <Version>8.0.0</Version>
+      <PrivateAssets>all</PrivateAssets>
+    </PackageReference>
+  </ItemGroup>
+</Project>"#,
+            &central,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, "Newtonsoft.Json");
+        assert_eq!(entries[0].2.as_deref(), Some("13.0.3"));
+        assert_eq!(entries[1].2.as_deref(), Some("3.0.0"));
+        assert_eq!(entries[2].2.as_deref(), Some("8.0.0"));
+        assert!(entries[2].3);
+    }
+
+    #[test]
+    fn builds_grouped_report_with_copyleft_flags() {
+        let root = temp_root("report");
+        write(
+            &root.join("package.json"),
+            r#"{
+  "dependencies": { "left-pad": "^1.3.0", "gpl-lib": "2.0.0", "local": "workspace:*" },
+  "devDependencies": { "dev-tool": "1.0.0" }
+}"#,
+        );
+        write(
+            &root.join("node_modules/left-pad/package.json"),
+            r#"{ "name": "left-pad", "version": "1.3.0", "license": "WTFPL" }"#,
+        );
+        write(
+            &root.join("node_modules/gpl-lib/package.json"),
+            r#"{ "name": "gpl-lib", "version": "2.0.0" }"#,
+        );
+        write(
+            &root.join("node_modules/gpl-lib/LICENSE"),
+            "GNU GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007\n",
+        );
+        write(
+            &root.join("native/Cargo.toml"),
+            r#"[package]
+name = "native"
+version = "0.1.0"
+
+[dependencies]
+serde = "1"
+local = { path = "../local" }
+"#,
+        );
+        write(
+            &root.join("native/Cargo.lock"),
+            r#"[[package]]
+name = "serde"
+version = "1.0.200"
+"#,
+        );
+        let registry = temp_root("registry");
+        write(
+            &registry.join("index.crates.io-abc/serde-1.0.200/Cargo.toml"),
+            "[package]\nname = \"serde\"\nversion = \"1.0.200\"\nlicense = \"MIT OR Apache-2.0\"\n",
+        );
+
+        let caches = PackageCaches {
+            nuget_packages: None,
+            cargo_registry_src: Some(registry),
+        };
+        let report = audit_licenses_in(&root, &caches, false);
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.copyleft_count, 1);
+        assert_eq!(report.unknown_count, 0);
+        assert_eq!(report.groups[0].license, "GPL-3.0");
+        assert_eq!(report.groups[0].category, LicenseCategory::StrongCopyleft);
+        assert_eq!(
+            report.groups[0].packages[0].license_source.as_deref(),
+            Some("license-file")
+        );
+        assert!(report.flagged[0].contains("npm:gpl-lib@2.0.0"));
+        assert!(report
+            .groups
+            .iter()
+            .any(|g| g.license == "MIT OR Apache-2.0"
+                && g.packages[0].installed_version.as_deref() == Some("1.0.200")));
+
+        let with_dev = audit_licenses_in(&root, &caches, true);
+        assert_eq!(with_dev.total, 4);
+        assert_eq!(with_dev.unknown_count, 1);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(caches.cargo_registry_src.unwrap());
+    }
+}
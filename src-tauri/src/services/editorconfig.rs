@@ -0,0 +1,473 @@
+//! EditorConfig Resolver
+//!
+//! Resolves effective `.editorconfig` properties for a file following the
+//! EditorConfig specification: files are collected walking up from the file's
+//! directory until one declares `root = true`, sections are matched with
+//! EditorConfig globs, and closer files / later sections take precedence.
+
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+/// Effective properties for a file. Well-known properties are typed;
+/// everything (including unknown keys) is also available in `properties`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EditorConfigResult {
+    /// "space" or "tab"
+    pub indent_style: Option<String>,
+    pub indent_size: Option<u32>,
+    pub tab_width: Option<u32>,
+    /// "lf", "crlf", or "cr"
+    pub end_of_line: Option<String>,
+    /// "utf-8", "utf-8-bom", "latin1", "utf-16be", or "utf-16le"
+    pub charset: Option<String>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub max_line_length: Option<u32>,
+    /// All resolved key/value pairs (keys lowercased)
+    pub properties: BTreeMap<String, String>,
+    /// `.editorconfig` files that were consulted, closest first
+    pub sources: Vec<String>,
+}
+
+/// A parsed `.editorconfig` file
+#[derive(Debug, Default)]
+struct EditorConfigFile {
+    root: bool,
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+fn parse_editorconfig(content: &str) -> EditorConfigFile {
+    let mut file = EditorConfigFile::default();
+    let mut current: Option<(String, Vec<(String, String)>)> = None;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            if let Some(section) = current.take() {
+                file.sections.push(section);
+            }
+            current = Some((line[1..line.len() - 1].to_string(), Vec::new()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(['=', ':']) else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+
+        match current.as_mut() {
+            Some((_, props)) => props.push((key, value)),
+            // Preamble: only `root` is meaningful
+            None if key == "root" => file.root = value.eq_ignore_ascii_case("true"),
+            None => {}
+        }
+    }
+
+    if let Some(section) = current {
+        file.sections.push(section);
+    }
+    file
+}
+
+// ============================================================================
+// Glob Matching
+// ============================================================================
+
+/// A compiled section glob. Numeric ranges (`{1..3}`) are checked after the regex match.
+struct SectionGlob {
+    regex: Regex,
+    ranges: Vec<(i64, i64)>,
+}
+
+impl SectionGlob {
+    fn is_match(&self, path: &str) -> bool {
+        let Some(caps) = self.regex.captures(path) else {
+            return false;
+        };
+        self.ranges.iter().enumerate().all(|(i, (lo, hi))| {
+            caps.get(i + 1)
+                .and_then(|m| m.as_str().parse::<i64>().ok())
+                .map(|n| n >= *lo && n <= *hi)
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// Find the index of the `}` matching the `{` at `open`
+fn matching_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split brace contents on top-level commas
+fn split_alternatives(chars: &[char]) -> Vec<Vec<char>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                parts.last_mut().unwrap().extend([c, chars[i + 1]]);
+                i += 2;
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(Vec::new());
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(c);
+        i += 1;
+    }
+    parts
+}
+
+fn translate_glob(chars: &[char], out: &mut String, ranges: &mut Vec<(i64, i64)>) {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                out.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 2;
+                continue;
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                // `**/` also matches zero directories
+                let at_segment_start = i == 0 || chars[i - 1] == '/';
+                if at_segment_start && chars.get(i + 2) == Some(&'/') {
+                    out.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    out.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                if let Some(close) = chars[i + 1..].iter().position(|&c| c == ']') {
+                    let class: String = chars[i + 1..i + 1 + close].iter().collect();
+                    let (negated, body) = match class.strip_prefix('!') {
+                        Some(rest) => (true, rest.to_string()),
+                        None => (false, class),
+                    };
+                    // Character classes never match a path separator
+                    if !body.contains('/') {
+                        out.push('[');
+                        if negated {
+                            out.push('^');
+                        }
+                        out.push_str(&body.replace('\\', "\\\\").replace('[', "\\["));
+                        out.push(']');
+                        i += close + 2;
+                        continue;
+                    }
+                }
+                out.push_str("\\[");
+            }
+            '{' => {
+                if let Some(close) = matching_brace(chars, i) {
+                    let inner = &chars[i + 1..close];
+                    let inner_str: String = inner.iter().collect();
+
+                    // Numeric range {num1..num2}
+                    if let Some((lo, hi)) = inner_str.split_once("..") {
+                        if let (Ok(lo), Ok(hi)) = (lo.parse::<i64>(), hi.parse::<i64>()) {
+                            out.push_str("([+-]?\\d+)");
+                            ranges.push((lo.min(hi), lo.max(hi)));
+                            i = close + 1;
+                            continue;
+                        }
+                    }
+
+                    let alternatives = split_alternatives(inner);
+                    if alternatives.len() > 1 {
+                        out.push_str("(?:");
+                        for (idx, alt) in alternatives.iter().enumerate() {
+                            if idx > 0 {
+                                out.push('|');
+                            }
+                            translate_glob(alt, out, ranges);
+                        }
+                        out.push(')');
+                    } else {
+                        // A single alternative is literal: {foo} matches "{foo}"
+                        out.push_str("\\{");
+                        translate_glob(inner, out, ranges);
+                        out.push_str("\\}");
+                    }
+                    i = close + 1;
+                    continue;
+                }
+                out.push_str("\\{");
+            }
+            _ => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+}
+
+/// Compile a section name relative to the directory containing its `.editorconfig`.
+fn compile_section(section: &str, base_dir: &str) -> Option<SectionGlob> {
+    let pattern = section.trim();
+    // Globs without a slash match the file name at any depth
+    let pattern = if pattern.contains('/') {
+        pattern.strip_prefix('/').unwrap_or(pattern).to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut body = String::new();
+    let mut ranges = Vec::new();
+    translate_glob(&chars, &mut body, &mut ranges);
+
+    let base = base_dir.trim_end_matches('/');
+    let regex = Regex::new(&format!("^{}/{}$", regex::escape(base), body)).ok()?;
+    Some(SectionGlob { regex, ranges })
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Collect `.editorconfig` files from the file's directory upwards, closest first
+fn collect_files(file_path: &Path) -> Vec<(PathBuf, EditorConfigFile)> {
+    let mut files = Vec::new();
+    let mut dir = file_path.parent();
+    while let Some(current) = dir {
+        let candidate = current.join(EDITORCONFIG_FILE);
+        if let Ok(content) = std::fs::read_to_string(&candidate) {
+            let parsed = parse_editorconfig(&content);
+            let is_root = parsed.root;
+            files.push((current.to_path_buf(), parsed));
+            if is_root {
+                break;
+            }
+        }
+        dir = current.parent();
+    }
+    files
+}
+
+fn parse_u32(properties: &BTreeMap<String, String>, key: &str) -> Option<u32> {
+    properties.get(key).and_then(|v| v.parse().ok())
+}
+
+fn parse_bool(properties: &BTreeMap<String, String>, key: &str) -> Option<bool> {
+    match properties.get(key).map(String::as_str) {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    }
+}
+
+pub fn resolve(file_path: &Path) -> EditorConfigResult {
+    let absolute = if file_path.is_absolute() {
+        file_path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(file_path))
+            .unwrap_or_else(|_| file_path.to_path_buf())
+    };
+    let target = normalize(&absolute);
+
+    let files = collect_files(&absolute);
+    let mut properties: BTreeMap<String, String> = BTreeMap::new();
+
+    // Apply farthest first so closer files and later sections win
+    for (dir, file) in files.iter().rev() {
+        let base = normalize(dir);
+        for (section, props) in &file.sections {
+            let Some(glob) = compile_section(section, &base) else {
+                continue;
+            };
+            if !glob.is_match(&target) {
+                continue;
+            }
+            for (key, value) in props {
+                properties.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    // Values are case-insensitive for known properties; `unset` removes a property
+    for (key, value) in properties.iter_mut() {
+        if matches!(
+            key.as_str(),
+            "indent_style"
+                | "indent_size"
+                | "tab_width"
+                | "end_of_line"
+                | "charset"
+                | "trim_trailing_whitespace"
+                | "insert_final_newline"
+                | "max_line_length"
+        ) {
+            *value = value.to_ascii_lowercase();
+        }
+    }
+    properties.retain(|_, v| !v.eq_ignore_ascii_case("unset"));
+
+    // Spec defaults between indent_style, indent_size, and tab_width
+    if properties.get("indent_style").map(String::as_str) == Some("tab")
+        && !properties.contains_key("indent_size")
+    {
+        properties.insert("indent_size".to_string(), "tab".to_string());
+    }
+    if let Some(size) = properties.get("indent_size").cloned() {
+        if size != "tab" && !properties.contains_key("tab_width") {
+            properties.insert("tab_width".to_string(), size);
+        }
+    }
+    if properties.get("indent_size").map(String::as_str) == Some("tab") {
+        if let Some(width) = properties.get("tab_width").cloned() {
+            properties.insert("indent_size".to_string(), width);
+        }
+    }
+
+    EditorConfigResult {
+        indent_style: properties.get("indent_style").cloned(),
+        indent_size: parse_u32(&properties, "indent_size"),
+        tab_width: parse_u32(&properties, "tab_width"),
+        end_of_line: properties.get("end_of_line").cloned(),
+        charset: properties.get("charset").cloned(),
+        trim_trailing_whitespace: parse_bool(&properties, "trim_trailing_whitespace"),
+        insert_final_newline: parse_bool(&properties, "insert_final_newline"),
+        max_line_length: parse_u32(&properties, "max_line_length"),
+        sources: files
+            .iter()
+            .map(|(dir, _)| normalize(&dir.join(EDITORCONFIG_FILE)))
+            .collect(),
+        properties,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Resolve the effective EditorConfig properties for a file
+#[fluxel_command]
+pub async fn resolve_editorconfig(path: String) -> Result<EditorConfigResult, String> {
+    tauri::async_runtime::spawn_blocking(move || resolve(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn create_temp_workspace(test_name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("fluxel-editorconfig-{test_name}-{unique}"));
+        fs::create_dir_all(&path).expect("temporary workspace should be created");
+        path
+    }
+
+    fn glob_matches(section: &str, path: &str) -> bool {
+        compile_section(section, "/repo")
+            .map(|g| g.is_match(&format!("/repo/{}", path)))
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn matches_editorconfig_globs() {
+        assert!(glob_matches("*", "src/main.rs"));
+        assert!(glob_matches("*.{js,ts}", "src/deep/app.ts"));
+        assert!(!glob_matches("*.{js,ts}", "src/app.rs"));
+        assert!(glob_matches("/src/*.rs", "src/lib.rs"));
+        assert!(!glob_matches("/src/*.rs", "src/nested/lib.rs"));
+        assert!(glob_matches("src/**/*.rs", "src/a/b/lib.rs"));
+        assert!(glob_matches("src/**/*.rs", "src/lib.rs"));
+        assert!(glob_matches("file{1..3}.txt", "file2.txt"));
+        assert!(!glob_matches("file{1..3}.txt", "file4.txt"));
+        assert!(glob_matches("[Mm]akefile", "Makefile"));
+        assert!(glob_matches("[!a]bc", "xbc"));
+    }
+
+    #[test]
+    fn merges_files_with_closest_precedence() {
+        let root = create_temp_workspace("merge");
+        fs::create_dir_all(root.join("pkg").join("src")).unwrap();
+        fs::write(
+            root.join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\nend_of_line = LF\ninsert_final_newline = true\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("pkg").join(".editorconfig"),
+            "[*.ts]\nindent_size = 2\n\n[src/*.ts]\ninsert_final_newline = unset\n",
+        )
+        .unwrap();
+        let file = root.join("pkg").join("src").join("index.ts");
+
+        let result = resolve(&file);
+        assert_eq!(result.indent_style.as_deref(), Some("space"));
+        assert_eq!(result.indent_size, Some(2));
+        assert_eq!(result.tab_width, Some(2));
+        assert_eq!(result.end_of_line.as_deref(), Some("lf"));
+        assert_eq!(result.insert_final_newline, None);
+        assert_eq!(result.sources.len(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn tab_indent_defaults_to_tab_width() {
+        let root = create_temp_workspace("tabs");
+        fs::write(
+            root.join(".editorconfig"),
+            "root = true\n[Makefile]\nindent_style = tab\ntab_width = 8\n",
+        )
+        .unwrap();
+
+        let result = resolve(&root.join("Makefile"));
+        assert_eq!(result.indent_style.as_deref(), Some("tab"));
+        assert_eq!(result.indent_size, Some(8));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,157 @@
+//! File Guard Service
+//!
+//! Tracks the files with unsaved editor changes (registered by the frontend via
+//! `set_dirty_files`) and refuses destructive file operations and git checkouts
+//! that would overwrite, move, or delete them.
+//!
+//! A refusal is returned as the command's error string holding a JSON
+//! [`DirtyFilesError`], so the frontend can offer "save all" or retry with `force`.
+
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Structured refusal of an operation that would clobber unsaved changes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirtyFilesError {
+    /// Always `"dirtyFiles"`, to tell this error apart from plain messages
+    pub kind: &'static str,
+    pub operation: String,
+    /// Dirty files the operation would affect
+    pub paths: Vec<String>,
+    pub message: String,
+}
+
+impl DirtyFilesError {
+    fn into_error_string(self) -> String {
+        serde_json::to_string(&self).unwrap_or(self.message)
+    }
+}
+
+/// Files with unsaved editor changes
+#[derive(Clone, Default)]
+pub struct DirtyFilesState {
+    dirty: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl DirtyFilesState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn set(&self, paths: Vec<String>) {
+        *self.dirty.lock().unwrap() = paths.iter().map(|p| normalize(Path::new(p))).collect();
+    }
+
+    /// Dirty files equal to, or inside, any of `affected`
+    pub fn dirty_within(&self, affected: &[PathBuf]) -> Vec<String> {
+        let affected: Vec<PathBuf> = affected.iter().map(|p| normalize(p)).collect();
+        let dirty = self.dirty.lock().unwrap();
+        let mut paths: Vec<String> = dirty
+            .iter()
+            .filter(|file| affected.iter().any(|path| file.starts_with(path)))
+            .map(|file| file.to_string_lossy().replace('\\', "/"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Refuse `operation` if it touches dirty files, unless `force` is set
+    pub fn guard(&self, operation: &str, affected: &[PathBuf], force: bool) -> Result<(), String> {
+        if force {
+            return Ok(());
+        }
+        let paths = self.dirty_within(affected);
+        if paths.is_empty() {
+            return Ok(());
+        }
+        println!(
+            "[FileGuard] Refused {}: {} file(s) have unsaved changes",
+            operation,
+            paths.len()
+        );
+        Err(DirtyFilesError {
+            kind: "dirtyFiles",
+            operation: operation.to_string(),
+            message: format!(
+                "{} would overwrite unsaved changes in {}",
+                operation,
+                paths.join(", ")
+            ),
+            paths,
+        }
+        .into_error_string())
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    crate::services::import_rewrite::normalize(path)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Replace the set of files with unsaved editor changes
+#[fluxel_command]
+pub async fn set_dirty_files(
+    paths: Vec<String>,
+    state: State<'_, DirtyFilesState>,
+) -> Result<(), String> {
+    state.set(paths);
+    Ok(())
+}
+
+/// Dirty files an operation on `paths` (files or directories) would affect
+#[fluxel_command]
+pub async fn check_dirty_files(
+    paths: Vec<String>,
+    state: State<'_, DirtyFilesState>,
+) -> Result<Vec<String>, String> {
+    let affected: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    Ok(state.dirty_within(&affected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_operations_touching_dirty_files() {
+        let state = DirtyFilesState::new();
+        state.set(vec![
+            "/repo/src/a.ts".to_string(),
+            "/repo/src/nested/../b.ts".to_string(),
+        ]);
+
+        assert!(state
+            .guard("rename_path", &[PathBuf::from("/repo/lib")], false)
+            .is_ok());
+        assert!(state
+            .guard("rename_path", &[PathBuf::from("/repo/src/ab.ts")], false)
+            .is_ok());
+
+        let error = state
+            .guard("git_pull", &[PathBuf::from("/repo/src")], false)
+            .unwrap_err();
+        let error: serde_json::Value = serde_json::from_str(&error).unwrap();
+        assert_eq!(error["kind"], "dirtyFiles");
+        assert_eq!(error["operation"], "git_pull");
+        assert_eq!(
+            error["paths"],
+            serde_json::json!(["/repo/src/a.ts", "/repo/src/b.ts"])
+        );
+
+        assert!(state
+            .guard("git_pull", &[PathBuf::from("/repo")], true)
+            .is_ok());
+        state.set(Vec::new());
+        assert!(state
+            .guard("git_pull", &[PathBuf::from("/repo")], false)
+            .is_ok());
+    }
+}
@@ -0,0 +1,385 @@
+//! File Templates Service
+//!
+//! Boilerplate for new files, stored in `~/.fluxel/file-templates.json` and
+//! `<workspace>/.fluxel/file-templates.json`. A template applies to files with
+//! an extension (`cs`, `d.ts`) or files matching a glob (`*.test.ts` against the
+//! file name, `src/**/*.rs` against the path relative to the workspace).
+//! Workspace templates are tried before user templates, each in file order, and
+//! the first match is used.
+//!
+//! Contents use the `{{variable}}` placeholders of project templates:
+//! `filename`, `name` (the file name without its extension), `extension`,
+//! `namespace` (the workspace folder and the folders below it, joined with dots),
+//! `date`, `year`, and `author` and `email` from git config.
+
+use crate::services::scaffold::render;
+use chrono::{Datelike, Local};
+use fluxel_macros::fluxel_command;
+use git2::{Config, Repository};
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATES_FILE: &str = "file-templates.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTemplate {
+    /// Extension without the leading dot, or a glob when it contains `*`, `?`,
+    /// `[` or `{`; globs with a `/` match the workspace-relative path
+    pub pattern: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TemplatesFile {
+    #[serde(default)]
+    templates: Vec<FileTemplate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateScope {
+    User,
+    Workspace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTemplateSet {
+    pub workspace: Vec<FileTemplate>,
+    pub user: Vec<FileTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedFileTemplate {
+    /// Pattern of the template that matched
+    pub pattern: String,
+    pub scope: TemplateScope,
+    pub content: String,
+}
+
+fn templates_path(scope: TemplateScope, workspace_root: Option<&Path>) -> Result<PathBuf, String> {
+    match scope {
+        TemplateScope::User => dirs::home_dir()
+            .map(|home| home.join(".fluxel").join(TEMPLATES_FILE))
+            .ok_or_else(|| "Could not determine home directory".to_string()),
+        TemplateScope::Workspace => workspace_root
+            .map(|root| root.join(".fluxel").join(TEMPLATES_FILE))
+            .ok_or_else(|| "Workspace templates need a workspace root".to_string()),
+    }
+}
+
+fn read_templates(path: &Path) -> Result<Vec<FileTemplate>, String> {
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let file: TemplatesFile =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid {:?}: {}", path, e))?;
+    Ok(file.templates)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{'])
+}
+
+fn glob_matcher(pattern: &str) -> Result<GlobMatcher, String> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| format!("Invalid template pattern '{}': {}", pattern, e))
+}
+
+/// Whether `template` applies to a file, given its workspace-relative path
+/// (`/`-separated) and its name
+fn template_matches(template: &FileTemplate, relative: &str, file_name: &str) -> bool {
+    let pattern = template.pattern.trim();
+    if is_glob(pattern) {
+        let subject = if pattern.contains('/') {
+            relative
+        } else {
+            file_name
+        };
+        return glob_matcher(pattern).is_ok_and(|matcher| matcher.is_match(subject));
+    }
+    let extension = pattern.trim_start_matches('.').to_lowercase();
+    !extension.is_empty()
+        && file_name
+            .to_lowercase()
+            .strip_suffix(&extension)
+            .is_some_and(|stem| stem.len() > 1 && stem.ends_with('.'))
+}
+
+/// Same rules as the C# namespaces of the new file dialog: invalid characters
+/// become `_`, leading non-letters are dropped
+fn namespace_segment(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let trimmed = sanitized.trim_start_matches(|c: char| !c.is_ascii_alphabetic() && c != '_');
+    if trimmed.is_empty() {
+        "Project".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// The workspace folder's name followed by the folders between it and `dir`
+fn namespace_for(dir: &Path, workspace_root: Option<&Path>) -> String {
+    let (root, relative) = workspace_root
+        .and_then(|root| Some((root, dir.strip_prefix(root).ok()?)))
+        .unwrap_or((dir, Path::new("")));
+    let root_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    std::iter::once(root_name)
+        .chain(
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string()),
+        )
+        .map(|segment| namespace_segment(&segment))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `user.name` and `user.email` from the repository containing `dir`, or from
+/// the global git config outside repositories
+fn git_author(dir: &Path) -> (Option<String>, Option<String>) {
+    let config = Repository::discover(dir)
+        .and_then(|repo| repo.config())
+        .or_else(|_| Config::open_default());
+    let Ok(config) = config else {
+        return (None, None);
+    };
+    (
+        config.get_string("user.name").ok(),
+        config.get_string("user.email").ok(),
+    )
+}
+
+fn template_variables(
+    path: &Path,
+    workspace_root: Option<&Path>,
+    author: (Option<String>, Option<String>),
+) -> HashMap<String, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let today = Local::now();
+    // Missing values render as empty rather than leaving placeholders behind
+    let (author, email) = author;
+    HashMap::from([
+        ("filename".to_string(), file_name),
+        ("name".to_string(), name),
+        ("extension".to_string(), extension),
+        ("namespace".to_string(), namespace_for(dir, workspace_root)),
+        ("date".to_string(), today.format("%Y-%m-%d").to_string()),
+        ("year".to_string(), today.year().to_string()),
+        ("author".to_string(), author.unwrap_or_default()),
+        ("email".to_string(), email.unwrap_or_default()),
+    ])
+}
+
+/// First template among `candidates` that applies to `path`, rendered
+fn render_with(
+    candidates: &[(TemplateScope, Vec<FileTemplate>)],
+    path: &Path,
+    workspace_root: Option<&Path>,
+    author: impl FnOnce() -> (Option<String>, Option<String>),
+) -> Option<RenderedFileTemplate> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let relative = workspace_root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|| file_name.clone());
+    let (scope, template) = candidates.iter().find_map(|(scope, templates)| {
+        templates
+            .iter()
+            .find(|template| template_matches(template, &relative, &file_name))
+            .map(|template| (*scope, template))
+    })?;
+    let variables = template_variables(path, workspace_root, author());
+    Some(RenderedFileTemplate {
+        pattern: template.pattern.clone(),
+        scope,
+        content: render(&template.content, &variables),
+    })
+}
+
+/// Render the workspace or user template for a new file at `path`, if one applies
+pub(crate) fn render_file_template_for(
+    path: &Path,
+    workspace_root: Option<&Path>,
+) -> Result<Option<RenderedFileTemplate>, String> {
+    let mut candidates = Vec::new();
+    for scope in [TemplateScope::Workspace, TemplateScope::User] {
+        if let Ok(templates_path) = templates_path(scope, workspace_root) {
+            candidates.push((scope, read_templates(&templates_path)?));
+        }
+    }
+    let dir = path.parent().unwrap_or(Path::new(""));
+    Ok(render_with(&candidates, path, workspace_root, || {
+        git_author(dir)
+    }))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the workspace and user file templates
+///
+/// # Arguments
+/// * `workspace_root` - Workspace whose `.fluxel/file-templates.json` is read
+#[fluxel_command]
+pub async fn list_file_templates(
+    workspace_root: Option<String>,
+) -> Result<FileTemplateSet, String> {
+    let root = workspace_root.map(PathBuf::from);
+    let workspace = match &root {
+        Some(root) => read_templates(&templates_path(TemplateScope::Workspace, Some(root))?)?,
+        None => Vec::new(),
+    };
+    let user = read_templates(&templates_path(TemplateScope::User, None)?)?;
+    Ok(FileTemplateSet { workspace, user })
+}
+
+/// Replace the file templates of one scope
+///
+/// # Arguments
+/// * `scope` - `user` for `~/.fluxel`, `workspace` for the workspace's `.fluxel`
+/// * `templates` - Templates to store, in match order
+/// * `workspace_root` - Workspace root (required for the workspace scope)
+#[fluxel_command]
+pub async fn save_file_templates(
+    scope: TemplateScope,
+    templates: Vec<FileTemplate>,
+    workspace_root: Option<String>,
+) -> Result<(), String> {
+    for template in &templates {
+        let pattern = template.pattern.trim();
+        if pattern.trim_start_matches('.').is_empty() {
+            return Err("File templates need a pattern".to_string());
+        }
+        if is_glob(pattern) {
+            glob_matcher(pattern)?;
+        }
+    }
+
+    let path = templates_path(scope, workspace_root.as_deref().map(Path::new))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(&TemplatesFile { templates }).map_err(|e| e.to_string())?;
+    fs::write(&path, content + "\n").map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Render the template a new file at `path` would be created with
+///
+/// # Arguments
+/// * `path` - Path of the file to be created
+/// * `workspace_root` - Workspace for workspace templates, globs and namespaces
+#[fluxel_command]
+pub async fn render_file_template(
+    path: String,
+    workspace_root: Option<String>,
+) -> Result<Option<RenderedFileTemplate>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        render_file_template_for(Path::new(&path), workspace_root.as_deref().map(Path::new))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(pattern: &str, content: &str) -> FileTemplate {
+        FileTemplate {
+            pattern: pattern.to_string(),
+            description: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_first_matching_template() {
+        let root = Path::new("/work/My-App");
+        let candidates = vec![
+            (
+                TemplateScope::Workspace,
+                vec![
+                    template("src/**/*.test.ts", "// test {{name}}\n"),
+                    template("*.config.ts", "export default {};\n"),
+                ],
+            ),
+            (
+                TemplateScope::User,
+                vec![
+                    template("d.ts", "declare module '{{name}}';\n"),
+                    template(
+                        ".cs",
+                        "// {{author}} <{{email}}>, {{year}}\nnamespace {{namespace}};\n\npublic class {{name}} {{ {{unknown}} }}\n",
+                    ),
+                    template("ts", "// {{filename}}\n"),
+                ],
+            ),
+        ];
+        let author = || (Some("Ada".to_string()), None);
+        let year = Local::now().year();
+
+        let rendered = render_with(
+            &candidates,
+            &root.join("Models").join("Enemy AI").join("Player.cs"),
+            Some(root),
+            author,
+        )
+        .unwrap();
+        assert_eq!(rendered.scope, TemplateScope::User);
+        assert_eq!(
+            rendered.content,
+            format!(
+                "// Ada <>, {year}\nnamespace My_App.Models.Enemy_AI;\n\npublic class Player {{{{ {{{{unknown}}}} }}}}\n"
+            )
+        );
+
+        let rendered = |path: &str| {
+            render_with(&candidates, &root.join(path), Some(root), author).map(|r| r.content)
+        };
+        assert_eq!(rendered("src/app/a.test.ts").unwrap(), "// test a.test\n");
+        assert_eq!(rendered("a.test.ts").unwrap(), "// a.test.ts\n");
+        assert_eq!(
+            rendered("web/vite.config.ts").unwrap(),
+            "export default {};\n"
+        );
+        assert_eq!(
+            rendered("types/env.d.ts").unwrap(),
+            "declare module 'env.d';\n"
+        );
+        assert!(rendered("README.md").is_none());
+        assert!(rendered("ts").is_none());
+    }
+}
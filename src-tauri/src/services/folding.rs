@@ -0,0 +1,1033 @@
+//! Folding Range Service
+//!
+//! Computes folding ranges with lightweight, language-aware heuristics (bracket
+//! matching, indentation, region markers, JSX/HTML tags) so large files can fold
+//! immediately, without waiting for a language server.
+
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Monaco ignores ranges past its `foldingMaximumRegions` default
+const MAX_FOLDING_RANGES: usize = 5000;
+
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FoldingRangeKind {
+    Comment,
+    Imports,
+    Region,
+}
+
+/// A foldable line range. Lines are 1-based and inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldingRange {
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<FoldingRangeKind>,
+}
+
+// ============================================================================
+// Language Families
+// ============================================================================
+
+/// Lexical details the bracket scanner needs to skip strings and comments
+#[derive(Debug, Clone, Copy, Default)]
+struct Syntax {
+    line_comment: Option<&'static str>,
+    block_comments: bool,
+    single_quote_strings: bool,
+    /// `"` strings may span lines (Rust, C# verbatim)
+    multiline_strings: bool,
+    /// JS template literals with `${}` interpolation
+    template_strings: bool,
+    /// Rust char literals vs. lifetimes, raw strings
+    rust: bool,
+    /// C# `@"..."` verbatim strings
+    verbatim_strings: bool,
+    jsx: bool,
+    /// Content is markup (HTML/XML) rather than code
+    markup: bool,
+    /// HTML void elements and raw-text `<script>`/`<style>`
+    html: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Family {
+    Code {
+        syntax: Syntax,
+        import_prefixes: &'static [&'static str],
+    },
+    Markup(Syntax),
+    Indentation {
+        comment: &'static str,
+        import_prefixes: &'static [&'static str],
+    },
+    Sections,
+    Markdown,
+}
+
+fn language_family(path: &str) -> Family {
+    let path = Path::new(path);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    let c_like = Syntax {
+        line_comment: Some("//"),
+        block_comments: true,
+        single_quote_strings: true,
+        ..Default::default()
+    };
+    let js = Syntax {
+        template_strings: true,
+        ..c_like
+    };
+    let js_imports: &[&str] = &["import "];
+
+    match extension.as_str() {
+        "js" | "mjs" | "cjs" | "ts" | "mts" | "cts" => Family::Code {
+            syntax: js,
+            import_prefixes: js_imports,
+        },
+        "jsx" | "tsx" => Family::Code {
+            syntax: Syntax { jsx: true, ..js },
+            import_prefixes: js_imports,
+        },
+        "rs" => Family::Code {
+            syntax: Syntax {
+                single_quote_strings: false,
+                multiline_strings: true,
+                rust: true,
+                ..c_like
+            },
+            import_prefixes: &["use ", "pub use ", "pub(crate) use ", "extern crate "],
+        },
+        "cs" => Family::Code {
+            syntax: Syntax {
+                verbatim_strings: true,
+                ..c_like
+            },
+            import_prefixes: &["using "],
+        },
+        "java" | "kt" | "kts" | "scala" | "go" | "dart" | "swift" => Family::Code {
+            syntax: c_like,
+            import_prefixes: &["import "],
+        },
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "m" | "php" | "json" | "jsonc"
+        | "json5" | "scss" | "less" | "gradle" | "proto" => Family::Code {
+            syntax: c_like,
+            import_prefixes: &["#include "],
+        },
+        "css" => Family::Code {
+            syntax: Syntax {
+                line_comment: None,
+                ..c_like
+            },
+            import_prefixes: &["@import "],
+        },
+        "sh" | "bash" | "zsh" | "ps1" | "psm1" | "r" => Family::Code {
+            syntax: Syntax {
+                line_comment: Some("#"),
+                block_comments: false,
+                ..c_like
+            },
+            import_prefixes: &[],
+        },
+        "html" | "htm" | "vue" | "svelte" | "astro" => Family::Markup(Syntax {
+            markup: true,
+            html: true,
+            ..Default::default()
+        }),
+        "xml" | "xaml" | "axaml" | "svg" | "csproj" | "fsproj" | "vbproj" | "props" | "targets"
+        | "config" | "resx" | "plist" | "xsd" => Family::Markup(Syntax {
+            markup: true,
+            ..Default::default()
+        }),
+        "md" | "markdown" | "mdx" => Family::Markdown,
+        "py" | "pyw" | "pyi" => Family::Indentation {
+            comment: "#",
+            import_prefixes: &["import ", "from "],
+        },
+        "yaml" | "yml" => Family::Indentation {
+            comment: "#",
+            import_prefixes: &[],
+        },
+        "toml" | "ini" | "cfg" | "conf" | "properties" => Family::Sections,
+        _ if file_name == ".editorconfig" || file_name == ".gitconfig" => Family::Sections,
+        _ => Family::Indentation {
+            comment: "#",
+            import_prefixes: &[],
+        },
+    }
+}
+
+// ============================================================================
+// Bracket and Tag Scanner
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Code,
+    /// Inside a JS template literal
+    Template,
+    /// Markup text (HTML body or JSX children)
+    Markup,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OpenKind {
+    Bracket(char),
+    /// `${` inside a template literal
+    TemplateExpr,
+    /// `{` inside JSX children or attributes
+    JsxExpr,
+    Tag(String),
+}
+
+#[derive(Debug)]
+struct Open {
+    kind: OpenKind,
+    line: usize,
+    /// Mode stack depth to restore when this opener closes
+    mode_depth: usize,
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    i: usize,
+    line: usize,
+    line_start: usize,
+    syntax: Syntax,
+    modes: Vec<Mode>,
+    stack: Vec<Open>,
+    /// Last significant code character, to tell JSX tags from comparisons
+    prev_significant: char,
+    ranges: Vec<FoldingRange>,
+}
+
+impl Scanner {
+    fn new(content: &str, syntax: Syntax) -> Self {
+        Self {
+            chars: content.chars().collect(),
+            i: 0,
+            line: 1,
+            line_start: 0,
+            syntax,
+            modes: vec![if syntax.markup {
+                Mode::Markup
+            } else {
+                Mode::Code
+            }],
+            stack: Vec::new(),
+            prev_significant: '\n',
+            ranges: Vec::new(),
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.i + offset).copied()
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(k, c)| self.peek(k) == Some(c))
+    }
+
+    /// Advance one character, tracking lines
+    fn bump(&mut self) {
+        if self.peek(0) == Some('\n') {
+            self.line += 1;
+            self.line_start = self.i + 1;
+        }
+        self.i += 1;
+    }
+
+    fn bump_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.bump();
+        }
+    }
+
+    /// Skip until just past `terminator` (or the end of input)
+    fn skip_past(&mut self, terminator: &str) {
+        while self.i < self.chars.len() && !self.starts_with(terminator) {
+            self.bump();
+        }
+        self.bump_n(terminator.chars().count());
+    }
+
+    fn at_line_start(&self, index: usize) -> bool {
+        index >= self.line_start
+            && self.chars[self.line_start..index]
+                .iter()
+                .all(|c| c.is_whitespace())
+    }
+
+    /// Record a range closed by the token at `close_index`. The closing line stays
+    /// visible when the token starts its line (`}` / `</div>`).
+    fn push_range(
+        &mut self,
+        start_line: usize,
+        close_index: usize,
+        kind: Option<FoldingRangeKind>,
+    ) {
+        let end_line = if self.at_line_start(close_index) {
+            self.line - 1
+        } else {
+            self.line
+        };
+        if end_line > start_line {
+            self.ranges.push(FoldingRange {
+                start_line,
+                end_line,
+                kind,
+            });
+        }
+    }
+
+    fn mode(&self) -> Mode {
+        *self.modes.last().unwrap_or(&Mode::Code)
+    }
+
+    fn open(&mut self, kind: OpenKind, mode: Option<Mode>) {
+        self.stack.push(Open {
+            kind,
+            line: self.line,
+            mode_depth: self.modes.len(),
+        });
+        if let Some(mode) = mode {
+            self.modes.push(mode);
+        }
+    }
+
+    /// Pop up to the nearest opener accepted by `matches`, recording its range
+    fn close(&mut self, close_index: usize, matches: impl Fn(&OpenKind) -> bool) {
+        let Some(position) = self.stack.iter().rposition(|open| matches(&open.kind)) else {
+            return;
+        };
+        let open = self.stack.remove(position);
+        self.stack.truncate(position);
+        self.modes.truncate(open.mode_depth.max(1));
+        self.push_range(open.line, close_index, None);
+    }
+
+    fn skip_quoted(&mut self, quote: char, multiline: bool, escapes: bool) {
+        self.bump();
+        while let Some(c) = self.peek(0) {
+            if c == '\\' && escapes {
+                self.bump_n(2);
+                continue;
+            }
+            if c == '\n' && !multiline {
+                return;
+            }
+            self.bump();
+            if c == quote {
+                // C# verbatim strings escape quotes by doubling them
+                if !escapes && self.peek(0) == Some(quote) {
+                    self.bump();
+                    continue;
+                }
+                return;
+            }
+        }
+    }
+
+    /// Rust raw string `r#"..."#` starting at `r`
+    fn try_skip_raw_string(&mut self) -> bool {
+        let mut hashes = 0;
+        while self.peek(1 + hashes) == Some('#') {
+            hashes += 1;
+        }
+        if self.peek(1 + hashes) != Some('"') {
+            return false;
+        }
+        self.bump_n(2 + hashes);
+        let terminator: String = std::iter::once('"')
+            .chain(std::iter::repeat_n('#', hashes))
+            .collect();
+        self.skip_past(&terminator);
+        true
+    }
+
+    /// Parse a tag starting at `<`. Returns (name, closing, self_closing).
+    fn scan_tag(&mut self) -> (String, bool, bool) {
+        self.bump();
+        let closing = self.peek(0) == Some('/');
+        if closing {
+            self.bump();
+        }
+        let mut name = String::new();
+        while let Some(c) = self.peek(0) {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '$') {
+                name.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+
+        let mut depth = 0usize;
+        let mut last = ' ';
+        while let Some(c) = self.peek(0) {
+            match c {
+                '"' | '\'' => {
+                    self.skip_quoted(c, true, false);
+                    last = c;
+                    continue;
+                }
+                '{' if self.syntax.jsx => depth += 1,
+                '}' if self.syntax.jsx => depth = depth.saturating_sub(1),
+                '>' if depth == 0 => {
+                    self.bump();
+                    return (name, closing, last == '/');
+                }
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                last = c;
+            }
+            self.bump();
+        }
+        (name, closing, false)
+    }
+
+    fn handle_tag(&mut self) {
+        let start = self.i;
+        let start_line = self.line;
+        let (name, closing, self_closing) = self.scan_tag();
+
+        if closing {
+            let is_html = self.syntax.html;
+            self.close(start, |kind| match kind {
+                OpenKind::Tag(open) if is_html => open.eq_ignore_ascii_case(&name),
+                OpenKind::Tag(open) => *open == name,
+                _ => false,
+            });
+            return;
+        }
+        if self_closing {
+            return;
+        }
+        let lower = name.to_ascii_lowercase();
+        if self.syntax.html && VOID_ELEMENTS.contains(&lower.as_str()) {
+            return;
+        }
+
+        // The opener is recorded at the line its `<` appeared on
+        let mode = self.syntax.jsx.then_some(Mode::Markup);
+        self.open(OpenKind::Tag(name), mode);
+        if let Some(open) = self.stack.last_mut() {
+            open.line = start_line;
+        }
+
+        // Script and style bodies are raw text in HTML
+        if self.syntax.html && (lower == "script" || lower == "style") {
+            let terminator = format!("</{}", lower);
+            while self.i < self.chars.len() {
+                let ahead: String = self.chars
+                    [self.i..(self.i + terminator.len()).min(self.chars.len())]
+                    .iter()
+                    .collect();
+                if ahead.eq_ignore_ascii_case(&terminator) {
+                    break;
+                }
+                self.bump();
+            }
+        }
+    }
+
+    /// JSX tags follow operators and keywords; `<` after an operand is a comparison
+    fn is_jsx_tag_start(&self) -> bool {
+        let next = self.peek(1);
+        let starts_tag = next.is_some_and(|c| c.is_alphabetic() || c == '>');
+        let after_operand = self.prev_significant.is_alphanumeric()
+            || matches!(self.prev_significant, ')' | ']' | '_' | '$');
+        // `return <div>` ends in an identifier character but is still a tag
+        let after_keyword = {
+            let before: String = self.chars[self.line_start..self.i].iter().collect();
+            let trimmed = before.trim_end();
+            trimmed.ends_with("return") || trimmed.ends_with("yield") || trimmed.ends_with("=>")
+        };
+        starts_tag && (!after_operand || after_keyword)
+    }
+
+    fn scan_code(&mut self, c: char) {
+        if let Some(comment) = self.syntax.line_comment {
+            if self.starts_with(comment) {
+                while self.peek(0).is_some_and(|c| c != '\n') {
+                    self.bump();
+                }
+                return;
+            }
+        }
+        if self.syntax.block_comments && self.starts_with("/*") {
+            let start_line = self.line;
+            self.skip_past("*/");
+            let end_line = self.line;
+            if end_line > start_line {
+                self.ranges.push(FoldingRange {
+                    start_line,
+                    end_line,
+                    kind: Some(FoldingRangeKind::Comment),
+                });
+            }
+            return;
+        }
+
+        match c {
+            '"' => {
+                let verbatim = self.syntax.verbatim_strings && self.prev_significant == '@';
+                if verbatim {
+                    self.skip_quoted('"', true, false);
+                } else {
+                    self.skip_quoted('"', self.syntax.multiline_strings, true);
+                }
+                self.prev_significant = '"';
+            }
+            '\'' if self.syntax.rust => {
+                // 'x' and '\n' are chars; 'a without a closing quote is a lifetime
+                if self.peek(1) == Some('\\') || self.peek(2) == Some('\'') {
+                    self.skip_quoted('\'', false, true);
+                } else {
+                    self.bump();
+                }
+                self.prev_significant = '\'';
+            }
+            '\'' if self.syntax.single_quote_strings => {
+                self.skip_quoted('\'', false, true);
+                self.prev_significant = '\'';
+            }
+            '`' if self.syntax.template_strings => {
+                self.bump();
+                self.modes.push(Mode::Template);
+            }
+            'r' if self.syntax.rust
+                && !self.prev_significant.is_alphanumeric()
+                && self.try_skip_raw_string() =>
+            {
+                self.prev_significant = '"';
+            }
+            '{' | '[' | '(' => {
+                self.open(OpenKind::Bracket(c), None);
+                self.bump();
+                self.prev_significant = c;
+            }
+            '}' | ']' | ')' => {
+                let opener = match c {
+                    '}' => '{',
+                    ']' => '[',
+                    _ => '(',
+                };
+                let close_index = self.i;
+                self.close(close_index, |kind| match kind {
+                    OpenKind::Bracket(open) => *open == opener,
+                    OpenKind::TemplateExpr | OpenKind::JsxExpr => opener == '{',
+                    OpenKind::Tag(_) => false,
+                });
+                self.bump();
+                self.prev_significant = c;
+            }
+            '<' if self.syntax.jsx && self.is_jsx_tag_start() => {
+                self.handle_tag();
+                self.prev_significant = '>';
+            }
+            _ => {
+                if !c.is_whitespace() {
+                    self.prev_significant = c;
+                }
+                self.bump();
+            }
+        }
+    }
+
+    fn scan_template(&mut self, c: char) {
+        match c {
+            '\\' => self.bump_n(2),
+            '`' => {
+                self.bump();
+                self.modes.pop();
+                self.prev_significant = '`';
+            }
+            '$' if self.peek(1) == Some('{') => {
+                self.open(OpenKind::TemplateExpr, Some(Mode::Code));
+                self.bump_n(2);
+                self.prev_significant = '{';
+            }
+            _ => self.bump(),
+        }
+    }
+
+    fn scan_markup(&mut self, c: char) {
+        if self.starts_with("<!--") {
+            let start_line = self.line;
+            self.skip_past("-->");
+            if self.line > start_line {
+                self.ranges.push(FoldingRange {
+                    start_line,
+                    end_line: self.line,
+                    kind: Some(FoldingRangeKind::Comment),
+                });
+            }
+            return;
+        }
+        if self.starts_with("<!") || self.starts_with("<?") {
+            self.skip_past(">");
+            return;
+        }
+        match c {
+            '<' if self
+                .peek(1)
+                .is_some_and(|n| n.is_alphabetic() || n == '/' || n == '>') =>
+            {
+                self.handle_tag();
+            }
+            '{' if self.syntax.jsx => {
+                self.open(OpenKind::JsxExpr, Some(Mode::Code));
+                self.bump();
+                self.prev_significant = '{';
+            }
+            _ => self.bump(),
+        }
+    }
+
+    fn run(mut self) -> Vec<FoldingRange> {
+        while let Some(c) = self.peek(0) {
+            match self.mode() {
+                Mode::Code => self.scan_code(c),
+                Mode::Template => self.scan_template(c),
+                Mode::Markup => self.scan_markup(c),
+            }
+        }
+        self.ranges
+    }
+}
+
+// ============================================================================
+// Line-Based Heuristics
+// ============================================================================
+
+fn region_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*(?:(?://|--|;|/\*|<!--)\s*#?|#)\s*(?:pragma\s+)?(end)?region\b").unwrap()
+    })
+}
+
+/// `#region` / `#endregion` style markers in any comment syntax
+fn region_ranges(lines: &[&str]) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    let mut open = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(caps) = region_regex().captures(line) else {
+            continue;
+        };
+        if caps.get(1).is_some() {
+            if let Some(start) = open.pop() {
+                ranges.push(FoldingRange {
+                    start_line: start,
+                    end_line: index + 1,
+                    kind: Some(FoldingRangeKind::Region),
+                });
+            }
+        } else {
+            open.push(index + 1);
+        }
+    }
+    ranges
+}
+
+/// Runs of two or more consecutive line comments
+fn comment_block_ranges(lines: &[&str], prefix: &str) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    let mut start: Option<usize> = None;
+    for (index, line) in lines.iter().chain(std::iter::once(&"")).enumerate() {
+        let is_comment = line.trim_start().starts_with(prefix) && !region_regex().is_match(line);
+        match (is_comment, start) {
+            (true, None) => start = Some(index + 1),
+            (false, Some(first)) => {
+                if index > first {
+                    ranges.push(FoldingRange {
+                        start_line: first,
+                        end_line: index,
+                        kind: Some(FoldingRangeKind::Comment),
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// The block of import statements at the top of a file (blank lines allowed,
+/// multi-line `import { ... }` followed by bracket depth)
+fn import_ranges(lines: &[&str], prefixes: &[&str]) -> Vec<FoldingRange> {
+    if prefixes.is_empty() {
+        return Vec::new();
+    }
+    let mut ranges = Vec::new();
+    let mut first: Option<usize> = None;
+    let mut last = 0;
+    let mut depth: i32 = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let line_number = index + 1;
+        let starts_import = prefixes.iter().any(|p| trimmed.starts_with(p))
+            // `using (var x = ...)` / `using var x` are statements, not imports
+            && !trimmed.starts_with("using (")
+            && !trimmed.starts_with("using var ");
+
+        if depth > 0 || starts_import {
+            first.get_or_insert(line_number);
+            last = line_number;
+            depth += trimmed.matches(['{', '(']).count() as i32;
+            depth -= trimmed.matches(['}', ')']).count() as i32;
+            depth = depth.max(0);
+        } else if !trimmed.is_empty() {
+            if let Some(start) = first.take() {
+                if last > start {
+                    ranges.push(FoldingRange {
+                        start_line: start,
+                        end_line: last,
+                        kind: Some(FoldingRangeKind::Imports),
+                    });
+                }
+            }
+        }
+    }
+    if let Some(start) = first {
+        if last > start {
+            ranges.push(FoldingRange {
+                start_line: start,
+                end_line: last,
+                kind: Some(FoldingRangeKind::Imports),
+            });
+        }
+    }
+    ranges
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Each line folds over the following lines that are indented deeper
+fn indentation_ranges(lines: &[&str]) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    // (indent, line number)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut last_content_line = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_width(line);
+        while let Some(&(open_indent, start)) = stack.last() {
+            if open_indent < indent {
+                break;
+            }
+            stack.pop();
+            if last_content_line > start {
+                ranges.push(FoldingRange {
+                    start_line: start,
+                    end_line: last_content_line,
+                    kind: None,
+                });
+            }
+        }
+        stack.push((indent, index + 1));
+        last_content_line = index + 1;
+    }
+    for (_, start) in stack {
+        if last_content_line > start {
+            ranges.push(FoldingRange {
+                start_line: start,
+                end_line: last_content_line,
+                kind: None,
+            });
+        }
+    }
+    ranges
+}
+
+/// Last non-blank line in `start..end` (1-based, exclusive end)
+fn last_content_before(lines: &[&str], start: usize, end: usize) -> usize {
+    (start..end)
+        .rev()
+        .find(|&line| !lines[line - 1].trim().is_empty())
+        .unwrap_or(start)
+}
+
+/// `[section]` headers fold to the next header (TOML, INI)
+fn section_ranges(lines: &[&str]) -> Vec<FoldingRange> {
+    let headers: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| {
+            let trimmed = line.trim();
+            trimmed.starts_with('[') && trimmed.ends_with(']')
+        })
+        .map(|(index, _)| index + 1)
+        .collect();
+
+    headers
+        .iter()
+        .enumerate()
+        .filter_map(|(k, &start)| {
+            let next = headers.get(k + 1).copied().unwrap_or(lines.len() + 1);
+            let end_line = last_content_before(lines, start, next);
+            (end_line > start).then_some(FoldingRange {
+                start_line: start,
+                end_line,
+                kind: None,
+            })
+        })
+        .collect()
+}
+
+/// Headings fold to the next heading of the same or higher level; fenced code
+/// blocks fold as a unit
+fn markdown_ranges(lines: &[&str]) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    let mut headings: Vec<(usize, usize)> = Vec::new();
+    let mut fence: Option<(usize, String)> = None;
+
+    let close_headings = |headings: &mut Vec<(usize, usize)>,
+                          level: usize,
+                          next_line: usize,
+                          ranges: &mut Vec<FoldingRange>| {
+        while let Some(&(open_level, start)) = headings.last() {
+            if open_level < level {
+                break;
+            }
+            headings.pop();
+            let end_line = last_content_before(lines, start, next_line);
+            if end_line > start {
+                ranges.push(FoldingRange {
+                    start_line: start,
+                    end_line,
+                    kind: None,
+                });
+            }
+        }
+    };
+
+    for (index, line) in lines.iter().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim_start();
+
+        if let Some((start, marker)) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                ranges.push(FoldingRange {
+                    start_line: *start,
+                    end_line: line_number,
+                    kind: None,
+                });
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some((line_number, trimmed[..3].to_string()));
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with([' ', '\t']) {
+            close_headings(&mut headings, level, line_number, &mut ranges);
+            headings.push((level, line_number));
+        }
+    }
+    close_headings(&mut headings, 1, lines.len() + 1, &mut ranges);
+    ranges
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Compute folding ranges for `content`, choosing heuristics from the file extension
+pub fn compute(path: &str, content: &str) -> Vec<FoldingRange> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut ranges = region_ranges(&lines);
+
+    match language_family(path) {
+        Family::Code {
+            syntax,
+            import_prefixes,
+        } => {
+            ranges.extend(Scanner::new(content, syntax).run());
+            if let Some(prefix) = syntax.line_comment {
+                ranges.extend(comment_block_ranges(&lines, prefix));
+            }
+            ranges.extend(import_ranges(&lines, import_prefixes));
+        }
+        Family::Markup(syntax) => ranges.extend(Scanner::new(content, syntax).run()),
+        Family::Indentation {
+            comment,
+            import_prefixes,
+        } => {
+            ranges.extend(indentation_ranges(&lines));
+            ranges.extend(comment_block_ranges(&lines, comment));
+            ranges.extend(import_ranges(&lines, import_prefixes));
+        }
+        Family::Sections => {
+            ranges.extend(section_ranges(&lines));
+            ranges.extend(comment_block_ranges(&lines, "#"));
+        }
+        Family::Markdown => ranges.extend(markdown_ranges(&lines)),
+    }
+
+    // One range per start line; keep the widest
+    ranges.retain(|r| r.end_line > r.start_line);
+    ranges.sort_by(|a, b| {
+        a.start_line
+            .cmp(&b.start_line)
+            .then(b.end_line.cmp(&a.end_line))
+    });
+    ranges.dedup_by_key(|r| r.start_line);
+    ranges.truncate(MAX_FOLDING_RANGES);
+    ranges
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Compute folding ranges for a document without a language server.
+///
+/// # Arguments
+/// * `path` - File path (used to pick the language heuristics)
+/// * `content` - Current document text
+#[fluxel_command]
+pub async fn compute_folding_ranges(
+    path: String,
+    content: String,
+) -> Result<Vec<FoldingRange>, String> {
+    tauri::async_runtime::spawn_blocking(move || compute(&path, &content))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(ranges: &[FoldingRange]) -> Vec<(usize, usize)> {
+        ranges.iter().map(|r| (r.start_line, r.end_line)).collect()
+    }
+
+    #[test]
+    fn folds_brackets_jsx_imports_and_regions() {
+        let source = r#"import React from "react";
+import {
+  useState,
+} from "react";
+
+// #region helpers
+function add(a, b) {
+  const s = `${a} {`;
+  return a < b ? a : b;
+}
+// #endregion
+
+export function App() {
+  return (
+    <div className="app">
+      <p>It's {count > 1 ? "many" : "one"}</p>
+      <br />
+    </div>
+  );
+}
+"#;
+        let ranges = compute("App.jsx", source);
+        let spans = spans(&ranges);
+
+        assert!(ranges.contains(&FoldingRange {
+            start_line: 1,
+            end_line: 4,
+            kind: Some(FoldingRangeKind::Imports),
+        }));
+        assert!(ranges.contains(&FoldingRange {
+            start_line: 6,
+            end_line: 11,
+            kind: Some(FoldingRangeKind::Region),
+        }));
+        // Function body; the closing brace stays visible
+        assert!(spans.contains(&(7, 9)));
+        assert!(spans.contains(&(13, 19)));
+        assert!(spans.contains(&(14, 18)));
+        // <div> folds to the line before </div>
+        assert!(spans.contains(&(15, 17)));
+    }
+
+    #[test]
+    fn folds_rust_without_confusing_lifetimes_and_raw_strings() {
+        let source = r##"/// Docs
+/// more docs
+fn parse<'a>(input: &'a str) -> &'a str {
+    let brace = '{';
+    let raw = r#"}"#;
+    input
+}
+"##;
+        let ranges = compute("lib.rs", source);
+        assert!(ranges.contains(&FoldingRange {
+            start_line: 1,
+            end_line: 2,
+            kind: Some(FoldingRangeKind::Comment),
+        }));
+        assert!(spans(&ranges).contains(&(3, 6)));
+    }
+
+    #[test]
+    fn folds_by_indentation_and_markdown_headings() {
+        let python = "import os\nimport sys\n\nclass A:\n    def f(self):\n        return 1\n\n    def g(self):\n        pass\n";
+        let spans_py = spans(&compute("a.py", python));
+        assert!(spans_py.contains(&(1, 2)));
+        assert!(spans_py.contains(&(4, 9)));
+        assert!(spans_py.contains(&(5, 6)));
+
+        let markdown = "# Title\ntext\n## Section\nbody\n```rust\nfn x() {}\n```\n# Next\nend\n";
+        let spans_md = spans(&compute("README.md", markdown));
+        assert!(spans_md.contains(&(1, 7)));
+        assert!(spans_md.contains(&(3, 7)));
+        assert!(spans_md.contains(&(5, 7)));
+    }
+
+    #[test]
+    fn folds_html_tags_and_skips_void_elements() {
+        let html = "<html>\n<body>\n  <img src=\"a.png\">\n  <script>\n    if (a < b) {}\n  </script>\n</body>\n</html>\n";
+        let spans = spans(&compute("index.html", html));
+        assert!(spans.contains(&(1, 7)));
+        assert!(spans.contains(&(2, 6)));
+        assert!(spans.contains(&(4, 5)));
+    }
+}
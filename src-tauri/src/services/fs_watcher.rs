@@ -0,0 +1,190 @@
+//! File System Watcher Service
+//!
+//! Shared, reference-counted recursive watchers keyed by root directory.
+//! Consumers (preview server live-reload, module graph watch, LSP relays, ...)
+//! subscribe to a root and receive raw (undebounced) change events over a
+//! broadcast channel. The underlying OS watcher is dropped once the last
+//! subscription for a root goes away. Changes inside paths the root's
+//! `PathFilter` excludes (`node_modules`, build output, ...) are never delivered.
+
+use crate::services::path_filter::PathFilterState;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Capacity of the per-root broadcast channel. Slow subscribers that fall
+/// further behind than this will observe `RecvError::Lagged`.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Kind of change reported by the watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Other,
+}
+
+/// A single file system change notification
+#[derive(Debug, Clone, Serialize)]
+pub struct FsChangeEvent {
+    pub kind: FsChangeKind,
+    /// Affected paths, normalized to forward slashes
+    pub paths: Vec<String>,
+}
+
+struct WatchEntry {
+    /// Held only to keep the OS watcher alive
+    _watcher: RecommendedWatcher,
+    sender: broadcast::Sender<FsChangeEvent>,
+    subscribers: usize,
+}
+
+/// Manages recursive file system watchers shared between subsystems
+#[derive(Clone, Default)]
+pub struct FsWatcherService {
+    entries: Arc<Mutex<HashMap<PathBuf, WatchEntry>>>,
+    filters: PathFilterState,
+}
+
+impl FsWatcherService {
+    pub fn new(filters: PathFilterState) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            filters,
+        }
+    }
+
+    /// Subscribe to changes below `root`, starting a watcher if none exists yet.
+    pub fn subscribe(&self, root: &Path) -> Result<WatchSubscription, String> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve watch root {:?}: {}", root, e))?;
+
+        let mut entries = self.entries.lock().unwrap();
+
+        if let Some(entry) = entries.get_mut(&root) {
+            entry.subscribers += 1;
+            return Ok(WatchSubscription {
+                root,
+                receiver: entry.sender.subscribe(),
+                service: self.clone(),
+            });
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        let event_sender = sender.clone();
+        let filters = self.filters.clone();
+        let filter_root = root.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let kind = match event.kind {
+                    EventKind::Create(_) => FsChangeKind::Created,
+                    EventKind::Modify(_) => FsChangeKind::Modified,
+                    EventKind::Remove(_) => FsChangeKind::Removed,
+                    EventKind::Access(_) => return,
+                    _ => FsChangeKind::Other,
+                };
+                // Looked up per event so settings changes apply to running watchers
+                let filter = filters.filter_for(&filter_root);
+                let paths: Vec<String> = event
+                    .paths
+                    .iter()
+                    .filter(|p| !filter.excludes(p, p.is_dir()))
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .collect();
+                if paths.is_empty() && !event.paths.is_empty() {
+                    return;
+                }
+                // No receivers is not an error for us
+                let _ = event_sender.send(FsChangeEvent { kind, paths });
+            }
+        })
+        .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {:?}: {}", root, e))?;
+
+        println!("[FsWatcher] Watching {:?}", root);
+
+        entries.insert(
+            root.clone(),
+            WatchEntry {
+                _watcher: watcher,
+                sender,
+                subscribers: 1,
+            },
+        );
+
+        Ok(WatchSubscription {
+            root,
+            receiver,
+            service: self.clone(),
+        })
+    }
+
+    /// Number of roots currently being watched
+    pub fn watched_root_count(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Drop every OS watcher (used on app exit). Subscriptions stay valid but
+    /// receive no further events.
+    pub fn stop_all(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn release(&self, root: &Path) {
+        let mut entries = self.entries.lock().unwrap();
+        let remove = match entries.get_mut(root) {
+            Some(entry) => {
+                entry.subscribers = entry.subscribers.saturating_sub(1);
+                entry.subscribers == 0
+            }
+            None => false,
+        };
+        if remove {
+            entries.remove(root);
+            println!("[FsWatcher] Stopped watching {:?}", root);
+        }
+    }
+}
+
+/// An active subscription to a watched root.
+/// Dropping the subscription releases the underlying watcher reference.
+pub struct WatchSubscription {
+    root: PathBuf,
+    receiver: broadcast::Receiver<FsChangeEvent>,
+    service: FsWatcherService,
+}
+
+impl WatchSubscription {
+    /// The canonical root this subscription observes
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Wait for the next change event.
+    /// Returns `None` once the watcher has been torn down.
+    pub async fn recv(&mut self) -> Option<FsChangeEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                // Dropped events are acceptable; consumers re-scan as needed
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for WatchSubscription {
+    fn drop(&mut self) {
+        self.service.release(&self.root);
+    }
+}
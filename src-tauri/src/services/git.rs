@@ -2,8 +2,16 @@
 //!
 //! Provides git operations for the Fluxel editor.
 
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::git_hooks::{self, HookOutput, HookOutputSink};
+use crate::services::io_accounting::IoAccounting;
+use crate::services::{git_cli, git_sparse};
+use fluxel_macros::fluxel_command;
 use git2::{Cred, PushOptions, RemoteCallbacks, Repository, Status, StatusOptions};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitFileStatus {
@@ -15,13 +23,15 @@ pub struct GitFileStatus {
 pub struct GitStatusResult {
     pub branch: String,
     pub files: Vec<GitFileStatus>,
+    /// History is truncated (shallow clone or fetch); see `git_unshallow`
+    pub shallow: bool,
 }
 
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path), fields(category = "git"))
+    tracing::instrument(skip(root_path), fields(category = "git", workspace_root = %root_path))
 )]
-#[tauri::command]
 pub async fn git_status(root_path: String) -> Result<GitStatusResult, String> {
     // Run blocking git operations in a separate thread
     tauri::async_runtime::spawn_blocking(move || {
@@ -78,21 +88,30 @@ pub async fn git_status(root_path: String) -> Result<GitStatusResult, String> {
             });
         }
 
-        Ok(GitStatusResult { branch, files })
+        Ok(GitStatusResult {
+            branch,
+            files,
+            shallow: repo.is_shallow(),
+        })
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path, message, files), fields(category = "git"))
+    tracing::instrument(
+        skip(app, root_path, message, files),
+        fields(category = "git", workspace_root = %root_path)
+    )
 )]
-#[tauri::command]
-pub async fn git_commit(
+pub async fn git_commit<R: Runtime>(
+    app: AppHandle<R>,
     root_path: String,
     message: String,
     files: Vec<String>,
+    skip_hooks: Option<bool>,
 ) -> Result<String, String> {
     tauri::async_runtime::spawn_blocking(move || {
         let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
@@ -109,6 +128,19 @@ pub async fn git_commit(
             .map_err(|e| e.to_string())?;
         index.write().map_err(|e| e.to_string())?;
 
+        let run_hooks = !skip_hooks.unwrap_or(false);
+        let sink: HookOutputSink = Arc::new(move |output: HookOutput| {
+            let _ = app.emit("git://hook-output", output);
+        });
+        let message = if run_hooks {
+            git_hooks::run_hook(&repo, "pre-commit", &[], &sink)?;
+            // Hooks such as lint-staged may restage files
+            index.read(true).map_err(|e| e.to_string())?;
+            git_hooks::run_commit_msg_hook(&repo, &message, &sink)?
+        } else {
+            message
+        };
+
         let tree_id = index.write_tree().map_err(|e| e.to_string())?;
         let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
 
@@ -136,17 +168,27 @@ pub async fn git_commit(
         repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &parents)
             .map_err(|e| e.to_string())?;
 
+        if run_hooks {
+            // The commit already exists; a failing post-commit hook can't undo it
+            if let Err(e) = git_hooks::run_hook(&repo, "post-commit", &[], &sink) {
+                println!("[Git] post-commit hook failed: {}", e);
+            }
+        }
+
         Ok("Committed successfully".to_string())
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path, token), fields(category = "git"))
+    tracing::instrument(
+        skip(root_path, token),
+        fields(category = "git", workspace_root = %root_path)
+    )
 )]
-#[tauri::command]
 pub async fn git_push(root_path: String, token: String) -> Result<String, String> {
     tauri::async_runtime::spawn_blocking(move || {
         let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
@@ -176,12 +218,22 @@ pub async fn git_push(root_path: String, token: String) -> Result<String, String
     .map_err(|e| e.to_string())?
 }
 
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path, token), fields(category = "git"))
+    tracing::instrument(
+        skip(root_path, token, dirty_files),
+        fields(category = "git", workspace_root = %root_path)
+    )
 )]
-#[tauri::command]
-pub async fn git_pull(root_path: String, token: String) -> Result<String, String> {
+pub async fn git_pull(
+    root_path: String,
+    token: String,
+    depth: Option<u32>,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<String, String> {
+    let dirty_files = dirty_files.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
         let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
         let mut remote = repo.find_remote("origin").map_err(|e| e.to_string())?;
@@ -194,6 +246,10 @@ pub async fn git_pull(root_path: String, token: String) -> Result<String, String
         // Fetch
         let mut fetch_options = git2::FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
+        if let Some(depth) = depth {
+            // Keeps a shallow clone shallow instead of fetching the full history
+            fetch_options.depth(depth.min(i32::MAX as u32) as i32);
+        }
 
         let head = repo.head().map_err(|e| e.to_string())?;
         let branch_name = head.shorthand().ok_or("Detached HEAD")?;
@@ -218,6 +274,26 @@ pub async fn git_pull(root_path: String, token: String) -> Result<String, String
         if analysis.0.is_up_to_date() {
             Ok("Already up to date".to_string())
         } else if analysis.0.is_fast_forward() {
+            // Only files the fast-forward rewrites can lose unsaved changes
+            let workdir = repo
+                .workdir()
+                .ok_or("Repository has no working directory")?;
+            let head_tree = head.peel_to_tree().map_err(|e| e.to_string())?;
+            let fetch_tree = repo
+                .find_commit(fetch_commit.id())
+                .and_then(|commit| commit.tree())
+                .map_err(|e| e.to_string())?;
+            let diff = repo
+                .diff_tree_to_tree(Some(&head_tree), Some(&fetch_tree), None)
+                .map_err(|e| e.to_string())?;
+            let changed: Vec<PathBuf> = diff
+                .deltas()
+                .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+                .flatten()
+                .map(|path| workdir.join(path))
+                .collect();
+            dirty_files.guard("git_pull", &changed, force.unwrap_or(false))?;
+
             let refname = format!("refs/heads/{}", branch_name);
             let mut reference = repo.find_reference(&refname).map_err(|e| e.to_string())?;
             reference
@@ -238,12 +314,149 @@ pub async fn git_pull(root_path: String, token: String) -> Result<String, String
     .map_err(|e| e.to_string())?
 }
 
+/// Partial clone filter: blobless fetches file contents on demand, treeless also
+/// defers directory listings (best for one-off builds, slow for history browsing)
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloneFilter {
+    Blobless,
+    Treeless,
+}
+
+impl CloneFilter {
+    fn spec(self) -> &'static str {
+        match self {
+            CloneFilter::Blobless => "blob:none",
+            CloneFilter::Treeless => "tree:0",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneProgress {
+    pub destination: String,
+    /// e.g. "Receiving objects"
+    pub phase: Option<String>,
+    pub percent: Option<u32>,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchProgress {
+    pub root_path: String,
+    pub phase: Option<String>,
+    pub percent: Option<u32>,
+    pub line: String,
+}
+
+/// Split a git progress line like `Receiving objects:  45% (450/1000)`
+fn parse_progress(line: &str) -> (Option<String>, Option<u32>) {
+    let Some((phase, rest)) = line.split_once(':') else {
+        return (None, None);
+    };
+    let percent = rest
+        .trim_start()
+        .split_once('%')
+        .and_then(|(number, _)| number.trim().parse().ok());
+    (Some(phase.trim().to_string()), percent)
+}
+
+/// Clone a repository with the git CLI, which (unlike libgit2) supports partial
+/// clones and sparse checkout. Progress is emitted as `git://clone-progress`.
+///
+/// # Arguments
+/// * `url` - Remote URL
+/// * `destination` - Directory to clone into (must not exist or be empty)
+/// * `branch` - Branch to check out instead of the remote's default
+/// * `depth` - Shallow clone depth
+/// * `filter` - `"blobless"` or `"treeless"` partial clone
+/// * `sparse_paths` - Check out only these directories (cone mode)
+/// * `token` - HTTPS token, used for this command only
+#[fluxel_command]
+#[allow(clippy::too_many_arguments)]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path, file_path), fields(category = "git"))
+    tracing::instrument(skip(app, token), fields(category = "git", workspace_root = %destination))
+)]
+pub async fn git_clone<R: Runtime>(
+    app: AppHandle<R>,
+    url: String,
+    destination: String,
+    branch: Option<String>,
+    depth: Option<u32>,
+    filter: Option<CloneFilter>,
+    sparse_paths: Option<Vec<String>>,
+    token: Option<String>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let target = PathBuf::from(&destination);
+        let occupied = std::fs::read_dir(&target)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false);
+        if occupied {
+            return Err(format!("Destination is not empty: {}", destination));
+        }
+        let sparse_paths = sparse_paths.filter(|paths| !paths.is_empty());
+
+        let mut cmd = git_cli::git_command(None);
+        git_cli::set_token_env(&mut cmd, token.as_deref());
+        cmd.args(["clone", "--progress"]);
+        if let Some(filter) = filter {
+            cmd.arg(format!("--filter={}", filter.spec()));
+        }
+        if let Some(depth) = depth {
+            cmd.arg(format!("--depth={}", depth));
+        }
+        if let Some(branch) = &branch {
+            cmd.args(["--branch", branch]);
+        }
+        if sparse_paths.is_some() {
+            // Starts with only top-level files checked out
+            cmd.arg("--sparse");
+        }
+        cmd.arg("--").arg(&url).arg(&target);
+
+        println!("[Git] Cloning {} into {}", url, destination);
+        git_cli::run_git_streaming(cmd, |line| {
+            let (phase, percent) = parse_progress(line);
+            let _ = app.emit(
+                "git://clone-progress",
+                CloneProgress {
+                    destination: destination.clone(),
+                    phase,
+                    percent,
+                    line: line.to_string(),
+                },
+            );
+        })?;
+
+        if let Some(paths) = sparse_paths {
+            git_sparse::set_sparse_paths(&target, &paths, true)?;
+        }
+        Ok(destination)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(
+        skip(root_path, file_path),
+        fields(
+            category = "git",
+            workspace_root = %root_path,
+            file = %file_path,
+            bytes_read = tracing::field::Empty,
+            files_read = tracing::field::Empty
+        )
+    )
 )]
-#[tauri::command]
 pub async fn git_read_file_at_head(root_path: String, file_path: String) -> Result<String, String> {
+    let io = IoAccounting::current();
     tauri::async_runtime::spawn_blocking(move || {
         let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
 
@@ -259,6 +472,7 @@ pub async fn git_read_file_at_head(root_path: String, file_path: String) -> Resu
 
         let object = entry.to_object(&repo).map_err(|e| e.to_string())?;
         let blob = object.as_blob().ok_or("Not a blob")?;
+        io.file_read(blob.size() as u64);
 
         let content = std::str::from_utf8(blob.content())
             .map_err(|_| "File content is not valid UTF-8")?
@@ -270,15 +484,40 @@ pub async fn git_read_file_at_head(root_path: String, file_path: String) -> Resu
     .map_err(|e| e.to_string())?
 }
 
+#[fluxel_command]
 #[cfg_attr(
     feature = "profiling",
-    tracing::instrument(skip(root_path, file_path), fields(category = "git"))
+    tracing::instrument(
+        skip(root_path, file_path, dirty_files),
+        fields(
+            category = "git",
+            workspace_root = %root_path,
+            file = %file_path,
+            bytes_written = tracing::field::Empty,
+            files_written = tracing::field::Empty
+        )
+    )
 )]
-#[tauri::command]
-pub async fn git_discard_changes(root_path: String, file_path: String) -> Result<String, String> {
+pub async fn git_discard_changes(
+    root_path: String,
+    file_path: String,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<String, String> {
+    let dirty_files = dirty_files.inner().clone();
+    let io = IoAccounting::current();
     tauri::async_runtime::spawn_blocking(move || {
         let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
 
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?;
+        dirty_files.guard(
+            "git_discard_changes",
+            &[workdir.join(&file_path)],
+            force.unwrap_or(false),
+        )?;
+
         // Force checkout the specific file from HEAD
         let mut checkout_opts = git2::build::CheckoutBuilder::new();
         checkout_opts.path(&file_path);
@@ -286,9 +525,211 @@ pub async fn git_discard_changes(root_path: String, file_path: String) -> Result
 
         repo.checkout_head(Some(&mut checkout_opts))
             .map_err(|e| e.to_string())?;
+        // Discarding an added file removes it; nothing is written then
+        if let Ok(metadata) = std::fs::metadata(workdir.join(&file_path)) {
+            io.file_written(metadata.len());
+        }
 
         Ok("Discarded changes successfully".to_string())
     })
     .await
     .map_err(|e| e.to_string())?
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReflogEntry {
+    /// Selector that resolves to this entry, e.g. `HEAD@{3}`
+    pub selector: String,
+    pub old_id: String,
+    pub new_id: String,
+    /// Reflog message, e.g. `reset: moving to HEAD~2`
+    pub message: String,
+    /// Summary of the commit the entry points to
+    pub summary: String,
+    pub committer: String,
+    /// Unix seconds
+    pub time: i64,
+    /// Whether a branch or tag still contains the commit; `false` means it
+    /// would be lost when the reflog expires
+    pub reachable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredBranch {
+    pub branch: String,
+    pub commit: String,
+    pub summary: String,
+}
+
+/// Whether any branch or tag tip is, or descends from, `oid`
+fn is_reachable(repo: &Repository, tips: &[git2::Oid], oid: git2::Oid) -> bool {
+    tips.iter()
+        .any(|tip| *tip == oid || repo.graph_descendant_of(*tip, oid).unwrap_or(false))
+}
+
+/// List reflog entries, newest first
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `limit` - Maximum number of entries (default: 100)
+/// * `reference` - Reference whose reflog to read (default: HEAD)
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(skip(root_path), fields(category = "git", workspace_root = %root_path))
+)]
+pub async fn git_reflog(
+    root_path: String,
+    limit: Option<usize>,
+    reference: Option<String>,
+) -> Result<Vec<GitReflogEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let reference = reference.unwrap_or_else(|| "HEAD".to_string());
+        let reflog = repo.reflog(&reference).map_err(|e| e.to_string())?;
+
+        let tips: Vec<git2::Oid> = repo
+            .references()
+            .map_err(|e| e.to_string())?
+            .flatten()
+            .filter(|r| r.is_branch() || r.is_remote() || r.is_tag())
+            .filter_map(|r| r.peel_to_commit().ok().map(|commit| commit.id()))
+            .collect();
+
+        let mut entries = Vec::new();
+        for (index, entry) in reflog.iter().take(limit.unwrap_or(100)).enumerate() {
+            let new_id = entry.id_new();
+            let summary = repo
+                .find_commit(new_id)
+                .ok()
+                .and_then(|commit| commit.summary().map(str::to_string))
+                .unwrap_or_default();
+            let committer = entry.committer();
+            entries.push(GitReflogEntry {
+                selector: format!("{}@{{{}}}", reference, index),
+                old_id: entry.id_old().to_string(),
+                new_id: new_id.to_string(),
+                message: entry.message().unwrap_or("").to_string(),
+                summary,
+                committer: committer.name().unwrap_or("").to_string(),
+                time: committer.when().seconds(),
+                reachable: is_reachable(&repo, &tips, new_id),
+            });
+        }
+        Ok(entries)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Create a branch at a lost commit so it survives reflog expiry. The current
+/// checkout is left alone.
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `target` - Commit, reference, or reflog selector (e.g. `HEAD@{3}`)
+/// * `new_branch` - Name of the branch to create; must not exist yet
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(skip(root_path), fields(category = "git", workspace_root = %root_path))
+)]
+pub async fn git_recover(
+    root_path: String,
+    target: String,
+    new_branch: String,
+) -> Result<RecoveredBranch, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if !git2::Branch::name_is_valid(&new_branch).unwrap_or(false) {
+            return Err(format!("'{}' is not a valid branch name", new_branch));
+        }
+        let commit = repo
+            .revparse_single(&target)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| format!("'{}' does not resolve to a commit", target))?;
+        repo.branch(&new_branch, &commit, false).map_err(|e| {
+            if e.code() == git2::ErrorCode::Exists {
+                format!("Branch '{}' already exists", new_branch)
+            } else {
+                e.to_string()
+            }
+        })?;
+
+        println!(
+            "[Git] Recovered {} as branch {}",
+            &commit.id().to_string()[..7],
+            new_branch
+        );
+        Ok(RecoveredBranch {
+            branch: new_branch,
+            commit: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Fetch the history missing from a shallow repository, or only `deepen` more
+/// commits of it. Progress is emitted as `git://fetch-progress`. Returns whether
+/// the repository is still shallow.
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `deepen` - Fetch this many more commits instead of the full history
+/// * `token` - HTTPS token, used for this command only
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(
+        skip(app, root_path, token),
+        fields(category = "git", workspace_root = %root_path)
+    )
+)]
+pub async fn git_unshallow<R: Runtime>(
+    app: AppHandle<R>,
+    root_path: String,
+    deepen: Option<u32>,
+    token: Option<String>,
+) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if !repo.is_shallow() {
+            return Err("Repository already has its full history".to_string());
+        }
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?;
+
+        let mut cmd = git_cli::git_command(Some(workdir));
+        git_cli::set_token_env(&mut cmd, token.as_deref());
+        cmd.args(["fetch", "--progress"]);
+        match deepen {
+            Some(deepen) => cmd.arg(format!("--deepen={}", deepen)),
+            None => cmd.arg("--unshallow"),
+        };
+
+        println!("[Git] Fetching history for {}", root_path);
+        git_cli::run_git_streaming(cmd, |line| {
+            let (phase, percent) = parse_progress(line);
+            let _ = app.emit(
+                "git://fetch-progress",
+                FetchProgress {
+                    root_path: root_path.clone(),
+                    phase,
+                    percent,
+                    line: line.to_string(),
+                },
+            );
+        })?;
+
+        // Reopen: libgit2 caches the shallow state
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        Ok(repo.is_shallow())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,447 @@
+//! Git Bisect
+//!
+//! Drives `git bisect` from the UI. The bisect itself is git's (so it can be
+//! continued from a terminal); the candidate set, remaining steps, and culprit are
+//! computed from the `refs/bisect/*` refs with libgit2. When a test command is
+//! given, each candidate is checked out and tested automatically, with the same
+//! exit code convention as `git bisect run`: 0 is good, 125 is skip, anything else
+//! up to 127 is bad.
+//!
+//! Automatic steps are emitted as `git://bisect-progress`.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::git_cli::run_git;
+use fluxel_macros::fluxel_command;
+use git2::{Oid, Repository};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+/// Test command exit code meaning "can't test this commit"
+const SKIP_EXIT_CODE: i32 = 125;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectCommit {
+    pub sha: String,
+    pub summary: String,
+    pub author: String,
+    /// Unix seconds
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectStatus {
+    pub active: bool,
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    /// Commit checked out for testing
+    pub current: Option<BisectCommit>,
+    /// Untested candidates left
+    pub remaining: usize,
+    /// Roughly how many more marks are needed
+    pub steps_left: u32,
+    /// First bad commit, once found
+    pub culprit: Option<BisectCommit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectProgress {
+    pub commit: String,
+    /// `"good"`, `"bad"`, or `"skip"`
+    pub result: &'static str,
+    pub remaining: usize,
+}
+
+fn commit_info(repo: &Repository, oid: Oid) -> Result<BisectCommit, String> {
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let author = commit.author();
+    Ok(BisectCommit {
+        sha: oid.to_string(),
+        summary: commit.summary().unwrap_or("").to_string(),
+        author: author.name().unwrap_or("").to_string(),
+        time: commit.time().seconds(),
+    })
+}
+
+fn is_bisecting(repo: &Repository) -> bool {
+    repo.path().join("BISECT_START").exists()
+}
+
+/// Bisect state from `refs/bisect/*` and HEAD
+pub fn read_status(repo: &Repository) -> Result<BisectStatus, String> {
+    if !is_bisecting(repo) {
+        return Ok(BisectStatus {
+            active: false,
+            bad: None,
+            good: Vec::new(),
+            skipped: Vec::new(),
+            current: None,
+            remaining: 0,
+            steps_left: 0,
+            culprit: None,
+        });
+    }
+
+    let mut bad = None;
+    let mut good = Vec::new();
+    let mut skipped = Vec::new();
+    for reference in repo
+        .references_glob("refs/bisect/*")
+        .map_err(|e| e.to_string())?
+        .flatten()
+    {
+        let (Some(name), Some(target)) = (reference.name(), reference.target()) else {
+            continue;
+        };
+        let name = name.trim_start_matches("refs/bisect/");
+        if name == "bad" {
+            bad = Some(target);
+        } else if name.starts_with("good-") {
+            good.push(target);
+        } else if name.starts_with("skip-") {
+            skipped.push(target);
+        }
+    }
+
+    // Candidates: the bad commit and its ancestors that no good commit reaches
+    let mut candidates = Vec::new();
+    if let Some(bad) = bad {
+        let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+        walk.push(bad).map_err(|e| e.to_string())?;
+        for oid in &good {
+            walk.hide(*oid).map_err(|e| e.to_string())?;
+        }
+        candidates = walk
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+    }
+    let skipped_set: HashSet<Oid> = skipped.iter().copied().collect();
+    let remaining = candidates
+        .iter()
+        .filter(|oid| Some(**oid) != bad && !skipped_set.contains(oid))
+        .count();
+    // Only known once there is a good commit to bound the search
+    let culprit = match bad {
+        Some(bad) if !good.is_empty() && candidates.len() == 1 => Some(commit_info(repo, bad)?),
+        _ => None,
+    };
+    let current = match repo.head().ok().and_then(|head| head.target()) {
+        Some(oid) if culprit.is_none() => Some(commit_info(repo, oid)?),
+        _ => None,
+    };
+
+    Ok(BisectStatus {
+        active: true,
+        bad: bad.map(|oid| oid.to_string()),
+        good: good.iter().map(Oid::to_string).collect(),
+        skipped: skipped.iter().map(Oid::to_string).collect(),
+        current,
+        remaining,
+        steps_left: (remaining + 1).next_power_of_two().trailing_zeros(),
+        culprit,
+    })
+}
+
+/// Run `test_command` in `workdir` through the platform shell; `None` if it
+/// couldn't be run or was killed by a signal
+fn run_test(workdir: &Path, test_command: &str) -> Result<Option<i32>, String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C")
+            .arg(test_command)
+            .creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(test_command);
+        cmd
+    };
+    let status = cmd
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run test command: {}", e))?;
+    Ok(status.code())
+}
+
+/// Test and mark candidates until the culprit is found or only skipped commits
+/// are left. A test that can't run or exits with 128 or more stops the bisect
+/// where it is, as `git bisect run` does.
+pub fn run_bisect(
+    repo: &Repository,
+    test_command: &str,
+    mut on_progress: impl FnMut(BisectProgress),
+) -> Result<BisectStatus, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory")?;
+    loop {
+        let status = read_status(repo)?;
+        let Some(current) = status.current.as_ref() else {
+            return Ok(status);
+        };
+        if status.culprit.is_some() || status.remaining == 0 {
+            return Ok(status);
+        }
+
+        let result = match run_test(workdir, test_command)? {
+            Some(0) => "good",
+            Some(SKIP_EXIT_CODE) => "skip",
+            Some(code) if (1..128).contains(&code) => "bad",
+            Some(code) => {
+                return Err(format!(
+                    "Test command exited with {} at {}; bisect paused",
+                    code,
+                    &current.sha[..7]
+                ))
+            }
+            None => return Err("Test command was terminated; bisect paused".to_string()),
+        };
+        println!("[GitBisect] {} is {}", &current.sha[..7], result);
+        let commit = current.sha.clone();
+        mark(workdir, result)?;
+        on_progress(BisectProgress {
+            commit,
+            result,
+            remaining: read_status(repo)?.remaining,
+        });
+    }
+}
+
+/// Full id of the commit `rev` names. Anything starting with `-` is refused so a
+/// value can't be read as an option by the git commands it is passed to.
+fn resolve_commit(workdir: &Path, rev: &str) -> Result<String, String> {
+    let rev = rev.trim();
+    if rev.is_empty() || rev.starts_with('-') {
+        return Err(format!("'{}' is not a commit", rev));
+    }
+    run_git(
+        workdir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ],
+    )
+    .map(|output| output.trim().to_string())
+    .map_err(|_| format!("'{}' is not a commit", rev))
+}
+
+fn mark(workdir: &Path, state: &str) -> Result<(), String> {
+    match run_git(workdir, &["bisect", state]) {
+        Ok(_) => Ok(()),
+        // Git exits non-zero when only skipped commits are left; the status says so
+        Err(e) if state == "skip" && e.contains("only skipped commits") => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Start bisecting between a good and a bad commit, and optionally run it to the end
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `good` - A commit known to work
+/// * `bad` - A commit known to be broken (default: HEAD)
+/// * `test_command` - Shell command to test each candidate automatically
+/// * `force` - Proceed even if files have unsaved editor changes (bisect checks out commits)
+#[fluxel_command]
+#[allow(clippy::too_many_arguments)]
+pub async fn git_bisect_start<R: Runtime>(
+    app: AppHandle<R>,
+    root_path: String,
+    good: String,
+    bad: Option<String>,
+    test_command: Option<String>,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<BisectStatus, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?
+            .to_path_buf();
+        if is_bisecting(&repo) {
+            return Err("A bisect is already in progress".to_string());
+        }
+        dirty_files.guard(
+            "git_bisect_start",
+            std::slice::from_ref(&workdir),
+            force.unwrap_or(false),
+        )?;
+
+        let bad = resolve_commit(&workdir, bad.as_deref().unwrap_or("HEAD"))?;
+        let good = resolve_commit(&workdir, &good)?;
+        run_git(&workdir, &["bisect", "start", &bad, &good, "--"])?;
+        println!("[GitBisect] Started between {} and {}", good, bad);
+
+        match test_command.filter(|command| !command.trim().is_empty()) {
+            Some(command) => run_bisect(&repo, &command, |progress| {
+                let _ = app.emit("git://bisect-progress", progress);
+            }),
+            None => read_status(&repo),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Mark the checked-out commit and move to the next candidate
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `state` - `"good"`, `"bad"`, or `"skip"`
+/// * `force` - Proceed even if files have unsaved editor changes (marking checks out the next candidate)
+#[fluxel_command]
+pub async fn git_bisect_mark(
+    root_path: String,
+    state: String,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<BisectStatus, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if !matches!(state.as_str(), "good" | "bad" | "skip") {
+            return Err(format!("Unknown bisect state '{}'", state));
+        }
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if !is_bisecting(&repo) {
+            return Err("No bisect in progress".to_string());
+        }
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?
+            .to_path_buf();
+        dirty_files.guard(
+            "git_bisect_mark",
+            std::slice::from_ref(&workdir),
+            force.unwrap_or(false),
+        )?;
+        mark(&workdir, &state)?;
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Current bisect state, including the culprit once it is found
+#[fluxel_command]
+pub async fn git_bisect_status(root_path: String) -> Result<BisectStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// End the bisect and return to the commit checked out before it started
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `force` - Proceed even if files have unsaved editor changes (reset checks out the original commit)
+#[fluxel_command]
+pub async fn git_bisect_reset(
+    root_path: String,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<BisectStatus, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?
+            .to_path_buf();
+        dirty_files.guard(
+            "git_bisect_reset",
+            std::slice::from_ref(&workdir),
+            force.unwrap_or(false),
+        )?;
+        run_git(&workdir, &["bisect", "reset"])?;
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn finds_culprit_with_test_command() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-git-bisect-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let mut commits = Vec::new();
+        for (i, value) in ["pass", "pass", "pass", "fail", "fail", "fail"]
+            .iter()
+            .enumerate()
+        {
+            std::fs::write(dir.join("state.txt"), value).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("state.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let oid = repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &format!("commit {}", i),
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            commits.push(oid.to_string());
+        }
+
+        assert!(!read_status(&repo).unwrap().active);
+        assert_eq!(resolve_commit(&dir, "HEAD").unwrap(), commits[5]);
+        assert!(resolve_commit(&dir, "--help").is_err());
+        assert!(resolve_commit(&dir, "no-such-ref").is_err());
+        run_git(&dir, &["bisect", "start", "HEAD", &commits[0], "--"]).unwrap();
+        let started = read_status(&repo).unwrap();
+        assert!(started.active && started.culprit.is_none());
+        assert_eq!(started.remaining, 4);
+
+        let mut steps = 0;
+        let status = run_bisect(&repo, "grep -q pass state.txt", |_| steps += 1).unwrap();
+        assert_eq!(status.culprit.unwrap().sha, commits[3]);
+        assert!(steps <= 3);
+
+        run_git(&dir, &["bisect", "reset"]).unwrap();
+        assert!(!read_status(&repo).unwrap().active);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,158 @@
+//! Git CLI
+//!
+//! Runs the `git` executable for features libgit2 doesn't implement, such as
+//! partial clones and sparse checkout. Commands never prompt (credentials come
+//! from the caller's token) and don't open console windows on Windows.
+
+use base64::Engine;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Lines of stderr kept for the error of a failed streaming command
+const ERROR_TAIL_LINES: usize = 20;
+
+/// A `git` command running in `cwd`
+pub fn git_command(cwd: Option<&Path>) -> Command {
+    let mut cmd = Command::new("git");
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd);
+    }
+    cmd.env("GIT_TERMINAL_PROMPT", "0").stdin(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// Authenticate the HTTPS requests of `cmd` with `token`, without writing it to
+/// the repository config. The header is passed through `GIT_CONFIG_*`
+/// environment variables (git 2.31+) rather than `-c`, so the token never shows
+/// up in the process list.
+pub fn set_token_env(cmd: &mut Command, token: Option<&str>) {
+    if let Some(token) = token.filter(|token| !token.is_empty()) {
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("oauth2:{}", token));
+        cmd.env("GIT_CONFIG_COUNT", "1")
+            .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+            .env(
+                "GIT_CONFIG_VALUE_0",
+                format!("Authorization: Basic {}", credentials),
+            );
+    }
+}
+
+fn spawn_error(e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        "Git executable not found; install git and make sure it is on PATH".to_string()
+    } else {
+        format!("Failed to run git: {}", e)
+    }
+}
+
+/// Run git with `args` in `cwd` and return its stdout
+pub fn run_git(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let output = git_command(Some(cwd))
+        .args(args)
+        .output()
+        .map_err(spawn_error)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run git with `args` in `cwd`, feeding `input` to stdin, and return the raw
+/// output whatever the exit status
+pub fn run_git_with_input(
+    cwd: &Path,
+    args: &[&str],
+    input: &[u8],
+) -> Result<std::process::Output, String> {
+    let mut child = git_command(Some(cwd))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Write from another thread so a large input can't deadlock against full pipes
+    let mut stdin = child.stdin.take().ok_or("Failed to open git stdin")?;
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || {
+        use std::io::Write;
+        let _ = stdin.write_all(&input);
+    });
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let _ = writer.join();
+    Ok(output)
+}
+
+/// Run `cmd`, passing each line of stderr (where git reports progress) to `on_line`.
+/// Progress updates end in `\r` rather than `\n`; both count as line ends.
+pub fn run_git_streaming(mut cmd: Command, mut on_line: impl FnMut(&str)) -> Result<(), String> {
+    let mut child = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    let mut tail: Vec<String> = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buffer = [0u8; 4096];
+        let mut line = Vec::new();
+        loop {
+            let read = match stderr.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            for byte in &buffer[..read] {
+                if *byte != b'\r' && *byte != b'\n' {
+                    line.push(*byte);
+                    continue;
+                }
+                if line.is_empty() {
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&line).trim_end().to_string();
+                line.clear();
+                on_line(&text);
+                tail.push(text);
+                let excess = tail.len().saturating_sub(ERROR_TAIL_LINES);
+                tail.drain(..excess);
+            }
+        }
+        if !line.is_empty() {
+            let text = String::from_utf8_lossy(&line).trim_end().to_string();
+            on_line(&text);
+            tail.push(text);
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        return Ok(());
+    }
+    // Progress lines are noise in an error; keep the messages
+    let messages: Vec<&str> = tail
+        .iter()
+        .map(String::as_str)
+        .filter(|line| !line.contains('%'))
+        .collect();
+    if messages.is_empty() {
+        Err(format!("git exited with {}", status))
+    } else {
+        Err(messages.join("\n"))
+    }
+}
@@ -0,0 +1,365 @@
+//! Ref Diffs
+//!
+//! Structured diffs between two commits, branches, or tags for branch comparison
+//! and pull request previews. By default the head is compared with its merge base
+//! with the base (`base...head`, what a pull request shows), so commits that only
+//! landed on the base don't appear as reverted changes.
+
+use fluxel_macros::fluxel_command;
+use git2::{Delta, DiffFindOptions, DiffOptions, Patch, Repository};
+use serde::{Deserialize, Serialize};
+
+/// Diff lines kept per file; the rest of a huge file diff is dropped
+const MAX_LINES_PER_FILE: usize = 5000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffRefsOptions {
+    /// Limit the diff to these repository-relative paths or directories
+    pub paths: Vec<String>,
+    /// Compare with the merge base of `base` and `head` (default) instead of `base` itself
+    pub merge_base: bool,
+    /// Unchanged lines around each hunk
+    pub context_lines: u32,
+}
+
+impl Default for DiffRefsOptions {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            merge_base: true,
+            context_lines: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// `"context"`, `"addition"`, or `"deletion"`
+    pub kind: &'static str,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// e.g. `@@ -10,6 +10,8 @@ fn main() {`
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffFile {
+    pub path: String,
+    /// Previous path of a renamed or copied file
+    pub old_path: Option<String>,
+    /// `"added"`, `"deleted"`, `"modified"`, `"renamed"`, `"copied"`, or `"typechange"`
+    pub status: &'static str,
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+    /// Hunks were cut off at the per-file line limit
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefDiff {
+    /// Resolved commit the diff starts from (the merge base, unless disabled)
+    pub base: String,
+    pub head: String,
+    pub files: Vec<DiffFile>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+fn delta_status(status: Delta) -> &'static str {
+    match status {
+        Delta::Added | Delta::Untracked => "added",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        _ => "modified",
+    }
+}
+
+fn file_diff(patch: &Patch) -> Result<DiffFile, String> {
+    let delta = patch.delta();
+    let path_of = |file: git2::DiffFile| {
+        file.path()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+    };
+    let new_path = path_of(delta.new_file());
+    let old_path = path_of(delta.old_file());
+    let status = delta_status(delta.status());
+    let path = new_path
+        .clone()
+        .or_else(|| old_path.clone())
+        .unwrap_or_default();
+    let old_path = match status {
+        "renamed" | "copied" => old_path,
+        _ => None,
+    };
+
+    let (_, additions, deletions) = patch.line_stats().map_err(|e| e.to_string())?;
+    let mut hunks = Vec::new();
+    let mut kept = 0;
+    let mut truncated = false;
+    'hunks: for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index).map_err(|e| e.to_string())?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            if kept == MAX_LINES_PER_FILE {
+                truncated = true;
+                if !lines.is_empty() {
+                    hunks.push(hunk_info(&hunk, lines));
+                }
+                break 'hunks;
+            }
+            let line = patch
+                .line_in_hunk(hunk_index, line_index)
+                .map_err(|e| e.to_string())?;
+            let kind = match line.origin() {
+                '+' => "addition",
+                '-' => "deletion",
+                ' ' => "context",
+                // "\ No newline at end of file" markers
+                _ => continue,
+            };
+            lines.push(DiffLine {
+                kind,
+                old_line: line.old_lineno(),
+                new_line: line.new_lineno(),
+                content: String::from_utf8_lossy(line.content())
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string(),
+            });
+            kept += 1;
+        }
+        hunks.push(hunk_info(&hunk, lines));
+    }
+
+    Ok(DiffFile {
+        path,
+        old_path,
+        status,
+        binary: delta.flags().is_binary(),
+        additions,
+        deletions,
+        hunks,
+        truncated,
+    })
+}
+
+fn hunk_info(hunk: &git2::DiffHunk, lines: Vec<DiffLine>) -> DiffHunk {
+    DiffHunk {
+        header: String::from_utf8_lossy(hunk.header())
+            .trim_end()
+            .to_string(),
+        old_start: hunk.old_start(),
+        old_lines: hunk.old_lines(),
+        new_start: hunk.new_start(),
+        new_lines: hunk.new_lines(),
+        lines,
+    }
+}
+
+/// Structured diff from `base` (or its merge base with `head`) to `head`
+pub fn diff_refs(
+    repo: &Repository,
+    base: &str,
+    head: &str,
+    options: &DiffRefsOptions,
+) -> Result<RefDiff, String> {
+    let resolve = |spec: &str| {
+        repo.revparse_single(spec)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| format!("'{}' does not resolve to a commit", spec))
+    };
+    let head_commit = resolve(head)?;
+    let mut base_commit = resolve(base)?;
+    if options.merge_base {
+        let merge_base = repo
+            .merge_base(base_commit.id(), head_commit.id())
+            .map_err(|_| format!("'{}' and '{}' have no common history", base, head))?;
+        base_commit = repo.find_commit(merge_base).map_err(|e| e.to_string())?;
+    }
+
+    let mut diff_options = DiffOptions::new();
+    diff_options.context_lines(options.context_lines);
+    for path in &options.paths {
+        diff_options.pathspec(path.replace('\\', "/"));
+    }
+    let base_tree = base_commit.tree().map_err(|e| e.to_string())?;
+    let head_tree = head_commit.tree().map_err(|e| e.to_string())?;
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut diff_options))
+        .map_err(|e| e.to_string())?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(|e| e.to_string())?;
+
+    let mut files = Vec::with_capacity(diff.deltas().len());
+    for index in 0..diff.deltas().len() {
+        match Patch::from_diff(&diff, index).map_err(|e| e.to_string())? {
+            Some(patch) => files.push(file_diff(&patch)?),
+            // Binary files have no patch; report them without hunks
+            None => {
+                let delta = diff.get_delta(index).ok_or("Missing diff delta")?;
+                let path = delta
+                    .new_file()
+                    .path()
+                    .or(delta.old_file().path())
+                    .map(|path| path.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default();
+                files.push(DiffFile {
+                    path,
+                    old_path: None,
+                    status: delta_status(delta.status()),
+                    binary: true,
+                    additions: 0,
+                    deletions: 0,
+                    hunks: Vec::new(),
+                    truncated: false,
+                });
+            }
+        }
+    }
+
+    Ok(RefDiff {
+        base: base_commit.id().to_string(),
+        head: head_commit.id().to_string(),
+        additions: files.iter().map(|file| file.additions).sum(),
+        deletions: files.iter().map(|file| file.deletions).sum(),
+        files,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Diff two commits, branches, or tags as per-file hunks with line numbers
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `base` - What `head` is compared with, e.g. `main`
+/// * `head` - The changed side, e.g. a feature branch or `HEAD`
+/// * `options` - `paths` to limit the diff, `mergeBase: false` to compare with
+///   `base` directly, `contextLines` around each hunk
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(
+        skip(root_path, options),
+        fields(category = "git", workspace_root = %root_path)
+    )
+)]
+pub async fn git_diff_refs(
+    root_path: String,
+    base: String,
+    head: String,
+    options: Option<DiffRefsOptions>,
+) -> Result<RefDiff, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        diff_refs(&repo, &base, &head, &options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn commit(repo: &Repository, files: &[(&str, &str)], message: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (name, content) in files {
+            std::fs::write(workdir.join(name), content).unwrap();
+            index.add_path(Path::new(name)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn diffs_branch_against_merge_base() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-git-diff-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let root = commit(&repo, &[("a.txt", "one\ntwo\nthree\n")], "init");
+        let feature = commit(
+            &repo,
+            &[("a.txt", "one\n2\nthree\n"), ("b.txt", "b\n")],
+            "feature",
+        );
+
+        // A commit only on the base branch
+        repo.branch("main", &repo.find_commit(root).unwrap(), false)
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        commit(&repo, &[("c.txt", "c\n")], "main only");
+
+        let feature = feature.to_string();
+        let diff = diff_refs(&repo, "main", &feature, &DiffRefsOptions::default()).unwrap();
+        assert_eq!(diff.base, root.to_string());
+        let paths: Vec<&str> = diff.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.txt", "b.txt"]);
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+
+        let a = &diff.files[0];
+        assert_eq!(a.status, "modified");
+        let changed: Vec<(&str, Option<u32>, Option<u32>)> = a.hunks[0]
+            .lines
+            .iter()
+            .filter(|line| line.kind != "context")
+            .map(|line| (line.kind, line.old_line, line.new_line))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![("deletion", Some(2), None), ("addition", None, Some(2))]
+        );
+        assert_eq!(diff.files[1].status, "added");
+
+        // Directly against main, c.txt looks deleted
+        let direct = DiffRefsOptions {
+            merge_base: false,
+            paths: vec!["c.txt".to_string()],
+            ..Default::default()
+        };
+        let diff = diff_refs(&repo, "main", &feature, &direct).unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].status, "deleted");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
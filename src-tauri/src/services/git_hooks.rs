@@ -0,0 +1,268 @@
+//! Git Hooks
+//!
+//! Runs the repository's client-side commit hooks (`pre-commit`, `commit-msg`,
+//! `post-commit`) for commits made through libgit2, which never runs them itself.
+//! Hooks are looked up in `core.hooksPath` (husky v9 and similar setups) or
+//! `.git/hooks`, their output is reported line by line, and a failing hook is
+//! returned as the error string holding a JSON [`HookFailedError`].
+
+use git2::Repository;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Lines of hook output kept for the failure error
+const FAILURE_OUTPUT_LINES: usize = 40;
+
+/// One line of hook output
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutput {
+    pub hook: String,
+    /// `"stdout"` or `"stderr"`
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Structured failure of a blocking hook
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookFailedError {
+    /// Always `"hookFailed"`, to tell this error apart from plain messages
+    pub kind: &'static str,
+    pub hook: String,
+    pub exit_code: Option<i32>,
+    /// Trailing output of the hook
+    pub output: Vec<String>,
+    pub message: String,
+}
+
+pub type HookOutputSink = Arc<dyn Fn(HookOutput) + Send + Sync>;
+
+/// Directory hooks are loaded from: `core.hooksPath` (relative to the work tree)
+/// or the repository's `hooks` directory
+pub fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok());
+    match (configured, repo.workdir()) {
+        (Some(path), Some(workdir)) if path.is_relative() => workdir.join(path),
+        (Some(path), _) => path,
+        (None, _) => repo.path().join("hooks"),
+    }
+}
+
+/// The hook script for `name`, if present and runnable (git skips hooks that
+/// aren't executable)
+pub fn find_hook(repo: &Repository, name: &str) -> Option<PathBuf> {
+    let path = hooks_dir(repo).join(name);
+    let metadata = std::fs::metadata(&path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            println!("[GitHooks] Ignoring {:?}: not executable", path);
+            return None;
+        }
+    }
+    Some(path)
+}
+
+/// Run hook `name` with `args` if the repository has it. Returns whether it ran.
+pub fn run_hook(
+    repo: &Repository,
+    name: &str,
+    args: &[&str],
+    sink: &HookOutputSink,
+) -> Result<bool, String> {
+    let Some(hook) = find_hook(repo, name) else {
+        return Ok(false);
+    };
+    let workdir = repo
+        .workdir()
+        .ok_or("Cannot run hooks in a bare repository")?;
+
+    // Hook scripts are shell scripts; Windows has no shebang handling, so go through
+    // the `sh` that Git for Windows ships
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.arg(&hook);
+        c
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = Command::new(&hook);
+
+    cmd.args(args)
+        .current_dir(workdir)
+        .env("GIT_INDEX_FILE", repo.path().join("index"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    println!("[GitHooks] Running {}", name);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run {} hook: {}", name, e))?;
+
+    let tail = Arc::new(Mutex::new(Vec::new()));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward_lines(name, "stdout", stdout, sink, &tail));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward_lines(name, "stderr", stderr, sink, &tail));
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {} hook: {}", name, e))?;
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    if status.success() {
+        return Ok(true);
+    }
+    let output = std::mem::take(&mut *tail.lock().unwrap());
+    let error = HookFailedError {
+        kind: "hookFailed",
+        hook: name.to_string(),
+        exit_code: status.code(),
+        message: match status.code() {
+            Some(code) => format!("{} hook failed with exit code {}", name, code),
+            None => format!("{} hook was terminated", name),
+        },
+        output,
+    };
+    Err(serde_json::to_string(&error).unwrap_or(error.message))
+}
+
+/// Forward each line of `pipe` to `sink`, keeping the last lines in `tail`
+fn forward_lines(
+    hook: &str,
+    stream: &'static str,
+    pipe: impl Read + Send + 'static,
+    sink: &HookOutputSink,
+    tail: &Arc<Mutex<Vec<String>>>,
+) -> std::thread::JoinHandle<()> {
+    let hook = hook.to_string();
+    let sink = Arc::clone(sink);
+    let tail = Arc::clone(tail);
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            {
+                let mut tail = tail.lock().unwrap();
+                tail.push(line.clone());
+                let excess = tail.len().saturating_sub(FAILURE_OUTPUT_LINES);
+                tail.drain(..excess);
+            }
+            sink(HookOutput {
+                hook: hook.clone(),
+                stream,
+                line,
+            });
+        }
+    })
+}
+
+/// Run `commit-msg` on `message` and return the message as the hook left it
+pub fn run_commit_msg_hook(
+    repo: &Repository,
+    message: &str,
+    sink: &HookOutputSink,
+) -> Result<String, String> {
+    if find_hook(repo, "commit-msg").is_none() {
+        return Ok(message.to_string());
+    }
+    let path = repo.path().join("COMMIT_EDITMSG");
+    std::fs::write(&path, message).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    run_hook(repo, "commit-msg", &[&path_arg(&path)], sink)?;
+    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))
+}
+
+fn path_arg(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_hook(dir: &Path, name: &str, script: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn runs_hooks_from_hooks_path_and_reports_failures() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-git-hooks-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let collected = Arc::clone(&lines);
+        let sink: HookOutputSink = Arc::new(move |output: HookOutput| {
+            collected
+                .lock()
+                .unwrap()
+                .push(format!("{}:{}", output.hook, output.line));
+        });
+
+        assert_eq!(hooks_dir(&repo), repo.path().join("hooks"));
+        assert!(!run_hook(&repo, "pre-commit", &[], &sink).unwrap());
+
+        repo.config()
+            .unwrap()
+            .set_str("core.hooksPath", ".husky/_")
+            .unwrap();
+        let husky = dir.join(".husky/_");
+        write_hook(
+            &husky,
+            "pre-commit",
+            "#!/bin/sh\necho linting\necho 'lint failed' >&2\nexit 3\n",
+        );
+        write_hook(
+            &husky,
+            "commit-msg",
+            "#!/bin/sh\nprintf 'feat: %s' \"$(cat \"$1\")\" > \"$1\"\n",
+        );
+
+        let error = run_hook(&repo, "pre-commit", &[], &sink).unwrap_err();
+        let error: serde_json::Value = serde_json::from_str(&error).unwrap();
+        assert_eq!(error["kind"], "hookFailed");
+        assert_eq!(error["hook"], "pre-commit");
+        assert_eq!(error["exitCode"], 3);
+        assert_eq!(error["output"].as_array().unwrap().len(), 2);
+
+        let message = run_commit_msg_hook(&repo, "add hooks", &sink).unwrap();
+        assert_eq!(message, "feat: add hooks");
+
+        let mut lines = lines.lock().unwrap().clone();
+        lines.sort();
+        assert_eq!(lines, vec!["pre-commit:lint failed", "pre-commit:linting"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,328 @@
+//! Git Patches
+//!
+//! Creates unified diffs of staged or unstaged changes and applies patches to the
+//! working tree, so changes can be shared without leaving the editor. Patches are
+//! applied with `git apply` (all or nothing, and reversible, which libgit2 can't
+//! do); files it can't patch are reported as conflicts instead of failing the
+//! command outright.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::git_cli::run_git_with_input;
+use fluxel_macros::fluxel_command;
+use git2::{Diff, DiffFormat, DiffOptions, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::State;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CreatePatchOptions {
+    /// Diff the index against HEAD instead of the working tree against the index
+    pub staged: bool,
+    /// Limit the patch to these repository-relative paths or directories
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApplyPatchOptions {
+    /// Undo the patch instead of applying it
+    pub reverse: bool,
+    /// Only check whether the patch applies cleanly
+    pub check: bool,
+    /// Proceed even if patched files have unsaved editor changes
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchConflict {
+    pub path: String,
+    /// Line of the first hunk that didn't match, when git reports it
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPatchResult {
+    /// Whether the working tree was changed (never with `check`)
+    pub applied: bool,
+    /// Files the patch touches
+    pub files: Vec<String>,
+    /// Files the patch doesn't apply to; when non-empty nothing was changed
+    pub conflicts: Vec<PatchConflict>,
+}
+
+/// Render `diff` as unified diff text
+fn diff_to_patch(diff: &Diff) -> Result<String, String> {
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if let origin @ ('+' | '-' | ' ') = line.origin() {
+            patch.push(origin);
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .map_err(|e| e.to_string())?;
+    Ok(patch)
+}
+
+/// Unified diff of the staged or unstaged changes in `repo`
+pub fn create_patch(repo: &Repository, options: &CreatePatchOptions) -> Result<String, String> {
+    let mut diff_options = DiffOptions::new();
+    diff_options.show_binary(true);
+    for path in &options.paths {
+        diff_options.pathspec(path.replace('\\', "/"));
+    }
+
+    let diff = if options.staged {
+        let head = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(|e| e.to_string())?),
+            // Unborn branch: everything staged is new
+            Err(_) => None,
+        };
+        repo.diff_tree_to_index(head.as_ref(), None, Some(&mut diff_options))
+    } else {
+        diff_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        repo.diff_index_to_workdir(None, Some(&mut diff_options))
+    }
+    .map_err(|e| e.to_string())?;
+
+    diff_to_patch(&diff)
+}
+
+/// Repository-relative files a patch touches
+fn patch_files(patch: &str) -> Result<Vec<String>, String> {
+    let diff = Diff::from_buffer(patch.as_bytes()).map_err(|e| format!("Invalid patch: {}", e))?;
+    let mut files: Vec<String> = diff
+        .deltas()
+        .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+        .flatten()
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Conflicts from `git apply` error output, e.g.
+/// `error: patch failed: src/main.rs:12` followed by `error: src/main.rs: patch does not apply`
+fn parse_apply_errors(stderr: &str) -> Vec<PatchConflict> {
+    let mut conflicts: Vec<PatchConflict> = Vec::new();
+    for line in stderr.lines() {
+        let Some(error) = line.trim().strip_prefix("error: ") else {
+            continue;
+        };
+        if let Some(location) = error.strip_prefix("patch failed: ") {
+            let (path, line) = match location.rsplit_once(':') {
+                Some((path, line)) => (path, line.parse().ok()),
+                None => (location, None),
+            };
+            conflicts.push(PatchConflict {
+                path: path.to_string(),
+                line,
+                message: "Patch failed".to_string(),
+            });
+        } else if let Some((path, message)) = error.split_once(": ") {
+            // Refines the "patch failed" entry for the same file, if there is one
+            match conflicts.iter_mut().find(|conflict| conflict.path == path) {
+                Some(conflict) => conflict.message = capitalize(message),
+                None => conflicts.push(PatchConflict {
+                    path: path.to_string(),
+                    line: None,
+                    message: capitalize(message),
+                }),
+            }
+        }
+    }
+    conflicts
+}
+
+fn capitalize(message: &str) -> String {
+    let mut chars = message.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Apply (or check, or reverse) `patch` in the working tree of `repo`
+pub fn apply_patch(
+    repo: &Repository,
+    patch: &str,
+    options: &ApplyPatchOptions,
+) -> Result<ApplyPatchResult, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory")?;
+    let files = patch_files(patch)?;
+    if files.is_empty() {
+        return Err("Patch contains no changes".to_string());
+    }
+
+    let mut args = vec!["apply", "--verbose"];
+    if options.reverse {
+        args.push("--reverse");
+    }
+    if options.check {
+        args.push("--check");
+    }
+    let output = run_git_with_input(workdir, &args, patch.as_bytes())?;
+
+    if output.status.success() {
+        return Ok(ApplyPatchResult {
+            applied: !options.check,
+            files,
+            conflicts: Vec::new(),
+        });
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let conflicts = parse_apply_errors(&stderr);
+    if conflicts.is_empty() {
+        return Err(format!("git apply failed: {}", stderr.trim()));
+    }
+    Ok(ApplyPatchResult {
+        applied: false,
+        files,
+        conflicts,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Create a unified diff of the working tree or staged changes
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `options` - `staged` to diff the index against HEAD; `paths` to limit the patch
+#[fluxel_command]
+pub async fn git_create_patch(
+    root_path: String,
+    options: Option<CreatePatchOptions>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        create_patch(&repo, &options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Apply a unified diff to the working tree, reporting files it doesn't apply to
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `patch` - Unified diff text (`git diff` / `git format-patch` output)
+/// * `options` - `reverse` to undo the patch, `check` for a dry run, `force` to
+///   ignore unsaved editor changes
+#[fluxel_command]
+pub async fn git_apply_patch(
+    root_path: String,
+    patch: String,
+    options: Option<ApplyPatchOptions>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<ApplyPatchResult, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let options = options.unwrap_or_default();
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if !options.check {
+            let workdir = repo
+                .workdir()
+                .ok_or("Repository has no working directory")?;
+            let affected: Vec<PathBuf> = patch_files(&patch)?
+                .iter()
+                .map(|file| workdir.join(file))
+                .collect();
+            dirty_files.guard("git_apply_patch", &affected, options.force)?;
+        }
+
+        let result = apply_patch(&repo, &patch, &options)?;
+        println!(
+            "[GitPatch] {} patch to {} file(s), {} conflict(s)",
+            if options.check { "Checked" } else { "Applied" },
+            result.files.len(),
+            result.conflicts.len()
+        );
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_applies_and_reverses_patches() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-git-patch-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        std::fs::write(&file, "one\n2\nthree\n").unwrap();
+        let patch = create_patch(&repo, &CreatePatchOptions::default()).unwrap();
+        assert!(patch.contains("-two\n+2\n"));
+        assert_eq!(patch_files(&patch).unwrap(), vec!["notes.txt"]);
+        let staged = CreatePatchOptions {
+            staged: true,
+            paths: Vec::new(),
+        };
+        assert!(create_patch(&repo, &staged).unwrap().is_empty());
+
+        // Already applied: forward conflicts, reverse applies
+        let forward = apply_patch(&repo, &patch, &ApplyPatchOptions::default()).unwrap();
+        assert!(!forward.applied);
+        assert_eq!(forward.conflicts[0].path, "notes.txt");
+
+        let reverse = ApplyPatchOptions {
+            reverse: true,
+            ..Default::default()
+        };
+        assert!(apply_patch(&repo, &patch, &reverse).unwrap().applied);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\ntwo\nthree\n");
+
+        let check = ApplyPatchOptions {
+            check: true,
+            ..Default::default()
+        };
+        let checked = apply_patch(&repo, &patch, &check).unwrap();
+        assert!(!checked.applied && checked.conflicts.is_empty());
+        assert!(
+            apply_patch(&repo, &patch, &ApplyPatchOptions::default())
+                .unwrap()
+                .applied
+        );
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\n2\nthree\n");
+
+        assert_eq!(
+            parse_apply_errors(
+                "error: patch failed: src/a.rs:12\nerror: src/a.rs: patch does not apply\n"
+            )[0]
+            .line,
+            Some(12)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
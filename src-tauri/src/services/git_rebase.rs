@@ -0,0 +1,664 @@
+//! Interactive Rebase
+//!
+//! Backs the visual interactive rebase: [`git_rebase_plan`] lists the commits
+//! between an upstream and HEAD as editable steps, and [`git_rebase_execute`]
+//! runs `git rebase -i` with the edited todo list in place of an editor. The
+//! rebase pauses on conflicts (and `edit` steps) exactly as on the command line,
+//! so it can be continued, skipped, or aborted from the UI or a terminal.
+//!
+//! Progress is emitted as `git://rebase-progress`.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::git_cli::{git_command, run_git_streaming};
+use fluxel_macros::fluxel_command;
+use git2::{Oid, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    /// Folds into the previous commit
+    fn is_squash(self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseStep {
+    pub action: RebaseAction,
+    pub commit: String,
+    /// First line of the original message
+    #[serde(default)]
+    pub summary: String,
+    /// Original message
+    #[serde(default)]
+    pub message: String,
+    /// Replacement message for a `reword`, or for the combined commit when set on
+    /// any step of a squash group
+    #[serde(default)]
+    pub new_message: Option<String>,
+    #[serde(default)]
+    pub author: String,
+    /// Commit a `fixup!`/`squash!` commit belongs to, if it's in the plan
+    #[serde(default, skip_deserializing)]
+    pub squash_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebasePlan {
+    pub root_path: String,
+    pub upstream: String,
+    /// Commit the steps are replayed onto
+    pub onto: String,
+    /// Branch being rebased (`None` when HEAD is detached)
+    pub branch: Option<String>,
+    /// Oldest first, in the order they will be applied
+    pub steps: Vec<RebaseStep>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseStatus {
+    /// `"idle"`, `"completed"`, `"conflicts"`, or `"stopped"` (at an `edit` step)
+    pub state: &'static str,
+    pub current_step: Option<usize>,
+    pub total_steps: Option<usize>,
+    /// Commit being applied when the rebase paused
+    pub stopped_at: Option<String>,
+    /// Repository-relative paths with merge conflicts
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseProgress {
+    pub step: usize,
+    pub total: usize,
+}
+
+// ============================================================================
+// Planning
+// ============================================================================
+
+/// Steps that replay the commits of `upstream..HEAD`, oldest first. `fixup!` and
+/// `squash!` commits are suggested as such and moved after their target, as
+/// `--autosquash` would.
+pub fn plan_rebase(repo: &Repository, upstream: &str) -> Result<RebasePlan, String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| e.to_string())?;
+    let onto = repo
+        .revparse_single(upstream)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Unknown upstream '{}'", upstream))?;
+    let base = repo
+        .merge_base(head.id(), onto.id())
+        .map_err(|_| format!("HEAD and '{}' have no common history", upstream))?;
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)
+        .map_err(|e| e.to_string())?;
+    walk.push(head.id()).map_err(|e| e.to_string())?;
+    walk.hide(base).map_err(|e| e.to_string())?;
+
+    let mut steps = Vec::new();
+    for oid in walk {
+        let commit = repo
+            .find_commit(oid.map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+        if commit.parent_count() > 1 {
+            return Err(format!(
+                "Commit {} is a merge; interactive rebase of merges isn't supported",
+                short(&commit.id())
+            ));
+        }
+        steps.push(RebaseStep {
+            action: RebaseAction::Pick,
+            commit: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            message: commit.message().unwrap_or("").to_string(),
+            new_message: None,
+            author: commit.author().name().unwrap_or("").to_string(),
+            squash_target: None,
+        });
+    }
+
+    let branch = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+    Ok(RebasePlan {
+        root_path: repo
+            .workdir()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        upstream: upstream.to_string(),
+        onto: onto.id().to_string(),
+        branch,
+        steps: autosquash(steps),
+    })
+}
+
+/// Mark `fixup! <summary>`/`squash! <summary>` commits and move each right after
+/// the commit (or the last commit already folded into it) it refers to
+fn autosquash(steps: Vec<RebaseStep>) -> Vec<RebaseStep> {
+    let mut ordered: Vec<RebaseStep> = Vec::with_capacity(steps.len());
+    for mut step in steps {
+        let target = [
+            ("fixup! ", RebaseAction::Fixup),
+            ("squash! ", RebaseAction::Squash),
+        ]
+        .iter()
+        .find_map(|(prefix, action)| {
+            let subject = step.summary.strip_prefix(prefix)?;
+            let index = ordered.iter().position(|other| {
+                other.squash_target.is_none()
+                    && (other.summary == subject || other.commit.starts_with(subject))
+            })?;
+            Some((index, *action))
+        });
+        match target {
+            Some((index, action)) => {
+                step.action = action;
+                step.squash_target = Some(ordered[index].commit.clone());
+                let mut insert_at = index + 1;
+                while insert_at < ordered.len()
+                    && ordered[insert_at].squash_target.as_deref() == Some(&ordered[index].commit)
+                {
+                    insert_at += 1;
+                }
+                ordered.insert(insert_at, step);
+            }
+            None => ordered.push(step),
+        }
+    }
+    ordered
+}
+
+fn short(oid: &Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+/// Quote `value` for the shell git runs editors and `exec` lines through
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "/").replace('\'', "'\\''"))
+}
+
+/// The `git-rebase-todo` for `steps`. Custom messages are written to files in
+/// `message_dir` and applied with an `exec git commit --amend` after the step
+/// (or after the last step of a squash group).
+fn build_todo(steps: &[RebaseStep], message_dir: &Path) -> Result<String, String> {
+    let first = steps.iter().find(|step| step.action != RebaseAction::Drop);
+    if first.is_some_and(|step| step.action.is_squash()) {
+        return Err("The first commit can't be squashed; there is nothing before it".to_string());
+    }
+
+    let mut todo = String::new();
+    let mut pending_message: Option<String> = None;
+    let flush = |todo: &mut String, message: &mut Option<String>, index: usize| {
+        let Some(message) = message.take() else {
+            return Ok::<(), String>(());
+        };
+        let path = message_dir.join(format!("message-{}.txt", index));
+        std::fs::write(&path, message).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        todo.push_str(&format!(
+            "exec git commit --amend --allow-empty --no-verify --cleanup=strip -F {}\n",
+            shell_quote(&path.to_string_lossy())
+        ));
+        Ok(())
+    };
+
+    for (index, step) in steps.iter().enumerate() {
+        if step.action == RebaseAction::Drop {
+            continue;
+        }
+        // Each step is one todo line; a stray newline would smuggle in another command
+        if !is_object_id(&step.commit) {
+            return Err(format!("'{}' is not a commit id", step.commit));
+        }
+        if !step.action.is_squash() {
+            flush(&mut todo, &mut pending_message, index)?;
+        }
+        let command = match step.action {
+            // Without a new message a reword is a pick; the editor is disabled
+            RebaseAction::Pick | RebaseAction::Reword => "pick",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => unreachable!(),
+        };
+        let summary = step.summary.replace(['\r', '\n'], " ");
+        todo.push_str(&format!("{} {} {}\n", command, step.commit, summary));
+
+        let rewords = matches!(
+            step.action,
+            RebaseAction::Reword | RebaseAction::Squash | RebaseAction::Fixup
+        );
+        if rewords {
+            if let Some(message) = step.new_message.as_ref().filter(|m| !m.trim().is_empty()) {
+                pending_message = Some(message.clone());
+            }
+        }
+    }
+    flush(&mut todo, &mut pending_message, steps.len())?;
+    Ok(todo)
+}
+
+/// Whether `value` is a (possibly abbreviated) hex object id
+fn is_object_id(value: &str) -> bool {
+    (4..=64).contains(&value.len()) && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Directory of an in-progress interactive rebase
+fn rebase_dir(repo: &Repository) -> Option<PathBuf> {
+    let dir = repo.path().join("rebase-merge");
+    dir.is_dir().then_some(dir)
+}
+
+fn read_number(dir: &Path, name: &str) -> Option<usize> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn read_status(repo: &Repository, finished: bool) -> Result<RebaseStatus, String> {
+    let Some(dir) = rebase_dir(repo) else {
+        return Ok(RebaseStatus {
+            state: if finished { "completed" } else { "idle" },
+            current_step: None,
+            total_steps: None,
+            stopped_at: None,
+            conflicts: Vec::new(),
+        });
+    };
+
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let mut conflicts: Vec<String> = index
+        .conflicts()
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect();
+    conflicts.sort();
+    conflicts.dedup();
+
+    let stopped_at = std::fs::read_to_string(dir.join("stopped-sha"))
+        .ok()
+        .map(|sha| sha.trim().to_string());
+    Ok(RebaseStatus {
+        state: if conflicts.is_empty() {
+            "stopped"
+        } else {
+            "conflicts"
+        },
+        current_step: read_number(&dir, "msgnum"),
+        total_steps: read_number(&dir, "end"),
+        stopped_at,
+        conflicts,
+    })
+}
+
+/// Run a `git rebase` command, reporting `Rebasing (n/m)` progress to `on_progress`.
+/// A rebase that stops for conflicts isn't an error; the status says so.
+fn run_rebase(
+    repo: &Repository,
+    args: &[&str],
+    sequence_editor: Option<&str>,
+    mut on_progress: impl FnMut(RebaseProgress),
+) -> Result<RebaseStatus, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory")?;
+    let mut cmd = git_command(Some(workdir));
+    // Messages come from the plan; never open an editor
+    cmd.env("GIT_EDITOR", "true").arg("rebase").args(args);
+    if let Some(editor) = sequence_editor {
+        cmd.env("GIT_SEQUENCE_EDITOR", editor);
+    }
+
+    let result = run_git_streaming(cmd, |line| {
+        let Some(rest) = line.trim().strip_prefix("Rebasing (") else {
+            return;
+        };
+        let Some((step, total)) = rest.trim_end_matches(')').split_once('/') else {
+            return;
+        };
+        if let (Ok(step), Ok(total)) = (step.parse(), total.parse()) {
+            on_progress(RebaseProgress { step, total });
+        }
+    });
+
+    let status = read_status(repo, true)?;
+    match result {
+        Ok(()) => Ok(status),
+        // Stopped mid-rebase: conflicts or an `edit` step
+        Err(_) if status.state != "completed" => Ok(status),
+        Err(e) => Err(e),
+    }
+}
+
+/// Working-tree files a rebase onto `onto` can rewrite
+fn rebased_paths(repo: &Repository, plan: &RebasePlan) -> Result<Vec<PathBuf>, String> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no working directory")?;
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_tree())
+        .map_err(|e| e.to_string())?;
+    let onto = repo
+        .find_commit(Oid::from_str(&plan.onto).map_err(|e| e.to_string())?)
+        .and_then(|commit| commit.tree())
+        .map_err(|e| e.to_string())?;
+
+    let mut paths = HashSet::new();
+    let mut collect = |diff: git2::Diff| {
+        for delta in diff.deltas() {
+            for path in [delta.old_file().path(), delta.new_file().path()]
+                .into_iter()
+                .flatten()
+            {
+                paths.insert(workdir.join(path));
+            }
+        }
+    };
+    collect(
+        repo.diff_tree_to_tree(Some(&head), Some(&onto), None)
+            .map_err(|e| e.to_string())?,
+    );
+    for step in &plan.steps {
+        let commit = repo
+            .find_commit(Oid::from_str(&step.commit).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?;
+        let parent = commit.parent(0).and_then(|p| p.tree()).ok();
+        let tree = commit.tree().map_err(|e| e.to_string())?;
+        collect(
+            repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), None)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    Ok(paths.into_iter().collect())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the commits between `upstream` and HEAD as an editable rebase plan
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `upstream` - Branch, tag, or commit to rebase onto (e.g. `origin/main`)
+#[fluxel_command]
+pub async fn git_rebase_plan(root_path: String, upstream: String) -> Result<RebasePlan, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if rebase_dir(&repo).is_some() {
+            return Err("A rebase is already in progress".to_string());
+        }
+        let mut plan = plan_rebase(&repo, &upstream)?;
+        plan.root_path = root_path;
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Run an edited rebase plan. Pauses (without failing) on conflicts and `edit` steps.
+///
+/// # Arguments
+/// * `plan` - Plan from `git_rebase_plan`, with reordered steps and changed actions
+/// * `force` - Proceed even if files the rebase rewrites have unsaved editor changes
+#[fluxel_command]
+pub async fn git_rebase_execute<R: Runtime>(
+    app: AppHandle<R>,
+    plan: RebasePlan,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<RebaseStatus, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&plan.root_path).map_err(|e| e.to_string())?;
+        if rebase_dir(&repo).is_some() {
+            return Err("A rebase is already in progress".to_string());
+        }
+        dirty_files.guard(
+            "git_rebase_execute",
+            &rebased_paths(&repo, &plan)?,
+            force.unwrap_or(false),
+        )?;
+
+        // Message files must outlive this call when the rebase pauses
+        let message_dir = repo.path().join("fluxel-rebase");
+        let _ = std::fs::remove_dir_all(&message_dir);
+        std::fs::create_dir_all(&message_dir)
+            .map_err(|e| format!("Failed to create {:?}: {}", message_dir, e))?;
+        let todo_path = message_dir.join("todo");
+        std::fs::write(&todo_path, build_todo(&plan.steps, &message_dir)?)
+            .map_err(|e| format!("Failed to write rebase todo: {}", e))?;
+        let editor = format!("cp {}", shell_quote(&todo_path.to_string_lossy()));
+
+        println!(
+            "[GitRebase] Rebasing {} step(s) onto {}",
+            plan.steps.len(),
+            plan.upstream
+        );
+        let status = run_rebase(
+            &repo,
+            &["-i", "--no-autosquash", &plan.onto],
+            Some(&editor),
+            |progress| {
+                let _ = app.emit("git://rebase-progress", progress);
+            },
+        )?;
+        if status.state == "completed" {
+            let _ = std::fs::remove_dir_all(&message_dir);
+        }
+        Ok(status)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Continue a paused rebase after conflicts were resolved and staged
+#[fluxel_command]
+pub async fn git_rebase_continue<R: Runtime>(
+    app: AppHandle<R>,
+    root_path: String,
+) -> Result<RebaseStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if rebase_dir(&repo).is_none() {
+            return Err("No rebase in progress".to_string());
+        }
+        let status = run_rebase(&repo, &["--continue"], None, |progress| {
+            let _ = app.emit("git://rebase-progress", progress);
+        })?;
+        if status.state == "completed" {
+            let _ = std::fs::remove_dir_all(repo.path().join("fluxel-rebase"));
+        }
+        Ok(status)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Skip the commit the rebase stopped at and continue
+#[fluxel_command]
+pub async fn git_rebase_skip<R: Runtime>(
+    app: AppHandle<R>,
+    root_path: String,
+) -> Result<RebaseStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if rebase_dir(&repo).is_none() {
+            return Err("No rebase in progress".to_string());
+        }
+        run_rebase(&repo, &["--skip"], None, |progress| {
+            let _ = app.emit("git://rebase-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Abort a paused rebase and restore the original branch
+#[fluxel_command]
+pub async fn git_rebase_abort(root_path: String) -> Result<RebaseStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        if rebase_dir(&repo).is_none() {
+            return Err("No rebase in progress".to_string());
+        }
+        run_rebase(&repo, &["--abort"], None, |_| {})?;
+        let _ = std::fs::remove_dir_all(repo.path().join("fluxel-rebase"));
+        read_status(&repo, false)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// State of an in-progress rebase (`"idle"` when there is none)
+#[fluxel_command]
+pub async fn git_rebase_status(root_path: String) -> Result<RebaseStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        read_status(&repo, false)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_file(repo: &Repository, name: &str, content: &str, message: &str) {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn plans_and_executes_reordered_squashed_rebase() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-git-rebase-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        commit_file(&repo, "base.txt", "base\n", "base");
+        let base = repo.head().unwrap().target().unwrap().to_string();
+        commit_file(&repo, "a.txt", "a\n", "add a");
+        commit_file(&repo, "b.txt", "b\n", "add b");
+        commit_file(&repo, "a.txt", "a2\n", "fixup! add a");
+        commit_file(&repo, "c.txt", "c\n", "add c");
+
+        let mut plan = plan_rebase(&repo, &base).unwrap();
+        let summaries: Vec<&str> = plan.steps.iter().map(|s| s.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["add a", "fixup! add a", "add b", "add c"]);
+        assert_eq!(plan.steps[1].action, RebaseAction::Fixup);
+        assert_eq!(
+            plan.steps[1].squash_target.as_ref(),
+            Some(&plan.steps[0].commit)
+        );
+
+        // Drop "add b" and reword "add c"
+        plan.steps[2].action = RebaseAction::Drop;
+        plan.steps[3].action = RebaseAction::Reword;
+        plan.steps[3].new_message = Some("Add c file\n\nWith a body".to_string());
+
+        let message_dir = repo.path().join("fluxel-rebase");
+        std::fs::create_dir_all(&message_dir).unwrap();
+        let todo = build_todo(&plan.steps, &message_dir).unwrap();
+        assert!(todo.starts_with("pick "));
+        assert!(todo.contains("\nfixup "));
+        assert!(!todo.contains("add b"));
+        assert!(todo.trim_end().ends_with("message-4.txt'"));
+        std::fs::write(message_dir.join("todo"), &todo).unwrap();
+        let editor = format!(
+            "cp {}",
+            shell_quote(&message_dir.join("todo").to_string_lossy())
+        );
+
+        let status = run_rebase(
+            &repo,
+            &["-i", "--no-autosquash", &plan.onto],
+            Some(&editor),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(status.state, "completed");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message().unwrap().trim(), "Add c file\n\nWith a body");
+        let parent = head.parent(0).unwrap();
+        assert_eq!(parent.summary(), Some("add a"));
+        assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "a2\n");
+        assert!(!dir.join("b.txt").exists());
+
+        let squash_first = vec![RebaseStep {
+            action: RebaseAction::Squash,
+            ..plan.steps[0].clone()
+        }];
+        assert!(build_todo(&squash_first, &message_dir).is_err());
+
+        // Nothing from a step can add a line to the todo
+        let injected = vec![RebaseStep {
+            summary: "subject\nexec touch injected".to_string(),
+            ..plan.steps[0].clone()
+        }];
+        let todo = build_todo(&injected, &message_dir).unwrap();
+        assert_eq!(todo.lines().count(), 1);
+        assert!(todo.ends_with("subject exec touch injected\n"));
+        let bad_commit = vec![RebaseStep {
+            commit: "HEAD\nexec touch injected".to_string(),
+            ..plan.steps[0].clone()
+        }];
+        assert!(build_todo(&bad_commit, &message_dir).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,241 @@
+//! Sparse Checkout
+//!
+//! Limits the working tree to selected directories with `git sparse-checkout`, so
+//! a subdirectory of a giant monorepo can be opened without materializing the
+//! rest. Paired with a blobless clone (`git_clone` with `filter: "blobless"`),
+//! only the contents of the selected files are ever downloaded.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::git_cli::run_git;
+use fluxel_macros::fluxel_command;
+use git2::Repository;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SparseCheckoutStatus {
+    pub enabled: bool,
+    /// Cone mode: patterns select whole directories
+    pub cone: bool,
+    /// Raw patterns from `info/sparse-checkout`
+    pub patterns: Vec<String>,
+    /// Selected directories (cone mode only)
+    pub directories: Vec<String>,
+}
+
+/// Directories selected by cone-mode `patterns`. Every ancestor of a selected
+/// directory is listed as `/dir/` followed by `!/dir/*/`; the selected directories
+/// are the ones without that exclusion.
+pub fn cone_directories(patterns: &[String]) -> Vec<String> {
+    patterns
+        .iter()
+        .filter(|pattern| pattern.starts_with('/') && pattern.ends_with('/') && pattern.len() > 1)
+        .filter(|pattern| {
+            let excluded = format!("!{}*/", pattern);
+            !patterns.contains(&excluded)
+        })
+        .map(|pattern| pattern.trim_matches('/').to_string())
+        .collect()
+}
+
+/// Whether cone mode with `directories` keeps the repository-relative file `path`:
+/// files at the root, directly inside an ancestor of a selected directory, or
+/// anywhere under one
+pub fn in_cone(path: &str, directories: &[String]) -> bool {
+    let parent = match path.rsplit_once('/') {
+        Some((parent, _)) => parent,
+        None => return true,
+    };
+    directories.iter().any(|dir| {
+        let dir = dir.trim_matches('/');
+        path.starts_with(&format!("{}/", dir)) || dir.starts_with(&format!("{}/", parent))
+    })
+}
+
+fn read_status(repo: &Repository) -> Result<SparseCheckoutStatus, String> {
+    let config = repo.config().map_err(|e| e.to_string())?;
+    let enabled = config.get_bool("core.sparseCheckout").unwrap_or(false);
+    let cone = config.get_bool("core.sparseCheckoutCone").unwrap_or(false);
+
+    let patterns: Vec<String> = if enabled {
+        std::fs::read_to_string(repo.path().join("info").join("sparse-checkout"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let directories = if enabled && cone {
+        cone_directories(&patterns)
+    } else {
+        Vec::new()
+    };
+
+    Ok(SparseCheckoutStatus {
+        enabled,
+        cone,
+        patterns,
+        directories,
+    })
+}
+
+/// Repository-relative directory or pattern, with forward slashes. A leading
+/// slash means the repository root, as in sparse-checkout patterns.
+fn normalize_sparse_path(path: &str) -> Result<String, String> {
+    let path = path.trim().replace('\\', "/");
+    let trimmed = path.trim_matches('/');
+    if trimmed.is_empty() {
+        return Err("Sparse checkout paths cannot be empty".to_string());
+    }
+    // A drive letter is an absolute Windows path
+    if trimmed.contains(':') || trimmed.split('/').any(|part| part == "..") {
+        return Err(format!(
+            "Sparse checkout path '{}' must be inside the repository",
+            path
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Select `paths` with `git sparse-checkout set`, enabling sparse checkout if needed
+pub fn set_sparse_paths(workdir: &Path, paths: &[String], cone: bool) -> Result<(), String> {
+    let mut args = vec![
+        "sparse-checkout",
+        "set",
+        if cone { "--cone" } else { "--no-cone" },
+        "--",
+    ];
+    args.extend(paths.iter().map(String::as_str));
+    run_git(workdir, &args)?;
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Restrict the working tree to the given directories (or patterns, without cone mode)
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `paths` - Repository-relative directories to keep
+/// * `cone` - Use cone mode (default: true); otherwise `paths` are gitignore-style patterns
+/// * `force` - Proceed even if files leaving the checkout have unsaved editor changes
+#[fluxel_command]
+pub async fn git_sparse_checkout_set(
+    root_path: String,
+    paths: Vec<String>,
+    cone: Option<bool>,
+    force: Option<bool>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<SparseCheckoutStatus, String> {
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let cone = cone.unwrap_or(true);
+        let paths = paths
+            .iter()
+            .map(|path| normalize_sparse_path(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        if paths.is_empty() {
+            return Err("Select at least one directory to check out".to_string());
+        }
+
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?
+            .to_path_buf();
+
+        // Cone membership is cheap to check; pattern mode relies on git refusing
+        // to remove modified files
+        if cone {
+            let leaving: Vec<PathBuf> = dirty_files
+                .dirty_within(std::slice::from_ref(&workdir))
+                .into_iter()
+                .map(PathBuf::from)
+                .filter(|file| {
+                    file.strip_prefix(&workdir)
+                        .map(|rel| !in_cone(&rel.to_string_lossy().replace('\\', "/"), &paths))
+                        .unwrap_or(false)
+                })
+                .collect();
+            dirty_files.guard("git_sparse_checkout_set", &leaving, force.unwrap_or(false))?;
+        }
+
+        set_sparse_paths(&workdir, &paths, cone)?;
+        println!("[GitSparse] Checked out {} path(s)", paths.len());
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Current sparse checkout state and patterns
+#[fluxel_command]
+pub async fn git_sparse_checkout_list(root_path: String) -> Result<SparseCheckoutStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Turn sparse checkout off and materialize the full working tree
+#[fluxel_command]
+pub async fn git_sparse_checkout_disable(
+    root_path: String,
+) -> Result<SparseCheckoutStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?;
+        run_git(workdir, &["sparse-checkout", "disable"])?;
+        read_status(&repo)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cone_patterns_and_membership() {
+        let patterns: Vec<String> = [
+            "/*",
+            "!/*/",
+            "/packages/",
+            "!/packages/*/",
+            "/packages/app/",
+            "/docs/",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+        let directories = cone_directories(&patterns);
+        assert_eq!(directories, vec!["packages/app", "docs"]);
+
+        assert!(in_cone("README.md", &directories));
+        assert!(in_cone("packages/package.json", &directories));
+        assert!(in_cone("packages/app/src/main.ts", &directories));
+        assert!(in_cone("docs/guide/intro.md", &directories));
+        assert!(!in_cone("packages/lib/index.ts", &directories));
+        assert!(!in_cone("tools/build.sh", &directories));
+
+        assert_eq!(
+            normalize_sparse_path("\\packages\\app\\").unwrap(),
+            "packages/app"
+        );
+        assert!(normalize_sparse_path("../outside").is_err());
+        assert!(normalize_sparse_path("C:\\repo\\app").is_err());
+        assert!(normalize_sparse_path("  ").is_err());
+    }
+}
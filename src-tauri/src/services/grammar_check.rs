@@ -0,0 +1,471 @@
+//! AI Grammar Check
+//!
+//! Optional spelling and grammar pass over commit messages and markdown using a
+//! cheap local (Ollama) or remote (MiniMax) model. The model quotes the text it
+//! would change; each quote is located in the original to produce ranged
+//! suggestions shaped like diagnostics-store entries, so they show up next to
+//! spell check and language server results. Terms from the user and workspace
+//! dictionaries are never flagged, and markdown code is left alone.
+
+use crate::services::ai_audit::{AiAuditRecord, AiAuditState};
+use crate::services::ai_metrics::AiMetricsState;
+use crate::services::inline_completion::{InlineProvider, InlineProviderConfig};
+use crate::services::spellcheck::SpellCheckState;
+use crate::services::structured_output::{structured_chat, ChatTurn, StructuredChatRequest};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+use tauri::State;
+
+const SOURCE: &str = "ai-grammar";
+/// Longer texts should be checked a selection at a time
+const MAX_TEXT_CHARS: usize = 20_000;
+const MAX_SUGGESTIONS: usize = 50;
+/// Dictionary terms listed in the prompt
+const MAX_PROMPT_TERMS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GrammarTextKind {
+    CommitMessage,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrammarCheckRequest {
+    pub text: String,
+    pub kind: GrammarTextKind,
+    #[serde(default)]
+    pub provider: InlineProviderConfig,
+    /// Workspace whose dictionary terms are left alone
+    pub workspace_root: Option<String>,
+    /// File the text belongs to; defaults to the repository's `COMMIT_EDITMSG`
+    pub file_path: Option<String>,
+    /// 1-based position of `text` in the file, when checking a selection
+    pub start_line: Option<u32>,
+    pub start_column: Option<u32>,
+}
+
+/// 1-based range; columns are UTF-16 code units, matching Monaco
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrammarRange {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+/// A suggested edit, in the shape of a diagnostics-store entry
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrammarSuggestion {
+    pub id: String,
+    pub uri: String,
+    pub file_path: String,
+    pub file_name: String,
+    /// `info` for spelling and grammar, `hint` for style
+    pub severity: &'static str,
+    pub message: String,
+    /// `spelling`, `grammar` or `style`
+    pub code: String,
+    pub source: &'static str,
+    pub range: GrammarRange,
+    /// Text to replace the range with
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Issue {
+    original: String,
+    replacement: String,
+    message: String,
+    kind: String,
+}
+
+fn response_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "issues": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "original": { "type": "string", "minLength": 1 },
+                        "replacement": { "type": "string" },
+                        "message": { "type": "string" },
+                        "kind": { "enum": ["spelling", "grammar", "style"] }
+                    },
+                    "required": ["original", "replacement", "message", "kind"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["issues"],
+        "additionalProperties": false
+    })
+}
+
+fn build_prompt(text: &str, kind: GrammarTextKind, known_words: &HashSet<String>) -> String {
+    let subject = match kind {
+        GrammarTextKind::CommitMessage => {
+            "a git commit message. Imperative subject lines (\"Add\", \"Fix\") and \
+             conventional commit prefixes such as `feat(ui):` are correct"
+        }
+        GrammarTextKind::Markdown => {
+            "Markdown documentation. Ignore code spans, code blocks, link targets, and HTML"
+        }
+    };
+    let mut prompt = format!(
+        "Proofread {}. Report spelling and grammar mistakes, and only clear style problems. \
+         Quote each `original` exactly as it appears in the text, keeping it short but long \
+         enough to be unambiguous, and give the corrected `replacement`. \
+         Return an empty list when the text is fine.",
+        subject
+    );
+    if !known_words.is_empty() {
+        let mut terms: Vec<&str> = known_words.iter().map(String::as_str).collect();
+        terms.sort_unstable();
+        terms.truncate(MAX_PROMPT_TERMS);
+        prompt.push_str(&format!(
+            "\n\nThese project terms are spelled correctly: {}",
+            terms.join(", ")
+        ));
+    }
+    prompt.push_str(&format!("\n\nText:\n<<<\n{}\n>>>", text));
+    prompt
+}
+
+/// Byte ranges of fenced code blocks and inline code spans in markdown
+fn markdown_code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut fence_start = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match fence_start.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => fence_start = Some(offset),
+            }
+        } else if fence_start.is_none() {
+            let mut rest = line;
+            let mut base = offset;
+            while let Some(open) = rest.find('`') {
+                let Some(close) = rest[open + 1..].find('`') else {
+                    break;
+                };
+                let end = open + 1 + close + 1;
+                ranges.push(base + open..base + end);
+                base += end;
+                rest = &rest[end..];
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = fence_start {
+        ranges.push(start..text.len());
+    }
+    ranges
+}
+
+/// 1-based line and UTF-16 column of byte `offset`
+fn position_at(text: &str, offset: usize) -> (u32, u32) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() as u32 + 1;
+    let column = before[line_start..].encode_utf16().count() as u32 + 1;
+    (line, column)
+}
+
+/// Shift a position in a selection to its position in the file
+fn offset_position((line, column): (u32, u32), start_line: u32, start_column: u32) -> (u32, u32) {
+    if line == 1 {
+        (start_line, start_column + column - 1)
+    } else {
+        (start_line + line - 1, column)
+    }
+}
+
+/// Locate the quoted issues in `text`, dropping any that can't be found, touch
+/// code, change nothing, or flag a known term
+fn locate_issues(
+    text: &str,
+    issues: Vec<Issue>,
+    kind: GrammarTextKind,
+    known_words: &HashSet<String>,
+) -> Vec<(Range<usize>, Issue)> {
+    let code = match kind {
+        GrammarTextKind::Markdown => markdown_code_ranges(text),
+        GrammarTextKind::CommitMessage => Vec::new(),
+    };
+    let mut located: Vec<(Range<usize>, Issue)> = Vec::new();
+    // Models list issues in reading order; search after the previous match first
+    // so repeated phrases map to successive occurrences
+    let mut cursor = 0;
+    for issue in issues {
+        if issue.original == issue.replacement
+            || known_words.contains(&issue.original.trim().to_lowercase())
+        {
+            continue;
+        }
+        let find_from = |from: usize| {
+            text[from..]
+                .match_indices(issue.original.as_str())
+                .map(|(i, _)| from + i..from + i + issue.original.len())
+                .find(|range| {
+                    !code
+                        .iter()
+                        .any(|c| range.start < c.end && c.start < range.end)
+                        && !located.iter().any(|(r, _)| r == range)
+                })
+        };
+        let Some(range) = find_from(cursor).or_else(|| find_from(0)) else {
+            continue;
+        };
+        cursor = range.end;
+        located.push((range, issue));
+        if located.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    located.sort_by_key(|(range, _)| range.start);
+    located
+}
+
+fn to_suggestions(
+    text: &str,
+    located: Vec<(Range<usize>, Issue)>,
+    file_path: &str,
+    base_line: u32,
+    base_column: u32,
+) -> Vec<GrammarSuggestion> {
+    let normalized = file_path.replace('\\', "/");
+    let uri = if normalized.starts_with('/') {
+        format!("file://{}", normalized)
+    } else {
+        format!("file:///{}", normalized)
+    };
+    let file_name = Path::new(file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path.to_string());
+
+    located
+        .into_iter()
+        .map(|(range, issue)| {
+            let (start_line, start_column) =
+                offset_position(position_at(text, range.start), base_line, base_column);
+            let (end_line, end_column) =
+                offset_position(position_at(text, range.end), base_line, base_column);
+            let message = if issue.message.trim().is_empty() {
+                format!("Replace with \"{}\"", issue.replacement)
+            } else {
+                issue.message.trim().to_string()
+            };
+            GrammarSuggestion {
+                id: format!("{}:{}:{}:{}", SOURCE, file_path, start_line, start_column),
+                uri: uri.clone(),
+                file_path: file_path.to_string(),
+                file_name: file_name.clone(),
+                severity: if issue.kind == "style" {
+                    "hint"
+                } else {
+                    "info"
+                },
+                message,
+                code: issue.kind,
+                source: SOURCE,
+                range: GrammarRange {
+                    start_line,
+                    start_column,
+                    end_line,
+                    end_column,
+                },
+                replacement: issue.replacement,
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check a commit message or markdown selection for spelling and grammar
+///
+/// # Arguments
+/// * `request` - Text, its kind and position, provider, and workspace
+#[fluxel_command]
+pub async fn ai_grammar_check(
+    request: GrammarCheckRequest,
+    spellcheck: State<'_, SpellCheckState>,
+    audit: State<'_, AiAuditState>,
+    metrics: State<'_, AiMetricsState>,
+) -> Result<Vec<GrammarSuggestion>, String> {
+    if request.text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    if request.text.chars().count() > MAX_TEXT_CHARS {
+        return Err(format!(
+            "Text is too long for a grammar check ({} characters max); check a selection instead",
+            MAX_TEXT_CHARS
+        ));
+    }
+
+    let state = spellcheck.inner().clone();
+    let root = request.workspace_root.clone();
+    let known_words =
+        tauri::async_runtime::spawn_blocking(move || state.known_words(root.as_deref()))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let file_path =
+        request
+            .file_path
+            .clone()
+            .unwrap_or_else(|| match request.workspace_root.as_deref() {
+                Some(root) => Path::new(root)
+                    .join(".git")
+                    .join("COMMIT_EDITMSG")
+                    .to_string_lossy()
+                    .to_string(),
+                None => "COMMIT_EDITMSG".to_string(),
+            });
+
+    if let (InlineProvider::Minimax, Some(root)) =
+        (request.provider.provider, request.workspace_root.clone())
+    {
+        let audit = audit.inner().clone();
+        let record = AiAuditRecord {
+            provider: "minimax".to_string(),
+            model: request.provider.model.clone().unwrap_or_default(),
+            feature: "grammarCheck".to_string(),
+            files: request.file_path.iter().cloned().collect(),
+            snippets: vec![request.text.clone()],
+            ..Default::default()
+        };
+        let recorded =
+            tauri::async_runtime::spawn_blocking(move || audit.record(Path::new(&root), record))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result);
+        if let Err(e) = recorded {
+            println!("[GrammarCheck] Failed to record AI audit entry: {}", e);
+        }
+    }
+
+    let response = structured_chat(
+        StructuredChatRequest {
+            provider: request.provider.clone(),
+            messages: vec![ChatTurn {
+                role: "user".to_string(),
+                content: build_prompt(&request.text, request.kind, &known_words),
+            }],
+            response_schema: response_schema(),
+            max_repairs: Some(1),
+            temperature: Some(0.0),
+            max_tokens: None,
+        },
+        &metrics,
+        "grammarCheck",
+    )
+    .await?;
+    let issues: Vec<Issue> = serde_json::from_value(response.value["issues"].clone())
+        .map_err(|e| format!("Invalid grammar check reply: {}", e))?;
+
+    let located = locate_issues(&request.text, issues, request.kind, &known_words);
+    println!(
+        "[GrammarCheck] {} suggestion(s) for {}",
+        located.len(),
+        file_path
+    );
+    Ok(to_suggestions(
+        &request.text,
+        located,
+        &file_path,
+        request.start_line.unwrap_or(1).max(1),
+        request.start_column.unwrap_or(1).max(1),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(original: &str, replacement: &str, kind: &str) -> Issue {
+        Issue {
+            original: original.to_string(),
+            replacement: replacement.to_string(),
+            message: String::new(),
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn maps_issues_to_ranged_suggestions() {
+        let text = "# Intro\n\nThe the résumé parser `recieve()` will recieve\n\n```\nrecieve\n```\nfluxel is grate.\n";
+        let known: HashSet<String> = ["fluxel".to_string()].into_iter().collect();
+        let issues = vec![
+            issue("recieve", "receive", "spelling"),
+            issue("The the", "The", "grammar"),
+            issue("fluxel", "Fluxel", "spelling"),
+            issue("missing phrase", "x", "grammar"),
+            issue("grate", "great", "style"),
+            issue("same", "same", "style"),
+        ];
+        let located = locate_issues(text, issues, GrammarTextKind::Markdown, &known);
+        let suggestions = to_suggestions(text, located, "/ws/README.md", 1, 1);
+        let summary: Vec<(&str, u32, u32, u32)> = suggestions
+            .iter()
+            .map(|s| {
+                (
+                    s.replacement.as_str(),
+                    s.range.start_line,
+                    s.range.start_column,
+                    s.range.end_column,
+                )
+            })
+            .collect();
+        // The code span and code block copies of "recieve" are skipped; "résumé"
+        // counts as six UTF-16 units
+        assert_eq!(
+            summary,
+            vec![
+                ("The", 3, 1, 8),
+                ("receive", 3, 40, 47),
+                ("great", 8, 11, 16),
+            ]
+        );
+        assert_eq!(suggestions[0].uri, "file:///ws/README.md");
+        assert_eq!(suggestions[0].file_name, "README.md");
+        assert_eq!(suggestions[2].severity, "hint");
+        assert_eq!(suggestions[1].code, "spelling");
+
+        // Selections are offset into the file; only the first line shifts columns
+        let text = "teh end\nof teh line";
+        let issues = vec![
+            issue("teh", "the", "spelling"),
+            issue("teh", "the", "spelling"),
+        ];
+        let located = locate_issues(text, issues, GrammarTextKind::CommitMessage, &known);
+        let suggestions = to_suggestions(text, located, "COMMIT_EDITMSG", 10, 5);
+        assert_eq!(
+            suggestions[0].range,
+            GrammarRange {
+                start_line: 10,
+                start_column: 5,
+                end_line: 10,
+                end_column: 8
+            }
+        );
+        assert_eq!(suggestions[1].range.start_line, 11);
+        assert_eq!(suggestions[1].range.start_column, 4);
+        assert_eq!(suggestions[1].uri, "file:///COMMIT_EDITMSG");
+    }
+}
@@ -0,0 +1,203 @@
+//! Editor Health Snapshot
+//!
+//! One payload describing the state of the running editor: memory of the app
+//! and its child processes, language server status and request metrics, active
+//! watchers, cache sizes, and (in profiling builds) recent slow operations. The
+//! diagnostics page renders it, and users can copy it into bug reports as JSON.
+
+use crate::commands::{GitignoreCache, ProjectConfigCache};
+use crate::languages::lsp_manager::LSPState;
+use crate::languages::lsp_metrics::{collect_server_metrics, LspServerMetrics};
+use crate::services::process_manager::process_memory_bytes;
+use crate::services::search_index::SearchIndexStatus;
+use crate::services::{
+    FsWatcherService, ModuleGraphWatchState, ProcessManager, SearchIndexState, TsProjectState,
+    WatchBuildState,
+};
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(feature = "profiling")]
+use crate::profiling::SpanSummary;
+#[cfg(feature = "profiling")]
+use std::collections::HashSet;
+
+/// Spans at least this long count as slow operations
+#[cfg(feature = "profiling")]
+const SLOW_OPERATION_MS: f64 = 100.0;
+/// Recent spans searched for slow operations
+#[cfg(feature = "profiling")]
+const SLOW_OPERATION_WINDOW: usize = 2_000;
+/// Slow operations included in a snapshot
+#[cfg(feature = "profiling")]
+const MAX_SLOW_OPERATIONS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthSnapshot {
+    /// Unix time in milliseconds
+    pub generated_at: u64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub process: ProcessHealth,
+    pub child_processes: Vec<ProcessHealth>,
+    pub language_servers: Vec<LspServerMetrics>,
+    pub watchers: WatcherHealth,
+    pub caches: CacheHealth,
+    /// Outermost spans slower than 100ms, most recent first. Only in profiling
+    /// builds.
+    #[cfg(feature = "profiling")]
+    pub slow_operations: Vec<SpanSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessHealth {
+    pub pid: u32,
+    /// Resident memory; `None` if the process has exited or can't be read
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherHealth {
+    /// Directories with an OS file watcher, shared by all subscribers
+    pub watched_roots: usize,
+    pub module_graph_watches: usize,
+    pub build_watches: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheHealth {
+    /// Workspaces with a cached gitignore matcher
+    pub gitignore_workspaces: usize,
+    /// Workspaces with cached build configurations
+    pub build_config_workspaces: usize,
+    /// Cached TypeScript project reference graphs
+    pub ts_project_graphs: usize,
+    pub search_indexes: Vec<SearchIndexStatus>,
+}
+
+fn process_health(pid: u32) -> ProcessHealth {
+    ProcessHealth {
+        pid,
+        memory_bytes: process_memory_bytes(pid),
+    }
+}
+
+/// Slow spans that didn't run inside another slow span, most recent first
+#[cfg(feature = "profiling")]
+fn outermost_slow_spans(spans: Vec<SpanSummary>) -> Vec<SpanSummary> {
+    let slow: HashSet<String> = spans
+        .iter()
+        .filter(|span| span.duration_ms >= SLOW_OPERATION_MS)
+        .map(|span| span.id.clone())
+        .collect();
+    let mut outermost: Vec<SpanSummary> = spans
+        .into_iter()
+        .filter(|span| slow.contains(&span.id))
+        .filter(|span| !span.parent_id.as_ref().is_some_and(|id| slow.contains(id)))
+        .collect();
+    outermost.sort_by(|a, b| b.start_time_ms.total_cmp(&a.start_time_ms));
+    outermost.truncate(MAX_SLOW_OPERATIONS);
+    outermost
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Gather a health snapshot of the running editor for the diagnostics page
+#[fluxel_command]
+pub async fn get_health_snapshot<R: Runtime>(app: AppHandle<R>) -> Result<HealthSnapshot, String> {
+    let language_servers = collect_server_metrics(&app.state::<LSPState>()).await?;
+    let watchers = WatcherHealth {
+        watched_roots: app.state::<FsWatcherService>().watched_root_count(),
+        module_graph_watches: app.state::<ModuleGraphWatchState>().watch_count().await,
+        build_watches: app.state::<WatchBuildState>().watch_count().await,
+    };
+    let caches = CacheHealth {
+        gitignore_workspaces: app.state::<GitignoreCache>().cached_count().await,
+        build_config_workspaces: app.state::<ProjectConfigCache>().cached_count().await,
+        ts_project_graphs: app.state::<TsProjectState>().cached_count(),
+        search_indexes: app.state::<SearchIndexState>().statuses(),
+    };
+    let tracked_pids = app.state::<ProcessManager>().tracked_pids();
+
+    #[cfg(feature = "profiling")]
+    let slow_operations = app
+        .try_state::<crate::profiling::FluxelProfiler>()
+        .map(|profiler| outermost_slow_spans(profiler.recent_spans(SLOW_OPERATION_WINDOW)))
+        .unwrap_or_default();
+
+    // Memory is read from the OS, which may spawn a process
+    let (process, child_processes) = tauri::async_runtime::spawn_blocking(move || {
+        let children = tracked_pids.into_iter().map(process_health).collect();
+        (process_health(std::process::id()), children)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(HealthSnapshot {
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        process,
+        child_processes,
+        language_servers,
+        watchers,
+        caches,
+        #[cfg(feature = "profiling")]
+        slow_operations,
+    })
+}
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use super::*;
+    use crate::profiling::SpanCategory;
+
+    fn span(
+        id: &str,
+        parent_id: Option<&str>,
+        start_time_ms: f64,
+        duration_ms: f64,
+    ) -> SpanSummary {
+        SpanSummary {
+            id: id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            name: id.to_string(),
+            target: "fluxel".to_string(),
+            category: SpanCategory::TauriCommand,
+            start_time_ms,
+            duration_ms,
+            fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_outermost_slow_spans() {
+        let spans = vec![
+            span("search", None, 0.0, 400.0),
+            // Slow because its parent was; not reported twice
+            span("walk", Some("search"), 10.0, 350.0),
+            span("fast", None, 500.0, 5.0),
+            // Slow child of a fast parent
+            span("render", None, 600.0, 90.0),
+            span("read", Some("render"), 650.0, 100.0),
+        ];
+        let names: Vec<String> = outermost_slow_spans(spans)
+            .into_iter()
+            .map(|span| span.name)
+            .collect();
+        assert_eq!(names, ["read", "search"]);
+    }
+}
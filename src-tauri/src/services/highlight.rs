@@ -0,0 +1,361 @@
+//! Syntax Highlighting Service
+//!
+//! syntect-based highlighting for read-only views that have no Monaco editor
+//! attached (search results, diff views, hover previews). Returns either
+//! pre-styled HTML or per-line colored token ranges.
+
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Style, ThemeSet};
+use syntect::html::{
+    append_highlighted_html_for_styled_line, highlighted_html_for_string, IncludeBackground,
+};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+use tauri::State;
+
+/// Theme used when none is requested
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// Highlighting is line-by-line and slow on huge inputs; larger content is rejected
+const MAX_HIGHLIGHT_BYTES: usize = 512 * 1024;
+
+/// Output format for `highlight_snippet`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightFormat {
+    #[default]
+    Html,
+    Tokens,
+}
+
+/// A styled range within a single line. Offsets are UTF-16 code units.
+#[derive(Debug, Clone, Serialize)]
+pub struct HighlightToken {
+    pub start: usize,
+    pub end: usize,
+    /// Foreground color as `#rrggbb` (or `#rrggbbaa` when translucent)
+    pub color: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HighlightResult {
+    /// Name of the syntax that was used (e.g., "Rust", "Plain Text")
+    pub syntax: String,
+    /// Pre-styled HTML (`<pre>` block) when format is "html"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// Tokens per line when format is "tokens"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<Vec<Vec<HighlightToken>>>,
+    /// Theme background color, for hosts that render tokens themselves
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+}
+
+/// Lazily loaded syntax and theme sets shared by all highlighting requests
+#[derive(Clone, Default)]
+pub struct HighlightState {
+    sets: Arc<OnceLock<(SyntaxSet, ThemeSet)>>,
+}
+
+impl HighlightState {
+    pub fn new() -> Self {
+        Self {
+            sets: Arc::new(OnceLock::new()),
+        }
+    }
+
+    fn sets(&self) -> &(SyntaxSet, ThemeSet) {
+        self.sets.get_or_init(|| {
+            (
+                SyntaxSet::load_defaults_newlines(),
+                ThemeSet::load_defaults(),
+            )
+        })
+    }
+
+    /// Names of the available themes
+    pub fn theme_names(&self) -> Vec<String> {
+        self.sets().1.themes.keys().cloned().collect()
+    }
+
+    pub fn has_theme(&self, name: &str) -> bool {
+        self.sets().1.themes.contains_key(name)
+    }
+
+    /// Theme background as `#rrggbb`, if the theme defines one
+    pub fn background_color(&self, theme_name: &str) -> Option<String> {
+        let theme = self.sets().1.themes.get(theme_name)?;
+        theme.settings.background.map(color_hex)
+    }
+
+    /// Highlight code as inline-styled `<span>`s without a wrapping `<pre>`,
+    /// for embedding into other HTML such as markdown code fences.
+    pub fn highlight_inline_html(
+        &self,
+        code: &str,
+        language: &str,
+        theme_name: &str,
+    ) -> Result<String, String> {
+        let (syntax_set, theme_set) = self.sets();
+        let theme = theme_set
+            .themes
+            .get(theme_name)
+            .ok_or_else(|| format!("Unknown theme '{}'", theme_name))?;
+        let syntax = find_syntax(syntax_set, language);
+        let background = theme
+            .settings
+            .background
+            .unwrap_or(syntect::highlighting::Color::WHITE);
+
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut html = String::new();
+        for line in LinesWithEndings::from(code) {
+            let regions = highlighter
+                .highlight_line(line, syntax_set)
+                .map_err(|e| format!("Highlighting failed: {}", e))?;
+            append_highlighted_html_for_styled_line(
+                &regions,
+                IncludeBackground::IfDifferent(background),
+                &mut html,
+            )
+            .map_err(|e| format!("Highlighting failed: {}", e))?;
+        }
+        Ok(html)
+    }
+}
+
+fn color_hex(color: syntect::highlighting::Color) -> String {
+    if color.a == 0xFF {
+        format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+    } else {
+        format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            color.r, color.g, color.b, color.a
+        )
+    }
+}
+
+/// Map editor language ids (Monaco) to syntect tokens, falling back to close relatives
+/// for languages the default syntax set doesn't ship (e.g. TypeScript -> JavaScript).
+fn find_syntax<'a>(syntax_set: &'a SyntaxSet, language: &str) -> &'a SyntaxReference {
+    let language = language.trim().to_ascii_lowercase();
+    let token = match language.as_str() {
+        "typescript" | "typescriptreact" | "ts" | "tsx" | "javascriptreact" | "jsx" => "js",
+        "javascript" | "mjs" | "cjs" => "js",
+        "csharp" | "c#" => "cs",
+        "cpp" | "c++" => "cpp",
+        "shell" | "bash" | "zsh" | "shellscript" => "sh",
+        "powershell" => "ps1",
+        "markdown" => "md",
+        "python" => "py",
+        "rust" => "rs",
+        "ruby" => "rb",
+        "golang" => "go",
+        "jsonc" | "json5" => "json",
+        "yml" => "yaml",
+        "dockerfile" => "Dockerfile",
+        "plaintext" | "text" => "txt",
+        other => other,
+    };
+
+    syntax_set
+        .find_syntax_by_token(token)
+        .or_else(|| syntax_set.find_syntax_by_name(&language))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text())
+}
+
+/// Highlight `content` into colored tokens, one vector per line
+fn highlight_tokens(
+    content: &str,
+    syntax: &SyntaxReference,
+    syntax_set: &SyntaxSet,
+    theme: &syntect::highlighting::Theme,
+) -> Result<Vec<Vec<HighlightToken>>, String> {
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut lines = Vec::new();
+
+    for line in LinesWithEndings::from(content) {
+        let regions: Vec<(Style, &str)> = highlighter
+            .highlight_line(line, syntax_set)
+            .map_err(|e| format!("Highlighting failed: {}", e))?;
+
+        let mut tokens = Vec::with_capacity(regions.len());
+        let mut offset = 0;
+        for (style, text) in regions {
+            let text = text.trim_end_matches(['\n', '\r']);
+            let len = text.encode_utf16().count();
+            if len == 0 {
+                continue;
+            }
+            tokens.push(HighlightToken {
+                start: offset,
+                end: offset + len,
+                color: color_hex(style.foreground),
+                bold: style.font_style.contains(FontStyle::BOLD),
+                italic: style.font_style.contains(FontStyle::ITALIC),
+                underline: style.font_style.contains(FontStyle::UNDERLINE),
+            });
+            offset += len;
+        }
+        lines.push(tokens);
+    }
+
+    Ok(lines)
+}
+
+fn highlight(
+    state: &HighlightState,
+    content: &str,
+    language: &str,
+    theme_name: &str,
+    format: HighlightFormat,
+) -> Result<HighlightResult, String> {
+    if content.len() > MAX_HIGHLIGHT_BYTES {
+        return Err(format!(
+            "Content too large to highlight ({} bytes, limit {})",
+            content.len(),
+            MAX_HIGHLIGHT_BYTES
+        ));
+    }
+
+    let (syntax_set, theme_set) = state.sets();
+    let theme = theme_set.themes.get(theme_name).ok_or_else(|| {
+        format!(
+            "Unknown theme '{}'. Available: {}",
+            theme_name,
+            state.theme_names().join(", ")
+        )
+    })?;
+    let syntax = find_syntax(syntax_set, language);
+
+    let mut result = HighlightResult {
+        syntax: syntax.name.clone(),
+        html: None,
+        lines: None,
+        background: theme.settings.background.map(color_hex),
+    };
+
+    match format {
+        HighlightFormat::Html => {
+            result.html = Some(
+                highlighted_html_for_string(content, syntax_set, syntax, theme)
+                    .map_err(|e| format!("Highlighting failed: {}", e))?,
+            );
+        }
+        HighlightFormat::Tokens => {
+            result.lines = Some(highlight_tokens(content, syntax, syntax_set, theme)?);
+        }
+    }
+
+    Ok(result)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Highlight a code snippet for a read-only view.
+///
+/// # Arguments
+/// * `content` - Source text
+/// * `language` - Editor language id or file extension (e.g., "typescript", "rs")
+/// * `theme` - syntect theme name (default: base16-ocean.dark)
+/// * `format` - "html" (default) or "tokens"
+#[fluxel_command]
+pub async fn highlight_snippet(
+    content: String,
+    language: String,
+    theme: Option<String>,
+    format: Option<HighlightFormat>,
+    state: State<'_, HighlightState>,
+) -> Result<HighlightResult, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        highlight(
+            &state,
+            &content,
+            &language,
+            theme.as_deref().unwrap_or(DEFAULT_THEME),
+            format.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List the available highlighting themes
+#[fluxel_command]
+pub async fn list_highlight_themes(
+    state: State<'_, HighlightState>,
+) -> Result<Vec<String>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut names = state.theme_names();
+        names.sort();
+        names
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_editor_language_ids() {
+        let state = HighlightState::new();
+        let (syntax_set, _) = state.sets();
+        assert_eq!(find_syntax(syntax_set, "rust").name, "Rust");
+        assert_eq!(find_syntax(syntax_set, "csharp").name, "C#");
+        assert_eq!(find_syntax(syntax_set, "typescript").name, "JavaScript");
+        assert_eq!(find_syntax(syntax_set, "unknown-lang").name, "Plain Text");
+    }
+
+    #[test]
+    fn produces_line_tokens_with_utf16_offsets() {
+        let state = HighlightState::new();
+        let result = highlight(
+            &state,
+            "let s = \"é\";\nfn x() {}\n",
+            "rust",
+            DEFAULT_THEME,
+            HighlightFormat::Tokens,
+        )
+        .unwrap();
+
+        let lines = result.lines.expect("tokens");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0].last().unwrap().end,
+            "let s = \"é\";".encode_utf16().count()
+        );
+        assert!(lines[1].iter().all(|t| t.color.starts_with('#')));
+    }
+
+    #[test]
+    fn produces_html_and_rejects_unknown_themes() {
+        let state = HighlightState::new();
+        let result = highlight(
+            &state,
+            "fn main() {}",
+            "rs",
+            DEFAULT_THEME,
+            HighlightFormat::Html,
+        )
+        .unwrap();
+        assert!(result.html.unwrap().starts_with("<pre"));
+
+        assert!(highlight(&state, "x", "rs", "nope", HighlightFormat::Html).is_err());
+    }
+}
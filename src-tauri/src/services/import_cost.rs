@@ -0,0 +1,270 @@
+//! Import Cost Service
+//!
+//! Estimates what each import of a file pulls in: the on-disk size of the resolved
+//! module plus the files it reaches through relative imports inside the same package
+//! (or the project, for local imports). Packages that ship both ESM and CommonJS
+//! builds are flagged, since bundlers may end up including both.
+
+use crate::services::import_rewrite::{normalize, RESOLVE_EXTENSIONS, SOURCE_EXTENSIONS};
+use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
+use fluxel_node_resolver::{
+    collect_import_specifiers_native, resolve_module_native, ImportKind, ResolveOptions,
+    ResolveRequest, ResolveResponse,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files followed per import before the size is reported as truncated
+const MAX_FILES_PER_IMPORT: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCost {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// 1-based line of the specifier, for inline decorations
+    pub line: usize,
+    pub resolved: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Bytes on disk of the module and its transitive internal files
+    pub size: u64,
+    pub file_count: usize,
+    /// Stopped at `MAX_FILES_PER_IMPORT`; `size` is a lower bound
+    pub truncated: bool,
+    /// Package publishes both ESM and CommonJS entry points
+    pub dual_format: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCostReport {
+    pub file: String,
+    pub imports: Vec<ImportCost>,
+    /// Sum of the sizes of every distinct resolved import
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Footprint {
+    size: u64,
+    file_count: usize,
+    truncated: bool,
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+pub fn compute_import_costs(file: &Path, project_root: &Path) -> Result<ImportCostReport, String> {
+    let file = normalize(file);
+    let project_root = normalize(project_root);
+    let utf8_file = Utf8PathBuf::from_path_buf(file.clone())
+        .map_err(|p| format!("Path is not valid UTF-8: {:?}", p))?;
+    let content =
+        fs::read_to_string(&file).map_err(|e| format!("Failed to read {:?}: {}", file, e))?;
+    let specifiers = collect_import_specifiers_native(&utf8_file).map_err(|e| e.to_string())?;
+
+    let mut footprints: HashMap<PathBuf, Footprint> = HashMap::new();
+    let mut imports = Vec::new();
+
+    for specifier in specifiers {
+        let line = content[..specifier.start].matches('\n').count() + 1;
+        let response = resolve(&file, &specifier.specifier, &project_root);
+        let resolved = response
+            .as_ref()
+            .and_then(|r| r.resolved_path.as_deref())
+            .map(|p| normalize(Path::new(p)));
+
+        let package_json = response
+            .as_ref()
+            .and_then(|r| r.package_json.as_deref())
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+        let package_dir = response
+            .as_ref()
+            .and_then(|r| r.package_json.as_deref())
+            .and_then(|p| Path::new(p).parent())
+            .map(normalize);
+
+        let footprint = match &resolved {
+            Some(target) => *footprints.entry(target.clone()).or_insert_with(|| {
+                let boundary = package_dir.as_deref().unwrap_or(&project_root);
+                measure(target, boundary, &project_root)
+            }),
+            None => Footprint::default(),
+        };
+
+        imports.push(ImportCost {
+            specifier: specifier.specifier,
+            kind: specifier.kind,
+            line,
+            resolved: resolved.map(|p| p.to_string_lossy().replace('\\', "/")),
+            package: package_json
+                .as_ref()
+                .and_then(|pkg| pkg.get("name"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            version: package_json
+                .as_ref()
+                .and_then(|pkg| pkg.get("version"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            size: footprint.size,
+            file_count: footprint.file_count,
+            truncated: footprint.truncated,
+            dual_format: package_json.as_ref().is_some_and(is_dual_format),
+        });
+    }
+
+    let total_size = footprints.values().map(|f| f.size).sum();
+    Ok(ImportCostReport {
+        file: file.to_string_lossy().replace('\\', "/"),
+        imports,
+        total_size,
+    })
+}
+
+fn resolve(importer: &Path, specifier: &str, project_root: &Path) -> Option<ResolveResponse> {
+    let options = ResolveOptions {
+        extensions: RESOLVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+        ..Default::default()
+    };
+    resolve_module_native(
+        ResolveRequest {
+            specifier: specifier.to_string(),
+            importer: importer.to_string_lossy().to_string(),
+            project_root: Some(project_root.to_string_lossy().to_string()),
+        },
+        Some(options),
+    )
+    .ok()
+}
+
+/// Sum the sizes of `entry` and every file it reaches through relative imports that
+/// stay inside `boundary`
+fn measure(entry: &Path, boundary: &Path, project_root: &Path) -> Footprint {
+    let mut footprint = Footprint::default();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([entry.to_path_buf()]);
+
+    while let Some(path) = queue.pop_front() {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        if footprint.file_count >= MAX_FILES_PER_IMPORT {
+            footprint.truncated = true;
+            break;
+        }
+        footprint.size += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        footprint.file_count += 1;
+
+        let is_script = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+        let Some(utf8_path) = is_script
+            .then(|| Utf8PathBuf::from_path_buf(path.clone()).ok())
+            .flatten()
+        else {
+            continue;
+        };
+        let Ok(specifiers) = collect_import_specifiers_native(&utf8_path) else {
+            continue;
+        };
+        for specifier in specifiers {
+            if !specifier.specifier.starts_with('.') {
+                continue;
+            }
+            let target = resolve(&path, &specifier.specifier, project_root)
+                .and_then(|r| r.resolved_path)
+                .map(|p| normalize(Path::new(&p)));
+            if let Some(target) = target {
+                if target.starts_with(boundary) && !seen.contains(&target) {
+                    queue.push_back(target);
+                }
+            }
+        }
+    }
+
+    footprint
+}
+
+/// Whether an exports map uses `condition` anywhere
+pub(crate) fn has_condition(value: &Value, condition: &str) -> bool {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .any(|(key, value)| key == condition || has_condition(value, condition)),
+        Value::Array(items) => items.iter().any(|item| has_condition(item, condition)),
+        _ => false,
+    }
+}
+
+/// Whether a package.json declares both ESM and CommonJS entry points
+fn is_dual_format(pkg: &Value) -> bool {
+    if let Some(exports) = pkg.get("exports") {
+        if has_condition(exports, "import") && has_condition(exports, "require") {
+            return true;
+        }
+    }
+    match (
+        pkg.get("module").and_then(|v| v.as_str()),
+        pkg.get("main").and_then(|v| v.as_str()),
+    ) {
+        (Some(module), Some(main)) => normalize(Path::new(module)) != normalize(Path::new(main)),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Compute an import-cost style report for every import in a file
+///
+/// # Arguments
+/// * `file` - Source file whose imports are measured
+/// * `project_root` - Project root for `node_modules` resolution
+#[fluxel_command]
+pub async fn get_import_costs(
+    file: String,
+    project_root: String,
+) -> Result<ImportCostReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        compute_import_costs(Path::new(&file), Path::new(&project_root))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_dual_format_packages() {
+        assert!(is_dual_format(&json!({
+            "exports": { ".": { "import": "./dist/index.mjs", "require": "./dist/index.cjs" } }
+        })));
+        assert!(is_dual_format(&json!({
+            "main": "./lib/index.js",
+            "module": "./es/index.js"
+        })));
+        assert!(!is_dual_format(&json!({
+            "main": "./index.js",
+            "module": "index.js"
+        })));
+        assert!(!is_dual_format(&json!({
+            "type": "module",
+            "exports": { ".": { "types": "./index.d.ts", "import": "./index.js" } }
+        })));
+    }
+}
@@ -0,0 +1,563 @@
+//! Import Rewrite Service
+//!
+//! Computes the import specifier edits a file or directory move requires. Relative
+//! specifiers in JS/TS sources are resolved with `fluxel_node_resolver` against the
+//! tree as it is before the move, then rebuilt for the new locations in the style
+//! they were written in (extensionless, explicit extension or directory index).
+
+use camino::Utf8PathBuf;
+use fluxel_node_resolver::{
+    collect_import_specifiers_native, resolve_module_native, ResolveOptions, ResolveRequest,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Files scanned for import specifiers
+pub(crate) const SOURCE_EXTENSIONS: &[&str] =
+    &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Extensions tried when resolving an extensionless or directory specifier
+pub(crate) const RESOLVE_EXTENSIONS: &[&str] = &[
+    ".ts", ".tsx", ".d.ts", ".mts", ".cts", ".js", ".jsx", ".mjs", ".cjs", ".json",
+];
+
+/// TypeScript ESM sources import `.ts` files through the `.js` name they compile to
+const EMITTED_EXTENSIONS: &[(&str, &[&str])] = &[
+    (".js", &[".ts", ".tsx"]),
+    (".jsx", &[".tsx"]),
+    (".mjs", &[".mts"]),
+    (".cjs", &[".cts"]),
+];
+
+/// One specifier rewrite. Lines and columns are 1-based; columns count UTF-16 code
+/// units so they can be used as Monaco ranges directly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEdit {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub old_specifier: String,
+    pub new_specifier: String,
+    #[serde(skip)]
+    byte_range: (usize, usize),
+}
+
+/// Edits for one file, addressed by the path the file has after the move
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileImportEdits {
+    pub file_path: String,
+    pub edits: Vec<ImportEdit>,
+}
+
+// ============================================================================
+// Planning
+// ============================================================================
+
+/// Find every import that breaks when `from` moves to `to` and compute its replacement.
+///
+/// Must run before the move: specifiers are resolved against the current tree. Covers
+/// files importing the moved path as well as relative imports inside the moved files.
+pub fn plan_import_updates(workspace_root: &Path, from: &Path, to: &Path) -> Vec<FileImportEdits> {
+    let from = normalize(from);
+    let to = normalize(to);
+    let mut plans = Vec::new();
+
+    for file in source_files(workspace_root) {
+        let file = normalize(&file);
+        let Ok(utf8_file) = Utf8PathBuf::from_path_buf(file.clone()) else {
+            continue;
+        };
+        // Files the analyzer can't parse are left untouched
+        let Ok(specifiers) = collect_import_specifiers_native(&utf8_file) else {
+            continue;
+        };
+
+        let new_file = relocate(&file, &from, &to);
+        let mut rewrites = Vec::new();
+        for specifier in specifiers {
+            let Some(target) = resolve_relative(&file, &specifier.specifier) else {
+                continue;
+            };
+            let new_target = relocate(&target, &from, &to);
+            if new_file == file && new_target == target {
+                continue;
+            }
+            let Some(new_specifier) =
+                rewrite_specifier(&specifier.specifier, &target, &new_file, &new_target)
+            else {
+                continue;
+            };
+            if new_specifier != specifier.specifier {
+                rewrites.push((
+                    specifier.start,
+                    specifier.end,
+                    specifier.specifier,
+                    new_specifier,
+                ));
+            }
+        }
+        if rewrites.is_empty() {
+            continue;
+        }
+
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let edits = rewrites
+            .into_iter()
+            .map(|(start, end, old_specifier, new_specifier)| {
+                let (start_line, start_column) = position_at(&content, start);
+                let (end_line, end_column) = position_at(&content, end);
+                ImportEdit {
+                    start_line,
+                    start_column,
+                    end_line,
+                    end_column,
+                    old_specifier,
+                    new_specifier,
+                    byte_range: (start, end),
+                }
+            })
+            .collect();
+        plans.push(FileImportEdits {
+            file_path: new_file.to_string_lossy().replace('\\', "/"),
+            edits,
+        });
+    }
+
+    plans
+}
+
+/// Write planned edits to disk. A file is skipped with an error if its specifiers no
+/// longer match what was planned, so a concurrent save is never clobbered.
+pub fn apply_import_edits(plans: &[FileImportEdits]) -> Result<usize, String> {
+    let mut applied = 0;
+    let mut errors = Vec::new();
+
+    for plan in plans {
+        let path = Path::new(&plan.file_path);
+        let mut content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                errors.push(format!("Failed to read {}: {}", plan.file_path, e));
+                continue;
+            }
+        };
+        let unchanged = plan.edits.iter().all(|edit| {
+            content.get(edit.byte_range.0..edit.byte_range.1) == Some(edit.old_specifier.as_str())
+        });
+        if !unchanged {
+            errors.push(format!(
+                "{} changed since its edits were computed",
+                plan.file_path
+            ));
+            continue;
+        }
+
+        let mut edits: Vec<&ImportEdit> = plan.edits.iter().collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.byte_range.0));
+        for edit in edits {
+            content.replace_range(edit.byte_range.0..edit.byte_range.1, &edit.new_specifier);
+        }
+        if let Err(e) = fs::write(path, content) {
+            errors.push(format!("Failed to write {}: {}", plan.file_path, e));
+            continue;
+        }
+        applied += 1;
+    }
+
+    if errors.is_empty() {
+        Ok(applied)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+pub(crate) fn source_files(root: &Path) -> Vec<PathBuf> {
+    let mut builder = ignore::WalkBuilder::new(root);
+    builder.hidden(false);
+    builder.git_ignore(true);
+    builder.git_exclude(true);
+    builder.require_git(false);
+    builder.filter_entry(|entry| {
+        !matches!(
+            entry.file_name().to_str(),
+            Some("node_modules" | ".git" | "dist" | "build")
+        )
+    });
+
+    builder
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Specifiers
+// ============================================================================
+
+/// Resolve a relative specifier to the file it points at, or `None` for bare
+/// specifiers and imports that don't resolve.
+pub(crate) fn resolve_relative(importer: &Path, specifier: &str) -> Option<PathBuf> {
+    let (path_part, _) = split_query(specifier);
+    let path_part = match path_part {
+        "." => "./",
+        ".." => "../",
+        other => other,
+    };
+    if !path_part.starts_with("./") && !path_part.starts_with("../") {
+        return None;
+    }
+
+    let resolve = |spec: &str| {
+        let options = ResolveOptions {
+            extensions: RESOLVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            ..Default::default()
+        };
+        resolve_module_native(
+            ResolveRequest {
+                specifier: spec.to_string(),
+                importer: importer.to_string_lossy().to_string(),
+                project_root: None,
+            },
+            Some(options),
+        )
+        .ok()
+        .and_then(|response| response.resolved_path)
+    };
+
+    let resolved = resolve(path_part).or_else(|| {
+        EMITTED_EXTENSIONS.iter().find_map(|(emitted, sources)| {
+            let stem = path_part.strip_suffix(emitted)?;
+            sources
+                .iter()
+                .find_map(|source| resolve(&format!("{stem}{source}")))
+        })
+    })?;
+    Some(normalize(Path::new(&resolved)))
+}
+
+/// Build the specifier `new_importer` needs to reach `new_target`, keeping the form
+/// `specifier` used to reach `target`.
+fn rewrite_specifier(
+    specifier: &str,
+    target: &Path,
+    new_importer: &Path,
+    new_target: &Path,
+) -> Option<String> {
+    let (path_part, query) = split_query(specifier);
+    let written = path_part.trim_end_matches('/').rsplit('/').next()?;
+    let target_name = target.file_name()?.to_str()?;
+    let new_name = new_target.file_name()?.to_str()?;
+    let new_dir = new_target.parent()?;
+
+    let directory_form = is_index(target_name) && !written.starts_with("index");
+    let last_segment = if directory_form {
+        // `./dir` keeps pointing at the directory unless the index file itself was renamed
+        (!is_index(new_name)).then(|| strip_extension(new_name).to_string())
+    } else if written == target_name {
+        Some(new_name.to_string())
+    } else if target_name
+        .strip_prefix(written)
+        .is_some_and(|rest| rest.starts_with('.'))
+    {
+        Some(strip_extension(new_name).to_string())
+    } else {
+        // Written with a different extension than the file has (`./foo.js` for `foo.ts`)
+        let written_extension = &written[written.rfind('.')?..];
+        Some(format!(
+            "{}{}",
+            strip_extension(new_name),
+            written_extension
+        ))
+    };
+
+    let destination = match last_segment {
+        Some(name) => new_dir.join(name),
+        None => new_dir.to_path_buf(),
+    };
+    let mut relative = relative_path(new_importer.parent()?, &destination)?;
+    if relative.is_empty() {
+        relative = ".".to_string();
+    } else if relative != ".." && !relative.starts_with("../") {
+        relative = format!("./{}", relative);
+    }
+    if directory_form && path_part.ends_with('/') && !relative.ends_with('/') {
+        relative.push('/');
+    }
+    Some(format!("{}{}", relative, query))
+}
+
+/// Split `./file.svg?raw` into the path and the query/fragment suffix
+fn split_query(specifier: &str) -> (&str, &str) {
+    match specifier.find(['?', '#']) {
+        Some(index) => specifier.split_at(index),
+        None => (specifier, ""),
+    }
+}
+
+fn strip_extension(name: &str) -> &str {
+    for declaration in [".d.ts", ".d.mts", ".d.cts"] {
+        if let Some(stem) = name.strip_suffix(declaration) {
+            return stem;
+        }
+    }
+    match name.rfind('.') {
+        Some(index) if index > 0 => &name[..index],
+        _ => name,
+    }
+}
+
+fn is_index(name: &str) -> bool {
+    strip_extension(name) == "index"
+}
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+/// Lexically resolve `.` and `..` components
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Where `path` ends up when `from` moves to `to`
+fn relocate(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) if rest.as_os_str().is_empty() => to.to_path_buf(),
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// `/`-separated path from `base` to `target`, or `None` across drive roots
+fn relative_path(base: &Path, target: &Path) -> Option<String> {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    if base.first() != target.first() {
+        return None;
+    }
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); base.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    Some(parts.join("/"))
+}
+
+/// 1-based line and UTF-16 column of a byte offset
+fn position_at(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].encode_utf16().count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(specifier: &str, target: &str, new_importer: &str, new_target: &str) -> String {
+        rewrite_specifier(
+            specifier,
+            Path::new(target),
+            Path::new(new_importer),
+            Path::new(new_target),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn preserves_specifier_style() {
+        // Extensionless
+        assert_eq!(
+            rewrite(
+                "./utils/math",
+                "/w/src/utils/math.ts",
+                "/w/src/app.ts",
+                "/w/src/lib/math.ts"
+            ),
+            "./lib/math"
+        );
+        // Explicit extension follows a changed extension
+        assert_eq!(
+            rewrite(
+                "./view.ts",
+                "/w/src/view.ts",
+                "/w/src/app.ts",
+                "/w/src/view.tsx"
+            ),
+            "./view.tsx"
+        );
+        // Emitted `.js` name for a TypeScript source
+        assert_eq!(
+            rewrite(
+                "../shared/api.js",
+                "/w/shared/api.ts",
+                "/w/src/app.ts",
+                "/w/shared/client.ts"
+            ),
+            "../shared/client.js"
+        );
+        // Query suffixes survive
+        assert_eq!(
+            rewrite(
+                "./icon.svg?raw",
+                "/w/src/icon.svg",
+                "/w/src/app.ts",
+                "/w/assets/icon.svg"
+            ),
+            "../assets/icon.svg?raw"
+        );
+    }
+
+    #[test]
+    fn rewrites_directory_and_index_imports() {
+        assert_eq!(
+            rewrite(
+                "./components",
+                "/w/src/components/index.tsx",
+                "/w/src/app.tsx",
+                "/w/src/ui/index.tsx"
+            ),
+            "./ui"
+        );
+        assert_eq!(
+            rewrite(
+                "./components/index",
+                "/w/src/components/index.tsx",
+                "/w/src/app.tsx",
+                "/w/src/ui/index.tsx"
+            ),
+            "./ui/index"
+        );
+        // Importer moved next to the directory's index
+        assert_eq!(
+            rewrite(
+                "..",
+                "/w/src/index.ts",
+                "/w/src/feature/nested/util.ts",
+                "/w/src/index.ts"
+            ),
+            "../.."
+        );
+        // The index file itself was renamed, so the directory form no longer resolves
+        assert_eq!(
+            rewrite(
+                "./store",
+                "/w/src/store/index.ts",
+                "/w/src/app.ts",
+                "/w/src/store/root.ts"
+            ),
+            "./store/root"
+        );
+    }
+
+    #[test]
+    fn resolves_relative_specifiers() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-import-rewrite-resolve-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(dir.join("src/components")).unwrap();
+        fs::write(dir.join("src/app.ts"), "").unwrap();
+        fs::write(dir.join("src/api.ts"), "").unwrap();
+        fs::write(dir.join("src/components/index.tsx"), "").unwrap();
+
+        let importer = dir.join("src/app.ts");
+        let dir = normalize(&dir);
+        assert_eq!(
+            resolve_relative(&importer, "./api"),
+            Some(dir.join("src/api.ts"))
+        );
+        assert_eq!(
+            resolve_relative(&importer, "./api.js"),
+            Some(dir.join("src/api.ts"))
+        );
+        assert_eq!(
+            resolve_relative(&importer, "./components"),
+            Some(dir.join("src/components/index.tsx"))
+        );
+        assert_eq!(resolve_relative(&importer, "react"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn applies_edits_and_reports_utf16_positions() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-import-rewrite-apply-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("app.ts");
+        let content = "// é😀\nimport a from \"./a\"; import b from './b';\n";
+        fs::write(&file, content).unwrap();
+
+        let edit = |old: &str, new: &str| {
+            let start = content
+                .find(&format!("{old}\""))
+                .or_else(|| content.find(&format!("{old}'")))
+                .unwrap();
+            let (start_line, start_column) = position_at(content, start);
+            let (end_line, end_column) = position_at(content, start + old.len());
+            ImportEdit {
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                old_specifier: old.to_string(),
+                new_specifier: new.to_string(),
+                byte_range: (start, start + old.len()),
+            }
+        };
+        let a = edit("./a", "./lib/a");
+        assert_eq!((a.start_line, a.start_column, a.end_column), (2, 16, 19));
+        assert_eq!(position_at(content, "// é😀".len()), (1, 7));
+
+        let plans = vec![FileImportEdits {
+            file_path: file.to_string_lossy().to_string(),
+            edits: vec![a, edit("./b", "../b")],
+        }];
+        assert_eq!(apply_import_edits(&plans), Ok(1));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "// é😀\nimport a from \"./lib/a\"; import b from '../b';\n"
+        );
+        // Re-applying finds the old specifiers gone and leaves the file alone
+        assert!(apply_import_edits(&plans).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
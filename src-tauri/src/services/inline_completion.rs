@@ -0,0 +1,574 @@
+//! Inline Completion
+//!
+//! Low-latency fill-in-the-middle completions for editor ghost text. Prompts
+//! are assembled per provider: raw FIM tokens for local Ollama models and a
+//! cursor-marked chat prompt for MiniMax. Requests are debounced server-side,
+//! a newer request cancels any that are still waiting or in flight, and
+//! results are cached by a hash of the surrounding context.
+
+use crate::commands::minimax::{minimax_chat, MinimaxMessage, MinimaxRequest, SamplingParameters};
+use crate::services::ai_metrics::{
+    estimate_tokens, AiMetricsState, AiRequestSample, AiRequestSpan,
+};
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::State;
+use tokio::sync::watch;
+
+const DEFAULT_OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+const DEFAULT_OLLAMA_MODEL: &str = "qwen2.5-coder:1.5b";
+/// Wait before sending, so requests for intermediate keystrokes are dropped
+const DEBOUNCE: Duration = Duration::from_millis(75);
+/// Completions that take longer than this are no longer useful as ghost text
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_MAX_TOKENS: u32 = 64;
+const MAX_TOKENS_LIMIT: u32 = 256;
+/// Prefix characters (closest to the cursor) sent to the model
+const MAX_PREFIX_CHARS: usize = 4000;
+/// Suffix characters (closest to the cursor) sent to the model
+const MAX_SUFFIX_CHARS: usize = 1500;
+/// Lines kept from a completion; ghost text is a small logical unit
+const MAX_COMPLETION_LINES: usize = 8;
+const CACHE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InlineProvider {
+    #[default]
+    Ollama,
+    Minimax,
+}
+
+/// Provider to complete with; every field has a default except the MiniMax key
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InlineProviderConfig {
+    pub provider: InlineProvider,
+    /// Ollama server URL or MiniMax API base
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    /// Required for MiniMax
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineCompletion {
+    /// Text to insert at the cursor; empty when there is no suggestion
+    pub text: String,
+    /// Served from the context cache without a request
+    pub cached: bool,
+    /// A newer request superseded this one before it finished
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+/// FIM control tokens of a model family
+struct FimTokens {
+    prefix: &'static str,
+    suffix: &'static str,
+    middle: &'static str,
+    stops: &'static [&'static str],
+}
+
+/// Token sets by model name, matching the families the Ollama client knows
+fn fim_tokens(model: &str) -> FimTokens {
+    let model = model.to_lowercase();
+    let is = |names: &[&str]| names.iter().any(|name| model.contains(name));
+    if is(&["qwen"]) {
+        FimTokens {
+            prefix: "<|fim_prefix|>",
+            suffix: "<|fim_suffix|>",
+            middle: "<|fim_middle|>",
+            stops: &[
+                "<|im_start|>",
+                "<|im_end|>",
+                "<|file_sep|>",
+                "<|endoftext|>",
+            ],
+        }
+    } else if is(&["llama"]) {
+        FimTokens {
+            prefix: "<|fim_prefix|>",
+            suffix: "<|fim_suffix|>",
+            middle: "<|fim_middle|>",
+            stops: &["<|eot_id|>", "<|end_of_text|>", "<|file_separator|>"],
+        }
+    } else if is(&["deepseek"]) {
+        FimTokens {
+            prefix: "<｜fim▁begin｜>",
+            suffix: "<｜fim▁hole｜>",
+            middle: "<｜fim▁end｜>",
+            stops: &["<｜end▁of▁sentence｜>", "<|EOT|>", "<|file_sep|>"],
+        }
+    } else if is(&["mistral", "codestral", "mixtral"]) {
+        FimTokens {
+            prefix: "[PREFIX]",
+            suffix: "[SUFFIX]",
+            middle: "[MIDDLE]",
+            stops: &["</s>", "[INST]", "[/INST]"],
+        }
+    } else {
+        FimTokens {
+            prefix: "<fim_prefix>",
+            suffix: "<fim_suffix>",
+            middle: "<fim_middle>",
+            stops: &["<|endoftext|>", "<file_sep>"],
+        }
+    }
+}
+
+/// Raw FIM prompt and stop sequences for an Ollama model
+fn ollama_fim_prompt(model: &str, prefix: &str, suffix: &str) -> (String, Vec<String>) {
+    let tokens = fim_tokens(model);
+    let mut stops: Vec<String> = tokens.stops.iter().map(|stop| stop.to_string()).collect();
+    stops.push(tokens.suffix.to_string());
+    stops.push("```".to_string());
+
+    let prompt = if suffix.is_empty() {
+        // Nothing after the cursor: plain continuation of the prefix
+        format!("{}{}", tokens.prefix, prefix)
+    } else {
+        format!(
+            "{}{}{}{}{}",
+            tokens.prefix, prefix, tokens.suffix, suffix, tokens.middle
+        )
+    };
+    (prompt, stops)
+}
+
+/// Chat messages asking a general model to fill the hole at the cursor
+fn minimax_fim_messages(language: &str, prefix: &str, suffix: &str) -> Vec<MinimaxMessage> {
+    let message = |role: &str, content: String| MinimaxMessage {
+        role: role.to_string(),
+        content: Some(content.into()),
+        tool_calls: None,
+        tool_call_id: None,
+        name: None,
+    };
+    vec![
+        message(
+            "system",
+            "You are a code completion engine. Reply with only the code that belongs at \
+             <CURSOR>: no explanation, no code fences, and nothing that already follows the \
+             cursor. Reply with nothing if no completion fits."
+                .to_string(),
+        ),
+        message(
+            "user",
+            format!("Language: {}\n\n{}<CURSOR>{}", language, prefix, suffix),
+        ),
+    ]
+}
+
+/// The last `max` characters of `text`
+fn tail_chars(text: &str, max: usize) -> &str {
+    if max == 0 {
+        return "";
+    }
+    match text.char_indices().rev().nth(max - 1) {
+        Some((index, _)) => &text[index..],
+        None => text,
+    }
+}
+
+/// The first `max` characters of `text`
+fn head_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+fn special_token_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"<\|[^|>]*\|>|</?fim_(?:prefix|middle|suffix)>|<｜[^｜]*｜>|<file_sep>|\[EOL\]")
+            .unwrap()
+    })
+}
+
+fn think_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<think>.*?</think>").unwrap())
+}
+
+/// Turn raw model output into ghost text that fits between prefix and suffix
+fn clean_completion(raw: &str, suffix: &str) -> String {
+    let text = think_regex().replace_all(raw, "");
+    let text = special_token_regex().replace_all(&text, "");
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    if lines
+        .first()
+        .is_some_and(|line| line.trim_start().starts_with("```"))
+    {
+        lines.remove(0);
+    }
+    if let Some(end) = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("```"))
+    {
+        lines.truncate(end);
+    }
+    lines.truncate(MAX_COMPLETION_LINES);
+    let completion = lines.join("\n");
+    let completion = completion.trim_end();
+
+    // A completion that only repeats what follows the cursor adds nothing
+    let suffix_start = suffix.trim_start();
+    if completion.trim().is_empty() || suffix_start.starts_with(completion.trim()) {
+        return String::new();
+    }
+
+    // Drop a tail that duplicates the start of the suffix's first line, such
+    // as a closing bracket the editor already has
+    let first_line = suffix_start.lines().next().unwrap_or("");
+    let overlap = (1..=first_line.len().min(completion.len()))
+        .rev()
+        .filter(|&len| first_line.is_char_boundary(len))
+        .find(|&len| completion.ends_with(&first_line[..len]))
+        .unwrap_or(0);
+    completion[..completion.len() - overlap].to_string()
+}
+
+/// Cache key for a completion context
+fn context_hash(
+    config: &InlineProviderConfig,
+    model: &str,
+    language: &str,
+    prefix: &str,
+    suffix: &str,
+    max_tokens: u32,
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        serde_json::to_string(&config.provider)
+            .unwrap_or_default()
+            .as_str(),
+        config.endpoint.as_deref().unwrap_or(""),
+        model,
+        language,
+        &max_tokens.to_string(),
+        prefix,
+        suffix,
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Completions by context hash, evicting the oldest beyond the capacity
+#[derive(Default)]
+struct CompletionCache {
+    entries: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl CompletionCache {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: String, text: String) {
+        if self.entries.insert(key.clone(), text).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+async fn complete_with_ollama(
+    client: &reqwest::Client,
+    config: &InlineProviderConfig,
+    model: &str,
+    prefix: &str,
+    suffix: &str,
+    max_tokens: u32,
+) -> Result<String, String> {
+    let endpoint = config
+        .endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .unwrap_or(DEFAULT_OLLAMA_ENDPOINT)
+        .trim_end_matches('/');
+    let (prompt, stop) = ollama_fim_prompt(model, prefix, suffix);
+    let span = AiRequestSpan::start("ollama", model, estimate_tokens(&prompt));
+    let body = serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "raw": true,
+        "stream": false,
+        "options": {
+            "num_predict": max_tokens,
+            "temperature": 0.2,
+            "stop": stop,
+        },
+    });
+
+    let response = client
+        .post(format!("{}/api/generate", endpoint))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama request failed: {}", e))?;
+    let status = response.status();
+    let value: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?;
+    if !status.is_success() {
+        span.finish(None);
+        return Err(format!("Ollama error {}: {}", status, value));
+    }
+    let text = value
+        .get("response")
+        .and_then(|text| text.as_str())
+        .unwrap_or_default()
+        .to_string();
+    record_ollama_usage(span, &value, &text);
+    Ok(text)
+}
+
+/// Finish `span` with the token counts Ollama reports, estimating any it leaves out
+pub(crate) fn record_ollama_usage(span: AiRequestSpan, response: &serde_json::Value, text: &str) {
+    if let Some(tokens) = response.get("prompt_eval_count").and_then(|v| v.as_u64()) {
+        span.record_input_tokens(tokens);
+    }
+    let output_tokens = response
+        .get("eval_count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or_else(|| estimate_tokens(text));
+    span.finish(Some(output_tokens));
+}
+
+async fn complete_with_minimax(
+    config: &InlineProviderConfig,
+    model: &str,
+    language: &str,
+    prefix: &str,
+    suffix: &str,
+    max_tokens: u32,
+) -> Result<String, String> {
+    let api_key = config
+        .api_key
+        .clone()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or("MiniMax inline completion requires an API key")?;
+    let request = MinimaxRequest {
+        model: model.to_string(),
+        messages: minimax_fim_messages(language, prefix, suffix),
+        max_tokens: Some(max_tokens),
+        temperature: Some(0.1),
+        tools: None,
+        response_schema: None,
+        sampling: SamplingParameters::default(),
+    };
+    let response = tokio::time::timeout(
+        REQUEST_TIMEOUT,
+        minimax_chat(api_key, request, config.endpoint.clone()),
+    )
+    .await
+    .map_err(|_| "MiniMax inline completion timed out".to_string())??;
+    Ok(response
+        .get("content")
+        .and_then(|content| content.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Request generations and cached completions shared by inline requests
+#[derive(Clone)]
+pub struct InlineCompletionState {
+    /// Generation of the newest request; older requests stop once it moves
+    latest: Arc<watch::Sender<u64>>,
+    cache: Arc<Mutex<CompletionCache>>,
+    /// Reused so requests skip connection setup
+    client: reqwest::Client,
+}
+
+impl InlineCompletionState {
+    pub fn new() -> Self {
+        Self {
+            latest: Arc::new(watch::channel(0).0),
+            cache: Arc::new(Mutex::new(CompletionCache::default())),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for InlineCompletionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Complete the code between `prefix` and `suffix` for ghost text
+///
+/// Each call supersedes the previous one: a request that is still debouncing
+/// or waiting on the provider returns early with `cancelled` set.
+///
+/// # Arguments
+/// * `prefix` - Text before the cursor
+/// * `suffix` - Text after the cursor
+/// * `language` - Editor language id
+/// * `max_tokens` - Completion length limit
+/// * `provider` - Provider, endpoint, and model; local Ollama when omitted
+#[fluxel_command]
+pub async fn ai_complete_inline(
+    prefix: String,
+    suffix: String,
+    language: String,
+    max_tokens: Option<u32>,
+    provider: Option<InlineProviderConfig>,
+    state: State<'_, InlineCompletionState>,
+    metrics: State<'_, AiMetricsState>,
+) -> Result<InlineCompletion, String> {
+    let started = Instant::now();
+    let config = provider.unwrap_or_default();
+    let max_tokens = max_tokens
+        .unwrap_or(DEFAULT_MAX_TOKENS)
+        .clamp(1, MAX_TOKENS_LIMIT);
+    let model = config
+        .model
+        .clone()
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| match config.provider {
+            InlineProvider::Ollama => DEFAULT_OLLAMA_MODEL.to_string(),
+            // MiniMax picks its default model for an empty name
+            InlineProvider::Minimax => String::new(),
+        });
+    let prefix = tail_chars(&prefix, MAX_PREFIX_CHARS);
+    let suffix = head_chars(&suffix, MAX_SUFFIX_CHARS);
+
+    let mut generation = 0;
+    state.latest.send_modify(|latest| {
+        *latest += 1;
+        generation = *latest;
+    });
+
+    let key = context_hash(&config, &model, &language, prefix, suffix, max_tokens);
+    if let Some(text) = state.cache.lock().map_err(|e| e.to_string())?.get(&key) {
+        return Ok(InlineCompletion {
+            text,
+            cached: true,
+            cancelled: false,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    let mut latest = state.latest.subscribe();
+    let superseded = async move {
+        let _ = latest.wait_for(|latest| *latest != generation).await;
+    };
+    let request = async {
+        tokio::time::sleep(DEBOUNCE).await;
+        match config.provider {
+            InlineProvider::Ollama => {
+                complete_with_ollama(&state.client, &config, &model, prefix, suffix, max_tokens)
+                    .await
+            }
+            InlineProvider::Minimax => {
+                complete_with_minimax(&config, &model, &language, prefix, suffix, max_tokens).await
+            }
+        }
+    };
+
+    let raw = tokio::select! {
+        _ = superseded => {
+            return Ok(InlineCompletion {
+                cancelled: true,
+                duration_ms: started.elapsed().as_millis() as u64,
+                ..Default::default()
+            });
+        }
+        result = request => result,
+    };
+    let provider_name = match config.provider {
+        InlineProvider::Ollama => "ollama",
+        InlineProvider::Minimax => "minimax",
+    };
+    metrics.record(AiRequestSample::completed(
+        provider_name,
+        &model,
+        "inlineCompletion",
+        started.elapsed(),
+        raw.as_deref().ok(),
+    ));
+    let raw = raw?;
+
+    let text = clean_completion(&raw, suffix);
+    state
+        .cache
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(key, text.clone());
+
+    Ok(InlineCompletion {
+        text,
+        cached: false,
+        cancelled: false,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assembles_prompts_and_cleans_completions() {
+        let (prompt, stops) = ollama_fim_prompt("qwen2.5-coder:1.5b", "fn a() {", "}");
+        assert_eq!(
+            prompt,
+            "<|fim_prefix|>fn a() {<|fim_suffix|>}<|fim_middle|>"
+        );
+        assert!(stops.contains(&"<|fim_suffix|>".to_string()));
+        let (prompt, _) = ollama_fim_prompt("deepseek-coder", "x = ", "");
+        assert_eq!(prompt, "<｜fim▁begin｜>x = ");
+
+        assert_eq!(tail_chars("héllo", 3), "llo");
+        assert_eq!(tail_chars("hé", 5), "hé");
+        assert_eq!(head_chars("héllo", 2), "hé");
+
+        assert_eq!(
+            clean_completion("```rust\nlet x = 1;\n```\nmore", ""),
+            "let x = 1;"
+        );
+        assert_eq!(
+            clean_completion("<think>plan</think>a + b)<|endoftext|>", ");\n"),
+            "a + b"
+        );
+        assert_eq!(clean_completion("}", "\n}\n"), "");
+
+        let config = InlineProviderConfig::default();
+        let key = context_hash(&config, "m", "rust", "a", "b", 64);
+        assert_eq!(key, context_hash(&config, "m", "rust", "a", "b", 64));
+        assert_ne!(key, context_hash(&config, "m", "rust", "ab", "", 64));
+
+        let mut cache = CompletionCache::default();
+        for index in 0..=CACHE_CAPACITY {
+            cache.insert(index.to_string(), index.to_string());
+        }
+        assert!(cache.get("0").is_none());
+        assert_eq!(cache.get("1").as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,101 @@
+//! I/O accounting for profiler spans.
+//!
+//! File operations tally the bytes and files they read and write, and the totals
+//! are recorded on the span they were created in as `bytes_read`,
+//! `bytes_written`, `files_read` and `files_written` when the tally is dropped.
+//! The span must declare the fields it uses as `tracing::field::Empty`; directory
+//! listings count the entries they read as files. Attribution ranks spans by
+//! these, telling disk-bound slowness from CPU-bound. A no-op unless the
+//! `profiling` feature is enabled.
+
+#[cfg(feature = "profiling")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Running I/O totals for the span that was current when it was created.
+pub struct IoAccounting {
+    #[cfg(feature = "profiling")]
+    span: tracing::Span,
+    #[cfg(feature = "profiling")]
+    bytes_read: AtomicU64,
+    #[cfg(feature = "profiling")]
+    bytes_written: AtomicU64,
+    #[cfg(feature = "profiling")]
+    files_read: AtomicU64,
+    #[cfg(feature = "profiling")]
+    files_written: AtomicU64,
+}
+
+impl IoAccounting {
+    /// Tally I/O for the current span. Create it before handing work to a
+    /// blocking thread, where the span is no longer current.
+    pub fn current() -> Self {
+        Self {
+            #[cfg(feature = "profiling")]
+            span: tracing::Span::current(),
+            #[cfg(feature = "profiling")]
+            bytes_read: AtomicU64::new(0),
+            #[cfg(feature = "profiling")]
+            bytes_written: AtomicU64::new(0),
+            #[cfg(feature = "profiling")]
+            files_read: AtomicU64::new(0),
+            #[cfg(feature = "profiling")]
+            files_written: AtomicU64::new(0),
+        }
+    }
+
+    /// Count a file read, with the bytes read from it so far
+    pub fn file_read(&self, bytes: u64) {
+        #[cfg(feature = "profiling")]
+        {
+            self.files_read.fetch_add(1, Ordering::Relaxed);
+            self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+        }
+        #[cfg(not(feature = "profiling"))]
+        let _ = bytes;
+    }
+
+    /// Count further bytes read from files already counted
+    pub fn bytes_read(&self, bytes: u64) {
+        #[cfg(feature = "profiling")]
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+        #[cfg(not(feature = "profiling"))]
+        let _ = bytes;
+    }
+
+    /// Count a file written, with the bytes written to it
+    pub fn file_written(&self, bytes: u64) {
+        #[cfg(feature = "profiling")]
+        {
+            self.files_written.fetch_add(1, Ordering::Relaxed);
+            self.bytes_written.fetch_add(bytes, Ordering::Relaxed);
+        }
+        #[cfg(not(feature = "profiling"))]
+        let _ = bytes;
+    }
+
+    /// Count directory entries read by a listing
+    pub fn entries_listed(&self, count: u64) {
+        #[cfg(feature = "profiling")]
+        self.files_read.fetch_add(count, Ordering::Relaxed);
+        #[cfg(not(feature = "profiling"))]
+        let _ = count;
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl Drop for IoAccounting {
+    fn drop(&mut self) {
+        let totals = [
+            ("bytes_read", &self.bytes_read),
+            ("bytes_written", &self.bytes_written),
+            ("files_read", &self.files_read),
+            ("files_written", &self.files_written),
+        ];
+        for (field, total) in totals {
+            let total = total.load(Ordering::Relaxed);
+            if total > 0 {
+                self.span.record(field, total);
+            }
+        }
+    }
+}
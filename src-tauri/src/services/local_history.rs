@@ -0,0 +1,436 @@
+//! Local History Service
+//!
+//! Snapshots files just before they are overwritten so edits can be recovered
+//! without git. Snapshots live in `<workspace>/.fluxel/history/`:
+//! - `files/<sha256 of relative path>.json` - the versions of one file, oldest first
+//! - `blobs/<sha256 of relative path>/<sha256>` - the contents of those versions,
+//!   stored once however many versions of the file share them
+//!
+//! History is capped per file by version count and size. Recording a version
+//! prunes only that file: its oldest versions are dropped first, along with the
+//! blobs no remaining version references.
+
+use crate::services::file_guard::DirtyFilesState;
+use crate::services::workspace_store::sha256_hex;
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// Files larger than this are not snapshotted
+const MAX_SNAPSHOT_SIZE: u64 = 2 * 1024 * 1024;
+const MAX_VERSIONS_PER_FILE: usize = 50;
+/// Total blob size kept per file; the newest version is always kept
+const MAX_HISTORY_BYTES_PER_FILE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryVersion {
+    pub id: String,
+    /// SHA-256 of the content
+    pub hash: String,
+    pub size: u64,
+    /// Snapshot time (unix milliseconds)
+    pub timestamp: u64,
+    /// What produced the snapshot, e.g. `"save"` or `"restore"`
+    pub source: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileHistory {
+    /// Workspace-relative path, forward slashes
+    path: String,
+    versions: Vec<HistoryVersion>,
+}
+
+// ============================================================================
+// History Store
+// ============================================================================
+
+struct HistoryStore {
+    workspace_root: PathBuf,
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    fn for_workspace(workspace_root: &Path) -> Self {
+        Self {
+            workspace_root: workspace_root.to_path_buf(),
+            dir: workspace_root.join(".fluxel").join("history"),
+        }
+    }
+
+    /// Workspace-relative path of `file`. `..` is rejected rather than resolved,
+    /// so a path can't climb out of the workspace after the prefix check.
+    fn relative(&self, file: &Path) -> Result<String, String> {
+        let outside = || format!("{:?} is outside the workspace", file);
+        let absolute = if file.is_absolute() {
+            file.to_path_buf()
+        } else {
+            self.workspace_root.join(file)
+        };
+        let rel = absolute
+            .strip_prefix(&self.workspace_root)
+            .map_err(|_| outside())?;
+        let mut parts = Vec::new();
+        for component in rel.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy()),
+                Component::CurDir => {}
+                _ => return Err(outside()),
+            }
+        }
+        if parts.is_empty() {
+            return Err(format!("{:?} is not a file in the workspace", file));
+        }
+        Ok(parts.join("/"))
+    }
+
+    fn manifest_path(&self, relative: &str) -> PathBuf {
+        self.dir
+            .join("files")
+            .join(format!("{}.json", sha256_hex(relative.as_bytes())))
+    }
+
+    /// Directory holding the contents of the versions of `relative`
+    fn blob_dir(&self, relative: &str) -> PathBuf {
+        self.dir.join("blobs").join(sha256_hex(relative.as_bytes()))
+    }
+
+    fn blob_path(&self, relative: &str, hash: &str) -> PathBuf {
+        self.blob_dir(relative).join(hash)
+    }
+
+    fn load(&self, relative: &str) -> Result<FileHistory, String> {
+        match fs::read_to_string(self.manifest_path(relative)) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse history of {}: {}", relative, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(FileHistory {
+                path: relative.to_string(),
+                versions: Vec::new(),
+            }),
+            Err(e) => Err(format!("Failed to read history of {}: {}", relative, e)),
+        }
+    }
+
+    fn save(&self, history: &FileHistory) -> Result<(), String> {
+        let path = self.manifest_path(&history.path);
+        if history.versions.is_empty() {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("Failed to remove {:?}: {}", path, e))
+                }
+                _ => Ok(()),
+            };
+        }
+        let json = serde_json::to_string_pretty(history).map_err(|e| e.to_string())?;
+        write_atomic(&path, json.as_bytes())
+    }
+
+    fn ensure_dirs(&self, relative: &str) -> Result<(), String> {
+        for dir in [self.blob_dir(relative), self.dir.join("files")] {
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        }
+        // Keep history out of commits
+        let gitignore = self.dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n").map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot `content` as the newest version of `relative`, unless it matches the
+    /// newest version already
+    fn record(
+        &self,
+        relative: &str,
+        content: &[u8],
+        source: &str,
+    ) -> Result<Option<HistoryVersion>, String> {
+        if content.len() as u64 > MAX_SNAPSHOT_SIZE {
+            return Ok(None);
+        }
+        let hash = sha256_hex(content);
+        let mut history = self.load(relative)?;
+        if history.versions.last().is_some_and(|v| v.hash == hash) {
+            return Ok(None);
+        }
+
+        self.ensure_dirs(relative)?;
+        let blob = self.blob_path(relative, &hash);
+        if !blob.exists() {
+            write_atomic(&blob, content)?;
+        }
+
+        let timestamp = now_millis();
+        let version = HistoryVersion {
+            id: format!("{}-{}", timestamp, &hash[..8]),
+            hash,
+            size: content.len() as u64,
+            timestamp,
+            source: source.to_string(),
+        };
+        history.versions.push(version.clone());
+        self.prune(&mut history);
+        self.save(&history)?;
+        Ok(Some(version))
+    }
+
+    fn read(&self, relative: &str, version_id: &str) -> Result<(HistoryVersion, Vec<u8>), String> {
+        let version = self
+            .load(relative)?
+            .versions
+            .into_iter()
+            .find(|v| v.id == version_id)
+            .ok_or_else(|| format!("Version {} of {} not found", version_id, relative))?;
+        let content = fs::read(self.blob_path(relative, &version.hash))
+            .map_err(|e| format!("Failed to read version {}: {}", version_id, e))?;
+        Ok((version, content))
+    }
+
+    /// Drop the oldest versions of `history` until it fits the per-file caps,
+    /// then delete the blobs of the file that no version references
+    fn prune(&self, history: &mut FileHistory) {
+        let excess = history.versions.len().saturating_sub(MAX_VERSIONS_PER_FILE);
+        history.versions.drain(..excess);
+        while history.versions.len() > 1 && referenced_size(history) > MAX_HISTORY_BYTES_PER_FILE {
+            history.versions.remove(0);
+        }
+
+        let referenced: HashSet<&str> = history.versions.iter().map(|v| v.hash.as_str()).collect();
+        if let Ok(blobs) = fs::read_dir(self.blob_dir(&history.path)) {
+            for blob in blobs.filter_map(|entry| entry.ok()) {
+                let name = blob.file_name().to_string_lossy().to_string();
+                if !referenced.contains(name.as_str()) {
+                    let _ = fs::remove_file(blob.path());
+                }
+            }
+        }
+    }
+}
+
+/// Size of the distinct blobs `history` references
+fn referenced_size(history: &FileHistory) -> u64 {
+    let mut seen = HashSet::new();
+    history
+        .versions
+        .iter()
+        .filter(|v| seen.insert(v.hash.as_str()))
+        .map(|v| v.size)
+        .sum()
+}
+
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Serializes read-modify-write cycles on history manifests
+#[derive(Clone, Default)]
+pub struct LocalHistoryState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl LocalHistoryState {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn with_store<T>(
+        &self,
+        workspace_root: &str,
+        f: impl FnOnce(&HistoryStore) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        f(&HistoryStore::for_workspace(Path::new(workspace_root)))
+    }
+}
+
+/// Snapshot a file's current on-disk content. Call before overwriting it; returns
+/// `None` when the file doesn't exist yet, or its content is unchanged since the
+/// last snapshot or too large to keep.
+#[fluxel_command]
+pub async fn record_local_history(
+    workspace_root: String,
+    file_path: String,
+    state: State<'_, LocalHistoryState>,
+) -> Result<Option<HistoryVersion>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(&workspace_root, |store| {
+            let relative = store.relative(Path::new(&file_path))?;
+            if relative.starts_with(".fluxel/history/") {
+                return Ok(None);
+            }
+            let content = match fs::read(store.workspace_root.join(&relative)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(format!("Failed to read {}: {}", file_path, e)),
+            };
+            store.record(&relative, &content, "save")
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List the snapshots of a file, newest first
+#[fluxel_command]
+pub async fn list_local_history(
+    workspace_root: String,
+    file_path: String,
+    state: State<'_, LocalHistoryState>,
+) -> Result<Vec<HistoryVersion>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(&workspace_root, |store| {
+            let relative = store.relative(Path::new(&file_path))?;
+            let mut versions = store.load(&relative)?.versions;
+            versions.reverse();
+            Ok(versions)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Read the content of a snapshot
+#[fluxel_command]
+pub async fn read_local_history_version(
+    workspace_root: String,
+    file_path: String,
+    version_id: String,
+    state: State<'_, LocalHistoryState>,
+) -> Result<String, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(&workspace_root, |store| {
+            let relative = store.relative(Path::new(&file_path))?;
+            let (_, content) = store.read(&relative, &version_id)?;
+            String::from_utf8(content).map_err(|_| "Version content is not valid UTF-8".to_string())
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Overwrite a file with a snapshot. The content being replaced is snapshotted
+/// first, so a restore can itself be undone.
+///
+/// # Arguments
+/// * `force` - Restore even if the file has unsaved editor changes
+#[fluxel_command]
+pub async fn restore_local_history_version(
+    workspace_root: String,
+    file_path: String,
+    version_id: String,
+    force: Option<bool>,
+    state: State<'_, LocalHistoryState>,
+    dirty_files: State<'_, DirtyFilesState>,
+) -> Result<HistoryVersion, String> {
+    let state = state.inner().clone();
+    let dirty_files = dirty_files.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(&workspace_root, |store| {
+            let relative = store.relative(Path::new(&file_path))?;
+            let target = store.workspace_root.join(&relative);
+            dirty_files.guard(
+                "restore_local_history_version",
+                std::slice::from_ref(&target),
+                force.unwrap_or(false),
+            )?;
+
+            let (version, content) = store.read(&relative, &version_id)?;
+            if let Ok(current) = fs::read(&target) {
+                store.record(&relative, &current, "restore")?;
+            }
+            fs::write(&target, &content)
+                .map_err(|e| format!("Failed to restore {}: {}", file_path, e))?;
+            println!("[LocalHistory] Restored {} to {}", relative, version.id);
+            Ok(version)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_deduplicates_and_prunes_versions() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-local-history-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let store = HistoryStore::for_workspace(&dir);
+        let relative = store.relative(&dir.join("src/main.ts")).unwrap();
+        assert_eq!(relative, "src/main.ts");
+        assert_eq!(
+            store.relative(Path::new("./src/./lib.rs")).unwrap(),
+            "src/lib.rs"
+        );
+        assert!(store.relative(Path::new("/elsewhere/file.ts")).is_err());
+        assert!(store.relative(&dir.join("../escape.ts")).is_err());
+        assert!(store.relative(&dir.join("src/../../escape.ts")).is_err());
+        assert!(store.relative(Path::new("../escape.ts")).is_err());
+        assert!(store.relative(&dir).is_err());
+
+        let first = store.record(&relative, b"one", "save").unwrap().unwrap();
+        assert!(store.record(&relative, b"one", "save").unwrap().is_none());
+        store.record(&relative, b"two", "save").unwrap().unwrap();
+        // Same content as `first`: a new version sharing its blob
+        store.record(&relative, b"one", "save").unwrap().unwrap();
+
+        let history = store.load(&relative).unwrap();
+        assert_eq!(history.versions.len(), 3);
+        let (version, content) = store.read(&relative, &first.id).unwrap();
+        assert_eq!(version.hash, first.hash);
+        assert_eq!(content, b"one");
+        let blobs = fs::read_dir(store.blob_dir(&relative)).unwrap().count();
+        assert_eq!(blobs, 2);
+
+        // Another file with the same content keeps its own blob
+        let other = store.relative(Path::new("src/other.ts")).unwrap();
+        let other_first = store.record(&other, b"one", "save").unwrap().unwrap();
+
+        for i in 0..MAX_VERSIONS_PER_FILE + 5 {
+            store
+                .record(&relative, format!("v{}", i).as_bytes(), "save")
+                .unwrap();
+        }
+        let history = store.load(&relative).unwrap();
+        assert_eq!(history.versions.len(), MAX_VERSIONS_PER_FILE);
+        // Versions dropped by the cap take their blobs with them
+        assert!(store.read(&relative, &first.id).is_err());
+        let blobs = fs::read_dir(store.blob_dir(&relative)).unwrap().count();
+        assert_eq!(blobs, MAX_VERSIONS_PER_FILE);
+        // and pruning one file leaves the others alone
+        assert_eq!(store.read(&other, &other_first.id).unwrap().1, b"one");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
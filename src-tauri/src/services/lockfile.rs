@@ -0,0 +1,1210 @@
+//! Lockfile Parsing
+//!
+//! Reads lockfiles (package-lock.json, pnpm-lock.yaml, yarn.lock, bun.lock,
+//! Cargo.lock) into a normalized map of package name → resolved version →
+//! dependency paths (the chain of packages through which each version is pulled
+//! in from the project's direct dependencies).
+
+use crate::services::dependency_audit::Ecosystem;
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Keep the report small for packages that are pulled in from many places
+const MAX_PATHS_PER_VERSION: usize = 5;
+
+/// Lockfile format, named after the tool that writes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockfileKind {
+    Bun,
+    Pnpm,
+    Yarn,
+    Npm,
+    Cargo,
+}
+
+impl LockfileKind {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            LockfileKind::Bun => "bun.lock",
+            LockfileKind::Pnpm => "pnpm-lock.yaml",
+            LockfileKind::Yarn => "yarn.lock",
+            LockfileKind::Npm => "package-lock.json",
+            LockfileKind::Cargo => "Cargo.lock",
+        }
+    }
+
+    pub fn ecosystem(self) -> Ecosystem {
+        match self {
+            LockfileKind::Cargo => Ecosystem::Cargo,
+            _ => Ecosystem::Npm,
+        }
+    }
+}
+
+/// Where a resolved version comes from
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedVersion {
+    /// Dependency chains from a direct dependency down to this package,
+    /// e.g. `["react-dom", "scheduler"]`. Empty if unreachable.
+    pub paths: Vec<Vec<String>>,
+    /// Only reachable through development dependencies
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedLockfile {
+    pub kind: LockfileKind,
+    pub path: String,
+    pub ecosystem: Ecosystem,
+    /// package name → resolved version → where it comes from
+    pub packages: BTreeMap<String, BTreeMap<String, ResolvedVersion>>,
+}
+
+// ============================================================================
+// Dependency Graph
+// ============================================================================
+
+#[derive(Debug)]
+struct LockNode {
+    name: String,
+    version: String,
+    /// Dependencies and whether the edge is a dev dependency
+    dependencies: Vec<(usize, bool)>,
+    /// The project itself and workspace members: traversed, never reported
+    local: bool,
+}
+
+/// Package graph shared by all lockfile formats
+#[derive(Debug, Default)]
+struct LockGraph {
+    nodes: Vec<LockNode>,
+    /// Project and workspace member nodes whose dependencies are the direct ones
+    roots: Vec<usize>,
+}
+
+impl LockGraph {
+    fn add_node(&mut self, name: &str, version: &str, local: bool) -> usize {
+        self.nodes.push(LockNode {
+            name: name.to_string(),
+            version: version.to_string(),
+            dependencies: Vec::new(),
+            local,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn add_root(&mut self, name: &str) -> usize {
+        let index = self.add_node(name, "", true);
+        self.roots.push(index);
+        index
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, dev: bool) {
+        if from != to && !self.nodes[from].dependencies.contains(&(to, dev)) {
+            self.nodes[from].dependencies.push((to, dev));
+        }
+    }
+
+    /// Non-local dependencies of the project and its workspace members
+    fn direct_dependencies(&self) -> Vec<(usize, bool)> {
+        let mut direct = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(usize, bool)> = self.roots.iter().map(|&r| (r, false)).collect();
+        while let Some((index, dev)) = queue.pop_front() {
+            if !self.nodes[index].local {
+                direct.push((index, dev));
+            } else if visited.insert(index) {
+                for &(dependency, edge_dev) in &self.nodes[index].dependencies {
+                    queue.push_back((dependency, dev || edge_dev));
+                }
+            }
+        }
+        direct
+    }
+
+    /// Walk the graph breadth-first from the production dependencies, then from
+    /// the dev dependencies, recording the shortest path to every node.
+    fn into_packages(self) -> BTreeMap<String, BTreeMap<String, ResolvedVersion>> {
+        let direct = self.direct_dependencies();
+        let mut paths: Vec<Option<(Vec<String>, bool)>> = vec![None; self.nodes.len()];
+
+        for dev_pass in [false, true] {
+            let mut queue = VecDeque::new();
+            for &(root, dev) in &direct {
+                if dev == dev_pass && paths[root].is_none() {
+                    paths[root] = Some((vec![self.nodes[root].name.clone()], dev_pass));
+                    queue.push_back(root);
+                }
+            }
+            while let Some(index) = queue.pop_front() {
+                let base = paths[index].as_ref().map(|(p, _)| p.clone()).unwrap();
+                for &(dependency, _) in &self.nodes[index].dependencies {
+                    if paths[dependency].is_none() {
+                        let mut path = base.clone();
+                        path.push(self.nodes[dependency].name.clone());
+                        paths[dependency] = Some((path, dev_pass));
+                        queue.push_back(dependency);
+                    }
+                }
+            }
+        }
+
+        let mut packages: BTreeMap<String, BTreeMap<String, ResolvedVersion>> = BTreeMap::new();
+        for (node, path) in self.nodes.into_iter().zip(paths) {
+            if node.local {
+                continue;
+            }
+            let reached = path.is_some();
+            let entry = packages
+                .entry(node.name)
+                .or_default()
+                .entry(node.version)
+                .or_insert_with(|| ResolvedVersion {
+                    paths: Vec::new(),
+                    dev: reached,
+                });
+            if let Some((path, dev)) = path {
+                // A version is dev-only if every copy of it is
+                entry.dev &= dev;
+                if entry.paths.len() < MAX_PATHS_PER_VERSION && !entry.paths.contains(&path) {
+                    entry.paths.push(path);
+                }
+            }
+        }
+        packages
+    }
+}
+
+/// Split `name@version` where the name may be scoped (`@scope/name@1.0.0`)
+fn split_name_version(ident: &str) -> Option<(&str, &str)> {
+    let at = ident.get(1..)?.find('@')? + 1;
+    Some((&ident[..at], &ident[at + 1..]))
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+}
+
+fn dependency_names(entry: &serde_json::Value, sections: &[&str]) -> Vec<(String, bool)> {
+    let mut names = Vec::new();
+    for section in sections {
+        if let Some(deps) = entry.get(*section).and_then(|v| v.as_object()) {
+            let dev = *section == "devDependencies";
+            names.extend(deps.keys().map(|k| (k.clone(), dev)));
+        }
+    }
+    names
+}
+
+/// Dependency sections of a project/workspace manifest
+const NODE_DEPENDENCY_SECTIONS: &[&str] = &[
+    "dependencies",
+    "optionalDependencies",
+    "peerDependencies",
+    "devDependencies",
+];
+
+/// Dependency sections of an installed package (its dev dependencies aren't installed)
+const NODE_PACKAGE_SECTIONS: &[&str] =
+    &["dependencies", "optionalDependencies", "peerDependencies"];
+
+// ============================================================================
+// package-lock.json
+// ============================================================================
+
+/// Package name from an install location like `node_modules/a/node_modules/@scope/b`
+fn name_from_location(location: &str) -> &str {
+    match location.rfind("node_modules/") {
+        Some(pos) => &location[pos + "node_modules/".len()..],
+        None => location.rsplit('/').next().unwrap_or(location),
+    }
+}
+
+/// Resolve `name` as Node would when required from `location`: the nearest
+/// `node_modules/<name>` walking up the install tree
+fn resolve_location(
+    locations: &HashMap<String, usize>,
+    location: &str,
+    name: &str,
+) -> Option<usize> {
+    let mut base = location.to_string();
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", base, name)
+        };
+        if let Some(&index) = locations.get(&candidate) {
+            return Some(index);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = match base.rfind("/node_modules/") {
+            Some(pos) => base[..pos].to_string(),
+            None => String::new(),
+        };
+    }
+}
+
+/// Flatten a v1 `dependencies` tree into the v2 `packages` location map
+fn flatten_v1_dependencies(
+    tree: &serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    out: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    for (name, entry) in tree {
+        let location = if prefix.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", prefix, name)
+        };
+        let mut flattened = serde_json::Map::new();
+        if let Some(version) = entry.get("version") {
+            flattened.insert("version".to_string(), version.clone());
+        }
+        if let Some(requires) = entry.get("requires") {
+            flattened.insert("dependencies".to_string(), requires.clone());
+        }
+        if let Some(dev) = entry.get("dev") {
+            flattened.insert("dev".to_string(), dev.clone());
+        }
+        out.insert(location.clone(), serde_json::Value::Object(flattened));
+        if let Some(nested) = entry.get("dependencies").and_then(|v| v.as_object()) {
+            flatten_v1_dependencies(nested, &location, out);
+        }
+    }
+}
+
+fn parse_package_lock(content: &str, project_root: &Path) -> Result<LockGraph, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid package-lock.json: {}", e))?;
+
+    let packages = match json.get("packages").and_then(|v| v.as_object()) {
+        Some(packages) => packages.clone(),
+        None => {
+            let mut flattened = serde_json::Map::new();
+            if let Some(tree) = json.get("dependencies").and_then(|v| v.as_object()) {
+                flatten_v1_dependencies(tree, "", &mut flattened);
+            }
+            // v1 has no root entry; the manifest provides the direct dependencies
+            match read_json(&project_root.join("package.json")) {
+                Some(manifest) => {
+                    flattened.insert(String::new(), manifest);
+                }
+                None => {
+                    // Without a manifest, treat every top-level entry as direct
+                    let mut dependencies = serde_json::Map::new();
+                    let mut dev_dependencies = serde_json::Map::new();
+                    for (location, entry) in &flattened {
+                        if location.matches("node_modules/").count() != 1 {
+                            continue;
+                        }
+                        let name = name_from_location(location).to_string();
+                        if entry.get("dev").and_then(|v| v.as_bool()) == Some(true) {
+                            dev_dependencies.insert(name, serde_json::Value::Null);
+                        } else {
+                            dependencies.insert(name, serde_json::Value::Null);
+                        }
+                    }
+                    flattened.insert(
+                        String::new(),
+                        serde_json::json!({
+                            "dependencies": dependencies,
+                            "devDependencies": dev_dependencies,
+                        }),
+                    );
+                }
+            }
+            flattened
+        }
+    };
+
+    let mut graph = LockGraph::default();
+    let mut locations: HashMap<String, usize> = HashMap::new();
+    let mut links: Vec<(String, String)> = Vec::new();
+
+    for (location, entry) in &packages {
+        // Workspace symlinks point at the member's own entry
+        if entry.get("link").and_then(|v| v.as_bool()) == Some(true) {
+            if let Some(target) = entry.get("resolved").and_then(|v| v.as_str()) {
+                links.push((location.clone(), target.to_string()));
+            }
+            continue;
+        }
+        let name = entry
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| name_from_location(location));
+        let index = if location.contains("node_modules/") {
+            let version = entry.get("version").and_then(|v| v.as_str()).unwrap_or("");
+            graph.add_node(name, version, false)
+        } else {
+            // "" is the project, other locations are workspace members
+            graph.add_root(name)
+        };
+        locations.insert(location.clone(), index);
+    }
+    for (location, target) in links {
+        if let Some(&index) = locations.get(&target) {
+            locations.insert(location, index);
+        }
+    }
+
+    for (location, entry) in &packages {
+        let Some(&index) = locations.get(location) else {
+            continue;
+        };
+        let sections = if graph.nodes[index].local {
+            NODE_DEPENDENCY_SECTIONS
+        } else {
+            NODE_PACKAGE_SECTIONS
+        };
+        for (name, dev) in dependency_names(entry, sections) {
+            if let Some(target) = resolve_location(&locations, location, &name) {
+                graph.add_edge(index, target, dev);
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+// ============================================================================
+// pnpm-lock.yaml
+// ============================================================================
+
+/// Normalize a pnpm package key to `name@version(peers)`.
+/// v5: `/name/1.0.0_peer`, v6: `/name@1.0.0(peer)`, v9: `name@1.0.0(peer)`.
+fn normalize_pnpm_key(key: &str, legacy: bool) -> Option<(String, String)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    if legacy {
+        let slash = key.rfind('/')?;
+        return Some((key[..slash].to_string(), key[slash + 1..].to_string()));
+    }
+    let (name, version) = split_name_version(key)?;
+    Some((name.to_string(), version.to_string()))
+}
+
+/// Strip peer-dependency suffixes from a pnpm version (`1.0.0(react@18)`, `1.0.0_react@18`)
+fn clean_pnpm_version(version: &str, legacy: bool) -> &str {
+    let end = if legacy {
+        version.find('_')
+    } else {
+        version.find('(')
+    };
+    &version[..end.unwrap_or(version.len())]
+}
+
+fn yaml_str(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Dependency name → reference pairs from a pnpm section. Importer entries are
+/// `{ specifier, version }` maps in v6+, plain strings before.
+fn pnpm_dependencies(entry: &serde_yaml::Value, section: &str) -> Vec<(String, String)> {
+    let Some(map) = entry.get(section).and_then(|v| v.as_mapping()) else {
+        return Vec::new();
+    };
+    map.iter()
+        .filter_map(|(name, reference)| {
+            let name = yaml_str(name)?;
+            let reference = match reference {
+                serde_yaml::Value::Mapping(_) => reference.get("version").and_then(yaml_str)?,
+                other => yaml_str(other)?,
+            };
+            Some((name, reference))
+        })
+        .collect()
+}
+
+fn parse_pnpm_lock(content: &str) -> Result<LockGraph, String> {
+    let doc: serde_yaml::Value =
+        serde_yaml::from_str(content).map_err(|e| format!("Invalid pnpm-lock.yaml: {}", e))?;
+    let lockfile_version: f64 = doc
+        .get("lockfileVersion")
+        .and_then(yaml_str)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(9.0);
+    let legacy = lockfile_version < 6.0;
+
+    // v9 moved the dependency graph from `packages` into `snapshots`
+    let entries = doc
+        .get("snapshots")
+        .or_else(|| doc.get("packages"))
+        .and_then(|v| v.as_mapping())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut graph = LockGraph::default();
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    for key in entries.keys() {
+        let Some((name, version)) = yaml_str(key).and_then(|k| normalize_pnpm_key(&k, legacy))
+        else {
+            continue;
+        };
+        let index = graph.add_node(&name, clean_pnpm_version(&version, legacy), false);
+        ids.insert(format!("{}@{}", name, version), index);
+    }
+
+    let resolve = |name: &str, reference: &str| -> Option<usize> {
+        if reference.starts_with("link:") || reference.starts_with("workspace:") {
+            return None;
+        }
+        // Aliases reference another package's full key (`/other@1.0.0`, or
+        // `other@1.0.0` in v9) instead of a bare version
+        let is_key = reference.starts_with('/')
+            || (!legacy && !reference.starts_with(|c: char| c.is_ascii_digit()));
+        if is_key {
+            if let Some((alias, version)) = normalize_pnpm_key(reference, legacy) {
+                if let Some(&index) = ids.get(&format!("{}@{}", alias, version)) {
+                    return Some(index);
+                }
+            }
+        }
+        ids.get(&format!("{}@{}", name, reference)).copied()
+    };
+
+    let mut edges = Vec::new();
+    for (key, entry) in &entries {
+        let Some((name, version)) = yaml_str(key).and_then(|k| normalize_pnpm_key(&k, legacy))
+        else {
+            continue;
+        };
+        let Some(&index) = ids.get(&format!("{}@{}", name, version)) else {
+            continue;
+        };
+        for section in ["dependencies", "optionalDependencies"] {
+            for (dependency, reference) in pnpm_dependencies(entry, section) {
+                if let Some(target) = resolve(&dependency, &reference) {
+                    edges.push((index, target));
+                }
+            }
+        }
+    }
+    for (from, to) in edges {
+        graph.add_edge(from, to, false);
+    }
+
+    // Single-project lockfiles before v6 workspaces keep the importer at the top level
+    let importers: Vec<(String, serde_yaml::Value)> =
+        match doc.get("importers").and_then(|v| v.as_mapping()) {
+            Some(importers) => importers
+                .iter()
+                .filter_map(|(path, importer)| Some((yaml_str(path)?, importer.clone())))
+                .collect(),
+            None => vec![(".".to_string(), doc.clone())],
+        };
+
+    for (path, importer) in importers {
+        let root = graph.add_root(&path);
+        for (section, dev) in [
+            ("dependencies", false),
+            ("optionalDependencies", false),
+            ("devDependencies", true),
+        ] {
+            for (dependency, reference) in pnpm_dependencies(&importer, section) {
+                if let Some(target) = resolve(&dependency, &reference) {
+                    graph.add_edge(root, target, dev);
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+// ============================================================================
+// yarn.lock
+// ============================================================================
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Split a classic yarn.lock line into key and value (`"@scope/a" "^1.0.0"`)
+fn split_yarn_pair(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    let split = if let Some(rest) = line.strip_prefix('"') {
+        rest.find('"').map(|end| end + 2)
+    } else {
+        line.find(char::is_whitespace)
+    };
+    match split {
+        Some(at) if at <= line.len() => (unquote(&line[..at]), unquote(&line[at..])),
+        _ => (unquote(line), ""),
+    }
+}
+
+/// One resolved entry of a yarn.lock: the descriptors it satisfies, its
+/// version, and its dependency descriptors
+#[derive(Debug, Default)]
+struct YarnEntry {
+    descriptors: Vec<String>,
+    version: String,
+    dependencies: Vec<(String, String)>,
+    /// Berry workspace path (`app@workspace:packages/app`)
+    workspace: Option<String>,
+}
+
+fn parse_yarn_classic_entries(content: &str) -> Vec<YarnEntry> {
+    let mut entries: Vec<YarnEntry> = Vec::new();
+    let mut in_dependencies = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+
+        if indent == 0 {
+            let descriptors = trimmed
+                .trim_end_matches(':')
+                .split(", ")
+                .map(|d| unquote(d).to_string())
+                .collect();
+            entries.push(YarnEntry {
+                descriptors,
+                ..Default::default()
+            });
+            in_dependencies = false;
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+
+        if indent <= 2 {
+            in_dependencies = trimmed == "dependencies:" || trimmed == "optionalDependencies:";
+            let (key, value) = split_yarn_pair(trimmed);
+            if key == "version" {
+                entry.version = value.to_string();
+            }
+        } else if in_dependencies {
+            let (name, range) = split_yarn_pair(trimmed);
+            entry
+                .dependencies
+                .push((name.to_string(), range.to_string()));
+        }
+    }
+
+    entries
+}
+
+fn parse_yarn_berry_entries(doc: &serde_yaml::Value) -> Vec<YarnEntry> {
+    let Some(map) = doc.as_mapping() else {
+        return Vec::new();
+    };
+    map.iter()
+        .filter_map(|(key, entry)| {
+            let key = yaml_str(key)?;
+            if key == "__metadata" {
+                return None;
+            }
+            let descriptors = key.split(", ").map(|d| d.trim().to_string()).collect();
+            let resolution = entry
+                .get("resolution")
+                .and_then(yaml_str)
+                .unwrap_or_default();
+            let workspace = resolution
+                .split_once("@workspace:")
+                .map(|(_, path)| path.to_string());
+            let mut dependencies = pnpm_dependencies(entry, "dependencies");
+            dependencies.extend(pnpm_dependencies(entry, "optionalDependencies"));
+            Some(YarnEntry {
+                descriptors,
+                version: entry.get("version").and_then(yaml_str).unwrap_or_default(),
+                dependencies,
+                workspace,
+            })
+        })
+        .collect()
+}
+
+/// Directories matched by package.json `workspaces` patterns (`packages/*` or literal paths)
+fn expand_workspace_patterns(root: &Path, manifest: &serde_json::Value) -> Vec<PathBuf> {
+    let patterns = manifest
+        .get("workspaces")
+        .and_then(|w| w.as_array().or_else(|| w.get("packages")?.as_array()))
+        .cloned()
+        .unwrap_or_default();
+
+    let mut dirs = Vec::new();
+    for pattern in patterns.iter().filter_map(|p| p.as_str()) {
+        let pattern = pattern.trim_start_matches("./");
+        if let Some(parent) = pattern
+            .strip_suffix("/**")
+            .or_else(|| pattern.strip_suffix("/*"))
+        {
+            if let Ok(entries) = std::fs::read_dir(root.join(parent)) {
+                dirs.extend(
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.path())
+                        .filter(|p| p.join("package.json").is_file()),
+                );
+            }
+        } else if root.join(pattern).join("package.json").is_file() {
+            dirs.push(root.join(pattern));
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+fn parse_yarn_lock(content: &str, project_root: &Path) -> Result<LockGraph, String> {
+    // Berry (v2+) lockfiles are YAML with a __metadata header
+    let berry = content.contains("\n__metadata:") || content.starts_with("__metadata:");
+    let entries = if berry {
+        let doc: serde_yaml::Value =
+            serde_yaml::from_str(content).map_err(|e| format!("Invalid yarn.lock: {}", e))?;
+        parse_yarn_berry_entries(&doc)
+    } else {
+        parse_yarn_classic_entries(content)
+    };
+
+    let mut graph = LockGraph::default();
+    let mut descriptors: HashMap<String, usize> = HashMap::new();
+    let mut indices = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let Some((name, _)) = entry
+            .descriptors
+            .first()
+            .and_then(|d| split_name_version(d))
+        else {
+            indices.push(None);
+            continue;
+        };
+        let index = match &entry.workspace {
+            Some(_) => graph.add_root(name),
+            None => graph.add_node(name, &entry.version, false),
+        };
+        for descriptor in &entry.descriptors {
+            descriptors.insert(descriptor.clone(), index);
+        }
+        indices.push(Some(index));
+    }
+
+    let resolve = |name: &str, range: &str| -> Option<usize> {
+        descriptors
+            .get(&format!("{}@{}", name, range))
+            // Berry dependency ranges omit the default `npm:` protocol
+            .or_else(|| descriptors.get(&format!("{}@npm:{}", name, range)))
+            .copied()
+    };
+
+    for (entry, index) in entries.iter().zip(indices) {
+        let Some(index) = index else {
+            continue;
+        };
+        // Berry merges a workspace's dev dependencies into `dependencies`
+        let dev_names: HashSet<String> = entry
+            .workspace
+            .as_ref()
+            .and_then(|path| read_json(&project_root.join(path).join("package.json")))
+            .map(|manifest| {
+                dependency_names(&manifest, &["devDependencies"])
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (name, range) in &entry.dependencies {
+            if let Some(target) = resolve(name, range) {
+                graph.add_edge(index, target, dev_names.contains(name));
+            }
+        }
+    }
+
+    // Classic lockfiles don't record the project; read it (and its workspaces) from package.json
+    if !berry {
+        if let Some(manifest) = read_json(&project_root.join("package.json")) {
+            let mut manifests = vec![manifest.clone()];
+            manifests.extend(
+                expand_workspace_patterns(project_root, &manifest)
+                    .iter()
+                    .filter_map(|dir| read_json(&dir.join("package.json"))),
+            );
+            for manifest in manifests {
+                let name = manifest.get("name").and_then(|v| v.as_str()).unwrap_or(".");
+                let root = graph.add_root(name);
+                for section in NODE_DEPENDENCY_SECTIONS {
+                    let Some(deps) = manifest.get(*section).and_then(|v| v.as_object()) else {
+                        continue;
+                    };
+                    for (dependency, range) in deps {
+                        let range = range.as_str().unwrap_or("");
+                        if let Some(target) = resolve(dependency, range) {
+                            graph.add_edge(root, target, *section == "devDependencies");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+// ============================================================================
+// bun.lock
+// ============================================================================
+
+/// Package names along a bun.lock install path (`a/@scope/b` → `["a", "@scope/b"]`)
+fn bun_path_segments(key: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut parts = key.split('/');
+    while let Some(part) = parts.next() {
+        if part.starts_with('@') {
+            segments.push(format!("{}/{}", part, parts.next().unwrap_or("")));
+        } else if !part.is_empty() {
+            segments.push(part.to_string());
+        }
+    }
+    segments
+}
+
+/// Resolve a dependency from an install path, nearest nested copy first
+fn resolve_bun_key(keys: &HashMap<String, usize>, base: &[String], name: &str) -> Option<usize> {
+    (0..=base.len()).rev().find_map(|depth| {
+        let mut candidate = base[..depth].join("/");
+        if !candidate.is_empty() {
+            candidate.push('/');
+        }
+        candidate.push_str(name);
+        keys.get(&candidate).copied()
+    })
+}
+
+fn parse_bun_lock(content: &str) -> Result<LockGraph, String> {
+    // bun.lock is JSONC (trailing commas)
+    let doc: serde_json::Value =
+        json5::from_str(content).map_err(|e| format!("Invalid bun.lock: {}", e))?;
+
+    let packages = doc
+        .get("packages")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut graph = LockGraph::default();
+    let mut keys: HashMap<String, usize> = HashMap::new();
+    for (key, entry) in &packages {
+        let ident = entry
+            .get(0)
+            .and_then(|v| v.as_str())
+            .unwrap_or(key.as_str());
+        let (name, version) = split_name_version(ident).unwrap_or((ident, ""));
+        let local = version.starts_with("workspace:");
+        let index = graph.add_node(name, version, local);
+        keys.insert(key.clone(), index);
+    }
+
+    for (key, entry) in &packages {
+        let index = keys[key];
+        // Entry layout varies by source; the metadata is the first object
+        let Some(metadata) = entry
+            .as_array()
+            .and_then(|items| items.iter().find(|item| item.is_object()))
+        else {
+            continue;
+        };
+        let base = bun_path_segments(key);
+        for (name, _) in dependency_names(metadata, NODE_PACKAGE_SECTIONS) {
+            if let Some(target) = resolve_bun_key(&keys, &base, &name) {
+                graph.add_edge(index, target, false);
+            }
+        }
+    }
+
+    if let Some(workspaces) = doc.get("workspaces").and_then(|v| v.as_object()) {
+        for (path, workspace) in workspaces {
+            let name = workspace
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or(path.as_str());
+            let root = graph.add_root(name);
+            // Packages specific to a member are nested under its name
+            let base = if path.is_empty() {
+                Vec::new()
+            } else {
+                vec![name.to_string()]
+            };
+            for (dependency, dev) in dependency_names(workspace, NODE_DEPENDENCY_SECTIONS) {
+                if let Some(target) = resolve_bun_key(&keys, &base, &dependency) {
+                    graph.add_edge(root, target, dev);
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+// ============================================================================
+// Cargo.lock
+// ============================================================================
+
+fn parse_cargo_lock(content: &str) -> Result<LockGraph, String> {
+    let doc: toml::Table =
+        toml::from_str(content).map_err(|e| format!("Invalid Cargo.lock: {}", e))?;
+    let packages = doc
+        .get("package")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut graph = LockGraph::default();
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for package in &packages {
+        let name = package.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let version = package
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        // Packages without a source are workspace members (or path dependencies)
+        let local = package.get("source").is_none();
+        let index = graph.add_node(name, version, local);
+        if local {
+            graph.roots.push(index);
+        }
+        by_name.entry(name.to_string()).or_default().push(index);
+    }
+
+    for (index, package) in packages.iter().enumerate() {
+        let dependencies = package
+            .get("dependencies")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        for dependency in dependencies.iter().filter_map(|d| d.as_str()) {
+            // "name", "name version", or "name version (source)"
+            let mut parts = dependency.split_whitespace();
+            let name = parts.next().unwrap_or("");
+            let version = parts.next();
+            let target = by_name.get(name).and_then(|candidates| match version {
+                Some(version) => candidates
+                    .iter()
+                    .copied()
+                    .find(|&c| graph.nodes[c].version == version),
+                None => candidates.first().copied(),
+            });
+            if let Some(target) = target {
+                graph.add_edge(index, target, false);
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Lockfiles present in the project root, in preference order
+pub fn find_lockfiles(project_root: &Path) -> Vec<(LockfileKind, PathBuf)> {
+    [
+        LockfileKind::Bun,
+        LockfileKind::Pnpm,
+        LockfileKind::Yarn,
+        LockfileKind::Npm,
+        LockfileKind::Cargo,
+    ]
+    .into_iter()
+    .map(|kind| (kind, project_root.join(kind.file_name())))
+    .filter(|(_, path)| path.is_file())
+    .collect()
+}
+
+/// Parse a single lockfile of the given kind
+pub fn parse_lockfile_at(kind: LockfileKind, path: &Path) -> Result<ParsedLockfile, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let project_root = path.parent().unwrap_or(Path::new("."));
+
+    let graph = match kind {
+        LockfileKind::Bun => parse_bun_lock(&content)?,
+        LockfileKind::Pnpm => parse_pnpm_lock(&content)?,
+        LockfileKind::Yarn => parse_yarn_lock(&content, project_root)?,
+        LockfileKind::Npm => parse_package_lock(&content, project_root)?,
+        LockfileKind::Cargo => parse_cargo_lock(&content)?,
+    };
+
+    Ok(ParsedLockfile {
+        kind,
+        path: path.to_string_lossy().to_string(),
+        ecosystem: kind.ecosystem(),
+        packages: graph.into_packages(),
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Parse every lockfile in the project root into resolved versions and
+/// dependency paths.
+///
+/// # Arguments
+/// * `project_root` - Directory containing the lockfile(s)
+#[fluxel_command]
+pub async fn parse_lockfile(project_root: String) -> Result<Vec<ParsedLockfile>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&project_root);
+        let lockfiles = find_lockfiles(&root);
+        if lockfiles.is_empty() {
+            if root.join("bun.lockb").is_file() {
+                return Err(
+                    "bun.lockb is a binary lockfile; run `bun install --save-text-lockfile` to create bun.lock"
+                        .to_string(),
+                );
+            }
+            return Err(format!("No lockfile found in {}", project_root));
+        }
+
+        let mut parsed = Vec::new();
+        let mut first_error = None;
+        for (kind, path) in lockfiles {
+            match parse_lockfile_at(kind, &path) {
+                Ok(lockfile) => parsed.push(lockfile),
+                Err(e) => {
+                    println!("[Lockfile] {}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if parsed.is_empty() => Err(e),
+            _ => Ok(parsed),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_package_lock_with_nested_versions_and_paths() {
+        let lock = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "a": "^1.0.0" }, "devDependencies": { "b": "^1.0.0" } },
+    "node_modules/a": { "version": "1.0.0", "dependencies": { "c": "^1.0.0" } },
+    "node_modules/b": { "version": "1.0.0", "dev": true, "dependencies": { "c": "^2.0.0" } },
+    "node_modules/b/node_modules/c": { "version": "2.0.0", "dev": true },
+    "node_modules/c": { "version": "1.0.0" }
+  }
+}"#;
+        let packages = parse_package_lock(lock, Path::new("."))
+            .unwrap()
+            .into_packages();
+
+        let c = &packages["c"];
+        assert_eq!(c.len(), 2);
+        assert_eq!(c["1.0.0"].paths, vec![path(&["a", "c"])]);
+        assert!(!c["1.0.0"].dev);
+        assert_eq!(c["2.0.0"].paths, vec![path(&["b", "c"])]);
+        assert!(c["2.0.0"].dev);
+        assert!(packages["b"]["1.0.0"].dev);
+    }
+
+    #[test]
+    fn parses_cargo_lock_from_workspace_members() {
+        let lock = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["serde", "rand 0.8.5"]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["rand_core"]
+
+[[package]]
+name = "rand_core"
+version = "0.6.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let packages = parse_cargo_lock(lock).unwrap().into_packages();
+
+        assert!(!packages.contains_key("app"));
+        assert_eq!(
+            packages["rand_core"]["0.6.4"].paths,
+            vec![path(&["rand", "rand_core"])]
+        );
+    }
+
+    #[test]
+    fn parses_pnpm_v9_lock_with_peer_suffixes() {
+        let lock = r#"
+lockfileVersion: '9.0'
+
+importers:
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+    devDependencies:
+      '@types/react':
+        specifier: ^18.0.0
+        version: 18.2.0
+
+packages:
+  react-dom@18.2.0:
+    resolution: {integrity: sha512-x}
+  react@18.2.0:
+    resolution: {integrity: sha512-y}
+  scheduler@0.23.0:
+    resolution: {integrity: sha512-z}
+  '@types/react@18.2.0':
+    resolution: {integrity: sha512-w}
+
+snapshots:
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      react: 18.2.0
+      scheduler: 0.23.0
+  react@18.2.0: {}
+  scheduler@0.23.0: {}
+  '@types/react@18.2.0': {}
+"#;
+        let packages = parse_pnpm_lock(lock).unwrap().into_packages();
+
+        assert!(packages["react-dom"].contains_key("18.2.0"));
+        assert_eq!(
+            packages["scheduler"]["0.23.0"].paths,
+            vec![path(&["react-dom", "scheduler"])]
+        );
+        assert!(packages["@types/react"]["18.2.0"].dev);
+        assert!(!packages["react"]["18.2.0"].dev);
+    }
+
+    #[test]
+    fn parses_classic_and_berry_yarn_locks() {
+        let root = std::env::temp_dir().join(format!(
+            "fluxel-lockfile-yarn-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{ "name": "app", "dependencies": { "@scope/a": "^1.0.0" }, "devDependencies": { "b": "~2.0.0" } }"#,
+        )
+        .unwrap();
+
+        let classic = r#"# yarn lockfile v1
+
+"@scope/a@^1.0.0", "@scope/a@^1.1.0":
+  version "1.1.0"
+  resolved "https://registry.yarnpkg.com/@scope/a/-/a-1.1.0.tgz"
+  dependencies:
+    c "^3.0.0"
+
+b@~2.0.0:
+  version "2.0.1"
+
+c@^3.0.0:
+  version "3.2.0"
+"#;
+        let packages = parse_yarn_lock(classic, &root).unwrap().into_packages();
+        assert_eq!(packages["c"]["3.2.0"].paths, vec![path(&["@scope/a", "c"])]);
+        assert!(packages["b"]["2.0.1"].dev);
+
+        let berry = r#"__metadata:
+  version: 8
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  dependencies:
+    "@scope/a": "npm:^1.0.0"
+    b: "npm:~2.0.0"
+  languageName: unknown
+  linkType: soft
+
+"@scope/a@npm:^1.0.0":
+  version: 1.1.0
+  resolution: "@scope/a@npm:1.1.0"
+  dependencies:
+    c: "npm:^3.0.0"
+
+"b@npm:~2.0.0":
+  version: 2.0.1
+  resolution: "b@npm:2.0.1"
+
+"c@npm:^3.0.0":
+  version: 3.2.0
+  resolution: "c@npm:3.2.0"
+"#;
+        let packages = parse_yarn_lock(berry, &root).unwrap().into_packages();
+        assert!(!packages.contains_key("app"));
+        assert_eq!(packages["c"]["3.2.0"].paths, vec![path(&["@scope/a", "c"])]);
+        assert!(packages["b"]["2.0.1"].dev);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parses_bun_lock_with_nested_packages() {
+        let lock = r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {
+      "name": "app",
+      "dependencies": { "a": "^1.0.0", "b": "^1.0.0" },
+      "devDependencies": { "@types/node": "^20.0.0" },
+    },
+  },
+  "packages": {
+    "a": ["a@1.0.0", "", { "dependencies": { "c": "^1.0.0" } }, "sha512-a"],
+    "b": ["b@1.0.0", "", { "dependencies": { "c": "^2.0.0" } }, "sha512-b"],
+    "c": ["c@1.0.0", "", {}, "sha512-c1"],
+    "b/c": ["c@2.0.0", "", {}, "sha512-c2"],
+    "@types/node": ["@types/node@20.1.0", "", {}, "sha512-n"],
+  }
+}"#;
+        let packages = parse_bun_lock(lock).unwrap().into_packages();
+
+        assert_eq!(packages["c"]["1.0.0"].paths, vec![path(&["a", "c"])]);
+        assert_eq!(packages["c"]["2.0.0"].paths, vec![path(&["b", "c"])]);
+        assert!(packages["@types/node"]["20.1.0"].dev);
+    }
+}
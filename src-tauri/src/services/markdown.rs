@@ -0,0 +1,450 @@
+//! Markdown Rendering Service
+//!
+//! Renders GitHub-flavored markdown to sanitized HTML for the preview pane using
+//! comrak, with fenced code blocks highlighted by syntect. Relative image paths are
+//! resolved against a base directory and inlined as data URLs so the webview never
+//! needs filesystem access, and headings get stable anchor ids for the outline.
+
+use base64::Engine;
+use comrak::adapters::SyntaxHighlighterAdapter;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{format_html_with_plugins, parse_document, Anchorizer, Arena, Options, Plugins};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::services::highlight::{HighlightState, DEFAULT_THEME};
+use crate::services::preview_server::mime_type_for;
+
+/// Local images above this size are linked by absolute path instead of inlined
+const MAX_INLINE_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderMarkdownOptions {
+    /// Directory relative image paths are resolved against
+    /// (defaults to the markdown file's directory)
+    pub base_dir: Option<String>,
+    /// Highlighting theme for code blocks (defaults to base16-ocean.dark)
+    pub theme: Option<String>,
+}
+
+/// A heading in the rendered document, for outlines and anchor navigation
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkdownHeading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub headings: Vec<MarkdownHeading>,
+}
+
+/// Highlights fenced code blocks through the shared highlighting service
+struct CodeFenceHighlighter<'a> {
+    highlight: &'a HighlightState,
+    theme: &'a str,
+}
+
+impl SyntaxHighlighterAdapter for CodeFenceHighlighter<'_> {
+    fn write_highlighted(
+        &self,
+        output: &mut dyn Write,
+        lang: Option<&str>,
+        code: &str,
+    ) -> io::Result<()> {
+        let lang = lang.filter(|l| !l.is_empty()).unwrap_or("plaintext");
+        match self.highlight.highlight_inline_html(code, lang, self.theme) {
+            Ok(html) => output.write_all(html.as_bytes()),
+            Err(_) => output.write_all(html_escape::encode_text(code).as_bytes()),
+        }
+    }
+
+    fn write_pre_tag(
+        &self,
+        output: &mut dyn Write,
+        mut attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        if let Some(background) = self.highlight.background_color(self.theme) {
+            let style = attributes.entry("style".to_string()).or_default();
+            style.insert_str(0, &format!("background-color:{};", background));
+        }
+        comrak::html::write_opening_tag(output, "pre", attributes)
+    }
+
+    fn write_code_tag(
+        &self,
+        output: &mut dyn Write,
+        attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        comrak::html::write_opening_tag(output, "code", attributes)
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+fn markdown_options() -> Options<'static> {
+    let mut options = Options::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tasklist = true;
+    options.extension.footnotes = true;
+    options.extension.header_ids = Some(String::new());
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    // Raw HTML is allowed through comrak and cleaned by ammonia afterwards
+    options.render.unsafe_ = true;
+    options
+}
+
+/// Collect the plain text of a node's descendants
+fn collect_text<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    for child in node.children() {
+        match &child.data.borrow().value {
+            NodeValue::Text(text) => out.push_str(text),
+            NodeValue::Code(code) => out.push_str(&code.literal),
+            NodeValue::SoftBreak | NodeValue::LineBreak => out.push(' '),
+            _ => {}
+        }
+        collect_text(child, out);
+    }
+}
+
+fn is_external_url(url: &str) -> bool {
+    url.starts_with('#')
+        || url.starts_with("//")
+        || url
+            .split_once(':')
+            .map(|(scheme, _)| {
+                !scheme.is_empty()
+                    && scheme.len() > 1 // a single letter is a Windows drive
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+            })
+            .unwrap_or(false)
+}
+
+/// Resolve a relative image URL against `base_dir`, inlining it when small enough
+fn resolve_image_url(url: &str, base_dir: &Path) -> Option<String> {
+    if url.is_empty() || is_external_url(url) {
+        return None;
+    }
+
+    // Strip query/fragment and percent-encoded spaces, which are common in READMEs
+    let clean = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .replace("%20", " ");
+    let path = if Path::new(&clean).is_absolute() {
+        PathBuf::from(&clean)
+    } else {
+        base_dir.join(&clean)
+    };
+
+    let metadata = std::fs::metadata(&path).ok()?;
+    if metadata.len() > MAX_INLINE_IMAGE_BYTES {
+        return Some(path.to_string_lossy().replace('\\', "/"));
+    }
+
+    let bytes = std::fs::read(&path).ok()?;
+    let mime = mime_type_for(&path).split(';').next().unwrap_or_default();
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// Remove scripts, event handlers, and unsafe URLs while keeping highlighting and anchors
+fn sanitize_html(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("a", ["id", "class", "aria-hidden"])
+        .add_tag_attributes("span", ["style", "class"])
+        .add_tag_attributes("pre", ["style", "class", "lang"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("li", ["class", "id"])
+        .add_tag_attributes("sup", ["class", "id"])
+        .add_tag_attributes("section", ["class"])
+        .add_generic_attributes(["align"])
+        .add_url_schemes(["data"])
+        .attribute_filter(|element, attribute, value| {
+            // data: URLs are only acceptable as inlined images
+            if value.trim_start().to_ascii_lowercase().starts_with("data:") {
+                let is_image = element == "img"
+                    && attribute == "src"
+                    && value.trim_start().starts_with("data:image/");
+                return is_image.then_some(Cow::Borrowed(value));
+            }
+            Some(Cow::Borrowed(value))
+        })
+        .clean(html)
+        .to_string()
+}
+
+/// Whether a link in untrusted markdown may be kept: relative, or http(s)/mailto
+fn is_safe_link(url: &str) -> bool {
+    match url.trim().split_once(':') {
+        // A single letter is a Windows drive
+        Some((scheme, _)) if scheme.len() > 1 && !scheme.contains(['/', '?', '#']) => {
+            matches!(
+                scheme.to_ascii_lowercase().as_str(),
+                "http" | "https" | "mailto"
+            )
+        }
+        _ => true,
+    }
+}
+
+/// Clean untrusted markdown (e.g. language server hovers) for a webview that
+/// renders it: raw HTML blocks are dropped, inline HTML is kept as literal text,
+/// and links with scripting or local schemes are emptied. The result is markdown.
+pub fn sanitize_markdown(content: &str) -> Result<String, String> {
+    let mut options = Options::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &options);
+
+    let mut html_blocks = Vec::new();
+    for node in root.descendants() {
+        let mut data = node.data.borrow_mut();
+        match &mut data.value {
+            NodeValue::HtmlBlock(_) => html_blocks.push(node),
+            NodeValue::HtmlInline(html) => {
+                let text = std::mem::take(html);
+                data.value = NodeValue::Text(text);
+            }
+            NodeValue::Link(link) | NodeValue::Image(link) if !is_safe_link(&link.url) => {
+                link.url.clear();
+            }
+            _ => {}
+        }
+    }
+    for node in html_blocks {
+        node.detach();
+    }
+
+    let mut output = Vec::new();
+    comrak::format_commonmark(root, &options, &mut output)
+        .map_err(|e| format!("Failed to format markdown: {}", e))?;
+    String::from_utf8(output).map_err(|e| e.to_string())
+}
+
+fn render(
+    content: &str,
+    base_dir: &Path,
+    highlighter: &CodeFenceHighlighter,
+) -> Result<RenderedMarkdown, String> {
+    let options = markdown_options();
+    let arena = Arena::new();
+    let root = parse_document(&arena, content, &options);
+
+    let mut headings = Vec::new();
+    let mut anchorizer = Anchorizer::new();
+    for node in root.descendants() {
+        let level = match &mut node.data.borrow_mut().value {
+            NodeValue::Image(link) => {
+                if let Some(resolved) = resolve_image_url(&link.url, base_dir) {
+                    link.url = resolved;
+                }
+                continue;
+            }
+            NodeValue::Heading(heading) => heading.level,
+            _ => continue,
+        };
+
+        let mut text = String::new();
+        collect_text(node, &mut text);
+        // Same algorithm comrak uses for header_ids, so anchors match the rendered ids
+        let anchor = anchorizer.anchorize(text.clone());
+        headings.push(MarkdownHeading {
+            level,
+            text,
+            anchor,
+        });
+    }
+
+    let mut plugins = Plugins::default();
+    plugins.render.codefence_syntax_highlighter = Some(highlighter);
+
+    let mut html = Vec::new();
+    format_html_with_plugins(root, &options, &mut html, &plugins)
+        .map_err(|e| format!("Failed to render markdown: {}", e))?;
+    let html = String::from_utf8(html).map_err(|e| e.to_string())?;
+
+    Ok(RenderedMarkdown {
+        html: sanitize_html(&html),
+        headings,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Render markdown to sanitized HTML.
+///
+/// # Arguments
+/// * `path` - Markdown file to render (used when `content` is not given)
+/// * `content` - Markdown source, e.g. unsaved editor contents
+/// * `options` - Base directory for relative images and code block theme
+#[fluxel_command]
+#[cfg_attr(
+    feature = "profiling",
+    tracing::instrument(
+        skip(path, content, highlight),
+        fields(category = "workspace", file = path.as_deref().unwrap_or_default())
+    )
+)]
+pub async fn render_markdown(
+    path: Option<String>,
+    content: Option<String>,
+    options: Option<RenderMarkdownOptions>,
+    highlight: State<'_, HighlightState>,
+) -> Result<RenderedMarkdown, String> {
+    let options = options.unwrap_or_default();
+    let theme = options.theme.unwrap_or_else(|| DEFAULT_THEME.to_string());
+    let highlight = highlight.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let content = match (content, path.as_ref()) {
+            (Some(content), _) => content,
+            (None, Some(path)) => std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?,
+            (None, None) => return Err("Either path or content is required".to_string()),
+        };
+
+        let base_dir = options
+            .base_dir
+            .map(PathBuf::from)
+            .or_else(|| {
+                path.as_ref()
+                    .and_then(|p| Path::new(p).parent().map(Path::to_path_buf))
+            })
+            .unwrap_or_default();
+
+        if !highlight.has_theme(&theme) {
+            return Err(format!("Unknown code theme '{}'", theme));
+        }
+        let highlighter = CodeFenceHighlighter {
+            highlight: &highlight,
+            theme: &theme,
+        };
+        render(&content, &base_dir, &highlighter)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn create_temp_dir(test_name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("fluxel-markdown-{test_name}-{unique}"));
+        fs::create_dir_all(&path).expect("temporary directory should be created");
+        path
+    }
+
+    #[test]
+    fn renders_headings_with_anchors() {
+        let highlight = HighlightState::new();
+        let adapter = CodeFenceHighlighter {
+            highlight: &highlight,
+            theme: DEFAULT_THEME,
+        };
+        let result = render(
+            "# Getting Started\n\n## Install `fluxel`\n",
+            Path::new("."),
+            &adapter,
+        )
+        .unwrap();
+
+        assert_eq!(result.headings.len(), 2);
+        assert_eq!(result.headings[0].anchor, "getting-started");
+        assert_eq!(result.headings[1].text, "Install fluxel");
+        assert!(result.html.contains("id=\"getting-started\""));
+    }
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let highlight = HighlightState::new();
+        let adapter = CodeFenceHighlighter {
+            highlight: &highlight,
+            theme: DEFAULT_THEME,
+        };
+        let result = render(
+            "<script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(1)\">\n\n[x](javascript:alert(1))",
+            Path::new("."),
+            &adapter,
+        )
+        .unwrap();
+
+        assert!(!result.html.contains("<script"));
+        assert!(!result.html.contains("onerror"));
+        assert!(!result.html.contains("javascript:"));
+    }
+
+    #[test]
+    fn inlines_relative_images() {
+        let dir = create_temp_dir("images");
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs").join("logo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let highlight = HighlightState::new();
+        let adapter = CodeFenceHighlighter {
+            highlight: &highlight,
+            theme: DEFAULT_THEME,
+        };
+        let result = render("![logo](docs/logo.png)", &dir, &adapter).unwrap();
+        assert!(result.html.contains("src=\"data:image/png;base64,"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn highlights_fenced_code() {
+        let highlight = HighlightState::new();
+        let adapter = CodeFenceHighlighter {
+            highlight: &highlight,
+            theme: DEFAULT_THEME,
+        };
+        let result = render("```rust\nfn main() {}\n```", Path::new("."), &adapter).unwrap();
+        assert!(result.html.contains("<span style="));
+    }
+
+    #[test]
+    fn sanitizes_untrusted_markdown() {
+        let cleaned = sanitize_markdown(
+            "<div onclick=\"x()\">block</div>\n\nUse <b>bold</b> [docs](https://example.com) [run](javascript:alert(1))\n\n```csharp\nvoid Main();\n```\n",
+        )
+        .unwrap();
+
+        assert!(!cleaned.contains("<div"));
+        assert!(!cleaned.contains("<b>"));
+        assert!(cleaned.contains("bold"));
+        assert!(cleaned.contains("(https://example.com)"));
+        assert!(!cleaned.contains("javascript:"));
+        assert!(cleaned.contains("csharp\nvoid Main();\n```"));
+    }
+}
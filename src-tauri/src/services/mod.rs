@@ -4,19 +4,161 @@
 //!
 //! ## Structure
 //!
+//! - `ai_audit` - Opt-in log of what was sent to AI providers, with secrets redacted
+//! - `ai_context` - Relevant code gathered around a cursor for AI prompts
+//! - `ai_edits` - Validated, all-or-nothing application of AI-proposed file edits
+//! - `ai_images` - Workspace images fitted to vision providers' input limits
+//! - `ai_metrics` - Time to first token and throughput of AI requests per provider
+//! - `author_identity` - Commit author names and avatars with a disk cache
 //! - `batch_file_reader` - Batch file reading for efficient type loading
+//! - `benchmarks` - Benchmark discovery, result parsing, and run-to-run deltas
+//! - `code_host` - GitHub/GitLab pull requests, checks, review comments, and cached issues
+//! - `commit_message` - Conventional commit validation and drafted messages
+//! - `dependency_audit` - Dependency license audit across package manifests
+//! - `editorconfig` - `.editorconfig` resolution for per-file settings
+//! - `file_guard` - Unsaved-changes guard for destructive file and git operations
+//! - `file_templates` - Per-extension and per-glob boilerplate for new files
+//! - `folding` - Folding range computation without a language server
+//! - `fs_watcher` - Shared recursive file system watchers
 //! - `git` - Git operations (status, commit, push, pull)
+//! - `git_bisect` - Bisect driven from the UI, optionally with a test command
+//! - `git_cli` - Runner for git CLI features libgit2 lacks
+//! - `git_diff` - Structured diffs between refs for branch comparison
+//! - `git_hooks` - Client-side commit hook execution for libgit2 commits
+//! - `git_patch` - Unified diff creation and patch application
+//! - `git_rebase` - Interactive rebase planning and execution
+//! - `git_sparse` - Sparse checkout of selected directories
+//! - `grammar_check` - Optional AI spelling and grammar pass with ranged suggestions
+//! - `health` - Health snapshot of processes, language servers, watchers, and caches
+//! - `highlight` - Syntax highlighting for read-only views
+//! - `import_cost` - Import size estimates for inline display
+//! - `inline_completion` - Debounced, cached fill-in-the-middle completions for ghost text
+//! - `import_rewrite` - Import specifier updates for moved files
+//! - `io_accounting` - Bytes and files read/written, recorded on profiler spans
+//! - `local_history` - Content-addressed local history snapshots of saved files
+//! - `lockfile` - Lockfile parsing into resolved versions and dependency paths
+//! - `markdown` - Markdown preview rendering with code highlighting
+//! - `module_graph` - Live module graph watching with incremental re-analysis
 //! - `node_resolver` - Node.js module resolution service
+//! - `open_with` - Reveal in the file manager and open with a chosen external application
+//! - `package_info` - Installed and locked metadata of npm packages for import hovers
+//! - `path_filter` - Shared workspace exclusions for search, indexing, watchers, and stats
 //! - `plugin_loader` - Community plugin discovery and loading
+//! - `preview_server` - Static file preview server with live-reload
 //! - `process_manager` - Child process lifecycle management
+//! - `project_clean` - Build output and cache cleanup per detected ecosystem
 //! - `project_detector` - Project type detection
+//! - `repo_stats` - Commit, churn, and language statistics for the insights dashboard
+//! - `run_configs` - Workspace run configurations with ordered pre-launch tasks
+//! - `runnables` - Catalog of scripts, tasks, and project targets for the Run palette
+//! - `scaffold` - New projects from built-in generators and user templates
+//! - `search_history` - Persisted per-workspace search history and saved searches
+//! - `search_index` - Background trigram index that prefilters text search
+//! - `secrets` - Encrypted per-workspace secret storage
+//! - `shutdown` - Time-bounded shutdown of every subsystem on app exit
+//! - `snippets` - User/workspace snippet loading and expansion
+//! - `spellcheck` - Hunspell dictionary spell checking
+//! - `ssh_hosts` - SSH host profiles and commands run on them for remote terminals
+//! - `structured_output` - AI replies validated against a JSON Schema, with repair retries
+//! - `terminal_history` - Per-workspace history of terminal commands and exit codes
+//! - `terminal_links` - File location and URL detection in terminal output
+//! - `terminal_output` - Carriage-return coalescing and ANSI handling for command output
+//! - `ts_projects` - tsconfig project-reference graph and source/output mapping
+//! - `tree_shaking` - Tree-shaking friendliness report for package imports
+//! - `unused_exports` - Exports no project file imports, found through the module graph
+//! - `vulnerability_audit` - OSV.dev vulnerability audit of resolved dependencies
+//! - `watch_build` - Debounced rebuilds on source changes, cancelling stale builds
+//! - `workspace_archive` - Zip or tar.gz export of a workspace folder, honoring its path filter
+//! - `workspace_store` - Per-workspace JSON files keyed by a hash of the workspace path
+//! - `wsl` - WSL distro listing, path translation, and commands run inside a distro
 
+pub mod ai_audit;
+pub mod ai_context;
+pub mod ai_edits;
+pub mod ai_images;
+pub mod ai_metrics;
+pub mod author_identity;
 pub mod batch_file_reader;
+pub mod benchmarks;
+pub mod code_host;
+pub mod commit_message;
+pub mod dependency_audit;
+pub mod editorconfig;
+pub mod file_guard;
+pub mod file_templates;
+pub mod folding;
+pub mod fs_watcher;
 pub mod git;
+pub mod git_bisect;
+pub mod git_cli;
+pub mod git_diff;
+pub mod git_hooks;
+pub mod git_patch;
+pub mod git_rebase;
+pub mod git_sparse;
+pub mod grammar_check;
+pub mod health;
+pub mod highlight;
+pub mod import_cost;
+pub mod import_rewrite;
+pub mod inline_completion;
+pub mod io_accounting;
+pub mod local_history;
+pub mod lockfile;
+pub mod markdown;
+pub mod module_graph;
 pub mod node_resolver;
+pub mod open_with;
+pub mod package_info;
+pub mod path_filter;
 pub mod plugin_loader;
+pub mod preview_server;
 pub mod process_manager;
+pub mod project_clean;
 pub mod project_detector;
+pub mod repo_stats;
+pub mod run_configs;
+pub mod runnables;
+pub mod scaffold;
+pub mod search_history;
+pub mod search_index;
+pub mod secrets;
+pub mod shutdown;
+pub mod snippets;
+pub mod spellcheck;
+pub mod ssh_hosts;
+pub mod structured_output;
+pub mod terminal_history;
+pub mod terminal_links;
+pub mod terminal_output;
+pub mod tree_shaking;
+pub mod ts_projects;
+pub mod unused_exports;
+pub mod vulnerability_audit;
+pub mod watch_build;
+pub mod workspace_archive;
+pub mod workspace_store;
+pub mod wsl;
 
 // Re-export commonly used types
+pub use ai_audit::AiAuditState;
+pub use ai_metrics::AiMetricsState;
+pub use author_identity::AuthorIdentityState;
+pub use code_host::CodeHostState;
+pub use file_guard::DirtyFilesState;
+pub use fs_watcher::FsWatcherService;
+pub use highlight::HighlightState;
+pub use inline_completion::InlineCompletionState;
+pub use local_history::LocalHistoryState;
+pub use module_graph::ModuleGraphWatchState;
+pub use path_filter::PathFilterState;
+pub use preview_server::PreviewServerState;
 pub use process_manager::ProcessManager;
+pub use search_history::SearchHistoryState;
+pub use search_index::SearchIndexState;
+pub use secrets::WorkspaceSecretsState;
+pub use shutdown::SessionState;
+pub use spellcheck::SpellCheckState;
+pub use terminal_history::TerminalHistoryState;
+pub use ts_projects::TsProjectState;
+pub use watch_build::WatchBuildState;
@@ -0,0 +1,845 @@
+//! Module Graph Watch Service
+//!
+//! Builds the transitive import graph of an entry module with `fluxel_node_resolver`
+//! and keeps it current. Changes from the shared file system watcher re-analyze only
+//! the modules that changed, re-resolve the edges a created or removed file can
+//! affect, and are emitted as `module-graph://updated` deltas for live dependency views.
+
+use crate::services::fs_watcher::{FsChangeKind, FsWatcherService};
+use crate::services::import_rewrite::{normalize, RESOLVE_EXTENSIONS, SOURCE_EXTENSIONS};
+use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
+use fluxel_node_resolver::{
+    collect_import_specifiers_native, detect_format, resolve_module_native, ImportKind,
+    ModuleFormat, ResolveOptions, ResolveRequest,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tokio::sync::Mutex;
+
+/// Stop expanding the graph past this many modules
+const MAX_MODULES: usize = 5000;
+
+/// Quiet period used to batch the burst of events a single save produces
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+const UPDATED_EVENT: &str = "module-graph://updated";
+
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleEdge {
+    pub specifier: String,
+    pub kind: ImportKind,
+    /// Resolved file, or `None` when the specifier doesn't resolve
+    pub resolved: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleNode {
+    pub path: String,
+    pub imports: Vec<ModuleEdge>,
+    /// Resolved into `node_modules`; listed as a leaf but not analyzed
+    pub external: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Full graph returned when a watch starts
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphSnapshot {
+    pub watch_id: String,
+    pub entry: String,
+    pub modules: Vec<ModuleNode>,
+    /// The graph hit `MAX_MODULES` and is incomplete
+    pub truncated: bool,
+}
+
+/// Payload of `module-graph://updated`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphDelta {
+    pub watch_id: String,
+    pub added: Vec<ModuleNode>,
+    pub updated: Vec<ModuleNode>,
+    pub removed: Vec<String>,
+}
+
+impl ModuleGraphDelta {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+// ============================================================================
+// Graph
+// ============================================================================
+
+pub struct ModuleGraph {
+    entry: String,
+    project_root: String,
+    nodes: BTreeMap<String, ModuleNode>,
+    truncated: bool,
+}
+
+impl ModuleGraph {
+    /// Analyze `entry` and everything it transitively imports
+    pub fn build(entry: &Path, project_root: &Path) -> Self {
+        let entry = module_key(&normalize(entry));
+        let mut graph = Self {
+            entry: entry.clone(),
+            project_root: module_key(&normalize(project_root)),
+            nodes: BTreeMap::new(),
+            truncated: false,
+        };
+        graph.expand(vec![entry]);
+        graph
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.nodes.contains_key(path)
+    }
+
+    pub fn snapshot(&self, watch_id: &str) -> ModuleGraphSnapshot {
+        ModuleGraphSnapshot {
+            watch_id: watch_id.to_string(),
+            entry: self.entry.clone(),
+            modules: self.nodes.values().cloned().collect(),
+            truncated: self.truncated,
+        }
+    }
+
+    /// Apply a batch of changed paths and report what changed in the graph.
+    ///
+    /// Modified modules are re-analyzed in place. When files were created or removed,
+    /// only edges that could now resolve differently are re-resolved: unresolved edges
+    /// and edges into a directory that gained or lost a file.
+    pub fn apply_changes(
+        &mut self,
+        modified: &[String],
+        structural: &[String],
+    ) -> ModuleGraphDelta {
+        let mut updated = BTreeSet::new();
+        let mut pending = Vec::new();
+
+        for path in modified.iter().chain(structural) {
+            if !self.nodes.contains_key(path) || !Path::new(path).is_file() {
+                continue;
+            }
+            let node = self.analyze(path);
+            if self.nodes.get(path) != Some(&node) {
+                pending.extend(node.imports.iter().filter_map(|e| e.resolved.clone()));
+                self.nodes.insert(path.clone(), node);
+                updated.insert(path.clone());
+            }
+        }
+
+        let mut removed = Vec::new();
+        if !structural.is_empty() {
+            // Modules under a removed path (file or whole directory) drop out
+            let gone: Vec<String> = self
+                .nodes
+                .iter()
+                .filter(|(path, node)| !node.external && !Path::new(path).is_file())
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in gone {
+                self.nodes.remove(&path);
+                removed.push(path);
+            }
+
+            let touched: HashSet<String> = structural
+                .iter()
+                .filter_map(|path| parent_key(path))
+                .collect();
+            for path in self.reresolve_edges(&touched, &removed) {
+                if let Some(node) = self.nodes.get(&path) {
+                    pending.extend(node.imports.iter().filter_map(|e| e.resolved.clone()));
+                }
+                updated.insert(path);
+            }
+        }
+
+        let added: BTreeSet<String> = self.expand(pending).into_iter().collect();
+        removed.extend(self.prune());
+        let removed_set: HashSet<&String> = removed.iter().collect();
+
+        ModuleGraphDelta {
+            watch_id: String::new(),
+            added: added
+                .iter()
+                .filter_map(|path| self.nodes.get(path).cloned())
+                .collect(),
+            updated: updated
+                .iter()
+                .filter(|path| !added.contains(*path) && !removed_set.contains(path))
+                .filter_map(|path| self.nodes.get(path).cloned())
+                .collect(),
+            removed,
+        }
+    }
+
+    /// Analyze queued modules that aren't in the graph yet and everything they reach.
+    /// Returns the newly added modules.
+    fn expand(&mut self, pending: Vec<String>) -> Vec<String> {
+        let mut queue: VecDeque<String> = pending.into();
+        let mut added = Vec::new();
+
+        while let Some(path) = queue.pop_front() {
+            if self.nodes.contains_key(&path) {
+                continue;
+            }
+            if self.nodes.len() >= MAX_MODULES {
+                self.truncated = true;
+                break;
+            }
+            let node = self.analyze(&path);
+            queue.extend(
+                node.imports
+                    .iter()
+                    .filter_map(|edge| edge.resolved.clone())
+                    .filter(|target| !self.nodes.contains_key(target)),
+            );
+            self.nodes.insert(path.clone(), node);
+            added.push(path);
+        }
+
+        added
+    }
+
+    fn analyze(&self, path: &str) -> ModuleNode {
+        let mut node = ModuleNode {
+            path: path.to_string(),
+            imports: Vec::new(),
+            external: path.contains("/node_modules/"),
+            error: None,
+        };
+        // Packages and non-script files (styles, JSON, assets) are leaves
+        let is_script = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+        if node.external || !is_script {
+            return node;
+        }
+
+        match collect_import_specifiers_native(&Utf8PathBuf::from(path)) {
+            Ok(specifiers) => {
+                let mut seen = HashSet::new();
+                for specifier in specifiers {
+                    if seen.insert(specifier.specifier.clone()) {
+                        let resolved = self.resolve(path, &specifier.specifier);
+                        node.imports.push(ModuleEdge {
+                            specifier: specifier.specifier,
+                            kind: specifier.kind,
+                            resolved,
+                        });
+                    }
+                }
+            }
+            Err(e) => node.error = Some(e.to_string()),
+        }
+        node
+    }
+
+    fn resolve(&self, importer: &str, specifier: &str) -> Option<String> {
+        let options = ResolveOptions {
+            extensions: RESOLVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            ..Default::default()
+        };
+        resolve_module_native(
+            ResolveRequest {
+                specifier: specifier.to_string(),
+                importer: importer.to_string(),
+                project_root: Some(self.project_root.clone()),
+            },
+            Some(options),
+        )
+        .ok()?
+        .resolved_path
+        .map(|resolved| module_key(&normalize(Path::new(&resolved))))
+    }
+
+    /// Re-resolve edges a created or removed file could affect. Returns the modules
+    /// whose edges changed.
+    fn reresolve_edges(
+        &mut self,
+        touched_dirs: &HashSet<String>,
+        removed: &[String],
+    ) -> Vec<String> {
+        let removed: HashSet<&String> = removed.iter().collect();
+        let mut changed = Vec::new();
+
+        let paths: Vec<String> = self.nodes.keys().cloned().collect();
+        for path in paths {
+            let Some(node) = self.nodes.get(&path) else {
+                continue;
+            };
+            let mut imports = node.imports.clone();
+            let mut dirty = false;
+            for edge in &mut imports {
+                let affected = match &edge.resolved {
+                    None => true,
+                    Some(target) => {
+                        // `./dir` resolving to `dir/index.ts` is shadowed by a new `dir.ts`
+                        let parent = parent_key(target);
+                        let grandparent = parent.as_deref().and_then(parent_key);
+                        removed.contains(target)
+                            || parent.is_some_and(|p| touched_dirs.contains(&p))
+                            || grandparent.is_some_and(|p| touched_dirs.contains(&p))
+                    }
+                };
+                if affected {
+                    let resolved = self.resolve(&path, &edge.specifier);
+                    if resolved != edge.resolved {
+                        edge.resolved = resolved;
+                        dirty = true;
+                    }
+                }
+            }
+            if dirty {
+                if let Some(node) = self.nodes.get_mut(&path) {
+                    node.imports = imports;
+                }
+                changed.push(path);
+            }
+        }
+
+        changed
+    }
+
+    /// Drop modules no longer reachable from the entry
+    fn prune(&mut self) -> Vec<String> {
+        let mut reachable = HashSet::new();
+        let mut queue = VecDeque::from([self.entry.clone()]);
+        while let Some(path) = queue.pop_front() {
+            if !reachable.insert(path.clone()) {
+                continue;
+            }
+            if let Some(node) = self.nodes.get(&path) {
+                queue.extend(node.imports.iter().filter_map(|e| e.resolved.clone()));
+            }
+        }
+
+        let unreachable: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|path| !reachable.contains(*path))
+            .cloned()
+            .collect();
+        for path in &unreachable {
+            self.nodes.remove(path);
+        }
+        unreachable
+    }
+}
+
+fn module_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn parent_key(path: &str) -> Option<String> {
+    path.rsplit_once('/').map(|(parent, _)| parent.to_string())
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleGraphFormat {
+    Dot,
+    Json,
+}
+
+/// d3-force friendly graph: `nodes` with ids, `links` with `source`/`target` ids
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphJson {
+    pub entry: String,
+    pub nodes: Vec<ModuleGraphJsonNode>,
+    pub links: Vec<ModuleGraphJsonLink>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphJsonNode {
+    /// Path relative to the project root
+    pub id: String,
+    pub label: String,
+    /// File size in bytes
+    pub size: u64,
+    pub format: ModuleFormat,
+    pub external: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphJsonLink {
+    pub source: String,
+    pub target: String,
+    pub kind: ImportKind,
+}
+
+/// Result of `export_module_graph`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphExport {
+    /// Rendered graph, omitted when it was written to `output_path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+    pub module_count: usize,
+    pub edge_count: usize,
+    pub truncated: bool,
+}
+
+impl ModuleGraph {
+    pub fn to_json(&self) -> ModuleGraphJson {
+        let nodes = self
+            .nodes
+            .values()
+            .map(|node| ModuleGraphJsonNode {
+                id: self.relative_id(&node.path),
+                label: self.label(node),
+                size: fs::metadata(&node.path).map(|m| m.len()).unwrap_or(0),
+                format: detect_format(camino::Utf8Path::new(&node.path)),
+                external: node.external,
+            })
+            .collect();
+        let links = self
+            .edges()
+            .map(|(source, target, kind)| ModuleGraphJsonLink {
+                source: self.relative_id(source),
+                target: self.relative_id(target),
+                kind,
+            })
+            .collect();
+
+        ModuleGraphJson {
+            entry: self.relative_id(&self.entry),
+            nodes,
+            links,
+        }
+    }
+
+    /// Render as Graphviz DOT. Packages are dashed boxes, lazy edges are dashed arrows.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph modules {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, fontname=\"Helvetica\"];\n");
+
+        for node in self.nodes.values() {
+            let id = dot_escape(&self.relative_id(&node.path));
+            let label = dot_escape(&self.label(node));
+            let style = if node.external {
+                ", style=dashed"
+            } else if node.path == self.entry {
+                ", style=bold"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "  \"{}\" [label=\"{}\"{}];", id, label, style);
+        }
+        for (source, target, kind) in self.edges() {
+            let attributes = match kind {
+                ImportKind::Import => "",
+                ImportKind::ReExport => " [label=\"re-export\"]",
+                ImportKind::DynamicImport => " [style=dashed, label=\"dynamic\"]",
+                ImportKind::Require => " [label=\"require\"]",
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\"{};",
+                dot_escape(&self.relative_id(source)),
+                dot_escape(&self.relative_id(target)),
+                attributes
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Resolved edges between modules in the graph
+    fn edges(&self) -> impl Iterator<Item = (&str, &str, ImportKind)> {
+        self.nodes.values().flat_map(move |node| {
+            node.imports.iter().filter_map(move |edge| {
+                let target = edge.resolved.as_deref()?;
+                self.nodes
+                    .contains_key(target)
+                    .then_some((node.path.as_str(), target, edge.kind))
+            })
+        })
+    }
+
+    fn relative_id(&self, path: &str) -> String {
+        path.strip_prefix(&self.project_root)
+            .map(|rest| rest.trim_start_matches('/'))
+            .unwrap_or(path)
+            .to_string()
+    }
+
+    /// Package name for external modules, project-relative path otherwise
+    fn label(&self, node: &ModuleNode) -> String {
+        if node.external {
+            if let Some((_, rest)) = node.path.rsplit_once("/node_modules/") {
+                let mut segments = rest.split('/');
+                let first = segments.next().unwrap_or(rest);
+                return match segments.next() {
+                    Some(scoped) if first.starts_with('@') => format!("{}/{}", first, scoped),
+                    _ => first.to_string(),
+                };
+            }
+        }
+        self.relative_id(&node.path)
+    }
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// ============================================================================
+// Watch State
+// ============================================================================
+
+/// Running module graph watches, keyed by watch id
+#[derive(Clone, Default)]
+pub struct ModuleGraphWatchState {
+    watches: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+}
+
+impl ModuleGraphWatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of running watches
+    pub async fn watch_count(&self) -> usize {
+        self.watches.lock().await.len()
+    }
+
+    /// Stop every watch (used on app exit)
+    pub async fn stop_all(&self) {
+        for (_, task) in self.watches.lock().await.drain() {
+            task.abort();
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Build the module graph for `entry` and keep it updated as files change.
+///
+/// Returns the initial graph; later changes are emitted as `module-graph://updated`
+/// deltas carrying the returned `watch_id`.
+///
+/// # Arguments
+/// * `entry` - Entry module to start the graph from
+/// * `project_root` - Root to watch and resolve `node_modules` against
+#[fluxel_command]
+pub async fn watch_module_graph<R: Runtime>(
+    app: AppHandle<R>,
+    entry: String,
+    project_root: String,
+    state: State<'_, ModuleGraphWatchState>,
+    watcher: State<'_, FsWatcherService>,
+) -> Result<ModuleGraphSnapshot, String> {
+    // Canonical paths so graph keys match the paths the watcher reports
+    let entry_path = PathBuf::from(&entry)
+        .canonicalize()
+        .map_err(|e| format!("Entry module not found: {} ({})", entry, e))?;
+    let root_path = PathBuf::from(&project_root)
+        .canonicalize()
+        .map_err(|e| format!("Project root not found: {} ({})", project_root, e))?;
+
+    let mut subscription = watcher.subscribe(&root_path)?;
+    let graph =
+        tauri::async_runtime::spawn_blocking(move || ModuleGraph::build(&entry_path, &root_path))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let watch_id = format!(
+        "module_graph_{}",
+        NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let snapshot = graph.snapshot(&watch_id);
+    println!(
+        "[ModuleGraph] Watching {} ({} modules)",
+        snapshot.entry,
+        snapshot.modules.len()
+    );
+
+    let graph = Arc::new(std::sync::Mutex::new(graph));
+    let task_id = watch_id.clone();
+    let task =
+        tokio::spawn(async move {
+            while let Some(first) = subscription.recv().await {
+                let mut modified = BTreeSet::new();
+                let mut structural = BTreeSet::new();
+                let mut pending = Some(first);
+                while let Some(event) = pending {
+                    let target = match event.kind {
+                        FsChangeKind::Modified => &mut modified,
+                        _ => &mut structural,
+                    };
+                    target.extend(event.paths.into_iter().filter(|path| {
+                        !path.contains("/node_modules/") && !path.contains("/.git/")
+                    }));
+                    pending = tokio::time::timeout(DEBOUNCE, subscription.recv())
+                        .await
+                        .ok()
+                        .flatten();
+                }
+
+                let graph = Arc::clone(&graph);
+                let delta = tauri::async_runtime::spawn_blocking(move || {
+                    let mut graph = graph.lock().unwrap();
+                    let modified: Vec<String> = modified
+                        .into_iter()
+                        .filter(|path| graph.contains(path))
+                        .collect();
+                    let structural: Vec<String> = structural.into_iter().collect();
+                    if modified.is_empty() && structural.is_empty() {
+                        return ModuleGraphDelta::default();
+                    }
+                    graph.apply_changes(&modified, &structural)
+                })
+                .await;
+
+                if let Ok(mut delta) = delta {
+                    if !delta.is_empty() {
+                        delta.watch_id = task_id.clone();
+                        let _ = app.emit(UPDATED_EVENT, delta);
+                    }
+                }
+            }
+        });
+
+    state.watches.lock().await.insert(watch_id, task);
+    Ok(snapshot)
+}
+
+/// Stop a module graph watch started with `watch_module_graph`
+#[fluxel_command]
+pub async fn unwatch_module_graph(
+    watch_id: String,
+    state: State<'_, ModuleGraphWatchState>,
+) -> Result<(), String> {
+    let task = state
+        .watches
+        .lock()
+        .await
+        .remove(&watch_id)
+        .ok_or_else(|| format!("Module graph watch not found: {}", watch_id))?;
+    // Aborting drops the watcher subscription with the task
+    task.abort();
+    Ok(())
+}
+
+/// Export the dependency graph of `entry` as Graphviz DOT or d3-friendly JSON
+///
+/// # Arguments
+/// * `entry` - Entry module to start the graph from
+/// * `project_root` - Root that node ids are made relative to
+/// * `format` - `"dot"` or `"json"`
+/// * `output_path` - Write the export to this file instead of returning it
+#[fluxel_command]
+pub async fn export_module_graph(
+    entry: String,
+    project_root: String,
+    format: ModuleGraphFormat,
+    output_path: Option<String>,
+) -> Result<ModuleGraphExport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let entry_path = PathBuf::from(&entry)
+            .canonicalize()
+            .map_err(|e| format!("Entry module not found: {} ({})", entry, e))?;
+        let root_path = PathBuf::from(&project_root)
+            .canonicalize()
+            .map_err(|e| format!("Project root not found: {} ({})", project_root, e))?;
+
+        let graph = ModuleGraph::build(&entry_path, &root_path);
+        let content = match format {
+            ModuleGraphFormat::Dot => graph.to_dot(),
+            ModuleGraphFormat::Json => serde_json::to_string_pretty(&graph.to_json())
+                .map_err(|e| format!("Failed to serialize module graph: {}", e))?,
+        };
+        let module_count = graph.nodes.len();
+        let edge_count = graph.edges().count();
+
+        let content = match &output_path {
+            Some(path) => {
+                fs::write(path, &content)
+                    .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+                println!(
+                    "[ModuleGraph] Exported {} modules to {}",
+                    module_count, path
+                );
+                None
+            }
+            None => Some(content),
+        };
+
+        Ok(ModuleGraphExport {
+            content,
+            output_path,
+            module_count,
+            edge_count,
+            truncated: graph.truncated,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn edge(specifier: &str, resolved: Option<&str>) -> ModuleEdge {
+        ModuleEdge {
+            specifier: specifier.to_string(),
+            kind: ImportKind::Import,
+            resolved: resolved.map(str::to_string),
+        }
+    }
+
+    fn node(path: &str, imports: Vec<ModuleEdge>) -> ModuleNode {
+        ModuleNode {
+            path: path.to_string(),
+            imports,
+            external: false,
+            error: None,
+        }
+    }
+
+    fn graph(root: &str, nodes: Vec<ModuleNode>) -> ModuleGraph {
+        ModuleGraph {
+            entry: nodes[0].path.clone(),
+            project_root: root.to_string(),
+            nodes: nodes.into_iter().map(|n| (n.path.clone(), n)).collect(),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn prunes_modules_no_longer_reachable() {
+        let mut graph = graph(
+            "/w",
+            vec![
+                node("/w/main.ts", vec![edge("./a", Some("/w/a.ts"))]),
+                node("/w/a.ts", vec![]),
+                node("/w/b.ts", vec![edge("./c", Some("/w/c.ts"))]),
+                node("/w/c.ts", vec![]),
+            ],
+        );
+        assert_eq!(graph.prune(), vec!["/w/b.ts", "/w/c.ts"]);
+        assert!(graph.contains("/w/a.ts"));
+    }
+
+    #[test]
+    fn reresolves_edges_affected_by_created_files() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-module-graph-reresolve-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(dir.join("store")).unwrap();
+        fs::write(dir.join("main.ts"), "").unwrap();
+        fs::write(dir.join("store/index.ts"), "").unwrap();
+        fs::write(dir.join("theme.ts"), "").unwrap();
+        let root = module_key(&normalize(&dir));
+        let main = format!("{root}/main.ts");
+        let store_index = format!("{root}/store/index.ts");
+        let elsewhere = format!("{root}/lib/deep/util.ts");
+
+        let mut graph = graph(
+            &root,
+            vec![node(
+                &main,
+                vec![
+                    edge("./theme", None),
+                    edge("./store", Some(&store_index)),
+                    edge("./lib/deep/util", Some(&elsewhere)),
+                ],
+            )],
+        );
+
+        // `theme.ts` now exists and a new `store.ts` shadows `store/index.ts`
+        fs::write(dir.join("store.ts"), "").unwrap();
+        let touched = HashSet::from([root.clone()]);
+        assert_eq!(graph.reresolve_edges(&touched, &[]), vec![main.clone()]);
+
+        let imports = &graph.nodes[&main].imports;
+        assert_eq!(imports[0].resolved, Some(format!("{root}/theme.ts")));
+        assert_eq!(imports[1].resolved, Some(format!("{root}/store.ts")));
+        // Untouched directories keep their previous resolution
+        assert_eq!(imports[2].resolved, Some(elsewhere));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+    #[test]
+    fn exports_dot_and_json() {
+        let mut app = node(
+            "/w/src/app.ts",
+            vec![
+                edge("react", Some("/w/node_modules/@scope/ui/index.js")),
+                edge("./page", Some("/w/src/page \"x\".ts")),
+                edge("./missing", None),
+            ],
+        );
+        app.imports[1].kind = ImportKind::DynamicImport;
+        let mut package = node("/w/node_modules/@scope/ui/index.js", vec![]);
+        package.external = true;
+        let graph = graph(
+            "/w",
+            vec![app, package, node("/w/src/page \"x\".ts", vec![])],
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph modules {\n"));
+        assert!(dot.contains("  \"src/app.ts\" [label=\"src/app.ts\", style=bold];\n"));
+        assert!(dot.contains(
+            "  \"node_modules/@scope/ui/index.js\" [label=\"@scope/ui\", style=dashed];\n"
+        ));
+        assert!(dot.contains(
+            "  \"src/app.ts\" -> \"src/page \\\"x\\\".ts\" [style=dashed, label=\"dynamic\"];\n"
+        ));
+
+        let json = graph.to_json();
+        assert_eq!(json.entry, "src/app.ts");
+        assert_eq!(json.nodes.len(), 3);
+        assert!(matches!(json.nodes[1].format, ModuleFormat::Esm));
+        let links: Vec<(&str, ImportKind)> = json
+            .links
+            .iter()
+            .map(|l| (l.target.as_str(), l.kind))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("node_modules/@scope/ui/index.js", ImportKind::Import),
+                ("src/page \"x\".ts", ImportKind::DynamicImport),
+            ]
+        );
+    }
+}
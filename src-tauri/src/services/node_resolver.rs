@@ -5,16 +5,24 @@
 //!
 //! It delegates to the `fluxel_node_resolver` crate for the actual resolution logic.
 
-use camino::Utf8PathBuf;
+use crate::services::ts_projects::{ReferenceStrategy, TsProjectState};
+use camino::{Utf8Path, Utf8PathBuf};
+use fluxel_macros::fluxel_command;
 use fluxel_node_resolver::{
-    analyze_module_native, discover_typings_native, resolve_module_native, AnalyzeResponse,
-    ResolveOptions, ResolveRequest, ResolveResponse, TypingsResponse,
+    analyze_module_native, bundle_typings_native, detect_format, discover_typings_native,
+    extract_dts_symbols, resolve_module_native, walk_module_graph_native, AnalyzeResponse,
+    BundleOptions, DtsSymbol, GraphOptions, ModuleGraph, Platform, ResolveOptions, ResolveRequest,
+    ResolveResponse, TransformOptions, TypingsBundle, TypingsResponse,
 };
+use std::path::Path;
+use tauri::State;
 
 fn build_options(
     conditions: Option<Vec<String>>,
     extensions: Option<Vec<String>>,
     prefer_cjs: Option<bool>,
+    platform: Option<Platform>,
+    trace: Option<bool>,
 ) -> ResolveOptions {
     let mut opts = ResolveOptions::default();
     if let Some(conds) = conditions {
@@ -30,6 +38,10 @@ fn build_options(
     if let Some(prefer) = prefer_cjs {
         opts.prefer_cjs = prefer;
     }
+    if let Some(platform) = platform {
+        opts.platform = platform;
+    }
+    opts.trace = trace.unwrap_or(false);
     opts
 }
 
@@ -42,7 +54,13 @@ fn build_options(
 /// * `conditions` - Optional export conditions (e.g., ["import", "node"])
 /// * `extensions` - Optional file extensions to try
 /// * `prefer_cjs` - Whether to prefer CommonJS over ESM
-#[tauri::command]
+/// * `platform` - `Browser` to apply package.json `browser` field mappings (default `Node`)
+/// * `reference_strategy` - Map results inside tsconfig project references to their
+///   sources (`"source"`) or emitted declarations (`"declarations"`)
+/// * `trace` - Record the directories probed, conditions evaluated and fallbacks
+///   taken in the response's `trace`
+#[fluxel_command]
+#[allow(clippy::too_many_arguments)]
 pub async fn resolve_node_module(
     specifier: String,
     importer: String,
@@ -50,17 +68,40 @@ pub async fn resolve_node_module(
     conditions: Option<Vec<String>>,
     extensions: Option<Vec<String>>,
     prefer_cjs: Option<bool>,
+    platform: Option<Platform>,
+    reference_strategy: Option<ReferenceStrategy>,
+    trace: Option<bool>,
+    ts_projects: State<'_, TsProjectState>,
 ) -> Result<ResolveResponse, String> {
-    let opts = build_options(conditions, extensions, prefer_cjs);
-    resolve_module_native(
+    let opts = build_options(conditions, extensions, prefer_cjs, platform, trace);
+    let mut response = resolve_module_native(
         ResolveRequest {
             specifier,
             importer,
-            project_root,
+            project_root: project_root.clone(),
         },
         Some(opts),
     )
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    let tsconfig = project_root.map(|root| Path::new(&root).join("tsconfig.json"));
+    if let (Some(strategy), Some(tsconfig)) = (reference_strategy, tsconfig) {
+        let mapped = response
+            .resolved_path
+            .as_deref()
+            .filter(|_| tsconfig.is_file())
+            .and_then(|resolved| {
+                ts_projects
+                    .graph_for(&tsconfig)
+                    .map_resolved(Path::new(resolved), strategy)
+            });
+        if let Some(mapped) = mapped {
+            let mapped = mapped.to_string_lossy().replace('\\', "/");
+            response.format = detect_format(Utf8Path::new(&mapped));
+            response.resolved_path = Some(mapped);
+        }
+    }
+    Ok(response)
 }
 
 /// Discover TypeScript typings for a package
@@ -68,21 +109,102 @@ pub async fn resolve_node_module(
 /// # Arguments
 /// * `package_name` - The name of the package to find typings for
 /// * `project_root` - The project root directory containing node_modules
-#[tauri::command]
+/// * `subpath` - Entry point within the package, e.g. `merge` for `lodash/merge`
+#[fluxel_command]
 pub async fn discover_package_typings(
     package_name: String,
     project_root: String,
+    subpath: Option<String>,
 ) -> Result<TypingsResponse, String> {
     let root = Utf8PathBuf::from(project_root.clone());
-    discover_typings_native(&package_name, &root).map_err(|e| e.to_string())
+    discover_typings_native(&package_name, &root, subpath.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Collect a package's declaration files by following `/// <reference>` directives
+/// and relative imports from its entry `.d.ts`
+///
+/// # Arguments
+/// * `package_name` - The name of the package to bundle typings for
+/// * `project_root` - The project root directory containing node_modules
+/// * `options` - Subpath, whether to include file contents or a concatenated bundle, and the file cap
+#[fluxel_command]
+pub async fn bundle_typings(
+    package_name: String,
+    project_root: String,
+    options: Option<BundleOptions>,
+) -> Result<TypingsBundle, String> {
+    let root = Utf8PathBuf::from(project_root);
+    tauri::async_runtime::spawn_blocking(move || {
+        bundle_typings_native(&package_name, &root, options.unwrap_or_default())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Analyze the module dependency graph starting from a given file
 ///
 /// # Arguments
 /// * `path` - The entry point file to analyze
-#[tauri::command]
-pub async fn analyze_module_graph(path: String) -> Result<AnalyzeResponse, String> {
+/// * `transform` - Strip types or convert between ESM and CommonJS in the returned
+///   `transformed` source, for previewing or running the module
+#[fluxel_command]
+pub async fn analyze_module_graph(
+    path: String,
+    transform: Option<TransformOptions>,
+) -> Result<AnalyzeResponse, String> {
     let module_path = Utf8PathBuf::from(path);
-    analyze_module_native(&module_path).map_err(|e| e.to_string())
+    analyze_module_native(&module_path, transform).map_err(|e| e.to_string())
+}
+
+/// Walk the transitive dependency graph of a file
+///
+/// # Arguments
+/// * `path` - The entry point file
+/// * `project_root` - Optional project root for node_modules resolution
+/// * `max_depth` - How many imports deep to follow (default 10)
+/// * `max_files` - How many files to parse at most (default 500)
+/// * `follow_packages` - Walk into node_modules packages instead of stopping at their entry
+/// * `platform` - `Browser` to apply package.json `browser` field mappings (default `Node`)
+#[fluxel_command]
+pub async fn walk_module_graph(
+    path: String,
+    project_root: Option<String>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    follow_packages: Option<bool>,
+    platform: Option<Platform>,
+) -> Result<ModuleGraph, String> {
+    let defaults = GraphOptions::default();
+    let options = GraphOptions {
+        max_depth: max_depth.unwrap_or(defaults.max_depth),
+        max_files: max_files.unwrap_or(defaults.max_files),
+        follow_packages: follow_packages.unwrap_or(defaults.follow_packages),
+        resolve: build_options(None, None, None, platform, None),
+    };
+    // Parsing every reachable file is too slow for the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        walk_module_graph_native(
+            Utf8Path::new(&path),
+            project_root.as_deref().map(Utf8Path::new),
+            &options,
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List the symbols declared in a `.d.ts` file, to prime completions without
+/// sending the file to the webview
+///
+/// # Arguments
+/// * `path` - The declaration file
+#[fluxel_command]
+pub async fn extract_declaration_symbols(path: String) -> Result<Vec<DtsSymbol>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        extract_dts_symbols(Utf8Path::new(&path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
@@ -0,0 +1,688 @@
+//! Open With Service
+//!
+//! Reveals files in Explorer/Finder/the desktop file manager with the file
+//! selected, and opens files with an external application other than the
+//! system default. The applications registered for a file are enumerated per
+//! OS:
+//!
+//! - Linux: `.desktop` entries in the XDG data dirs whose `MimeType` lists the
+//!   file's type, as reported by `xdg-mime`
+//! - macOS: Launch Services, through `NSWorkspace` from `osascript`
+//! - Windows: the extension's `OpenWithProgids` and `OpenWithList` registry keys,
+//!   launched directly from their `shell\open\command` rather than through `cmd`
+//!
+//! Handler ids are opaque to the frontend, which remembers the chosen handler
+//! per extension in its settings.
+
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use std::{collections::HashMap, fs};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenWithHandler {
+    /// Passed back to `open_with_handler`
+    pub id: String,
+    pub name: String,
+    /// The application the system opens the file with by default
+    pub is_default: bool,
+}
+
+fn existing_path(path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Show `path` in the system file manager with the file selected
+#[fluxel_command]
+pub async fn reveal_in_file_manager(path: String) -> Result<(), String> {
+    let path = existing_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        tauri_plugin_opener::reveal_item_in_dir(&path)
+            .map_err(|e| format!("Failed to reveal {}: {}", path.display(), e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List the applications registered to open `path`, the default one first
+#[fluxel_command]
+pub async fn list_open_with_handlers(path: String) -> Result<Vec<OpenWithHandler>, String> {
+    let path = existing_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut handlers = platform::handlers(&path)?;
+        // Stable sort keeps the registry/XDG order within each group
+        handlers.sort_by_key(|handler| !handler.is_default);
+        Ok(handlers)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Open `path` with a handler from `list_open_with_handlers`.
+///
+/// # Arguments
+/// * `path` - File to open
+/// * `handler_id` - Handler to open it with; the system default when omitted
+#[fluxel_command]
+pub async fn open_with_handler(path: String, handler_id: Option<String>) -> Result<(), String> {
+    let path = existing_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || match handler_id {
+        Some(handler_id) => platform::open_with(&path, &handler_id),
+        None => tauri_plugin_opener::open_path(&path, None::<&str>)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e)),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Start `cmd` without waiting for the application to exit
+fn spawn_detached(mut cmd: std::process::Command, what: &str) -> Result<(), String> {
+    cmd.stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", what, e))?;
+    // Reap the process once it exits so it doesn't linger as a zombie
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// A `.desktop` entry that can open files
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DesktopEntry {
+    name: String,
+    exec: String,
+    mime_types: Vec<String>,
+}
+
+/// Parse the `[Desktop Entry]` group of a `.desktop` file. Hidden entries and
+/// entries that aren't applications yield `None`.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn parse_desktop_entry(content: &str) -> Option<DesktopEntry> {
+    let mut in_entry = false;
+    let mut fields = HashMap::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+        // Localized keys such as `Name[de]` are skipped
+        if let Some((key, value)) = line.split_once('=') {
+            fields.insert(key.trim(), value.trim());
+        }
+    }
+    if fields.get("Type").is_some_and(|t| *t != "Application")
+        || fields.get("Hidden") == Some(&"true")
+        || fields.get("NoDisplay") == Some(&"true")
+    {
+        return None;
+    }
+    Some(DesktopEntry {
+        name: fields.get("Name")?.to_string(),
+        exec: fields.get("Exec")?.to_string(),
+        mime_types: fields
+            .get("MimeType")
+            .map(|types| {
+                types
+                    .split(';')
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Split an `Exec` value into arguments and expand its field codes for `path`.
+/// The path is appended when the value has no file or URL field code.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn expand_exec(exec: &str, name: &str, path: &Path) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            '\\' if quoted => current.extend(chars.next()),
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+
+    let file = path.to_string_lossy();
+    let mut has_file = false;
+    let mut args = Vec::new();
+    for token in tokens {
+        match token.as_str() {
+            "%f" | "%F" | "%u" | "%U" => {
+                has_file = true;
+                args.push(file.to_string());
+            }
+            // Icons and other deprecated codes expand to nothing
+            "%i" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" | "%k" => {}
+            _ => args.push(token.replace("%c", name).replace("%%", "%")),
+        }
+    }
+    if !has_file {
+        args.push(file.to_string());
+    }
+    args
+}
+
+/// Split a registry `shell\open\command` value into the program and its
+/// arguments, expanding `%VAR%` references with `env` and `%1`/`%L`/`%l` to
+/// `path`. The path is appended when the value doesn't mention it. Each element
+/// is passed to the program as its own argument, so nothing in the path is ever
+/// interpreted by a shell.
+#[cfg(any(target_os = "windows", test))]
+fn expand_shell_command(
+    command: &str,
+    path: &Path,
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quoted = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+
+    let file = path.to_string_lossy();
+    let mut has_file = false;
+    let mut args = Vec::new();
+    for token in tokens {
+        // Extra arguments of the verb; there are none
+        if token == "%*" {
+            continue;
+        }
+        let mut arg = String::new();
+        let mut rest = token.as_str();
+        while let Some(start) = rest.find('%') {
+            arg.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            match after.chars().next() {
+                Some('1' | 'L' | 'l') => {
+                    has_file = true;
+                    arg.push_str(&file);
+                    rest = &after[1..];
+                }
+                _ => match after
+                    .find('%')
+                    .and_then(|end| Some((end, env(&after[..end])?)))
+                {
+                    Some((end, value)) => {
+                        arg.push_str(&value);
+                        rest = &after[end + 1..];
+                    }
+                    None => {
+                        arg.push('%');
+                        rest = after;
+                    }
+                },
+            }
+        }
+        arg.push_str(rest);
+        args.push(arg);
+    }
+    if !has_file {
+        args.push(file.to_string());
+    }
+    args
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    /// `applications` folders in XDG lookup order, user entries first
+    fn application_dirs() -> Vec<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")));
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        data_home
+            .into_iter()
+            .chain(std::env::split_paths(&data_dirs))
+            .map(|dir| dir.join("applications"))
+            .collect()
+    }
+
+    /// Every visible desktop entry by desktop file id; earlier folders win
+    fn desktop_entries() -> Vec<(String, DesktopEntry)> {
+        let mut seen = std::collections::HashSet::new();
+        let mut entries = Vec::new();
+        for dir in application_dirs() {
+            let walker = walkdir::WalkDir::new(&dir)
+                .max_depth(2)
+                .sort_by_file_name()
+                .into_iter()
+                .flatten();
+            for file in walker {
+                if file.path().extension().and_then(|e| e.to_str()) != Some("desktop") {
+                    continue;
+                }
+                // Entries in subfolders get ids like `kde-okular.desktop`
+                let Ok(relative) = file.path().strip_prefix(&dir) else {
+                    continue;
+                };
+                let id = relative.to_string_lossy().replace('/', "-");
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                if let Some(entry) = fs::read_to_string(file.path())
+                    .ok()
+                    .and_then(|content| parse_desktop_entry(&content))
+                {
+                    entries.push((id, entry));
+                }
+            }
+        }
+        entries
+    }
+
+    fn xdg_mime(args: &[&str]) -> Option<String> {
+        let output = Command::new("xdg-mime").args(args).output().ok()?;
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !value.is_empty()).then_some(value)
+    }
+
+    pub(super) fn handlers(path: &Path) -> Result<Vec<OpenWithHandler>, String> {
+        let mime = xdg_mime(&["query", "filetype", &path.to_string_lossy()])
+            .ok_or_else(|| format!("Could not determine the file type of {}", path.display()))?;
+        let default = xdg_mime(&["query", "default", &mime]);
+        Ok(desktop_entries()
+            .into_iter()
+            .filter(|(_, entry)| entry.mime_types.contains(&mime))
+            .map(|(id, entry)| OpenWithHandler {
+                is_default: default.as_deref() == Some(id.as_str()),
+                id,
+                name: entry.name,
+            })
+            .collect())
+    }
+
+    pub(super) fn open_with(path: &Path, handler_id: &str) -> Result<(), String> {
+        let (_, entry) = desktop_entries()
+            .into_iter()
+            .find(|(id, _)| id == handler_id)
+            .ok_or_else(|| format!("Application not found: {}", handler_id))?;
+        let args = expand_exec(&entry.exec, &entry.name, path);
+        let (program, args) = args
+            .split_first()
+            .ok_or_else(|| format!("Application has no command: {}", handler_id))?;
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        if let Some(parent) = path.parent() {
+            cmd.current_dir(parent);
+        }
+        spawn_detached(cmd, &entry.name)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    /// Prints the default application and every application Launch Services
+    /// offers for the file given as the first argument, as JSON
+    const LIST_SCRIPT: &str = r#"
+function run(argv) {
+    ObjC.import('AppKit');
+    const workspace = $.NSWorkspace.sharedWorkspace;
+    const url = $.NSURL.fileURLWithPath(argv[0]);
+    let defaultApp = null;
+    try {
+        defaultApp = ObjC.unwrap(workspace.URLForApplicationToOpenURL(url).path) || null;
+    } catch (e) {}
+    const apps = workspace.URLsForApplicationsToOpenURL(url);
+    const paths = [];
+    for (let i = 0; i < apps.count; i++) {
+        paths.push(ObjC.unwrap(apps.objectAtIndex(i).path));
+    }
+    return JSON.stringify({ defaultApp, apps: paths });
+}
+"#;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct LaunchServicesApps {
+        default_app: Option<String>,
+        apps: Vec<String>,
+    }
+
+    pub(super) fn handlers(path: &Path) -> Result<Vec<OpenWithHandler>, String> {
+        let output = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", LIST_SCRIPT])
+            .arg(path)
+            .output()
+            .map_err(|e| format!("Failed to run osascript: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to list applications: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let apps: LaunchServicesApps = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Unexpected osascript output: {}", e))?;
+        Ok(apps
+            .apps
+            .into_iter()
+            .map(|app| OpenWithHandler {
+                name: Path::new(&app)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| app.clone()),
+                is_default: apps.default_app.as_deref() == Some(app.as_str()),
+                id: app,
+            })
+            .collect())
+    }
+
+    pub(super) fn open_with(path: &Path, handler_id: &str) -> Result<(), String> {
+        let mut cmd = Command::new("open");
+        cmd.arg("-a").arg(handler_id).arg(path);
+        spawn_detached(cmd, handler_id)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const FILE_EXTS: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts";
+    /// Handler id prefixes for ProgIds and bare executables
+    const PROG_ID: &str = "progid:";
+    const APP: &str = "app:";
+    const APP_PATHS_HKCU: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\App Paths";
+    const APP_PATHS_HKLM: &str = r"HKLM\Software\Microsoft\Windows\CurrentVersion\App Paths";
+
+    /// Output of `reg query <key> [args]`, or `None` when the key doesn't exist
+    fn reg_query(key: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new("reg")
+            .arg("query")
+            .arg(key)
+            .args(args)
+            .creation_flags(0x08000000)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// `(name, data)` of the values listed by `reg query`; the default value is
+    /// named `(Default)`
+    fn reg_values(output: &str) -> Vec<(String, String)> {
+        output
+            .lines()
+            .filter(|line| line.starts_with("    "))
+            .filter_map(|line| {
+                let line = line.trim();
+                let type_start = line.find("    REG_")?;
+                let name = line[..type_start].trim().to_string();
+                let data = line[type_start..]
+                    .trim()
+                    .split_once("    ")
+                    .map(|(_, data)| data.trim().to_string())
+                    .unwrap_or_default();
+                Some((name, data))
+            })
+            .collect()
+    }
+
+    /// Names of the subkeys listed by `reg query <key>`
+    fn reg_subkeys(output: &str, key: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let (parent, name) = line.trim().rsplit_once('\\')?;
+                parent
+                    .eq_ignore_ascii_case(&expand_root(key))
+                    .then(|| name.to_string())
+            })
+            .collect()
+    }
+
+    /// `reg query` prints full root key names
+    fn expand_root(key: &str) -> String {
+        key.replacen("HKCR", "HKEY_CLASSES_ROOT", 1)
+            .replacen("HKCU", "HKEY_CURRENT_USER", 1)
+    }
+
+    fn reg_value(key: &str, name: Option<&str>) -> Option<String> {
+        let args = match name {
+            Some(name) => vec!["/v", name],
+            None => vec!["/ve"],
+        };
+        reg_values(&reg_query(key, &args)?)
+            .into_iter()
+            .next()
+            .map(|(_, data)| data)
+            .filter(|data| !data.is_empty())
+    }
+
+    fn extension(path: &Path) -> Result<String, String> {
+        path.extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy().to_ascii_lowercase()))
+            .ok_or_else(|| format!("{} has no file extension", path.display()))
+    }
+
+    pub(super) fn handlers(path: &Path) -> Result<Vec<OpenWithHandler>, String> {
+        let ext = extension(path)?;
+        let user_ext = format!(r"{}\{}", FILE_EXTS, ext);
+        let default = reg_value(&format!(r"{}\UserChoice", user_ext), Some("ProgId"))
+            .or_else(|| reg_value(&format!(r"HKCR\{}", ext), None));
+
+        let mut prog_ids: Vec<String> = default.iter().cloned().collect();
+        for key in [
+            format!(r"HKCR\{}\OpenWithProgids", ext),
+            format!(r"{}\OpenWithProgids", user_ext),
+        ] {
+            if let Some(output) = reg_query(&key, &[]) {
+                prog_ids.extend(reg_values(&output).into_iter().map(|(name, _)| name));
+            }
+        }
+        let mut apps = Vec::new();
+        let key = format!(r"HKCR\{}\OpenWithList", ext);
+        if let Some(output) = reg_query(&key, &[]) {
+            apps.extend(reg_subkeys(&output, &key));
+        }
+        if let Some(output) = reg_query(&format!(r"{}\OpenWithList", user_ext), &[]) {
+            apps.extend(
+                reg_values(&output)
+                    .into_iter()
+                    .filter(|(name, _)| name != "MRUList")
+                    .map(|(_, exe)| exe),
+            );
+        }
+
+        let mut handlers: Vec<OpenWithHandler> = Vec::new();
+        for prog_id in prog_ids {
+            let id = format!("{}{}", PROG_ID, prog_id);
+            if prog_id == "(Default)" || handlers.iter().any(|h| h.id.eq_ignore_ascii_case(&id)) {
+                continue;
+            }
+            // ProgIds without an open command can't be launched
+            if reg_value(&format!(r"HKCR\{}\shell\open\command", prog_id), None).is_none() {
+                continue;
+            }
+            handlers.push(OpenWithHandler {
+                name: reg_value(&format!(r"HKCR\{}", prog_id), None)
+                    .unwrap_or_else(|| prog_id.clone()),
+                is_default: default.as_deref() == Some(prog_id.as_str()),
+                id,
+            });
+        }
+        for exe in apps {
+            let id = format!("{}{}", APP, exe);
+            if handlers.iter().any(|h| h.id.eq_ignore_ascii_case(&id)) {
+                continue;
+            }
+            handlers.push(OpenWithHandler {
+                name: reg_value(
+                    &format!(r"HKCR\Applications\{}", exe),
+                    Some("FriendlyAppName"),
+                )
+                .unwrap_or_else(|| exe.trim_end_matches(".exe").to_string()),
+                is_default: false,
+                id,
+            });
+        }
+        Ok(handlers)
+    }
+
+    pub(super) fn open_with(path: &Path, handler_id: &str) -> Result<(), String> {
+        let command_key = if let Some(prog_id) = handler_id.strip_prefix(PROG_ID) {
+            format!(r"HKCR\{}\shell\open\command", prog_id)
+        } else if let Some(exe) = handler_id.strip_prefix(APP) {
+            format!(r"HKCR\Applications\{}\shell\open\command", exe)
+        } else {
+            return Err(format!("Unknown handler: {}", handler_id));
+        };
+
+        // The handler is started directly rather than through `cmd /C`, which
+        // would interpret `%` and `&` in the file name
+        let args = match reg_value(&command_key, None) {
+            Some(command) => expand_shell_command(&command, path, |name| std::env::var(name).ok()),
+            None => {
+                // Executables registered by name only are found through App Paths
+                let exe = handler_id.strip_prefix(APP).unwrap_or(handler_id);
+                let program = [APP_PATHS_HKCU, APP_PATHS_HKLM]
+                    .iter()
+                    .find_map(|root| reg_value(&format!(r"{}\{}", root, exe), None))
+                    .unwrap_or_else(|| exe.to_string());
+                let program = program.trim_matches('"').to_string();
+                vec![program, path.to_string_lossy().to_string()]
+            }
+        };
+        let (program, args) = args
+            .split_first()
+            .ok_or_else(|| format!("Handler {} has an empty command", handler_id))?;
+        let mut cmd = Command::new(program);
+        cmd.args(args).creation_flags(0x08000000);
+        spawn_detached(cmd, handler_id)
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "windows", target_os = "macos"))))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_desktop_entries_and_expands_exec() {
+        let entry = parse_desktop_entry(
+            "[Desktop Entry]\nType=Application\nName=Code\nName[de]=Kode\n\
+             Exec=/usr/bin/code --new-window \"--title=%c\" %F\n\
+             MimeType=text/plain;application/json;\n\n\
+             [Desktop Action new]\nName=New Window\nExec=/usr/bin/code -n\n",
+        )
+        .unwrap();
+        assert_eq!(entry.name, "Code");
+        assert_eq!(entry.mime_types, ["text/plain", "application/json"]);
+
+        let path = Path::new("/work/my notes.txt");
+        assert_eq!(
+            expand_exec(&entry.exec, &entry.name, path),
+            [
+                "/usr/bin/code",
+                "--new-window",
+                "--title=Code",
+                "/work/my notes.txt"
+            ]
+        );
+        assert_eq!(
+            expand_exec("gedit %i", "Text Editor", path),
+            ["gedit", "/work/my notes.txt"]
+        );
+
+        assert!(parse_desktop_entry("[Desktop Entry]\nName=X\nExec=x\nNoDisplay=true\n").is_none());
+    }
+
+    #[test]
+    fn expands_registry_commands_without_a_shell() {
+        let env = |name: &str| (name == "SystemRoot").then(|| r"C:\Windows".to_string());
+        let path = Path::new(r"C:\work\100% & done.txt");
+        assert_eq!(
+            expand_shell_command(r#"%SystemRoot%\system32\NOTEPAD.EXE %1"#, path, env),
+            [
+                r"C:\Windows\system32\NOTEPAD.EXE",
+                r"C:\work\100% & done.txt"
+            ]
+        );
+        assert_eq!(
+            expand_shell_command(
+                r#""C:\Program Files\Editor\editor.exe" --file="%L" %*"#,
+                path,
+                env
+            ),
+            [
+                r"C:\Program Files\Editor\editor.exe",
+                r"--file=C:\work\100% & done.txt"
+            ]
+        );
+        assert_eq!(
+            expand_shell_command(r#""C:\Tools\viewer.exe" %UNSET%"#, path, env),
+            [
+                r"C:\Tools\viewer.exe",
+                "%UNSET%",
+                r"C:\work\100% & done.txt"
+            ]
+        );
+    }
+}
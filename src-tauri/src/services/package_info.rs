@@ -0,0 +1,251 @@
+//! Package Info Service
+//!
+//! Metadata about an npm package for hover tooltips on import lines: the
+//! installed version and what its package.json says about it, the range the
+//! project asks for, and the versions the lockfile pins, so a newer locked
+//! version than the one in `node_modules` (a stale install) can be pointed out.
+//!
+//! Everything is read from disk; nothing is fetched from the registry.
+
+use crate::services::dependency_audit::{compare_versions, find_node_module, npm_license};
+use crate::services::lockfile::{find_lockfiles, parse_lockfile_at, LockfileKind};
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Sections of the project's package.json that can request a package
+const REQUEST_SECTIONS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInfo {
+    pub name: String,
+    /// Version installed in `node_modules`
+    pub version: Option<String>,
+    pub license: Option<String>,
+    pub description: Option<String>,
+    /// `homepage`, or the repository URL when there is none
+    pub homepage: Option<String>,
+    /// Range the project's package.json asks for
+    pub requested: Option<String>,
+    /// Dependencies the installed package declares
+    pub dependencies: BTreeMap<String, String>,
+    pub peer_dependencies: BTreeMap<String, String>,
+    /// Versions of the package pinned by the lockfile, oldest first
+    pub locked_versions: Vec<String>,
+    /// Newest locked version, when it is newer than the installed one
+    pub newer_version: Option<String>,
+    /// Directory the package is installed in
+    pub install_path: Option<String>,
+}
+
+/// Package name of a bare specifier: `lodash/fp` → `lodash`,
+/// `@scope/pkg/sub` → `@scope/pkg`
+fn package_root(specifier: &str) -> &str {
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(segments - 1) {
+        Some((index, _)) => &specifier[..index],
+        None => specifier,
+    }
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn string_map(json: &serde_json::Value, section: &str) -> BTreeMap<String, String> {
+    json.get(section)
+        .and_then(|value| value.as_object())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|(name, range)| Some((name.clone(), range.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `homepage`, else the repository URL without its `git+` prefix and `.git` suffix
+fn homepage(json: &serde_json::Value) -> Option<String> {
+    if let Some(homepage) = json.get("homepage").and_then(|v| v.as_str()) {
+        return Some(homepage.to_string());
+    }
+    let repository = match json.get("repository")? {
+        serde_json::Value::String(url) => url.as_str(),
+        serde_json::Value::Object(repository) => repository.get("url")?.as_str()?,
+        _ => return None,
+    };
+    let url = repository.trim_start_matches("git+");
+    Some(url.strip_suffix(".git").unwrap_or(url).to_string())
+}
+
+/// Every version of `name` pinned by the npm lockfiles of the nearest directory
+/// at or above `project_root` that has any
+fn locked_versions(project_root: &Path, name: &str) -> Vec<String> {
+    let Some(lockfiles) = project_root
+        .ancestors()
+        .map(find_lockfiles)
+        .find(|lockfiles| !lockfiles.is_empty())
+    else {
+        return Vec::new();
+    };
+
+    let mut versions = BTreeSet::new();
+    for (kind, path) in lockfiles {
+        if kind == LockfileKind::Cargo {
+            continue;
+        }
+        match parse_lockfile_at(kind, &path) {
+            Ok(lockfile) => {
+                if let Some(locked) = lockfile.packages.get(name) {
+                    versions.extend(locked.keys().cloned());
+                }
+            }
+            Err(e) => println!("[PackageInfo] {}", e),
+        }
+    }
+    let mut versions: Vec<String> = versions.into_iter().collect();
+    versions.sort_by(|a, b| compare_versions(a, b));
+    versions
+}
+
+fn package_info(project_root: &Path, specifier: &str) -> PackageInfo {
+    let name = package_root(specifier);
+    let mut info = PackageInfo {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    if let Some(manifest) = read_json(&project_root.join("package.json")) {
+        info.requested = REQUEST_SECTIONS
+            .iter()
+            .find_map(|section| string_map(&manifest, section).remove(name));
+    }
+
+    // Hoisted installs may live above the project root
+    let top = project_root.ancestors().last().unwrap_or(project_root);
+    if let Some(package_dir) = find_node_module(project_root, top, name) {
+        if let Some(json) = read_json(&package_dir.join("package.json")) {
+            info.version = json
+                .get("version")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            info.license = npm_license(&json, &package_dir).map(|(license, _)| license);
+            info.description = json
+                .get("description")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            info.homepage = homepage(&json);
+            info.dependencies = string_map(&json, "dependencies");
+            info.peer_dependencies = string_map(&json, "peerDependencies");
+        }
+        info.install_path = Some(package_dir.to_string_lossy().to_string());
+    }
+
+    info.locked_versions = locked_versions(project_root, name);
+    info.newer_version = match (&info.version, info.locked_versions.last()) {
+        (Some(installed), Some(newest)) if compare_versions(newest, installed).is_gt() => {
+            Some(newest.clone())
+        }
+        _ => None,
+    };
+    info
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Get what is known locally about an npm package
+///
+/// # Arguments
+/// * `package_name` - Package name or bare import specifier (`lodash/fp`)
+/// * `project_root` - Project whose package.json, `node_modules` and lockfile are read
+#[fluxel_command]
+pub async fn get_package_info(
+    package_name: String,
+    project_root: String,
+) -> Result<PackageInfo, String> {
+    if package_name.trim().is_empty() {
+        return Err("Package name is empty".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        package_info(&PathBuf::from(&project_root), package_name.trim())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn reads_installed_and_locked_package_info() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-package-info-{unique}"));
+        let package_dir = root.join("node_modules").join("@scope").join("ui");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "name": "app", "devDependencies": { "@scope/ui": "^1.2.0" } }"#,
+        )
+        .unwrap();
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{
+                "name": "@scope/ui",
+                "version": "1.2.0",
+                "license": "MIT",
+                "description": "Widgets",
+                "repository": { "type": "git", "url": "git+https://github.com/scope/ui.git" },
+                "dependencies": { "clsx": "^2.0.0" },
+                "peerDependencies": { "react": ">=18" }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("package-lock.json"),
+            r#"{
+                "name": "app",
+                "lockfileVersion": 3,
+                "packages": {
+                    "": { "devDependencies": { "@scope/ui": "^1.2.0" } },
+                    "node_modules/@scope/ui": { "version": "1.10.0", "dev": true }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let info = package_info(&root, "@scope/ui/button");
+        assert_eq!(info.name, "@scope/ui");
+        assert_eq!(info.version.as_deref(), Some("1.2.0"));
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(info.description.as_deref(), Some("Widgets"));
+        assert_eq!(
+            info.homepage.as_deref(),
+            Some("https://github.com/scope/ui")
+        );
+        assert_eq!(info.requested.as_deref(), Some("^1.2.0"));
+        assert_eq!(info.dependencies["clsx"], "^2.0.0");
+        assert_eq!(info.peer_dependencies["react"], ">=18");
+        assert_eq!(info.locked_versions, ["1.10.0"]);
+        assert_eq!(info.newer_version.as_deref(), Some("1.10.0"));
+
+        let missing = package_info(&root, "left-pad");
+        assert!(missing.version.is_none() && missing.install_path.is_none());
+        assert!(missing.newer_version.is_none());
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
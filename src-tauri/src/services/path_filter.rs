@@ -0,0 +1,284 @@
+//! Path Filter Service
+//!
+//! One set of exclusion rules for every scan of a workspace folder: text search,
+//! the trigram index, file watcher events, and repository stats. A path is
+//! skipped when it lies in a dependency, build output, or tool cache directory
+//! (`node_modules`, `dist`, `target`, ...) or matches one of the folder's exclude
+//! patterns. Include patterns override both, e.g. for a project that commits its
+//! `dist` folder. Walks also honor `.gitignore` unless the folder turns it off.
+//!
+//! Patterns use `.gitignore` syntax. The frontend sets them per workspace folder
+//! from its project settings; folders without settings use the built-in list.
+
+use fluxel_macros::fluxel_command;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{DirEntry, WalkBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Directories holding dependencies, build output, or tool caches, skipped at any depth
+const HEAVY_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "bower_components",
+    "jspm_packages",
+    ".yarn",
+    ".pnpm-store",
+    "dist",
+    "build",
+    "out",
+    "target",
+    ".next",
+    ".nuxt",
+    ".svelte-kit",
+    ".turbo",
+    ".parcel-cache",
+    ".angular",
+    ".cache",
+    "coverage",
+    "__pycache__",
+    ".venv",
+    ".gradle",
+    "Pods",
+];
+
+/// Exclusion settings of one workspace folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PathFilterConfig {
+    /// Patterns to skip on top of the built-in directories
+    pub exclude: Vec<String>,
+    /// Patterns to scan even though a built-in directory or `exclude` covers them
+    pub include: Vec<String>,
+    /// Skip what `.gitignore` files ignore when walking the folder
+    pub respect_gitignore: bool,
+}
+
+impl Default for PathFilterConfig {
+    fn default() -> Self {
+        Self {
+            exclude: Vec::new(),
+            include: Vec::new(),
+            respect_gitignore: true,
+        }
+    }
+}
+
+/// Compiled exclusion rules for one workspace folder
+pub struct PathFilter {
+    root: PathBuf,
+    /// Built-in directories and exclude patterns, followed by negated include patterns
+    rules: Gitignore,
+    respect_gitignore: bool,
+}
+
+impl PathFilter {
+    /// Compile `config` for `root`. Invalid patterns are logged and skipped.
+    pub fn new(root: &Path, config: &PathFilterConfig) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let heavy = HEAVY_DIRS.iter().map(|dir| format!("{}/", dir));
+        let exclude = config
+            .exclude
+            .iter()
+            .map(|pattern| pattern.trim().to_string());
+        let include = config
+            .include
+            .iter()
+            .map(|pattern| format!("!{}", pattern.trim().trim_start_matches('!')));
+        for line in heavy.chain(exclude).chain(include) {
+            if line.is_empty() || line == "!" {
+                continue;
+            }
+            if let Err(e) = builder.add_line(None, &line) {
+                println!("[PathFilter] Skipping invalid pattern '{}': {}", line, e);
+            }
+        }
+        let rules = builder.build().unwrap_or_else(|e| {
+            println!("[PathFilter] Failed to build rules for {:?}: {}", root, e);
+            Gitignore::empty()
+        });
+        Self {
+            root: root.to_path_buf(),
+            rules,
+            respect_gitignore: config.respect_gitignore,
+        }
+    }
+
+    /// The workspace folder the filter was built for
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether `path`, absolute or relative to the root, is excluded by the built-in
+    /// directories or the folder's patterns. `.gitignore` is not consulted; paths
+    /// outside the root are never excluded.
+    pub fn excludes(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.root) {
+                Ok(relative) => relative,
+                Err(_) => return false,
+            }
+        } else {
+            path
+        };
+        !relative.as_os_str().is_empty()
+            && self
+                .rules
+                .matched_path_or_any_parents(relative, is_dir)
+                .is_ignore()
+    }
+
+    /// Walker over `dir` (the root or a directory below it) that prunes excluded
+    /// directories and, unless turned off, applies `.gitignore` files
+    pub fn walk_builder(self: &Arc<Self>, dir: &Path) -> WalkBuilder {
+        self.walk_builder_with(dir, |_| true)
+    }
+
+    /// Like [`PathFilter::walk_builder`], additionally pruning entries `keep` rejects
+    pub fn walk_builder_with<F>(self: &Arc<Self>, dir: &Path, keep: F) -> WalkBuilder
+    where
+        F: Fn(&DirEntry) -> bool + Send + Sync + 'static,
+    {
+        let mut builder = WalkBuilder::new(dir);
+        builder.hidden(false);
+        builder.git_ignore(self.respect_gitignore);
+        builder.git_exclude(self.respect_gitignore);
+        builder.require_git(false);
+        let filter = Arc::clone(self);
+        builder.filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !filter.excludes(entry.path(), is_dir) && keep(entry)
+        });
+        builder
+    }
+}
+
+#[derive(Default)]
+struct PathFilterInner {
+    /// Settings of configured folders, keyed by canonical root
+    configs: HashMap<PathBuf, PathFilterConfig>,
+    /// Filters built so far, keyed by root as given
+    filters: HashMap<PathBuf, Arc<PathFilter>>,
+}
+
+/// Exclusion settings and compiled filters of every workspace folder
+#[derive(Clone, Default)]
+pub struct PathFilterState {
+    inner: Arc<Mutex<PathFilterInner>>,
+}
+
+impl PathFilterState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The filter for a workspace folder, from its settings or the defaults
+    pub fn filter_for(&self, root: &Path) -> Arc<PathFilter> {
+        if let Some(filter) = self.inner.lock().unwrap().filters.get(root) {
+            return Arc::clone(filter);
+        }
+        let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut inner = self.inner.lock().unwrap();
+        let config = inner.configs.get(&canonical).cloned().unwrap_or_default();
+        let filter = Arc::new(PathFilter::new(root, &config));
+        inner
+            .filters
+            .insert(root.to_path_buf(), Arc::clone(&filter));
+        filter
+    }
+
+    /// Replace the settings of a workspace folder
+    pub fn set_config(&self, root: &Path, config: PathFilterConfig) {
+        let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut inner = self.inner.lock().unwrap();
+        if inner.configs.get(&canonical) == Some(&config) {
+            return;
+        }
+        inner.configs.insert(canonical, config);
+        // Filters of other roots below this folder are rebuilt too
+        inner.filters.clear();
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Set the exclusion patterns of a workspace folder, used by search, indexing,
+/// file watchers, and repository stats
+///
+/// # Arguments
+/// * `workspace_root` - The workspace folder
+/// * `config` - Exclude and include patterns, and whether `.gitignore` applies
+#[fluxel_command]
+pub fn set_path_filter_config(
+    workspace_root: String,
+    config: PathFilterConfig,
+    state: State<'_, PathFilterState>,
+) {
+    state.set_config(Path::new(&workspace_root), config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_excludes_and_walks() {
+        let root = std::env::temp_dir().join(format!(
+            "fluxel-path-filter-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        for file in [
+            "src/app.ts",
+            "src/app.snap",
+            "dist/app.js",
+            "node_modules/dep/index.js",
+            "packages/web/node_modules/dep/index.js",
+            "logs/today.log",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "logs/\n").unwrap();
+
+        let state = PathFilterState::new();
+        let defaults = state.filter_for(&root);
+        assert!(defaults.excludes(&root.join("dist/app.js"), false));
+        assert!(defaults.excludes(Path::new("packages/web/node_modules"), true));
+        assert!(!defaults.excludes(Path::new("src/app.ts"), false));
+        assert!(!defaults.excludes(Path::new("/elsewhere/dist/app.js"), false));
+
+        state.set_config(
+            &root,
+            PathFilterConfig {
+                exclude: vec!["*.snap".to_string()],
+                include: vec!["dist".to_string()],
+                respect_gitignore: true,
+            },
+        );
+        let filter = state.filter_for(&root);
+        let mut walked: Vec<String> = filter
+            .walk_builder(&root)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| {
+                let relative = entry.path().strip_prefix(&root).unwrap();
+                relative.to_string_lossy().replace('\\', "/")
+            })
+            .collect();
+        walked.sort();
+        assert_eq!(walked, [".gitignore", "dist/app.js", "src/app.ts"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles discovery and loading of community plugins from the filesystem.
 //! Community plugins are located in ~/.fluxel/plugins/
 
+use fluxel_macros::fluxel_command;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -57,7 +58,7 @@ fn default_main() -> String {
 /// Discover community plugins in the given plugins directory
 ///
 /// Scans the directory for subdirectories containing a plugin.json manifest.
-#[tauri::command]
+#[fluxel_command]
 pub async fn discover_community_plugins(
     plugins_path: String,
 ) -> Result<Vec<CommunityPluginMeta>, String> {
@@ -150,7 +151,7 @@ fn load_plugin_manifest(
 }
 
 /// Get the default community plugins path for the current user
-#[tauri::command]
+#[fluxel_command]
 pub fn get_community_plugins_path() -> Result<String, String> {
     let home = dirs::home_dir().ok_or("Failed to get home directory")?;
     let plugins_path = home.join(".fluxel").join("plugins");
@@ -158,7 +159,7 @@ pub fn get_community_plugins_path() -> Result<String, String> {
 }
 
 /// Check if a plugin directory exists and is valid
-#[tauri::command]
+#[fluxel_command]
 pub fn validate_plugin_directory(path: String) -> bool {
     let plugin_dir = PathBuf::from(&path);
     if !plugin_dir.is_dir() {
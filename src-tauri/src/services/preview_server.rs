@@ -0,0 +1,501 @@
+//! Preview Server Service
+//!
+//! A small managed static file server used by "Open Preview". It binds to a free
+//! localhost port, serves a chosen directory with correct MIME types, can fall back
+//! to `index.html` for single-page apps, and optionally injects a live-reload client
+//! that is notified through the shared file system watcher.
+
+use crate::services::fs_watcher::FsWatcherService;
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::State;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot, Mutex};
+
+/// Maximum size of an HTTP request head we are willing to buffer
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// Path of the server-sent events endpoint used by the live-reload client
+const LIVE_RELOAD_PATH: &str = "/__fluxel_live_reload";
+
+/// Script injected into HTML responses when live-reload is enabled
+const LIVE_RELOAD_SNIPPET: &str = "<script>(function(){var s=new EventSource('/__fluxel_live_reload');s.onmessage=function(e){if(e.data==='reload'){location.reload();}};})();</script>";
+
+static NEXT_SERVER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Information about a running preview server
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewServerInfo {
+    pub id: String,
+    pub url: String,
+    pub port: u16,
+    pub root_dir: String,
+    pub spa_fallback: bool,
+    pub live_reload: bool,
+}
+
+struct RunningServer {
+    info: PreviewServerInfo,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+/// State holding all running preview servers
+#[derive(Default)]
+pub struct PreviewServerState {
+    servers: Mutex<HashMap<String, RunningServer>>,
+}
+
+impl PreviewServerState {
+    pub fn new() -> Self {
+        Self {
+            servers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Stop every running server (used on app exit)
+    pub async fn stop_all(&self) {
+        let mut servers = self.servers.lock().await;
+        for (_, mut server) in servers.drain() {
+            if let Some(tx) = server.shutdown.take() {
+                let _ = tx.send(());
+            }
+        }
+    }
+}
+
+/// Per-server configuration shared with connection handlers
+struct ServeConfig {
+    root: PathBuf,
+    spa_fallback: bool,
+    live_reload: bool,
+    reload_tx: broadcast::Sender<()>,
+}
+
+/// Look up the MIME type for a file extension
+pub(crate) fn mime_type_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" | "cjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json; charset=utf-8",
+        "webmanifest" => "application/manifest+json",
+        "txt" | "md" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Decode `%XX` escapes in a URL path
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(value) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(value);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Map a request path to a file below the server root.
+/// Returns `None` for paths that try to escape the root.
+fn resolve_request_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let path_only = request_path.split(['?', '#']).next().unwrap_or("/");
+    let decoded = percent_decode(path_only);
+
+    let mut resolved = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            // Reject parent traversal, absolute paths and drive prefixes
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Inject the live-reload client into an HTML document
+fn inject_live_reload(html: &str) -> String {
+    if let Some(pos) = html.rfind("</body>") {
+        let mut out = String::with_capacity(html.len() + LIVE_RELOAD_SNIPPET.len());
+        out.push_str(&html[..pos]);
+        out.push_str(LIVE_RELOAD_SNIPPET);
+        out.push_str(&html[pos..]);
+        out
+    } else {
+        format!("{}{}", html, LIVE_RELOAD_SNIPPET)
+    }
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    include_body: bool,
+) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    if include_body {
+        stream.write_all(body).await?;
+    }
+    stream.flush().await
+}
+
+/// Hold a server-sent events connection open and push reload notifications
+async fn serve_live_reload(
+    mut stream: TcpStream,
+    mut reload_rx: broadcast::Receiver<()>,
+) -> std::io::Result<()> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+    stream.flush().await?;
+
+    loop {
+        match reload_rx.recv().await {
+            Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                stream.write_all(b"data: reload\n\n").await?;
+                stream.flush().await?;
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, config: Arc<ServeConfig>) -> std::io::Result<()> {
+    // Read the request head
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+        if buf.len() > MAX_REQUEST_HEAD {
+            return write_response(
+                &mut stream,
+                "431 Request Header Fields Too Large",
+                "text/plain",
+                b"",
+                false,
+            )
+            .await;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut parts = head.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("/");
+
+    let include_body = match method {
+        "GET" => true,
+        "HEAD" => false,
+        _ => {
+            return write_response(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain; charset=utf-8",
+                b"Method Not Allowed",
+                true,
+            )
+            .await;
+        }
+    };
+
+    if config.live_reload && target.starts_with(LIVE_RELOAD_PATH) {
+        return serve_live_reload(stream, config.reload_tx.subscribe()).await;
+    }
+
+    let mut file_path = match resolve_request_path(&config.root, target) {
+        Some(path) => path,
+        None => {
+            return write_response(
+                &mut stream,
+                "403 Forbidden",
+                "text/plain; charset=utf-8",
+                b"Forbidden",
+                include_body,
+            )
+            .await;
+        }
+    };
+
+    if file_path.is_dir() {
+        file_path = file_path.join("index.html");
+    }
+
+    // SPA fallback: unknown extensionless routes render the root index.html
+    if !file_path.is_file() && config.spa_fallback && file_path.extension().is_none() {
+        file_path = config.root.join("index.html");
+    }
+
+    let contents = match tokio::fs::read(&file_path).await {
+        Ok(contents) => contents,
+        Err(_) => {
+            return write_response(
+                &mut stream,
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                b"Not Found",
+                include_body,
+            )
+            .await;
+        }
+    };
+
+    let content_type = mime_type_for(&file_path);
+    if config.live_reload && content_type.starts_with("text/html") {
+        let html = inject_live_reload(&String::from_utf8_lossy(&contents));
+        write_response(
+            &mut stream,
+            "200 OK",
+            content_type,
+            html.as_bytes(),
+            include_body,
+        )
+        .await
+    } else {
+        write_response(&mut stream, "200 OK", content_type, &contents, include_body).await
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Start a static preview server for a directory.
+///
+/// # Arguments
+/// * `root_dir` - Directory to serve (e.g., `dist/` or the workspace root)
+/// * `port` - Optional fixed port; a free port is picked when omitted
+/// * `spa_fallback` - Serve `index.html` for unknown extensionless routes
+/// * `live_reload` - Inject a reload client and reload on file changes
+#[fluxel_command]
+pub async fn start_preview_server(
+    root_dir: String,
+    port: Option<u16>,
+    spa_fallback: Option<bool>,
+    live_reload: Option<bool>,
+    state: State<'_, PreviewServerState>,
+    watcher: State<'_, FsWatcherService>,
+) -> Result<PreviewServerInfo, String> {
+    let root = PathBuf::from(&root_dir)
+        .canonicalize()
+        .map_err(|e| format!("Preview root does not exist: {} ({})", root_dir, e))?;
+    if !root.is_dir() {
+        return Err(format!("Preview root is not a directory: {}", root_dir));
+    }
+
+    let spa_fallback = spa_fallback.unwrap_or(false);
+    let live_reload = live_reload.unwrap_or(false);
+
+    let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("Failed to bind preview server: {}", e))?;
+    let bound_port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read preview server address: {}", e))?
+        .port();
+
+    let (reload_tx, _) = broadcast::channel(16);
+    let subscription = if live_reload {
+        Some(watcher.subscribe(&root)?)
+    } else {
+        None
+    };
+
+    let config = Arc::new(ServeConfig {
+        root: root.clone(),
+        spa_fallback,
+        live_reload,
+        reload_tx: reload_tx.clone(),
+    });
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+    tokio::spawn(async move {
+        // Forward watcher events into reload notifications for connected clients
+        let reload_task = subscription.map(|mut subscription| {
+            tokio::spawn(async move {
+                while subscription.recv().await.is_some() {
+                    let _ = reload_tx.send(());
+                }
+            })
+        });
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        let config = Arc::clone(&config);
+                        tokio::spawn(async move {
+                            let _ = handle_connection(stream, config).await;
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(task) = reload_task {
+            task.abort();
+        }
+        println!("[PreviewServer] Stopped server on port {}", bound_port);
+    });
+
+    let id = format!("preview_{}", NEXT_SERVER_ID.fetch_add(1, Ordering::Relaxed));
+    let info = PreviewServerInfo {
+        id: id.clone(),
+        url: format!("http://127.0.0.1:{}/", bound_port),
+        port: bound_port,
+        root_dir: root.to_string_lossy().replace('\\', "/"),
+        spa_fallback,
+        live_reload,
+    };
+
+    println!(
+        "[PreviewServer] Serving {:?} at {}",
+        info.root_dir, info.url
+    );
+
+    state.servers.lock().await.insert(
+        id,
+        RunningServer {
+            info: info.clone(),
+            shutdown: Some(shutdown_tx),
+        },
+    );
+
+    Ok(info)
+}
+
+/// Stop a running preview server
+#[fluxel_command]
+pub async fn stop_preview_server(
+    id: String,
+    state: State<'_, PreviewServerState>,
+) -> Result<(), String> {
+    let mut server = state
+        .servers
+        .lock()
+        .await
+        .remove(&id)
+        .ok_or_else(|| format!("Preview server not found: {}", id))?;
+
+    if let Some(tx) = server.shutdown.take() {
+        let _ = tx.send(());
+    }
+    Ok(())
+}
+
+/// List all running preview servers
+#[fluxel_command]
+pub async fn list_preview_servers(
+    state: State<'_, PreviewServerState>,
+) -> Result<Vec<PreviewServerInfo>, String> {
+    let servers = state.servers.lock().await;
+    Ok(servers.values().map(|s| s.info.clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_paths_inside_root() {
+        let root = Path::new("/srv/site");
+        assert_eq!(
+            resolve_request_path(root, "/assets/app.js?v=3"),
+            Some(PathBuf::from("/srv/site/assets/app.js"))
+        );
+        assert_eq!(
+            resolve_request_path(root, "/my%20file.html"),
+            Some(PathBuf::from("/srv/site/my file.html"))
+        );
+    }
+
+    #[test]
+    fn rejects_parent_traversal() {
+        let root = Path::new("/srv/site");
+        assert_eq!(resolve_request_path(root, "/../secret.txt"), None);
+        assert_eq!(resolve_request_path(root, "/a/%2e%2e/%2e%2e/etc"), None);
+    }
+
+    #[test]
+    fn injects_live_reload_before_body_close() {
+        let html = "<html><body><h1>Hi</h1></body></html>";
+        let injected = inject_live_reload(html);
+        assert!(injected.contains("__fluxel_live_reload"));
+        assert!(injected.ends_with("</script></body></html>"));
+    }
+
+    #[test]
+    fn maps_common_mime_types() {
+        assert_eq!(
+            mime_type_for(Path::new("index.HTML")),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(mime_type_for(Path::new("app.wasm")), "application/wasm");
+        assert_eq!(
+            mime_type_for(Path::new("unknown.bin")),
+            "application/octet-stream"
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! Manages the lifecycle of spawned child processes.
 //! This ensures dev servers and other child processes are killed when the app exits.
 
+use fluxel_macros::fluxel_command;
 use std::collections::HashSet;
 use std::sync::Mutex;
 use tauri::State;
@@ -52,6 +53,13 @@ impl ProcessManager {
         }
     }
 
+    /// PIDs currently being tracked, in ascending order
+    pub fn tracked_pids(&self) -> Vec<u32> {
+        let mut pids: Vec<u32> = self.tracked_pids.lock().unwrap().iter().copied().collect();
+        pids.sort_unstable();
+        pids
+    }
+
     /// Kill a specific process by PID
     pub fn kill_pid(&self, pid: u32) {
         kill_process_tree(pid);
@@ -127,24 +135,70 @@ fn kill_process_tree(pid: u32) {
     }
 }
 
+/// Resident memory of a process in bytes, or `None` if it can't be read
+pub fn process_memory_bytes(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let kilobytes = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kilobytes * 1024)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // "csharp-ls.exe","1234","Console","1","123,456 K"
+        let output = std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let memory = stdout.lines().next()?.rsplit("\",\"").next()?;
+        let kilobytes: String = memory.chars().filter(char::is_ascii_digit).collect();
+        kilobytes
+            .parse::<u64>()
+            .ok()
+            .map(|kilobytes| kilobytes * 1024)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "rss=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let kilobytes = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kilobytes * 1024)
+    }
+}
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
 
 /// Register a child process PID for cleanup on app exit
-#[tauri::command]
+#[fluxel_command]
 pub fn register_child_process(pid: u32, state: State<'_, ProcessManager>) {
     state.register(pid);
 }
 
 /// Unregister a child process PID (call when process exits normally)
-#[tauri::command]
+#[fluxel_command]
 pub fn unregister_child_process(pid: u32, state: State<'_, ProcessManager>) {
     state.unregister(pid);
 }
 
 /// Kill all tracked child processes (can be called from frontend on cleanup)
-#[tauri::command]
+#[fluxel_command]
 pub fn kill_all_child_processes(state: State<'_, ProcessManager>) {
     state.kill_all();
 }
@@ -0,0 +1,358 @@
+//! Project Clean
+//!
+//! Removes build output and tool caches for the ecosystems found in a
+//! workspace: `bin/` and `obj/` next to .NET projects (after `dotnet clean`),
+//! framework and bundler caches next to package.json, and Cargo `target/`
+//! directories. A dry run reports what would go and how much space it frees.
+
+use crate::languages::lsp_manager::{find_project_file, find_solution_file};
+use fluxel_macros::fluxel_command;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use walkdir::WalkDir;
+
+/// How deep project manifests are searched for
+const MANIFEST_SEARCH_DEPTH: usize = 4;
+
+/// Cache and build directories next to a package.json
+const NODE_CACHE_DIRS: &[&str] = &[
+    "node_modules/.cache",
+    "node_modules/.vite",
+    ".turbo",
+    ".next",
+    ".nuxt",
+    ".svelte-kit",
+    ".parcel-cache",
+    ".angular/cache",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanTarget {
+    Dotnet,
+    Node,
+    Cargo,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanOptions {
+    /// Ecosystems to clean; every detected one when omitted
+    pub targets: Option<Vec<CleanTarget>>,
+    /// Only report what would be removed
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanEntry {
+    pub target: CleanTarget,
+    /// Absolute path of the directory
+    pub path: String,
+    pub bytes: u64,
+    /// Whether the directory was removed; always false in a dry run
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub entries: Vec<CleanEntry>,
+    /// Space taken by the entries, freed unless this is a dry run
+    pub total_bytes: u64,
+    /// Output of `dotnet clean`, when it ran
+    pub dotnet_clean_output: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// Manifests found in the workspace, by ecosystem
+#[derive(Debug, Default)]
+struct Manifests {
+    dotnet_projects: Vec<PathBuf>,
+    package_dirs: Vec<PathBuf>,
+    cargo_dirs: Vec<PathBuf>,
+}
+
+fn find_manifests(root: &Path) -> Manifests {
+    let mut manifests = Manifests::default();
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(MANIFEST_SEARCH_DEPTH))
+        .filter_entry(|entry| {
+            !matches!(
+                entry.file_name().to_str(),
+                Some("node_modules" | "bin" | "obj" | "target")
+            )
+        })
+        .build();
+
+    for entry in walker.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Some(dir) = path.parent() else {
+            continue;
+        };
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some("package.json") => manifests.package_dirs.push(dir.to_path_buf()),
+            Some("Cargo.toml") => manifests.cargo_dirs.push(dir.to_path_buf()),
+            Some(name) if name.ends_with(".csproj") || name.ends_with(".fsproj") => {
+                manifests.dotnet_projects.push(path.to_path_buf())
+            }
+            _ => {}
+        }
+    }
+    manifests
+}
+
+/// Directories to remove for each detected target
+fn clean_candidates(root: &Path, manifests: &Manifests) -> Vec<(CleanTarget, PathBuf)> {
+    let mut candidates: Vec<(CleanTarget, PathBuf)> = Vec::new();
+    for project in &manifests.dotnet_projects {
+        if let Some(dir) = project.parent() {
+            for output in ["bin", "obj"] {
+                candidates.push((CleanTarget::Dotnet, dir.join(output)));
+            }
+        }
+    }
+    for dir in &manifests.package_dirs {
+        for cache in NODE_CACHE_DIRS {
+            candidates.push((CleanTarget::Node, dir.join(cache)));
+        }
+    }
+    for dir in &manifests.cargo_dirs {
+        candidates.push((CleanTarget::Cargo, dir.join("target")));
+    }
+
+    // Only real directories inside the workspace; a symlinked cache must not
+    // take its target with it
+    let mut seen = BTreeSet::new();
+    candidates
+        .into_iter()
+        .filter(|(_, path)| {
+            path.symlink_metadata().is_ok_and(|meta| meta.is_dir())
+                && path.starts_with(root)
+                && seen.insert(path.clone())
+        })
+        .collect()
+}
+
+fn directory_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|meta| meta.is_file())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Find and, unless `dry_run`, remove the directories of `targets`
+fn clean_directories(
+    root: &Path,
+    manifests: &Manifests,
+    targets: &[CleanTarget],
+    dry_run: bool,
+    errors: &mut Vec<String>,
+) -> Vec<CleanEntry> {
+    clean_candidates(root, manifests)
+        .into_iter()
+        .filter(|(target, _)| targets.contains(target))
+        .map(|(target, path)| {
+            let bytes = directory_size(&path);
+            let removed = !dry_run
+                && match std::fs::remove_dir_all(&path) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        errors.push(format!("Failed to remove {}: {}", path.display(), e));
+                        false
+                    }
+                };
+            CleanEntry {
+                target,
+                path: path.to_string_lossy().replace('\\', "/"),
+                bytes,
+                removed,
+            }
+        })
+        .collect()
+}
+
+/// Run `dotnet clean` on the workspace's solution or project
+async fn dotnet_clean(root: &Path) -> Result<String, String> {
+    let target = find_solution_file(root)
+        .or_else(|| find_project_file(root))
+        .ok_or("No solution or project file found")?;
+
+    let mut cmd = Command::new("dotnet");
+    cmd.arg("clean").arg(&target).current_dir(root);
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        cmd.creation_flags(0x08000000);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run dotnet clean: {}", e))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if output.status.success() {
+        Ok(text)
+    } else {
+        Err(format!("dotnet clean failed:\n{}", text))
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Remove build output and caches from a workspace
+///
+/// # Arguments
+/// * `workspace_root` - Workspace to clean
+/// * `options` - Ecosystems to clean and whether this is a dry run
+#[fluxel_command]
+pub async fn clean_project(
+    workspace_root: String,
+    options: Option<CleanOptions>,
+) -> Result<CleanReport, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err(format!(
+            "Workspace root is not a directory or does not exist: {}",
+            workspace_root
+        ));
+    }
+    let options = options.unwrap_or_default();
+
+    let scan_root = root.clone();
+    let manifests = tauri::async_runtime::spawn_blocking(move || find_manifests(&scan_root))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let targets: Vec<CleanTarget> = options.targets.unwrap_or_else(|| {
+        let detected = [
+            (CleanTarget::Dotnet, !manifests.dotnet_projects.is_empty()),
+            (CleanTarget::Node, !manifests.package_dirs.is_empty()),
+            (CleanTarget::Cargo, !manifests.cargo_dirs.is_empty()),
+        ];
+        detected
+            .into_iter()
+            .filter(|(_, present)| *present)
+            .map(|(target, _)| target)
+            .collect()
+    });
+
+    let mut errors = Vec::new();
+    let mut dotnet_clean_output = None;
+    if !options.dry_run
+        && targets.contains(&CleanTarget::Dotnet)
+        && !manifests.dotnet_projects.is_empty()
+    {
+        // Lets MSBuild remove outputs it tracks outside bin/obj first
+        match dotnet_clean(&root).await {
+            Ok(output) => dotnet_clean_output = Some(output),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let dry_run = options.dry_run;
+    let (entries, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let entries = clean_directories(&root, &manifests, &targets, dry_run, &mut errors);
+        (entries, errors)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let total_bytes = entries.iter().map(|entry| entry.bytes).sum();
+    println!(
+        "[Clean] {} {} directories ({} bytes) in {}",
+        if dry_run { "Found" } else { "Removed" },
+        entries.len(),
+        total_bytes,
+        workspace_root
+    );
+
+    Ok(CleanReport {
+        dry_run,
+        entries,
+        total_bytes,
+        dotnet_clean_output,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn cleans_detected_ecosystems() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-project-clean-{}", nanos));
+        let write = |relative: &str, content: &str| {
+            let path = root.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("App/App.csproj", "<Project />");
+        write("App/bin/Debug/App.dll", "12345");
+        write("App/obj/project.assets.json", "{}");
+        write("web/package.json", "{}");
+        write("web/node_modules/.cache/babel/x.json", "123");
+        write("web/node_modules/react/index.js", "keep");
+        write("web/.turbo/log", "1");
+        write("Cargo.toml", "[workspace]");
+        write("target/debug/app", "1234567890");
+
+        let manifests = find_manifests(&root);
+        let mut errors = Vec::new();
+        let all = [CleanTarget::Dotnet, CleanTarget::Node, CleanTarget::Cargo];
+
+        let dry = clean_directories(&root, &manifests, &all, true, &mut errors);
+        let mut found: Vec<(String, u64)> = dry
+            .iter()
+            .map(|entry| {
+                let path = Path::new(&entry.path).strip_prefix(&root).unwrap();
+                (path.to_string_lossy().replace('\\', "/"), entry.bytes)
+            })
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("App/bin".to_string(), 5),
+                ("App/obj".to_string(), 2),
+                ("target".to_string(), 10),
+                ("web/.turbo".to_string(), 1),
+                ("web/node_modules/.cache".to_string(), 3),
+            ]
+        );
+        assert!(root.join("App/bin").exists());
+
+        let cleaned =
+            clean_directories(&root, &manifests, &[CleanTarget::Node], false, &mut errors);
+        assert!(errors.is_empty());
+        assert_eq!(cleaned.len(), 2);
+        assert!(cleaned.iter().all(|entry| entry.removed));
+        assert!(!root.join("web/.turbo").exists());
+        assert!(root.join("web/node_modules/react/index.js").exists());
+        assert!(root.join("App/bin").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
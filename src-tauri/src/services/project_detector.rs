@@ -4,6 +4,8 @@
 //! initialize the right language services and tooling (C#/.NET, JS/TS with Bun, etc.).
 
 use crate::languages::lsp_manager::{find_project_file, find_solution_file};
+use crate::services::wsl::{wsl_workspace, WslWorkspace};
+use fluxel_macros::fluxel_command;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tauri::async_runtime::spawn_blocking;
@@ -48,9 +50,12 @@ pub struct ProjectProfile {
     pub node: NodeInfo,
     /// Suggested build system for "auto" mode.
     pub build_system_hint: Option<String>,
+    /// Set when the workspace lives inside a WSL distro; toolchains are then
+    /// probed and run inside that distro.
+    pub wsl: Option<WslWorkspace>,
 }
 
-fn detect_node_info(root: &Path) -> NodeInfo {
+pub(crate) fn detect_node_info(root: &Path) -> NodeInfo {
     let has_package_json = root.join("package.json").is_file();
     let has_tsconfig = root.join("tsconfig.json").is_file();
     let has_jsconfig = root.join("jsconfig.json").is_file();
@@ -116,7 +121,48 @@ fn build_system_hint(kind: &ProjectKind, node: &NodeInfo) -> Option<String> {
     }
 }
 
-#[tauri::command]
+/// Detect the profile of the project at `root`.
+pub(crate) fn detect_profile(root: &Path) -> ProjectProfile {
+    #[cfg(feature = "profiling")]
+    let _blocking_span =
+        tracing::span!(tracing::Level::INFO, "project_detection_blocking").entered();
+
+    // Use rayon for CPU-bound parallel file system checks
+    // This is more appropriate than async since we're doing synchronous IO
+    let (dotnet, node) = rayon::join(
+        || {
+            #[cfg(feature = "profiling")]
+            let _dotnet_span =
+                tracing::span!(tracing::Level::DEBUG, "detect_dotnet_info").entered();
+            let result = detect_dotnet_info(root);
+            #[cfg(feature = "profiling")]
+            drop(_dotnet_span);
+            result
+        },
+        || {
+            #[cfg(feature = "profiling")]
+            let _node_span = tracing::span!(tracing::Level::DEBUG, "detect_node_info").entered();
+            let result = detect_node_info(root);
+            #[cfg(feature = "profiling")]
+            drop(_node_span);
+            result
+        },
+    );
+
+    let kind = project_kind(&dotnet, &node);
+    let hint = build_system_hint(&kind, &node);
+
+    ProjectProfile {
+        root_path: root.to_string_lossy().replace('\\', "/"),
+        kind,
+        dotnet,
+        node,
+        build_system_hint: hint,
+        wsl: wsl_workspace(&root.to_string_lossy()),
+    }
+}
+
+#[fluxel_command]
 pub async fn detect_project_profile(
     workspace_root: String,
     trace_parent: Option<String>,
@@ -138,49 +184,7 @@ pub async fn detect_project_profile(
     #[cfg(feature = "profiling")]
     drop(_span); // Drop span before await to ensure Send trait
 
-    spawn_blocking(move || {
-        #[cfg(feature = "profiling")]
-        let _blocking_span =
-            tracing::span!(tracing::Level::INFO, "project_detection_blocking").entered();
-
-        // Clone root for parallel detection
-        let root_for_dotnet = root.clone();
-        let root_for_node = root.clone();
-
-        // Use rayon for CPU-bound parallel file system checks
-        // This is more appropriate than async since we're doing synchronous IO
-        let (dotnet, node) = rayon::join(
-            || {
-                #[cfg(feature = "profiling")]
-                let _dotnet_span =
-                    tracing::span!(tracing::Level::DEBUG, "detect_dotnet_info").entered();
-                let result = detect_dotnet_info(&root_for_dotnet);
-                #[cfg(feature = "profiling")]
-                drop(_dotnet_span);
-                result
-            },
-            || {
-                #[cfg(feature = "profiling")]
-                let _node_span =
-                    tracing::span!(tracing::Level::DEBUG, "detect_node_info").entered();
-                let result = detect_node_info(&root_for_node);
-                #[cfg(feature = "profiling")]
-                drop(_node_span);
-                result
-            },
-        );
-
-        let kind = project_kind(&dotnet, &node);
-        let hint = build_system_hint(&kind, &node);
-
-        Ok(ProjectProfile {
-            root_path: root.to_string_lossy().replace('\\', "/"),
-            kind,
-            dotnet,
-            node,
-            build_system_hint: hint,
-        })
-    })
-    .await
-    .map_err(|e| format!("Failed to detect project: {e}"))?
+    spawn_blocking(move || Ok(detect_profile(&root)))
+        .await
+        .map_err(|e| format!("Failed to detect project: {e}"))?
 }
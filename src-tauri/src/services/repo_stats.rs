@@ -0,0 +1,675 @@
+//! Repository Stats Service
+//!
+//! Computes repository insights for the dashboard: commits per author, file churn
+//! hotspots, and a language breakdown by lines of the files at HEAD.
+//!
+//! Languages are classified by extension (and a few well-known file names) with
+//! linguist-style exclusions: vendored and generated paths and whatever the
+//! workspace's `PathFilter` excludes are skipped, data and prose formats aren't
+//! counted, and the `linguist-vendored`, `linguist-generated`,
+//! `linguist-documentation`, and `linguist-language` attributes in
+//! `.gitattributes` override the defaults. Results are cached per workspace in
+//! `~/.fluxel/cache/repo-stats/<workspace-hash>.json` and reused until HEAD moves.
+
+use crate::services::path_filter::{PathFilter, PathFilterState};
+use crate::services::workspace_store::workspace_file;
+use chrono::{DateTime, NaiveDate};
+use fluxel_macros::fluxel_command;
+use git2::{
+    AttrCheckFlags, AttrValue, Delta, DiffOptions, ObjectType, Repository, Sort, TreeWalkMode,
+    TreeWalkResult,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+/// Commits walked at most; older history is reported as truncated
+const MAX_COMMITS: usize = 10_000;
+/// Hotspots returned unless a limit is given
+const DEFAULT_HOTSPOT_LIMIT: usize = 50;
+/// Hotspots kept in the cache, so later calls can ask for more than the default
+const CACHED_HOTSPOTS: usize = 500;
+/// Blobs larger than this are assumed generated and not counted
+const MAX_BLOB_SIZE: usize = 1024 * 1024;
+/// Cached results kept per workspace (one per `since` value)
+const MAX_CACHED_RESULTS: usize = 8;
+
+/// Directories of checked-in third-party code. Dependency and build output
+/// directories are excluded by the workspace's `PathFilter`.
+const VENDORED_DIRS: &[&str] = &["vendor", "third_party"];
+
+/// Files generated by tools rather than written by people
+const GENERATED_FILES: &[&str] = &[
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Cargo.lock",
+    "composer.lock",
+    "Gemfile.lock",
+    "poetry.lock",
+];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RepoStatsOptions {
+    /// Only count commits at or after this date (`YYYY-MM-DD` or RFC 3339)
+    pub since: Option<String>,
+    /// Maximum hotspots returned (default: 50)
+    pub hotspot_limit: Option<usize>,
+    /// Recompute even if a cached result for the current HEAD exists
+    pub refresh: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorStats {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unix seconds
+    pub first_commit: i64,
+    pub last_commit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChurn {
+    pub path: String,
+    /// Commits that changed the file
+    pub commits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    /// Non-blank lines
+    pub lines: usize,
+    /// Share of all counted lines, 0-100
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoStats {
+    /// HEAD commit the stats describe
+    pub head: String,
+    pub since: Option<String>,
+    pub total_commits: usize,
+    /// Most commits first
+    pub authors: Vec<AuthorStats>,
+    /// Most frequently changed files first
+    pub hotspots: Vec<FileChurn>,
+    /// Most lines first
+    pub languages: Vec<LanguageStats>,
+    /// History went back further than the commit limit
+    pub truncated: bool,
+    /// Unix seconds
+    pub computed_at: u64,
+}
+
+// ============================================================================
+// Language Classification
+// ============================================================================
+
+/// Programming or markup language of `path`; data and prose formats aren't languages here
+fn language_for_path(path: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name {
+        "Dockerfile" => return Some("Dockerfile"),
+        "Makefile" | "GNUmakefile" => return Some("Makefile"),
+        "CMakeLists.txt" => return Some("CMake"),
+        _ => {}
+    }
+    if name.ends_with(".d.ts") {
+        return Some("TypeScript");
+    }
+    let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    let language = match extension.as_str() {
+        "rs" => "Rust",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" => "TSX",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "py" | "pyi" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "cs" => "C#",
+        "fs" | "fsx" => "F#",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "m" => "Objective-C",
+        "swift" => "Swift",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "lua" => "Lua",
+        "dart" => "Dart",
+        "scala" => "Scala",
+        "zig" => "Zig",
+        "ex" | "exs" => "Elixir",
+        "erl" => "Erlang",
+        "hs" => "Haskell",
+        "ml" | "mli" => "OCaml",
+        "r" => "R",
+        "jl" => "Julia",
+        "sh" | "bash" | "zsh" => "Shell",
+        "ps1" | "psm1" => "PowerShell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" => "SCSS",
+        "sass" => "Sass",
+        "less" => "Less",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "astro" => "Astro",
+        "glsl" | "vert" | "frag" => "GLSL",
+        "wgsl" => "WGSL",
+        "hlsl" => "HLSL",
+        _ => return None,
+    };
+    Some(language)
+}
+
+fn is_vendored_dir(name: &str) -> bool {
+    VENDORED_DIRS.contains(&name)
+}
+
+fn is_generated_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    GENERATED_FILES.contains(&name) || name.contains(".min.") || name.ends_with(".map")
+}
+
+/// A boolean gitattribute: `attr`/`attr=true` is true, `-attr`/`attr=false` is false
+fn attr_flag(repo: &Repository, path: &Path, name: &str) -> Option<bool> {
+    let value = repo
+        .get_attr(path, name, AttrCheckFlags::INDEX_THEN_FILE)
+        .ok()?;
+    match AttrValue::from_string(value) {
+        AttrValue::True => Some(true),
+        AttrValue::False => Some(false),
+        AttrValue::String(value) => match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn count_lines(content: &[u8]) -> usize {
+    content
+        .split(|b| *b == b'\n')
+        .filter(|line| line.iter().any(|b| !b.is_ascii_whitespace()))
+        .count()
+}
+
+fn language_breakdown(
+    repo: &Repository,
+    tree: &git2::Tree,
+    filter: &PathFilter,
+) -> Result<Vec<LanguageStats>, String> {
+    let mut totals: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        let Some(name) = entry.name() else {
+            return TreeWalkResult::Skip;
+        };
+        let path = format!("{}{}", dir, name);
+        let attr_path = Path::new(&path);
+
+        if entry.kind() == Some(ObjectType::Tree) {
+            // Vendored directories can be opted back in with `linguist-vendored=false`
+            if (is_vendored_dir(name) || filter.excludes(attr_path, true))
+                && attr_flag(repo, &attr_path.join("_"), "linguist-vendored") != Some(false)
+            {
+                return TreeWalkResult::Skip;
+            }
+            return TreeWalkResult::Ok;
+        }
+        if entry.kind() != Some(ObjectType::Blob) || filter.excludes(attr_path, false) {
+            return TreeWalkResult::Ok;
+        }
+
+        let excluded =
+            |attr: &str, default: bool| attr_flag(repo, attr_path, attr).unwrap_or(default);
+        if excluded("linguist-vendored", false)
+            || excluded("linguist-generated", is_generated_file(&path))
+            || excluded("linguist-documentation", false)
+        {
+            return TreeWalkResult::Ok;
+        }
+
+        let override_language = repo
+            .get_attr(
+                attr_path,
+                "linguist-language",
+                AttrCheckFlags::INDEX_THEN_FILE,
+            )
+            .ok()
+            .flatten()
+            .map(str::to_string);
+        let Some(language) =
+            override_language.or_else(|| language_for_path(&path).map(str::to_string))
+        else {
+            return TreeWalkResult::Ok;
+        };
+
+        let Ok(blob) = repo.find_blob(entry.id()) else {
+            return TreeWalkResult::Ok;
+        };
+        if blob.is_binary() || blob.size() > MAX_BLOB_SIZE {
+            return TreeWalkResult::Ok;
+        }
+        let total = totals.entry(language).or_default();
+        total.0 += 1;
+        total.1 += count_lines(blob.content());
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| e.to_string())?;
+
+    let all_lines: usize = totals.values().map(|(_, lines)| lines).sum();
+    let mut languages: Vec<LanguageStats> = totals
+        .into_iter()
+        .map(|(language, (files, lines))| LanguageStats {
+            language,
+            files,
+            lines,
+            percent: if all_lines == 0 {
+                0.0
+            } else {
+                (lines as f64 * 1000.0 / all_lines as f64).round() / 10.0
+            },
+        })
+        .collect();
+    languages.sort_by(|a, b| {
+        b.lines
+            .cmp(&a.lines)
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    Ok(languages)
+}
+
+// ============================================================================
+// History
+// ============================================================================
+
+/// Parse `YYYY-MM-DD` or an RFC 3339 timestamp into unix seconds
+fn parse_since(since: &str) -> Result<i64, String> {
+    let since = since.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(since) {
+        return Ok(date.timestamp());
+    }
+    NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp())
+        .ok_or_else(|| format!("Invalid date '{}': use YYYY-MM-DD or RFC 3339", since))
+}
+
+/// Compute stats for HEAD of `repo`, counting commits at or after `since` (unix seconds).
+/// Paths `filter` excludes are left out of the hotspots and languages.
+pub fn compute_repo_stats(
+    repo: &Repository,
+    since: Option<i64>,
+    hotspot_limit: usize,
+    filter: &PathFilter,
+) -> Result<RepoStats, String> {
+    let head = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| format!("Repository has no commits: {}", e))?;
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
+    walk.push(head.id()).map_err(|e| e.to_string())?;
+
+    let mut authors: HashMap<String, AuthorStats> = HashMap::new();
+    let mut churn: HashMap<String, FileChurn> = HashMap::new();
+    let mut total_commits = 0;
+    let mut truncated = false;
+
+    for oid in walk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let time = commit.time().seconds();
+        // Time-sorted, so everything after this is older too
+        if since.is_some_and(|since| time < since) {
+            break;
+        }
+        if total_commits == MAX_COMMITS {
+            truncated = true;
+            break;
+        }
+        total_commits += 1;
+
+        let signature = commit.author();
+        let email = signature.email().unwrap_or("").to_lowercase();
+        let author = authors.entry(email.clone()).or_insert_with(|| AuthorStats {
+            // The walk is newest first, so this is the author's latest name
+            name: signature.name().unwrap_or(&email).to_string(),
+            email,
+            commits: 0,
+            additions: 0,
+            deletions: 0,
+            first_commit: time,
+            last_commit: time,
+        });
+        author.commits += 1;
+        author.first_commit = author.first_commit.min(time);
+        author.last_commit = author.last_commit.max(time);
+
+        // Merge commits repeat changes already counted on their branches
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(|e| e.to_string())?),
+            Err(_) => None,
+        };
+        let tree = commit.tree().map_err(|e| e.to_string())?;
+        let mut diff_options = DiffOptions::new();
+        diff_options.context_lines(0);
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_options))
+            .map_err(|e| e.to_string())?;
+
+        // Both callbacks record into the map; binary files only reach the file callback
+        let lines: RefCell<HashMap<String, (usize, usize)>> = RefCell::default();
+        diff.foreach(
+            &mut |delta, _| {
+                if delta.status() != Delta::Unmodified {
+                    if let Some(path) = delta_path(&delta) {
+                        lines.borrow_mut().entry(path).or_default();
+                    }
+                }
+                true
+            },
+            None,
+            None,
+            Some(&mut |delta, _, line| {
+                if let Some(path) = delta_path(&delta) {
+                    let mut lines = lines.borrow_mut();
+                    let counts = lines.entry(path).or_default();
+                    match line.origin() {
+                        '+' => counts.0 += 1,
+                        '-' => counts.1 += 1,
+                        _ => {}
+                    }
+                }
+                true
+            }),
+        )
+        .map_err(|e| e.to_string())?;
+
+        for (path, (additions, deletions)) in lines.into_inner() {
+            author.additions += additions;
+            author.deletions += deletions;
+            let file = churn.entry(path.clone()).or_insert_with(|| FileChurn {
+                path,
+                commits: 0,
+                additions: 0,
+                deletions: 0,
+            });
+            file.commits += 1;
+            file.additions += additions;
+            file.deletions += deletions;
+        }
+    }
+
+    let mut authors: Vec<AuthorStats> = authors.into_values().collect();
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+    let mut hotspots: Vec<FileChurn> = churn
+        .into_values()
+        .filter(|file| !is_generated_file(&file.path))
+        .filter(|file| !filter.excludes(Path::new(&file.path), false))
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| (b.additions + b.deletions).cmp(&(a.additions + a.deletions)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    hotspots.truncate(hotspot_limit);
+
+    let tree = head.tree().map_err(|e| e.to_string())?;
+    Ok(RepoStats {
+        head: head.id().to_string(),
+        since: None,
+        total_commits,
+        authors,
+        hotspots,
+        languages: language_breakdown(repo, &tree, filter)?,
+        truncated,
+        computed_at: now_secs(),
+    })
+}
+
+fn delta_path(delta: &git2::DiffDelta) -> Option<String> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Cache
+// ============================================================================
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StatsCacheFile {
+    /// Keyed by the `since` option (empty for all history)
+    results: BTreeMap<String, RepoStats>,
+}
+
+fn cache_path(workdir: &Path) -> Option<PathBuf> {
+    let dir = dirs::home_dir()?
+        .join(".fluxel")
+        .join("cache")
+        .join("repo-stats");
+    Some(workspace_file(&dir, workdir).0)
+}
+
+fn load_cache(path: &Path) -> StatsCacheFile {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &Path, cache: &StatsCacheFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+    let json = serde_json::to_string(cache).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write repo stats cache: {}", e))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Commit, churn, and language statistics for the repository dashboard
+///
+/// # Arguments
+/// * `root_path` - Repository root
+/// * `options` - `since` date, hotspot limit, and whether to bypass the cache
+#[fluxel_command]
+pub async fn git_repo_stats(
+    root_path: String,
+    options: Option<RepoStatsOptions>,
+    filters: State<'_, PathFilterState>,
+) -> Result<RepoStats, String> {
+    let options = options.unwrap_or_default();
+    let since = options
+        .since
+        .as_deref()
+        .filter(|since| !since.trim().is_empty())
+        .map(parse_since)
+        .transpose()?;
+    let hotspot_limit = options.hotspot_limit.unwrap_or(DEFAULT_HOTSPOT_LIMIT);
+    let filters = filters.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&root_path).map_err(|e| e.to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or("Repository has no working directory")?
+            .to_path_buf();
+        let head = repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+
+        let cache_file = cache_path(&workdir);
+        let mut cache = cache_file.as_deref().map(load_cache).unwrap_or_default();
+        let key = options.since.clone().unwrap_or_default();
+        if !options.refresh {
+            if let Some(cached) = cache.results.get(&key) {
+                if Some(&cached.head) == head.as_ref() {
+                    let mut cached = cached.clone();
+                    cached.hotspots.truncate(hotspot_limit);
+                    return Ok(cached);
+                }
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let filter = filters.filter_for(&workdir);
+        let mut stats =
+            compute_repo_stats(&repo, since, CACHED_HOTSPOTS.max(hotspot_limit), &filter)?;
+        stats.since = options.since.clone();
+        println!(
+            "[RepoStats] {} commits analyzed in {:?}",
+            stats.total_commits,
+            started.elapsed()
+        );
+
+        if let Some(path) = cache_file {
+            cache.results.insert(key, stats.clone());
+            while cache.results.len() > MAX_CACHED_RESULTS {
+                let oldest = cache
+                    .results
+                    .iter()
+                    .min_by_key(|(_, stats)| stats.computed_at)
+                    .map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => cache.results.remove(&oldest),
+                    None => break,
+                };
+            }
+            if let Err(e) = save_cache(&path, &cache) {
+                println!("[RepoStats] {}", e);
+            }
+        }
+        stats.hotspots.truncate(hotspot_limit);
+        Ok(stats)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    fn commit_all(repo: &Repository, who: &str, time: i64, message: &str) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new(
+            who,
+            &format!("{}@example.com", who.to_lowercase()),
+            &git2::Time::new(time, 0),
+        )
+        .unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn computes_authors_churn_and_languages() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-repo-stats-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let repo = Repository::init(&dir).unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write("src/main.rs", "fn main() {\n\n    run();\n}\n");
+        write("web/app.ts", "export const a = 1;\n");
+        write("node_modules/dep/index.js", "module.exports = 1;\n");
+        write("Cargo.lock", "# generated\n");
+        write("README.md", "# Readme\n");
+        commit_all(&repo, "Ada", 1_700_000_000, "init");
+
+        write("src/main.rs", "fn main() {\n\n    run();\n    stop();\n}\n");
+        write(".gitattributes", "web/** linguist-vendored\n");
+        commit_all(&repo, "Grace", 1_700_100_000, "stop");
+        write("src/main.rs", "fn main() {\n    stop();\n}\n");
+        commit_all(&repo, "Grace", 1_700_200_000, "simplify");
+
+        let filter = PathFilter::new(&dir, &Default::default());
+        let stats = compute_repo_stats(&repo, None, 10, &filter).unwrap();
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.authors[0].name, "Grace");
+        assert_eq!(stats.authors[0].commits, 2);
+        assert_eq!(stats.authors[1].additions, 8);
+        assert_eq!(stats.hotspots[0].path, "src/main.rs");
+        assert_eq!(stats.hotspots[0].commits, 3);
+        assert!(stats.hotspots.iter().all(|file| file.path != "Cargo.lock"));
+
+        // node_modules is vendored, web/ is vendored by attribute, README is prose
+        assert_eq!(stats.languages.len(), 1);
+        assert_eq!(stats.languages[0].language, "Rust");
+        assert_eq!(stats.languages[0].lines, 3);
+        assert_eq!(stats.languages[0].percent, 100.0);
+
+        let recent = compute_repo_stats(&repo, Some(1_700_050_000), 10, &filter).unwrap();
+        assert_eq!(recent.total_commits, 2);
+        assert_eq!(recent.authors.len(), 1);
+        assert_eq!(parse_since("2023-11-14").unwrap(), 1_699_920_000);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
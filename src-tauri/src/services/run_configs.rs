@@ -0,0 +1,453 @@
+//! Run Configurations
+//!
+//! Named launch entries stored in `<workspace>/.fluxel/launch.json`. A
+//! configuration names the program to start, its working directory and
+//! environment, and optionally a `preLaunchTask`. Launching first runs that
+//! task and everything it `dependsOn` (tasks from `.fluxel/tasks.json` or any
+//! entry of the runnables catalog), dependencies first, and stops at the first
+//! failure so the program never starts against a broken build.
+
+use crate::commands::terminal::{secret_shell_command, shell_command, spawn_streaming};
+use crate::services::runnables::{collect_runnables, read_tasks};
+use crate::services::secrets::{
+    quote_shell_arg, resolve_secret_references, secret_env, ShellSyntax,
+};
+use crate::services::terminal_output::{stream_output, OutputOptions};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Event carrying every `PreLaunchTaskEvent`
+const TASK_EVENT: &str = "run-config://task";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunConfiguration {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Relative to the workspace root
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Extra environment variables; values may use `${secret:NAME}`
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Task to run first, by label or runnable id
+    #[serde(default)]
+    pub pre_launch_task: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LaunchFile {
+    #[serde(default)]
+    configurations: Vec<RunConfiguration>,
+}
+
+/// A task ready to run, with the names of the tasks it depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PlannedTask {
+    label: String,
+    command: String,
+    args: Vec<String>,
+    cwd: PathBuf,
+    env: BTreeMap<String, String>,
+    depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPhase {
+    Started,
+    Output,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreLaunchTaskEvent {
+    pub configuration: String,
+    pub task: String,
+    pub phase: TaskPhase,
+    /// Output line, on `output`
+    pub data: Option<String>,
+    pub stderr: bool,
+    /// Exit code, on `succeeded` and `failed`
+    pub code: Option<i32>,
+}
+
+/// `command` followed by `args`, each argument quoted so the shell passes it to
+/// the program as one argument, whatever it contains
+fn command_line(command: &str, args: &[String], syntax: ShellSyntax) -> Result<String, String> {
+    let mut line = command.to_string();
+    for arg in args {
+        line.push(' ');
+        line.push_str(&quote_shell_arg(arg, syntax)?);
+    }
+    Ok(line)
+}
+
+fn launch_file_path(root: &Path) -> PathBuf {
+    root.join(".fluxel").join("launch.json")
+}
+
+fn read_configurations(root: &Path) -> Result<Vec<RunConfiguration>, String> {
+    let path = launch_file_path(root);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    let file: LaunchFile =
+        json5::from_str(&content).map_err(|e| format!("Invalid .fluxel/launch.json: {}", e))?;
+    Ok(file.configurations)
+}
+
+/// Every task a pre-launch task can name: `.fluxel/tasks.json` entries by
+/// label, then catalog runnables by id and by label
+fn available_tasks(root: &Path) -> Result<Vec<(Vec<String>, PlannedTask)>, String> {
+    let mut tasks: Vec<(Vec<String>, PlannedTask)> = read_tasks(root)?
+        .into_iter()
+        .map(|task| {
+            let planned = PlannedTask {
+                label: task.label.clone(),
+                command: task.command,
+                args: task.args,
+                cwd: task
+                    .cwd
+                    .as_deref()
+                    .map(|cwd| root.join(cwd))
+                    .unwrap_or_else(|| root.to_path_buf()),
+                env: task.env,
+                depends_on: task.depends_on,
+            };
+            (vec![task.label], planned)
+        })
+        .collect();
+
+    for runnable in collect_runnables(root)? {
+        let planned = PlannedTask {
+            label: runnable.label.clone(),
+            command: runnable.command,
+            args: runnable.args,
+            cwd: PathBuf::from(&runnable.cwd),
+            env: BTreeMap::new(),
+            depends_on: Vec::new(),
+        };
+        tasks.push((vec![runnable.id, runnable.label], planned));
+    }
+    Ok(tasks)
+}
+
+/// `name` and its dependencies, each once, dependencies first
+fn plan_tasks(
+    name: &str,
+    tasks: &[(Vec<String>, PlannedTask)],
+) -> Result<Vec<PlannedTask>, String> {
+    fn visit(
+        name: &str,
+        tasks: &[(Vec<String>, PlannedTask)],
+        stack: &mut Vec<String>,
+        plan: &mut Vec<PlannedTask>,
+    ) -> Result<(), String> {
+        let task = tasks
+            .iter()
+            .find(|(names, _)| names.iter().any(|n| n == name))
+            .map(|(_, task)| task)
+            .ok_or_else(|| format!("Task not found: {}", name))?;
+        if plan.iter().any(|planned| planned.label == task.label) {
+            return Ok(());
+        }
+        if stack.contains(&task.label) {
+            stack.push(task.label.clone());
+            return Err(format!("Task dependency cycle: {}", stack.join(" -> ")));
+        }
+
+        stack.push(task.label.clone());
+        for dependency in &task.depends_on {
+            visit(dependency, tasks, stack, plan)?;
+        }
+        stack.pop();
+        plan.push(task.clone());
+        Ok(())
+    }
+
+    let mut plan = Vec::new();
+    visit(name, tasks, &mut Vec::new(), &mut plan)?;
+    Ok(plan)
+}
+
+/// Values of `env` with secret references resolved
+fn resolve_env(
+    env: &BTreeMap<String, String>,
+    root: &Path,
+) -> Result<BTreeMap<String, String>, String> {
+    env.iter()
+        .map(|(key, value)| Ok((key.clone(), resolve_secret_references(value, root)?)))
+        .collect()
+}
+
+/// Run `task` to completion, streaming its output as task events
+fn run_task<R: Runtime>(
+    app: &AppHandle<R>,
+    configuration: &str,
+    task: &PlannedTask,
+    root: &Path,
+) -> Result<(), String> {
+    let event = |phase: TaskPhase, data: Option<String>, stderr: bool, code: Option<i32>| {
+        PreLaunchTaskEvent {
+            configuration: configuration.to_string(),
+            task: task.label.clone(),
+            phase,
+            data,
+            stderr,
+            code,
+        }
+    };
+    let _ = app.emit(TASK_EVENT, event(TaskPhase::Started, None, false, None));
+
+    let mut cmd = shell_command(&command_line(
+        &task.command,
+        &task.args,
+        ShellSyntax::native(),
+    )?);
+    cmd.current_dir(&task.cwd)
+        .envs(resolve_env(&task.env, root)?)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        cmd.creation_flags(0x08000000);
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start task '{}': {}", task.label, e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to open stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to open stderr")?;
+    let forward = |reader: Box<dyn std::io::Read + Send>, is_stderr: bool| {
+        let app = app.clone();
+        let template = event(TaskPhase::Output, None, is_stderr, None);
+        std::thread::spawn(move || {
+            stream_output(reader, OutputOptions::default(), |line| {
+                let mut output = template.clone();
+                output.data = Some(line.text);
+                let _ = app.emit(TASK_EVENT, output);
+            });
+        })
+    };
+    let streams = [
+        forward(Box::new(stdout), false),
+        forward(Box::new(stderr), true),
+    ];
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for task '{}': {}", task.label, e))?;
+    for stream in streams {
+        let _ = stream.join();
+    }
+
+    let phase = if status.success() {
+        TaskPhase::Succeeded
+    } else {
+        TaskPhase::Failed
+    };
+    let _ = app.emit(TASK_EVENT, event(phase, None, false, status.code()));
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Pre-launch task '{}' failed with exit code {}",
+            task.label,
+            status
+                .code()
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        ))
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the run configurations of a workspace
+///
+/// # Arguments
+/// * `workspace_root` - Workspace whose `.fluxel/launch.json` is read
+#[fluxel_command]
+pub async fn list_run_configurations(
+    workspace_root: String,
+) -> Result<Vec<RunConfiguration>, String> {
+    read_configurations(Path::new(&workspace_root))
+}
+
+/// Replace the run configurations of a workspace
+///
+/// # Arguments
+/// * `workspace_root` - Workspace whose `.fluxel/launch.json` is written
+/// * `configurations` - Configurations to store; names must be unique
+#[fluxel_command]
+pub async fn save_run_configurations(
+    workspace_root: String,
+    configurations: Vec<RunConfiguration>,
+) -> Result<(), String> {
+    for (index, configuration) in configurations.iter().enumerate() {
+        if configuration.name.trim().is_empty() {
+            return Err("Run configurations need a name".to_string());
+        }
+        if configurations[..index]
+            .iter()
+            .any(|other| other.name == configuration.name)
+        {
+            return Err(format!(
+                "Duplicate run configuration name: {}",
+                configuration.name
+            ));
+        }
+    }
+
+    let path = launch_file_path(Path::new(&workspace_root));
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(&LaunchFile { configurations }).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content + "\n").map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Run a configuration's pre-launch tasks, then start its program in a
+/// terminal. Task progress is emitted as `run-config://task` events; the
+/// program's output arrives as terminal events for the returned pid.
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the configuration belongs to
+/// * `name` - Configuration to launch
+#[fluxel_command]
+pub async fn launch_run_configuration<R: Runtime>(
+    app: AppHandle<R>,
+    workspace_root: String,
+    name: String,
+) -> Result<u32, String> {
+    let root = PathBuf::from(&workspace_root);
+    tauri::async_runtime::spawn_blocking(move || {
+        let configuration = read_configurations(&root)?
+            .into_iter()
+            .find(|configuration| configuration.name == name)
+            .ok_or_else(|| format!("Run configuration not found: {}", name))?;
+
+        if let Some(task) = &configuration.pre_launch_task {
+            let plan = plan_tasks(task, &available_tasks(&root)?)?;
+            println!(
+                "[RunConfig] Running {} pre-launch task(s) for {}",
+                plan.len(),
+                name
+            );
+            for task in &plan {
+                run_task(&app, &name, task, &root)?;
+            }
+        }
+
+        let command_line = command_line(
+            &configuration.command,
+            &configuration.args,
+            ShellSyntax::native(),
+        )?;
+        let cwd = configuration
+            .cwd
+            .as_deref()
+            .map(|cwd| root.join(cwd))
+            .unwrap_or_else(|| root.clone());
+
+        // Secrets reach the program through its environment, never the shell line
+        let secrets = secret_env(&command_line, &root, ShellSyntax::native())?;
+        let mut cmd = secret_shell_command(&secrets);
+        cmd.current_dir(&cwd)
+            .envs(resolve_env(&configuration.env, &root)?);
+        println!("[RunConfig] Launching {}: {}", name, command_line);
+
+        let history = Some((
+            workspace_root,
+            command_line,
+            Some(cwd.to_string_lossy().to_string()),
+        ));
+        spawn_streaming(&app, cmd, OutputOptions::default(), history)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(label: &str, depends_on: &[&str]) -> (Vec<String>, PlannedTask) {
+        (
+            vec![format!("task:{}", label), label.to_string()],
+            PlannedTask {
+                label: label.to_string(),
+                command: "echo".to_string(),
+                args: vec![label.to_string()],
+                cwd: PathBuf::from("/work"),
+                env: BTreeMap::new(),
+                depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            },
+        )
+    }
+
+    #[test]
+    fn orders_pre_launch_tasks() {
+        let tasks = vec![
+            task("restore", &[]),
+            task("codegen", &["restore"]),
+            task("build", &["codegen", "task:restore"]),
+            task("loop-a", &["loop-b"]),
+            task("loop-b", &["loop-a"]),
+        ];
+        let labels = |plan: Vec<PlannedTask>| -> Vec<String> {
+            plan.into_iter().map(|task| task.label).collect()
+        };
+
+        assert_eq!(
+            labels(plan_tasks("build", &tasks).unwrap()),
+            vec!["restore", "codegen", "build"]
+        );
+        assert_eq!(
+            plan_tasks("loop-a", &tasks).unwrap_err(),
+            "Task dependency cycle: loop-a -> loop-b -> loop-a"
+        );
+        assert_eq!(
+            plan_tasks("deploy", &tasks).unwrap_err(),
+            "Task not found: deploy"
+        );
+
+        let file: LaunchFile = json5::from_str(
+            r#"{ configurations: [
+                { name: "API", command: "dotnet", args: ["run"], preLaunchTask: "build",
+                  env: { ASPNETCORE_ENVIRONMENT: "Development" } },
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.configurations[0].pre_launch_task.as_deref(),
+            Some("build")
+        );
+        assert_eq!(file.configurations[0].env.len(), 1);
+        assert_eq!(
+            command_line(
+                "node",
+                &["app.js".to_string(), "a b; rm -rf ~".to_string()],
+                ShellSyntax::Posix
+            )
+            .unwrap(),
+            "node 'app.js' 'a b; rm -rf ~'"
+        );
+    }
+}
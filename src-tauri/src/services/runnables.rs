@@ -0,0 +1,606 @@
+//! Runnables Catalog
+//!
+//! One list of everything that can be run in a workspace, for the "Run…"
+//! palette: package.json scripts, tasks from `.fluxel/tasks.json`, .NET
+//! projects (run and test), Cargo targets, and dev-server presets for detected
+//! frameworks. Ids are derived from what a runnable is rather than where it
+//! sits in the list, so they stay stable across refreshes and can be
+//! remembered as "last run".
+
+use crate::services::project_detector::{detect_node_info, PackageManager};
+use fluxel_macros::fluxel_command;
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// How deep .NET projects are searched for
+const DOTNET_SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnableSource {
+    Script,
+    Task,
+    Dotnet,
+    Cargo,
+    Preset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnableGroup {
+    Build,
+    Test,
+    Run,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Runnable {
+    /// Stable identifier, e.g. `script:dev` or `dotnet:test:tests/App.Tests/App.Tests.csproj`
+    pub id: String,
+    pub source: RunnableSource,
+    pub group: RunnableGroup,
+    pub label: String,
+    /// Where the runnable comes from, for display
+    pub detail: Option<String>,
+    pub command: String,
+    pub args: Vec<String>,
+    /// Absolute working directory
+    pub cwd: String,
+}
+
+impl Runnable {
+    fn new(
+        id: String,
+        source: RunnableSource,
+        group: RunnableGroup,
+        label: String,
+        command_line: &[&str],
+        cwd: &Path,
+    ) -> Self {
+        Self {
+            id,
+            source,
+            group,
+            label,
+            detail: None,
+            command: command_line[0].to_string(),
+            args: command_line[1..]
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect(),
+            cwd: path_string(cwd),
+        }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// The command as one line
+    pub(crate) fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    path_string(path.strip_prefix(root).unwrap_or(path))
+}
+
+/// Guess the group of a script or task from its name
+fn group_for_name(name: &str) -> RunnableGroup {
+    let name = name.to_lowercase();
+    let first = name
+        .split([':', '-', '_', ' '])
+        .next()
+        .unwrap_or(name.as_str());
+    match first {
+        "build" | "compile" | "bundle" | "package" => RunnableGroup::Build,
+        "test" | "tests" | "e2e" | "coverage" | "check" | "lint" => RunnableGroup::Test,
+        "dev" | "start" | "serve" | "preview" | "run" | "watch" => RunnableGroup::Run,
+        _ => RunnableGroup::Other,
+    }
+}
+
+// ============================================================================
+// package.json Scripts and Framework Presets
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    #[serde(default, deserialize_with = "ordered_scripts")]
+    scripts: Vec<(String, String)>,
+    #[serde(default)]
+    dependencies: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    dev_dependencies: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Scripts in the order package.json lists them
+fn ordered_scripts<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct ScriptsVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ScriptsVisitor {
+        type Value = Vec<(String, String)>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a map of script names to commands")
+        }
+
+        fn visit_map<A: serde::de::MapAccess<'de>>(
+            self,
+            mut map: A,
+        ) -> Result<Self::Value, A::Error> {
+            let mut scripts = Vec::new();
+            while let Some((name, value)) = map.next_entry::<String, serde_json::Value>()? {
+                if let Some(command) = value.as_str() {
+                    scripts.push((name, command.to_string()));
+                }
+            }
+            Ok(scripts)
+        }
+    }
+
+    deserializer.deserialize_map(ScriptsVisitor)
+}
+
+/// Dev servers offered for a framework dependency: (dependency, label, command)
+const FRAMEWORK_PRESETS: &[(&str, &str, &[&str])] = &[
+    ("next", "Next.js dev server", &["next", "dev"]),
+    ("nuxt", "Nuxt dev server", &["nuxt", "dev"]),
+    ("astro", "Astro dev server", &["astro", "dev"]),
+    ("@sveltejs/kit", "SvelteKit dev server", &["vite", "dev"]),
+    ("@remix-run/dev", "Remix dev server", &["remix", "dev"]),
+    ("@angular/cli", "Angular dev server", &["ng", "serve"]),
+    (
+        "react-scripts",
+        "Create React App dev server",
+        &["react-scripts", "start"],
+    ),
+    ("vite", "Vite dev server", &["vite"]),
+];
+
+pub(crate) fn package_runner(
+    manager: Option<&PackageManager>,
+) -> (&'static str, &'static [&'static str]) {
+    match manager {
+        Some(PackageManager::Pnpm) => ("pnpm", &["exec"]),
+        Some(PackageManager::Yarn) => ("yarn", &[]),
+        Some(PackageManager::Npm) => ("npx", &[]),
+        // Fluxel defaults to bun in several places
+        Some(PackageManager::Bun) | None => ("bunx", &[]),
+    }
+}
+
+fn package_runnables(root: &Path) -> Vec<Runnable> {
+    let Some(package) = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<PackageJson>(&content).ok())
+    else {
+        return Vec::new();
+    };
+
+    let node = detect_node_info(root);
+    let manager = match node.package_manager {
+        Some(PackageManager::Pnpm) => "pnpm",
+        Some(PackageManager::Yarn) => "yarn",
+        Some(PackageManager::Npm) => "npm",
+        Some(PackageManager::Bun) | None => "bun",
+    };
+
+    let mut runnables: Vec<Runnable> = package
+        .scripts
+        .iter()
+        .map(|(name, command)| {
+            Runnable::new(
+                format!("script:{}", name),
+                RunnableSource::Script,
+                group_for_name(name),
+                name.clone(),
+                &[manager, "run", name],
+                root,
+            )
+            .with_detail(command.clone())
+        })
+        .collect();
+
+    // Presets only for frameworks whose dev server no script already starts
+    let (runner, runner_args) = package_runner(node.package_manager.as_ref());
+    for (dependency, label, command) in FRAMEWORK_PRESETS {
+        let present = package.dependencies.contains_key(*dependency)
+            || package.dev_dependencies.contains_key(*dependency);
+        let covered = package.scripts.iter().any(|(_, script)| {
+            let script = script.trim();
+            script == command.join(" ") || script.starts_with(&format!("{} ", command.join(" ")))
+        });
+        if !present || covered {
+            continue;
+        }
+        let command_line: Vec<&str> = std::iter::once(runner)
+            .chain(runner_args.iter().copied())
+            .chain(command.iter().copied())
+            .collect();
+        runnables.push(
+            Runnable::new(
+                format!("preset:{}", dependency),
+                RunnableSource::Preset,
+                RunnableGroup::Run,
+                label.to_string(),
+                &command_line,
+                root,
+            )
+            .with_detail(format!("Detected {}", dependency)),
+        );
+        // One dev server is enough; later entries are the frameworks these build on
+        break;
+    }
+
+    runnables
+}
+
+// ============================================================================
+// Fluxel Tasks
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct TasksFile {
+    #[serde(default)]
+    tasks: Vec<TaskEntry>,
+}
+
+/// Entry of `.fluxel/tasks.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TaskEntry {
+    pub label: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Relative to the workspace root
+    pub cwd: Option<String>,
+    group: Option<RunnableGroup>,
+    /// Tasks that must succeed before this one runs, by label or runnable id
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Extra environment variables
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Tasks from `.fluxel/tasks.json`; empty when the file does not exist
+pub(crate) fn read_tasks(root: &Path) -> Result<Vec<TaskEntry>, String> {
+    let path = root.join(".fluxel").join("tasks.json");
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    // Comments and trailing commas are common in hand-written task files
+    let file: TasksFile =
+        json5::from_str(&content).map_err(|e| format!("Invalid .fluxel/tasks.json: {}", e))?;
+    Ok(file.tasks)
+}
+
+fn task_runnables(root: &Path) -> Result<Vec<Runnable>, String> {
+    Ok(read_tasks(root)?
+        .into_iter()
+        .map(|task| {
+            let cwd = task
+                .cwd
+                .as_deref()
+                .map(|cwd| root.join(cwd))
+                .unwrap_or_else(|| root.to_path_buf());
+            let runnable = Runnable {
+                id: format!("task:{}", task.label),
+                source: RunnableSource::Task,
+                group: task.group.unwrap_or_else(|| group_for_name(&task.label)),
+                label: task.label,
+                detail: None,
+                command: task.command,
+                args: task.args,
+                cwd: path_string(&cwd),
+            };
+            let detail = runnable.command_line();
+            runnable.with_detail(detail)
+        })
+        .collect())
+}
+
+// ============================================================================
+// .NET Projects
+// ============================================================================
+
+pub(crate) fn dotnet_projects(root: &Path) -> Vec<PathBuf> {
+    let mut projects: Vec<PathBuf> = WalkBuilder::new(root)
+        .max_depth(Some(DOTNET_SEARCH_DEPTH))
+        .filter_entry(|entry| !matches!(entry.file_name().to_str(), Some("bin" | "obj")))
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "csproj"))
+        .collect();
+    projects.sort();
+    projects
+}
+
+fn dotnet_runnables(root: &Path) -> Vec<Runnable> {
+    let mut runnables = Vec::new();
+    for project in dotnet_projects(root) {
+        let Ok(content) = std::fs::read_to_string(&project) else {
+            continue;
+        };
+        let name = project
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let relative_path = relative(&project, root);
+        let project_arg = project.to_string_lossy().to_string();
+
+        let is_test = ["Microsoft.NET.Test.Sdk", "xunit", "NUnit", "MSTest"]
+            .iter()
+            .any(|marker| content.contains(marker));
+        let is_runnable = !is_test
+            && (content.contains("<OutputType>Exe</OutputType>")
+                || content.contains("<OutputType>WinExe</OutputType>")
+                || content.contains("Microsoft.NET.Sdk.Web")
+                || content.contains("Microsoft.NET.Sdk.Worker"));
+
+        if is_runnable {
+            runnables.push(
+                Runnable::new(
+                    format!("dotnet:run:{}", relative_path),
+                    RunnableSource::Dotnet,
+                    RunnableGroup::Run,
+                    format!("Run {}", name),
+                    &["dotnet", "run", "--project", &project_arg],
+                    root,
+                )
+                .with_detail(relative_path.clone()),
+            );
+        }
+        if is_test {
+            runnables.push(
+                Runnable::new(
+                    format!("dotnet:test:{}", relative_path),
+                    RunnableSource::Dotnet,
+                    RunnableGroup::Test,
+                    format!("Test {}", name),
+                    &["dotnet", "test", &project_arg],
+                    root,
+                )
+                .with_detail(relative_path.clone()),
+            );
+        }
+    }
+    runnables
+}
+
+// ============================================================================
+// Cargo Targets
+// ============================================================================
+
+fn cargo_runnables(root: &Path) -> Vec<Runnable> {
+    let Some(manifest) = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+    else {
+        return Vec::new();
+    };
+
+    let cargo = |id: &str, group, label: String, command_line: &[&str]| {
+        Runnable::new(
+            format!("cargo:{}", id),
+            RunnableSource::Cargo,
+            group,
+            label,
+            command_line,
+            root,
+        )
+        .with_detail("Cargo.toml")
+    };
+
+    let is_workspace = manifest.contains_key("workspace");
+    let scope: &[&str] = if is_workspace { &["--workspace"] } else { &[] };
+    let with_scope = |base: &[&'static str]| -> Vec<&'static str> {
+        base.iter().chain(scope.iter()).copied().collect()
+    };
+
+    let mut runnables = vec![
+        cargo(
+            "build",
+            RunnableGroup::Build,
+            "cargo build".to_string(),
+            &with_scope(&["cargo", "build"]),
+        ),
+        cargo(
+            "test",
+            RunnableGroup::Test,
+            "cargo test".to_string(),
+            &with_scope(&["cargo", "test"]),
+        ),
+        cargo(
+            "clippy",
+            RunnableGroup::Test,
+            "cargo clippy".to_string(),
+            &with_scope(&["cargo", "clippy"]),
+        ),
+    ];
+
+    // Binaries of the root package: the default one plus any [[bin]] and
+    // src/bin/*.rs targets
+    let Some(package_name) = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+    else {
+        return runnables;
+    };
+
+    let mut binaries: Vec<String> = Vec::new();
+    if root.join("src").join("main.rs").is_file() {
+        binaries.push(package_name.to_string());
+    }
+    if let Some(bins) = manifest.get("bin").and_then(|bins| bins.as_array()) {
+        binaries.extend(
+            bins.iter()
+                .filter_map(|bin| bin.get("name").and_then(|name| name.as_str()))
+                .map(str::to_string),
+        );
+    }
+    if let Ok(entries) = std::fs::read_dir(root.join("src").join("bin")) {
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        binaries.extend(names);
+    }
+
+    let mut seen = HashSet::new();
+    for binary in binaries.into_iter().filter(|b| seen.insert(b.clone())) {
+        runnables.push(cargo(
+            &format!("run:{}", binary),
+            RunnableGroup::Run,
+            format!("cargo run --bin {}", binary),
+            &["cargo", "run", "--bin", &binary],
+        ));
+    }
+    runnables
+}
+
+// ============================================================================
+// Catalog
+// ============================================================================
+
+/// Every runnable in the workspace, grouped by source
+pub fn collect_runnables(root: &Path) -> Result<Vec<Runnable>, String> {
+    let mut runnables = package_runnables(root);
+    runnables.extend(task_runnables(root)?);
+    runnables.extend(dotnet_runnables(root));
+    runnables.extend(cargo_runnables(root));
+    Ok(runnables)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List everything runnable in a workspace for the "Run…" palette
+///
+/// # Arguments
+/// * `workspace_root` - Workspace to scan
+#[fluxel_command]
+pub async fn list_runnables(workspace_root: String) -> Result<Vec<Runnable>, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err(format!(
+            "Workspace root is not a directory or does not exist: {}",
+            workspace_root
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || collect_runnables(&root))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn collects_runnables_from_every_source() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-runnables-{}", nanos));
+        fs::create_dir_all(root.join(".fluxel")).unwrap();
+        fs::create_dir_all(root.join("src").join("bin")).unwrap();
+        fs::create_dir_all(root.join("App")).unwrap();
+        fs::create_dir_all(root.join("App.Tests")).unwrap();
+
+        fs::write(
+            root.join("package.json"),
+            r#"{ "scripts": { "test": "vitest", "dev": "vite --port 3000" },
+                 "devDependencies": { "vite": "^5.0.0", "next": "^14.0.0" } }"#,
+        )
+        .unwrap();
+        fs::write(root.join("pnpm-lock.yaml"), "").unwrap();
+        fs::write(
+            root.join(".fluxel").join("tasks.json"),
+            r#"{ // docs
+                "tasks": [{ "label": "Build docs", "command": "mdbook", "args": ["build"], "cwd": "docs" },],
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("App").join("App.csproj"),
+            "<Project Sdk=\"Microsoft.NET.Sdk\"><PropertyGroup><OutputType>Exe</OutputType></PropertyGroup></Project>",
+        )
+        .unwrap();
+        fs::write(
+            root.join("App.Tests").join("App.Tests.csproj"),
+            "<Project Sdk=\"Microsoft.NET.Sdk\"><ItemGroup><PackageReference Include=\"Microsoft.NET.Test.Sdk\" /></ItemGroup></Project>",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"tool\"\n\n[[bin]]\nname = \"extra\"\npath = \"extra.rs\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("src").join("main.rs"), "").unwrap();
+        fs::write(root.join("src").join("bin").join("gen.rs"), "").unwrap();
+
+        let runnables = collect_runnables(&root).unwrap();
+        let ids: Vec<&str> = runnables.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "script:test",
+                "script:dev",
+                "preset:next",
+                "task:Build docs",
+                "dotnet:run:App/App.csproj",
+                "dotnet:test:App.Tests/App.Tests.csproj",
+                "cargo:build",
+                "cargo:test",
+                "cargo:clippy",
+                "cargo:run:tool",
+                "cargo:run:extra",
+                "cargo:run:gen",
+            ]
+        );
+
+        let dev = &runnables[1];
+        assert_eq!(dev.group, RunnableGroup::Run);
+        assert_eq!(
+            (dev.command.as_str(), dev.args.clone()),
+            ("pnpm", vec!["run".to_string(), "dev".to_string()])
+        );
+        assert_eq!(runnables[2].command_line(), "pnpm exec next dev");
+        assert_eq!(runnables[3].group, RunnableGroup::Build);
+        assert!(runnables[3].cwd.ends_with("/docs"));
+        assert_eq!(runnables[5].group, RunnableGroup::Test);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
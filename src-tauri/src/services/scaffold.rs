@@ -0,0 +1,563 @@
+//! Project Scaffolding Service
+//!
+//! Creates new projects from templates. Built-in templates run the toolchains'
+//! own generators (`dotnet new`, `bun create vite`, `cargo new`); user templates
+//! are folders under `~/.fluxel/templates/` that are copied with `{{variable}}`
+//! placeholders filled in, in file contents as well as file names. An optional
+//! `template.json` in the folder names the template and declares its variables.
+//!
+//! Generator output is streamed line by line as `scaffold://output` events, and
+//! the new project's profile is detected once it has been created so the UI
+//! can open it with the right tooling.
+
+use crate::services::project_detector::{detect_profile, ProjectProfile};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use walkdir::WalkDir;
+
+const OUTPUT_EVENT: &str = "scaffold://output";
+
+/// Manifest read from a user template folder; not copied into projects
+const MANIFEST_FILE: &str = "template.json";
+
+/// Variable every template gets, defaulting to the destination folder's name
+const NAME_VARIABLE: &str = "name";
+
+/// Built-in templates: id, display name and description
+const BUILTIN_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "dotnet-console",
+        "Console App (.NET)",
+        "A .NET console application, via `dotnet new console`",
+    ),
+    (
+        "dotnet-web",
+        "ASP.NET Core Empty (.NET)",
+        "An empty ASP.NET Core web application, via `dotnet new web`",
+    ),
+    (
+        "vite-react",
+        "React + TypeScript (Vite)",
+        "A Vite app with React and TypeScript, via `bun create vite`",
+    ),
+    (
+        "rust-bin",
+        "Binary (Rust)",
+        "A Rust binary crate, via `cargo new`",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Builtin,
+    User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value used when none is given; variables without one are required
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub source: TemplateSource,
+    pub variables: Vec<TemplateVariable>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TemplateManifest {
+    name: Option<String>,
+    description: Option<String>,
+    variables: Vec<TemplateVariable>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldOutput {
+    /// Destination of the project being created
+    pub dest: String,
+    /// `stdout` or `stderr` for generator output, `fluxel` for copied files
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaffoldResult {
+    pub profile: ProjectProfile,
+    pub duration_ms: u64,
+}
+
+fn user_templates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".fluxel").join("templates"))
+}
+
+fn name_variable() -> TemplateVariable {
+    TemplateVariable {
+        name: NAME_VARIABLE.to_string(),
+        description: Some("Project name; defaults to the destination folder's name".to_string()),
+        default: None,
+    }
+}
+
+fn builtin_templates() -> Vec<ProjectTemplate> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, name, description)| ProjectTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            source: TemplateSource::Builtin,
+            variables: vec![name_variable()],
+        })
+        .collect()
+}
+
+/// The user template in `dir`, described by its manifest when it has one
+fn read_user_template(dir: &Path) -> Result<ProjectTemplate, String> {
+    let id = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid template folder: {}", dir.display()))?;
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: TemplateManifest = if manifest_path.is_file() {
+        let content = fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?
+    } else {
+        TemplateManifest::default()
+    };
+
+    let mut variables = manifest.variables;
+    if !variables.iter().any(|v| v.name == NAME_VARIABLE) {
+        variables.insert(0, name_variable());
+    }
+    Ok(ProjectTemplate {
+        name: manifest.name.unwrap_or_else(|| id.clone()),
+        id,
+        description: manifest.description,
+        source: TemplateSource::User,
+        variables,
+    })
+}
+
+/// User templates under `templates_dir`, sorted by id. Folders named like a
+/// built-in template are skipped, since the built-in one takes precedence.
+fn list_user_templates(templates_dir: &Path) -> Vec<ProjectTemplate> {
+    let Ok(entries) = fs::read_dir(templates_dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<ProjectTemplate> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|dir| match read_user_template(&dir) {
+            Ok(template) => Some(template),
+            Err(error) => {
+                println!("[Scaffold] Skipping template: {}", error);
+                None
+            }
+        })
+        .filter(|template| !BUILTIN_TEMPLATES.iter().any(|(id, ..)| *id == template.id))
+        .collect();
+    templates.sort_by(|a, b| a.id.cmp(&b.id));
+    templates
+}
+
+/// Values for every variable of `template`: given values first, then defaults.
+/// `name` falls back to the destination folder's name.
+fn resolve_variables(
+    template: &ProjectTemplate,
+    dest: &Path,
+    mut given: HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    if !given.contains_key(NAME_VARIABLE) {
+        let name = dest
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid destination: {}", dest.display()))?;
+        given.insert(NAME_VARIABLE.to_string(), name);
+    }
+    for variable in &template.variables {
+        if given.contains_key(&variable.name) {
+            continue;
+        }
+        match &variable.default {
+            Some(default) => {
+                given.insert(variable.name.clone(), default.clone());
+            }
+            None => return Err(format!("Missing value for variable '{}'", variable.name)),
+        }
+    }
+    Ok(given)
+}
+
+/// Replace `{{ variable }}` placeholders in `text`. Unknown placeholders are
+/// left alone, since templates may contain other templating syntax.
+pub(crate) fn render(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match variables.get(key) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 4 + len]),
+        }
+        rest = &rest[start + 4 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Copy the user template in `template_dir` to `dest`, rendering placeholders
+/// in file names and text files. Calls `on_file` with each file's path
+/// relative to `dest`.
+fn copy_template(
+    template_dir: &Path,
+    dest: &Path,
+    variables: &HashMap<String, String>,
+    mut on_file: impl FnMut(&Path),
+) -> Result<usize, String> {
+    let mut copied = 0;
+    let walker = WalkDir::new(template_dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("Failed to read template: {}", e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(template_dir)
+            .map_err(|e| e.to_string())?;
+        if relative == Path::new(MANIFEST_FILE) {
+            continue;
+        }
+
+        let rendered = PathBuf::from(render(&relative.to_string_lossy(), variables));
+        // A variable value must not move files out of the project
+        if !rendered
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!(
+                "Template path '{}' renders outside the project: {}",
+                relative.display(),
+                rendered.display()
+            ));
+        }
+        let target = dest.join(&rendered);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let bytes = fs::read(entry.path())
+            .map_err(|e| format!("Failed to read {}: {}", entry.path().display(), e))?;
+        // Binary files are copied as they are
+        let contents = match String::from_utf8(bytes) {
+            Ok(text) => render(&text, variables).into_bytes(),
+            Err(err) => err.into_bytes(),
+        };
+        fs::write(&target, contents)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        on_file(&rendered);
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Generator command for a built-in template
+fn builtin_command(id: &str, dest: &Path, name: &str) -> Result<Command, String> {
+    let parent = dest
+        .parent()
+        .ok_or_else(|| format!("Invalid destination: {}", dest.display()))?;
+    let mut cmd = match id {
+        "dotnet-console" | "dotnet-web" => {
+            let mut cmd = Command::new("dotnet");
+            cmd.args([
+                "new",
+                id.trim_start_matches("dotnet-"),
+                "--name",
+                name,
+                "--output",
+            ])
+            .arg(dest);
+            cmd
+        }
+        "vite-react" => {
+            // create-vite takes the package name from the folder it creates
+            let folder = dest
+                .file_name()
+                .ok_or_else(|| format!("Invalid destination: {}", dest.display()))?;
+            let mut cmd = Command::new("bun");
+            cmd.args(["create", "vite"])
+                .arg(folder)
+                .args(["--template", "react-ts"]);
+            cmd
+        }
+        "rust-bin" => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["new", "--color", "never", "--bin", "--name", name])
+                .arg(dest);
+            cmd
+        }
+        _ => return Err(format!("Unknown template: {}", id)),
+    };
+    cmd.current_dir(parent);
+    Ok(cmd)
+}
+
+/// Emit each line read from `reader` as a `scaffold://output` event
+async fn forward_lines<R: Runtime>(
+    app: &AppHandle<R>,
+    dest: &str,
+    stream: &str,
+    reader: Option<impl AsyncRead + Unpin>,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = app.emit(
+            OUTPUT_EVENT,
+            ScaffoldOutput {
+                dest: dest.to_string(),
+                stream: stream.to_string(),
+                line,
+            },
+        );
+    }
+}
+
+/// Run a generator to completion, streaming its output
+async fn run_generator<R: Runtime>(
+    app: &AppHandle<R>,
+    dest: &str,
+    mut cmd: Command,
+) -> Result<(), String> {
+    let program = cmd.as_std().get_program().to_string_lossy().to_string();
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+        cmd.creation_flags(0x08000000);
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (status, _, _) = tokio::join!(
+        child.wait(),
+        forward_lines(app, dest, "stdout", stdout),
+        forward_lines(app, dest, "stderr", stderr),
+    );
+    let status = status.map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !status.success() {
+        return Err(format!("{} failed ({})", program, status));
+    }
+    Ok(())
+}
+
+/// List the built-in templates followed by the user templates in
+/// `~/.fluxel/templates`
+#[fluxel_command]
+pub async fn list_templates() -> Result<Vec<ProjectTemplate>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let mut templates = builtin_templates();
+        if let Some(dir) = user_templates_dir() {
+            templates.extend(list_user_templates(&dir));
+        }
+        templates
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Create a project from a template and detect its profile.
+///
+/// Generator output and copied files are streamed as `scaffold://output` events.
+///
+/// # Arguments
+/// * `template` - Template id from `list_templates`
+/// * `dest` - Folder to create the project in; must not exist or be empty
+/// * `variables` - Template variable values; `name` defaults to the folder's name
+#[fluxel_command]
+pub async fn create_project<R: Runtime>(
+    app: AppHandle<R>,
+    template: String,
+    dest: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<ScaffoldResult, String> {
+    let dest_path = PathBuf::from(&dest);
+    if !dest_path.is_absolute() {
+        return Err(format!("Destination must be an absolute path: {}", dest));
+    }
+    if dest_path.exists() {
+        let is_empty_dir = fs::read_dir(&dest_path)
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(false);
+        if !is_empty_dir {
+            return Err(format!("Destination already exists: {}", dest));
+        }
+    }
+
+    let builtin = builtin_templates().into_iter().find(|t| t.id == template);
+    let user_dir = user_templates_dir().map(|dir| dir.join(&template));
+    let project_template = match (&builtin, &user_dir) {
+        (Some(builtin), _) => builtin.clone(),
+        (None, Some(dir)) if dir.is_dir() => read_user_template(dir)?,
+        _ => return Err(format!("Template not found: {}", template)),
+    };
+    let variables =
+        resolve_variables(&project_template, &dest_path, variables.unwrap_or_default())?;
+
+    println!("[Scaffold] Creating {} from {}", dest, template);
+    let start_time = Instant::now();
+
+    if builtin.is_some() {
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let cmd = builtin_command(&template, &dest_path, &variables[NAME_VARIABLE])?;
+        run_generator(&app, &dest, cmd).await?;
+    } else if let Some(template_dir) = user_dir {
+        let app = app.clone();
+        let dest = dest.clone();
+        let dest_path = dest_path.clone();
+        let copied = tauri::async_runtime::spawn_blocking(move || {
+            copy_template(&template_dir, &dest_path, &variables, |path| {
+                let _ = app.emit(
+                    OUTPUT_EVENT,
+                    ScaffoldOutput {
+                        dest: dest.clone(),
+                        stream: "fluxel".to_string(),
+                        line: format!("Created {}", path.display()),
+                    },
+                );
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        println!("[Scaffold] Copied {} file(s)", copied);
+    }
+
+    let profile = tauri::async_runtime::spawn_blocking(move || detect_profile(&dest_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let duration_ms = start_time.elapsed().as_millis() as u64;
+    println!("[Scaffold] Created {} in {}ms", dest, duration_ms);
+
+    Ok(ScaffoldResult {
+        profile,
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_user_templates_with_variables() {
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-scaffold-{unique}"));
+        let template_dir = root.join("templates").join("lib");
+        fs::create_dir_all(template_dir.join("src")).unwrap();
+        fs::write(
+            template_dir.join(MANIFEST_FILE),
+            r#"{ "name": "Library", "variables": [
+                { "name": "author" },
+                { "name": "license", "default": "MIT" }
+            ] }"#,
+        )
+        .unwrap();
+        fs::write(
+            template_dir.join("src").join("{{name}}.ts"),
+            "// {{ name }} by {{author}} ({{license}})\nconst view = \"{{ other }}\";\n",
+        )
+        .unwrap();
+        fs::write(template_dir.join("logo.bin"), [0xff, 0xfe, b'{', b'{']).unwrap();
+
+        let templates = list_user_templates(&root.join("templates"));
+        assert_eq!(templates.len(), 1);
+        let template = &templates[0];
+        assert_eq!(
+            (template.id.as_str(), template.name.as_str()),
+            ("lib", "Library")
+        );
+        assert_eq!(template.variables[0].name, NAME_VARIABLE);
+
+        let dest = root.join("my-lib");
+        assert!(resolve_variables(template, &dest, HashMap::new()).is_err());
+        let given = HashMap::from([("author".to_string(), "Ada".to_string())]);
+        let variables = resolve_variables(template, &dest, given).unwrap();
+
+        let mut created = Vec::new();
+        let copied = copy_template(&template_dir, &dest, &variables, |path| {
+            created.push(path.to_path_buf())
+        })
+        .unwrap();
+        assert_eq!(copied, 2);
+        assert_eq!(
+            created,
+            [
+                PathBuf::from("logo.bin"),
+                Path::new("src").join("my-lib.ts")
+            ]
+        );
+        assert!(!dest.join(MANIFEST_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(dest.join("src").join("my-lib.ts")).unwrap(),
+            "// my-lib by Ada (MIT)\nconst view = \"{{ other }}\";\n"
+        );
+        assert_eq!(
+            fs::read(dest.join("logo.bin")).unwrap(),
+            [0xff, 0xfe, b'{', b'{']
+        );
+
+        let escaping = HashMap::from([("name".to_string(), "../out".to_string())]);
+        assert!(copy_template(&template_dir, &root.join("other"), &escaping, |_| {}).is_err());
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,309 @@
+//! Search History Service
+//!
+//! Records executed searches and named (pinned) searches per workspace in
+//! `~/.fluxel/search/<workspace-hash>.json`, so the search panel can offer history
+//! and saved queries across restarts.
+
+use crate::services::workspace_store::{WorkspaceFile, WorkspaceStore};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const STORE_VERSION: u32 = 1;
+/// Recent searches kept per workspace; older entries are dropped
+const MAX_RECENT: usize = 50;
+
+/// Options a search was run with. Two searches with the same query and options are
+/// the same history entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchOptions {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentSearch {
+    pub query: String,
+    pub options: SearchOptions,
+    pub result_count: usize,
+    /// Last execution time (unix seconds)
+    pub executed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub options: SearchOptions,
+    pub saved_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    version: u32,
+    workspace: String,
+    /// Most recent first
+    recent: Vec<RecentSearch>,
+    saved: Vec<SavedSearch>,
+}
+
+impl WorkspaceFile for HistoryFile {
+    const VERSION: u32 = STORE_VERSION;
+    const LABEL: &'static str = "search history";
+
+    fn empty(workspace: String) -> Self {
+        Self {
+            version: STORE_VERSION,
+            workspace,
+            ..Default::default()
+        }
+    }
+}
+
+type HistoryStore = WorkspaceStore<HistoryFile>;
+
+fn record(file: &mut HistoryFile, query: String, options: SearchOptions, result_count: usize) {
+    file.recent
+        .retain(|entry| entry.query != query || entry.options != options);
+    file.recent.insert(
+        0,
+        RecentSearch {
+            query,
+            options,
+            result_count,
+            executed_at: now_secs(),
+        },
+    );
+    file.recent.truncate(MAX_RECENT);
+}
+
+fn save_named(file: &mut HistoryFile, name: String, query: String, options: SearchOptions) {
+    let saved = SavedSearch {
+        name,
+        query,
+        options,
+        saved_at: now_secs(),
+    };
+    match file.saved.iter_mut().find(|s| s.name == saved.name) {
+        Some(existing) => *existing = saved,
+        None => file.saved.push(saved),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Serializes read-modify-write cycles on history files
+#[derive(Clone, Default)]
+pub struct SearchHistoryState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl SearchHistoryState {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn with_store<T>(
+        &self,
+        f: impl FnOnce(&HistoryStore) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        f(&HistoryStore::for_user("search")?)
+    }
+}
+
+/// Record an executed search, moving repeats to the top of the history
+#[fluxel_command]
+pub async fn record_search(
+    workspace_root: String,
+    query: String,
+    options: Option<SearchOptions>,
+    result_count: usize,
+    state: State<'_, SearchHistoryState>,
+) -> Result<(), String> {
+    if query.trim().is_empty() {
+        return Ok(());
+    }
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            store.update(Path::new(&workspace_root), |file| {
+                record(file, query, options.unwrap_or_default(), result_count)
+            })
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List recent searches for a workspace, most recent first
+#[fluxel_command]
+pub async fn list_recent_searches(
+    workspace_root: String,
+    limit: Option<usize>,
+    state: State<'_, SearchHistoryState>,
+) -> Result<Vec<RecentSearch>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            let mut recent = store.load(Path::new(&workspace_root))?.recent;
+            recent.truncate(limit.unwrap_or(MAX_RECENT));
+            Ok(recent)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Forget the recent searches of a workspace (saved searches are kept)
+#[fluxel_command]
+pub async fn clear_search_history(
+    workspace_root: String,
+    state: State<'_, SearchHistoryState>,
+) -> Result<(), String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            store.update(Path::new(&workspace_root), |file| file.recent.clear())
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Save (or overwrite) a named search
+#[fluxel_command]
+pub async fn save_search(
+    workspace_root: String,
+    name: String,
+    query: String,
+    options: Option<SearchOptions>,
+    state: State<'_, SearchHistoryState>,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Saved search name cannot be empty".to_string());
+    }
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            store.update(Path::new(&workspace_root), |file| {
+                save_named(file, name, query, options.unwrap_or_default())
+            })
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// List the named searches of a workspace in the order they were first saved
+#[fluxel_command]
+pub async fn list_saved_searches(
+    workspace_root: String,
+    state: State<'_, SearchHistoryState>,
+) -> Result<Vec<SavedSearch>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| Ok(store.load(Path::new(&workspace_root))?.saved))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Delete a named search. Returns whether it existed.
+#[fluxel_command]
+pub async fn delete_saved_search(
+    workspace_root: String,
+    name: String,
+    state: State<'_, SearchHistoryState>,
+) -> Result<bool, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            store.update(Path::new(&workspace_root), |file| {
+                let before = file.saved.len();
+                file.saved.retain(|s| s.name != name);
+                file.saved.len() != before
+            })
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_history_and_saved_searches_per_workspace() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-search-history-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let store = HistoryStore::new(dir.join("store"));
+        let workspace = dir.join("workspace");
+        let other = dir.join("other");
+
+        let ts_only = SearchOptions {
+            include: vec!["*.ts".to_string()],
+            ..Default::default()
+        };
+        for (query, options, count) in [
+            ("needle", SearchOptions::default(), 3),
+            ("todo", SearchOptions::default(), 10),
+            ("needle", ts_only.clone(), 1),
+            ("needle", SearchOptions::default(), 4),
+        ] {
+            store
+                .update(&workspace, |file| {
+                    record(file, query.to_string(), options, count)
+                })
+                .unwrap();
+        }
+        store
+            .update(&workspace, |file| {
+                save_named(file, "ts".to_string(), "a".to_string(), ts_only.clone());
+                save_named(file, "ts".to_string(), "b".to_string(), ts_only.clone())
+            })
+            .unwrap();
+
+        let loaded = store.load(&workspace).unwrap();
+        let recent: Vec<(&str, usize)> = loaded
+            .recent
+            .iter()
+            .map(|r| (r.query.as_str(), r.result_count))
+            .collect();
+        // Repeats move to the top; differing options are separate entries
+        assert_eq!(recent, vec![("needle", 4), ("needle", 1), ("todo", 10)]);
+        assert_eq!(loaded.saved.len(), 1);
+        assert_eq!(loaded.saved[0].query, "b");
+
+        assert!(store.load(&other).unwrap().recent.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,771 @@
+//! Search Index Service
+//!
+//! Optional trigram index for very large workspaces. The index is built in the
+//! background, persisted to `~/.fluxel/search-index/<workspace-hash>.bin`, reconciled
+//! against file timestamps when reloaded, and kept current by the shared file watcher.
+//! `search_files` asks it for the files that contain every trigram of the query and
+//! only confirms matches in those, instead of reading the whole tree.
+
+use crate::services::fs_watcher::FsWatcherService;
+use crate::services::path_filter::{PathFilter, PathFilterState};
+use crate::services::workspace_store::workspace_file;
+use fluxel_macros::fluxel_command;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+const MAGIC: &[u8; 4] = b"FXTI";
+const INDEX_VERSION: u32 = 1;
+/// Larger files are not indexed and are always searched
+const MAX_INDEXED_FILE_SIZE: u64 = 1024 * 1024;
+/// Shorter queries have no trigram to filter on
+const MIN_QUERY_LEN: usize = 3;
+const DEBOUNCE: Duration = Duration::from_millis(200);
+/// Minimum time between persisting watcher updates
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+const READY_EVENT: &str = "search-index://ready";
+
+const BINARY_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "ico", "svg", "woff", "woff2", "ttf", "eot", "pdf", "zip", "tar",
+    "gz", "7z", "rar", "exe", "dll", "so", "dylib", "bin", "dat", "db", "sqlite",
+];
+
+/// Whether search skips `path` as binary (basic extension check)
+pub(crate) fn is_binary_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| BINARY_EXTENSIONS.contains(&ext.as_str()))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndexStatus {
+    pub root: String,
+    /// Index is usable; `false` while the initial build runs
+    pub ready: bool,
+    pub file_count: usize,
+    pub trigram_count: usize,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    /// Root-relative, forward slashes
+    path: String,
+    modified: u64,
+    size: u64,
+    /// `false` for files that were too large or unreadable
+    indexed: bool,
+    live: bool,
+}
+
+// ============================================================================
+// Trigram Index
+// ============================================================================
+
+/// Posting lists of file ids per lowercase byte trigram. Removed files are
+/// tombstoned and dropped on compaction; ids are only ever appended, so posting
+/// lists stay sorted.
+pub struct TrigramIndex {
+    root: PathBuf,
+    /// Workspace exclusions applied to every walk
+    filter: Arc<PathFilter>,
+    files: Vec<IndexedFile>,
+    ids: HashMap<String, u32>,
+    postings: HashMap<u32, Vec<u32>>,
+    dead: usize,
+}
+
+impl TrigramIndex {
+    fn empty(filter: &Arc<PathFilter>) -> Self {
+        Self {
+            root: filter.root().to_path_buf(),
+            filter: Arc::clone(filter),
+            files: Vec::new(),
+            ids: HashMap::new(),
+            postings: HashMap::new(),
+            dead: 0,
+        }
+    }
+
+    /// Index every searchable file under the filter's root
+    pub fn build(filter: &Arc<PathFilter>) -> Self {
+        let mut index = Self::empty(filter);
+        index.index_files(walk_files(filter, filter.root()));
+        index
+    }
+
+    fn index_files(&mut self, paths: Vec<PathBuf>) {
+        let scanned: Vec<_> = paths
+            .par_iter()
+            .filter_map(|path| {
+                let rel = self.relative(path)?;
+                let (modified, size) = stamp(path)?;
+                Some((rel, modified, size, file_trigrams(path, size)))
+            })
+            .collect();
+        for (rel, modified, size, trigrams) in scanned {
+            self.insert(rel, modified, size, trigrams);
+        }
+    }
+
+    fn insert(&mut self, path: String, modified: u64, size: u64, trigrams: Option<Vec<u32>>) {
+        self.remove(&path);
+        let id = self.files.len() as u32;
+        self.files.push(IndexedFile {
+            path: path.clone(),
+            modified,
+            size,
+            indexed: trigrams.is_some(),
+            live: true,
+        });
+        self.ids.insert(path, id);
+        for trigram in trigrams.unwrap_or_default() {
+            self.postings.entry(trigram).or_default().push(id);
+        }
+    }
+
+    fn remove(&mut self, path: &str) {
+        if let Some(id) = self.ids.remove(path) {
+            self.files[id as usize].live = false;
+            self.dead += 1;
+        }
+    }
+
+    fn remove_prefix(&mut self, dir: &str) {
+        let prefix = format!("{}/", dir);
+        let removed: Vec<String> = self
+            .ids
+            .keys()
+            .filter(|path| path.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for path in removed {
+            self.remove(&path);
+        }
+    }
+
+    /// Re-check changed paths against disk and the ignore rules of their directory
+    pub fn update_paths(&mut self, paths: &[PathBuf]) {
+        let mut listings = HashMap::new();
+        let mut reindex = Vec::new();
+        for path in paths {
+            let Some(rel) = self.relative(path) else {
+                continue;
+            };
+            if !self.is_visible(path, &mut listings) {
+                self.remove(&rel);
+                self.remove_prefix(&rel);
+            } else if path.is_dir() {
+                reindex.extend(walk_files(&self.filter, path));
+            } else if !is_binary_file(path) {
+                let current = self.ids.get(&rel).map(|&id| &self.files[id as usize]);
+                if current.map(|f| (f.modified, f.size)) != stamp(path) {
+                    reindex.push(path.clone());
+                }
+            }
+        }
+        self.index_files(reindex);
+        self.compact_if_needed();
+    }
+
+    /// Whether a full walk from the root would reach `path`. Each ancestor is
+    /// checked against a depth-1 listing of its parent, which applies every
+    /// .gitignore above it; `listings` caches those per directory.
+    fn is_visible(&self, path: &Path, listings: &mut HashMap<PathBuf, HashSet<PathBuf>>) -> bool {
+        if path == self.root {
+            return true;
+        }
+        let Some(parent) = path.parent().filter(|p| p.starts_with(&self.root)) else {
+            return false;
+        };
+        if !self.is_visible(parent, listings) {
+            return false;
+        }
+        listings
+            .entry(parent.to_path_buf())
+            .or_insert_with(|| {
+                self.filter
+                    .walk_builder(parent)
+                    .max_depth(Some(1))
+                    .build()
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.into_path())
+                    .collect()
+            })
+            .contains(path)
+    }
+
+    /// Bring a reloaded index up to date with the tree
+    fn reconcile(&mut self) {
+        let mut seen = HashSet::new();
+        let mut reindex = Vec::new();
+        for path in walk_files(&self.filter, &self.root) {
+            let Some(rel) = self.relative(&path) else {
+                continue;
+            };
+            let current = self.ids.get(&rel).map(|&id| &self.files[id as usize]);
+            if current.map(|f| (f.modified, f.size)) != stamp(&path) {
+                reindex.push(path);
+            }
+            seen.insert(rel);
+        }
+        let stale: Vec<String> = self
+            .ids
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect();
+        for path in stale {
+            self.remove(&path);
+        }
+        self.index_files(reindex);
+        self.compact_if_needed();
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.dead > 0 && self.dead * 2 >= self.files.len() {
+            self.compact();
+        }
+    }
+
+    fn compact(&mut self) {
+        let mut remap = vec![u32::MAX; self.files.len()];
+        let mut files = Vec::with_capacity(self.files.len() - self.dead);
+        for (old, file) in self.files.drain(..).enumerate() {
+            if file.live {
+                remap[old] = files.len() as u32;
+                files.push(file);
+            }
+        }
+        for ids in self.postings.values_mut() {
+            ids.retain_mut(|id| {
+                *id = remap[*id as usize];
+                *id != u32::MAX
+            });
+        }
+        self.postings.retain(|_, ids| !ids.is_empty());
+        self.ids = files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| (file.path.clone(), id as u32))
+            .collect();
+        self.files = files;
+        self.dead = 0;
+    }
+
+    /// Files that may contain `query` (case-insensitive), sorted by path.
+    /// `None` when the query is too short to filter on.
+    pub fn candidates(&self, query: &str) -> Option<Vec<PathBuf>> {
+        let query = query.to_lowercase();
+        if query.len() < MIN_QUERY_LEN {
+            return None;
+        }
+        let mut trigrams: Vec<u32> = query.as_bytes().windows(3).map(pack).collect();
+        trigrams.sort_unstable();
+        trigrams.dedup();
+
+        let mut lists: Vec<&Vec<u32>> = Vec::with_capacity(trigrams.len());
+        for trigram in &trigrams {
+            match self.postings.get(trigram) {
+                Some(ids) => lists.push(ids),
+                None => {
+                    lists.clear();
+                    break;
+                }
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+
+        let mut matched: Vec<u32> = match lists.split_first() {
+            Some((first, rest)) => first
+                .iter()
+                .copied()
+                .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+                .collect(),
+            None => Vec::new(),
+        };
+        // Unindexed files can't be ruled out
+        matched.extend(
+            self.files
+                .iter()
+                .enumerate()
+                .filter(|(_, file)| !file.indexed)
+                .map(|(id, _)| id as u32),
+        );
+
+        let mut paths: Vec<&str> = matched
+            .into_iter()
+            .map(|id| &self.files[id as usize])
+            .filter(|file| file.live)
+            .map(|file| file.path.as_str())
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        Some(paths.into_iter().map(|p| self.root.join(p)).collect())
+    }
+
+    pub fn status(&self) -> SearchIndexStatus {
+        SearchIndexStatus {
+            root: self.root.to_string_lossy().replace('\\', "/"),
+            ready: true,
+            file_count: self.ids.len(),
+            trigram_count: self.postings.len(),
+        }
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.root).ok()?;
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        (!rel.is_empty()).then_some(rel)
+    }
+}
+
+/// Same traversal rules as `search_files`
+fn walk_files(filter: &Arc<PathFilter>, dir: &Path) -> Vec<PathBuf> {
+    filter
+        .walk_builder(dir)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| !is_binary_file(path))
+        .collect()
+}
+
+fn stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Some((modified, metadata.len()))
+}
+
+fn pack(window: &[u8]) -> u32 {
+    (window[0] as u32) << 16 | (window[1] as u32) << 8 | window[2] as u32
+}
+
+/// Distinct trigrams of the lowercased file, or `None` if it isn't indexable
+fn file_trigrams(path: &Path, size: u64) -> Option<Vec<u32>> {
+    if size > MAX_INDEXED_FILE_SIZE {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    let text = String::from_utf8_lossy(&bytes).to_lowercase();
+    let mut trigrams: Vec<u32> = text.as_bytes().windows(3).map(pack).collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    Some(trigrams)
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+fn index_path(root: &Path) -> Option<PathBuf> {
+    let dir = dirs::home_dir()?.join(".fluxel").join("search-index");
+    Some(workspace_file(&dir, root).0.with_extension("bin"))
+}
+
+impl TrigramIndex {
+    fn save(&mut self, path: &Path) -> Result<(), String> {
+        self.compact();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let tmp = path.with_extension("tmp");
+        let file =
+            fs::File::create(&tmp).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+        self.write_to(&mut BufWriter::new(file))
+            .map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+        fs::rename(&tmp, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    fn write_to(&self, w: &mut impl Write) -> std::io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&INDEX_VERSION.to_le_bytes())?;
+        write_str(w, &self.root.to_string_lossy())?;
+        w.write_all(&(self.files.len() as u32).to_le_bytes())?;
+        for file in &self.files {
+            write_str(w, &file.path)?;
+            w.write_all(&file.modified.to_le_bytes())?;
+            w.write_all(&file.size.to_le_bytes())?;
+            w.write_all(&[file.indexed as u8])?;
+        }
+        w.write_all(&(self.postings.len() as u32).to_le_bytes())?;
+        for (trigram, ids) in &self.postings {
+            w.write_all(&trigram.to_le_bytes())?;
+            w.write_all(&(ids.len() as u32).to_le_bytes())?;
+            for id in ids {
+                w.write_all(&id.to_le_bytes())?;
+            }
+        }
+        w.flush()
+    }
+
+    /// Load a saved index for the filter's root; `None` if missing, stale-format, or
+    /// for another root
+    fn load(path: &Path, filter: &Arc<PathFilter>) -> Option<Self> {
+        let mut r = BufReader::new(fs::File::open(path).ok()?);
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic).ok()?;
+        if &magic != MAGIC || read_u32(&mut r)? != INDEX_VERSION {
+            return None;
+        }
+        if Path::new(&read_str(&mut r)?) != filter.root() {
+            return None;
+        }
+
+        let mut index = Self::empty(filter);
+        let file_count = read_u32(&mut r)?;
+        for id in 0..file_count {
+            let path = read_str(&mut r)?;
+            let modified = read_u64(&mut r)?;
+            let size = read_u64(&mut r)?;
+            let mut indexed = [0u8; 1];
+            r.read_exact(&mut indexed).ok()?;
+            index.ids.insert(path.clone(), id);
+            index.files.push(IndexedFile {
+                path,
+                modified,
+                size,
+                indexed: indexed[0] != 0,
+                live: true,
+            });
+        }
+        let posting_count = read_u32(&mut r)?;
+        for _ in 0..posting_count {
+            let trigram = read_u32(&mut r)?;
+            let len = read_u32(&mut r)?;
+            let ids = (0..len)
+                .map(|_| read_u32(&mut r).filter(|&id| id < file_count))
+                .collect::<Option<Vec<u32>>>()?;
+            index.postings.insert(trigram, ids);
+        }
+        Some(index)
+    }
+}
+
+fn write_str(w: &mut impl Write, value: &str) -> std::io::Result<()> {
+    w.write_all(&(value.len() as u32).to_le_bytes())?;
+    w.write_all(value.as_bytes())
+}
+
+fn read_u32(r: &mut impl Read) -> Option<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf).ok()?;
+    Some(u32::from_le_bytes(buf))
+}
+
+fn read_u64(r: &mut impl Read) -> Option<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf).ok()?;
+    Some(u64::from_le_bytes(buf))
+}
+
+fn read_str(r: &mut impl Read) -> Option<String> {
+    let len = read_u32(r)? as usize;
+    let mut buf = vec![0u8; len];
+    r.read_exact(&mut buf).ok()?;
+    String::from_utf8(buf).ok()
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+struct IndexEntry {
+    /// `None` until the initial build or reload finishes
+    index: Arc<RwLock<Option<TrigramIndex>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+/// Enabled search indexes, keyed by canonical workspace root
+#[derive(Clone, Default)]
+pub struct SearchIndexState {
+    entries: Arc<Mutex<HashMap<PathBuf, IndexEntry>>>,
+}
+
+impl SearchIndexState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Candidate files for `query` under `root`, if a ready index covers it.
+    /// Paths are joined onto `root` as given, not its canonical form.
+    pub fn candidates(&self, root: &Path, query: &str) -> Option<Vec<PathBuf>> {
+        let canonical = root.canonicalize().ok()?;
+        let index = Arc::clone(&self.entries.lock().unwrap().get(&canonical)?.index);
+        let guard = index.read().unwrap();
+        let candidates = guard.as_ref()?.candidates(query)?;
+        Some(
+            candidates
+                .iter()
+                .filter_map(|path| path.strip_prefix(&canonical).ok())
+                .map(|relative| root.join(relative))
+                .collect(),
+        )
+    }
+
+    /// Status of every enabled index
+    pub fn statuses(&self) -> Vec<SearchIndexStatus> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(root, entry)| status_of(root, &entry.index))
+            .collect()
+    }
+
+    /// Stop updating every index and persist it for next time (used on app exit)
+    pub async fn save_all(&self) -> Result<(), String> {
+        let entries: Vec<(PathBuf, IndexEntry)> = self.entries.lock().unwrap().drain().collect();
+        tauri::async_runtime::spawn_blocking(move || {
+            for (root, entry) in entries {
+                if let Err(e) = stop_and_save(&root, entry) {
+                    println!("[SearchIndex] {}", e);
+                }
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Abort the update task of an index and save the index
+fn stop_and_save(root: &Path, entry: IndexEntry) -> Result<(), String> {
+    // Aborting drops the watcher subscription with the task
+    entry.task.abort();
+    let mut guard = entry.index.write().unwrap();
+    match (guard.as_mut(), index_path(root)) {
+        (Some(index), Some(saved)) => index.save(&saved),
+        _ => Ok(()),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Enable the trigram index for a workspace root. The index loads or builds in the
+/// background and `search-index://ready` is emitted with its status when done.
+#[fluxel_command]
+pub async fn enable_search_index<R: Runtime>(
+    app: AppHandle<R>,
+    root: String,
+    state: State<'_, SearchIndexState>,
+    watcher: State<'_, FsWatcherService>,
+    filters: State<'_, PathFilterState>,
+) -> Result<SearchIndexStatus, String> {
+    let root_path = PathBuf::from(&root)
+        .canonicalize()
+        .map_err(|e| format!("Workspace root not found: {} ({})", root, e))?;
+
+    let mut entries = state.entries.lock().unwrap();
+    if let Some(entry) = entries.get(&root_path) {
+        return Ok(status_of(&root_path, &entry.index));
+    }
+
+    let mut subscription = watcher.subscribe(&root_path)?;
+    let index: Arc<RwLock<Option<TrigramIndex>>> = Arc::new(RwLock::new(None));
+    let task_index = Arc::clone(&index);
+    let task_root = root_path.clone();
+    let filter = filters.filter_for(&root_path);
+    let task = tokio::spawn(async move {
+        let started = Instant::now();
+        let build_index = Arc::clone(&task_index);
+        let status = tauri::async_runtime::spawn_blocking(move || {
+            let saved = index_path(&task_root);
+            let mut index = match saved
+                .as_deref()
+                .and_then(|p| TrigramIndex::load(p, &filter))
+            {
+                Some(mut index) => {
+                    index.reconcile();
+                    index
+                }
+                None => TrigramIndex::build(&filter),
+            };
+            if let Some(saved) = saved {
+                if let Err(e) = index.save(&saved) {
+                    println!("[SearchIndex] {}", e);
+                }
+            }
+            let status = index.status();
+            *build_index.write().unwrap() = Some(index);
+            status
+        })
+        .await;
+        let Ok(status) = status else {
+            return;
+        };
+        println!(
+            "[SearchIndex] Indexed {} files under {} in {:?}",
+            status.file_count,
+            status.root,
+            started.elapsed()
+        );
+        let _ = app.emit(READY_EVENT, status);
+
+        let mut last_save = Instant::now();
+        while let Some(first) = subscription.recv().await {
+            let mut changed = BTreeSet::new();
+            let mut pending = Some(first);
+            while let Some(event) = pending {
+                changed.extend(event.paths.into_iter().map(PathBuf::from));
+                pending = tokio::time::timeout(DEBOUNCE, subscription.recv())
+                    .await
+                    .ok()
+                    .flatten();
+            }
+            if changed.is_empty() {
+                continue;
+            }
+
+            let index = Arc::clone(&task_index);
+            let save = last_save.elapsed() >= SAVE_INTERVAL;
+            let _ = tauri::async_runtime::spawn_blocking(move || {
+                let changed: Vec<PathBuf> = changed.into_iter().collect();
+                let mut guard = index.write().unwrap();
+                if let Some(index) = guard.as_mut() {
+                    index.update_paths(&changed);
+                    if save {
+                        if let Some(saved) = index_path(&index.root) {
+                            let _ = index.save(&saved);
+                        }
+                    }
+                }
+            })
+            .await;
+            if save {
+                last_save = Instant::now();
+            }
+        }
+    });
+
+    let status = status_of(&root_path, &index);
+    entries.insert(root_path, IndexEntry { index, task });
+    Ok(status)
+}
+
+/// Disable the trigram index for a workspace root, persisting it for next time
+#[fluxel_command]
+pub async fn disable_search_index(
+    root: String,
+    state: State<'_, SearchIndexState>,
+) -> Result<(), String> {
+    let root_path = PathBuf::from(&root)
+        .canonicalize()
+        .unwrap_or_else(|_| root.into());
+    let entry = state
+        .entries
+        .lock()
+        .unwrap()
+        .remove(&root_path)
+        .ok_or_else(|| format!("Search index not enabled for {:?}", root_path))?;
+    tauri::async_runtime::spawn_blocking(move || stop_and_save(&root_path, entry))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Status of the trigram index for a workspace root, if enabled
+#[fluxel_command]
+pub async fn get_search_index_status(
+    root: String,
+    state: State<'_, SearchIndexState>,
+) -> Result<Option<SearchIndexStatus>, String> {
+    let root_path = PathBuf::from(&root)
+        .canonicalize()
+        .unwrap_or_else(|_| root.into());
+    let entries = state.entries.lock().unwrap();
+    Ok(entries
+        .get(&root_path)
+        .map(|entry| status_of(&root_path, &entry.index)))
+}
+
+fn status_of(root: &Path, index: &RwLock<Option<TrigramIndex>>) -> SearchIndexStatus {
+    match index.try_read().ok().as_deref() {
+        Some(Some(index)) => index.status(),
+        // Building, or busy applying an update
+        _ => SearchIndexStatus {
+            root: root.to_string_lossy().replace('\\', "/"),
+            ready: false,
+            file_count: 0,
+            trigram_count: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn names(index: &TrigramIndex, query: &str) -> Vec<String> {
+        index
+            .candidates(query)
+            .unwrap()
+            .iter()
+            .map(|p| index.relative(p).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn prefilters_updates_and_round_trips() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-search-index-{}",
+            std::time::SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        write(&dir.join(".gitignore"), "out/\n");
+        write(&dir.join("src/a.ts"), "const Needle = 1;");
+        write(&dir.join("src/b.ts"), "const haystack = 2;");
+        write(&dir.join("out/a.js"), "needle");
+        write(&dir.join("logo.png"), "needle");
+        let root = dir.canonicalize().unwrap();
+
+        let filter = PathFilterState::new().filter_for(&root);
+        let mut index = TrigramIndex::build(&filter);
+        assert_eq!(names(&index, "NEEDLE"), vec!["src/a.ts"]);
+        assert_eq!(names(&index, "stack"), vec!["src/b.ts"]);
+        assert!(names(&index, "missing").is_empty());
+        assert!(index.candidates("ne").is_none());
+
+        write(&root.join("src/b.ts"), "needle here too");
+        write(&root.join("src/c/d.ts"), "needle");
+        write(&root.join("out/b.js"), "needle");
+        fs::remove_file(root.join("src/a.ts")).unwrap();
+        index.update_paths(&[
+            root.join("src/a.ts"),
+            root.join("src/b.ts"),
+            root.join("src/c"),
+            root.join("out/b.js"),
+        ]);
+        assert_eq!(names(&index, "needle"), vec!["src/b.ts", "src/c/d.ts"]);
+        assert!(names(&index, "haystack").is_empty());
+
+        let saved = dir.join("index.bin");
+        index.save(&saved).unwrap();
+        let loaded = TrigramIndex::load(&saved, &filter).unwrap();
+        assert_eq!(names(&loaded, "needle"), vec!["src/b.ts", "src/c/d.ts"]);
+        let nested = PathFilterState::new().filter_for(&root.join("src"));
+        assert!(TrigramIndex::load(&saved, &nested).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
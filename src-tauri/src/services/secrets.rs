@@ -0,0 +1,724 @@
+//! Workspace Secrets Service
+//!
+//! Per-workspace secret storage encrypted with ChaCha20-Poly1305 under a
+//! machine-local key (`~/.fluxel/machine.key`). Secret files live in
+//! `~/.fluxel/secrets/<workspace-hash>.json`, never inside the workspace,
+//! so they can't be committed by accident and are useless on other machines.
+//!
+//! Tasks, terminals, and AI tools reference secrets as `${secret:NAME}`;
+//! use [`resolve_secret_references`] to substitute them on the backend so
+//! plaintext never has to live in settings files. Command lines use
+//! [`secret_env`] instead, which hands values to the child process through its
+//! environment so they are never parsed by a shell or shown in the process list.
+
+use crate::services::workspace_store::workspace_file;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const STORE_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Matches `${secret:NAME}` references
+fn secret_reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\$\{secret:([A-Za-z_][A-Za-z0-9_.\-]*)\}").unwrap())
+}
+
+/// Public metadata about a stored secret (never includes the value)
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretInfo {
+    pub name: String,
+    /// Last update time (unix seconds)
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSecret {
+    value: String,
+    updated_at: u64,
+}
+
+/// On-disk envelope. Only `ciphertext` contains secret material.
+#[derive(Debug, Serialize, Deserialize)]
+struct SecretFile {
+    version: u32,
+    workspace: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// ============================================================================
+// Secret Store
+// ============================================================================
+
+/// Encrypted secret files for all workspaces on this machine
+struct SecretStore {
+    key_path: PathBuf,
+    store_dir: PathBuf,
+}
+
+impl SecretStore {
+    fn for_user() -> Result<Self, String> {
+        let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+        let fluxel_dir = home.join(".fluxel");
+        Ok(Self {
+            key_path: fluxel_dir.join("machine.key"),
+            store_dir: fluxel_dir.join("secrets"),
+        })
+    }
+
+    /// Load the machine key, generating it on first use
+    fn load_or_create_key(&self) -> Result<Key, String> {
+        if let Ok(bytes) = std::fs::read(&self.key_path) {
+            if bytes.len() != KEY_LEN {
+                return Err(format!(
+                    "Machine key {:?} is corrupt (expected {} bytes, found {})",
+                    self.key_path,
+                    KEY_LEN,
+                    bytes.len()
+                ));
+            }
+            return Ok(*Key::from_slice(&bytes));
+        }
+
+        if let Some(parent) = self.key_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        write_private_file(&self.key_path, key.as_slice())?;
+        println!("[Secrets] Generated machine key at {:?}", self.key_path);
+        Ok(key)
+    }
+
+    fn file_for(&self, workspace_root: &Path) -> (PathBuf, String) {
+        workspace_file(&self.store_dir, workspace_root)
+    }
+
+    fn load(&self, workspace_root: &Path) -> Result<BTreeMap<String, StoredSecret>, String> {
+        let (path, _) = self.file_for(workspace_root);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(format!("Failed to read secrets file: {}", e)),
+        };
+
+        let file: SecretFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse secrets file: {}", e))?;
+        if file.version != STORE_VERSION {
+            return Err(format!("Unsupported secrets file version {}", file.version));
+        }
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let nonce = engine
+            .decode(&file.nonce)
+            .map_err(|e| format!("Invalid secrets nonce: {}", e))?;
+        if nonce.len() != NONCE_LEN {
+            return Err("Invalid secrets nonce length".to_string());
+        }
+        let ciphertext = engine
+            .decode(&file.ciphertext)
+            .map_err(|e| format!("Invalid secrets ciphertext: {}", e))?;
+
+        let key = self.load_or_create_key()?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                "Failed to decrypt workspace secrets (machine key changed or file corrupted)"
+                    .to_string()
+            })?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to decode secrets: {}", e))
+    }
+
+    fn save(
+        &self,
+        workspace_root: &Path,
+        secrets: &BTreeMap<String, StoredSecret>,
+    ) -> Result<(), String> {
+        let (path, workspace) = self.file_for(workspace_root);
+
+        if secrets.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("Failed to remove secrets file: {}", e)),
+            };
+        }
+
+        let key = self.load_or_create_key()?;
+        let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+        // A fresh nonce on every write; reuse under the same key would be fatal
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| format!("Failed to encrypt secrets: {}", e))?;
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let file = SecretFile {
+            version: STORE_VERSION,
+            workspace,
+            nonce: engine.encode(nonce.as_slice()),
+            ciphertext: engine.encode(ciphertext),
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+
+        std::fs::create_dir_all(&self.store_dir)
+            .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+        write_private_file(&path, json.as_bytes())
+    }
+}
+
+/// Write a file readable only by the current user (where the platform supports it)
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict permissions on {:?}: {}", tmp, e))?;
+    }
+
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+fn validate_secret_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .next()
+            .map(|c| c == '_' || c.is_ascii_alphabetic())
+            .unwrap_or(false)
+        && name
+            .chars()
+            .all(|c| c == '_' || c == '.' || c == '-' || c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid secret name '{}': use letters, digits, '_', '.', or '-' and start with a letter or '_'",
+            name
+        ))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Integration Helpers
+// ============================================================================
+
+/// Whether `text` contains any `${secret:NAME}` reference
+pub fn contains_secret_reference(text: &str) -> bool {
+    secret_reference_regex().is_match(text)
+}
+
+fn resolve_with_store(
+    store: &SecretStore,
+    text: &str,
+    workspace_root: &Path,
+) -> Result<String, String> {
+    if !contains_secret_reference(text) {
+        return Ok(text.to_string());
+    }
+
+    let secrets = store.load(workspace_root)?;
+    let mut missing = Vec::new();
+    let resolved = secret_reference_regex().replace_all(text, |caps: &regex::Captures| {
+        let name = &caps[1];
+        match secrets.get(name) {
+            Some(secret) => secret.value.clone(),
+            None => {
+                missing.push(name.to_string());
+                String::new()
+            }
+        }
+    });
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Unknown workspace secret(s): {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(resolved.into_owned())
+}
+
+/// Replace `${secret:NAME}` references in `text` with the workspace's secret values.
+/// Fails if any referenced secret is not defined.
+pub fn resolve_secret_references(text: &str, workspace_root: &Path) -> Result<String, String> {
+    if !contains_secret_reference(text) {
+        return Ok(text.to_string());
+    }
+    resolve_with_store(&SecretStore::for_user()?, text, workspace_root)
+}
+
+/// How a shell refers to environment variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellSyntax {
+    /// `${NAME}`, for `sh`
+    Posix,
+    /// `!NAME!`, for `cmd /V:ON`, which expands it after parsing the line so
+    /// the value's `&`, `|` or quotes are never interpreted
+    Cmd,
+}
+
+impl ShellSyntax {
+    /// Syntax of the shell `shell_command` runs locally
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            ShellSyntax::Cmd
+        } else {
+            ShellSyntax::Posix
+        }
+    }
+}
+
+/// Quote `arg` as a single word of a `syntax` command line. `${secret:NAME}`
+/// references are left in place, positioned so that once `secret_env` has
+/// rewritten them the shell expands them within the same word.
+///
+/// `cmd` has no quoting that stops it expanding `%` (or `!` once delayed
+/// expansion is on), and a `"` would end the quoted word, so arguments with
+/// those characters are refused on Windows.
+pub fn quote_shell_arg(arg: &str, syntax: ShellSyntax) -> Result<String, String> {
+    let mut quoted = String::new();
+    let mut last = 0;
+    let push_literal = |quoted: &mut String, literal: &str| -> Result<(), String> {
+        if literal.is_empty() {
+            return Ok(());
+        }
+        match syntax {
+            ShellSyntax::Posix => {
+                quoted.push('\'');
+                quoted.push_str(&literal.replace('\'', r"'\''"));
+                quoted.push('\'');
+            }
+            ShellSyntax::Cmd => {
+                if literal.contains(['"', '%', '!', '\r', '\n']) {
+                    return Err(format!(
+                        "Argument {:?} can't be passed through cmd: it contains \", %, ! or a line break",
+                        arg
+                    ));
+                }
+                quoted.push('"');
+                quoted.push_str(literal);
+                quoted.push('"');
+            }
+        }
+        Ok(())
+    };
+    for reference in secret_reference_regex().find_iter(arg) {
+        push_literal(&mut quoted, &arg[last..reference.start()])?;
+        quoted.push('"');
+        quoted.push_str(reference.as_str());
+        quoted.push('"');
+        last = reference.end();
+    }
+    push_literal(&mut quoted, &arg[last..])?;
+    if quoted.is_empty() {
+        quoted.push_str(match syntax {
+            ShellSyntax::Posix => "''",
+            ShellSyntax::Cmd => "\"\"",
+        });
+    }
+    Ok(quoted)
+}
+
+/// A command line whose secret references point at environment variables,
+/// and the variables to set for it
+#[derive(Debug, Default)]
+pub struct SecretEnv {
+    pub command_line: String,
+    /// Variable names and secret values
+    pub vars: Vec<(String, String)>,
+}
+
+/// Environment variable carrying secret `name`; names may contain `.` and `-`
+fn secret_var_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("FLUXEL_SECRET_{}", sanitized)
+}
+
+fn secret_env_with_store(
+    store: &SecretStore,
+    command_line: &str,
+    workspace_root: &Path,
+    syntax: ShellSyntax,
+) -> Result<SecretEnv, String> {
+    if !contains_secret_reference(command_line) {
+        return Ok(SecretEnv {
+            command_line: command_line.to_string(),
+            vars: Vec::new(),
+        });
+    }
+
+    let secrets = store.load(workspace_root)?;
+    let mut vars: Vec<(String, String)> = Vec::new();
+    // Secret name → variable, so each secret is passed once
+    let mut assigned: BTreeMap<String, String> = BTreeMap::new();
+    let mut missing = Vec::new();
+    let rewritten = secret_reference_regex().replace_all(command_line, |caps: &regex::Captures| {
+        let name = &caps[1];
+        let Some(secret) = secrets.get(name) else {
+            missing.push(name.to_string());
+            return String::new();
+        };
+        let var = assigned.entry(name.to_string()).or_insert_with(|| {
+            // `a.b` and `a-b` sanitize to the same name; keep them apart
+            let base = secret_var_name(name);
+            let mut var = base.clone();
+            let mut n = 2;
+            while vars.iter().any(|(existing, _)| *existing == var) {
+                var = format!("{}_{}", base, n);
+                n += 1;
+            }
+            vars.push((var.clone(), secret.value.clone()));
+            var
+        });
+        match syntax {
+            ShellSyntax::Posix => format!("${{{}}}", var),
+            ShellSyntax::Cmd => format!("!{}!", var),
+        }
+    });
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Unknown workspace secret(s): {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(SecretEnv {
+        command_line: rewritten.into_owned(),
+        vars,
+    })
+}
+
+/// Rewrite `${secret:NAME}` references in a command line to references to
+/// `FLUXEL_SECRET_NAME` environment variables, and return the values to set
+/// them to in the child's environment. Fails if any referenced secret is not
+/// defined.
+pub fn secret_env(
+    command_line: &str,
+    workspace_root: &Path,
+    syntax: ShellSyntax,
+) -> Result<SecretEnv, String> {
+    if !contains_secret_reference(command_line) {
+        return Ok(SecretEnv {
+            command_line: command_line.to_string(),
+            vars: Vec::new(),
+        });
+    }
+    secret_env_with_store(
+        &SecretStore::for_user()?,
+        command_line,
+        workspace_root,
+        syntax,
+    )
+}
+
+/// Look up a single workspace secret by name, for backend integrations that
+/// authenticate with a stored token
+pub fn read_workspace_secret(workspace_root: &Path, name: &str) -> Result<Option<String>, String> {
+    Ok(SecretStore::for_user()?
+        .load(workspace_root)?
+        .remove(name)
+        .map(|secret| secret.value))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Serializes read-modify-write cycles on secret files
+#[derive(Clone, Default)]
+pub struct WorkspaceSecretsState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl WorkspaceSecretsState {
+    pub fn new() -> Self {
+        Self {
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn with_store<T>(
+        &self,
+        f: impl FnOnce(&SecretStore) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        f(&SecretStore::for_user()?)
+    }
+}
+
+/// List secret names defined for a workspace (values are never returned)
+#[fluxel_command]
+pub async fn list_workspace_secrets(
+    workspace_root: String,
+    state: State<'_, WorkspaceSecretsState>,
+) -> Result<Vec<SecretInfo>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            Ok(store
+                .load(Path::new(&workspace_root))?
+                .into_iter()
+                .map(|(name, secret)| SecretInfo {
+                    name,
+                    updated_at: secret.updated_at,
+                })
+                .collect())
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Create or update a workspace secret
+#[fluxel_command]
+pub async fn set_workspace_secret(
+    workspace_root: String,
+    name: String,
+    value: String,
+    state: State<'_, WorkspaceSecretsState>,
+) -> Result<(), String> {
+    validate_secret_name(&name)?;
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            let root = Path::new(&workspace_root);
+            let mut secrets = store.load(root)?;
+            secrets.insert(
+                name,
+                StoredSecret {
+                    value,
+                    updated_at: now_secs(),
+                },
+            );
+            store.save(root, &secrets)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Reveal a single secret value (for explicit "show" actions in the UI)
+#[fluxel_command]
+pub async fn get_workspace_secret(
+    workspace_root: String,
+    name: String,
+    state: State<'_, WorkspaceSecretsState>,
+) -> Result<Option<String>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            Ok(store
+                .load(Path::new(&workspace_root))?
+                .remove(&name)
+                .map(|s| s.value))
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Delete a workspace secret. Returns false if it did not exist.
+#[fluxel_command]
+pub async fn delete_workspace_secret(
+    workspace_root: String,
+    name: String,
+    state: State<'_, WorkspaceSecretsState>,
+) -> Result<bool, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            let root = Path::new(&workspace_root);
+            let mut secrets = store.load(root)?;
+            let removed = secrets.remove(&name).is_some();
+            if removed {
+                store.save(root, &secrets)?;
+            }
+            Ok(removed)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn create_temp_store(test_name: &str) -> (SecretStore, PathBuf) {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after unix epoch")
+            .as_nanos();
+        let base = std::env::temp_dir().join(format!("fluxel-secrets-{test_name}-{unique}"));
+        let workspace = base.join("workspace");
+        fs::create_dir_all(&workspace).expect("temporary workspace should be created");
+        let store = SecretStore {
+            key_path: base.join("machine.key"),
+            store_dir: base.join("secrets"),
+        };
+        (store, workspace)
+    }
+
+    fn secret(value: &str) -> StoredSecret {
+        StoredSecret {
+            value: value.to_string(),
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn round_trips_encrypted_secrets() {
+        let (store, workspace) = create_temp_store("roundtrip");
+        let mut secrets = BTreeMap::new();
+        secrets.insert("API_TOKEN".to_string(), secret("hunter2"));
+        store.save(&workspace, &secrets).unwrap();
+
+        let (path, _) = store.file_for(&workspace);
+        let on_disk = fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("hunter2"));
+
+        let loaded = store.load(&workspace).unwrap();
+        assert_eq!(loaded["API_TOKEN"].value, "hunter2");
+
+        let _ = fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn fails_to_decrypt_with_different_machine_key() {
+        let (store, workspace) = create_temp_store("wrongkey");
+        let mut secrets = BTreeMap::new();
+        secrets.insert("A".to_string(), secret("1"));
+        store.save(&workspace, &secrets).unwrap();
+
+        fs::write(&store.key_path, [7u8; KEY_LEN]).unwrap();
+        assert!(store.load(&workspace).is_err());
+
+        let _ = fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn quotes_arguments_for_the_shell() {
+        let posix = |arg| quote_shell_arg(arg, ShellSyntax::Posix).unwrap();
+        assert_eq!(posix("plain"), "'plain'");
+        assert_eq!(posix(""), "''");
+        assert_eq!(posix("it's; rm -rf ~"), r"'it'\''s; rm -rf ~'");
+        assert_eq!(
+            posix("--token=${secret:API}!"),
+            r#"'--token='"${secret:API}"'!'"#
+        );
+
+        let cmd = |arg| quote_shell_arg(arg, ShellSyntax::Cmd);
+        assert_eq!(cmd("a & b").unwrap(), r#""a & b""#);
+        assert_eq!(cmd("${secret:API}").unwrap(), r#""${secret:API}""#);
+        assert!(cmd("100%").is_err());
+        assert!(cmd(r#"say "hi""#).is_err());
+
+        // The rewritten reference stays inside the quoted word
+        let (store, workspace) = create_temp_store("quote");
+        let mut secrets = BTreeMap::new();
+        secrets.insert("API".to_string(), secret("a b"));
+        store.save(&workspace, &secrets).unwrap();
+        let env = secret_env_with_store(
+            &store,
+            &format!("curl {}", posix("-H=${secret:API}")),
+            &workspace,
+            ShellSyntax::Posix,
+        )
+        .unwrap();
+        assert_eq!(env.command_line, r#"curl '-H='"${FLUXEL_SECRET_API}""#);
+
+        let _ = fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn resolves_secret_references() {
+        let (store, workspace) = create_temp_store("resolve");
+        let mut secrets = BTreeMap::new();
+        secrets.insert("DB_PASSWORD".to_string(), secret("s3cret"));
+        store.save(&workspace, &secrets).unwrap();
+
+        let resolved =
+            resolve_with_store(&store, "psql -p ${secret:DB_PASSWORD}", &workspace).unwrap();
+        assert_eq!(resolved, "psql -p s3cret");
+
+        let err = resolve_with_store(&store, "${secret:MISSING}", &workspace).unwrap_err();
+        assert!(err.contains("MISSING"));
+
+        secrets.insert("db.user".to_string(), secret("a b; rm -rf ~"));
+        secrets.insert("db-user".to_string(), secret("$(whoami)"));
+        store.save(&workspace, &secrets).unwrap();
+        let env = secret_env_with_store(
+            &store,
+            "psql -U \"${secret:db.user}\" -p ${secret:DB_PASSWORD} ${secret:db-user} ${secret:db.user}",
+            &workspace,
+            ShellSyntax::Posix,
+        )
+        .unwrap();
+        assert_eq!(
+            env.command_line,
+            "psql -U \"${FLUXEL_SECRET_db_user}\" -p ${FLUXEL_SECRET_DB_PASSWORD} ${FLUXEL_SECRET_db_user_2} ${FLUXEL_SECRET_db_user}"
+        );
+        assert_eq!(
+            env.vars,
+            [
+                (
+                    "FLUXEL_SECRET_db_user".to_string(),
+                    "a b; rm -rf ~".to_string()
+                ),
+                (
+                    "FLUXEL_SECRET_DB_PASSWORD".to_string(),
+                    "s3cret".to_string()
+                ),
+                (
+                    "FLUXEL_SECRET_db_user_2".to_string(),
+                    "$(whoami)".to_string()
+                ),
+            ]
+        );
+        let env = secret_env_with_store(
+            &store,
+            "echo ${secret:DB_PASSWORD}",
+            &workspace,
+            ShellSyntax::Cmd,
+        )
+        .unwrap();
+        assert_eq!(env.command_line, "echo !FLUXEL_SECRET_DB_PASSWORD!");
+
+        let _ = fs::remove_dir_all(workspace.parent().unwrap());
+    }
+
+    #[test]
+    fn validates_secret_names() {
+        assert!(validate_secret_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_secret_name("db.password").is_ok());
+        assert!(validate_secret_name("1BAD").is_err());
+        assert!(validate_secret_name("has space").is_err());
+    }
+}
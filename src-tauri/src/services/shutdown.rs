@@ -0,0 +1,211 @@
+//! Shutdown Coordinator
+//!
+//! Runs when the app exits: persists search indexes, stops file and build
+//! watchers and preview servers, asks language servers to `shutdown` and
+//! `exit`, ends the active profiling session, and records the session in
+//! `~/.fluxel/session.json`. Every step shares one time budget, so a hung
+//! language server can't keep the app from quitting; tracked child processes
+//! are killed last regardless.
+//!
+//! The session record is written as running on startup and completed on a
+//! clean exit, so the next launch can tell whether the previous one crashed.
+
+use crate::languages::lsp_manager::{self, LSPState};
+use crate::services::{
+    FsWatcherService, ModuleGraphWatchState, PreviewServerState, ProcessManager, SearchIndexState,
+    WatchBuildState,
+};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+/// Time all shutdown steps may take together
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time a language server gets to answer `shutdown`, and again to exit
+const LSP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// One run of the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecord {
+    /// Unix milliseconds
+    pub started_at: u64,
+    /// Unix milliseconds; `None` if the app didn't shut down cleanly
+    pub ended_at: Option<u64>,
+    /// Shutdown steps that ran out of time
+    #[serde(default)]
+    pub incomplete_steps: Vec<String>,
+}
+
+/// The current session, and the previous one as it was found on startup
+pub struct SessionState {
+    current: SessionRecord,
+    previous: Option<SessionRecord>,
+}
+
+impl SessionState {
+    /// Load the previous session and record the current one as running
+    pub fn begin() -> Self {
+        let path = session_path();
+        let previous = path.as_deref().and_then(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            serde_json::from_str(&content).ok()
+        });
+        let current = SessionRecord {
+            started_at: now_millis(),
+            ended_at: None,
+            incomplete_steps: Vec::new(),
+        };
+        if let Some(path) = path {
+            if let Err(e) = save_session(&path, &current) {
+                println!("[Shutdown] {}", e);
+            }
+        }
+        Self { current, previous }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn session_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".fluxel").join("session.json"))
+}
+
+fn save_session(path: &Path, record: &SessionRecord) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create session directory: {}", e))?;
+    }
+    let json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write session state: {}", e))
+}
+
+/// Runs shutdown steps against a shared deadline
+struct Shutdown {
+    deadline: Instant,
+    incomplete: Vec<String>,
+}
+
+impl Shutdown {
+    /// Run `step` with the time left, recording it as incomplete if it runs out
+    async fn step(&mut self, name: &str, step: impl Future<Output = ()>) {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if tokio::time::timeout(remaining, step).await.is_err() {
+            println!("[Shutdown] {} did not finish in time", name);
+            self.incomplete.push(name.to_string());
+        }
+    }
+}
+
+/// Shut down every managed subsystem within `SHUTDOWN_TIMEOUT`, then kill the
+/// remaining child processes. Called from the `RunEvent::Exit` handler.
+pub async fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    let started = Instant::now();
+    let mut shutdown = Shutdown {
+        deadline: started + SHUTDOWN_TIMEOUT,
+        incomplete: Vec::new(),
+    };
+
+    shutdown
+        .step("search indexes", async {
+            if let Err(e) = app.state::<SearchIndexState>().save_all().await {
+                println!("[Shutdown] Failed to save search indexes: {}", e);
+            }
+        })
+        .await;
+
+    shutdown
+        .step("watchers", async {
+            app.state::<ModuleGraphWatchState>().stop_all().await;
+            app.state::<WatchBuildState>().stop_all().await;
+            app.state::<PreviewServerState>().stop_all().await;
+            app.state::<FsWatcherService>().stop_all();
+        })
+        .await;
+
+    shutdown
+        .step("language servers", async {
+            let lsp = app.state::<LSPState>();
+            futures::future::join_all(
+                lsp.servers()
+                    .map(|manager| lsp_manager::shutdown(manager, LSP_SHUTDOWN_TIMEOUT)),
+            )
+            .await;
+        })
+        .await;
+
+    #[cfg(feature = "profiling")]
+    if let Some(profiler) = app.try_state::<crate::profiling::FluxelProfiler>() {
+        if let Some(session_id) = profiler.active_session_id() {
+            let filter = crate::profiling::SpanFilter::default();
+            if let Some(report) = profiler.end_session(&session_id, &filter) {
+                println!(
+                    "[Shutdown] Ended profiling session '{}' ({} spans)",
+                    report.session.name, report.session.span_count
+                );
+            }
+        }
+    }
+
+    if let (Some(session), Some(path)) = (app.try_state::<SessionState>(), session_path()) {
+        let record = SessionRecord {
+            ended_at: Some(now_millis()),
+            incomplete_steps: shutdown.incomplete.clone(),
+            ..session.current.clone()
+        };
+        if let Err(e) = save_session(&path, &record) {
+            println!("[Shutdown] {}", e);
+        }
+    }
+
+    // Also catches servers that didn't exit when asked
+    app.state::<ProcessManager>().kill_all();
+    println!("[Shutdown] Finished in {:?}", started.elapsed());
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// The previous run of the app; `endedAt` is null if it didn't shut down cleanly
+#[fluxel_command]
+pub fn get_previous_session(state: State<'_, SessionState>) -> Option<SessionRecord> {
+    state.previous.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_share_one_deadline() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let incomplete = runtime.block_on(async {
+            let mut shutdown = Shutdown {
+                deadline: Instant::now() + Duration::from_millis(50),
+                incomplete: Vec::new(),
+            };
+            shutdown.step("fast", async {}).await;
+            shutdown
+                .step("hung", tokio::time::sleep(Duration::from_secs(60)))
+                .await;
+            // Nothing left of the budget
+            shutdown
+                .step("after", tokio::time::sleep(Duration::from_millis(10)))
+                .await;
+            shutdown.incomplete
+        });
+        assert_eq!(incomplete, ["hung", "after"]);
+    }
+}
@@ -0,0 +1,1113 @@
+//! Snippet Service
+//!
+//! Loads user snippets from `~/.fluxel/snippets/*.json` and workspace snippets from
+//! `<workspace>/.fluxel/snippets/*.json` (VS Code snippet file format), validates them,
+//! and expands TextMate-style snippet bodies in Rust.
+//!
+//! Supported syntax:
+//! - Tab stops: `$1`, `${1}`, final cursor `$0`
+//! - Placeholders: `${1:default}` (nestable) and choices `${1|one,two|}`
+//! - Variables: `$TM_FILENAME`, `${CURRENT_YEAR}`, `${VAR:default}`,
+//!   `${VAR/regex/format/flags}` transforms
+//! - Escapes: `\$`, `\}`, `\\`
+
+use chrono::{Datelike, Local, Timelike};
+use fluxel_macros::fluxel_command;
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A snippet definition loaded from disk
+#[derive(Debug, Clone, Serialize)]
+pub struct Snippet {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    pub body: String,
+    pub description: Option<String>,
+    /// Languages this snippet applies to (empty = all languages)
+    pub scopes: Vec<String>,
+    /// "user" or "workspace"
+    pub source: String,
+    pub file_path: String,
+}
+
+/// A problem found while loading snippet files
+#[derive(Debug, Clone, Serialize)]
+pub struct SnippetError {
+    pub file_path: String,
+    pub snippet: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnippetListResult {
+    pub snippets: Vec<Snippet>,
+    pub errors: Vec<SnippetError>,
+}
+
+/// A range in the expanded text, in UTF-16 code units (matching Monaco offsets)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TextRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A tab stop in the expanded snippet. Multiple ranges are mirrors.
+#[derive(Debug, Clone, Serialize)]
+pub struct TabStop {
+    pub index: u32,
+    pub ranges: Vec<TextRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpandedSnippet {
+    pub text: String,
+    /// Tab stops ordered by navigation order ($1, $2, ..., $0 last)
+    pub tab_stops: Vec<TabStop>,
+}
+
+/// Raw snippet entry as stored in a snippets JSON file
+#[derive(Debug, Deserialize)]
+struct RawSnippet {
+    prefix: Option<StringOrList>,
+    body: Option<StringOrList>,
+    description: Option<StringOrList>,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrList::One(s) => vec![s],
+            StringOrList::Many(v) => v,
+        }
+    }
+}
+
+// ============================================================================
+// Snippet Parsing
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    TabStop {
+        index: u32,
+        children: Vec<Node>,
+        choices: Option<Vec<String>>,
+    },
+    Variable {
+        name: String,
+        default: Option<Vec<Node>>,
+        transform: Option<Transform>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Transform {
+    regex: String,
+    format: String,
+    flags: String,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Syntax problems encountered (the parser recovers by treating input as text)
+    errors: Vec<String>,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_all(&mut self) -> Vec<Node> {
+        self.parse_nodes(&[])
+    }
+
+    /// Parse nodes until one of `terminators` (unescaped) or end of input
+    fn parse_nodes(&mut self, terminators: &[char]) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.peek() {
+            if terminators.contains(&c) {
+                break;
+            }
+            match c {
+                '\\' => {
+                    // Escapes only apply to syntax characters
+                    let next = self.chars.get(self.pos + 1).copied();
+                    match next {
+                        Some(n)
+                            if n == '$' || n == '}' || n == '\\' || terminators.contains(&n) =>
+                        {
+                            text.push(n);
+                            self.pos += 2;
+                        }
+                        _ => {
+                            text.push('\\');
+                            self.pos += 1;
+                        }
+                    }
+                }
+                '$' => {
+                    let start = self.pos;
+                    match self.parse_dollar() {
+                        Some(node) => {
+                            if !text.is_empty() {
+                                nodes.push(Node::Text(std::mem::take(&mut text)));
+                            }
+                            nodes.push(node);
+                        }
+                        None => {
+                            // Not a valid construct: keep the `$` as literal text
+                            self.pos = start + 1;
+                            text.push('$');
+                        }
+                    }
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        nodes
+    }
+
+    fn parse_number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return None;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn parse_var_name(&mut self) -> Option<String> {
+        let start = self.pos;
+        if !self
+            .peek()
+            .map(|c| c == '_' || c.is_ascii_alphabetic())
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        while self
+            .peek()
+            .map(|c| c == '_' || c.is_ascii_alphanumeric())
+            .unwrap_or(false)
+        {
+            self.pos += 1;
+        }
+        Some(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Parse a construct starting at `$`. Returns None (without consuming) on failure.
+    fn parse_dollar(&mut self) -> Option<Node> {
+        self.pos += 1; // consume '$'
+
+        if let Some(index) = self.parse_number() {
+            return Some(Node::TabStop {
+                index,
+                children: Vec::new(),
+                choices: None,
+            });
+        }
+        if let Some(name) = self.parse_var_name() {
+            return Some(Node::Variable {
+                name,
+                default: None,
+                transform: None,
+            });
+        }
+        if self.peek() != Some('{') {
+            return None;
+        }
+        self.pos += 1; // consume '{'
+
+        if let Some(index) = self.parse_number() {
+            match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    return Some(Node::TabStop {
+                        index,
+                        children: Vec::new(),
+                        choices: None,
+                    });
+                }
+                Some(':') => {
+                    self.pos += 1;
+                    let children = self.parse_nodes(&['}']);
+                    if self.peek() == Some('}') {
+                        self.pos += 1;
+                        return Some(Node::TabStop {
+                            index,
+                            children,
+                            choices: None,
+                        });
+                    }
+                    self.errors
+                        .push(format!("Unclosed placeholder ${{{}:...", index));
+                    return None;
+                }
+                Some('|') => {
+                    self.pos += 1;
+                    let choices = self.parse_choices()?;
+                    return Some(Node::TabStop {
+                        index,
+                        children: choices
+                            .first()
+                            .map(|c| vec![Node::Text(c.clone())])
+                            .unwrap_or_default(),
+                        choices: Some(choices),
+                    });
+                }
+                _ => {
+                    self.errors
+                        .push(format!("Invalid tab stop syntax after ${{{}", index));
+                    return None;
+                }
+            }
+        }
+
+        let name = match self.parse_var_name() {
+            Some(name) => name,
+            None => {
+                self.errors
+                    .push("Expected tab stop or variable after ${".to_string());
+                return None;
+            }
+        };
+        match self.peek() {
+            Some('}') => {
+                self.pos += 1;
+                Some(Node::Variable {
+                    name,
+                    default: None,
+                    transform: None,
+                })
+            }
+            Some(':') => {
+                self.pos += 1;
+                let default = self.parse_nodes(&['}']);
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    Some(Node::Variable {
+                        name,
+                        default: Some(default),
+                        transform: None,
+                    })
+                } else {
+                    self.errors
+                        .push(format!("Unclosed variable ${{{}:...", name));
+                    None
+                }
+            }
+            Some('/') => {
+                self.pos += 1;
+                let regex = self.read_until_unescaped('/')?;
+                let format = self.read_format()?;
+                let flags = self.read_until_unescaped('}')?;
+                Some(Node::Variable {
+                    name,
+                    default: None,
+                    transform: Some(Transform {
+                        regex,
+                        format,
+                        flags,
+                    }),
+                })
+            }
+            _ => {
+                self.errors
+                    .push(format!("Invalid variable syntax after ${{{}", name));
+                None
+            }
+        }
+    }
+
+    /// Parse `a,b,c|}` choice lists (after the opening `|`)
+    fn parse_choices(&mut self) -> Option<Vec<String>> {
+        let mut choices = Vec::new();
+        let mut current = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' => {
+                    if let Some(n) = self.chars.get(self.pos + 1).copied() {
+                        if n == ',' || n == '|' || n == '\\' {
+                            current.push(n);
+                            self.pos += 2;
+                            continue;
+                        }
+                    }
+                    current.push('\\');
+                    self.pos += 1;
+                }
+                ',' => {
+                    choices.push(std::mem::take(&mut current));
+                    self.pos += 1;
+                }
+                '|' if self.chars.get(self.pos + 1) == Some(&'}') => {
+                    choices.push(current);
+                    self.pos += 2;
+                    return Some(choices);
+                }
+                _ => {
+                    current.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.errors.push("Unclosed choice list".to_string());
+        None
+    }
+
+    /// Read a transform format up to the closing `/`, keeping `${1:/upcase}` intact
+    fn read_format(&mut self) -> Option<String> {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' if self.pos + 1 < self.chars.len() => {
+                    out.push(c);
+                    out.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                '$' if self.chars.get(self.pos + 1) == Some(&'{') => {
+                    let close = self.chars[self.pos..].iter().position(|&ch| ch == '}')?;
+                    out.extend(&self.chars[self.pos..=self.pos + close]);
+                    self.pos += close + 1;
+                }
+                '/' => {
+                    self.pos += 1;
+                    return Some(out);
+                }
+                _ => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.errors.push("Unclosed variable transform".to_string());
+        None
+    }
+
+    /// Read raw text up to an unescaped delimiter, consuming the delimiter
+    fn read_until_unescaped(&mut self, delimiter: char) -> Option<String> {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                if let Some(n) = self.chars.get(self.pos + 1).copied() {
+                    if n == delimiter {
+                        out.push(n);
+                    } else {
+                        out.push('\\');
+                        out.push(n);
+                    }
+                    self.pos += 2;
+                    continue;
+                }
+            }
+            if c == delimiter {
+                self.pos += 1;
+                return Some(out);
+            }
+            out.push(c);
+            self.pos += 1;
+        }
+        self.errors.push("Unclosed variable transform".to_string());
+        None
+    }
+}
+
+/// Parse a snippet body, returning the syntax problems found.
+fn validate_body(body: &str) -> Vec<String> {
+    let mut parser = Parser::new(body);
+    parser.parse_all();
+    parser.errors
+}
+
+// ============================================================================
+// Variable Resolution
+// ============================================================================
+
+/// Context used to compute built-in variables
+#[derive(Debug, Default)]
+struct VariableContext {
+    provided: HashMap<String, String>,
+    file_path: Option<PathBuf>,
+    workspace_root: Option<PathBuf>,
+}
+
+impl VariableContext {
+    fn resolve(&self, name: &str) -> Option<String> {
+        if let Some(value) = self.provided.get(name) {
+            return Some(value.clone());
+        }
+
+        let now = Local::now();
+        let file = self.file_path.as_deref();
+        let value = match name {
+            "TM_FILENAME" => file?.file_name()?.to_string_lossy().into_owned(),
+            "TM_FILENAME_BASE" => {
+                let name = file?.file_name()?.to_string_lossy().into_owned();
+                match name.find('.') {
+                    Some(0) | None => name,
+                    Some(idx) => name[..idx].to_string(),
+                }
+            }
+            "TM_DIRECTORY" => file?.parent()?.to_string_lossy().replace('\\', "/"),
+            "TM_FILEPATH" => file?.to_string_lossy().replace('\\', "/"),
+            "RELATIVE_FILEPATH" => {
+                let root = self.workspace_root.as_deref()?;
+                file?
+                    .strip_prefix(root)
+                    .ok()?
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            }
+            "WORKSPACE_NAME" => self
+                .workspace_root
+                .as_deref()?
+                .file_name()?
+                .to_string_lossy()
+                .into_owned(),
+            "WORKSPACE_FOLDER" => self
+                .workspace_root
+                .as_deref()?
+                .to_string_lossy()
+                .replace('\\', "/"),
+            "GIT_BRANCH" => {
+                let start = self
+                    .workspace_root
+                    .as_deref()
+                    .or_else(|| file.and_then(|f| f.parent()))?;
+                let repo = Repository::discover(start).ok()?;
+                let head = repo.head().ok()?;
+                head.shorthand()?.to_string()
+            }
+            "CURRENT_YEAR" => now.year().to_string(),
+            "CURRENT_YEAR_SHORT" => format!("{:02}", now.year() % 100),
+            "CURRENT_MONTH" => format!("{:02}", now.month()),
+            "CURRENT_MONTH_NAME" => now.format("%B").to_string(),
+            "CURRENT_MONTH_NAME_SHORT" => now.format("%b").to_string(),
+            "CURRENT_DATE" => format!("{:02}", now.day()),
+            "CURRENT_DAY_NAME" => now.format("%A").to_string(),
+            "CURRENT_DAY_NAME_SHORT" => now.format("%a").to_string(),
+            "CURRENT_HOUR" => format!("{:02}", now.hour()),
+            "CURRENT_MINUTE" => format!("{:02}", now.minute()),
+            "CURRENT_SECOND" => format!("{:02}", now.second()),
+            "CURRENT_SECONDS_UNIX" => now.timestamp().to_string(),
+            "CURRENT_TIMEZONE_OFFSET" => now.format("%:z").to_string(),
+            "RANDOM" => format!("{:06}", pseudo_random() % 1_000_000),
+            "RANDOM_HEX" => format!("{:06x}", pseudo_random() & 0xFF_FFFF),
+            // Selection/line variables only exist when the editor provides them
+            "TM_SELECTED_TEXT" | "TM_CURRENT_LINE" | "TM_CURRENT_WORD" | "CLIPBOARD" => {
+                String::new()
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Cheap non-cryptographic random number for RANDOM/RANDOM_HEX variables
+fn pseudo_random() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    hasher.finish()
+}
+
+/// Apply a `/regex/format/flags` transform to a resolved variable value
+fn apply_transform(value: &str, transform: &Transform) -> String {
+    let mut pattern = String::new();
+    if transform.flags.contains('i') {
+        pattern.push_str("(?i)");
+    }
+    if transform.flags.contains('m') {
+        pattern.push_str("(?m)");
+    }
+    pattern.push_str(&transform.regex);
+
+    let regex = match Regex::new(&pattern) {
+        Ok(r) => r,
+        Err(_) => return value.to_string(),
+    };
+
+    let render = |caps: &regex::Captures| format_transform(&transform.format, caps);
+    if transform.flags.contains('g') {
+        regex.replace_all(value, render).into_owned()
+    } else {
+        regex.replace(value, render).into_owned()
+    }
+}
+
+/// Render a transform format string (`$1`, `${1}`, `${1:/upcase}`, `${1:+if}`, `${1:-else}`)
+fn format_transform(format: &str, caps: &regex::Captures) -> String {
+    let chars: Vec<char> = format.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    let group = |n: usize| caps.get(n).map(|m| m.as_str()).unwrap_or("");
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && i + 1 < chars.len() {
+            match chars[i + 1] {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            }
+            i += 2;
+            continue;
+        }
+        if c == '$' && i + 1 < chars.len() {
+            if chars[i + 1].is_ascii_digit() {
+                let mut j = i + 1;
+                while j < chars.len() && chars[j].is_ascii_digit() {
+                    j += 1;
+                }
+                let n: usize = chars[i + 1..j]
+                    .iter()
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0);
+                out.push_str(group(n));
+                i = j;
+                continue;
+            }
+            if chars[i + 1] == '{' {
+                if let Some(close) = chars[i..].iter().position(|&ch| ch == '}') {
+                    let inner: String = chars[i + 2..i + close].iter().collect();
+                    let (num, rest) = match inner.split_once(':') {
+                        Some((n, r)) => (n, Some(r)),
+                        None => (inner.as_str(), None),
+                    };
+                    if let Ok(n) = num.parse::<usize>() {
+                        let value = group(n);
+                        let rendered = match rest {
+                            None => value.to_string(),
+                            Some("/upcase") => value.to_uppercase(),
+                            Some("/downcase") => value.to_lowercase(),
+                            Some("/capitalize") => {
+                                let mut cs = value.chars();
+                                match cs.next() {
+                                    Some(first) => first.to_uppercase().chain(cs).collect(),
+                                    None => String::new(),
+                                }
+                            }
+                            Some(r) if r.starts_with('+') => {
+                                if value.is_empty() {
+                                    String::new()
+                                } else {
+                                    r[1..].to_string()
+                                }
+                            }
+                            Some(r) => {
+                                let fallback = r.strip_prefix('-').unwrap_or(r);
+                                if value.is_empty() {
+                                    fallback.to_string()
+                                } else {
+                                    value.to_string()
+                                }
+                            }
+                        };
+                        out.push_str(&rendered);
+                        i += close + 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+// ============================================================================
+// Expansion
+// ============================================================================
+
+struct Expander<'a> {
+    context: &'a VariableContext,
+    text: String,
+    /// Current length of `text` in UTF-16 code units
+    offset: usize,
+    stops: BTreeMap<u32, TabStop>,
+    /// Rendered default text for each placeholder index (used by mirrors)
+    placeholder_text: HashMap<u32, String>,
+    /// Next free index for unknown variables turned into placeholders
+    next_synthetic_index: u32,
+}
+
+impl<'a> Expander<'a> {
+    fn push_text(&mut self, s: &str) {
+        self.text.push_str(s);
+        self.offset += s.encode_utf16().count();
+    }
+
+    fn record(&mut self, index: u32, range: TextRange, choices: Option<Vec<String>>) {
+        let stop = self.stops.entry(index).or_insert_with(|| TabStop {
+            index,
+            ranges: Vec::new(),
+            choices: None,
+        });
+        stop.ranges.push(range);
+        if stop.choices.is_none() {
+            stop.choices = choices;
+        }
+    }
+
+    fn render(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.push_text(text),
+                Node::TabStop {
+                    index,
+                    children,
+                    choices,
+                } => {
+                    let start = self.offset;
+                    if children.is_empty() {
+                        if let Some(mirror) = self.placeholder_text.get(index).cloned() {
+                            self.push_text(&mirror);
+                        }
+                    } else {
+                        self.render(children);
+                    }
+                    let range = TextRange {
+                        start,
+                        end: self.offset,
+                    };
+                    self.record(*index, range, choices.clone());
+                }
+                Node::Variable {
+                    name,
+                    default,
+                    transform,
+                } => match self.context.resolve(name) {
+                    Some(value) => {
+                        let value = match transform {
+                            Some(t) => apply_transform(&value, t),
+                            None => value,
+                        };
+                        if value.is_empty() {
+                            if let Some(default) = default {
+                                self.render(default);
+                                continue;
+                            }
+                        }
+                        self.push_text(&value);
+                    }
+                    None => {
+                        if let Some(default) = default {
+                            self.render(default);
+                        } else {
+                            // Unknown variables become placeholders named after the variable
+                            let index = self.next_synthetic_index;
+                            self.next_synthetic_index += 1;
+                            let start = self.offset;
+                            self.push_text(name);
+                            let range = TextRange {
+                                start,
+                                end: self.offset,
+                            };
+                            self.record(index, range, None);
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Collect the highest explicit tab stop index
+fn max_tab_stop(nodes: &[Node]) -> u32 {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::TabStop {
+                index, children, ..
+            } => (*index).max(max_tab_stop(children)),
+            Node::Variable {
+                default: Some(d), ..
+            } => max_tab_stop(d),
+            _ => 0,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Pre-render placeholder defaults so bare `$n` mirrors show the same text
+fn collect_placeholder_text(
+    nodes: &[Node],
+    context: &VariableContext,
+    out: &mut HashMap<u32, String>,
+) {
+    for node in nodes {
+        match node {
+            Node::TabStop {
+                index, children, ..
+            } if !children.is_empty() => {
+                if !out.contains_key(index) {
+                    let mut expander = Expander {
+                        context,
+                        text: String::new(),
+                        offset: 0,
+                        stops: BTreeMap::new(),
+                        placeholder_text: HashMap::new(),
+                        next_synthetic_index: u32::MAX / 2,
+                    };
+                    expander.render(children);
+                    out.insert(*index, expander.text);
+                }
+                collect_placeholder_text(children, context, out);
+            }
+            Node::Variable {
+                default: Some(d), ..
+            } => collect_placeholder_text(d, context, out),
+            _ => {}
+        }
+    }
+}
+
+fn expand(body: &str, context: &VariableContext) -> ExpandedSnippet {
+    let nodes = Parser::new(body).parse_all();
+
+    let mut placeholder_text = HashMap::new();
+    collect_placeholder_text(&nodes, context, &mut placeholder_text);
+
+    let mut expander = Expander {
+        context,
+        text: String::new(),
+        offset: 0,
+        stops: BTreeMap::new(),
+        placeholder_text,
+        next_synthetic_index: max_tab_stop(&nodes) + 1,
+    };
+    expander.render(&nodes);
+
+    let mut tab_stops: Vec<TabStop> = expander.stops.into_values().collect();
+    // $0 is the final cursor position and is visited last
+    if let Some(pos) = tab_stops.iter().position(|s| s.index == 0) {
+        let final_stop = tab_stops.remove(pos);
+        tab_stops.push(final_stop);
+    }
+
+    ExpandedSnippet {
+        text: expander.text,
+        tab_stops,
+    }
+}
+
+// ============================================================================
+// Snippet Loading
+// ============================================================================
+
+fn user_snippets_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".fluxel").join("snippets"))
+}
+
+/// Load all snippet files in a directory
+fn load_snippet_dir(
+    dir: &Path,
+    source: &str,
+    snippets: &mut Vec<Snippet>,
+    errors: &mut Vec<SnippetError>,
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("json" | "code-snippets")
+                )
+        })
+        .collect();
+    files.sort();
+
+    for file in files {
+        load_snippet_file(&file, source, snippets, errors);
+    }
+}
+
+fn load_snippet_file(
+    path: &Path,
+    source: &str,
+    snippets: &mut Vec<Snippet>,
+    errors: &mut Vec<SnippetError>,
+) {
+    let file_path = path.to_string_lossy().replace('\\', "/");
+    let push_error = |errors: &mut Vec<SnippetError>, snippet: Option<&str>, message: String| {
+        errors.push(SnippetError {
+            file_path: file_path.clone(),
+            snippet: snippet.map(|s| s.to_string()),
+            message,
+        });
+    };
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            push_error(errors, None, format!("Failed to read file: {}", e));
+            return;
+        }
+    };
+
+    let raw: BTreeMap<String, RawSnippet> = match serde_json::from_str(&content) {
+        Ok(raw) => raw,
+        Err(e) => {
+            push_error(errors, None, format!("Invalid snippet JSON: {}", e));
+            return;
+        }
+    };
+
+    // `typescript.json` scopes all of its snippets to typescript
+    let file_scope = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| *s != "global")
+            .map(|s| s.to_string()),
+        _ => None,
+    };
+
+    for (name, entry) in raw {
+        let prefixes: Vec<String> = entry
+            .prefix
+            .map(StringOrList::into_vec)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|p| !p.trim().is_empty())
+            .collect();
+        if prefixes.is_empty() {
+            push_error(errors, Some(&name), "Snippet has no prefix".to_string());
+            continue;
+        }
+
+        let body = match entry.body {
+            Some(body) => body.into_vec().join("\n"),
+            None => {
+                push_error(errors, Some(&name), "Snippet has no body".to_string());
+                continue;
+            }
+        };
+
+        let problems = validate_body(&body);
+        if !problems.is_empty() {
+            push_error(errors, Some(&name), problems.join("; "));
+            continue;
+        }
+
+        let scopes = match (&entry.scope, &file_scope) {
+            (Some(scope), _) => scope
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            (None, Some(scope)) => vec![scope.clone()],
+            (None, None) => Vec::new(),
+        };
+
+        snippets.push(Snippet {
+            name,
+            prefixes,
+            body,
+            description: entry.description.map(|d| d.into_vec().join("\n")),
+            scopes,
+            source: source.to_string(),
+            file_path: file_path.clone(),
+        });
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List user and workspace snippets, optionally filtered to a language.
+/// Workspace snippets come after user snippets so they take precedence on prefix clashes.
+#[fluxel_command]
+pub async fn list_snippets(
+    workspace_root: Option<String>,
+    language: Option<String>,
+) -> Result<SnippetListResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut snippets = Vec::new();
+        let mut errors = Vec::new();
+
+        if let Some(dir) = user_snippets_dir() {
+            load_snippet_dir(&dir, "user", &mut snippets, &mut errors);
+        }
+        if let Some(root) = workspace_root.as_ref() {
+            let dir = Path::new(root).join(".fluxel").join("snippets");
+            load_snippet_dir(&dir, "workspace", &mut snippets, &mut errors);
+        }
+
+        if let Some(language) = language {
+            snippets.retain(|s| s.scopes.is_empty() || s.scopes.contains(&language));
+        }
+
+        SnippetListResult { snippets, errors }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Get the user snippets directory (`~/.fluxel/snippets`)
+#[fluxel_command]
+pub fn get_user_snippets_path() -> Result<String, String> {
+    user_snippets_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+/// Expand a snippet body into text plus tab stop ranges.
+///
+/// # Arguments
+/// * `body` - The snippet body (TextMate syntax)
+/// * `variables` - Editor-provided variables (e.g., TM_SELECTED_TEXT, TM_CURRENT_LINE)
+/// * `file_path` - File the snippet is inserted into (for TM_FILENAME etc.)
+/// * `workspace_root` - Workspace root (for WORKSPACE_NAME, GIT_BRANCH)
+#[fluxel_command]
+pub async fn expand_snippet(
+    body: String,
+    variables: Option<HashMap<String, String>>,
+    file_path: Option<String>,
+    workspace_root: Option<String>,
+) -> Result<ExpandedSnippet, String> {
+    let problems = validate_body(&body);
+    if !problems.is_empty() {
+        return Err(format!("Invalid snippet: {}", problems.join("; ")));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let context = VariableContext {
+            provided: variables.unwrap_or_default(),
+            file_path: file_path.map(PathBuf::from),
+            workspace_root: workspace_root.map(PathBuf::from),
+        };
+        expand(&body, &context)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_plain(body: &str) -> ExpandedSnippet {
+        expand(body, &VariableContext::default())
+    }
+
+    #[test]
+    fn expands_tab_stops_and_placeholders() {
+        let result = expand_plain("for (let ${1:i} = 0; $1 < ${2:n}; $1++) {\n\t$0\n}");
+        assert_eq!(result.text, "for (let i = 0; i < n; i++) {\n\t\n}");
+
+        let first = &result.tab_stops[0];
+        assert_eq!(first.index, 1);
+        assert_eq!(first.ranges.len(), 3);
+        assert_eq!(first.ranges[0], TextRange { start: 9, end: 10 });
+
+        // $0 is always visited last
+        assert_eq!(result.tab_stops.last().unwrap().index, 0);
+    }
+
+    #[test]
+    fn expands_choices_with_first_option() {
+        let result = expand_plain("${1|let,const,var|} x = $0;");
+        assert_eq!(result.text, "let x = ;");
+        assert_eq!(
+            result.tab_stops[0].choices,
+            Some(vec![
+                "let".to_string(),
+                "const".to_string(),
+                "var".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn resolves_file_variables_and_transforms() {
+        let context = VariableContext {
+            provided: HashMap::new(),
+            file_path: Some(PathBuf::from("/proj/src/user_service.ts")),
+            workspace_root: Some(PathBuf::from("/proj")),
+        };
+        let result = expand(
+            "// ${TM_FILENAME} in $WORKSPACE_NAME\nclass ${TM_FILENAME_BASE/(.*)/${1:/capitalize}/} {}",
+            &context,
+        );
+        assert_eq!(
+            result.text,
+            "// user_service.ts in proj\nclass User_service {}"
+        );
+    }
+
+    #[test]
+    fn unknown_variables_become_placeholders() {
+        let result = expand_plain("$1 ${UNKNOWN_VAR}");
+        assert_eq!(result.text, " UNKNOWN_VAR");
+        assert!(result.tab_stops.iter().any(|s| s.index == 2));
+    }
+
+    #[test]
+    fn escapes_and_invalid_syntax_are_literal() {
+        let result = expand_plain("cost: \\$5 and $ alone");
+        assert_eq!(result.text, "cost: $5 and $ alone");
+    }
+
+    #[test]
+    fn reports_unclosed_placeholders() {
+        assert!(!validate_body("${1:oops").is_empty());
+        assert!(validate_body("${1:ok} $2").is_empty());
+    }
+}
@@ -0,0 +1,478 @@
+//! Spell Check Service
+//!
+//! Hunspell dictionary based spell checking for comments, strings, and markdown.
+//! Dictionaries (`<lang>.aff` + `<lang>.dic`) are looked up in the bundled
+//! `dictionaries/` resource folder, `~/.fluxel/dictionaries/`, and the system
+//! Hunspell directories. Words added by the user go to
+//! `~/.fluxel/dictionaries/user-words.txt` or, when scoped to a workspace,
+//! `<workspace>/.fluxel/dictionary.txt`.
+
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use spellbook::Dictionary;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+const DEFAULT_LANGUAGE: &str = "en_US";
+const USER_WORDS_FILE: &str = "user-words.txt";
+
+/// Maximum suggestions returned per misspelled word
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Suggestions are expensive; only compute them for this many distinct words per call
+const MAX_SUGGESTED_WORDS: usize = 100;
+
+/// A misspelled word in the checked text.
+/// Offsets are UTF-16 code units, matching Monaco model offsets.
+#[derive(Debug, Clone, Serialize)]
+pub struct Misspelling {
+    pub word: String,
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+/// A dictionary available for spell checking
+#[derive(Debug, Clone, Serialize)]
+pub struct SpellLanguage {
+    pub language: String,
+    pub path: String,
+}
+
+/// Cached dictionaries and user words
+#[derive(Clone, Default)]
+pub struct SpellCheckState {
+    dictionaries: Arc<RwLock<HashMap<String, Arc<Dictionary>>>>,
+    user_words: Arc<RwLock<Option<HashSet<String>>>>,
+}
+
+impl SpellCheckState {
+    pub fn new() -> Self {
+        Self {
+            dictionaries: Arc::new(RwLock::new(HashMap::new())),
+            user_words: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Get a cached dictionary or load it from the first search directory that has it
+    fn dictionary(
+        &self,
+        language: &str,
+        search_dirs: &[PathBuf],
+    ) -> Result<Arc<Dictionary>, String> {
+        if let Some(dict) = self.dictionaries.read().unwrap().get(language) {
+            return Ok(Arc::clone(dict));
+        }
+
+        let (aff_path, dic_path) = find_dictionary(language, search_dirs)
+            .ok_or_else(|| format!("No Hunspell dictionary found for '{}'", language))?;
+
+        let aff = std::fs::read_to_string(&aff_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", aff_path, e))?;
+        let dic = std::fs::read_to_string(&dic_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", dic_path, e))?;
+        let dict = Arc::new(
+            Dictionary::new(&aff, &dic)
+                .map_err(|e| format!("Failed to parse dictionary '{}': {}", language, e))?,
+        );
+
+        println!(
+            "[SpellCheck] Loaded dictionary '{}' from {:?}",
+            language, dic_path
+        );
+        self.dictionaries
+            .write()
+            .unwrap()
+            .insert(language.to_string(), Arc::clone(&dict));
+        Ok(dict)
+    }
+
+    fn user_words(&self) -> HashSet<String> {
+        if let Some(words) = self.user_words.read().unwrap().as_ref() {
+            return words.clone();
+        }
+        let words = user_dictionary_dir()
+            .map(|dir| read_word_list(&dir.join(USER_WORDS_FILE)))
+            .unwrap_or_default();
+        *self.user_words.write().unwrap() = Some(words.clone());
+        words
+    }
+
+    /// Lowercased user words, plus the workspace dictionary when a root is given
+    pub(crate) fn known_words(&self, workspace_root: Option<&str>) -> HashSet<String> {
+        let mut words = self.user_words();
+        if let Some(root) = workspace_root {
+            words.extend(read_word_list(&workspace_dictionary_path(root)));
+        }
+        words
+    }
+
+    fn invalidate_user_words(&self) {
+        *self.user_words.write().unwrap() = None;
+    }
+}
+
+// ============================================================================
+// Dictionary Discovery
+// ============================================================================
+
+fn user_dictionary_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".fluxel").join("dictionaries"))
+}
+
+fn workspace_dictionary_path(root: &str) -> PathBuf {
+    Path::new(root).join(".fluxel").join("dictionary.txt")
+}
+
+/// Directories searched for `<lang>.aff`/`<lang>.dic`, in priority order
+fn dictionary_search_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(resources) = app.path().resource_dir() {
+        dirs.push(resources.join("dictionaries"));
+    }
+    if let Some(user_dir) = user_dictionary_dir() {
+        dirs.push(user_dir);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            dirs.push(home.join("Library").join("Spelling"));
+        }
+        dirs.push(PathBuf::from("/Library/Spelling"));
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        dirs.push(PathBuf::from("/usr/share/hunspell"));
+        dirs.push(PathBuf::from("/usr/share/myspell"));
+        dirs.push(PathBuf::from("/usr/share/myspell/dicts"));
+    }
+    dirs
+}
+
+/// Normalize `en-us` / `en_US` style tags to Hunspell file naming (`en_US`)
+fn normalize_language(language: &str) -> String {
+    let mut parts = language.split(['-', '_']);
+    let lang = parts.next().unwrap_or_default().to_ascii_lowercase();
+    match parts.next() {
+        Some(region) => format!("{}_{}", lang, region.to_ascii_uppercase()),
+        None => lang,
+    }
+}
+
+fn find_dictionary(language: &str, search_dirs: &[PathBuf]) -> Option<(PathBuf, PathBuf)> {
+    search_dirs.iter().find_map(|dir| {
+        let aff = dir.join(format!("{}.aff", language));
+        let dic = dir.join(format!("{}.dic", language));
+        (aff.is_file() && dic.is_file()).then_some((aff, dic))
+    })
+}
+
+/// Read a newline-separated word list, ignoring blanks and `#` comments
+fn read_word_list(path: &Path) -> HashSet<String> {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(|w| w.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Tokenization
+// ============================================================================
+
+/// A word candidate with UTF-16 offsets into the source text
+#[derive(Debug, PartialEq)]
+struct WordToken {
+    word: String,
+    start: usize,
+    end: usize,
+}
+
+/// Runs that look like URLs, emails, or paths are not prose
+fn is_non_prose_run(run: &str) -> bool {
+    run.contains("://") || run.contains('@') || run.contains('/') || run.contains('\\')
+}
+
+/// Split a word at camelCase boundaries (`parseJSONValue` -> `parse`, `JSON`, `Value`)
+fn split_camel_case(chars: &[(char, usize)]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let prev = chars[i - 1].0;
+        let cur = chars[i].0;
+        let next_is_lower = chars
+            .get(i + 1)
+            .map(|c| c.0.is_lowercase())
+            .unwrap_or(false);
+        let boundary = (prev.is_lowercase() && cur.is_uppercase())
+            || (prev.is_uppercase() && cur.is_uppercase() && next_is_lower);
+        if boundary {
+            ranges.push(start..i);
+            start = i;
+        }
+    }
+    ranges.push(start..chars.len());
+    ranges
+}
+
+/// Emit the prose sub-words of a letter run, skipping single letters and acronyms
+fn push_word_tokens(word: &[(char, usize)], tokens: &mut Vec<WordToken>) {
+    let trimmed_len = word.len() - word.iter().rev().take_while(|(c, _)| *c == '\'').count();
+    let word = &word[..trimmed_len];
+    if word.is_empty() {
+        return;
+    }
+    for range in split_camel_case(word) {
+        let part = &word[range];
+        if part.len() < 2 || part.iter().all(|(c, _)| !c.is_lowercase()) {
+            continue;
+        }
+        let (last_char, last_pos) = part[part.len() - 1];
+        tokens.push(WordToken {
+            word: part.iter().map(|(c, _)| *c).collect(),
+            start: part[0].1,
+            end: last_pos + last_char.len_utf16(),
+        });
+    }
+}
+
+/// Extract checkable words from prose or code comments
+fn tokenize(text: &str) -> Vec<WordToken> {
+    let mut tokens = Vec::new();
+
+    // Whitespace-separated runs, as (char, UTF-16 offset) pairs
+    let mut runs: Vec<Vec<(char, usize)>> = vec![Vec::new()];
+    let mut offset = 0usize;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            runs.push(Vec::new());
+        } else if let Some(run) = runs.last_mut() {
+            run.push((c, offset));
+        }
+        offset += c.len_utf16();
+    }
+
+    for run in runs {
+        let run_text: String = run.iter().map(|(c, _)| *c).collect();
+        if run.is_empty() || is_non_prose_run(&run_text) {
+            continue;
+        }
+
+        let mut word: Vec<(char, usize)> = Vec::new();
+        // Letters touching digits or underscores form identifiers, not prose
+        let mut identifier = false;
+        for &(c, pos) in &run {
+            if c.is_alphabetic() || (c == '\'' && !word.is_empty()) {
+                word.push((c, pos));
+            } else if c.is_ascii_digit() || c == '_' {
+                identifier = true;
+            } else {
+                if !identifier {
+                    push_word_tokens(&word, &mut tokens);
+                }
+                word.clear();
+                identifier = false;
+            }
+        }
+        if !identifier {
+            push_word_tokens(&word, &mut tokens);
+        }
+    }
+    tokens
+}
+
+fn check_with(dict: &Dictionary, text: &str, ignored: &HashSet<String>) -> Vec<Misspelling> {
+    let mut results = Vec::new();
+    let mut suggestion_cache: HashMap<String, Vec<String>> = HashMap::new();
+
+    for token in tokenize(text) {
+        if ignored.contains(&token.word.to_lowercase()) || dict.check(&token.word) {
+            continue;
+        }
+
+        let suggestions = match suggestion_cache.get(&token.word) {
+            Some(cached) => cached.clone(),
+            None if suggestion_cache.len() < MAX_SUGGESTED_WORDS => {
+                let mut suggestions = Vec::new();
+                dict.suggest(&token.word, &mut suggestions);
+                suggestions.truncate(MAX_SUGGESTIONS);
+                suggestion_cache.insert(token.word.clone(), suggestions.clone());
+                suggestions
+            }
+            None => Vec::new(),
+        };
+
+        results.push(Misspelling {
+            word: token.word,
+            start: token.start,
+            end: token.end,
+            suggestions,
+        });
+    }
+    results
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check text and return misspelled words with suggestions.
+///
+/// # Arguments
+/// * `text` - Prose to check (comment/string contents or markdown)
+/// * `language` - Dictionary language, e.g. "en_US" (default)
+/// * `workspace_root` - Include the workspace dictionary when provided
+#[fluxel_command]
+pub async fn check_text<R: Runtime>(
+    app: AppHandle<R>,
+    text: String,
+    language: Option<String>,
+    workspace_root: Option<String>,
+    state: State<'_, SpellCheckState>,
+) -> Result<Vec<Misspelling>, String> {
+    let language = normalize_language(language.as_deref().unwrap_or(DEFAULT_LANGUAGE));
+    let search_dirs = dictionary_search_dirs(&app);
+    let state = state.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let dict = state.dictionary(&language, &search_dirs)?;
+        let ignored = state.known_words(workspace_root.as_deref());
+        Ok(check_with(&dict, &text, &ignored))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Add a word to the user dictionary, or to the workspace dictionary when a root is given
+#[fluxel_command]
+pub async fn add_to_dictionary(
+    word: String,
+    workspace_root: Option<String>,
+    state: State<'_, SpellCheckState>,
+) -> Result<(), String> {
+    let word = word.trim().to_string();
+    if word.is_empty() || word.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid dictionary word: '{}'", word));
+    }
+
+    let path = match workspace_root.as_ref() {
+        Some(root) => workspace_dictionary_path(root),
+        None => user_dictionary_dir()
+            .ok_or("Failed to get home directory")?
+            .join(USER_WORDS_FILE),
+    };
+
+    let existing = read_word_list(&path);
+    if existing.contains(&word.to_lowercase()) {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&word);
+    content.push('\n');
+    std::fs::write(&path, content).map_err(|e| format!("Failed to update dictionary: {}", e))?;
+
+    if workspace_root.is_none() {
+        state.invalidate_user_words();
+    }
+    Ok(())
+}
+
+/// List dictionaries available in the search directories
+#[fluxel_command]
+pub async fn list_spell_languages<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<SpellLanguage>, String> {
+    let search_dirs = dictionary_search_dirs(&app);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut seen = HashSet::new();
+        let mut languages = Vec::new();
+        for dir in search_dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut found: Vec<_> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("dic"))
+                .filter(|p| p.with_extension("aff").is_file())
+                .collect();
+            found.sort();
+            for dic in found {
+                let Some(language) = dic.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if seen.insert(language.to_string()) {
+                    languages.push(SpellLanguage {
+                        language: language.to_string(),
+                        path: dic.to_string_lossy().replace('\\', "/"),
+                    });
+                }
+            }
+        }
+        languages
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dictionary() -> Dictionary {
+        let aff = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwz\n";
+        let dic = "6\nhello\nworld\nparse\nvalue\nthe\nfunction\n";
+        Dictionary::new(aff, dic).expect("test dictionary should parse")
+    }
+
+    #[test]
+    fn tokenizes_prose_with_utf16_offsets() {
+        let tokens = tokenize("héllo wörld");
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[1].word, "wörld");
+        assert_eq!((tokens[1].start, tokens[1].end), (6, 11));
+    }
+
+    #[test]
+    fn splits_camel_case_and_skips_non_prose() {
+        let words: Vec<String> = tokenize("parseJSONValue see https://example.com/x user_id v2 OK")
+            .into_iter()
+            .map(|t| t.word)
+            .collect();
+        assert_eq!(words, vec!["parse", "Value", "see"]);
+    }
+
+    #[test]
+    fn reports_misspellings_and_respects_ignored_words() {
+        let dict = test_dictionary();
+        let mut ignored = HashSet::new();
+        ignored.insert("fluxel".to_string());
+
+        let results = check_with(&dict, "hello wrld from Fluxel", &ignored);
+        let words: Vec<&str> = results.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(words, vec!["wrld", "from"]);
+        assert_eq!((results[0].start, results[0].end), (6, 10));
+        assert!(results[0].suggestions.contains(&"world".to_string()));
+    }
+
+    #[test]
+    fn normalizes_language_tags() {
+        assert_eq!(normalize_language("en-us"), "en_US");
+        assert_eq!(normalize_language("de_DE"), "de_DE");
+        assert_eq!(normalize_language("fr"), "fr");
+    }
+}
@@ -0,0 +1,201 @@
+//! SSH Hosts
+//!
+//! Host profiles from `~/.ssh/config` and commands that run on them through
+//! the system `ssh` client, so terminal sessions can target a remote machine.
+//! Keys, agents, jump hosts, and known hosts all come from the user's OpenSSH
+//! setup. Where OpenSSH supports it, one multiplexed connection per host is
+//! kept open and shared by every command run on that host.
+
+use fluxel_macros::fluxel_command;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How long an idle shared connection stays open
+const CONTROL_PERSIST: &str = "10m";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHost {
+    /// `Host` alias, passed to `ssh` as the destination
+    pub alias: String,
+    pub host_name: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+}
+
+/// Named `Host` entries of an OpenSSH config; wildcard patterns are skipped
+fn parse_ssh_config(content: &str) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = Vec::new();
+    // Indices into `hosts` of the block being read
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((keyword, value)) => (
+                keyword.to_lowercase(),
+                value
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+                    .trim(),
+            ),
+            None => continue,
+        };
+
+        match keyword.as_str() {
+            "host" => {
+                current.clear();
+                for alias in value.split_whitespace() {
+                    if alias.contains(['*', '?', '!']) {
+                        continue;
+                    }
+                    let index = match hosts.iter().position(|h| h.alias == alias) {
+                        Some(index) => index,
+                        None => {
+                            hosts.push(SshHost {
+                                alias: alias.to_string(),
+                                host_name: None,
+                                user: None,
+                                port: None,
+                            });
+                            hosts.len() - 1
+                        }
+                    };
+                    current.push(index);
+                }
+            }
+            "match" => current.clear(),
+            // OpenSSH keeps the first value given for each option
+            "hostname" => {
+                for &index in &current {
+                    hosts[index]
+                        .host_name
+                        .get_or_insert_with(|| value.to_string());
+                }
+            }
+            "user" => {
+                for &index in &current {
+                    hosts[index].user.get_or_insert_with(|| value.to_string());
+                }
+            }
+            "port" => {
+                for &index in &current {
+                    if hosts[index].port.is_none() {
+                        hosts[index].port = value.parse().ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    hosts
+}
+
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh"))
+}
+
+/// Host profiles from the user's `~/.ssh/config`
+pub fn configured_hosts() -> Vec<SshHost> {
+    ssh_dir()
+        .map(|dir| dir.join("config"))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|content| parse_ssh_config(&content))
+        .unwrap_or_default()
+}
+
+fn ssh_args(host: &str, control_dir: Option<&Path>, command_line: &str) -> Vec<String> {
+    // No TTY and no prompts: output is streamed through pipes, and a password
+    // prompt would hang with nothing to answer it
+    let mut args = vec!["-T", "-o", "BatchMode=yes"]
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if let Some(dir) = control_dir {
+        args.extend([
+            "-o".to_string(),
+            "ControlMaster=auto".to_string(),
+            "-o".to_string(),
+            format!("ControlPath={}", dir.join("%C").to_string_lossy()),
+            "-o".to_string(),
+            format!("ControlPersist={}", CONTROL_PERSIST),
+        ]);
+    }
+    args.extend([host.to_string(), "--".to_string(), command_line.to_string()]);
+    args
+}
+
+/// Command that runs `command_line` in the login shell of `host`. Fails for
+/// hosts that are not in `~/.ssh/config`.
+pub fn ssh_command(host: &str, command_line: &str) -> Result<Command, String> {
+    if !configured_hosts().iter().any(|h| h.alias == host) {
+        return Err(format!("Unknown SSH host: {}", host));
+    }
+
+    // Windows OpenSSH has no connection multiplexing
+    let control_dir = if cfg!(windows) {
+        None
+    } else {
+        dirs::home_dir()
+            .map(|home| home.join(".fluxel").join("ssh"))
+            .filter(|dir| std::fs::create_dir_all(dir).is_ok())
+    };
+
+    let mut cmd = Command::new("ssh");
+    cmd.args(ssh_args(host, control_dir.as_deref(), command_line));
+    Ok(cmd)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the SSH hosts terminals can be opened on
+#[fluxel_command]
+pub async fn list_ssh_hosts() -> Result<Vec<SshHost>, String> {
+    tauri::async_runtime::spawn_blocking(configured_hosts)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_host_profiles() {
+        let config = "\
+# Personal
+Host dev devbox
+    HostName 10.0.0.5
+    User me
+    Port 2222
+
+Host *.internal !bastion
+    User ops
+
+Host bastion
+  HostName=bastion.example.com
+  User first
+  User second
+
+Host *
+    User fallback
+";
+        let hosts = parse_ssh_config(config);
+        let aliases: Vec<&str> = hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["dev", "devbox", "bastion"]);
+        assert_eq!(hosts[1].host_name.as_deref(), Some("10.0.0.5"));
+        assert_eq!(hosts[1].port, Some(2222));
+        assert_eq!(hosts[2].host_name.as_deref(), Some("bastion.example.com"));
+        assert_eq!(hosts[2].user.as_deref(), Some("first"));
+
+        let args = ssh_args("dev", Some(Path::new("/tmp/ssh")), "ls -la");
+        assert_eq!(args[..3], ["-T", "-o", "BatchMode=yes"]);
+        assert!(args.contains(&"ControlMaster=auto".to_string()));
+        assert_eq!(args[args.len() - 3..], ["dev", "--", "ls -la"]);
+    }
+}
@@ -0,0 +1,380 @@
+//! Structured AI Output
+//!
+//! Chat requests whose reply must be JSON matching a schema. The schema is
+//! passed to the provider's own mechanism (Ollama's `format`, MiniMax's
+//! `json_schema` response format) and repeated in the system prompt; replies
+//! are validated with the schema validator and, when they don't match, the
+//! model is asked to repair its answer before an error is returned.
+
+use crate::commands::minimax::{minimax_chat, MinimaxMessage, MinimaxRequest, SamplingParameters};
+use crate::languages::schemas::document::{self, Format};
+use crate::languages::schemas::validator::Validator;
+use crate::services::ai_metrics::{
+    estimate_tokens, AiMetricsState, AiRequestSample, AiRequestSpan,
+};
+use crate::services::inline_completion::{
+    record_ollama_usage, InlineProvider, InlineProviderConfig,
+};
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+const DEFAULT_OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+const DEFAULT_OLLAMA_MODEL: &str = "qwen2.5-coder:1.5b";
+const DEFAULT_REPAIR_ATTEMPTS: u32 = 2;
+const MAX_REPAIR_ATTEMPTS: u32 = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+/// Validation errors quoted back to the model in a repair prompt
+const MAX_REPORTED_ERRORS: usize = 10;
+/// Characters of the offending JSON quoted with each error
+const MAX_SNIPPET_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatTurn {
+    /// `system`, `user` or `assistant`
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredChatRequest {
+    #[serde(default)]
+    pub provider: InlineProviderConfig,
+    pub messages: Vec<ChatTurn>,
+    /// JSON Schema the reply must satisfy
+    pub response_schema: Value,
+    /// Repair prompts sent after invalid replies (default 2)
+    pub max_repairs: Option<u32>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredChatResponse {
+    /// The validated reply
+    pub value: Value,
+    /// Reply text the value was parsed from
+    pub raw: String,
+    /// Requests made, including repairs
+    pub attempts: u32,
+    pub model: String,
+}
+
+/// The JSON document in a reply, without reasoning blocks, code fences, or
+/// prose around it
+pub fn extract_json(reply: &str) -> &str {
+    static THINK: OnceLock<Regex> = OnceLock::new();
+    let think = THINK.get_or_init(|| Regex::new(r"(?s)^\s*<think>.*?</think>").unwrap());
+    let reply = match think.find(reply) {
+        Some(block) => &reply[block.end()..],
+        None => reply,
+    };
+    let start = reply.find(['{', '[']);
+    let end = reply.rfind(['}', ']']);
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => reply.trim(),
+    }
+}
+
+/// Parse `reply` and check it against `schema`, describing every problem
+pub fn validate_reply(schema: &Value, reply: &str) -> Result<Value, Vec<String>> {
+    let json = extract_json(reply);
+    let node = document::parse(Format::Json, json)
+        .map_err(|e| vec![format!("The reply is not valid JSON: {}", e.message)])?;
+    let errors = Validator::new(schema).validate(&node);
+    if errors.is_empty() {
+        return Ok(node.to_json());
+    }
+    Err(errors
+        .into_iter()
+        .map(|error| {
+            let snippet: String = json
+                .get(error.range.clone())
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_SNIPPET_CHARS)
+                .collect();
+            if snippet.trim().is_empty() {
+                error.message
+            } else {
+                format!("{} (at `{}`)", error.message, snippet)
+            }
+        })
+        .collect())
+}
+
+fn schema_instruction(schema: &Value) -> String {
+    format!(
+        "Reply with a single JSON value that validates against this JSON Schema. \
+         Output only the JSON, with no code fences or commentary.\n\n{}",
+        serde_json::to_string_pretty(schema).unwrap_or_default()
+    )
+}
+
+fn repair_prompt(errors: &[String]) -> String {
+    let listed: Vec<String> = errors
+        .iter()
+        .take(MAX_REPORTED_ERRORS)
+        .map(|error| format!("- {}", error))
+        .collect();
+    format!(
+        "Your reply does not match the required JSON Schema:\n{}\n\n\
+         Reply again with only the corrected JSON.",
+        listed.join("\n")
+    )
+}
+
+async fn chat_ollama(
+    client: &reqwest::Client,
+    config: &InlineProviderConfig,
+    model: &str,
+    messages: &[ChatTurn],
+    request: &StructuredChatRequest,
+) -> Result<String, String> {
+    let endpoint = config
+        .endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .unwrap_or(DEFAULT_OLLAMA_ENDPOINT)
+        .trim_end_matches('/');
+    let mut options = serde_json::json!({ "temperature": request.temperature.unwrap_or(0.0) });
+    if let Some(max_tokens) = request.max_tokens {
+        options["num_predict"] = max_tokens.into();
+    }
+    let body = serde_json::json!({
+        "model": model,
+        "messages": messages
+            .iter()
+            .map(|turn| serde_json::json!({ "role": turn.role, "content": turn.content }))
+            .collect::<Vec<_>>(),
+        "stream": false,
+        "format": request.response_schema,
+        "options": options,
+    });
+    let prompt_tokens = messages
+        .iter()
+        .map(|turn| estimate_tokens(&turn.content))
+        .sum();
+    let span = AiRequestSpan::start("ollama", model, prompt_tokens);
+
+    let response = client
+        .post(format!("{}/api/chat", endpoint))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama request failed: {}", e))?;
+    let status = response.status();
+    let value: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))?;
+    if !status.is_success() {
+        span.finish(None);
+        return Err(format!("Ollama error {}: {}", status, value));
+    }
+    let text = value
+        .pointer("/message/content")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    record_ollama_usage(span, &value, &text);
+    Ok(text)
+}
+
+async fn chat_minimax(
+    config: &InlineProviderConfig,
+    model: &str,
+    messages: &[ChatTurn],
+    request: &StructuredChatRequest,
+) -> Result<String, String> {
+    let api_key = config
+        .api_key
+        .clone()
+        .filter(|key| !key.trim().is_empty())
+        .ok_or("MiniMax requires an API key")?;
+    let request = MinimaxRequest {
+        model: model.to_string(),
+        messages: messages
+            .iter()
+            .map(|turn| MinimaxMessage {
+                role: turn.role.clone(),
+                content: Some(turn.content.clone().into()),
+                tool_calls: None,
+                tool_call_id: None,
+                name: None,
+            })
+            .collect(),
+        max_tokens: request.max_tokens,
+        temperature: Some(request.temperature.unwrap_or(0.1)),
+        tools: None,
+        response_schema: Some(request.response_schema.clone()),
+        sampling: SamplingParameters::default(),
+    };
+    let response = minimax_chat(api_key, request, config.endpoint.clone()).await?;
+    Ok(response
+        .get("content")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Run a structured chat, recording each request in `metrics` under `feature`
+pub(crate) async fn structured_chat(
+    request: StructuredChatRequest,
+    metrics: &AiMetricsState,
+    feature: &str,
+) -> Result<StructuredChatResponse, String> {
+    if request.messages.is_empty() {
+        return Err("No messages to send".to_string());
+    }
+    if !request.response_schema.is_object() && !request.response_schema.is_boolean() {
+        return Err("Response schema must be a JSON object".to_string());
+    }
+    let config = request.provider.clone();
+    let (provider_name, model) = match config.provider {
+        InlineProvider::Ollama => (
+            "ollama",
+            config
+                .model
+                .clone()
+                .filter(|model| !model.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+        ),
+        InlineProvider::Minimax => ("minimax", config.model.clone().unwrap_or_default()),
+    };
+    let repairs = request
+        .max_repairs
+        .unwrap_or(DEFAULT_REPAIR_ATTEMPTS)
+        .min(MAX_REPAIR_ATTEMPTS);
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut messages = vec![ChatTurn {
+        role: "system".to_string(),
+        content: schema_instruction(&request.response_schema),
+    }];
+    messages.extend(request.messages.iter().cloned());
+
+    let mut last_errors = Vec::new();
+    for attempt in 1..=repairs + 1 {
+        let started = Instant::now();
+        let reply = match config.provider {
+            InlineProvider::Ollama => {
+                chat_ollama(&client, &config, &model, &messages, &request).await
+            }
+            InlineProvider::Minimax => chat_minimax(&config, &model, &messages, &request).await,
+        };
+        metrics.record(AiRequestSample::completed(
+            provider_name,
+            &model,
+            feature,
+            started.elapsed(),
+            reply.as_deref().ok(),
+        ));
+        let reply = reply?;
+
+        match validate_reply(&request.response_schema, &reply) {
+            Ok(value) => {
+                return Ok(StructuredChatResponse {
+                    value,
+                    raw: reply,
+                    attempts: attempt,
+                    model,
+                })
+            }
+            Err(errors) => {
+                println!(
+                    "[StructuredOutput] Attempt {} did not match the schema: {}",
+                    attempt,
+                    errors.join("; ")
+                );
+                messages.push(ChatTurn {
+                    role: "assistant".to_string(),
+                    content: reply,
+                });
+                messages.push(ChatTurn {
+                    role: "user".to_string(),
+                    content: repair_prompt(&errors),
+                });
+                last_errors = errors;
+            }
+        }
+    }
+
+    Err(format!(
+        "Reply did not match the schema after {} attempts: {}",
+        repairs + 1,
+        last_errors.join("; ")
+    ))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Chat completion whose reply must be JSON matching `responseSchema`
+///
+/// # Arguments
+/// * `request` - Provider, conversation, schema, and repair budget
+#[fluxel_command]
+pub async fn ai_structured_chat(
+    request: StructuredChatRequest,
+    metrics: State<'_, AiMetricsState>,
+) -> Result<StructuredChatResponse, String> {
+    structured_chat(request, &metrics, "structuredOutput").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_and_describes_replies() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "severity": { "enum": ["low", "high"] },
+                "lines": { "type": "array", "items": { "type": "integer" } }
+            },
+            "required": ["title", "severity"],
+            "additionalProperties": false
+        });
+
+        assert_eq!(
+            extract_json("<think>{ not this }</think>\n```json\n{\"a\": 1}\n```"),
+            "{\"a\": 1}"
+        );
+        assert_eq!(extract_json("Here you go: [1, 2]."), "[1, 2]");
+
+        let value = validate_reply(
+            &schema,
+            "```json\n{\"title\": \"Leak\", \"severity\": \"high\", \"lines\": [3]}\n```",
+        )
+        .unwrap();
+        assert_eq!(value["lines"], json!([3]));
+
+        let errors = validate_reply(&schema, r#"{"title": 5, "lines": ["x"]}"#).unwrap_err();
+        assert!(errors.len() >= 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("severity")));
+        assert!(errors.iter().any(|e| e.contains("`5`")));
+
+        let errors = validate_reply(&schema, "{\"title\": ").unwrap_err();
+        assert!(errors[0].starts_with("The reply is not valid JSON"));
+
+        let prompt = repair_prompt(&errors);
+        assert!(prompt.contains("- The reply is not valid JSON"));
+        assert!(schema_instruction(&schema).contains("\"additionalProperties\": false"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Terminal History Service
+//!
+//! Records commands run through the terminal, with when they ran and how they
+//! exited, per workspace in `~/.fluxel/terminal/<workspace-hash>.json`. The
+//! terminal panel reads it back for up-arrow history and command search across
+//! restarts.
+
+use crate::services::workspace_store::{WorkspaceFile, WorkspaceStore};
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const STORE_VERSION: u32 = 1;
+/// Commands kept per workspace; older entries are dropped
+const MAX_ENTRIES: usize = 1000;
+/// Entries returned when no limit is given
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalHistoryEntry {
+    pub id: String,
+    /// Command line as typed, before secret references are substituted
+    pub command: String,
+    pub cwd: Option<String>,
+    /// Start time (unix milliseconds)
+    pub started_at: u64,
+    /// Exit time (unix milliseconds); `None` while running or if Fluxel closed first
+    pub finished_at: Option<u64>,
+    /// `None` while running, or when the process was killed by a signal
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    version: u32,
+    workspace: String,
+    /// Oldest first
+    entries: Vec<TerminalHistoryEntry>,
+}
+
+// ============================================================================
+// History Store
+// ============================================================================
+
+impl WorkspaceFile for HistoryFile {
+    const VERSION: u32 = STORE_VERSION;
+    const LABEL: &'static str = "terminal history";
+
+    fn empty(workspace: String) -> Self {
+        Self {
+            version: STORE_VERSION,
+            workspace,
+            ..Default::default()
+        }
+    }
+}
+
+type HistoryStore = WorkspaceStore<HistoryFile>;
+
+fn push_entry(file: &mut HistoryFile, entry: TerminalHistoryEntry) {
+    file.entries.push(entry);
+    if file.entries.len() > MAX_ENTRIES {
+        let excess = file.entries.len() - MAX_ENTRIES;
+        file.entries.drain(..excess);
+    }
+}
+
+fn finish_entry(file: &mut HistoryFile, id: &str, exit_code: Option<i32>) {
+    if let Some(entry) = file.entries.iter_mut().rev().find(|e| e.id == id) {
+        entry.finished_at = Some(now_millis());
+        entry.exit_code = exit_code;
+    }
+}
+
+/// Most recent first, optionally only commands containing `filter` (ignoring case)
+fn query(file: HistoryFile, filter: Option<&str>, limit: usize) -> Vec<TerminalHistoryEntry> {
+    let filter = filter
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty());
+    file.entries
+        .into_iter()
+        .rev()
+        .filter(|entry| {
+            filter
+                .as_deref()
+                .is_none_or(|f| entry.command.to_lowercase().contains(f))
+        })
+        .take(limit)
+        .collect()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Serializes read-modify-write cycles on history files
+#[derive(Clone, Default)]
+pub struct TerminalHistoryState {
+    lock: Arc<Mutex<()>>,
+}
+
+impl TerminalHistoryState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_store<T>(
+        &self,
+        f: impl FnOnce(&HistoryStore) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        f(&HistoryStore::for_user("terminal")?)
+    }
+
+    /// Record a command that just started. Returns the entry id to pass to
+    /// [`TerminalHistoryState::finish`].
+    pub fn start(
+        &self,
+        workspace_root: &Path,
+        command: &str,
+        cwd: Option<&str>,
+        pid: u32,
+    ) -> Result<String, String> {
+        let started_at = now_millis();
+        let id = format!("{}-{}", started_at, pid);
+        let entry = TerminalHistoryEntry {
+            id: id.clone(),
+            command: command.to_string(),
+            cwd: cwd.map(str::to_string),
+            started_at,
+            finished_at: None,
+            exit_code: None,
+        };
+        self.with_store(|store| store.update(workspace_root, |file| push_entry(file, entry)))?;
+        Ok(id)
+    }
+
+    /// Record how a command exited
+    pub fn finish(
+        &self,
+        workspace_root: &Path,
+        id: &str,
+        exit_code: Option<i32>,
+    ) -> Result<(), String> {
+        self.with_store(|store| {
+            store.update(workspace_root, |file| finish_entry(file, id, exit_code))
+        })
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List terminal commands run in a workspace, most recent first
+///
+/// # Arguments
+/// * `workspace_root` - Workspace the commands ran in
+/// * `filter` - Only commands containing this text (case-insensitive)
+/// * `limit` - Maximum entries to return (default 100)
+#[fluxel_command]
+pub async fn get_terminal_history(
+    workspace_root: String,
+    filter: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, TerminalHistoryState>,
+) -> Result<Vec<TerminalHistoryEntry>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        state.with_store(|store| {
+            let file = store.load(Path::new(&workspace_root))?;
+            Ok(query(
+                file,
+                filter.as_deref(),
+                limit.unwrap_or(DEFAULT_LIMIT),
+            ))
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_commands_with_exit_codes() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-terminal-history-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let store = HistoryStore::new(dir.join("store"));
+        let workspace = dir.join("workspace");
+
+        for (index, command) in ["cargo build", "npm test", "cargo test"].iter().enumerate() {
+            let entry = TerminalHistoryEntry {
+                id: index.to_string(),
+                command: command.to_string(),
+                cwd: None,
+                started_at: index as u64,
+                finished_at: None,
+                exit_code: None,
+            };
+            store
+                .update(&workspace, |file| push_entry(file, entry))
+                .unwrap();
+        }
+        store
+            .update(&workspace, |file| finish_entry(file, "1", Some(1)))
+            .unwrap();
+
+        let all = query(store.load(&workspace).unwrap(), None, 10);
+        let commands: Vec<&str> = all.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, vec!["cargo test", "npm test", "cargo build"]);
+        assert_eq!(all[1].exit_code, Some(1));
+        assert!(all[1].finished_at.is_some());
+        assert!(all[0].finished_at.is_none());
+
+        let cargo = query(store.load(&workspace).unwrap(), Some("CARGO"), 1);
+        assert_eq!(cargo.len(), 1);
+        assert_eq!(cargo[0].command, "cargo test");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,349 @@
+//! Terminal Link Detection
+//!
+//! Finds URLs and file references in terminal output so they can be clicked.
+//! File references are recognized in the formats compilers and runtimes print
+//! (`path(line,col)` from MSBuild and tsc, `path:line:col` from cargo, gcc, and
+//! eslint, Python tracebacks, and bare paths) and kept only when the file exists
+//! relative to the terminal's working directory, so version numbers and times
+//! never turn into dead links.
+
+use crate::services::wsl::{wsl_to_windows, wsl_workspace};
+use fluxel_macros::fluxel_command;
+use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Output longer than this is scanned only up to here
+const MAX_SCAN_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalLinkKind {
+    File,
+    Url,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalLink {
+    /// Start of the link text, in UTF-16 code units from the start of the text
+    pub start: usize,
+    /// End of the link text (exclusive), in UTF-16 code units
+    pub end: usize,
+    pub kind: TerminalLinkKind,
+    /// Absolute file path or URL
+    pub target: String,
+    /// 1-based line, for file links that name one
+    pub line: Option<u32>,
+    /// 1-based column, for file links that name one
+    pub column: Option<u32>,
+}
+
+fn url_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"\bhttps?://[^\s<>"'`]+"#).unwrap())
+}
+
+/// `File "path", line N` from Python tracebacks
+fn python_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"File "(?P<path>[^"\r\n]+)", line (?P<line>\d+)"#).unwrap())
+}
+
+/// `path(line)`, `path(line,col)`, or `path(line,col,endLine,endCol)` from
+/// MSBuild and tsc. Paths may contain spaces; the exact start is settled by
+/// checking which suffix exists.
+fn msbuild_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?P<path>[^\s()<>|]+(?: [^\s()<>|]+)*)\((?P<line>\d+)(?:,(?P<column>\d+))?(?:,\d+,\d+)?\)",
+        )
+        .unwrap()
+    })
+}
+
+/// `path:line` or `path:line:col` from cargo, gcc, eslint, and most others
+fn colon_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?P<path>(?:[A-Za-z]:[\\/])?[^\s:"'<>|()\[\]{},]+):(?P<line>\d+)(?::(?P<column>\d+))?"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Paths with a directory and an extension but no position
+fn bare_path_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:[A-Za-z]:)?(?:\.{1,2}[\\/]|[\\/])?(?:[\w.@+-]+[\\/])+[\w@+-][\w.@+-]*\.[A-Za-z0-9]+")
+            .unwrap()
+    })
+}
+
+/// Resolve `raw` against `cwd` without touching the disk, so symlinked
+/// workspaces keep their paths
+fn resolve_path(raw: &str, cwd: &Path) -> PathBuf {
+    // Tools running inside a WSL-rooted workspace print Linux paths
+    if raw.starts_with('/') {
+        if let Some(workspace) = wsl_workspace(&cwd.to_string_lossy()) {
+            return PathBuf::from(wsl_to_windows(raw, &workspace.distro));
+        }
+    }
+
+    let path = Path::new(raw);
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    };
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// The longest suffix of `raw`, starting at a word, that names an existing
+/// file, with its offset into `raw`
+fn existing_file(raw: &str, cwd: &Path) -> Option<(usize, PathBuf)> {
+    let starts = std::iter::once(0).chain(raw.match_indices(' ').map(|(i, _)| i + 1));
+    starts.filter(|&start| start < raw.len()).find_map(|start| {
+        let candidate = raw[start..].trim_matches(['"', '\'']);
+        let path = resolve_path(candidate, cwd);
+        path.is_file().then_some((start, path))
+    })
+}
+
+fn parse_number(text: Option<regex::Match>) -> Option<u32> {
+    text.and_then(|m| m.as_str().parse().ok())
+        .filter(|&n| n > 0)
+}
+
+/// Drop punctuation that ends a sentence rather than a URL, keeping a closing
+/// parenthesis when the URL opened one
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let Some(last) = url.chars().last() else {
+            return url;
+        };
+        let unbalanced_paren = last == ')' && url.matches('(').count() < url.matches(')').count();
+        if matches!(
+            last,
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | ']' | '}'
+        ) || unbalanced_paren
+        {
+            url = &url[..url.len() - 1];
+        } else {
+            return url;
+        }
+    }
+}
+
+struct Candidate {
+    range: Range<usize>,
+    kind: TerminalLinkKind,
+    target: String,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+/// Find links in `text`, with byte ranges
+fn find_links(text: &str, cwd: &Path) -> Vec<Candidate> {
+    let mut found: Vec<Candidate> = Vec::new();
+    let overlaps = |found: &[Candidate], range: &Range<usize>| {
+        found
+            .iter()
+            .any(|c| c.range.start < range.end && range.start < c.range.end)
+    };
+
+    for m in url_pattern().find_iter(text) {
+        let url = trim_url(m.as_str());
+        found.push(Candidate {
+            range: m.start()..m.start() + url.len(),
+            kind: TerminalLinkKind::Url,
+            target: url.to_string(),
+            line: None,
+            column: None,
+        });
+    }
+
+    // Most specific formats first; later ones skip text already linked. Python
+    // links the whole `File "...", line N`; the others start at the path.
+    let patterns = [
+        (python_pattern(), true),
+        (msbuild_pattern(), false),
+        (colon_pattern(), false),
+    ];
+    for (pattern, link_whole_match) in patterns {
+        for caps in pattern.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            let path = caps.name("path").unwrap();
+            let Some((offset, file)) = existing_file(path.as_str(), cwd) else {
+                continue;
+            };
+            let start = if link_whole_match {
+                whole.start()
+            } else {
+                path.start() + offset
+            };
+            let range = start..whole.end();
+            if overlaps(&found, &range) {
+                continue;
+            }
+            found.push(Candidate {
+                range,
+                kind: TerminalLinkKind::File,
+                target: file.to_string_lossy().to_string(),
+                line: parse_number(caps.name("line")),
+                column: parse_number(caps.name("column")),
+            });
+        }
+    }
+
+    for m in bare_path_pattern().find_iter(text) {
+        let range = m.range();
+        if overlaps(&found, &range) {
+            continue;
+        }
+        let file = resolve_path(m.as_str(), cwd);
+        if file.is_file() {
+            found.push(Candidate {
+                range,
+                kind: TerminalLinkKind::File,
+                target: file.to_string_lossy().to_string(),
+                line: None,
+                column: None,
+            });
+        }
+    }
+
+    found.sort_by_key(|c| c.range.start);
+    found
+}
+
+/// Detect links in terminal output
+pub fn detect_links(text: &str, cwd: &Path) -> Vec<TerminalLink> {
+    let mut limit = text.len().min(MAX_SCAN_BYTES);
+    while !text.is_char_boundary(limit) {
+        limit -= 1;
+    }
+    let text = &text[..limit];
+
+    // Byte offsets become UTF-16 offsets, as JavaScript strings index them
+    let utf16 = |offset: usize| text[..offset].encode_utf16().count();
+    find_links(text, cwd)
+        .into_iter()
+        .map(|c| TerminalLink {
+            start: utf16(c.range.start),
+            end: utf16(c.range.end),
+            kind: c.kind,
+            target: c.target,
+            line: c.line,
+            column: c.column,
+        })
+        .collect()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Detect clickable file references and URLs in terminal output
+///
+/// # Arguments
+/// * `text` - Output to scan; may span several lines
+/// * `cwd` - Directory relative paths in the output are resolved against
+#[fluxel_command]
+pub async fn detect_terminal_links(text: String, cwd: String) -> Result<Vec<TerminalLink>, String> {
+    tauri::async_runtime::spawn_blocking(move || detect_links(&text, Path::new(&cwd)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn detects_compiler_locations_and_urls() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("fluxel-terminal-links-{}", nanos));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("My App")).unwrap();
+        fs::write(root.join("src").join("main.rs"), "").unwrap();
+        fs::write(root.join("My App").join("Program.cs"), "").unwrap();
+        fs::write(root.join("app.py"), "").unwrap();
+
+        let text = "error[E0425]: cannot find value\n  --> src/main.rs:12:5\n\
+            My App/Program.cs(3,17): error CS1002: ; expected [My App/App.csproj]\n\
+            File \"app.py\", line 4, in <module>\n\
+            see https://doc.rust-lang.org/error_codes/E0425.html.\n\
+            built ./src/main.rs in 12:30, missing.rs:3\n";
+        let links = detect_links(text, &root);
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        let summary: Vec<(String, TerminalLinkKind, Option<u32>, Option<u32>)> = links
+            .iter()
+            .map(|link| {
+                let linked = String::from_utf16(&utf16[link.start..link.end]).unwrap();
+                (linked, link.kind, link.line, link.column)
+            })
+            .collect();
+        let expected = [
+            (
+                "src/main.rs:12:5",
+                TerminalLinkKind::File,
+                Some(12),
+                Some(5),
+            ),
+            (
+                "My App/Program.cs(3,17)",
+                TerminalLinkKind::File,
+                Some(3),
+                Some(17),
+            ),
+            (
+                "File \"app.py\", line 4",
+                TerminalLinkKind::File,
+                Some(4),
+                None,
+            ),
+            (
+                "https://doc.rust-lang.org/error_codes/E0425.html",
+                TerminalLinkKind::Url,
+                None,
+                None,
+            ),
+            ("./src/main.rs", TerminalLinkKind::File, None, None),
+        ];
+        assert_eq!(
+            summary,
+            expected
+                .iter()
+                .map(|(text, kind, line, column)| (text.to_string(), *kind, *line, *column))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Path::new(&links[0].target),
+            root.join("src").join("main.rs")
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
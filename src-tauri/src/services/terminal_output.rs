@@ -0,0 +1,530 @@
+//! Terminal Output Processing
+//!
+//! Turns a child process's raw output stream into the lines emitted on
+//! `terminal://output` and `terminal://stderr`. Progress bars redraw a line
+//! with `\r` many times a second; with coalescing on, those redraws are
+//! throttled into updates that replace the previous line instead of each
+//! becoming a new one. ANSI escape sequences can be passed through, stripped,
+//! or parsed into styled segments.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Minimum time between updates of a line that is being redrawn
+const COALESCE_INTERVAL: Duration = Duration::from_millis(100);
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnsiMode {
+    /// Pass escape sequences through unchanged
+    #[default]
+    Raw,
+    /// Remove escape sequences
+    Strip,
+    /// Remove escape sequences and describe the styling as segments
+    Parse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OutputOptions {
+    /// Collapse `\r` redraws into throttled replacements of the current line
+    pub coalesce_carriage_returns: bool,
+    pub ansi: AnsiMode,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            coalesce_carriage_returns: true,
+            ansi: AnsiMode::Raw,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    /// One of the 16 standard colors
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn from_256(index: u8) -> Self {
+        match index {
+            0..=15 => Color::Indexed(index),
+            16..=231 => {
+                let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+                let n = index - 16;
+                Color::Rgb(level(n / 36), level((n / 6) % 6), level(n % 6))
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+                Color::Rgb(gray, gray, gray)
+            }
+        }
+    }
+
+    /// Theme color name (`red`, `brightBlue`, ...) or `#rrggbb`
+    fn css(self) -> String {
+        const NAMES: [&str; 8] = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        match self {
+            Color::Indexed(index) if index < 8 => NAMES[index as usize].to_string(),
+            Color::Indexed(index) => {
+                let name = NAMES[(index % 8) as usize];
+                format!("bright{}{}", name[..1].to_uppercase(), &name[1..])
+            }
+            Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+    strikethrough: bool,
+}
+
+impl Style {
+    /// Apply the parameters of an SGR (`ESC [ ... m`) sequence
+    fn apply_sgr(&mut self, params: &str) {
+        let codes: Vec<u16> = params
+            .split([';', ':'])
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let mut i = 0;
+        while i < codes.len() {
+            match codes[i] {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                29 => self.strikethrough = false,
+                code @ 30..=37 => self.fg = Some(Color::Indexed((code - 30) as u8)),
+                code @ 90..=97 => self.fg = Some(Color::Indexed((code - 90 + 8) as u8)),
+                39 => self.fg = None,
+                code @ 40..=47 => self.bg = Some(Color::Indexed((code - 40) as u8)),
+                code @ 100..=107 => self.bg = Some(Color::Indexed((code - 100 + 8) as u8)),
+                49 => self.bg = None,
+                code @ (38 | 48) => {
+                    let (color, used) = match codes.get(i + 1) {
+                        Some(5) => (
+                            codes.get(i + 2).map(|&n| Color::from_256(n.min(255) as u8)),
+                            2,
+                        ),
+                        Some(2) if i + 4 < codes.len() => {
+                            let channel = |n: u16| n.min(255) as u8;
+                            (
+                                Some(Color::Rgb(
+                                    channel(codes[i + 2]),
+                                    channel(codes[i + 3]),
+                                    channel(codes[i + 4]),
+                                )),
+                                4,
+                            )
+                        }
+                        _ => (None, 0),
+                    };
+                    if let Some(color) = color {
+                        if code == 38 {
+                            self.fg = Some(color);
+                        } else {
+                            self.bg = Some(color);
+                        }
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Run of text sharing one style
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyledSegment {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dim: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strikethrough: bool,
+}
+
+impl StyledSegment {
+    fn new(text: String, style: Style) -> Self {
+        let (fg, bg) = if style.inverse {
+            (style.bg, style.fg)
+        } else {
+            (style.fg, style.bg)
+        };
+        Self {
+            text,
+            fg: fg.map(Color::css),
+            bg: bg.map(Color::css),
+            bold: style.bold,
+            dim: style.dim,
+            italic: style.italic,
+            underline: style.underline,
+            strikethrough: style.strikethrough,
+        }
+    }
+}
+
+/// Remove escape sequences from `text`, starting in `style`. Returns the plain
+/// text, its segments, and the style in effect at the end.
+fn parse_ansi(text: &str, style: Style) -> (String, Vec<StyledSegment>, Style) {
+    let mut plain = String::with_capacity(text.len());
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut style = style;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            current.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some('[') => {
+                let mut params = String::new();
+                let mut end = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        end = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if end == Some('m') {
+                    let mut next = style;
+                    next.apply_sgr(&params);
+                    if next != style && !current.is_empty() {
+                        segments.push(StyledSegment::new(std::mem::take(&mut current), style));
+                    }
+                    style = next;
+                }
+            }
+            // OSC (titles, hyperlinks): ends with BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-character sequences
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        segments.push(StyledSegment::new(current, style));
+    }
+    (plain, segments, style)
+}
+
+/// Line ready to be emitted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    pub text: String,
+    /// Present in [`AnsiMode::Parse`]
+    pub segments: Option<Vec<StyledSegment>>,
+    /// Replaces the previous line from the same stream rather than following it
+    pub replace: bool,
+}
+
+/// Incremental line splitter for one output stream
+pub struct OutputProcessor {
+    options: OutputOptions,
+    line: Vec<u8>,
+    /// A `\r` ended the last frame; the next byte decides whether it was a line
+    /// ending or a redraw
+    after_cr: bool,
+    /// Part of the current line was already emitted, so the next emit replaces it
+    shown: bool,
+    /// The current line changed since it was last emitted
+    dirty: bool,
+    last_update: Option<Instant>,
+    /// Style in effect at the start of the current line
+    line_style: Style,
+}
+
+impl OutputProcessor {
+    pub fn new(options: OutputOptions) -> Self {
+        Self {
+            options,
+            line: Vec::new(),
+            after_cr: false,
+            shown: false,
+            dirty: false,
+            last_update: None,
+            line_style: Style::default(),
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8], now: Instant, emit: &mut impl FnMut(OutputLine)) {
+        for &byte in bytes {
+            if !self.options.coalesce_carriage_returns {
+                if byte == b'\n' {
+                    if self.line.last() == Some(&b'\r') {
+                        self.line.pop();
+                    }
+                    self.end_line(emit);
+                } else {
+                    self.line.push(byte);
+                    self.dirty = true;
+                }
+                continue;
+            }
+
+            if std::mem::take(&mut self.after_cr) {
+                if byte == b'\n' {
+                    self.end_line(emit);
+                    continue;
+                }
+                // A redraw: show the finished frame if enough time has passed,
+                // then start the next one
+                if self.update_due(now) {
+                    self.emit_update(now, emit);
+                }
+                if self.options.ansi != AnsiMode::Raw {
+                    self.line_style = self.render().2;
+                }
+                self.line.clear();
+                self.dirty = false;
+            }
+
+            match byte {
+                b'\n' => self.end_line(emit),
+                b'\r' => self.after_cr = true,
+                _ => {
+                    self.line.push(byte);
+                    self.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Show a line left unfinished while the stream is quiet, such as the last
+    /// progress frame or a prompt
+    pub fn idle(&mut self, now: Instant, emit: &mut impl FnMut(OutputLine)) {
+        if self.options.coalesce_carriage_returns
+            && self.dirty
+            && !self.line.is_empty()
+            && self.update_due(now)
+        {
+            self.emit_update(now, emit);
+        }
+    }
+
+    /// Emit whatever is left when the stream closes
+    pub fn finish(&mut self, emit: &mut impl FnMut(OutputLine)) {
+        self.after_cr = false;
+        if self.dirty {
+            self.end_line(emit);
+        }
+    }
+
+    fn update_due(&self, now: Instant) -> bool {
+        self.last_update
+            .is_none_or(|last| now.duration_since(last) >= COALESCE_INTERVAL)
+    }
+
+    fn emit_update(&mut self, now: Instant, emit: &mut impl FnMut(OutputLine)) {
+        let (text, segments, _) = self.render();
+        emit(OutputLine {
+            text,
+            segments,
+            replace: self.shown,
+        });
+        self.shown = true;
+        self.dirty = false;
+        self.last_update = Some(now);
+    }
+
+    fn end_line(&mut self, emit: &mut impl FnMut(OutputLine)) {
+        let (text, segments, style) = self.render();
+        emit(OutputLine {
+            text,
+            segments,
+            replace: self.shown,
+        });
+        self.line.clear();
+        self.line_style = style;
+        self.shown = false;
+        self.dirty = false;
+    }
+
+    fn render(&self) -> (String, Option<Vec<StyledSegment>>, Style) {
+        let text = String::from_utf8_lossy(&self.line);
+        match self.options.ansi {
+            AnsiMode::Raw => (text.into_owned(), None, self.line_style),
+            AnsiMode::Strip => {
+                let (plain, _, style) = parse_ansi(&text, self.line_style);
+                (plain, None, style)
+            }
+            AnsiMode::Parse => {
+                let (plain, segments, style) = parse_ansi(&text, self.line_style);
+                (plain, Some(segments), style)
+            }
+        }
+    }
+}
+
+/// Read `reader` to the end, emitting processed lines. Blocks the calling thread.
+pub fn stream_output(
+    reader: impl Read + Send + 'static,
+    options: OutputOptions,
+    mut emit: impl FnMut(OutputLine),
+) {
+    // Reads happen on their own thread so a quiet stream still lets the last
+    // progress frame through
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        let mut reader = reader;
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if tx.send(buffer[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut processor = OutputProcessor::new(options);
+    loop {
+        match rx.recv_timeout(COALESCE_INTERVAL) {
+            Ok(bytes) => processor.feed(&bytes, Instant::now(), &mut emit),
+            Err(RecvTimeoutError::Timeout) => processor.idle(Instant::now(), &mut emit),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    processor.finish(&mut emit);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(options: OutputOptions, chunks: &[(&str, u64)]) -> Vec<OutputLine> {
+        let start = Instant::now();
+        let mut processor = OutputProcessor::new(options);
+        let mut lines = Vec::new();
+        let mut emit = |line: OutputLine| lines.push(line);
+        for (chunk, millis) in chunks {
+            processor.feed(
+                chunk.as_bytes(),
+                start + Duration::from_millis(*millis),
+                &mut emit,
+            );
+        }
+        processor.finish(&mut emit);
+        lines
+    }
+
+    #[test]
+    fn coalesces_progress_redraws() {
+        let lines = run(
+            OutputOptions::default(),
+            &[
+                ("building\r\n", 0),
+                ("10%\r20%\r30%\r", 1),
+                ("40%\r", 150),
+                ("100%\ndone", 160),
+            ],
+        );
+        let summary: Vec<(&str, bool)> = lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.replace))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("building", false),
+                ("10%", false),
+                ("30%", true),
+                ("100%", true),
+                ("done", false),
+            ]
+        );
+
+        let raw = run(
+            OutputOptions {
+                coalesce_carriage_returns: false,
+                ansi: AnsiMode::Raw,
+            },
+            &[("a\r\nb\rc\n", 0)],
+        );
+        let texts: Vec<&str> = raw.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, vec!["a", "b\rc"]);
+    }
+
+    #[test]
+    fn parses_ansi_styles() {
+        let lines = run(
+            OutputOptions {
+                coalesce_carriage_returns: true,
+                ansi: AnsiMode::Parse,
+            },
+            &[(
+                "\x1b[1;31merror\x1b[0m: \x1b[38;5;196mbad\n\x1b]0;title\x07still red\x1b[39m\n",
+                0,
+            )],
+        );
+        assert_eq!(lines[0].text, "error: bad");
+        let segments = lines[0].segments.as_ref().unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].text, "error");
+        assert_eq!(segments[0].fg.as_deref(), Some("red"));
+        assert!(segments[0].bold);
+        assert_eq!(segments[1].fg, None);
+        assert_eq!(segments[2].fg.as_deref(), Some("#ff0000"));
+
+        // Styles carry across lines; OSC titles are dropped
+        assert_eq!(lines[1].text, "still red");
+        let segments = lines[1].segments.as_ref().unwrap();
+        assert_eq!(segments[0].fg.as_deref(), Some("#ff0000"));
+    }
+}
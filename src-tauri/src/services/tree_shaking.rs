@@ -0,0 +1,509 @@
+//! Tree-Shaking Report Service
+//!
+//! Flags imports likely to pull an entire package into a bundle: namespace, default,
+//! `require` and `export *` imports of a package root, and any import of a package
+//! without an ESM entry. Each package's `sideEffects` field is reported alongside, and
+//! subpath alternatives are suggested from the exports map for the members a file uses.
+
+use crate::services::import_cost::has_condition;
+use crate::services::import_rewrite::{normalize, source_files};
+use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
+use fluxel_node_resolver::{
+    collect_import_specifiers_native, resolve_module_native, ImportBinding, ImportKind,
+    ImportSpecifier, ResolveRequest,
+};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Members listed per finding, and subpaths suggested for them
+const MAX_MEMBERS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SideEffects {
+    /// `"sideEffects": false`
+    Free,
+    /// Only the listed files have side effects
+    Partial(Vec<String>),
+    /// `"sideEffects": true` or no field; bundlers keep every imported module
+    Assumed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStyle {
+    Named,
+    Default,
+    Namespace,
+    Require,
+    Dynamic,
+    ReExportAll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeShakingIssue {
+    /// No ESM entry point, so bundlers include the package whole
+    CommonJsOnly,
+    /// Namespace, default, `require`, `import()` or `export *` of the package root
+    WholePackageImport,
+    /// The package doesn't declare `sideEffects: false`
+    SideEffectsNotDeclared,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeShakingFinding {
+    pub file: String,
+    /// 1-based line of the specifier
+    pub line: usize,
+    pub specifier: String,
+    pub package: String,
+    pub style: ImportStyle,
+    pub issues: Vec<TreeShakingIssue>,
+    /// Members the file imports or accesses through the binding
+    pub members: Vec<String>,
+    /// Subpath specifiers that import those members directly
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageTreeShaking {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub side_effects: SideEffects,
+    pub esm: bool,
+    pub import_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeShakingReport {
+    pub root: String,
+    pub files_scanned: usize,
+    pub findings: Vec<TreeShakingFinding>,
+    pub packages: Vec<PackageTreeShaking>,
+}
+
+struct PackageInfo {
+    dir: PathBuf,
+    manifest: Value,
+    summary: PackageTreeShaking,
+}
+
+// ============================================================================
+// Analysis
+// ============================================================================
+
+pub fn analyze_tree_shaking(root: &Path) -> TreeShakingReport {
+    let root = normalize(root);
+    let mut packages: HashMap<PathBuf, Option<PackageInfo>> = HashMap::new();
+    let mut findings = Vec::new();
+    let mut files_scanned = 0;
+
+    for file in source_files(&root) {
+        let Ok(utf8_file) = Utf8PathBuf::from_path_buf(file.clone()) else {
+            continue;
+        };
+        let Ok(specifiers) = collect_import_specifiers_native(&utf8_file) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        files_scanned += 1;
+
+        for specifier in specifiers {
+            if specifier.type_only || !is_bare(&specifier.specifier) {
+                continue;
+            }
+            let Some(style) = import_style(&specifier) else {
+                continue;
+            };
+            let Some(manifest_path) = package_manifest(&file, &specifier.specifier, &root) else {
+                continue;
+            };
+            let Some(package) = packages
+                .entry(manifest_path.clone())
+                .or_insert_with(|| load_package(&manifest_path))
+            else {
+                continue;
+            };
+            package.summary.import_count += 1;
+
+            let (_, subpath) = split_package(&specifier.specifier);
+            let mut issues = Vec::new();
+            if !package.summary.esm {
+                issues.push(TreeShakingIssue::CommonJsOnly);
+            }
+            if subpath.is_none() && style != ImportStyle::Named {
+                issues.push(TreeShakingIssue::WholePackageImport);
+            }
+            if issues.is_empty() {
+                continue;
+            }
+            if package.summary.side_effects == SideEffects::Assumed {
+                issues.push(TreeShakingIssue::SideEffectsNotDeclared);
+            }
+
+            let members = used_members(&specifier, &content);
+            let suggestions = if subpath.is_none() {
+                members
+                    .iter()
+                    .filter_map(|member| {
+                        suggest_subpath(&package.dir, &package.manifest, member)
+                            .map(|subpath| format!("{}/{}", package.summary.name, subpath))
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            findings.push(TreeShakingFinding {
+                file: file.to_string_lossy().replace('\\', "/"),
+                line: content[..specifier.start].matches('\n').count() + 1,
+                specifier: specifier.specifier,
+                package: package.summary.name.clone(),
+                style,
+                issues,
+                members,
+                suggestions,
+            });
+        }
+    }
+
+    let mut packages: Vec<PackageTreeShaking> = packages
+        .into_values()
+        .flatten()
+        .map(|package| package.summary)
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    TreeShakingReport {
+        root: root.to_string_lossy().replace('\\', "/"),
+        files_scanned,
+        findings,
+        packages,
+    }
+}
+
+/// Package specifiers only: no relative paths, `#imports`, or `node:`-style schemes
+fn is_bare(specifier: &str) -> bool {
+    !specifier.is_empty()
+        && !specifier.starts_with('.')
+        && !specifier.starts_with('/')
+        && !specifier.starts_with('#')
+        && !specifier.contains(':')
+}
+
+fn import_style(specifier: &ImportSpecifier) -> Option<ImportStyle> {
+    match specifier.kind {
+        ImportKind::Require => return Some(ImportStyle::Require),
+        ImportKind::DynamicImport => return Some(ImportStyle::Dynamic),
+        ImportKind::Import | ImportKind::ReExport => {}
+    }
+    let mut style = None;
+    for binding in &specifier.bindings {
+        let binding_style = match binding {
+            ImportBinding::Namespace { local } if local == "*" => ImportStyle::ReExportAll,
+            ImportBinding::Namespace { .. } => ImportStyle::Namespace,
+            ImportBinding::Default { .. } => ImportStyle::Default,
+            ImportBinding::Named { .. } => ImportStyle::Named,
+        };
+        // The broadest binding decides: `import React, { useState }` counts as default
+        if style.is_none() || style == Some(ImportStyle::Named) {
+            style = Some(binding_style);
+        }
+    }
+    // Side-effect-only imports are deliberate
+    style
+}
+
+/// Split `@scope/pkg/sub/path` into the package name and optional subpath
+fn split_package(specifier: &str) -> (&str, Option<&str>) {
+    let name_segments = if specifier.starts_with('@') { 2 } else { 1 };
+    match specifier.match_indices('/').nth(name_segments - 1) {
+        Some((index, _)) => (&specifier[..index], Some(&specifier[index + 1..])),
+        None => (specifier, None),
+    }
+}
+
+fn package_manifest(importer: &Path, specifier: &str, root: &Path) -> Option<PathBuf> {
+    let response = resolve_module_native(
+        ResolveRequest {
+            specifier: specifier.to_string(),
+            importer: importer.to_string_lossy().to_string(),
+            project_root: Some(root.to_string_lossy().to_string()),
+        },
+        None,
+    )
+    .ok()?;
+    response.package_json.map(PathBuf::from)
+}
+
+fn load_package(manifest_path: &Path) -> Option<PackageInfo> {
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(manifest_path).ok()?).ok()?;
+    let name = manifest.get("name").and_then(|v| v.as_str())?.to_string();
+    let summary = PackageTreeShaking {
+        name,
+        version: manifest
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        side_effects: side_effects(&manifest),
+        esm: has_esm_entry(&manifest),
+        import_count: 0,
+    };
+    Some(PackageInfo {
+        dir: manifest_path.parent()?.to_path_buf(),
+        manifest,
+        summary,
+    })
+}
+
+fn side_effects(manifest: &Value) -> SideEffects {
+    match manifest.get("sideEffects") {
+        Some(Value::Bool(false)) => SideEffects::Free,
+        Some(Value::Array(files)) => SideEffects::Partial(
+            files
+                .iter()
+                .filter_map(|f| f.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => SideEffects::Assumed,
+    }
+}
+
+fn has_esm_entry(manifest: &Value) -> bool {
+    let field = |key: &str| manifest.get(key).and_then(|v| v.as_str());
+    field("type") == Some("module")
+        || field("module").is_some()
+        || field("main").is_some_and(|main| main.ends_with(".mjs"))
+        || manifest.get("exports").is_some_and(|exports| {
+            has_condition(exports, "import")
+                || has_condition(exports, "module")
+                || exports
+                    .as_str()
+                    .is_some_and(|entry| entry.ends_with(".mjs"))
+        })
+}
+
+/// Names the file takes from the import: named bindings directly, and property
+/// accesses such as `_.debounce` for default, namespace and `require` bindings
+fn used_members(specifier: &ImportSpecifier, content: &str) -> Vec<String> {
+    let mut members = BTreeSet::new();
+    let mut locals = Vec::new();
+
+    for binding in &specifier.bindings {
+        match binding {
+            ImportBinding::Named { imported, .. } if imported != "default" => {
+                members.insert(imported.clone());
+            }
+            ImportBinding::Named { .. } => {}
+            ImportBinding::Default { local } | ImportBinding::Namespace { local } => {
+                if local != "*" {
+                    locals.push(local.clone());
+                }
+            }
+        }
+    }
+
+    if specifier.kind == ImportKind::Require {
+        let escaped = regex::escape(&specifier.specifier);
+        let simple = Regex::new(&format!(
+            r#"(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*require\(\s*['"]{escaped}['"]\s*\)"#
+        ));
+        let destructured = Regex::new(&format!(
+            r#"(?:const|let|var)\s*\{{([^}}]*)\}}\s*=\s*require\(\s*['"]{escaped}['"]\s*\)"#
+        ));
+        if let Ok(simple) = simple {
+            locals.extend(simple.captures_iter(content).map(|c| c[1].to_string()));
+        }
+        if let Ok(destructured) = destructured {
+            for captures in destructured.captures_iter(content) {
+                members.extend(
+                    captures[1]
+                        .split(',')
+                        .filter_map(|part| part.split(':').next())
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty()),
+                );
+            }
+        }
+    }
+
+    for local in locals {
+        let Ok(access) = Regex::new(&format!(
+            r"(?:^|[^\w$.]){}\s*\.\s*([A-Za-z_$][\w$]*)",
+            regex::escape(&local)
+        )) else {
+            continue;
+        };
+        members.extend(access.captures_iter(content).map(|c| c[1].to_string()));
+    }
+
+    members.into_iter().take(MAX_MEMBERS).collect()
+}
+
+/// A subpath that exposes `member` on its own, from the exports map or, for packages
+/// without one, a file of that name in the package
+fn suggest_subpath(dir: &Path, manifest: &Value, member: &str) -> Option<String> {
+    if let Some(exports) = manifest.get("exports").and_then(|e| e.as_object()) {
+        if !exports.keys().any(|k| k.starts_with('.')) {
+            return None;
+        }
+        let key = format!("./{}", member);
+        let matches_pattern = exports.keys().any(|pattern| {
+            pattern.split_once('*').is_some_and(|(prefix, suffix)| {
+                prefix == "./" && (suffix.is_empty() || suffix == ".js")
+            })
+        });
+        return (exports.contains_key(&key) || matches_pattern).then(|| member.to_string());
+    }
+    if manifest.get("exports").is_some() {
+        return None;
+    }
+    ["js", "mjs", "cjs"]
+        .iter()
+        .any(|ext| dir.join(format!("{}.{}", member, ext)).is_file())
+        .then(|| member.to_string())
+        .or_else(|| {
+            dir.join(member)
+                .join("index.js")
+                .is_file()
+                .then(|| member.to_string())
+        })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Report imports that defeat tree-shaking across a project
+///
+/// # Arguments
+/// * `project_root` - Project root whose sources are scanned
+#[fluxel_command]
+pub async fn analyze_tree_shaking_report(
+    project_root: String,
+) -> Result<TreeShakingReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&project_root);
+        if !root.is_dir() {
+            return Err(format!("Project root not found: {}", project_root));
+        }
+        let report = analyze_tree_shaking(&root);
+        println!(
+            "[TreeShaking] {} findings across {} files",
+            report.findings.len(),
+            report.files_scanned
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn import(specifier: &str, kind: ImportKind, bindings: Vec<ImportBinding>) -> ImportSpecifier {
+        ImportSpecifier {
+            specifier: specifier.to_string(),
+            kind,
+            bindings,
+            type_bindings: Vec::new(),
+            type_only: false,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    #[test]
+    fn reads_package_metadata() {
+        assert_eq!(split_package("lodash"), ("lodash", None));
+        assert_eq!(
+            split_package("lodash/debounce"),
+            ("lodash", Some("debounce"))
+        );
+        assert_eq!(
+            split_package("@mui/icons-material"),
+            ("@mui/icons-material", None)
+        );
+        assert_eq!(
+            split_package("@mui/icons-material/Add"),
+            ("@mui/icons-material", Some("Add"))
+        );
+
+        assert_eq!(
+            side_effects(&json!({"sideEffects": false})),
+            SideEffects::Free
+        );
+        assert_eq!(
+            side_effects(&json!({"sideEffects": ["*.css"]})),
+            SideEffects::Partial(vec!["*.css".to_string()])
+        );
+        assert_eq!(side_effects(&json!({})), SideEffects::Assumed);
+
+        assert!(!has_esm_entry(&json!({"main": "lodash.js"})));
+        assert!(has_esm_entry(
+            &json!({"main": "index.js", "module": "es/index.js"})
+        ));
+        assert!(has_esm_entry(&json!({
+            "exports": {".": {"import": "./index.mjs", "require": "./index.cjs"}}
+        })));
+
+        let exports = json!({"exports": {".": "./index.js", "./debounce": "./debounce.js"}});
+        assert_eq!(
+            suggest_subpath(Path::new("/nowhere"), &exports, "debounce"),
+            Some("debounce".to_string())
+        );
+        assert_eq!(
+            suggest_subpath(Path::new("/nowhere"), &exports, "throttle"),
+            None
+        );
+    }
+
+    #[test]
+    fn collects_members_used_through_bindings() {
+        let content =
+            "import _, { map as m } from 'lodash';\n_.debounce(f); _ . throttle(g); x_.nope();\n";
+        let specifier = import(
+            "lodash",
+            ImportKind::Import,
+            vec![
+                ImportBinding::Default {
+                    local: "_".to_string(),
+                },
+                ImportBinding::Named {
+                    imported: "map".to_string(),
+                    local: "m".to_string(),
+                },
+            ],
+        );
+        assert_eq!(import_style(&specifier), Some(ImportStyle::Default));
+        assert_eq!(
+            used_members(&specifier, content),
+            vec!["debounce", "map", "throttle"]
+        );
+
+        let content = "const { pick, omit: o } = require(\"lodash\");\nconst fp = require('lodash');\nfp.flow();\n";
+        let specifier = import("lodash", ImportKind::Require, Vec::new());
+        assert_eq!(
+            used_members(&specifier, content),
+            vec!["flow", "omit", "pick"]
+        );
+
+        let side_effect_only = import("./polyfills", ImportKind::Import, Vec::new());
+        assert_eq!(import_style(&side_effect_only), None);
+    }
+}
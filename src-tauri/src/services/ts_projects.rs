@@ -0,0 +1,691 @@
+//! TypeScript Project References Service
+//!
+//! Loads a `tsconfig.json` and every project it transitively `references` (following
+//! `extends` chains for compiler options), maps source files to the sub-project that
+//! owns them, and translates resolved paths between a project's sources and its
+//! `outDir`/`declarationDir` outputs so module resolution can prefer either side.
+
+use fluxel_macros::fluxel_command;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::State;
+
+/// Guards against `extends` cycles and runaway chains
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+/// Which side of a referenced project resolution should land on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceStrategy {
+    /// Map built outputs back to the project's TypeScript sources
+    Source,
+    /// Map sources to the `.d.ts` files the project emits
+    Declarations,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TsProject {
+    pub config_path: String,
+    pub dir: String,
+    /// `rootDir`, or the directory inferred from `include`
+    pub root_dir: String,
+    pub out_dir: Option<String>,
+    pub declaration_dir: Option<String>,
+    pub composite: bool,
+    /// Config paths of the projects this one references
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReferenceGraph {
+    pub root: String,
+    pub projects: Vec<TsProject>,
+    /// Unreadable configs, missing references and reference cycles
+    pub errors: Vec<String>,
+    #[serde(skip)]
+    matchers: Vec<ProjectMatcher>,
+}
+
+/// Resolved file-membership rules of one project (parallel to `projects`)
+#[derive(Debug, Clone)]
+struct ProjectMatcher {
+    dir: PathBuf,
+    root_dir: PathBuf,
+    out_dirs: Vec<PathBuf>,
+    include: GlobSet,
+    exclude: GlobSet,
+    files: Vec<PathBuf>,
+}
+
+/// A tsconfig with `extends` applied. Paths are absolute.
+#[derive(Debug, Clone, Default)]
+struct ResolvedConfig {
+    out_dir: Option<PathBuf>,
+    root_dir: Option<PathBuf>,
+    declaration_dir: Option<PathBuf>,
+    composite: Option<bool>,
+    /// Patterns paired with the directory they are relative to
+    include: Option<(PathBuf, Vec<String>)>,
+    exclude: Option<(PathBuf, Vec<String>)>,
+    files: Option<Vec<PathBuf>>,
+    references: Vec<PathBuf>,
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+impl ProjectReferenceGraph {
+    /// Load `tsconfig` and all projects reachable through `references`
+    pub fn load(tsconfig: &Path) -> Self {
+        let root = config_file(tsconfig);
+        let mut graph = Self {
+            root: path_string(&root),
+            projects: Vec::new(),
+            errors: Vec::new(),
+            matchers: Vec::new(),
+        };
+        let mut visiting = Vec::new();
+        graph.visit(&root, &mut visiting);
+        graph
+    }
+
+    fn visit(&mut self, config: &Path, visiting: &mut Vec<PathBuf>) {
+        if let Some(start) = visiting.iter().position(|p| p == config) {
+            let cycle: Vec<String> = visiting[start..]
+                .iter()
+                .chain(std::iter::once(&config.to_path_buf()))
+                .map(|p| path_string(p))
+                .collect();
+            self.errors
+                .push(format!("Reference cycle: {}", cycle.join(" -> ")));
+            return;
+        }
+        let key = path_string(config);
+        if self.projects.iter().any(|p| p.config_path == key) {
+            return;
+        }
+
+        let resolved = match load_config(config, 0) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                self.errors.push(e);
+                return;
+            }
+        };
+        let dir = config.parent().unwrap_or(Path::new("")).to_path_buf();
+        let references: Vec<PathBuf> = resolved.references.iter().map(|r| config_file(r)).collect();
+
+        let root_dir = resolved
+            .root_dir
+            .clone()
+            .or_else(|| inferred_root_dir(&resolved))
+            .unwrap_or_else(|| dir.clone());
+        let out_dirs: Vec<PathBuf> = resolved
+            .declaration_dir
+            .iter()
+            .chain(resolved.out_dir.iter())
+            .cloned()
+            .collect();
+
+        self.projects.push(TsProject {
+            config_path: key,
+            dir: path_string(&dir),
+            root_dir: path_string(&root_dir),
+            out_dir: resolved.out_dir.as_deref().map(path_string),
+            declaration_dir: resolved.declaration_dir.as_deref().map(path_string),
+            composite: resolved.composite.unwrap_or(false),
+            references: references.iter().map(|r| path_string(r)).collect(),
+        });
+        self.matchers
+            .push(build_matcher(&dir, root_dir, out_dirs, &resolved));
+
+        visiting.push(config.to_path_buf());
+        for reference in references {
+            if reference.is_file() {
+                self.visit(&reference, visiting);
+            } else {
+                self.errors.push(format!(
+                    "{} references missing project {}",
+                    path_string(config),
+                    path_string(&reference)
+                ));
+            }
+        }
+        visiting.pop();
+    }
+}
+
+/// `tsconfig.json` inside a directory reference, otherwise the path itself
+fn config_file(path: &Path) -> PathBuf {
+    let path = if path.is_dir() {
+        path.join("tsconfig.json")
+    } else {
+        path.to_path_buf()
+    };
+    path.canonicalize().unwrap_or(path)
+}
+
+fn load_config(path: &Path, depth: usize) -> Result<ResolvedConfig, String> {
+    if depth > MAX_EXTENDS_DEPTH {
+        return Err(format!("{}: extends chain too deep", path_string(path)));
+    }
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path_string(path), e))?;
+    // tsconfig is JSONC: comments and trailing commas are allowed
+    let json: Value = json5::from_str(&text)
+        .map_err(|e| format!("Invalid tsconfig {}: {}", path_string(path), e))?;
+    let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    let mut config = ResolvedConfig::default();
+    let bases: Vec<&str> = match json.get("extends") {
+        Some(Value::String(base)) => vec![base.as_str()],
+        Some(Value::Array(bases)) => bases.iter().filter_map(|b| b.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    for base in bases {
+        let base_path = resolve_extends(&dir, base)
+            .ok_or_else(|| format!("{}: cannot find base config {}", path_string(path), base))?;
+        let inherited = load_config(&base_path, depth + 1)?;
+        config = ResolvedConfig {
+            // References are never inherited
+            references: Vec::new(),
+            out_dir: inherited.out_dir.or(config.out_dir),
+            root_dir: inherited.root_dir.or(config.root_dir),
+            declaration_dir: inherited.declaration_dir.or(config.declaration_dir),
+            composite: inherited.composite.or(config.composite),
+            include: inherited.include.or(config.include),
+            exclude: inherited.exclude.or(config.exclude),
+            files: inherited.files.or(config.files),
+        };
+    }
+
+    let options = json.get("compilerOptions");
+    let option_path = |key: &str| {
+        options
+            .and_then(|o| o.get(key))
+            .and_then(|v| v.as_str())
+            .map(|v| normalize(&dir.join(v)))
+    };
+    if let Some(out_dir) = option_path("outDir") {
+        config.out_dir = Some(out_dir);
+    }
+    if let Some(root_dir) = option_path("rootDir") {
+        config.root_dir = Some(root_dir);
+    }
+    if let Some(declaration_dir) = option_path("declarationDir") {
+        config.declaration_dir = Some(declaration_dir);
+    }
+    if let Some(composite) = options
+        .and_then(|o| o.get("composite"))
+        .and_then(|v| v.as_bool())
+    {
+        config.composite = Some(composite);
+    }
+
+    let strings = |key: &str| {
+        json.get(key).and_then(|v| v.as_array()).map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+    };
+    if let Some(include) = strings("include") {
+        config.include = Some((dir.clone(), include));
+    }
+    if let Some(exclude) = strings("exclude") {
+        config.exclude = Some((dir.clone(), exclude));
+    }
+    if let Some(files) = strings("files") {
+        config.files = Some(files.iter().map(|f| normalize(&dir.join(f))).collect());
+    }
+    config.references = json
+        .get("references")
+        .and_then(|v| v.as_array())
+        .map(|refs| {
+            refs.iter()
+                .filter_map(|r| r.get("path").and_then(|p| p.as_str()))
+                .map(|p| normalize(&dir.join(p)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(config)
+}
+
+/// Relative paths and packages (`@tsconfig/node20/tsconfig.json`, `@org/tsconfig`)
+fn resolve_extends(dir: &Path, base: &str) -> Option<PathBuf> {
+    let candidates = |path: PathBuf| {
+        let with_json = PathBuf::from(format!("{}.json", path.to_string_lossy()));
+        [path.clone(), with_json, path.join("tsconfig.json")]
+    };
+    if base.starts_with('.') || Path::new(base).is_absolute() {
+        return candidates(normalize(&dir.join(base)))
+            .into_iter()
+            .find(|c| c.is_file());
+    }
+    dir.ancestors().find_map(|ancestor| {
+        candidates(ancestor.join("node_modules").join(base))
+            .into_iter()
+            .find(|c| c.is_file())
+    })
+}
+
+/// TypeScript derives the root from the common directory of the inputs; `include`
+/// usually names it outright (`["src"]`, `["src/**/*"]`)
+fn inferred_root_dir(config: &ResolvedConfig) -> Option<PathBuf> {
+    let (base, patterns) = config.include.as_ref()?;
+    let mut roots = patterns.iter().map(|pattern| {
+        let fixed: Vec<&str> = pattern
+            .split('/')
+            .take_while(|segment| !segment.contains(['*', '?']))
+            .collect();
+        // A trailing segment with an extension is a file, not a directory
+        let fixed = match fixed.last() {
+            Some(last) if last.contains('.') && *last != "." && *last != ".." => {
+                &fixed[..fixed.len() - 1]
+            }
+            _ => &fixed[..],
+        };
+        normalize(&base.join(fixed.join("/")))
+    });
+    let first = roots.next()?;
+    roots.all(|root| root == first).then_some(first)
+}
+
+fn build_matcher(
+    dir: &Path,
+    root_dir: PathBuf,
+    out_dirs: Vec<PathBuf>,
+    config: &ResolvedConfig,
+) -> ProjectMatcher {
+    let include = match (&config.include, &config.files) {
+        (Some((base, patterns)), _) => globs(base, patterns),
+        // `files` without `include` lists the whole project
+        (None, Some(_)) => GlobSet::empty(),
+        (None, None) => globs(dir, &["**/*".to_string()]),
+    };
+    let exclude = match &config.exclude {
+        Some((base, patterns)) => globs(base, patterns),
+        None => globs(
+            dir,
+            &[
+                "node_modules".to_string(),
+                "bower_components".to_string(),
+                "jspm_packages".to_string(),
+            ],
+        ),
+    };
+    ProjectMatcher {
+        dir: dir.to_path_buf(),
+        root_dir,
+        out_dirs,
+        include,
+        exclude,
+        files: config.files.clone().unwrap_or_default(),
+    }
+}
+
+/// Compile tsconfig patterns to absolute globs. Patterns without wildcards or an
+/// extension name directories and match everything below them.
+fn globs(base: &Path, patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let absolute = path_string(&normalize(&base.join(pattern)));
+        let last = pattern.rsplit('/').next().unwrap_or(pattern);
+        let is_directory = !last.contains(['*', '?', '.']);
+        let pattern = if is_directory {
+            format!("{}/**", absolute.trim_end_matches('/'))
+        } else {
+            absolute
+        };
+        if let Ok(glob) = Glob::new(&pattern) {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+impl ProjectReferenceGraph {
+    /// The most specific project whose `include`/`files` list `file`
+    pub fn owner_of(&self, file: &Path) -> Option<&TsProject> {
+        let file = file.canonicalize().unwrap_or_else(|_| normalize(file));
+        let file_str = path_string(&file);
+        self.projects
+            .iter()
+            .zip(&self.matchers)
+            .filter(|(_, matcher)| {
+                let out_of_tree = matcher.out_dirs.iter().any(|out| file.starts_with(out));
+                !out_of_tree
+                    && (matcher.files.contains(&file)
+                        || (matcher.include.is_match(&file_str)
+                            && !matcher.exclude.is_match(&file_str)))
+            })
+            .max_by_key(|(_, matcher)| matcher.dir.components().count())
+            .map(|(project, _)| project)
+    }
+
+    /// Translate a resolved module to the side `strategy` prefers, if it belongs to a
+    /// project in the graph and the counterpart exists on disk
+    pub fn map_resolved(&self, resolved: &Path, strategy: ReferenceStrategy) -> Option<PathBuf> {
+        let resolved = resolved
+            .canonicalize()
+            .unwrap_or_else(|_| normalize(resolved));
+        let name = resolved.file_name()?.to_str()?;
+
+        match strategy {
+            ReferenceStrategy::Declarations => {
+                if is_declaration(name) {
+                    return None;
+                }
+                let project = self.owner_of(&resolved)?;
+                let matcher = self.matcher(project)?;
+                let relative = resolved.strip_prefix(&matcher.root_dir).ok()?;
+                let declaration = declaration_name(name)?;
+                matcher
+                    .out_dirs
+                    .iter()
+                    .map(|out| out.join(relative).with_file_name(&declaration))
+                    .find(|candidate| candidate.is_file())
+            }
+            ReferenceStrategy::Source => self.matchers.iter().find_map(|matcher| {
+                let relative = matcher
+                    .out_dirs
+                    .iter()
+                    .find_map(|out| resolved.strip_prefix(out).ok())?;
+                let stem = output_stem(name)?;
+                source_extensions(name).iter().find_map(|ext| {
+                    let candidate = matcher
+                        .root_dir
+                        .join(relative)
+                        .with_file_name(format!("{}{}", stem, ext));
+                    candidate.is_file().then_some(candidate)
+                })
+            }),
+        }
+    }
+
+    fn matcher(&self, project: &TsProject) -> Option<&ProjectMatcher> {
+        let index = self
+            .projects
+            .iter()
+            .position(|p| p.config_path == project.config_path)?;
+        self.matchers.get(index)
+    }
+}
+
+fn is_declaration(name: &str) -> bool {
+    name.ends_with(".d.ts") || name.ends_with(".d.mts") || name.ends_with(".d.cts")
+}
+
+/// `foo.ts` -> `foo.d.ts`, `foo.mts` -> `foo.d.mts`
+fn declaration_name(name: &str) -> Option<String> {
+    for (source, declaration) in [
+        (".tsx", ".d.ts"),
+        (".ts", ".d.ts"),
+        (".mts", ".d.mts"),
+        (".cts", ".d.cts"),
+        (".jsx", ".d.ts"),
+        (".js", ".d.ts"),
+    ] {
+        if let Some(stem) = name.strip_suffix(source) {
+            return Some(format!("{}{}", stem, declaration));
+        }
+    }
+    None
+}
+
+fn output_stem(name: &str) -> Option<&str> {
+    [".d.ts", ".d.mts", ".d.cts", ".js", ".mjs", ".cjs"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+}
+
+fn source_extensions(output_name: &str) -> &'static [&'static str] {
+    if output_name.ends_with(".d.mts") || output_name.ends_with(".mjs") {
+        &[".mts"]
+    } else if output_name.ends_with(".d.cts") || output_name.ends_with(".cjs") {
+        &[".cts"]
+    } else {
+        &[".ts", ".tsx", ".js", ".jsx"]
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    crate::services::import_rewrite::normalize(path)
+}
+
+/// Forward-slash path without the Windows verbatim prefix `canonicalize` adds
+fn path_string(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    text.strip_prefix(r"\\?\")
+        .unwrap_or(&text)
+        .replace('\\', "/")
+}
+
+// ============================================================================
+// State
+// ============================================================================
+
+struct CachedGraph {
+    /// Modification times of every config the graph was built from
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+    graph: Arc<ProjectReferenceGraph>,
+}
+
+/// Reference graphs keyed by root tsconfig, rebuilt when any involved config changes
+#[derive(Clone, Default)]
+pub struct TsProjectState {
+    graphs: Arc<Mutex<HashMap<PathBuf, CachedGraph>>>,
+}
+
+impl TsProjectState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached reference graphs
+    pub fn cached_count(&self) -> usize {
+        self.graphs.lock().unwrap().len()
+    }
+
+    pub fn graph_for(&self, tsconfig: &Path) -> Arc<ProjectReferenceGraph> {
+        let key = config_file(tsconfig);
+        let mut graphs = self.graphs.lock().unwrap();
+        if let Some(cached) = graphs.get(&key) {
+            if cached
+                .stamps
+                .iter()
+                .all(|(path, stamp)| modified(path) == *stamp)
+            {
+                return Arc::clone(&cached.graph);
+            }
+        }
+
+        let graph = Arc::new(ProjectReferenceGraph::load(&key));
+        // `extends` bases aren't tracked; editing one needs a reference or root touch
+        let stamps = graph
+            .projects
+            .iter()
+            .map(|p| {
+                let path = PathBuf::from(&p.config_path);
+                let stamp = modified(&path);
+                (path, stamp)
+            })
+            .collect();
+        graphs.insert(
+            key,
+            CachedGraph {
+                stamps,
+                graph: Arc::clone(&graph),
+            },
+        );
+        graph
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Load the project-reference graph rooted at a tsconfig
+///
+/// # Arguments
+/// * `tsconfig` - Path to a tsconfig file, or a directory containing `tsconfig.json`
+#[fluxel_command]
+pub async fn get_ts_project_references(
+    tsconfig: String,
+    state: State<'_, TsProjectState>,
+) -> Result<ProjectReferenceGraph, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = config_file(Path::new(&tsconfig));
+        if !path.is_file() {
+            return Err(format!("tsconfig not found: {}", tsconfig));
+        }
+        Ok(state.graph_for(&path).as_ref().clone())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Find the sub-project that owns a source file
+///
+/// # Arguments
+/// * `tsconfig` - Root tsconfig (file or directory) of the reference graph
+/// * `file` - Source file to look up
+#[fluxel_command]
+pub async fn find_ts_project_for_file(
+    tsconfig: String,
+    file: String,
+    state: State<'_, TsProjectState>,
+) -> Result<Option<TsProject>, String> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let graph = state.graph_for(Path::new(&tsconfig));
+        Ok(graph.owner_of(Path::new(&file)).cloned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn builds_graph_and_maps_between_sources_and_outputs() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-ts-projects-graph-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        write(
+            &dir.join("tsconfig.json"),
+            r#"{
+  // Solution-style root
+  "files": [],
+  "references": [{ "path": "./packages/app" }, { "path": "./packages/core" },],
+}"#,
+        );
+        write(
+            &dir.join("tsconfig.base.json"),
+            r#"{ "compilerOptions": { "composite": true, "outDir": "./should-be-overridden" } }"#,
+        );
+        write(
+            &dir.join("packages/core/tsconfig.json"),
+            r#"{
+  "extends": "../../tsconfig.base.json",
+  "compilerOptions": { "outDir": "dist", "rootDir": "src" },
+  "include": ["src"]
+}"#,
+        );
+        write(
+            &dir.join("packages/app/tsconfig.json"),
+            r#"{
+  "extends": "../../tsconfig.base.json",
+  "compilerOptions": { "outDir": "lib" },
+  "include": ["src/**/*"],
+  "references": [{ "path": "../core" }, { "path": "../missing" }]
+}"#,
+        );
+        write(&dir.join("packages/core/src/util/math.ts"), "");
+        write(&dir.join("packages/core/dist/util/math.d.ts"), "");
+        write(&dir.join("packages/app/src/main.tsx"), "");
+
+        let graph = ProjectReferenceGraph::load(&dir);
+        assert_eq!(graph.projects.len(), 3);
+        assert_eq!(graph.errors.len(), 1, "{:?}", graph.errors);
+        assert!(graph.errors[0].contains("missing"));
+
+        let core = graph
+            .projects
+            .iter()
+            .find(|p| p.config_path.ends_with("core/tsconfig.json"))
+            .unwrap();
+        assert!(core.composite);
+        assert!(core
+            .out_dir
+            .as_deref()
+            .unwrap()
+            .ends_with("packages/core/dist"));
+        let app = graph
+            .projects
+            .iter()
+            .find(|p| p.config_path.ends_with("app/tsconfig.json"))
+            .unwrap();
+        // Inferred from `include`
+        assert!(app.root_dir.ends_with("packages/app/src"));
+
+        let source = dir.join("packages/core/src/util/math.ts");
+        let declaration = dir.join("packages/core/dist/util/math.d.ts");
+        assert_eq!(
+            graph.owner_of(&source).map(|p| p.config_path.as_str()),
+            Some(core.config_path.as_str())
+        );
+        assert!(graph.owner_of(&declaration).is_none());
+        assert_eq!(
+            graph
+                .owner_of(&dir.join("packages/app/src/main.tsx"))
+                .map(|p| &p.config_path),
+            Some(&app.config_path)
+        );
+
+        let canonical = |p: &Path| p.canonicalize().unwrap();
+        assert_eq!(
+            graph.map_resolved(&declaration, ReferenceStrategy::Source),
+            Some(canonical(&source))
+        );
+        assert_eq!(
+            graph.map_resolved(&source, ReferenceStrategy::Declarations),
+            Some(canonical(&declaration))
+        );
+        assert_eq!(graph.map_resolved(&source, ReferenceStrategy::Source), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,712 @@
+//! Unused Exports Service
+//!
+//! Cross-references the imports of every JS/TS source in a project against the
+//! exports of the files they resolve to, and lists the exports nothing imports.
+//! Uses flow through re-exports: a barrel's `export { a } from './a'` only keeps
+//! `a` alive if something imports it from the barrel. Namespace imports,
+//! `import()` and `require()` count as using every export of the target.
+//!
+//! Files other tools load directly are entry points whose exports are always
+//! used: `package.json` entries (`main`, `module`, `types`, `bin`, `exports`),
+//! config files, tests and stories, declaration files, and framework route files.
+
+use crate::services::import_rewrite::{
+    normalize, resolve_relative, source_files, RESOLVE_EXTENSIONS, SOURCE_EXTENSIONS,
+};
+use camino::Utf8PathBuf;
+use fluxel_macros::fluxel_command;
+use fluxel_node_resolver::{
+    collect_exports_native, collect_import_specifiers_native, resolve_module_native, ExportedName,
+    ImportBinding, ImportKind, ImportSpecifier, ResolveOptions, ResolveRequest,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Next.js app router files, loaded by the framework
+const ROUTE_FILE_STEMS: &[&str] = &[
+    "page",
+    "layout",
+    "template",
+    "loading",
+    "error",
+    "global-error",
+    "not-found",
+    "route",
+    "default",
+];
+
+/// Directories whose files are routes of file-system routers
+const ROUTE_DIRS: &[&str] = &["pages", "routes"];
+
+/// Directories package entries usually point into, built from `src`
+const BUILD_DIRS: &[&str] = &["dist", "build", "lib", "out", "esm", "cjs"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedExport {
+    /// Relative to the project root, `/`-separated
+    pub file: String,
+    /// 1-based line of the exported name
+    pub line: usize,
+    pub name: String,
+    /// Interfaces, type aliases and `export type`
+    pub type_only: bool,
+    /// Forwarded from another file with `export ... from`
+    pub re_export: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedExportsReport {
+    pub files_analyzed: usize,
+    /// Files whose exports count as used, relative to the project root
+    pub entry_points: Vec<String>,
+    /// Ordered by file, then line
+    pub unused: Vec<UnusedExport>,
+    /// Files that couldn't be parsed; their imports are missing from the analysis
+    pub errors: Vec<String>,
+}
+
+/// `export { imported as local } from 'target'`, or `export * as local from 'target'`
+/// when `imported` is `None`
+#[derive(Debug, Clone)]
+struct ReExport {
+    local: String,
+    target: PathBuf,
+    imported: Option<String>,
+    type_only: bool,
+    /// Byte offset of the specifier
+    start: usize,
+}
+
+/// What one file exports, from its own declarations and from other files
+#[derive(Debug, Default)]
+struct FileExports {
+    exports: Vec<ExportedName>,
+    re_exports: Vec<ReExport>,
+    /// `export * from 'target'`
+    star_targets: Vec<PathBuf>,
+}
+
+impl FileExports {
+    fn exports_name(&self, name: &str) -> bool {
+        self.exports.iter().any(|export| export.name == name)
+            || self
+                .re_exports
+                .iter()
+                .any(|re_export| re_export.local == name)
+    }
+}
+
+/// Export names of a file that something uses
+#[derive(Default)]
+struct Usage {
+    /// Every export, e.g. through a namespace import
+    all: bool,
+    names: HashSet<String>,
+}
+
+/// A use of `name` of a file, or of all its exports for `None`
+type Use = (PathBuf, Option<String>);
+
+/// An export nothing uses
+#[derive(Debug, PartialEq)]
+struct Unused {
+    file: PathBuf,
+    name: String,
+    start: usize,
+    type_only: bool,
+    re_export: bool,
+}
+
+/// Exports of `files` that none of `uses` reach, directly or through re-exports
+fn find_unused(files: &HashMap<PathBuf, FileExports>, mut uses: Vec<Use>) -> Vec<Unused> {
+    let mut usage: HashMap<&Path, Usage> = HashMap::new();
+    while let Some((file, name)) = uses.pop() {
+        let Some((file, exports)) = files.get_key_value(&file) else {
+            continue;
+        };
+        let used = usage.entry(file).or_default();
+        match name {
+            None => {
+                if used.all {
+                    continue;
+                }
+                used.all = true;
+                for re_export in &exports.re_exports {
+                    uses.push((re_export.target.clone(), re_export.imported.clone()));
+                }
+                for target in &exports.star_targets {
+                    uses.push((target.clone(), None));
+                }
+            }
+            Some(name) => {
+                if used.all || !used.names.insert(name.clone()) {
+                    continue;
+                }
+                for re_export in exports.re_exports.iter().filter(|r| r.local == name) {
+                    uses.push((re_export.target.clone(), re_export.imported.clone()));
+                }
+                // `export *` forwards the names the file doesn't export itself,
+                // except `default`
+                if name != "default" && !exports.exports_name(&name) {
+                    for target in &exports.star_targets {
+                        uses.push((target.clone(), Some(name.clone())));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut unused = Vec::new();
+    for (file, exports) in files {
+        let used = usage.get(file.as_path());
+        if used.is_some_and(|used| used.all) {
+            continue;
+        }
+        let is_used = |name: &str| used.is_some_and(|used| used.names.contains(name));
+        for export in exports.exports.iter().filter(|e| !is_used(&e.name)) {
+            unused.push(Unused {
+                file: file.clone(),
+                name: export.name.clone(),
+                start: export.start,
+                type_only: export.type_only,
+                re_export: false,
+            });
+        }
+        for re_export in exports.re_exports.iter().filter(|r| !is_used(&r.local)) {
+            unused.push(Unused {
+                file: file.clone(),
+                name: re_export.local.clone(),
+                start: re_export.start,
+                type_only: re_export.type_only,
+                re_export: true,
+            });
+        }
+    }
+    unused
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/// Resolves import specifiers to project files, including tsconfig `paths`
+/// aliases and workspace packages linked into `node_modules`
+struct ImportResolver {
+    root: PathBuf,
+    /// `paths` patterns with their targets and the directory they're relative to
+    aliases: Vec<(String, Vec<String>, PathBuf)>,
+    base_url: Option<PathBuf>,
+    /// Bare specifiers by importing directory
+    packages: HashMap<(PathBuf, String), Option<PathBuf>>,
+}
+
+impl ImportResolver {
+    /// Read `baseUrl` and `paths` from the `tsconfig*.json` files in `root`
+    fn new(root: &Path) -> Self {
+        let mut resolver = Self {
+            root: root.to_path_buf(),
+            aliases: Vec::new(),
+            base_url: None,
+            packages: HashMap::new(),
+        };
+        let Ok(entries) = fs::read_dir(root) else {
+            return resolver;
+        };
+        let mut configs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("tsconfig") && name.ends_with(".json"))
+            })
+            .collect();
+        configs.sort();
+        for config in configs {
+            let Some(options) = fs::read_to_string(&config)
+                .ok()
+                .and_then(|text| json5::from_str::<Value>(&text).ok())
+                .and_then(|json| json.get("compilerOptions").cloned())
+            else {
+                continue;
+            };
+            let base_url = options
+                .get("baseUrl")
+                .and_then(Value::as_str)
+                .map(|base| normalize(&root.join(base)));
+            let base = base_url.clone().unwrap_or_else(|| root.to_path_buf());
+            if let Some(paths) = options.get("paths").and_then(Value::as_object) {
+                for (pattern, targets) in paths {
+                    let targets = targets
+                        .as_array()
+                        .map(|targets| {
+                            targets
+                                .iter()
+                                .filter_map(|t| t.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    resolver
+                        .aliases
+                        .push((pattern.clone(), targets, base.clone()));
+                }
+            }
+            resolver.base_url = resolver.base_url.or(base_url);
+        }
+        resolver
+    }
+
+    fn resolve(&mut self, importer: &Path, specifier: &str) -> Option<PathBuf> {
+        if specifier.starts_with('.') {
+            return resolve_relative(importer, specifier);
+        }
+        if specifier.starts_with('/') || specifier.contains(':') {
+            return None;
+        }
+        for (pattern, targets, base) in &self.aliases {
+            let Some(matched) = match_alias(pattern, specifier) else {
+                continue;
+            };
+            let resolved = targets
+                .iter()
+                .find_map(|target| resolve_in(base, &target.replace('*', matched)));
+            if resolved.is_some() {
+                return resolved;
+            }
+        }
+        if let Some(resolved) = self
+            .base_url
+            .as_deref()
+            .and_then(|base| resolve_in(base, specifier))
+        {
+            return Some(resolved);
+        }
+
+        let dir = importer.parent()?.to_path_buf();
+        let root = &self.root;
+        self.packages
+            .entry((dir, specifier.to_string()))
+            .or_insert_with(|| {
+                let options = ResolveOptions {
+                    extensions: RESOLVE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+                    ..Default::default()
+                };
+                let response = resolve_module_native(
+                    ResolveRequest {
+                        specifier: specifier.to_string(),
+                        importer: importer.to_string_lossy().to_string(),
+                        project_root: Some(root.to_string_lossy().to_string()),
+                    },
+                    Some(options),
+                )
+                .ok()?;
+                // Workspace packages are symlinked into `node_modules`
+                fs::canonicalize(response.resolved_path?).ok()
+            })
+            .clone()
+    }
+}
+
+/// The part of `specifier` matching the `*` of a `paths` pattern
+fn match_alias<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier
+            .strip_prefix(prefix)?
+            .strip_suffix(suffix)
+            .filter(|_| specifier.len() >= prefix.len() + suffix.len()),
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+/// Resolve `path`, relative to `dir`, like a relative import
+fn resolve_in(dir: &Path, path: &str) -> Option<PathBuf> {
+    let specifier = format!("./{}", path.trim_start_matches("./"));
+    // Relative imports resolve against the importer's directory
+    resolve_relative(&dir.join("index"), &specifier)
+}
+
+// ============================================================================
+// Entry Points
+// ============================================================================
+
+/// Config files, tests, stories, declarations and framework routes
+fn is_conventional_entry(relative: &Path) -> bool {
+    let Some(name) = relative.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let stem = name.split('.').next().unwrap_or(name);
+    let dirs: Vec<&str> = relative
+        .parent()
+        .map(|parent| parent.iter().filter_map(|c| c.to_str()).collect())
+        .unwrap_or_default();
+
+    // Dotfiles such as `.eslintrc.cjs` are configs too
+    name.starts_with('.')
+        || [".config.", ".test.", ".spec.", ".stories.", ".story.", ".d."]
+            .iter()
+            .any(|marker| name.contains(marker))
+        || dirs.iter().any(|dir| matches!(*dir, "__tests__" | "__mocks__"))
+        // SvelteKit `+page.ts`, `+layout.server.ts`, ...
+        || stem.starts_with('+')
+        || (ROUTE_FILE_STEMS.contains(&stem) && dirs.contains(&"app"))
+        || dirs.iter().any(|dir| ROUTE_DIRS.contains(dir))
+}
+
+/// Files referenced by the `package.json` in `dir`
+fn package_entries(dir: &Path, files: &HashMap<PathBuf, FileExports>) -> Vec<PathBuf> {
+    let Some(manifest) = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+    else {
+        return Vec::new();
+    };
+    let mut targets = Vec::new();
+    for field in [
+        "main", "module", "types", "typings", "browser", "source", "bin", "exports",
+    ] {
+        if let Some(value) = manifest.get(field) {
+            collect_strings(value, &mut targets);
+        }
+    }
+    targets
+        .iter()
+        .filter(|target| !target.contains('*'))
+        .filter_map(|target| entry_source(&normalize(&dir.join(target)), dir, files))
+        .collect()
+}
+
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, out)),
+        _ => {}
+    }
+}
+
+/// The source file behind a package entry, which usually points at build output
+/// (`dist/index.js` for `src/index.ts`)
+fn entry_source(
+    target: &Path,
+    package_dir: &Path,
+    files: &HashMap<PathBuf, FileExports>,
+) -> Option<PathBuf> {
+    if files.contains_key(target) {
+        return Some(target.to_path_buf());
+    }
+    let relative = target.strip_prefix(package_dir).ok()?;
+    let name = relative.file_name()?.to_str()?;
+    let stem = name.split('.').next().unwrap_or(name);
+    let parent = relative.parent().unwrap_or(Path::new(""));
+    let mut dirs = vec![package_dir.join(parent)];
+    let mut components = parent.iter();
+    if components
+        .next()
+        .and_then(|first| first.to_str())
+        .is_some_and(|first| BUILD_DIRS.contains(&first))
+    {
+        dirs.push(package_dir.join("src").join(components.as_path()));
+    }
+    dirs.iter()
+        .flat_map(|dir| {
+            SOURCE_EXTENSIONS
+                .iter()
+                .map(move |ext| dir.join(format!("{}.{}", stem, ext)))
+        })
+        .find(|candidate| files.contains_key(candidate))
+}
+
+// ============================================================================
+// Analysis
+// ============================================================================
+
+/// Record the uses and re-exports of one import specifier of a file
+fn record_specifier(
+    specifier: &ImportSpecifier,
+    target: PathBuf,
+    exports: &mut FileExports,
+    uses: &mut Vec<Use>,
+) {
+    let bindings = specifier
+        .bindings
+        .iter()
+        .map(|binding| (binding, specifier.type_only))
+        .chain(
+            specifier
+                .type_bindings
+                .iter()
+                .map(|binding| (binding, true)),
+        );
+    match specifier.kind {
+        ImportKind::Import => {
+            for (binding, _) in bindings {
+                let name = match binding {
+                    ImportBinding::Named { imported, .. } => Some(imported.clone()),
+                    ImportBinding::Default { .. } => Some("default".to_string()),
+                    ImportBinding::Namespace { .. } => None,
+                };
+                uses.push((target.clone(), name));
+            }
+        }
+        ImportKind::DynamicImport | ImportKind::Require => uses.push((target, None)),
+        ImportKind::ReExport => {
+            for (binding, type_only) in bindings {
+                let (local, imported) = match binding {
+                    ImportBinding::Namespace { local } if local == "*" => {
+                        exports.star_targets.push(target.clone());
+                        continue;
+                    }
+                    ImportBinding::Named { imported, local } => (local, Some(imported.clone())),
+                    ImportBinding::Default { local } => (local, Some("default".to_string())),
+                    ImportBinding::Namespace { local } => (local, None),
+                };
+                exports.re_exports.push(ReExport {
+                    local: local.clone(),
+                    target: target.clone(),
+                    imported,
+                    type_only,
+                    start: specifier.start,
+                });
+            }
+        }
+    }
+}
+
+/// List the exports of JS/TS sources under `root` that no other file imports
+pub fn analyze_unused_exports_in(root: &Path) -> UnusedExportsReport {
+    let root = root.canonicalize().unwrap_or_else(|_| normalize(root));
+    let mut resolver = ImportResolver::new(&root);
+    let mut files: HashMap<PathBuf, FileExports> = HashMap::new();
+    let mut specifiers: Vec<(PathBuf, Vec<ImportSpecifier>)> = Vec::new();
+    let mut errors = Vec::new();
+
+    for file in source_files(&root) {
+        let Ok(utf8_file) = Utf8PathBuf::from_path_buf(file.clone()) else {
+            continue;
+        };
+        let parsed = collect_exports_native(&utf8_file)
+            .and_then(|exports| Ok((exports, collect_import_specifiers_native(&utf8_file)?)));
+        match parsed {
+            Ok((exports, imports)) => {
+                files.insert(
+                    file.clone(),
+                    FileExports {
+                        exports,
+                        ..Default::default()
+                    },
+                );
+                specifiers.push((file, imports));
+            }
+            Err(e) => errors.push(format!("{}: {}", relative_display(&root, &file), e)),
+        }
+    }
+
+    let mut uses = Vec::new();
+    for (file, imports) in specifiers {
+        for specifier in imports {
+            let Some(target) = resolver.resolve(&file, &specifier.specifier) else {
+                continue;
+            };
+            if !files.contains_key(&target) || target == file {
+                continue;
+            }
+            let exports = files.get_mut(&file).expect("parsed files are recorded");
+            record_specifier(&specifier, target, exports, &mut uses);
+        }
+    }
+
+    let mut entry_points: HashSet<PathBuf> = files
+        .keys()
+        .filter(|file| file.strip_prefix(&root).is_ok_and(is_conventional_entry))
+        .cloned()
+        .collect();
+    let dirs: HashSet<&Path> = files
+        .keys()
+        .flat_map(|file| {
+            file.ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(&root))
+        })
+        .collect();
+    for dir in dirs {
+        if dir.join("package.json").is_file() {
+            entry_points.extend(package_entries(dir, &files));
+        }
+    }
+    uses.extend(entry_points.iter().map(|file| (file.clone(), None)));
+
+    let mut contents: HashMap<PathBuf, String> = HashMap::new();
+    let mut unused: Vec<UnusedExport> = find_unused(&files, uses)
+        .into_iter()
+        .map(|found| {
+            let content = contents
+                .entry(found.file.clone())
+                .or_insert_with(|| fs::read_to_string(&found.file).unwrap_or_default());
+            UnusedExport {
+                file: relative_display(&root, &found.file),
+                line: content
+                    .get(..found.start)
+                    .map_or(1, |before| before.matches('\n').count() + 1),
+                name: found.name,
+                type_only: found.type_only,
+                re_export: found.re_export,
+            }
+        })
+        .collect();
+    unused.sort_by(|a, b| (&a.file, a.line, &a.name).cmp(&(&b.file, b.line, &b.name)));
+
+    let mut entry_points: Vec<String> = entry_points
+        .iter()
+        .map(|file| relative_display(&root, file))
+        .collect();
+    entry_points.sort();
+
+    UnusedExportsReport {
+        files_analyzed: files.len(),
+        entry_points,
+        unused,
+        errors,
+    }
+}
+
+fn relative_display(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List the exports no file in the project imports
+///
+/// # Arguments
+/// * `project_root` - Project root whose sources are analyzed
+#[fluxel_command]
+pub async fn analyze_unused_exports(project_root: String) -> Result<UnusedExportsReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&project_root);
+        if !root.is_dir() {
+            return Err(format!("Project root not found: {}", project_root));
+        }
+        let report = analyze_unused_exports_in(&root);
+        println!(
+            "[UnusedExports] {} unused exports across {} files",
+            report.unused.len(),
+            report.files_analyzed
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(name: &str, type_only: bool) -> ExportedName {
+        ExportedName {
+            name: name.to_string(),
+            type_only,
+            start: 0,
+        }
+    }
+
+    #[test]
+    fn test_uses_flow_through_re_exports() {
+        let file = |name: &str| PathBuf::from("/app/src").join(name);
+        let mut files = HashMap::new();
+        files.insert(
+            file("a.ts"),
+            FileExports {
+                exports: vec![export("x", false), export("y", false), export("T", true)],
+                ..Default::default()
+            },
+        );
+        files.insert(
+            file("b.ts"),
+            FileExports {
+                exports: vec![export("z", false), export("w", false)],
+                ..Default::default()
+            },
+        );
+        // index.ts: export { x } from './a'; export * from './b'; export * as c from './c'
+        files.insert(
+            file("index.ts"),
+            FileExports {
+                re_exports: vec![
+                    ReExport {
+                        local: "x".to_string(),
+                        target: file("a.ts"),
+                        imported: Some("x".to_string()),
+                        type_only: false,
+                        start: 0,
+                    },
+                    ReExport {
+                        local: "c".to_string(),
+                        target: file("c.ts"),
+                        imported: None,
+                        type_only: false,
+                        start: 0,
+                    },
+                ],
+                star_targets: vec![file("b.ts")],
+                ..Default::default()
+            },
+        );
+        files.insert(
+            file("c.ts"),
+            FileExports {
+                exports: vec![export("helper", false)],
+                ..Default::default()
+            },
+        );
+        files.insert(
+            file("lazy.ts"),
+            FileExports {
+                exports: vec![export("default", false)],
+                ..Default::default()
+            },
+        );
+
+        // main.ts: import { x, z } from './index'; import('./lazy')
+        let uses = vec![
+            (file("index.ts"), Some("x".to_string())),
+            (file("index.ts"), Some("z".to_string())),
+            (file("lazy.ts"), None),
+        ];
+        let mut unused: Vec<(String, String, bool)> = find_unused(&files, uses)
+            .into_iter()
+            .map(|u| {
+                let name = u.file.file_name().unwrap().to_string_lossy().to_string();
+                (name, u.name, u.re_export)
+            })
+            .collect();
+        unused.sort();
+        assert_eq!(
+            unused,
+            [
+                ("a.ts".to_string(), "T".to_string(), false),
+                ("a.ts".to_string(), "y".to_string(), false),
+                ("b.ts".to_string(), "w".to_string(), false),
+                ("c.ts".to_string(), "helper".to_string(), false),
+                ("index.ts".to_string(), "c".to_string(), true),
+            ]
+        );
+
+        assert!(is_conventional_entry(Path::new("vite.config.ts")));
+        assert!(is_conventional_entry(Path::new("src/app/blog/page.tsx")));
+        assert!(is_conventional_entry(Path::new("src/routes/+page.ts")));
+        assert!(!is_conventional_entry(Path::new("src/lib/page.ts")));
+        assert_eq!(match_alias("@/*", "@/lib/util"), Some("lib/util"));
+        assert_eq!(match_alias("@/*", "@scope/pkg"), None);
+    }
+}
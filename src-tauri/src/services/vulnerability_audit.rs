@@ -0,0 +1,850 @@
+//! Vulnerability Audit Service
+//!
+//! Builds the list of resolved dependency versions (from lockfiles, falling back
+//! to exact manifest versions) and checks them against the OSV.dev database.
+//! Results are cached in `~/.fluxel/cache/osv.json` so the last known state is
+//! still available offline.
+
+use crate::services::dependency_audit::{
+    collect_declared_dependencies, compare_versions, find_manifests, Ecosystem,
+};
+use crate::services::lockfile::{find_lockfiles, parse_lockfile_at};
+use fluxel_macros::fluxel_command;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const OSV_QUERY_BATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+const OSV_VULN_URL: &str = "https://api.osv.dev/v1/vulns";
+
+/// OSV accepts at most 1000 queries per batch
+const OSV_BATCH_SIZE: usize = 1000;
+
+/// Parallel advisory detail requests
+const OSV_FETCH_CONCURRENCY: usize = 8;
+
+/// Cached results younger than this are used without asking OSV again
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// A resolved package version to check
+#[derive(Debug, Clone)]
+struct PackageVersion {
+    ecosystem: Ecosystem,
+    name: String,
+    version: String,
+    dev: bool,
+    paths: Vec<Vec<String>>,
+}
+
+impl PackageVersion {
+    fn cache_key(&self) -> String {
+        format!("{}:{}@{}", self.ecosystem.as_str(), self.name, self.version)
+    }
+}
+
+/// Summary of an OSV advisory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Advisory {
+    pub id: String,
+    pub summary: String,
+    pub aliases: Vec<String>,
+    /// "low", "moderate", "high", "critical", or "unknown"
+    pub severity: String,
+    /// CVSS vector, when the advisory has one
+    pub cvss: Option<String>,
+    /// Versions that fix the advisory, per affected package name
+    pub fixed_versions: BTreeMap<String, Vec<String>>,
+    pub url: String,
+    pub published: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerablePackage {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub version: String,
+    pub dev: bool,
+    /// Highest severity among the package's advisories
+    pub severity: String,
+    /// Advisory ids (keys into `VulnerabilityReport::advisories`)
+    pub advisories: Vec<String>,
+    /// Direct dependencies that pull this package in
+    pub via: Vec<String>,
+    /// Full dependency paths from a direct dependency
+    pub paths: Vec<Vec<String>>,
+    /// Lowest fixed version above the installed one, if known
+    pub fixed_in: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerabilityReport {
+    pub root: String,
+    /// Number of package versions checked
+    pub scanned: usize,
+    /// Number of vulnerable package versions (the badge count)
+    pub vulnerability_count: usize,
+    pub severity_counts: BTreeMap<String, usize>,
+    pub packages: Vec<VulnerablePackage>,
+    pub advisories: BTreeMap<String, Advisory>,
+    /// Package versions with no result (offline and not cached)
+    pub unchecked: usize,
+    /// True when OSV couldn't be reached and cached results were used
+    pub offline: bool,
+    pub errors: Vec<String>,
+}
+
+// ============================================================================
+// Dependency Collection
+// ============================================================================
+
+/// A manifest version is usable without a lockfile only when it pins one version
+fn exact_version(requirement: &str, ecosystem: Ecosystem) -> Option<String> {
+    let requirement = requirement.trim();
+    let version = match ecosystem {
+        Ecosystem::Npm => requirement,
+        Ecosystem::Cargo => requirement.strip_prefix('=')?.trim(),
+        // "[1.2.3]" is NuGet's exact form; a bare version means ">= 1.2.3" but
+        // restores that exact version when available
+        Ecosystem::Nuget => requirement
+            .strip_prefix('[')
+            .and_then(|r| r.strip_suffix(']'))
+            .unwrap_or(requirement),
+    };
+    let looks_exact = version.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+    looks_exact.then(|| version.to_string())
+}
+
+/// Resolved versions for the workspace: lockfiles next to each manifest, then exact
+/// manifest versions for ecosystems without a lockfile there
+fn collect_package_versions(root: &Path, errors: &mut Vec<String>) -> Vec<PackageVersion> {
+    let mut packages: BTreeMap<String, PackageVersion> = BTreeMap::new();
+    let mut locked_dirs: HashSet<(PathBuf, Ecosystem)> = HashSet::new();
+    let mut seen_lockfiles: HashSet<PathBuf> = HashSet::new();
+
+    let mut push = |package: PackageVersion| {
+        let entry = packages
+            .entry(package.cache_key())
+            .or_insert_with(|| PackageVersion {
+                paths: Vec::new(),
+                ..package.clone()
+            });
+        entry.dev &= package.dev;
+        for path in package.paths {
+            if !entry.paths.contains(&path) {
+                entry.paths.push(path);
+            }
+        }
+    };
+
+    for manifest in find_manifests(root) {
+        let Some(dir) = manifest.parent() else {
+            continue;
+        };
+        for (kind, path) in find_lockfiles(dir) {
+            locked_dirs.insert((dir.to_path_buf(), kind.ecosystem()));
+            if !seen_lockfiles.insert(path.clone()) {
+                continue;
+            }
+            match parse_lockfile_at(kind, &path) {
+                Ok(lockfile) => {
+                    for (name, versions) in lockfile.packages {
+                        for (version, resolved) in versions {
+                            push(PackageVersion {
+                                ecosystem: lockfile.ecosystem,
+                                name: name.clone(),
+                                version,
+                                dev: resolved.dev,
+                                paths: resolved.paths,
+                            });
+                        }
+                    }
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    for dependency in collect_declared_dependencies(root) {
+        let dir = root
+            .join(&dependency.manifest)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| root.to_path_buf());
+        if locked_dirs.contains(&(dir, dependency.ecosystem)) {
+            continue;
+        }
+        let Some(version) = dependency
+            .requirement
+            .as_deref()
+            .and_then(|r| exact_version(r, dependency.ecosystem))
+        else {
+            continue;
+        };
+        push(PackageVersion {
+            ecosystem: dependency.ecosystem,
+            name: dependency.name.clone(),
+            version,
+            dev: dependency.dev,
+            paths: vec![vec![dependency.name]],
+        });
+    }
+
+    packages.into_values().collect()
+}
+
+// ============================================================================
+// OSV Cache
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedQuery {
+    ids: Vec<String>,
+    fetched_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedAdvisory {
+    advisory: Advisory,
+    fetched_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OsvCache {
+    #[serde(default)]
+    queries: HashMap<String, CachedQuery>,
+    #[serde(default)]
+    advisories: HashMap<String, CachedAdvisory>,
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".fluxel").join("cache").join("osv.json"))
+}
+
+fn load_cache(path: &Path) -> OsvCache {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &Path, cache: &OsvCache) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+    let json = serde_json::to_string(cache).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write OSV cache: {}", e))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// OSV API
+// ============================================================================
+
+fn osv_ecosystem(ecosystem: Ecosystem) -> &'static str {
+    match ecosystem {
+        Ecosystem::Npm => "npm",
+        Ecosystem::Nuget => "NuGet",
+        Ecosystem::Cargo => "crates.io",
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvBatchResponse {
+    #[serde(default)]
+    results: Vec<OsvBatchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvBatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVulnRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvVulnRef {
+    id: String,
+}
+
+/// Ask OSV which advisories affect each package version. Returns ids in query order.
+async fn query_batch(
+    client: &reqwest::Client,
+    packages: &[&PackageVersion],
+) -> Result<Vec<Vec<String>>, String> {
+    let queries: Vec<serde_json::Value> = packages
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "package": { "name": p.name, "ecosystem": osv_ecosystem(p.ecosystem) },
+                "version": p.version,
+            })
+        })
+        .collect();
+
+    let response = client
+        .post(OSV_QUERY_BATCH_URL)
+        .json(&serde_json::json!({ "queries": queries }))
+        .send()
+        .await
+        .map_err(|e| format!("OSV request failed: {e}"))?;
+
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read OSV response: {e}"))?;
+    if !status.is_success() {
+        return Err(format!("OSV error {status}: {text}"));
+    }
+
+    let parsed: OsvBatchResponse =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse OSV response: {e}"))?;
+    if parsed.results.len() != packages.len() {
+        return Err(format!(
+            "OSV returned {} results for {} queries",
+            parsed.results.len(),
+            packages.len()
+        ));
+    }
+
+    Ok(parsed
+        .results
+        .into_iter()
+        .map(|r| r.vulns.into_iter().map(|v| v.id).collect())
+        .collect())
+}
+
+async fn fetch_advisory(client: &reqwest::Client, id: &str) -> Result<Advisory, String> {
+    let response = client
+        .get(format!("{}/{}", OSV_VULN_URL, id))
+        .send()
+        .await
+        .map_err(|e| format!("OSV request failed: {e}"))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read OSV response: {e}"))?;
+    if !status.is_success() {
+        return Err(format!("OSV error {status} for {id}"));
+    }
+    let vuln: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse {id}: {e}"))?;
+    Ok(advisory_from_osv(&vuln))
+}
+
+/// Map a CVSS v3 vector to a qualitative rating using its impact metrics. This
+/// approximates the base score closely enough to rank advisories.
+fn severity_from_cvss(vector: &str) -> Option<&'static str> {
+    if !vector.starts_with("CVSS:3") {
+        return None;
+    }
+    let metrics: HashMap<&str, &str> = vector
+        .split('/')
+        .filter_map(|part| part.split_once(':'))
+        .collect();
+    let high_impacts = ["C", "I", "A"]
+        .iter()
+        .filter(|m| metrics.get(*m) == Some(&"H"))
+        .count();
+    let any_impact = ["C", "I", "A"]
+        .iter()
+        .any(|m| matches!(metrics.get(*m), Some(&"H") | Some(&"L")));
+    let network = metrics.get("AV") == Some(&"N");
+    let easy = metrics.get("AC") == Some(&"L") && metrics.get("PR") == Some(&"N");
+
+    Some(match (high_impacts, any_impact) {
+        (_, false) => "low",
+        (3, _) if network && easy => "critical",
+        (h, _) if h >= 1 && (network || easy) => "high",
+        _ => "moderate",
+    })
+}
+
+fn advisory_from_osv(vuln: &serde_json::Value) -> Advisory {
+    let str_field = |v: &serde_json::Value, key: &str| {
+        v.get(key)
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .unwrap_or_default()
+    };
+
+    let id = str_field(vuln, "id");
+    let summary = Some(str_field(vuln, "summary"))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| {
+            str_field(vuln, "details")
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string()
+        });
+    let aliases = vuln
+        .get("aliases")
+        .and_then(|a| a.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|s| s.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let cvss = vuln
+        .get("severity")
+        .and_then(|s| s.as_array())
+        .and_then(|list| {
+            list.iter()
+                .find(|s| str_field(s, "type").starts_with("CVSS_V3"))
+                .or_else(|| list.first())
+        })
+        .map(|s| str_field(s, "score"))
+        .filter(|s| !s.is_empty());
+
+    // GitHub advisories carry a qualitative rating; otherwise derive one from CVSS
+    let severity = vuln
+        .get("database_specific")
+        .and_then(|d| d.get("severity"))
+        .and_then(|s| s.as_str())
+        .map(|s| s.to_ascii_lowercase())
+        .map(|s| if s == "medium" { "moderate".into() } else { s })
+        .or_else(|| {
+            cvss.as_deref()
+                .and_then(severity_from_cvss)
+                .map(String::from)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut fixed_versions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for affected in vuln
+        .get("affected")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let package = affected
+            .get("package")
+            .map(|p| str_field(p, "name"))
+            .unwrap_or_default();
+        for range in affected
+            .get("ranges")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+        {
+            for event in range
+                .get("events")
+                .and_then(|e| e.as_array())
+                .into_iter()
+                .flatten()
+            {
+                if let Some(fixed) = event.get("fixed").and_then(|f| f.as_str()) {
+                    let list = fixed_versions.entry(package.clone()).or_default();
+                    if !list.iter().any(|v| v == fixed) {
+                        list.push(fixed.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    Advisory {
+        url: format!("https://osv.dev/vulnerability/{}", id),
+        id,
+        summary,
+        aliases,
+        severity,
+        cvss,
+        fixed_versions,
+        published: vuln
+            .get("published")
+            .and_then(|p| p.as_str())
+            .map(String::from),
+    }
+}
+
+// ============================================================================
+// Report
+// ============================================================================
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 4,
+        "high" => 3,
+        "moderate" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn build_report(
+    root: &Path,
+    packages: &[PackageVersion],
+    results: &HashMap<String, Vec<String>>,
+    advisories: &HashMap<String, Advisory>,
+    offline: bool,
+    errors: Vec<String>,
+) -> VulnerabilityReport {
+    let mut vulnerable = Vec::new();
+    let mut report_advisories = BTreeMap::new();
+    let mut severity_counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut unchecked = 0;
+
+    for package in packages {
+        let Some(ids) = results.get(&package.cache_key()) else {
+            unchecked += 1;
+            continue;
+        };
+        if ids.is_empty() {
+            continue;
+        }
+
+        let mut severity = "unknown".to_string();
+        let mut fixed_in: Option<String> = None;
+        for id in ids {
+            let Some(advisory) = advisories.get(id) else {
+                continue;
+            };
+            if severity_rank(&advisory.severity) > severity_rank(&severity) {
+                severity = advisory.severity.clone();
+            }
+            // The fix that applies is the lowest one above the installed version
+            for fixed in advisory
+                .fixed_versions
+                .get(&package.name)
+                .into_iter()
+                .flatten()
+            {
+                let newer = compare_versions(fixed, &package.version).is_gt();
+                let lower = fixed_in
+                    .as_deref()
+                    .is_none_or(|current| compare_versions(fixed, current).is_lt());
+                if newer && lower {
+                    fixed_in = Some(fixed.clone());
+                }
+            }
+            report_advisories.insert(id.clone(), advisory.clone());
+        }
+
+        let mut via: Vec<String> = package
+            .paths
+            .iter()
+            .filter_map(|p| p.first().cloned())
+            .collect();
+        via.sort();
+        via.dedup();
+
+        *severity_counts.entry(severity.clone()).or_default() += 1;
+        vulnerable.push(VulnerablePackage {
+            ecosystem: package.ecosystem,
+            name: package.name.clone(),
+            version: package.version.clone(),
+            dev: package.dev,
+            severity,
+            advisories: ids.clone(),
+            via,
+            paths: package.paths.clone(),
+            fixed_in,
+        });
+    }
+
+    vulnerable.sort_by(|a, b| {
+        severity_rank(&b.severity)
+            .cmp(&severity_rank(&a.severity))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    VulnerabilityReport {
+        root: root.to_string_lossy().to_string(),
+        scanned: packages.len(),
+        vulnerability_count: vulnerable.len(),
+        severity_counts,
+        packages: vulnerable,
+        advisories: report_advisories,
+        unchecked,
+        offline,
+        errors,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Check the workspace's resolved dependencies against OSV.dev.
+///
+/// # Arguments
+/// * `workspace_root` - Workspace root directory
+/// * `include_dev` - Include development-only dependencies (default: true)
+/// * `offline` - Only use cached results, never contact OSV (default: false)
+#[fluxel_command]
+pub async fn audit_vulnerabilities(
+    workspace_root: String,
+    include_dev: Option<bool>,
+    offline: Option<bool>,
+) -> Result<VulnerabilityReport, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err(format!("Workspace root not found: {}", workspace_root));
+    }
+    let include_dev = include_dev.unwrap_or(true);
+    let offline_only = offline.unwrap_or(false);
+    let cache_file = cache_path().ok_or("Failed to get home directory")?;
+
+    let (packages, mut cache, mut errors) = {
+        let root = root.clone();
+        let cache_file = cache_file.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut errors = Vec::new();
+            let packages: Vec<PackageVersion> = collect_package_versions(&root, &mut errors)
+                .into_iter()
+                .filter(|p| include_dev || !p.dev)
+                .collect();
+            (packages, load_cache(&cache_file), errors)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    let now = now_secs();
+    let mut offline = offline_only;
+    let client = reqwest::Client::new();
+
+    // Query OSV for versions without a fresh cached result
+    if !offline_only {
+        let stale: Vec<&PackageVersion> = packages
+            .iter()
+            .filter(|p| {
+                cache
+                    .queries
+                    .get(&p.cache_key())
+                    .is_none_or(|q| now.saturating_sub(q.fetched_at) > CACHE_TTL_SECS)
+            })
+            .collect();
+
+        for chunk in stale.chunks(OSV_BATCH_SIZE) {
+            match query_batch(&client, chunk).await {
+                Ok(results) => {
+                    for (package, ids) in chunk.iter().zip(results) {
+                        cache.queries.insert(
+                            package.cache_key(),
+                            CachedQuery {
+                                ids,
+                                fetched_at: now,
+                            },
+                        );
+                    }
+                }
+                Err(e) => {
+                    println!("[VulnAudit] {}", e);
+                    errors.push(e);
+                    offline = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    let results: HashMap<String, Vec<String>> = packages
+        .iter()
+        .filter_map(|p| {
+            let key = p.cache_key();
+            cache.queries.get(&key).map(|q| (key, q.ids.clone()))
+        })
+        .collect();
+
+    // Fetch details for advisories we haven't seen (or that may have been updated)
+    if !offline {
+        let mut wanted: Vec<String> = results
+            .values()
+            .flatten()
+            .filter(|id| {
+                cache
+                    .advisories
+                    .get(*id)
+                    .is_none_or(|a| now.saturating_sub(a.fetched_at) > CACHE_TTL_SECS)
+            })
+            .cloned()
+            .collect();
+        wanted.sort();
+        wanted.dedup();
+
+        let fetched: Vec<(String, Result<Advisory, String>)> = futures::stream::iter(wanted)
+            .map(|id| {
+                let client = client.clone();
+                async move {
+                    let result = fetch_advisory(&client, &id).await;
+                    (id, result)
+                }
+            })
+            .buffer_unordered(OSV_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        for (id, result) in fetched {
+            match result {
+                Ok(advisory) => {
+                    cache.advisories.insert(
+                        id,
+                        CachedAdvisory {
+                            advisory,
+                            fetched_at: now,
+                        },
+                    );
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    let advisories: HashMap<String, Advisory> = cache
+        .advisories
+        .iter()
+        .map(|(id, cached)| (id.clone(), cached.advisory.clone()))
+        .collect();
+    let report = build_report(&root, &packages, &results, &advisories, offline, errors);
+
+    if !offline_only {
+        if let Err(e) = save_cache(&cache_file, &cache) {
+            println!("[VulnAudit] {}", e);
+        }
+    }
+
+    println!(
+        "[VulnAudit] {} package versions scanned, {} vulnerable{}",
+        report.scanned,
+        report.vulnerability_count,
+        if report.offline { " (offline)" } else { "" }
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osv_advisory() {
+        let vuln = serde_json::json!({
+            "id": "GHSA-xxxx-yyyy-zzzz",
+            "summary": "Prototype pollution in lodash",
+            "aliases": ["CVE-2020-8203"],
+            "published": "2020-07-15T19:15:00Z",
+            "severity": [{ "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:H/A:H" }],
+            "database_specific": { "severity": "HIGH" },
+            "affected": [{
+                "package": { "name": "lodash", "ecosystem": "npm" },
+                "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "4.17.19" }] }]
+            }]
+        });
+        let advisory = advisory_from_osv(&vuln);
+
+        assert_eq!(advisory.severity, "high");
+        assert_eq!(advisory.aliases, vec!["CVE-2020-8203"]);
+        assert_eq!(advisory.fixed_versions["lodash"], vec!["4.17.19"]);
+        assert_eq!(
+            advisory.url,
+            "https://osv.dev/vulnerability/GHSA-xxxx-yyyy-zzzz"
+        );
+    }
+
+    #[test]
+    fn maps_advisories_to_importing_packages() {
+        let packages = vec![
+            PackageVersion {
+                ecosystem: Ecosystem::Npm,
+                name: "lodash".to_string(),
+                version: "4.17.15".to_string(),
+                dev: false,
+                paths: vec![
+                    vec!["a".to_string(), "lodash".to_string()],
+                    vec!["b".to_string(), "c".to_string(), "lodash".to_string()],
+                ],
+            },
+            PackageVersion {
+                ecosystem: Ecosystem::Npm,
+                name: "safe".to_string(),
+                version: "1.0.0".to_string(),
+                dev: false,
+                paths: vec![vec!["safe".to_string()]],
+            },
+            PackageVersion {
+                ecosystem: Ecosystem::Cargo,
+                name: "uncached".to_string(),
+                version: "0.1.0".to_string(),
+                dev: false,
+                paths: Vec::new(),
+            },
+        ];
+        let results = HashMap::from([
+            ("npm:lodash@4.17.15".to_string(), vec!["GHSA-1".to_string()]),
+            ("npm:safe@1.0.0".to_string(), Vec::new()),
+        ]);
+        let advisories = HashMap::from([(
+            "GHSA-1".to_string(),
+            Advisory {
+                id: "GHSA-1".to_string(),
+                summary: "Prototype pollution".to_string(),
+                aliases: Vec::new(),
+                severity: "high".to_string(),
+                cvss: None,
+                fixed_versions: BTreeMap::from([(
+                    "lodash".to_string(),
+                    vec!["4.17.12".to_string(), "4.17.19".to_string()],
+                )]),
+                url: String::new(),
+                published: None,
+            },
+        )]);
+
+        let report = build_report(
+            Path::new("/project"),
+            &packages,
+            &results,
+            &advisories,
+            true,
+            Vec::new(),
+        );
+
+        assert_eq!(report.scanned, 3);
+        assert_eq!(report.vulnerability_count, 1);
+        assert_eq!(report.unchecked, 1);
+        assert_eq!(report.severity_counts["high"], 1);
+        let lodash = &report.packages[0];
+        assert_eq!(lodash.via, vec!["a", "b"]);
+        assert_eq!(lodash.fixed_in.as_deref(), Some("4.17.19"));
+        assert!(report.advisories.contains_key("GHSA-1"));
+    }
+
+    #[test]
+    fn accepts_only_pinned_manifest_versions() {
+        assert_eq!(
+            exact_version("1.2.3", Ecosystem::Npm).as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(exact_version("^1.2.3", Ecosystem::Npm), None);
+        assert_eq!(
+            exact_version("=0.4.1", Ecosystem::Cargo).as_deref(),
+            Some("0.4.1")
+        );
+        assert_eq!(exact_version("0.4", Ecosystem::Cargo), None);
+        assert_eq!(
+            exact_version("[13.0.3]", Ecosystem::Nuget).as_deref(),
+            Some("13.0.3")
+        );
+        assert_eq!(exact_version("[1.0,2.0)", Ecosystem::Nuget), None);
+    }
+}
@@ -0,0 +1,458 @@
+//! Watch Build Service
+//!
+//! "Build on save" for a workspace: source changes from the shared file system
+//! watcher, filtered to the files that feed the chosen build system, are
+//! debounced into a build. A change that arrives while a build is running
+//! cancels it and starts over. Every step is emitted as a
+//! `watch-build://lifecycle` event so the UI can show the build state.
+
+use crate::commands::build::{dotnet_build_command, parse_build_diagnostics, BuildDiagnostic};
+use crate::languages::diagnostics::DiagnosticsState;
+use crate::services::fs_watcher::{FsWatcherService, WatchSubscription};
+use crate::services::project_detector::{detect_node_info, PackageManager};
+use fluxel_macros::fluxel_command;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+/// Event carrying every `WatchBuildEvent`
+const LIFECYCLE_EVENT: &str = "watch-build://lifecycle";
+
+/// Quiet period after the last change before a build starts; long enough to
+/// cover "save all" and formatters rewriting the file
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// At most this many changed paths are reported with a build
+const MAX_REPORTED_PATHS: usize = 20;
+
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildSystem {
+    Dotnet,
+    Node,
+    Cargo,
+}
+
+impl BuildSystem {
+    /// Files whose changes should trigger a build
+    fn source_globs(self) -> &'static [&'static str] {
+        match self {
+            BuildSystem::Dotnet => &[
+                "**/*.{cs,fs,fsi,vb,razor,cshtml,xaml,axaml,resx}",
+                "**/*.{csproj,fsproj,vbproj,props,targets,sln,slnx}",
+            ],
+            BuildSystem::Node => &[
+                "**/*.{js,jsx,mjs,cjs,ts,tsx,mts,cts,vue,svelte,astro}",
+                "**/*.{css,scss,sass,less,html,json}",
+            ],
+            BuildSystem::Cargo => &["**/*.rs", "**/Cargo.toml", "**/Cargo.lock"],
+        }
+    }
+
+    /// Directories the build writes to; changes there must not retrigger it
+    fn output_dirs(self) -> &'static [&'static str] {
+        match self {
+            BuildSystem::Dotnet => &["bin", "obj"],
+            BuildSystem::Node => &["node_modules", "dist", "build", "out", ".next", ".turbo"],
+            BuildSystem::Cargo => &["target"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchBuildPhase {
+    Started,
+    /// The running build was stopped by newer changes or by `unwatch_build`
+    Cancelled,
+    Finished,
+    /// The build could not be started
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchBuildEvent {
+    pub watch_id: String,
+    pub phase: WatchBuildPhase,
+    /// Changed files that triggered the build
+    pub changed_paths: Vec<String>,
+    pub success: Option<bool>,
+    pub duration_ms: Option<u64>,
+    /// Build output, on `finished`
+    pub output: Option<String>,
+    /// Parsed diagnostics, on `finished` (.NET builds only)
+    pub diagnostics: Vec<BuildDiagnostic>,
+    pub error: Option<String>,
+}
+
+impl WatchBuildEvent {
+    fn new(watch_id: &str, phase: WatchBuildPhase) -> Self {
+        Self {
+            watch_id: watch_id.to_string(),
+            phase,
+            changed_paths: Vec::new(),
+            success: None,
+            duration_ms: None,
+            output: None,
+            diagnostics: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// Filters watcher paths down to the sources of one build system
+struct SourceFilter {
+    root: PathBuf,
+    globs: GlobSet,
+    excluded_dirs: Vec<&'static str>,
+}
+
+impl SourceFilter {
+    fn new(root: &Path, system: BuildSystem) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in system.source_globs() {
+            builder.add(Glob::new(pattern).map_err(|e| e.to_string())?);
+        }
+        let mut excluded_dirs = vec![".git", ".fluxel"];
+        excluded_dirs.extend(system.output_dirs());
+        Ok(Self {
+            root: root.to_path_buf(),
+            globs: builder.build().map_err(|e| e.to_string())?,
+            excluded_dirs,
+        })
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let Ok(relative) = Path::new(path).strip_prefix(&self.root) else {
+            return false;
+        };
+        let in_output = relative.components().any(|component| {
+            component
+                .as_os_str()
+                .to_str()
+                .is_some_and(|name| self.excluded_dirs.contains(&name))
+        });
+        !in_output && self.globs.is_match(relative)
+    }
+}
+
+/// Command that builds `root` with `system`
+fn build_command(root: &Path, system: BuildSystem) -> Result<Command, String> {
+    match system {
+        BuildSystem::Dotnet => dotnet_build_command(&root.to_string_lossy(), None),
+        BuildSystem::Node => {
+            let manager = match detect_node_info(root).package_manager {
+                Some(PackageManager::Pnpm) => "pnpm",
+                Some(PackageManager::Yarn) => "yarn",
+                Some(PackageManager::Npm) => "npm",
+                Some(PackageManager::Bun) | None => "bun",
+            };
+            // Windows package managers are `.cmd` shims
+            let program = if cfg!(windows) && manager != "bun" {
+                format!("{}.cmd", manager)
+            } else {
+                manager.to_string()
+            };
+            let mut cmd = Command::new(program);
+            cmd.args(["run", "build"]).current_dir(root);
+            Ok(cmd)
+        }
+        BuildSystem::Cargo => {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["build", "--color", "never"]).current_dir(root);
+            Ok(cmd)
+        }
+    }
+}
+
+/// Wait for a relevant change, then collect changes until the watcher has been
+/// quiet for `DEBOUNCE`. Returns `None` once the watcher is gone.
+async fn next_changes(
+    subscription: &mut WatchSubscription,
+    filter: &SourceFilter,
+    changed: &mut BTreeSet<String>,
+) -> Option<()> {
+    loop {
+        let event = subscription.recv().await?;
+        changed.extend(event.paths.into_iter().filter(|path| filter.matches(path)));
+        if !changed.is_empty() {
+            break;
+        }
+    }
+    while let Ok(event) = tokio::time::timeout(DEBOUNCE, subscription.recv()).await {
+        let event = event?;
+        changed.extend(event.paths.into_iter().filter(|path| filter.matches(path)));
+    }
+    Some(())
+}
+
+/// Wait for `child` and return its exit status with stdout and stderr combined
+async fn collect_output(child: &mut Child) -> std::io::Result<(bool, String)> {
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let read_stdout = async {
+        let mut buf = Vec::new();
+        if let Some(out) = stdout.as_mut() {
+            out.read_to_end(&mut buf).await?;
+        }
+        Ok::<_, std::io::Error>(buf)
+    };
+    let read_stderr = async {
+        let mut buf = Vec::new();
+        if let Some(err) = stderr.as_mut() {
+            err.read_to_end(&mut buf).await?;
+        }
+        Ok::<_, std::io::Error>(buf)
+    };
+    let (status, out, err) = tokio::try_join!(child.wait(), read_stdout, read_stderr)?;
+    let output = format!(
+        "{}{}",
+        String::from_utf8_lossy(&out),
+        String::from_utf8_lossy(&err)
+    );
+    Ok((status.success(), output))
+}
+
+async fn watch_loop<R: Runtime>(
+    app: AppHandle<R>,
+    watch_id: String,
+    root: PathBuf,
+    system: BuildSystem,
+    mut subscription: WatchSubscription,
+    filter: SourceFilter,
+) {
+    let emit = |event: WatchBuildEvent| {
+        let _ = app.emit(LIFECYCLE_EVENT, event);
+    };
+    let mut changed = BTreeSet::new();
+
+    loop {
+        // Changes that cancelled the previous build start the next one right away
+        if changed.is_empty()
+            && next_changes(&mut subscription, &filter, &mut changed)
+                .await
+                .is_none()
+        {
+            break;
+        }
+
+        let changed_paths: Vec<String> = std::mem::take(&mut changed)
+            .into_iter()
+            .take(MAX_REPORTED_PATHS)
+            .collect();
+
+        let child = build_command(&root, system).and_then(|mut cmd| {
+            cmd.stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            #[cfg(target_os = "windows")]
+            {
+                // CREATE_NO_WINDOW flag to prevent popup windows for console apps
+                cmd.creation_flags(0x08000000);
+            }
+            cmd.spawn()
+                .map_err(|e| format!("Failed to start {:?} build: {}", system, e))
+        });
+        let mut child = match child {
+            Ok(child) => child,
+            Err(error) => {
+                let mut event = WatchBuildEvent::new(&watch_id, WatchBuildPhase::Failed);
+                event.changed_paths = changed_paths;
+                event.error = Some(error);
+                emit(event);
+                continue;
+            }
+        };
+
+        let mut started = WatchBuildEvent::new(&watch_id, WatchBuildPhase::Started);
+        started.changed_paths = changed_paths.clone();
+        emit(started);
+        let start = Instant::now();
+
+        tokio::select! {
+            result = collect_output(&mut child) => {
+                let mut event = WatchBuildEvent::new(&watch_id, WatchBuildPhase::Finished);
+                event.changed_paths = changed_paths;
+                event.duration_ms = Some(start.elapsed().as_millis() as u64);
+                match result {
+                    Ok((success, output)) => {
+                        if system == BuildSystem::Dotnet {
+                            event.diagnostics =
+                                parse_build_diagnostics(&output, &root.to_string_lossy());
+                            app.state::<DiagnosticsState>().set_provider(
+                                &app,
+                                "build",
+                                event
+                                    .diagnostics
+                                    .iter()
+                                    .map(BuildDiagnostic::to_diagnostic)
+                                    .collect(),
+                            );
+                        }
+                        event.success = Some(success);
+                        event.output = Some(output);
+                    }
+                    Err(e) => {
+                        event.success = Some(false);
+                        event.error = Some(format!("Failed to read build output: {}", e));
+                    }
+                }
+                println!(
+                    "[WatchBuild] {} build finished in {}ms",
+                    watch_id,
+                    event.duration_ms.unwrap_or_default()
+                );
+                emit(event);
+            }
+            changes = next_changes(&mut subscription, &filter, &mut changed) => {
+                let _ = child.kill().await;
+                let mut event = WatchBuildEvent::new(&watch_id, WatchBuildPhase::Cancelled);
+                event.changed_paths = changed_paths;
+                emit(event);
+                println!("[WatchBuild] {} build restarted after new changes", watch_id);
+                if changes.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Watch State
+// ============================================================================
+
+/// Running build watches, keyed by watch id
+#[derive(Clone, Default)]
+pub struct WatchBuildState {
+    watches: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
+}
+
+impl WatchBuildState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of running watches
+    pub async fn watch_count(&self) -> usize {
+        self.watches.lock().await.len()
+    }
+
+    /// Stop every watch, cancelling running builds (used on app exit)
+    pub async fn stop_all(&self) {
+        for (_, task) in self.watches.lock().await.drain() {
+            task.abort();
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Rebuild the workspace whenever its sources change.
+///
+/// Returns the watch id carried by every `watch-build://lifecycle` event.
+///
+/// # Arguments
+/// * `workspace_root` - Workspace to watch and build
+/// * `build_system` - Build system to run; also decides which files are watched
+#[fluxel_command]
+pub async fn watch_build<R: Runtime>(
+    app: AppHandle<R>,
+    workspace_root: String,
+    build_system: BuildSystem,
+    state: State<'_, WatchBuildState>,
+    watcher: State<'_, FsWatcherService>,
+) -> Result<String, String> {
+    let root = PathBuf::from(&workspace_root);
+    if !root.is_dir() {
+        return Err(format!(
+            "Workspace root is not a directory or does not exist: {}",
+            workspace_root
+        ));
+    }
+    // Validate up front so a missing toolchain or project is reported here
+    // rather than on the first save
+    build_command(&root, build_system)?;
+
+    let subscription = watcher.subscribe(&root)?;
+    let filter = SourceFilter::new(subscription.root(), build_system)?;
+
+    let watch_id = format!(
+        "watch_build_{}",
+        NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    println!(
+        "[WatchBuild] Watching {} for {:?} builds ({})",
+        workspace_root, build_system, watch_id
+    );
+
+    let task = tokio::spawn(watch_loop(
+        app,
+        watch_id.clone(),
+        root,
+        build_system,
+        subscription,
+        filter,
+    ));
+    state.watches.lock().await.insert(watch_id.clone(), task);
+    Ok(watch_id)
+}
+
+/// Stop a build watch started with `watch_build`, cancelling a running build
+#[fluxel_command]
+pub async fn unwatch_build(
+    watch_id: String,
+    state: State<'_, WatchBuildState>,
+) -> Result<(), String> {
+    let task = state
+        .watches
+        .lock()
+        .await
+        .remove(&watch_id)
+        .ok_or_else(|| format!("Build watch not found: {}", watch_id))?;
+    // Aborting drops the subscription and the running build, which is killed
+    // on drop
+    task.abort();
+    println!("[WatchBuild] Stopped {}", watch_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_sources_per_build_system() {
+        let root = Path::new("/work/app");
+        let dotnet = SourceFilter::new(root, BuildSystem::Dotnet).unwrap();
+        assert!(dotnet.matches("/work/app/src/Program.cs"));
+        assert!(dotnet.matches("/work/app/App.csproj"));
+        assert!(!dotnet.matches("/work/app/obj/Debug/App.AssemblyInfo.cs"));
+        assert!(!dotnet.matches("/work/app/README.md"));
+        assert!(!dotnet.matches("/elsewhere/Program.cs"));
+
+        let node = SourceFilter::new(root, BuildSystem::Node).unwrap();
+        assert!(node.matches("/work/app/src/main.tsx"));
+        assert!(node.matches("/work/app/package.json"));
+        assert!(!node.matches("/work/app/dist/main.js"));
+        assert!(!node.matches("/work/app/node_modules/react/index.js"));
+
+        let cargo = SourceFilter::new(root, BuildSystem::Cargo).unwrap();
+        assert!(cargo.matches("/work/app/crates/core/src/lib.rs"));
+        assert!(!cargo.matches("/work/app/target/debug/build/out.rs"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Workspace Archive
+//!
+//! Packs a workspace folder into a `.zip` or `.tar.gz` for sharing a project or
+//! attaching a bug reproduction. Files are picked with the folder's path filter,
+//! so dependencies, build output and `.gitignore`d files stay out, along with
+//! any extra exclude patterns; `.git` is only packed on request.
+//!
+//! The archive is streamed to a `.partial` file next to the destination and
+//! renamed into place once complete, emitting `workspace://archive-progress`
+//! events on the way. The file list and its uncompressed size can be estimated
+//! beforehand, for the export dialog.
+
+use crate::services::path_filter::{PathFilter, PathFilterState};
+use chrono::{Datelike, Local, Timelike};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use fluxel_macros::fluxel_command;
+use ignore::gitignore::GitignoreBuilder;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime, State};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const PROGRESS_EVENT: &str = "workspace://archive-progress";
+
+/// Bytes written between progress events
+const PROGRESS_INTERVAL: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// The format named by a destination's extension: `.zip`, `.tar.gz` or `.tgz`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveOptions {
+    /// Pack the `.git` directory too, keeping the history
+    pub include_git: bool,
+    /// `.gitignore`-style patterns to leave out on top of the folder's path filter
+    pub extra_excludes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEstimate {
+    pub files: usize,
+    /// Total size before compression
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveProgress {
+    pub files_written: usize,
+    pub total_files: usize,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveResult {
+    pub path: String,
+    pub files: usize,
+    /// Total size before compression
+    pub bytes: u64,
+    pub archive_bytes: u64,
+}
+
+/// A file to pack
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub path: PathBuf,
+    /// Path inside the archive, `/`-separated
+    pub name: String,
+    pub size: u64,
+}
+
+/// The files of the workspace `filter` was built for, sorted by name. `skip`
+/// leaves out a file inside the workspace, such as the archive being written.
+pub fn collect_entries(
+    filter: &Arc<PathFilter>,
+    options: &ArchiveOptions,
+    skip: &[PathBuf],
+) -> Result<Vec<ArchiveEntry>, String> {
+    let root = filter.root().to_path_buf();
+    let mut builder = GitignoreBuilder::new(&root);
+    for pattern in &options.extra_excludes {
+        let pattern = pattern.trim();
+        if !pattern.is_empty() {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+        }
+    }
+    let excludes = builder.build().map_err(|e| e.to_string())?;
+
+    let walk_excludes = excludes.clone();
+    let walked = filter
+        .walk_builder_with(&root, move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            entry.depth() == 0
+                || !walk_excludes
+                    .matched_path_or_any_parents(entry.path(), is_dir)
+                    .is_ignore()
+        })
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path());
+    // The path filter always skips `.git`
+    let git: Vec<PathBuf> = if options.include_git {
+        WalkDir::new(root.join(".git"))
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut entries: Vec<ArchiveEntry> = walked
+        .chain(git)
+        .filter(|path| !skip.contains(path))
+        .filter_map(|path| {
+            let name = path
+                .strip_prefix(&root)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let size = fs::metadata(&path).ok()?.len();
+            Some(ArchiveEntry { path, name, size })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// Stream `entries` into `out` as `format`, reporting progress every few megabytes
+pub fn write_archive<W: Write + io::Seek>(
+    entries: &[ArchiveEntry],
+    format: ArchiveFormat,
+    out: W,
+    mut on_progress: impl FnMut(ArchiveProgress),
+) -> Result<(), String> {
+    let mut progress = ArchiveProgress {
+        files_written: 0,
+        total_files: entries.len(),
+        bytes_written: 0,
+        total_bytes: entries.iter().map(|entry| entry.size).sum(),
+    };
+    let mut last_reported = 0;
+    let mut advance = |entry: &ArchiveEntry| {
+        progress.files_written += 1;
+        progress.bytes_written += entry.size;
+        let done = progress.files_written == progress.total_files;
+        if done || progress.bytes_written - last_reported >= PROGRESS_INTERVAL {
+            last_reported = progress.bytes_written;
+            on_progress(progress.clone());
+        }
+    };
+    let read_error =
+        |entry: &ArchiveEntry, e: io::Error| format!("Failed to add {}: {}", entry.name, e);
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(out);
+            for entry in entries {
+                let mut file = File::open(&entry.path).map_err(|e| read_error(entry, e))?;
+                let metadata = file.metadata().map_err(|e| read_error(entry, e))?;
+                zip.start_file(entry.name.as_str(), zip_options(&metadata))
+                    .map_err(|e| e.to_string())?;
+                io::copy(&mut file, &mut zip).map_err(|e| read_error(entry, e))?;
+                advance(entry);
+            }
+            zip.finish().map_err(|e| e.to_string())?;
+        }
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+            for entry in entries {
+                tar.append_path_with_name(&entry.path, &entry.name)
+                    .map_err(|e| read_error(entry, e))?;
+                advance(entry);
+            }
+            tar.into_inner()
+                .and_then(|gz| gz.finish())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Deflated, keeping the modification time and, on Unix, the permissions
+fn zip_options(metadata: &fs::Metadata) -> SimpleFileOptions {
+    let mut options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(metadata.len() >= u32::MAX as u64);
+    if let Ok(modified) = metadata.modified() {
+        let modified = chrono::DateTime::<Local>::from(modified);
+        let time = zip::DateTime::from_date_and_time(
+            modified.year().clamp(1980, 2107) as u16,
+            modified.month() as u8,
+            modified.day() as u8,
+            modified.hour() as u8,
+            modified.minute() as u8,
+            modified.second() as u8,
+        );
+        if let Ok(time) = time {
+            options = options.last_modified_time(time);
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        options = options.unix_permissions(metadata.permissions().mode());
+    }
+    options
+}
+
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    dest.with_file_name(name)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Count the files an export would pack and their size before compression
+///
+/// # Arguments
+/// * `root` - The workspace folder
+/// * `options` - Whether to include `.git`, and extra exclude patterns
+#[fluxel_command]
+pub async fn estimate_workspace_archive(
+    root: String,
+    options: Option<ArchiveOptions>,
+    filters: State<'_, PathFilterState>,
+) -> Result<ArchiveEstimate, String> {
+    let filter = filters.filter_for(Path::new(&root));
+    tauri::async_runtime::spawn_blocking(move || {
+        let entries = collect_entries(&filter, &options.unwrap_or_default(), &[])?;
+        Ok(ArchiveEstimate {
+            files: entries.len(),
+            bytes: entries.iter().map(|entry| entry.size).sum(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Pack a workspace folder into a `.zip`, `.tar.gz` or `.tgz`, emitting
+/// `workspace://archive-progress` events while writing
+///
+/// # Arguments
+/// * `root` - The workspace folder
+/// * `dest` - Archive to write; its extension picks the format
+/// * `options` - Whether to include `.git`, and extra exclude patterns
+#[fluxel_command]
+pub async fn export_workspace_archive<R: Runtime>(
+    app: AppHandle<R>,
+    root: String,
+    dest: String,
+    options: Option<ArchiveOptions>,
+    filters: State<'_, PathFilterState>,
+) -> Result<ArchiveResult, String> {
+    let root_path = PathBuf::from(&root);
+    if !root_path.is_dir() {
+        return Err(format!("Workspace folder not found: {}", root));
+    }
+    let dest = PathBuf::from(dest);
+    let format = ArchiveFormat::from_path(&dest)
+        .ok_or("Unsupported archive format; use .zip, .tar.gz or .tgz")?;
+    let filter = filters.filter_for(&root_path);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let partial = partial_path(&dest);
+        let entries = collect_entries(
+            &filter,
+            &options.unwrap_or_default(),
+            &[dest.clone(), partial.clone()],
+        )?;
+        let bytes = entries.iter().map(|entry| entry.size).sum();
+        println!(
+            "[WorkspaceArchive] Packing {} files ({} bytes) into {:?}",
+            entries.len(),
+            bytes,
+            dest
+        );
+
+        let file =
+            File::create(&partial).map_err(|e| format!("Failed to create {:?}: {}", partial, e))?;
+        let written = write_archive(&entries, format, BufWriter::new(file), |progress| {
+            let _ = app.emit(PROGRESS_EVENT, progress);
+        })
+        .and_then(|_| fs::rename(&partial, &dest).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+
+        Ok(ArchiveResult {
+            path: dest.to_string_lossy().to_string(),
+            files: entries.len(),
+            bytes,
+            archive_bytes: fs::metadata(&dest).map(|m| m.len()).unwrap_or(0),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::path_filter::PathFilterConfig;
+    use flate2::read::GzDecoder;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_collects_and_writes_archives() {
+        let root = std::env::temp_dir().join(format!(
+            "fluxel-archive-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        for file in [
+            "src/app.ts",
+            "README.md",
+            "notes.tmp",
+            "node_modules/dep/index.js",
+            "logs/today.log",
+            ".git/HEAD",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, file).unwrap();
+        }
+        fs::write(root.join(".gitignore"), "logs/\n").unwrap();
+
+        let filter = Arc::new(PathFilter::new(&root, &PathFilterConfig::default()));
+        let options = ArchiveOptions {
+            include_git: true,
+            extra_excludes: vec!["*.tmp".to_string()],
+        };
+        let entries = collect_entries(&filter, &options, &[]).unwrap();
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [".git/HEAD", ".gitignore", "README.md", "src/app.ts"]
+        );
+
+        let mut zip = Cursor::new(Vec::new());
+        let mut reports = Vec::new();
+        write_archive(&entries, ArchiveFormat::Zip, &mut zip, |p| reports.push(p)).unwrap();
+        let archive = zip::ZipArchive::new(zip).unwrap();
+        let mut zipped: Vec<&str> = archive.file_names().collect();
+        zipped.sort();
+        assert_eq!(zipped, names);
+        // Small archives report once, when done
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].files_written, 4);
+
+        let mut tar_gz = Cursor::new(Vec::new());
+        write_archive(&entries, ArchiveFormat::TarGz, &mut tar_gz, |_| {}).unwrap();
+        tar_gz.set_position(0);
+        let mut tar = tar::Archive::new(GzDecoder::new(tar_gz));
+        let tarred: Vec<String> = tar
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(tarred, names);
+
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("repro.TGZ")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("repro.rar")), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+//! Workspace Store
+//!
+//! Per-workspace JSON files under `~/.fluxel/<name>/<workspace-hash>.json`, used
+//! by the services that keep history or settings for each workspace. The hash is
+//! of the canonical workspace path, so one folder always maps to one file however
+//! it was opened.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// Contents of a per-workspace file
+pub trait WorkspaceFile: Serialize + DeserializeOwned {
+    /// Version written to new files; files with another version are rejected
+    const VERSION: u32;
+    /// What the file holds, for error messages (`"search history"`)
+    const LABEL: &'static str;
+
+    /// Contents for a workspace that has no file yet
+    fn empty(workspace: String) -> Self;
+}
+
+/// Just enough of a file to check its version before parsing the rest
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+/// Lowercase hex SHA-256 of `bytes`, as used for cache and store file names
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Path of the file for `workspace_root` in `dir`, and the normalized workspace
+/// path it is keyed by
+pub fn workspace_file(dir: &Path, workspace_root: &Path) -> (PathBuf, String) {
+    let canonical = workspace_root
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_root.to_path_buf());
+    let workspace = canonical.to_string_lossy().replace('\\', "/");
+    let name = sha256_hex(workspace.as_bytes());
+    (dir.join(format!("{}.json", name)), workspace)
+}
+
+/// Files of one kind for all workspaces on this machine
+pub struct WorkspaceStore<T> {
+    store_dir: PathBuf,
+    file: PhantomData<T>,
+}
+
+impl<T: WorkspaceFile> WorkspaceStore<T> {
+    pub fn new(store_dir: PathBuf) -> Self {
+        Self {
+            store_dir,
+            file: PhantomData,
+        }
+    }
+
+    /// Store in `~/.fluxel/<name>`
+    pub fn for_user(name: &str) -> Result<Self, String> {
+        let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+        Ok(Self::new(home.join(".fluxel").join(name)))
+    }
+
+    pub fn load(&self, workspace_root: &Path) -> Result<T, String> {
+        let (path, workspace) = workspace_file(&self.store_dir, workspace_root);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::empty(workspace)),
+            Err(e) => return Err(format!("Failed to read {}: {}", T::LABEL, e)),
+        };
+        let header: Header = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", T::LABEL, e))?;
+        if header.version != T::VERSION {
+            return Err(format!(
+                "Unsupported {} version {}",
+                T::LABEL,
+                header.version
+            ));
+        }
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", T::LABEL, e))
+    }
+
+    pub fn save(&self, workspace_root: &Path, file: &T) -> Result<(), String> {
+        let (path, _) = workspace_file(&self.store_dir, workspace_root);
+        std::fs::create_dir_all(&self.store_dir)
+            .map_err(|e| format!("Failed to create {} directory: {}", T::LABEL, e))?;
+        let json = serde_json::to_string(file).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    /// Load, change, and save the file of a workspace
+    pub fn update<R>(
+        &self,
+        workspace_root: &Path,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Result<R, String> {
+        let mut file = self.load(workspace_root)?;
+        let result = f(&mut file);
+        self.save(workspace_root, &file)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize, Deserialize)]
+    struct Counter {
+        version: u32,
+        workspace: String,
+        count: u32,
+    }
+
+    impl WorkspaceFile for Counter {
+        const VERSION: u32 = 2;
+        const LABEL: &'static str = "counter";
+
+        fn empty(workspace: String) -> Self {
+            Self {
+                version: Self::VERSION,
+                workspace,
+                count: 0,
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_one_file_per_workspace_and_checks_its_version() {
+        let dir = std::env::temp_dir().join(format!(
+            "fluxel-workspace-store-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let workspace = dir.join("workspace");
+        std::fs::create_dir_all(workspace.join("sub")).unwrap();
+        let store = WorkspaceStore::<Counter>::new(dir.join("store"));
+
+        store.update(&workspace, |file| file.count += 1).unwrap();
+        // The same folder through a different path is the same file
+        store
+            .update(&workspace.join("sub").join(".."), |file| file.count += 1)
+            .unwrap();
+        let loaded = store.load(&workspace).unwrap();
+        assert_eq!(loaded.count, 2);
+        assert!(loaded.workspace.ends_with("/workspace"));
+        assert_eq!(store.load(&dir.join("other")).unwrap().count, 0);
+
+        let (path, _) = workspace_file(&dir.join("store"), &workspace);
+        std::fs::write(&path, r#"{"version":1,"workspace":"","count":5}"#).unwrap();
+        let error = store.load(&workspace).err().unwrap();
+        assert!(error.contains("Unsupported counter version 1"), "{}", error);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
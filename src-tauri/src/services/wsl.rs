@@ -0,0 +1,282 @@
+//! WSL Integration
+//!
+//! Windows Subsystem for Linux support: listing installed distros, running
+//! commands inside one, and translating paths between Windows form
+//! (`C:\src\app`, `\\wsl.localhost\Ubuntu\home\me\app`) and WSL form
+//! (`/mnt/c/src/app`, `/home/me/app`). Workspaces opened through a
+//! `\\wsl$` or `\\wsl.localhost` share are WSL-rooted: their terminals and
+//! builds run inside the distro, where the toolchains live.
+
+use fluxel_macros::fluxel_command;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslDistro {
+    pub name: String,
+    pub is_default: bool,
+    /// `Running`, `Stopped`, ... as reported by `wsl.exe`
+    pub state: String,
+    /// WSL version (1 or 2)
+    pub version: u8,
+}
+
+/// Workspace that lives inside a distro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WslWorkspace {
+    pub distro: String,
+    /// Workspace root as seen from inside the distro
+    pub linux_root: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WslPathTarget {
+    Wsl,
+    Windows,
+}
+
+/// `wsl.exe` prints UTF-16 unless `WSL_UTF8` is set, so accept both
+fn decode_output(bytes: &[u8]) -> String {
+    let utf16 = bytes.starts_with(&[0xFF, 0xFE]) || (bytes.len() >= 2 && bytes[1] == 0);
+    if utf16 {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+            .trim_start_matches('\u{feff}')
+            .to_string()
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// Parse the table printed by `wsl.exe --list --verbose`
+fn parse_distro_list(output: &str) -> Vec<WslDistro> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("NAME"))
+        .skip(1)
+        .filter_map(|line| {
+            let line = line.trim();
+            let (is_default, line) = match line.strip_prefix('*') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let mut columns = line.split_whitespace();
+            let name = columns.next()?.to_string();
+            let state = columns.next()?.to_string();
+            let version = columns.next()?.parse().ok()?;
+            Some(WslDistro {
+                name,
+                is_default,
+                state,
+                version,
+            })
+        })
+        .collect()
+}
+
+/// Split a `\\wsl$\<distro>\...` or `\\wsl.localhost\<distro>\...` path into
+/// the distro and the path inside it
+pub fn wsl_workspace(path: &str) -> Option<WslWorkspace> {
+    let normalized = path.replace('\\', "/");
+    let lower = normalized.to_lowercase();
+    let rest = [
+        "//wsl$/",
+        "//wsl.localhost/",
+        "//?/unc/wsl$/",
+        "//?/unc/wsl.localhost/",
+    ]
+    .iter()
+    .find(|prefix| lower.starts_with(*prefix))
+    .map(|prefix| &normalized[prefix.len()..])?;
+
+    let (distro, inner) = rest.split_once('/').unwrap_or((rest, ""));
+    if distro.is_empty() {
+        return None;
+    }
+    let inner = inner.trim_end_matches('/');
+    Some(WslWorkspace {
+        distro: distro.to_string(),
+        linux_root: format!("/{}", inner),
+    })
+}
+
+/// Windows path in WSL form, for drive paths and distro shares
+pub fn windows_to_wsl(path: &str) -> Option<String> {
+    if let Some(workspace) = wsl_workspace(path) {
+        return Some(workspace.linux_root);
+    }
+    let normalized = path.replace('\\', "/");
+    let mut chars = normalized.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+    let rest = chars.as_str().trim_start_matches('/').trim_end_matches('/');
+    let mut wsl = format!("/mnt/{}", drive.to_ascii_lowercase());
+    if !rest.is_empty() {
+        wsl.push('/');
+        wsl.push_str(rest);
+    }
+    Some(wsl)
+}
+
+/// WSL path in Windows form: `/mnt/<drive>/...` maps back to the drive,
+/// anything else to the distro's share
+pub fn wsl_to_windows(path: &str, distro: &str) -> String {
+    if let Some(rest) = path.strip_prefix("/mnt/") {
+        let (drive, inner) = rest.split_once('/').unwrap_or((rest, ""));
+        if drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic()) {
+            return format!(
+                "{}:\\{}",
+                drive.to_ascii_uppercase(),
+                inner.replace('/', "\\")
+            );
+        }
+    }
+    format!(
+        "\\\\wsl.localhost\\{}{}",
+        distro,
+        path.trim_end_matches('/').replace('/', "\\")
+    )
+}
+
+/// Quote `value` for a POSIX shell
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn wsl_args(distro: &str, cwd: Option<&str>, command_line: &str) -> Vec<String> {
+    let mut args = vec!["-d".to_string(), distro.to_string()];
+    if let Some(cwd) = cwd {
+        args.push("--cd".to_string());
+        args.push(cwd.to_string());
+    }
+    // A login shell picks up the PATH set in the distro's profile, where
+    // toolchains such as dotnet and nvm usually register themselves
+    args.extend(["--", "sh", "-lc", command_line].map(str::to_string));
+    args
+}
+
+/// Command that runs `command_line` through the shell of `distro`, starting
+/// in `cwd` (a WSL path)
+pub fn wsl_command(distro: &str, cwd: Option<&str>, command_line: &str) -> Result<Command, String> {
+    if !cfg!(windows) {
+        return Err("WSL is only available on Windows".to_string());
+    }
+    let mut cmd = Command::new("wsl.exe");
+    cmd.args(wsl_args(distro, cwd, command_line));
+    Ok(cmd)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// List installed WSL distros; empty when WSL is unavailable
+#[fluxel_command]
+pub async fn list_wsl_distros() -> Result<Vec<WslDistro>, String> {
+    if !cfg!(windows) {
+        return Ok(Vec::new());
+    }
+
+    let mut cmd = tokio::process::Command::new("wsl.exe");
+    cmd.args(["--list", "--verbose"]).env("WSL_UTF8", "1");
+    #[cfg(target_os = "windows")]
+    {
+        // CREATE_NO_WINDOW flag to prevent a console window flashing up
+        cmd.creation_flags(0x08000000);
+    }
+
+    let output = match cmd.output().await {
+        Ok(output) => output,
+        Err(e) => {
+            println!("[WSL] wsl.exe not available: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+    if !output.status.success() {
+        // No distros installed, or WSL not enabled
+        return Ok(Vec::new());
+    }
+    Ok(parse_distro_list(&decode_output(&output.stdout)))
+}
+
+/// Translate a path between Windows and WSL form
+///
+/// # Arguments
+/// * `path` - Path to translate
+/// * `target` - Form to translate to
+/// * `distro` - Distro the path belongs to; required when translating to Windows
+#[fluxel_command]
+pub fn translate_wsl_path(
+    path: String,
+    target: WslPathTarget,
+    distro: Option<String>,
+) -> Result<String, String> {
+    match target {
+        WslPathTarget::Wsl => windows_to_wsl(&path)
+            .ok_or_else(|| format!("Not a translatable Windows path: {}", path)),
+        WslPathTarget::Windows => {
+            let distro = distro.ok_or("A distro is required to translate WSL paths")?;
+            if !path.starts_with('/') {
+                return Err(format!("Not an absolute WSL path: {}", path));
+            }
+            Ok(wsl_to_windows(&path, &distro))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_paths_and_lists_distros() {
+        assert_eq!(
+            wsl_workspace(r"\\wsl.localhost\Ubuntu-22.04\home\me\app\"),
+            Some(WslWorkspace {
+                distro: "Ubuntu-22.04".to_string(),
+                linux_root: "/home/me/app".to_string(),
+            })
+        );
+        assert_eq!(
+            wsl_workspace("//wsl$/Debian").map(|w| w.linux_root),
+            Some("/".to_string())
+        );
+        assert_eq!(wsl_workspace(r"C:\src\app"), None);
+
+        assert_eq!(
+            windows_to_wsl(r"C:\src\my app"),
+            Some("/mnt/c/src/my app".to_string())
+        );
+        assert_eq!(windows_to_wsl("relative/path"), None);
+        assert_eq!(
+            wsl_to_windows("/mnt/d/work/x.cs", "Ubuntu"),
+            r"D:\work\x.cs"
+        );
+        assert_eq!(
+            wsl_to_windows("/home/me/app/Program.cs", "Ubuntu"),
+            r"\\wsl.localhost\Ubuntu\home\me\app\Program.cs"
+        );
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+
+        let listing = "  NAME            STATE           VERSION\r\n\
+            * Ubuntu-22.04    Running         2\r\n  \
+            docker-desktop  Stopped         2\r\n";
+        let utf16: Vec<u8> = listing
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        let distros = parse_distro_list(&decode_output(&utf16));
+        assert_eq!(distros.len(), 2);
+        assert!(distros[0].is_default);
+        assert_eq!(distros[0].name, "Ubuntu-22.04");
+        assert_eq!(distros[1].state, "Stopped");
+        assert_eq!(distros[1].version, 2);
+    }
+}
@@ -3,7 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { TitleBar } from "./components/ui/titlebar";
 import { useSettingsStore, usePreviewStore } from "@/stores";
-import { openWorkspace, closeWorkspace, FrontendProfiler } from "@/lib/services";
+import { openWorkspace, closeWorkspace, FrontendProfiler, ProfilerService } from "@/lib/services";
 import { useProfiler } from "@/hooks/useProfiler";
 import { useGlobalShortcuts } from "@/hooks/useGlobalShortcuts";
 import { preloadIconPack } from "@/lib/icons";
@@ -209,6 +209,8 @@ function App() {
         span.end({ error: 'true' });
         console.error("Failed to check launch path:", error);
       }
+      // The first view (and the launch workspace, if any) is up: end the startup profile
+      void ProfilerService.finishStartup();
     })();
   }, []); // Run only on mount
 
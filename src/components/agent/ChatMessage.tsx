@@ -1,5 +1,5 @@
 import { useState } from 'react';
-import { Brain, ChevronDown, Sparkles, User, Wrench } from 'lucide-react';
+import { Brain, ChevronDown, Image as ImageIcon, Sparkles, User, Wrench } from 'lucide-react';
 import { Streamdown } from 'streamdown';
 import { cn } from '@/lib/utils';
 import { useProfiler } from '@/hooks/useProfiler';
@@ -46,6 +46,14 @@ export function ChatMessage({ message }: Props) {
                             <span className="text-[11px] text-muted-foreground/60">
                                 {timestamp.toLocaleTimeString([], { hour: 'numeric', minute: '2-digit' })}
                             </span>
+                            {message.servedBy && (
+                                <span
+                                    className="rounded-full border border-border/70 bg-muted/40 px-2 py-0.5 text-[10px] font-medium text-muted-foreground"
+                                    title="Provider that served this response"
+                                >
+                                    {message.servedBy.provider} · {message.servedBy.model}
+                                </span>
+                            )}
                             {message.isStreaming && (
                                 <span className="inline-flex items-center gap-1 rounded-full border border-primary/20 bg-primary/10 px-2 py-0.5 text-[10px] font-medium text-primary">
                                     <span className="h-1.5 w-1.5 animate-pulse rounded-full bg-current" />
@@ -85,6 +93,21 @@ export function ChatMessage({ message }: Props) {
                                 </div>
                             )}
 
+                            {message.images && message.images.length > 0 && (
+                                <div className="flex flex-wrap gap-1.5 border-b border-border/70 px-4 py-2">
+                                    {message.images.map(path => (
+                                        <span
+                                            key={path}
+                                            className="inline-flex items-center gap-1 rounded-full border border-border/70 bg-background/70 px-2 py-0.5 text-[11px] text-muted-foreground"
+                                            title={path}
+                                        >
+                                            <ImageIcon className="h-3 w-3" />
+                                            {path.split(/[\\/]/).pop()}
+                                        </span>
+                                    ))}
+                                </div>
+                            )}
+
                             <div className="px-4 py-3">
                                 <div className="prose prose-sm max-w-none text-foreground">
                                     <Streamdown
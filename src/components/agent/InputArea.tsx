@@ -1,11 +1,25 @@
 import { useCallback, useRef, useState, type KeyboardEvent } from 'react';
-import { Paperclip, Send, StopCircle, X } from 'lucide-react';
+import { ImagePlus, Image as ImageIcon, Paperclip, Send, StopCircle, X } from 'lucide-react';
+import { open } from '@tauri-apps/plugin-dialog';
 import { useAgentStore, useFileSystemStore } from '@/stores';
-import { getProvider, type ProviderMessage, type ProviderType } from '@/lib/agent/providers';
+import {
+    buildFailoverChain,
+    getProvider,
+    streamWithFailover,
+    type ProviderMessage,
+    type ProviderType,
+} from '@/lib/agent/providers';
 import { SYSTEM_PROMPT } from '@/lib/agent/systemPrompt';
 import { tools } from '@/lib/agent/tools';
 import { cn } from '@/lib/utils';
 import { useProfiler } from '@/hooks/useProfiler';
+import {
+    AI_IMAGE_EXTENSIONS,
+    prepareAiImage,
+    recordAiAuditEntry,
+    recordAiMetrics,
+    type AiImage,
+} from '@/lib/services';
 import { ModelSelector } from './ModelSelector';
 
 function convertStoreMessages(
@@ -31,6 +45,7 @@ function convertStoreMessages(
 export function InputArea({ panelWidth }: { panelWidth?: number }) {
     const { startSpan, trackInteraction, ProfilerWrapper } = useProfiler('AgentInputArea');
     const [input, setInput] = useState('');
+    const [attachedImages, setAttachedImages] = useState<AiImage[]>([]);
     const textareaRef = useRef<HTMLTextAreaElement>(null);
     const abortControllerRef = useRef<AbortController | null>(null);
 
@@ -41,12 +56,14 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
     const activeConversationId = useAgentStore(state => state.activeConversationId);
     const provider = useAgentStore(state => state.provider) as ProviderType;
     const providerConfigs = useAgentStore(state => state.providerConfigs);
+    const failoverChain = useAgentStore(state => state.failoverChain);
     const workspaceRoot = useFileSystemStore(state => state.rootPath);
 
     const addMessage = useAgentStore(state => state.addMessage);
     const setGenerating = useAgentStore(state => state.setGenerating);
     const appendStreamingContent = useAgentStore(state => state.appendStreamingContent);
     const appendStreamingThinking = useAgentStore(state => state.appendStreamingThinking);
+    const setStreamingStats = useAgentStore(state => state.setStreamingStats);
     const clearStreaming = useAgentStore(state => state.clearStreaming);
     const removeFile = useAgentStore(state => state.removeFile);
     const createConversation = useAgentStore(state => state.createConversation);
@@ -55,7 +72,9 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
         if (!input.trim() || isGenerating) return;
 
         const userMessage = input.trim();
+        const images = attachedImages;
         setInput('');
+        setAttachedImages([]);
 
         trackInteraction('message_sent', {
             length: userMessage.length.toString(),
@@ -71,6 +90,7 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
         addMessage({
             role: 'user',
             content: userMessage,
+            images: images.length > 0 ? images.map(image => image.path) : undefined,
         });
 
         let finalUserContent = userMessage;
@@ -90,12 +110,11 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
         const turnSpan = startSpan('process_turn', 'frontend_network');
 
         try {
-            const providerImpl = getProvider(provider);
-
-            const config = {
-                apiKey: providerConfigs?.[provider]?.apiKey,
-                apiBase: providerConfigs?.[provider]?.apiBase,
-            };
+            const chain = buildFailoverChain({ provider, model }, failoverChain ?? []);
+            const configFor = (type: ProviderType) => ({
+                apiKey: providerConfigs?.[type]?.apiKey,
+                apiBase: providerConfigs?.[type]?.apiBase,
+            });
 
             const currentConversationId = activeConversationId || useAgentStore.getState().activeConversationId!;
             const conversation = useAgentStore.getState().conversations.find(c => c.id === currentConversationId);
@@ -114,7 +133,13 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
             const historyMessages = convertStoreMessages(history.slice(0, -1));
             const currentMessages: ProviderMessage[] = [
                 ...historyMessages,
-                { role: 'user', content: finalUserContent },
+                {
+                    role: 'user',
+                    content: finalUserContent,
+                    images: images.length > 0
+                        ? images.map(image => ({ mimeType: image.mimeType, data: image.data }))
+                        : undefined,
+                },
             ];
 
             const workspaceContext = workspaceRoot
@@ -131,31 +156,87 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
             let keepGoing = true;
             let loopCount = 0;
             const MAX_LOOPS = 5;
+            // Messages already written to the AI audit log (a no-op unless enabled)
+            let auditedCount = historyMessages.length;
 
             while (keepGoing && loopCount < MAX_LOOPS) {
                 loopCount++;
+                if (workspaceRoot) {
+                    recordAiAuditEntry(workspaceRoot, {
+                        provider,
+                        model,
+                        feature: 'chat',
+                        files: loopCount === 1 ? attachedContext.map(ctx => ctx.path) : [],
+                        snippets: currentMessages.slice(auditedCount).map(m => m.content),
+                    }).catch(e => console.warn('[Agent] Failed to record AI audit entry:', e));
+                    auditedCount = currentMessages.length;
+                }
                 const streamSpan = startSpan('llm_stream_response_loop', 'frontend_network');
 
-                const result = await providerImpl.stream(
+                // Output is estimated at ~4 characters per token for live throughput
+                const streamStart = performance.now();
+                let firstTokenAt: number | null = null;
+                let outputChars = 0;
+                const trackOutput = (text: string) => {
+                    const now = performance.now();
+                    firstTokenAt ??= now;
+                    outputChars += text.length;
+                    const outputTokens = Math.ceil(outputChars / 4);
+                    const generatingMs = now - firstTokenAt;
+                    setStreamingStats({
+                        timeToFirstTokenMs: firstTokenAt - streamStart,
+                        tokensPerSecond: generatingMs > 0 ? outputTokens * 1000 / generatingMs : undefined,
+                        outputTokens,
+                    });
+                };
+                const reportMetrics = (served: { provider: string; model: string }, success: boolean) => {
+                    recordAiMetrics({
+                        ...served,
+                        feature: 'chat',
+                        timeToFirstTokenMs: firstTokenAt === null ? undefined : firstTokenAt - streamStart,
+                        durationMs: performance.now() - streamStart,
+                        outputTokens: Math.ceil(outputChars / 4),
+                        success,
+                    }).catch(e => console.warn('[Agent] Failed to record AI metrics:', e));
+                };
+
+                const result = await streamWithFailover(
+                    chain,
+                    getProvider,
                     currentMessages,
                     fullSystemPrompt,
                     toolDefs,
                     {
-                        model,
                         temperature,
                         abortSignal: controller.signal,
                     },
                     {
-                        onContent: appendStreamingContent,
-                        onThinking: appendStreamingThinking,
+                        onContent: text => {
+                            trackOutput(text);
+                            appendStreamingContent(text);
+                        },
+                        onThinking: text => {
+                            trackOutput(text);
+                            appendStreamingThinking(text);
+                        },
+                        onFailover: (from, to) => trackInteraction('provider_failover', {
+                            from: `${from.provider}/${from.model}`,
+                            to: `${to.provider}/${to.model}`,
+                        }),
                     },
-                    config
-                );
+                    configFor
+                ).catch(error => {
+                    reportMetrics({ provider, model }, false);
+                    throw error;
+                });
+                reportMetrics(result.servedBy, true);
+                const providerImpl = result.provider;
 
                 await streamSpan.end({
                     contentLength: result.content.length.toString(),
                     toolCalls: result.toolCalls.length.toString(),
                     hasThinking: (!!result.thinking).toString(),
+                    servedBy: `${result.servedBy.provider}/${result.servedBy.model}`,
                 });
 
                 const hasContent = result.content.length > 0;
@@ -166,6 +247,7 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
                         role: 'assistant',
                         content: result.content,
                         thinking: result.thinking,
+                        servedBy: result.servedBy,
                         toolCalls: result.toolCalls.map(tc => ({
                             id: tc.id,
                             name: tc.name,
@@ -246,6 +328,7 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
         }
     }, [
         input,
+        attachedImages,
         isGenerating,
         attachedContext,
         model,
@@ -253,17 +336,37 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
         activeConversationId,
         provider,
         providerConfigs,
+        failoverChain,
         workspaceRoot,
         addMessage,
         setGenerating,
         appendStreamingContent,
         appendStreamingThinking,
+        setStreamingStats,
         clearStreaming,
         createConversation,
         startSpan,
         trackInteraction,
     ]);
 
+    const handleAttachImages = useCallback(async () => {
+        if (!workspaceRoot) return;
+        const selected = await open({
+            multiple: true,
+            defaultPath: workspaceRoot,
+            filters: [{ name: 'Images', extensions: AI_IMAGE_EXTENSIONS }],
+        });
+        const paths = Array.isArray(selected) ? selected : selected ? [selected] : [];
+        for (const path of paths) {
+            try {
+                const image = await prepareAiImage(workspaceRoot, path, provider);
+                setAttachedImages(prev => [...prev.filter(i => i.path !== image.path), image]);
+            } catch (e) {
+                console.error('[Agent] Failed to attach image:', e);
+            }
+        }
+    }, [workspaceRoot, provider]);
+
     const handleKeyDown = useCallback((e: KeyboardEvent<HTMLTextAreaElement>) => {
         if (e.key === 'Enter' && !e.shiftKey) {
             e.preventDefault();
@@ -287,8 +390,26 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
     return (
         <ProfilerWrapper>
             <div className="border-t border-border/80 bg-gradient-to-t from-background via-background/95 to-background/80 p-4 backdrop-blur-sm">
-                {attachedContext.length > 0 && (
+                {(attachedContext.length > 0 || attachedImages.length > 0) && (
                     <div className="mb-3 flex flex-wrap gap-2">
+                        {attachedImages.map(image => (
+                            <div
+                                key={image.path}
+                                className="flex items-center gap-1.5 rounded-full border border-border/70 bg-background/80 px-2.5 py-1 text-xs shadow-sm"
+                                title={`${image.width}×${image.height}${image.converted ? ` (from ${image.originalWidth}×${image.originalHeight})` : ''}`}
+                            >
+                                <ImageIcon className="h-3 w-3 text-muted-foreground" />
+                                <span className="max-w-[150px] truncate">
+                                    {image.path.split(/[\\/]/).pop()}
+                                </span>
+                                <button
+                                    onClick={() => setAttachedImages(prev => prev.filter(i => i.path !== image.path))}
+                                    className="text-muted-foreground transition-colors hover:text-foreground"
+                                >
+                                    <X className="h-3 w-3" />
+                                </button>
+                            </div>
+                        ))}
                         {attachedContext.map(ctx => (
                             <div
                                 key={ctx.path}
@@ -338,6 +459,14 @@ export function InputArea({ panelWidth }: { panelWidth?: number }) {
                     <div className="flex items-center justify-between gap-2 border-t border-border/60 px-3 py-2">
                         <div className="flex min-w-0 items-center gap-2">
                             <ModelSelector />
+                            <button
+                                onClick={handleAttachImages}
+                                disabled={isGenerating || !workspaceRoot}
+                                className="rounded-full p-1.5 text-muted-foreground transition-colors hover:bg-muted hover:text-foreground disabled:opacity-40"
+                                title="Attach images"
+                            >
+                                <ImagePlus className="h-3.5 w-3.5" />
+                            </button>
                             <span className="rounded-full border border-border/70 bg-background/70 px-2 py-1 text-[11px] text-muted-foreground">
                                 {provider}
                             </span>
@@ -28,6 +28,7 @@ describe('CodeEditor - TypeScript Configuration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
     javascript: {
       root_path: '/test/js-project',
@@ -35,6 +36,7 @@ describe('CodeEditor - TypeScript Configuration Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixed: {
       root_path: '/test/mixed-project',
@@ -42,6 +44,7 @@ describe('CodeEditor - TypeScript Configuration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: true, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     unknown: {
       root_path: '/test/unknown',
@@ -49,6 +52,7 @@ describe('CodeEditor - TypeScript Configuration Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
   };
 
@@ -5,6 +5,13 @@ import type * as Monaco from 'monaco-editor';
 import { useEditorStore, useProjectStore } from '@/stores';
 import { getLanguageRegistry } from '@/lib/languages/registry';
 import { CSharpProvider } from '@/lib/languages/csharp';
+import {
+    FRAMEWORK_LANGUAGES,
+    FrameworkProvider,
+    isFrameworkLanguage,
+    registerFrameworkLanguages,
+} from '@/lib/languages/frameworks';
+import { registerSchemaServices } from '@/lib/languages/schemas';
 
 export function shouldActivateCSharpProvider(projectKind?: string | null, activeTabLanguage?: string | null): boolean {
     return projectKind === 'dotnet'
@@ -37,6 +44,17 @@ export function LanguageController() {
             registry.registerFactory('csharp', (m) => new CSharpProvider(m));
         }
 
+        // Vue, Svelte, and Astro files need their language ids before models are created
+        registerFrameworkLanguages(monaco);
+        for (const language of FRAMEWORK_LANGUAGES) {
+            if (!registry.hasLanguage(language)) {
+                registry.registerFactory(language, (m) => new FrameworkProvider(language, m));
+            }
+        }
+
+        // JSON, YAML, and TOML validation and key completion from known schemas
+        registerSchemaServices(monaco);
+
         // Cleanup on unmount (project close)
         return () => {
             // We don't necessarily want to destroy the registry on unmount if we're just
@@ -78,5 +96,28 @@ export function LanguageController() {
         };
     }, [monaco, projectProfile?.kind, currentProject?.rootPath, activeTabLanguage]);
 
+    // Start a framework's language server the first time one of its files is
+    // opened; it keeps running until the project changes
+    useReactiveEffect(() => {
+        if (!monaco || !currentProject?.rootPath || !isFrameworkLanguage(activeTabLanguage)) return;
+
+        getLanguageRegistry()
+            .startProvider(activeTabLanguage, currentProject.rootPath)
+            .catch((error) => {
+                console.error(`[LanguageController] Failed to start ${activeTabLanguage} provider:`, error);
+            });
+    }, [monaco, currentProject?.rootPath, activeTabLanguage]);
+
+    useReactiveEffect(() => {
+        return () => {
+            const registry = getLanguageRegistry();
+            for (const language of FRAMEWORK_LANGUAGES) {
+                if (registry.isProviderActive(language)) {
+                    void registry.stopProvider(language);
+                }
+            }
+        };
+    }, [currentProject?.rootPath]);
+
     return null; // Headless component
 }
@@ -328,6 +328,9 @@ function EditorTitleBarCenter() {
   const selectedConfiguration = useCSharpStore((state) => state.selectedConfiguration);
   const setSelectedConfiguration = useCSharpStore((state) => state.setSelectedConfiguration);
   const isLoadingConfigs = useCSharpStore((state) => state.isLoadingConfigs);
+  const solutions = useCSharpStore((state) => state.solutions);
+  const selectedSolution = useCSharpStore((state) => state.selectedSolution);
+  const setSelectedSolution = useCSharpStore((state) => state.setSelectedSolution);
 
   // Debug logging for C# configuration selector
   if (import.meta.env.DEV) {
@@ -377,6 +380,23 @@ function EditorTitleBarCenter() {
       <span className="text-xs text-muted-foreground">
         {currentProject.name}
       </span>
+      {/* C# Solution Selector, only when there is a choice to make */}
+      {solutions.length > 1 && (
+        <div className="flex items-center gap-2">
+          <span className="text-xs text-muted-foreground/70">|</span>
+          <TitlebarDropdown
+            value={selectedSolution}
+            options={solutions.map((solution) => ({
+              value: solution,
+              label: solution.split('/').pop() || solution,
+              description: solution,
+            }))}
+            onChange={setSelectedSolution}
+            width="auto"
+            align="center"
+          />
+        </div>
+      )}
       {/* C# Build Configuration Selector */}
       {isLoadingConfigs && (
         <div className="flex items-center gap-2">
@@ -30,6 +30,7 @@ describe('EditorTitleBarCenter - C# Configuration Selector Visibility', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
     javascript: {
       root_path: '/test/js-project',
@@ -37,6 +38,7 @@ describe('EditorTitleBarCenter - C# Configuration Selector Visibility', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixed: {
       root_path: '/test/mixed-project',
@@ -44,6 +46,7 @@ describe('EditorTitleBarCenter - C# Configuration Selector Visibility', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: true, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
   };
 
@@ -1,6 +1,7 @@
 import { useRef, useState, useMemo } from 'react';
 import { X, Loader2, CheckCircle2, XCircle, Terminal, Copy, Check, Hammer, AlertCircle, AlertTriangle, ExternalLink } from 'lucide-react';
-import { useBuildPanelStore, useTerminalStore, useDiagnosticsStore, useProjectStore } from '@/stores';
+import { useBuildPanelStore, useTerminalStore, useDiagnosticsStore, useProjectStore, useEditorStore, type TerminalLink, type TerminalSegment } from '@/stores';
+import { open as shellOpen } from '@tauri-apps/plugin-shell';
 import TerminalTabs from './TerminalTabs';
 import type { Diagnostic } from '@/stores/diagnostics/useDiagnosticsStore';
 import ScrollableArea from '@/components/ui/scrollable-area';
@@ -308,14 +309,14 @@ function BuildView() {
             {buildDiagnostics.length > 0 && buildStatus !== 'running' && (
                 <div className="px-3 py-2 bg-muted/10 border-b border-border">
                     <div className="flex flex-col gap-1.5">
-                        {buildDiagnostics.slice(0, 5).map((diagnostic, index) => {
+                        {buildDiagnostics.slice(0, 5).map((diagnostic) => {
                             const Icon = diagnostic.severity === 'error' ? AlertCircle : AlertTriangle;
                             const iconColor = diagnostic.severity === 'error' ? 'text-red-500' : 'text-yellow-500';
                             const fileName = diagnostic.file_path.split(/[/\\]/).pop() || diagnostic.file_path;
 
                             return (
                                 <button
-                                    key={index}
+                                    key={diagnostic.id}
                                     onClick={() => handleDiagnosticClick(diagnostic)}
                                     className="flex items-start gap-2 px-2 py-1 rounded hover:bg-muted/50 text-left group transition-colors"
                                 >
@@ -337,6 +338,14 @@ function BuildView() {
                                             <span className="font-mono opacity-70">
                                                 Ln {diagnostic.line}, Col {diagnostic.column}
                                             </span>
+                                            {diagnostic.target_frameworks.length > 0 && (
+                                                <>
+                                                    <span className="opacity-50">•</span>
+                                                    <span className="truncate opacity-70" title={diagnostic.projects.join('\n')}>
+                                                        {diagnostic.target_frameworks.join(', ')}
+                                                    </span>
+                                                </>
+                                            )}
                                             <ExternalLink className="w-3 h-3 opacity-0 group-hover:opacity-50 transition-opacity shrink-0 ml-auto" />
                                         </div>
                                     </div>
@@ -477,6 +486,101 @@ function TerminalView({ projectProfile }: { projectProfile: any }) {
     );
 }
 
+function openTerminalLink(link: TerminalLink) {
+    if (link.kind === 'url') {
+        void shellOpen(link.target).catch((error) => console.error('[Terminal] Failed to open URL:', error));
+        return;
+    }
+    const position = link.line ? { line: link.line, column: link.column ?? undefined } : undefined;
+    void useEditorStore.getState().openFile(link.target, position);
+}
+
+// Terminal output with detected file locations and URLs made clickable
+/** ANSI color names as sent by the backend, matched to the terminal palette */
+const ANSI_COLORS: Record<string, string> = {
+    black: '#3b3b3b',
+    red: '#f14c4c',
+    green: '#23d18b',
+    yellow: '#e5c07b',
+    blue: '#3b8eea',
+    magenta: '#d670d6',
+    cyan: '#29b8db',
+    white: '#cccccc',
+    brightBlack: '#767676',
+    brightRed: '#ff6b6b',
+    brightGreen: '#5af78e',
+    brightYellow: '#f4f99d',
+    brightBlue: '#6cb6ff',
+    brightMagenta: '#ff92df',
+    brightCyan: '#9aedfe',
+    brightWhite: '#ffffff',
+};
+
+function segmentStyle(segment: TerminalSegment): React.CSSProperties | undefined {
+    const style: React.CSSProperties = {};
+    if (segment.fg) style.color = ANSI_COLORS[segment.fg] ?? segment.fg;
+    if (segment.bg) style.backgroundColor = ANSI_COLORS[segment.bg] ?? segment.bg;
+    if (segment.bold) style.fontWeight = 'bold';
+    if (segment.dim) style.opacity = 0.7;
+    if (segment.italic) style.fontStyle = 'italic';
+    const decorations = [segment.underline && 'underline', segment.strikethrough && 'line-through'].filter(Boolean);
+    if (decorations.length > 0) style.textDecoration = decorations.join(' ');
+    return Object.keys(style).length > 0 ? style : undefined;
+}
+
+function TerminalLinkedText({ content, links, segments }: {
+    content: string;
+    links?: TerminalLink[];
+    segments?: TerminalSegment[];
+}) {
+    if ((!links || links.length === 0) && (!segments || segments.length === 0)) {
+        return <>{content}</>;
+    }
+
+    // Cut the text at both segment and link boundaries so each piece has one
+    // style and belongs to at most one link
+    const styled = segments && segments.length > 0 ? segments : [{ text: content }];
+    const parts: React.ReactNode[] = [];
+    let segmentStart = 0;
+    styled.forEach((segment, segmentIndex) => {
+        const segmentEnd = segmentStart + segment.text.length;
+        const style = segmentStyle(segment);
+        let offset = segmentStart;
+        const pushText = (end: number) => {
+            if (end > offset) {
+                const text = content.slice(offset, end);
+                parts.push(style ? <span key={`${segmentIndex}:${offset}`} style={style}>{text}</span> : text);
+            }
+        };
+
+        for (const link of links ?? []) {
+            if (link.end <= offset || link.start >= segmentEnd) continue;
+            pushText(Math.max(link.start, offset));
+            const linkStart = Math.max(link.start, offset);
+            const linkEnd = Math.min(link.end, segmentEnd);
+            parts.push(
+                <span
+                    key={`${segmentIndex}:${linkStart}:link`}
+                    role="link"
+                    style={style}
+                    className="underline decoration-dotted underline-offset-2 cursor-pointer hover:text-primary"
+                    title={link.kind === 'url' ? link.target : `Open ${link.target}${link.line ? `:${link.line}` : ''}`}
+                    onClick={(event) => {
+                        event.stopPropagation();
+                        openTerminalLink(link);
+                    }}
+                >
+                    {content.slice(linkStart, linkEnd)}
+                </span>
+            );
+            offset = linkEnd;
+        }
+        pushText(segmentEnd);
+        segmentStart = segmentEnd;
+    });
+    return <>{parts}</>;
+}
+
 // Single terminal instance view
 interface SingleTerminalViewProps {
     terminal: import('@/stores/terminal/useTerminalStore').TerminalInstance;
@@ -579,10 +683,14 @@ function SingleTerminalView({
                             </div>
                         )}
                         {entry.type === 'output' && (
-                            <div className="text-foreground/90 pl-4">{entry.content}</div>
+                            <div className="text-foreground/90 pl-4">
+                                <TerminalLinkedText content={entry.content} links={entry.links} segments={entry.segments} />
+                            </div>
                         )}
                         {entry.type === 'error' && (
-                            <div className="text-red-400 pl-4">{entry.content}</div>
+                            <div className="text-red-400 pl-4">
+                                <TerminalLinkedText content={entry.content} links={entry.links} segments={entry.segments} />
+                            </div>
                         )}
                         {entry.type === 'info' && (
                             <div className="text-blue-400 pl-4 italic">{entry.content}</div>
@@ -4,7 +4,7 @@ import type { FileEntry } from '@/types/fs';
 import { getFileExtension } from '@/types/fs';
 import { useFileIcon } from '@/lib/icons';
 import { useProfiler } from '@/hooks/useProfiler';
-import { FrontendProfiler } from '@/lib/services';
+import { FrontendProfiler, duplicatePath, copyRelativePath, copyPosixPath } from '@/lib/services';
 import { useReactiveEffect } from "@/hooks/useReactiveEffect";
 import type { NewFileTemplate } from '@/lib/plugins/types';
 import {
@@ -489,6 +489,32 @@ function FileTree() {
         navigator.clipboard.writeText(path);
     }, []);
 
+    const handleCopyRelativePath = useCallback(async (path: string) => {
+        if (!rootPath) return;
+        try {
+            await navigator.clipboard.writeText(await copyRelativePath(path, rootPath));
+        } catch (error) {
+            console.error('Failed to copy relative path:', error);
+        }
+    }, [rootPath]);
+
+    const handleCopyPosixPath = useCallback(async (path: string) => {
+        try {
+            await navigator.clipboard.writeText(await copyPosixPath(path));
+        } catch (error) {
+            console.error('Failed to copy POSIX path:', error);
+        }
+    }, []);
+
+    const handleDuplicate = useCallback(async (path: string) => {
+        try {
+            await duplicatePath(path);
+            await refreshTree();
+        } catch (error) {
+            console.error('Failed to duplicate:', error);
+        }
+    }, [refreshTree]);
+
     const handleRefresh = useCallback(() => {
         refreshTree();
     }, [refreshTree]);
@@ -654,6 +680,9 @@ function FileTree() {
                     onPaste={handlePaste}
                     onOpenInExplorer={handleOpenInExplorer}
                     onCopyPath={handleCopyPath}
+                    onCopyRelativePath={handleCopyRelativePath}
+                    onCopyPosixPath={handleCopyPosixPath}
+                    onDuplicate={handleDuplicate}
                     onRefresh={handleRefresh}
                     clipboardPath={clipboardPath}
                 />
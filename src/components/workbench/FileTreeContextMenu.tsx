@@ -10,6 +10,9 @@ import {
     FolderOpen,
     FileCode,
     RefreshCw,
+    CopyPlus,
+    Link,
+    Terminal,
 } from 'lucide-react';
 
 export interface ContextMenuPosition {
@@ -36,6 +39,9 @@ interface FileTreeContextMenuProps {
     onPaste: (targetPath: string) => void;
     onOpenInExplorer: (path: string) => void;
     onCopyPath: (path: string) => void;
+    onCopyRelativePath: (path: string) => void;
+    onCopyPosixPath: (path: string) => void;
+    onDuplicate: (path: string) => void;
     onRefresh: () => void;
     clipboardPath: string | null;
 }
@@ -101,6 +107,9 @@ const FileTreeContextMenu = memo(function FileTreeContextMenu({
     onPaste,
     onOpenInExplorer,
     onCopyPath,
+    onCopyRelativePath,
+    onCopyPosixPath,
+    onDuplicate,
     onRefresh,
     clipboardPath,
 }: FileTreeContextMenuProps) {
@@ -216,6 +225,14 @@ const FileTreeContextMenu = memo(function FileTreeContextMenu({
                             onClose();
                         }}
                     />
+                    <MenuItem
+                        icon={<CopyPlus size={14} />}
+                        label="Duplicate"
+                        onClick={() => {
+                            onDuplicate(target.path);
+                            onClose();
+                        }}
+                    />
                 </>
             )}
 
@@ -259,6 +276,24 @@ const FileTreeContextMenu = memo(function FileTreeContextMenu({
                     onClose();
                 }}
             />
+            {!isRoot && (
+                <MenuItem
+                    icon={<Link size={14} />}
+                    label="Copy Relative Path"
+                    onClick={() => {
+                        onCopyRelativePath(target.path);
+                        onClose();
+                    }}
+                />
+            )}
+            <MenuItem
+                icon={<Terminal size={14} />}
+                label="Copy POSIX Path"
+                onClick={() => {
+                    onCopyPosixPath(target.path);
+                    onClose();
+                }}
+            />
             <MenuItem
                 icon={<FolderOpen size={14} />}
                 label="Open in Explorer"
@@ -8,6 +8,7 @@ import {
   Keyboard,
   Layout,
   Palette,
+  Server,
   Sparkles,
   X,
 } from "lucide-react";
@@ -20,6 +21,7 @@ import { AppearanceSection } from "@/components/workbench/SettingsDialog/section
 import { AutocompleteSection } from "@/components/workbench/SettingsDialog/sections/AutocompleteSection";
 import { BuildSection } from "@/components/workbench/SettingsDialog/sections/BuildSection";
 import { EditorSection } from "@/components/workbench/SettingsDialog/sections/EditorSection";
+import { LanguageServersSection } from "@/components/workbench/SettingsDialog/sections/LanguageServersSection";
 import { ShortcutsSection } from "@/components/workbench/SettingsDialog/sections/ShortcutsSection";
 import { VersionControlSection } from "@/components/workbench/SettingsDialog/sections/VersionControlSection";
 import { WorkbenchSection } from "@/components/workbench/SettingsDialog/sections/WorkbenchSection";
@@ -32,6 +34,7 @@ type SettingsSection =
   | "autocomplete"
   | "workbench"
   | "build"
+  | "languageServers"
   | "versionControl"
   | "agent"
   | "shortcuts";
@@ -67,7 +70,7 @@ export default function SettingsDialog({
       setSidebarSize(workbench.sidebarDefaultSize);
       setErrors({});
       // Set initial section if provided and valid
-      const validSections: SettingsSection[] = ['appearance', 'editor', 'autocomplete', 'workbench', 'build', 'versionControl', 'agent', 'shortcuts'];
+      const validSections: SettingsSection[] = ['appearance', 'editor', 'autocomplete', 'workbench', 'build', 'languageServers', 'versionControl', 'agent', 'shortcuts'];
       if (initialSection && validSections.includes(initialSection as SettingsSection)) {
         setActiveSection(initialSection as SettingsSection);
       }
@@ -116,6 +119,11 @@ export default function SettingsDialog({
     { id: "autocomplete", label: "Autocomplete", icon: <Sparkles size={16} /> },
     { id: "workbench", label: "Workbench", icon: <Layout size={16} /> },
     { id: "build", label: "Build", icon: <Hammer size={16} /> },
+    {
+      id: "languageServers",
+      label: "Language Servers",
+      icon: <Server size={16} />,
+    },
     {
       id: "versionControl",
       label: "Version Control",
@@ -184,6 +192,9 @@ export default function SettingsDialog({
               />
             )}
             {activeSection === "build" && <BuildSection settings={settings} />}
+            {activeSection === "languageServers" && (
+              <LanguageServersSection settings={settings} />
+            )}
             {activeSection === "versionControl" && (
               <VersionControlSection settings={settings} />
             )}
@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { useAgentStore } from "@/stores";
-import { Eye, EyeOff, Plug, RefreshCw, AlertCircle } from "lucide-react";
+import { Eye, EyeOff, Plug, RefreshCw, AlertCircle, ArrowDown, ArrowUp, X } from "lucide-react";
+import type { FailoverEntry, ProviderType } from "@/stores/agent/types";
 
 export function AgentSection() {
     const {
@@ -9,6 +10,8 @@ export function AgentSection() {
         updateProviderConfig,
         toggleModelEnabled,
         fetchModels,
+        failoverChain,
+        setFailoverChain,
     } = useAgentStore();
 
     // Local state for API key visibility
@@ -18,6 +21,24 @@ export function AgentSection() {
         setShowKey(prev => ({ ...prev, [id]: !prev[id] }));
     };
 
+    const fallbackCandidates = models.filter(m =>
+        m.enabled && !failoverChain.some(e => e.provider === m.providerId && e.model === m.id)
+    );
+
+    const addFallback = (modelId: string) => {
+        const model = models.find(m => m.id === modelId);
+        if (!model) return;
+        setFailoverChain([...failoverChain, { provider: model.providerId as ProviderType, model: model.id }]);
+    };
+
+    const moveFallback = (index: number, delta: number) => {
+        const target = index + delta;
+        if (target < 0 || target >= failoverChain.length) return;
+        const next: FailoverEntry[] = [...failoverChain];
+        [next[index], next[target]] = [next[target], next[index]];
+        setFailoverChain(next);
+    };
+
     return (
         <div className="space-y-6">
             <div>
@@ -107,6 +128,54 @@ export function AgentSection() {
                 </div>
             </div>
 
+            {/* Failover Chain */}
+            <div className="space-y-4">
+                <div>
+                    <h4 className="text-sm font-medium text-foreground/80 uppercase tracking-wider">Fallback Models</h4>
+                    <p className="text-xs text-muted-foreground mt-1">
+                        Tried in order when the selected model's provider is unreachable or rate limited.
+                    </p>
+                </div>
+
+                <div className="bg-card border border-border rounded-lg overflow-hidden divide-y divide-border">
+                    {failoverChain.length === 0 && (
+                        <div className="p-3 text-sm text-muted-foreground">No fallbacks configured.</div>
+                    )}
+                    {failoverChain.map((entry, index) => (
+                        <div key={`${entry.provider}:${entry.model}`} className="flex items-center justify-between p-3">
+                            <div className="flex flex-col">
+                                <span className="text-sm font-medium">{index + 1}. {entry.model}</span>
+                                <span className="text-xs text-muted-foreground capitalize">{entry.provider}</span>
+                            </div>
+                            <div className="flex items-center gap-1 text-muted-foreground">
+                                <button onClick={() => moveFallback(index, -1)} disabled={index === 0} className="p-1 hover:text-foreground disabled:opacity-30">
+                                    <ArrowUp size={14} />
+                                </button>
+                                <button onClick={() => moveFallback(index, 1)} disabled={index === failoverChain.length - 1} className="p-1 hover:text-foreground disabled:opacity-30">
+                                    <ArrowDown size={14} />
+                                </button>
+                                <button onClick={() => setFailoverChain(failoverChain.filter((_, i) => i !== index))} className="p-1 hover:text-foreground">
+                                    <X size={14} />
+                                </button>
+                            </div>
+                        </div>
+                    ))}
+                </div>
+
+                {fallbackCandidates.length > 0 && (
+                    <select
+                        value=""
+                        onChange={(e) => addFallback(e.target.value)}
+                        className="w-full h-9 bg-background border border-border rounded-md px-3 text-sm focus:outline-none focus:ring-1 focus:ring-primary"
+                    >
+                        <option value="" disabled>Add fallback model...</option>
+                        {fallbackCandidates.map(m => (
+                            <option key={m.id} value={m.id}>{m.name} ({m.providerId})</option>
+                        ))}
+                    </select>
+                )}
+            </div>
+
             {/* Model Management */}
             <div className="space-y-4">
                 <div className="flex items-center justify-between">
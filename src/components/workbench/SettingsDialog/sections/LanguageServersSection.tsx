@@ -0,0 +1,112 @@
+import { useState } from "react";
+import type { SettingsState } from "@/stores";
+
+const languageServers: { id: string; label: string; example: string }[] = [
+  {
+    id: "csharp",
+    label: "C# (csharp-ls)",
+    example: '{\n  "csharp.applyFormattingOptions": true\n}',
+  },
+  {
+    id: "vue",
+    label: "Vue (@vue/language-server)",
+    example: '{\n  "vue.hybridMode": false\n}',
+  },
+  {
+    id: "svelte",
+    label: "Svelte (svelte-language-server)",
+    example: '{\n  "svelte.plugin.css.enable": true\n}',
+  },
+  {
+    id: "astro",
+    label: "Astro (@astrojs/language-server)",
+    example: '{\n  "typescript.tsdk": "node_modules/typescript/lib"\n}',
+  },
+];
+
+function formatSettings(settings: Record<string, unknown> | undefined): string {
+  return settings && Object.keys(settings).length > 0
+    ? JSON.stringify(settings, null, 2)
+    : "";
+}
+
+function ServerSettingsEditor({
+  settings,
+  languageId,
+  label,
+  example,
+}: {
+  settings: SettingsState;
+  languageId: string;
+  label: string;
+  example: string;
+}) {
+  const [text, setText] = useState(() =>
+    formatSettings(settings.serverSettings[languageId])
+  );
+  const [error, setError] = useState("");
+
+  const apply = () => {
+    if (!text.trim()) {
+      settings.setServerSettings(languageId, {});
+      setError("");
+      return;
+    }
+    try {
+      const parsed = JSON.parse(text);
+      if (typeof parsed !== "object" || parsed === null || Array.isArray(parsed)) {
+        setError("Settings must be a JSON object");
+        return;
+      }
+      settings.setServerSettings(languageId, parsed);
+      setError("");
+    } catch (e) {
+      setError(e instanceof Error ? e.message : "Invalid JSON");
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <label className="text-sm font-medium text-foreground">{label}</label>
+      <textarea
+        value={text}
+        onChange={(e) => setText(e.target.value)}
+        onBlur={apply}
+        placeholder={example}
+        rows={8}
+        spellCheck={false}
+        className="w-full px-3 py-2 text-sm rounded-lg border border-border bg-background focus:outline-none focus:ring-1 focus:ring-primary font-mono"
+      />
+      {error ? (
+        <p className="text-xs text-destructive">{error}</p>
+      ) : (
+        <p className="text-xs text-muted-foreground">
+          Sent as initialization options and pushed to the running server when changed
+        </p>
+      )}
+    </div>
+  );
+}
+
+export function LanguageServersSection({ settings }: { settings: SettingsState }) {
+  return (
+    <div className="space-y-6">
+      <div>
+        <h3 className="text-lg font-semibold mb-1">Language Servers</h3>
+        <p className="text-sm text-muted-foreground">
+          Server-specific settings as JSON, with nested objects or dotted keys
+        </p>
+      </div>
+
+      {languageServers.map((server) => (
+        <ServerSettingsEditor
+          key={server.id}
+          settings={settings}
+          languageId={server.id}
+          label={server.label}
+          example={server.example}
+        />
+      ))}
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { memo, useState, useRef } from 'react';
-import { Plus, X, Terminal, SplitSquareHorizontal, SplitSquareVertical, Square, MoreHorizontal, Trash2, Edit2, Palette } from 'lucide-react';
-import { useTerminalStore, type TerminalInstance, type TerminalColor } from '@/stores/terminal/useTerminalStore';
+import { invoke } from '@tauri-apps/api/core';
+import { Plus, X, Terminal, SplitSquareHorizontal, SplitSquareVertical, Square, MoreHorizontal, Trash2, Edit2, Palette, ChevronDown, Globe } from 'lucide-react';
+import { useTerminalStore, type TerminalInstance, type TerminalColor, type TerminalTarget } from '@/stores/terminal/useTerminalStore';
 import { cn } from '@/lib/utils';
 import { useReactiveEffect } from "@/hooks/useReactiveEffect";
 
@@ -120,7 +121,16 @@ const TerminalTab = memo(function TerminalTab({
                         onClick={(e) => e.stopPropagation()}
                     />
                 ) : (
-                    <span className="truncate max-w-[100px]">{terminal.name}</span>
+                    <span
+                        className="truncate max-w-[100px]"
+                        title={
+                            terminal.sshHost ? `${terminal.name} (SSH: ${terminal.sshHost})`
+                                : terminal.wslDistro ? `${terminal.name} (WSL: ${terminal.wslDistro})`
+                                    : terminal.name
+                        }
+                    >
+                        {terminal.name}
+                    </span>
                 )}
 
                 {/* Context menu button */}
@@ -206,6 +216,124 @@ const TerminalTab = memo(function TerminalTab({
     );
 });
 
+// ============================================================================
+// New Remote Terminal Menu
+// ============================================================================
+
+interface WslDistro {
+    name: string;
+    isDefault: boolean;
+    state: string;
+    version: number;
+}
+
+interface SshHost {
+    alias: string;
+    hostName: string | null;
+    user: string | null;
+    port: number | null;
+}
+
+/**
+ * Menu of WSL distros and SSH hosts to open a terminal on. Renders nothing
+ * when there are neither.
+ */
+const NewRemoteTerminalMenu = memo(function NewRemoteTerminalMenu({ onCreate }: { onCreate: (target: TerminalTarget) => void }) {
+    const [distros, setDistros] = useState<WslDistro[]>([]);
+    const [hosts, setHosts] = useState<SshHost[]>([]);
+    const [showMenu, setShowMenu] = useState(false);
+    const menuRef = useRef<HTMLDivElement>(null);
+
+    useReactiveEffect(() => {
+        let cancelled = false;
+        invoke<WslDistro[]>('list_wsl_distros')
+            .then((list) => {
+                if (!cancelled) setDistros(list);
+            })
+            .catch((error) => console.error('[Terminal] Failed to list WSL distros:', error));
+        invoke<SshHost[]>('list_ssh_hosts')
+            .then((list) => {
+                if (!cancelled) setHosts(list);
+            })
+            .catch((error) => console.error('[Terminal] Failed to list SSH hosts:', error));
+        return () => {
+            cancelled = true;
+        };
+    }, []);
+
+    // Close menu when clicking outside
+    useReactiveEffect(() => {
+        if (!showMenu) return;
+
+        const handleClickOutside = (e: MouseEvent) => {
+            if (menuRef.current && !menuRef.current.contains(e.target as Node)) {
+                setShowMenu(false);
+            }
+        };
+
+        document.addEventListener('mousedown', handleClickOutside);
+        return () => document.removeEventListener('mousedown', handleClickOutside);
+    }, [showMenu]);
+
+    if (distros.length === 0 && hosts.length === 0) return null;
+
+    const itemClass = "w-full flex items-center gap-2 px-3 py-1.5 text-xs hover:bg-muted transition-colors";
+
+    return (
+        <div ref={menuRef} className="relative">
+            <button
+                onClick={() => setShowMenu(!showMenu)}
+                className="p-1.5 rounded hover:bg-muted transition-colors text-muted-foreground hover:text-foreground"
+                title="New Remote Terminal"
+            >
+                <ChevronDown className="w-3 h-3" />
+            </button>
+
+            {showMenu && (
+                <div className="absolute top-full left-0 mt-1 bg-card border border-border rounded-lg shadow-lg py-1 z-50 min-w-[180px]">
+                    {distros.length > 0 && (
+                        <div className="px-3 py-1 text-[10px] uppercase tracking-wide text-muted-foreground">WSL</div>
+                    )}
+                    {distros.map((distro) => (
+                        <button
+                            key={`wsl:${distro.name}`}
+                            onClick={() => {
+                                setShowMenu(false);
+                                onCreate({ wslDistro: distro.name });
+                            }}
+                            className={itemClass}
+                        >
+                            <Terminal className="w-3 h-3" />
+                            {distro.name}
+                            {distro.isDefault && <span className="text-muted-foreground">(default)</span>}
+                        </button>
+                    ))}
+
+                    {distros.length > 0 && hosts.length > 0 && <div className="h-px bg-border my-1" />}
+
+                    {hosts.length > 0 && (
+                        <div className="px-3 py-1 text-[10px] uppercase tracking-wide text-muted-foreground">SSH</div>
+                    )}
+                    {hosts.map((host) => (
+                        <button
+                            key={`ssh:${host.alias}`}
+                            onClick={() => {
+                                setShowMenu(false);
+                                onCreate({ sshHost: host.alias });
+                            }}
+                            className={itemClass}
+                            title={host.hostName ? `${host.user ? `${host.user}@` : ''}${host.hostName}${host.port ? `:${host.port}` : ''}` : host.alias}
+                        >
+                            <Globe className="w-3 h-3" />
+                            {host.alias}
+                        </button>
+                    ))}
+                </div>
+            )}
+        </div>
+    );
+});
+
 // ============================================================================
 // Terminal Tabs Bar
 // ============================================================================
@@ -245,6 +373,7 @@ function TerminalTabs() {
                 >
                     <Plus className="w-4 h-4" />
                 </button>
+                <NewRemoteTerminalMenu onCreate={(target) => createTerminal(undefined, target)} />
             </div>
 
             {/* Layout Controls */}
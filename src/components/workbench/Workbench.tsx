@@ -58,6 +58,8 @@ function Workbench() {
     const isInspectorOpen = useInspectorStore((state) => state.isInspectorOpen);
     const isLoadingBuildConfigs = useCSharpStore((state) => state.isLoadingConfigs);
     const isAgentOpen = useAgentStore((state) => state.isOpen);
+    const isAgentGenerating = useAgentStore((state) => state.isGenerating);
+    const agentStreamingStats = useAgentStore((state) => state.streamingStats);
     const isDebugOpen = useDebugStore((state) => state.isPanelOpen);
     const detectedProjects = usePluginStore((state) => state.detectedProjects);
 
@@ -418,6 +420,15 @@ function Workbench() {
                         className="flex items-center"
                         style={{ gap: 'var(--density-gap-md, 0.75rem)' }}
                     >
+                        {/* Live AI Throughput */}
+                        {isAgentGenerating && agentStreamingStats?.tokensPerSecond !== undefined && (
+                            <span
+                                className="opacity-80"
+                                title={`First token after ${Math.round(agentStreamingStats.timeToFirstTokenMs ?? 0)}ms, ~${agentStreamingStats.outputTokens} tokens`}
+                            >
+                                AI: {agentStreamingStats.tokensPerSecond.toFixed(1)} tok/s
+                            </span>
+                        )}
                         {activeTab && (
                             <>
                                 {cursorPosition ? (
@@ -86,12 +86,14 @@ export const CategoryBreakdown: React.FC = () => {
             case 'file_io': return 'bg-yellow-500';
             case 'git_operation': return 'bg-pink-500';
             case 'lsp_request': return 'bg-violet-500';
+            case 'ai_request': return 'bg-rose-500';
             default: return 'bg-gray-500';
         }
     };
 
     const getCategoryLabel = (category: string) => {
         if (category === 'tauri_command') return 'Tauri Command';
+        if (category === 'ai_request') return 'AI Request';
         return category.split('_').map(w => w.charAt(0).toUpperCase() + w.slice(1)).join(' ');
     };
 
@@ -51,6 +51,7 @@ const FlameRect: React.FC<FlameRectProps> = ({
       case 'file_io': hue = 50; break; // yellow
       case 'git_operation': hue = 320; break; // magenta
       case 'lsp_request': hue = 260; break; // violet
+      case 'ai_request': hue = 350; break; // rose
       default: hue = 0; break; // red/gray
     }
     
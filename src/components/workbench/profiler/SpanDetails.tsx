@@ -1,8 +1,15 @@
 import React from 'react';
 import { useProfilerStore } from '@/stores/profiler';
-import { Clock, AlertTriangle, List, Activity } from 'lucide-react';
+import { Clock, AlertTriangle, List, Activity, Download, HardDrive, Hourglass } from 'lucide-react';
+import { ProfilerService } from '@/lib/services';
 import { useReactiveEffect } from "@/hooks/useReactiveEffect";
 
+function formatBytes(bytes: number): string {
+    if (bytes < 1024) return `${Math.round(bytes)} B`;
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
 export const SpanDetails: React.FC = () => {
     const { selectedSpan, attribution, analyzeSpan, isLoading } = useProfilerStore();
 
@@ -12,6 +19,16 @@ export const SpanDetails: React.FC = () => {
         }
     }, [selectedSpan, attribution, analyzeSpan]);
 
+    const handleExportFlamegraph = async () => {
+        if (!selectedSpan) return;
+        try {
+            await ProfilerService.downloadFlamegraph(selectedSpan.id, selectedSpan.name);
+        } catch (error) {
+            console.error('Failed to export flamegraph:', error);
+            alert(`Failed to export flamegraph: ${error instanceof Error ? error.message : String(error)}`);
+        }
+    };
+
     if (!selectedSpan) {
         return (
             <div className="flex flex-col items-center justify-center h-full text-muted-foreground p-8">
@@ -46,6 +63,14 @@ export const SpanDetails: React.FC = () => {
                     <span className="text-xs font-normal px-2 py-0.5 rounded-full bg-muted text-muted-foreground">
                         {selectedSpan.category}
                     </span>
+                    <button
+                        onClick={handleExportFlamegraph}
+                        className="ml-auto flex items-center gap-1 px-2 py-1 text-xs font-normal rounded border border-border hover:bg-muted"
+                        title="Save this span tree as a flamegraph SVG"
+                    >
+                        <Download size={12} />
+                        Export SVG
+                    </button>
                 </h3>
                 <div className="text-sm text-muted-foreground mt-1 font-mono">
                     {selectedSpan.target}
@@ -107,6 +132,85 @@ export const SpanDetails: React.FC = () => {
                 </div>
             </div>
 
+            {/* AI Usage */}
+            {attribution.aiUsage && (
+                <div>
+                    <h4 className="text-sm font-medium mb-3 flex items-center gap-2">
+                        AI Requests
+                        <span className="text-xs text-muted-foreground font-normal">
+                            ({attribution.aiUsage.percentage.toFixed(1)}% of total time)
+                        </span>
+                    </h4>
+                    <div className="space-y-2">
+                        {attribution.aiUsage.models.map((usage) => (
+                            <div key={`${usage.provider}/${usage.model}`} className="p-2 rounded bg-muted/30 text-sm">
+                                <div className="flex justify-between">
+                                    <span className="font-medium">{usage.provider}/{usage.model}</span>
+                                    <span className="font-mono text-muted-foreground">{usage.totalTimeMs.toFixed(2)}ms</span>
+                                </div>
+                                <div className="text-xs text-muted-foreground">
+                                    {usage.requestCount} request{usage.requestCount === 1 ? '' : 's'} · {usage.inputTokens} in / {usage.outputTokens} out tokens
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+
+            {/* I/O Volume */}
+            {attribution.ioHotspots.length > 0 && (
+                <div>
+                    <h4 className="text-sm font-medium mb-3 flex items-center gap-2">
+                        <HardDrive size={14} />
+                        I/O Volume
+                        <span className="text-xs text-muted-foreground font-normal">
+                            (Low throughput on a slow span points at CPU, not disk)
+                        </span>
+                    </h4>
+                    <div className="space-y-2">
+                        {attribution.ioHotspots.map((io) => (
+                            <div key={io.span.id} className="p-2 rounded bg-muted/30 text-sm">
+                                <div className="flex justify-between">
+                                    <span className="font-medium">{io.span.name}</span>
+                                    <span className="font-mono text-muted-foreground">
+                                        {formatBytes(io.bytesRead + io.bytesWritten)}
+                                    </span>
+                                </div>
+                                <div className="text-xs text-muted-foreground">
+                                    {formatBytes(io.bytesRead)} read / {formatBytes(io.bytesWritten)} written · {io.filesRead + io.filesWritten} file{io.filesRead + io.filesWritten === 1 ? '' : 's'} · {io.span.durationMs.toFixed(2)}ms ({formatBytes(io.throughputBytesPerSec)}/s)
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+
+            {/* Blocked Threads */}
+            {attribution.blockingSpans.length > 0 && (
+                <div>
+                    <h4 className="text-sm font-medium mb-3 flex items-center gap-2 text-destructive">
+                        <Hourglass size={14} />
+                        Blocked Threads
+                        <span className="text-xs text-muted-foreground font-normal">
+                            (Move synchronous work to spawn_blocking)
+                        </span>
+                    </h4>
+                    <div className="space-y-2">
+                        {attribution.blockingSpans.map((blocking) => (
+                            <div key={blocking.span.id} className="p-2 rounded bg-muted/30 text-sm">
+                                <div className="flex justify-between">
+                                    <span className="font-medium">{blocking.span.name}</span>
+                                    <span className="font-mono text-muted-foreground">{blocking.longestPollMs.toFixed(2)}ms</span>
+                                </div>
+                                <div className="text-xs text-muted-foreground">
+                                    {blocking.threadKind === 'ipc' ? 'IPC thread' : 'Async worker'} {blocking.thread} · {blocking.busyMs.toFixed(2)}ms busy of {blocking.span.durationMs.toFixed(2)}ms
+                                </div>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+
              {/* Fields/Metadata */}
              {selectedSpan.fields && selectedSpan.fields.length > 0 && (
                 <div>
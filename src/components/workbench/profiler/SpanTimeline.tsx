@@ -69,6 +69,7 @@ export const SpanTimeline: React.FC = () => {
             case 'tauri_command': return 'bg-orange-500';
             case 'backend_operation': return 'bg-cyan-600';
             case 'file_io': return 'bg-yellow-500';
+            case 'ai_request': return 'bg-rose-500';
             default: return 'bg-gray-500';
         }
     };
@@ -419,6 +419,111 @@ export function useDefaultCommands() {
                     }
                 },
             },
+            {
+                id: 'terminal.cleanProject',
+                label: 'Clean Project',
+                category: 'terminal',
+                description: 'Remove build output and caches (bin/obj, target, node_modules/.cache, ...)',
+                execute: async () => {
+                    const { useFileSystemStore } = await import('@/stores/editor/useFileSystemStore');
+                    const { useTerminalStore } = await import('@/stores/terminal/useTerminalStore');
+                    const { cleanProject, formatCleanSize } = await import('@/lib/services/tauri/ProjectCleanService');
+                    const { confirm, message } = await import('@tauri-apps/plugin-dialog');
+
+                    const rootPath = useFileSystemStore.getState().rootPath;
+                    if (!rootPath) return;
+
+                    const preview = await cleanProject(rootPath, { dryRun: true });
+                    if (preview.entries.length === 0) {
+                        await message('There is no build output or cache to clean.', { title: 'Clean Project', kind: 'info' });
+                        return;
+                    }
+                    const listing = preview.entries
+                        .map((entry) => `${entry.path} (${formatCleanSize(entry.bytes)})`)
+                        .join('\n');
+                    const proceed = await confirm(
+                        `Remove ${preview.entries.length} directories and free ${formatCleanSize(preview.totalBytes)}?\n\n${listing}`,
+                        { title: 'Clean Project', kind: 'warning' }
+                    );
+                    if (!proceed) return;
+
+                    const report = await cleanProject(rootPath);
+                    useBuildPanelStore.getState().openPanel();
+                    const terminals = useTerminalStore.getState();
+                    const terminalId = terminals.activeTerminalId ?? terminals.createTerminal();
+                    const removed = report.entries.filter((entry) => entry.removed);
+                    terminals.addEntry(
+                        terminalId,
+                        'info',
+                        `Cleaned ${removed.length} directories, freed ${formatCleanSize(removed.reduce((sum, entry) => sum + entry.bytes, 0))}`
+                    );
+                    for (const error of report.errors) {
+                        terminals.addEntry(terminalId, 'error', error);
+                    }
+                },
+            },
+            {
+                id: 'terminal.toggleWatchBuild',
+                label: 'Toggle Build on Save',
+                category: 'terminal',
+                description: 'Rebuild the project whenever its sources change',
+                execute: async () => {
+                    const { useProjectStore } = await import('@/stores/project/useProjectStore');
+                    const { useTerminalStore } = await import('@/stores/terminal/useTerminalStore');
+                    const { isWatchingBuild, startWatchBuild, stopWatchBuild } = await import('@/lib/services/tauri/WatchBuildService');
+
+                    useBuildPanelStore.getState().openPanel();
+                    const terminals = useTerminalStore.getState();
+                    const terminalId = terminals.activeTerminalId ?? terminals.createTerminal();
+                    const log = (type: 'info' | 'error', content: string) =>
+                        useTerminalStore.getState().addEntry(terminalId, type, content);
+
+                    if (isWatchingBuild()) {
+                        await stopWatchBuild();
+                        log('info', 'Build on save stopped');
+                        return;
+                    }
+
+                    const profile = useProjectStore.getState().projectProfile;
+                    if (!profile) return;
+                    const buildSystem = profile.dotnet.solution_path || profile.dotnet.project_path
+                        ? 'dotnet'
+                        : profile.node.has_package_json
+                            ? 'node'
+                            : null;
+                    if (!buildSystem) {
+                        log('error', 'Build on save: no supported build system found');
+                        return;
+                    }
+
+                    try {
+                        await startWatchBuild(profile.root_path, buildSystem, (event) => {
+                            switch (event.phase) {
+                                case 'started':
+                                    log('info', `Building (${event.changedPaths.length} changed file${event.changedPaths.length === 1 ? '' : 's'})...`);
+                                    break;
+                                case 'cancelled':
+                                    log('info', 'Build cancelled by newer changes');
+                                    break;
+                                case 'finished':
+                                    if (event.success) {
+                                        log('info', `Build succeeded in ${event.durationMs}ms`);
+                                    } else {
+                                        log('error', event.error ?? `Build failed in ${event.durationMs}ms`);
+                                        if (event.output) log('error', event.output.trimEnd());
+                                    }
+                                    break;
+                                case 'failed':
+                                    log('error', event.error ?? 'Build could not be started');
+                                    break;
+                            }
+                        });
+                        log('info', `Build on save started (${buildSystem})`);
+                    } catch (error) {
+                        log('error', `Build on save: ${error}`);
+                    }
+                },
+            },
             {
                 id: 'terminal.clear',
                 label: 'Clear Terminal',
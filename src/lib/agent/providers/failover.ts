@@ -0,0 +1,154 @@
+/**
+ * Provider failover
+ * Streams through an ordered provider/model chain, moving on to the next entry
+ * when a provider is unreachable or rate limited
+ */
+
+import type {
+    AgentProvider,
+    ProviderConfig,
+    ProviderMessage,
+    ProviderType,
+    StreamCallbacks,
+    StreamOptions,
+    StreamResult,
+    ToolDefinition,
+} from './types';
+
+/**
+ * One entry in a failover chain
+ */
+export interface FailoverTarget {
+    provider: ProviderType;
+    model: string;
+}
+
+/**
+ * Callbacks for a failover stream
+ */
+export interface FailoverCallbacks extends StreamCallbacks {
+    /** Called before moving on from a failed target to the next one */
+    onFailover?: (from: FailoverTarget, to: FailoverTarget, error: unknown) => void;
+}
+
+/**
+ * Result of a failover stream, annotated with the target that served it
+ */
+export interface FailoverStreamResult extends StreamResult {
+    servedBy: FailoverTarget;
+    /** Implementation of the serving provider, for formatting follow-up messages */
+    provider: AgentProvider;
+}
+
+const RETRYABLE_PATTERNS = [
+    /failed to fetch/i,
+    /network ?error/i,
+    /error sending request/i,
+    /connection (refused|reset|closed)/i,
+    /econnrefused|econnreset|etimedout|enotfound/i,
+    /timed? ?out/i,
+    /rate.?limit/i,
+    /too many requests/i,
+    /overloaded/i,
+    /\b(429|500|502|503|504|529)\b/,
+];
+
+/**
+ * Whether an error is worth retrying on another provider: connection failures,
+ * rate limits and server-side errors. Aborts and request errors are not.
+ */
+export function isFailoverError(error: unknown): boolean {
+    if (error instanceof DOMException && error.name === 'AbortError') return false;
+    if (error instanceof Error && error.name === 'AbortError') return false;
+    const message = error instanceof Error ? error.message : String(error);
+    return RETRYABLE_PATTERNS.some(pattern => pattern.test(message));
+}
+
+/**
+ * Build the effective chain: the primary target first, then the configured
+ * fallbacks with duplicates removed
+ */
+export function buildFailoverChain(primary: FailoverTarget, fallbacks: FailoverTarget[]): FailoverTarget[] {
+    const chain: FailoverTarget[] = [];
+    for (const target of [primary, ...fallbacks]) {
+        if (!target.model) continue;
+        if (chain.some(t => t.provider === target.provider && t.model === target.model)) continue;
+        chain.push(target);
+    }
+    return chain;
+}
+
+/**
+ * Drop images for models that can't read them, noting the omission in the text
+ */
+export function withSupportedImages(
+    messages: ProviderMessage[],
+    provider: AgentProvider,
+    model: string
+): ProviderMessage[] {
+    if (provider.supportsVision(model)) return messages;
+    return messages.map(msg => {
+        if (!msg.images || msg.images.length === 0) return msg;
+        const { images, ...rest } = msg;
+        const note = `[${images.length} attached image(s) omitted: ${model} does not accept images]`;
+        return { ...rest, content: msg.content ? `${msg.content}\n\n${note}` : note };
+    });
+}
+
+/**
+ * Stream a chat completion through a failover chain.
+ * A target is only abandoned before it has streamed any output; once content
+ * reaches the caller, errors are surfaced as-is rather than replayed elsewhere.
+ */
+export async function streamWithFailover(
+    chain: FailoverTarget[],
+    resolveProvider: (type: ProviderType) => AgentProvider,
+    messages: ProviderMessage[],
+    systemPrompt: string,
+    tools: ToolDefinition[],
+    options: Omit<StreamOptions, 'model'>,
+    callbacks: FailoverCallbacks,
+    configFor: (type: ProviderType) => ProviderConfig
+): Promise<FailoverStreamResult> {
+    if (chain.length === 0) {
+        throw new Error('No provider configured');
+    }
+
+    let lastError: unknown;
+    for (let i = 0; i < chain.length; i++) {
+        const target = chain[i];
+        const provider = resolveProvider(target.provider);
+        let streamed = false;
+
+        try {
+            const result = await provider.stream(
+                withSupportedImages(messages, provider, target.model),
+                systemPrompt,
+                tools,
+                { ...options, model: target.model },
+                {
+                    onContent: text => {
+                        streamed = true;
+                        callbacks.onContent(text);
+                    },
+                    onThinking: text => {
+                        streamed = true;
+                        callbacks.onThinking?.(text);
+                    },
+                },
+                configFor(target.provider)
+            );
+            return { ...result, servedBy: target, provider };
+        } catch (error) {
+            lastError = error;
+            const next = chain[i + 1];
+            if (streamed || !next || options.abortSignal?.aborted || !isFailoverError(error)) {
+                throw error;
+            }
+            console.warn(`[Agent] ${target.provider}/${target.model} failed, falling back to ${next.provider}/${next.model}:`, error);
+            callbacks.onFailover?.(target, next, error);
+        }
+    }
+
+    throw lastError;
+}
@@ -4,6 +4,7 @@
  */
 
 export * from './types';
+export * from './failover';
 
 import type { AgentProvider, ProviderType } from './types';
 import { ollamaProvider } from './ollamaProvider';
@@ -55,6 +55,19 @@ function toMinimaxMessages(messages: ProviderMessage[]): MinimaxMessage[] {
                 };
             }
 
+            if (msg.images && msg.images.length > 0) {
+                return {
+                    role: msg.role,
+                    content: [
+                        { type: 'text' as const, text: msg.content },
+                        ...msg.images.map(image => ({
+                            type: 'image_url' as const,
+                            image_url: { url: `data:${image.mimeType};base64,${image.data}` },
+                        })),
+                    ],
+                };
+            }
+
             return {
                 role: msg.role,
                 content: msg.content,
@@ -65,6 +78,10 @@ function toMinimaxMessages(messages: ProviderMessage[]): MinimaxMessage[] {
 export const minimaxProvider: AgentProvider = {
     name: 'minimax',
 
+    supportsVision(model: string): boolean {
+        return /vl|vision/i.test(model);
+    },
+
     async stream(
         messages: ProviderMessage[],
         systemPrompt: string,
@@ -96,6 +113,9 @@ export const minimaxProvider: AgentProvider = {
                 messages: minimaxMessages,
                 maxTokens: options.maxTokens || 4096,
                 temperature: options.temperature,
+                stop: options.stop,
+                topP: options.topP,
+                seed: options.seed,
                 tools: minimaxTools,
             },
             config.apiKey,
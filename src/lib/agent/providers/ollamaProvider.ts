@@ -22,14 +22,19 @@ import type {
 function toOllamaMessages(messages: ProviderMessage[]): Array<{
     role: string;
     content: string;
+    images?: string[];
     tool_calls?: Array<{ function: { name: string; arguments: Record<string, unknown> } }>;
 }> {
     return messages.map(msg => {
-        const base: { role: string; content: string; tool_calls?: any } = {
+        const base: { role: string; content: string; images?: string[]; tool_calls?: any } = {
             role: msg.role,
             content: msg.content,
         };
 
+        if (msg.images && msg.images.length > 0) {
+            base.images = msg.images.map(image => image.data);
+        }
+
         if (msg.toolCalls && msg.toolCalls.length > 0) {
             base.tool_calls = msg.toolCalls.map(tc => ({
                 function: {
@@ -131,9 +136,18 @@ function parseToolCallsFromText(
     return { toolCalls, remainingContent };
 }
 
+/**
+ * Ollama model families that accept images
+ */
+const OLLAMA_VISION_MODELS = /llava|bakllava|vision|[-.]vl|moondream|minicpm-v|gemma3|llama4|mistral-small3\.[12]/i;
+
 export const ollamaProvider: AgentProvider = {
     name: 'ollama',
 
+    supportsVision(model: string): boolean {
+        return OLLAMA_VISION_MODELS.test(model);
+    },
+
     async stream(
         messages: ProviderMessage[],
         systemPrompt: string,
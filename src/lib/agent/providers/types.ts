@@ -18,6 +18,16 @@ export interface ToolDefinition {
         };
     };
 }
+/**
+ * Base64 image attached to a message, for vision-capable models
+ */
+export interface ProviderImage {
+    /** e.g. `image/png` */
+    mimeType: string;
+    /** Base64 without a data URL prefix */
+    data: string;
+}
+
 /**
  * Unified message format used internally
  * Each provider converts to/from their specific format
@@ -29,6 +39,8 @@ export interface ProviderMessage {
     toolCallId?: string;
     /** Tool calls made by assistant */
     toolCalls?: ToolCall[];
+    /** Images attached to a user message */
+    images?: ProviderImage[];
 }
 
 /**
@@ -77,6 +89,12 @@ export interface StreamOptions {
     temperature?: number;
     /** Maximum tokens to generate */
     maxTokens?: number;
+    /** Sequences that end generation */
+    stop?: string[];
+    /** Nucleus sampling probability mass */
+    topP?: number;
+    /** Seed for reproducible sampling */
+    seed?: number;
     /** Abort signal for cancellation */
     abortSignal?: AbortSignal;
 }
@@ -107,6 +125,12 @@ export interface AgentProvider {
     /** Provider name for logging */
     readonly name: string;
 
+    /**
+     * Whether a model accepts image inputs
+     * @param model - Model name
+     */
+    supportsVision(model: string): boolean;
+
     /**
      * Stream a chat completion
      * @param messages - Conversation history in unified format
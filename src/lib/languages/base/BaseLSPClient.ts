@@ -2,6 +2,8 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { LSPMessage, LSPRequestHandler, LSPClientConfig } from './types';
 import { FrontendProfiler } from '@/lib/services';
+import { useSettingsStore } from '@/stores/workbench/useSettingsStore';
+import { expandServerSettings, resolveSettingsSection, type ServerSettings } from './serverSettings';
 
 /**
  * Generic LSP client for communicating with language servers via Tauri IPC
@@ -25,6 +27,7 @@ export class BaseLSPClient {
     private openDocuments = new Set<string>();
     private startPromise: Promise<void> | null = null;
     private initializePromise: Promise<any> | null = null;
+    private unsubscribeSettings: (() => void) | null = null;
 
     constructor(protected config: LSPClientConfig) {
         this.registerDefaultRequestHandlers();
@@ -64,7 +67,7 @@ export class BaseLSPClient {
                 console.log(`[LSPClient:${this.config.languageId}] Starting language server...`);
 
                 // Listen for LSP messages from Rust backend
-                this.unlisten = await listen<LSPMessage>('lsp-message', (event) => {
+                this.unlisten = await listen<LSPMessage>(this.config.eventName ?? 'lsp-message', (event) => {
                     this.handleMessage(event.payload);
                 });
 
@@ -112,13 +115,15 @@ export class BaseLSPClient {
             await this.sendNotification('exit', {});
 
             // Stop the language server process
-            await invoke(this.config.stopCommand);
+            await invoke(this.config.stopCommand, this.config.commandArgs);
 
             // Cleanup
             if (this.unlisten) {
                 this.unlisten();
                 this.unlisten = null;
             }
+            this.unsubscribeSettings?.();
+            this.unsubscribeSettings = null;
 
             this.isStarted = false;
             this.isInitialized = false;
@@ -172,12 +177,10 @@ export class BaseLSPClient {
                 params,
             };
 
-            const sendCommand = this.config.sendMessageCommand || 'send_lsp_message';
-
             return new Promise<T>((resolve, reject) => {
                 this.pendingRequests.set(id, { resolve, reject });
 
-                invoke(sendCommand, { message: JSON.stringify(message) })
+                this.sendMessage(message)
                     .catch((error) => {
                         this.pendingRequests.delete(id);
                         reject(error);
@@ -213,8 +216,15 @@ export class BaseLSPClient {
             params,
         };
 
+        await this.sendMessage(message);
+    }
+
+    /**
+     * Pass a message to the backend for the server
+     */
+    private async sendMessage(message: LSPMessage): Promise<void> {
         const sendCommand = this.config.sendMessageCommand || 'send_lsp_message';
-        await invoke(sendCommand, { message: JSON.stringify(message) });
+        await invoke(sendCommand, { ...this.config.commandArgs, message: JSON.stringify(message) });
     }
 
     /**
@@ -325,6 +335,12 @@ export class BaseLSPClient {
 
                 this.isInitialized = true;
 
+                // Servers that ignore initializationOptions read settings from here
+                if (Object.keys(this.getServerSettings()).length > 0) {
+                    await this.pushServerSettings();
+                }
+                this.watchServerSettings();
+
                 return result;
             } catch (error) {
                 console.error(`[LSPClient:${this.config.languageId}] Initialization failed:`, error);
@@ -339,6 +355,40 @@ export class BaseLSPClient {
         }
     }
 
+    /**
+     * Server settings for this language from Fluxel settings
+     */
+    protected getServerSettings(): ServerSettings {
+        return useSettingsStore.getState().serverSettings[this.config.languageId] ?? {};
+    }
+
+    /**
+     * Send the current server settings with `workspace/didChangeConfiguration`
+     */
+    protected async pushServerSettings(): Promise<void> {
+        try {
+            await this.sendNotification('workspace/didChangeConfiguration', {
+                settings: expandServerSettings(this.getServerSettings()),
+            });
+        } catch (error) {
+            console.warn(`[LSPClient:${this.config.languageId}] Failed to push settings:`, error);
+        }
+    }
+
+    /**
+     * Push server settings again whenever they change in Fluxel settings
+     */
+    private watchServerSettings(): void {
+        this.unsubscribeSettings?.();
+        this.unsubscribeSettings = useSettingsStore.subscribe((state, previous) => {
+            const languageId = this.config.languageId;
+            if (state.serverSettings[languageId] !== previous.serverSettings[languageId] && this.isInitialized) {
+                console.log(`[LSPClient:${languageId}] Server settings changed, pushing configuration`);
+                void this.pushServerSettings();
+            }
+        });
+    }
+
     /**
      * Build params for the `initialize` request.
      * Subclasses can override to customize URI normalization, workspace folders, and init options.
@@ -353,6 +403,7 @@ export class BaseLSPClient {
             rootPath: normalizedRoot,
             rootUri: rootUri,
             capabilities: this.getClientCapabilities(),
+            initializationOptions: expandServerSettings(this.getServerSettings()),
             workspaceFolders: [
                 {
                     uri: rootUri,
@@ -393,8 +444,7 @@ export class BaseLSPClient {
      */
     private async sendResponse(id: number | string, result: any): Promise<void> {
         const response: LSPMessage = { jsonrpc: '2.0', id, result };
-        const sendCommand = this.config.sendMessageCommand || 'send_lsp_message';
-        await invoke(sendCommand, { message: JSON.stringify(response) });
+        await this.sendMessage(response);
     }
 
     /**
@@ -406,8 +456,7 @@ export class BaseLSPClient {
                 ? error
                 : { code: -32603, message: String(error) };
         const response: LSPMessage = { jsonrpc: '2.0', id, error: safeError as any };
-        const sendCommand = this.config.sendMessageCommand || 'send_lsp_message';
-        await invoke(sendCommand, { message: JSON.stringify(response) });
+        await this.sendMessage(response);
     }
 
     /**
@@ -429,6 +478,12 @@ export class BaseLSPClient {
             return null;
         });
 
+        // Answer configuration pulls from the server settings
+        this.onRequest('workspace/configuration', async (params: any) => {
+            const items = Array.isArray(params?.items) ? params.items : [];
+            return items.map((item: any) => resolveSettingsSection(this.getServerSettings(), item?.section));
+        });
+
         // Handle window/workDoneProgress/create
         this.onRequest('window/workDoneProgress/create', async (_params: any) => {
             return null;
@@ -5,3 +5,4 @@
 export * from './types';
 export * from './BaseLSPClient';
 export * from './BaseLanguageProvider';
+export * from './serverSettings';
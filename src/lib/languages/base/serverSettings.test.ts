@@ -0,0 +1,38 @@
+import { describe, expect, it } from 'vitest';
+
+import { expandServerSettings, mergeServerSettings, resolveSettingsSection } from './serverSettings';
+
+describe('language server settings', () => {
+    it('expands dotted keys into nested objects', () => {
+        const result = expandServerSettings({
+            'python.analysis.typeCheckingMode': 'strict',
+            python: { analysis: { autoImportCompletions: false } },
+            csharp: { analyzers: true },
+        });
+
+        expect(result).toEqual({
+            python: { analysis: { typeCheckingMode: 'strict', autoImportCompletions: false } },
+            csharp: { analyzers: true },
+        });
+    });
+
+    it('resolves configuration sections', () => {
+        const settings = { 'python.analysis.typeCheckingMode': 'basic' };
+
+        expect(resolveSettingsSection(settings, 'python.analysis')).toEqual({ typeCheckingMode: 'basic' });
+        expect(resolveSettingsSection(settings, 'python.linting')).toBeNull();
+        expect(resolveSettingsSection(settings)).toEqual({ python: { analysis: { typeCheckingMode: 'basic' } } });
+    });
+
+    it('merges user settings over computed options', () => {
+        const result = mergeServerSettings(
+            { typescript: { tsdk: '/repo/node_modules/typescript/lib' }, vue: { hybridMode: false } },
+            { vue: { hybridMode: true }, typescript: { preferences: { quoteStyle: 'single' } } }
+        );
+
+        expect(result).toEqual({
+            typescript: { tsdk: '/repo/node_modules/typescript/lib', preferences: { quoteStyle: 'single' } },
+            vue: { hybridMode: true },
+        });
+    });
+});
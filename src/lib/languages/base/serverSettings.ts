@@ -0,0 +1,65 @@
+/**
+ * Per-language server settings, as configured in Fluxel settings.
+ * Keys may be nested objects (`{ "csharp": { ... } }`) or dotted paths
+ * (`{ "python.analysis.typeCheckingMode": "strict" }`).
+ */
+export type ServerSettings = Record<string, unknown>;
+
+function isObject(value: unknown): value is Record<string, unknown> {
+    return typeof value === 'object' && value !== null && !Array.isArray(value);
+}
+
+/**
+ * Expand dotted keys into nested objects, which is the shape servers expect in
+ * `initializationOptions` and `workspace/didChangeConfiguration`.
+ */
+export function expandServerSettings(settings: ServerSettings): ServerSettings {
+    const expanded: ServerSettings = {};
+    for (const [key, value] of Object.entries(settings)) {
+        const parts = key.split('.');
+        let target = expanded;
+        for (const part of parts.slice(0, -1)) {
+            if (!isObject(target[part])) {
+                target[part] = {};
+            }
+            target = target[part] as ServerSettings;
+        }
+        const last = parts[parts.length - 1];
+        target[last] = isObject(value)
+            ? { ...(isObject(target[last]) ? target[last] as ServerSettings : {}), ...expandServerSettings(value) }
+            : value;
+    }
+    return expanded;
+}
+
+/**
+ * Deep-merge `overrides` into `defaults`; nested objects merge, other values
+ * replace. Used to layer user settings over options Fluxel computes.
+ */
+export function mergeServerSettings(defaults: ServerSettings, overrides: ServerSettings): ServerSettings {
+    const merged: ServerSettings = { ...defaults };
+    for (const [key, value] of Object.entries(overrides)) {
+        merged[key] = isObject(value) && isObject(merged[key])
+            ? mergeServerSettings(merged[key] as ServerSettings, value)
+            : value;
+    }
+    return merged;
+}
+
+/**
+ * Value of a `workspace/configuration` section (e.g. `python.analysis`), or
+ * `null` when the settings don't define it. No section returns everything.
+ */
+export function resolveSettingsSection(settings: ServerSettings, section?: string): unknown {
+    let value: unknown = expandServerSettings(settings);
+    if (!section) {
+        return value;
+    }
+    for (const part of section.split('.')) {
+        if (!isObject(value) || !(part in value)) {
+            return null;
+        }
+        value = value[part];
+    }
+    return value;
+}
@@ -41,6 +41,10 @@ export interface LSPClientConfig {
     startCommand: string;
     stopCommand: string;
     sendMessageCommand?: string;
+    /** Event carrying server messages; servers sharing the default must not run together */
+    eventName?: string;
+    /** Extra arguments for every stop and send command (e.g. which server to address) */
+    commandArgs?: Record<string, unknown>;
 }
 
 /**
@@ -23,6 +23,12 @@ export interface BuildDiagnostic {
     code: string;
     /** Human-readable message */
     message: string;
+    /** Stable id derived from location, code, and message; matches between builds */
+    id: string;
+    /** Projects that reported the diagnostic */
+    projects: string[];
+    /** Target frameworks it was reported for, when the build is multi-targeted */
+    target_frameworks: string[];
 }
 
 /**
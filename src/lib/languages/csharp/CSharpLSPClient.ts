@@ -2,8 +2,9 @@ import { readDir } from '@tauri-apps/plugin-fs';
 import { BaseLSPClient } from '../base/BaseLSPClient';
 import type { LSPClientConfig } from '../base/types';
 import { fsPathToLspUri } from '../base/fileUris';
+import { expandServerSettings, resolveSettingsSection } from '../base/serverSettings';
 import { FrontendProfiler } from '@/lib/services';
-import { resolveWorkspaceBuildConfiguration } from './workspaceConfiguration';
+import { resolveWorkspaceBuildConfiguration, resolveWorkspaceSolution } from './workspaceConfiguration';
 
 /**
  * C#-specific LSP client
@@ -11,6 +12,7 @@ import { resolveWorkspaceBuildConfiguration } from './workspaceConfiguration';
  */
 export class CSharpLSPClient extends BaseLSPClient {
     private activeBuildConfiguration: string | null = null;
+    private activeSolution: string | null = null;
 
     constructor() {
         const config: LSPClientConfig = {
@@ -34,6 +36,7 @@ export class CSharpLSPClient extends BaseLSPClient {
 
             try {
                 this.activeBuildConfiguration = await resolveWorkspaceBuildConfiguration(workspaceRoot);
+                this.activeSolution = await resolveWorkspaceSolution(workspaceRoot);
                 console.log('[CSharpLSP] This may take a moment if csharp-ls needs to be installed...');
                 await super.start(workspaceRoot);
             } catch (error) {
@@ -54,6 +57,7 @@ export class CSharpLSPClient extends BaseLSPClient {
         return {
             workspace_root: workspaceRoot,
             configuration: this.activeBuildConfiguration,
+            solution: this.activeSolution,
         };
     }
 
@@ -72,6 +76,7 @@ export class CSharpLSPClient extends BaseLSPClient {
             rootPath: normalizedRoot,
             rootUri,
             capabilities: this.getClientCapabilities(),
+            initializationOptions: expandServerSettings(this.getServerSettings()),
             workspaceFolders: [
                 {
                     uri: rootUri,
@@ -135,6 +140,7 @@ export class CSharpLSPClient extends BaseLSPClient {
                     },
                 },
                 codeLens: {},
+                inlayHint: {},
                 formatting: {},
                 rangeFormatting: {},
                 rename: {
@@ -161,7 +167,8 @@ export class CSharpLSPClient extends BaseLSPClient {
                     ],
                     formats: ['relative'],
                     requests: {
-                        full: true,
+                        // Deltas are resolved by the backend (languages/semantic_tokens.rs)
+                        full: { delta: true },
                         range: false,
                     },
                 },
@@ -171,6 +178,11 @@ export class CSharpLSPClient extends BaseLSPClient {
                 didChangeConfiguration: {
                     dynamicRegistration: true,
                 },
+                // Changes are relayed by the backend (languages/watched_files.rs)
+                didChangeWatchedFiles: {
+                    dynamicRegistration: true,
+                    relativePatternSupport: true,
+                },
                 symbol: {
                     symbolKind: {
                         valueSet: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26],
@@ -190,15 +202,17 @@ export class CSharpLSPClient extends BaseLSPClient {
         // C#-specific handler for workspace configuration
         this.onRequest('workspace/configuration', async (params: any) => {
             return FrontendProfiler.profileAsync('csharp_workspace_config', 'lsp_request', async () => {
-                const solution = await findSolutionOrProjectFile(this.getWorkspaceRoot());
+                const solution = this.activeSolution ?? await findSolutionOrProjectFile(this.getWorkspaceRoot());
                 const items = Array.isArray(params?.items) && params.items.length > 0 ? params.items : [{}];
-                return items.map(() => ({
+                return items.map((item: any) => ({
                     // Be liberal in what we return: different clients/servers use different casing/keys.
                     solution,
                     solutionPath: solution,
                     SolutionPath: solution,
                     applyFormattingOptions: false,
                     ApplyFormattingOptions: false,
+                    // User-configured server settings for the requested section take precedence
+                    ...asObject(resolveSettingsSection(this.getServerSettings(), item?.section)),
                 }));
             });
         });
@@ -313,6 +327,12 @@ async function findSolutionOrProjectFile(workspaceRoot?: string): Promise<string
     });
 }
 
+function asObject(value: unknown): Record<string, unknown> {
+    return typeof value === 'object' && value !== null && !Array.isArray(value)
+        ? value as Record<string, unknown>
+        : {};
+}
+
 function normalizePath(path: string): string {
     return path.replace(/\\/g, '/');
 }
@@ -1,6 +1,6 @@
 import { describe, expect, it } from 'vitest';
 
-import { choosePreferredBuildConfiguration } from './workspaceConfiguration';
+import { choosePreferredBuildConfiguration, choosePreferredSolution } from './workspaceConfiguration';
 
 describe('C# workspace configuration selection', () => {
     it('prefers a saved configuration when it exists', () => {
@@ -30,3 +30,22 @@ describe('C# workspace configuration selection', () => {
         expect(result).toBe('CrossCompat');
     });
 });
+
+describe('C# solution selection', () => {
+    const solutions = ['C:/repo/App.sln', 'C:/repo/samples/Samples.sln', 'C:/repo/tools/Tools.sln'];
+
+    it('keeps a saved solution that still exists', () => {
+        expect(choosePreferredSolution(solutions, 'C:/repo/tools/Tools.sln')).toBe('C:/repo/tools/Tools.sln');
+    });
+
+    it('falls back to the shallowest solution when the saved one is gone', () => {
+        expect(choosePreferredSolution(
+            ['C:/repo/samples/Samples.sln', 'C:/repo/App.sln'],
+            'C:/repo/Old.sln'
+        )).toBe('C:/repo/App.sln');
+    });
+
+    it('returns null without solutions', () => {
+        expect(choosePreferredSolution([], 'C:/repo/App.sln')).toBeNull();
+    });
+});
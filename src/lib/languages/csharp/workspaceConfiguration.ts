@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import type { BuildConfiguration } from './BuildManager';
 import { getProjectConfigurations } from './BuildManager';
 import { useProjectSettingsStore } from '@/stores/project/useProjectSettingsStore';
@@ -43,3 +44,48 @@ export async function resolveWorkspaceBuildConfiguration(workspaceRoot: string):
         return savedConfiguration;
     }
 }
+
+export function choosePreferredSolution(
+    solutions: string[],
+    savedSolution: string | null
+): string | null {
+    if (savedSolution && solutions.includes(savedSolution)) {
+        return savedSolution;
+    }
+
+    // Prefer the shallowest solution, which is usually the one covering the whole repository
+    const depth = (path: string) => path.split('/').length;
+    return [...solutions].sort((a, b) => depth(a) - depth(b))[0] ?? null;
+}
+
+/**
+ * Solutions in the workspace, or its projects when it has none
+ */
+export async function listWorkspaceSolutions(workspaceRoot: string): Promise<string[]> {
+    return invoke<string[]>('list_csharp_solutions', { workspaceRoot });
+}
+
+export async function resolveWorkspaceSolution(workspaceRoot: string): Promise<string | null> {
+    if (!workspaceRoot) {
+        return null;
+    }
+
+    const savedSolution = useProjectSettingsStore.getState()
+        .getSettings(workspaceRoot)
+        .selectedSolution;
+
+    try {
+        const solutions = await listWorkspaceSolutions(workspaceRoot);
+        const preferredSolution = choosePreferredSolution(solutions, savedSolution);
+
+        if (preferredSolution !== savedSolution) {
+            useProjectSettingsStore.getState().setSettings(workspaceRoot, {
+                selectedSolution: preferredSolution,
+            });
+        }
+
+        return preferredSolution;
+    } catch {
+        return savedSolution;
+    }
+}
@@ -0,0 +1,115 @@
+import { invoke } from '@tauri-apps/api/core';
+import { BaseLSPClient } from '../base/BaseLSPClient';
+import { fsPathToLspUri } from '../base/fileUris';
+import { expandServerSettings, mergeServerSettings } from '../base/serverSettings';
+
+export type FrameworkLanguage = 'vue' | 'svelte' | 'astro';
+
+export const FRAMEWORK_LANGUAGES: FrameworkLanguage[] = ['vue', 'svelte', 'astro'];
+
+/**
+ * Language server resolved by the backend for a framework
+ */
+export interface FrameworkServerInfo {
+    language: FrameworkLanguage;
+    command: string;
+    source: 'project' | 'global';
+    /** `lib` directory of the TypeScript the server loads */
+    typescriptSdk: string | null;
+    initializationOptions: Record<string, unknown>;
+}
+
+/**
+ * LSP client for the Vue, Svelte, and Astro language servers.
+ * Each framework runs its own server process and message channel.
+ */
+export class FrameworkLSPClient extends BaseLSPClient {
+    private serverInfo: FrameworkServerInfo | null = null;
+
+    constructor(private readonly language: FrameworkLanguage) {
+        super({
+            languageId: language,
+            startCommand: 'start_framework_ls',
+            stopCommand: 'stop_framework_ls',
+            sendMessageCommand: 'send_framework_lsp_message',
+            eventName: `lsp-message:${language}`,
+            commandArgs: { language },
+        });
+    }
+
+    async start(workspaceRoot?: string): Promise<void> {
+        if (!workspaceRoot) {
+            console.warn(`[FrameworkLSP:${this.language}] Refusing to start without workspace root`);
+            return;
+        }
+
+        // Resolved up front so initialize can carry the TypeScript setup
+        this.serverInfo = await invoke<FrameworkServerInfo>('resolve_framework_ls', {
+            language: this.language,
+            workspaceRoot,
+        });
+        console.log(`[FrameworkLSP:${this.language}] Using ${this.serverInfo.source} server: ${this.serverInfo.command}`);
+        await super.start(workspaceRoot);
+    }
+
+    /**
+     * Server resolved for the last start, if any
+     */
+    getServerInfo(): FrameworkServerInfo | null {
+        return this.serverInfo;
+    }
+
+    protected buildStartCommandArgs(workspaceRoot?: string): Record<string, unknown> {
+        return { language: this.language, workspaceRoot };
+    }
+
+    protected buildInitializeParams(workspaceRoot: string): any {
+        const rootUri = fsPathToLspUri(workspaceRoot);
+        const workspaceName = workspaceRoot.replace(/\\/g, '/').split('/').pop() || 'workspace';
+
+        return {
+            ...super.buildInitializeParams(workspaceRoot),
+            rootUri,
+            initializationOptions: mergeServerSettings(
+                this.serverInfo?.initializationOptions ?? {},
+                expandServerSettings(this.getServerSettings())
+            ),
+            workspaceFolders: [{ uri: rootUri, name: workspaceName }],
+        };
+    }
+
+    protected getClientCapabilities(): any {
+        const capabilities = super.getClientCapabilities();
+        return {
+            ...capabilities,
+            textDocument: {
+                ...capabilities.textDocument,
+                synchronization: { didSave: true },
+                publishDiagnostics: { relatedInformation: true },
+            },
+            workspace: {
+                ...capabilities.workspace,
+                configuration: true,
+                didChangeWatchedFiles: { dynamicRegistration: true, relativePatternSupport: true },
+            },
+        };
+    }
+}
+
+const clients = new Map<FrameworkLanguage, FrameworkLSPClient>();
+
+/**
+ * Get or create the LSP client for a framework
+ */
+export function getFrameworkLSPClient(language: FrameworkLanguage): FrameworkLSPClient {
+    let client = clients.get(language);
+    if (!client) {
+        client = new FrameworkLSPClient(language);
+        clients.set(language, client);
+    }
+    return client;
+}
+
+export function isFrameworkLanguage(languageId?: string | null): languageId is FrameworkLanguage {
+    return FRAMEWORK_LANGUAGES.includes(languageId as FrameworkLanguage);
+}
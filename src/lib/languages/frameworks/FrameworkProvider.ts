@@ -0,0 +1,226 @@
+import type * as Monaco from 'monaco-editor';
+import { BaseLanguageProvider } from '../base/BaseLanguageProvider';
+import type { MonacoInstance } from '../base/types';
+import { fileUriToFsPath, lspUriToMonacoUri, monacoUriToLspUri } from '../base/fileUris';
+import { getFrameworkLSPClient, type FrameworkLanguage, type FrameworkLSPClient } from './FrameworkLSPClient';
+import { FrontendProfiler } from '@/lib/services';
+import { useDiagnosticsStore, type Diagnostic } from '@/stores/diagnostics';
+
+const extensions: Record<FrameworkLanguage, string> = {
+    vue: '.vue',
+    svelte: '.svelte',
+    astro: '.astro',
+};
+
+const severities: Record<number, Diagnostic['severity']> = {
+    1: 'error',
+    2: 'warning',
+    3: 'info',
+    4: 'hint',
+};
+
+/**
+ * Register the framework language ids with Monaco, which has none built in.
+ * Must run before models for these files are created, or they fall back to
+ * plaintext.
+ */
+export function registerFrameworkLanguages(monaco: MonacoInstance): void {
+    const registered = new Set(monaco.languages.getLanguages().map((language) => language.id));
+    for (const [id, extension] of Object.entries(extensions)) {
+        if (!registered.has(id)) {
+            monaco.languages.register({ id, extensions: [extension] });
+        }
+    }
+}
+
+/**
+ * Language provider for framework single-file components (Vue, Svelte, Astro).
+ * Keeps open models in sync with the framework's language server and shows
+ * its diagnostics as markers and in the Problems panel.
+ */
+export class FrameworkProvider extends BaseLanguageProvider {
+    private lspClient: FrameworkLSPClient;
+    private startPromise: Promise<void> | null = null;
+    private readonly markerOwner: string;
+    /** Files this provider put diagnostics in the store for */
+    private diagnosticPaths = new Set<string>();
+
+    constructor(private readonly language: FrameworkLanguage, monaco: MonacoInstance) {
+        super(language, monaco);
+        this.lspClient = getFrameworkLSPClient(language);
+        this.markerOwner = `${language}-ls`;
+        this.lspClient.onNotification('textDocument/publishDiagnostics', (params: any) => {
+            if (this.started) {
+                this.showDiagnostics(params);
+            }
+        });
+    }
+
+    async start(workspaceRoot?: string): Promise<void> {
+        if (this.startPromise) {
+            return this.startPromise;
+        }
+
+        this.startPromise = FrontendProfiler.profileAsync('framework_provider_start', 'frontend_render', async () => {
+            if (this.started || !workspaceRoot) {
+                return;
+            }
+
+            try {
+                console.log(`[${this.language}] Starting provider...`);
+                registerFrameworkLanguages(this.monaco);
+
+                await this.lspClient.start(workspaceRoot);
+                await this.lspClient.initialize(workspaceRoot);
+
+                this.syncDocuments();
+                this.addDisposable({ dispose: () => this.clearDiagnostics() });
+
+                this.started = true;
+                console.log(`[${this.language}] Provider started`);
+            } catch (error) {
+                console.error(`[${this.language}] Failed to start:`, error);
+                throw error;
+            }
+        }, { language: this.language });
+
+        try {
+            await this.startPromise;
+        } finally {
+            this.startPromise = null;
+        }
+    }
+
+    async stop(): Promise<void> {
+        if (!this.started) {
+            return;
+        }
+
+        this.dispose();
+        await this.lspClient.stop();
+        console.log(`[${this.language}] Provider stopped`);
+    }
+
+    getLSPClient(): FrameworkLSPClient {
+        return this.lspClient;
+    }
+
+    private lspUri(model: Monaco.editor.ITextModel): string {
+        const uri = model.uri.toString();
+        return uri.startsWith('file://') ? monacoUriToLspUri(uri) : uri;
+    }
+
+    /**
+     * Open every model of this language in the server and forward its edits
+     */
+    private syncDocuments(): void {
+        const tracked = new Map<string, Monaco.IDisposable>();
+
+        const open = (model: Monaco.editor.ITextModel) => {
+            const uri = this.lspUri(model);
+            if (model.getLanguageId() !== this.language || tracked.has(uri)) {
+                return;
+            }
+
+            void this.lspClient.sendNotification('textDocument/didOpen', {
+                textDocument: {
+                    uri,
+                    languageId: this.language,
+                    version: model.getVersionId(),
+                    text: model.getValue(),
+                },
+            }).catch((error) => console.error(`[${this.language}] Failed to send didOpen:`, error));
+
+            tracked.set(uri, model.onDidChangeContent(() => {
+                void this.lspClient.sendNotification('textDocument/didChange', {
+                    textDocument: { uri, version: model.getVersionId() },
+                    contentChanges: [{ text: model.getValue() }],
+                }).catch((error) => console.error(`[${this.language}] Failed to send didChange:`, error));
+            }));
+        };
+
+        const close = (model: Monaco.editor.ITextModel) => {
+            const uri = this.lspUri(model);
+            const listener = tracked.get(uri);
+            if (!listener) {
+                return;
+            }
+
+            listener.dispose();
+            tracked.delete(uri);
+            if (this.lspClient.getIsStarted()) {
+                void this.lspClient.sendNotification('textDocument/didClose', {
+                    textDocument: { uri },
+                }).catch((error) => console.error(`[${this.language}] Failed to send didClose:`, error));
+            }
+        };
+
+        this.monaco.editor.getModels().forEach(open);
+        this.addDisposable(this.monaco.editor.onDidCreateModel(open));
+        this.addDisposable(this.monaco.editor.onWillDisposeModel(close));
+        this.addDisposable(this.monaco.editor.onDidChangeModelLanguage(({ model }) => {
+            close(model);
+            open(model);
+        }));
+        this.addDisposable({
+            dispose: () => {
+                tracked.forEach((listener) => listener.dispose());
+                tracked.clear();
+            },
+        });
+    }
+
+    private showDiagnostics(params: any): void {
+        const uri: string = params.uri;
+        const diagnostics: any[] = params.diagnostics || [];
+        const model = this.monaco.editor.getModel(this.monaco.Uri.parse(lspUriToMonacoUri(uri)));
+        if (model) {
+            this.monaco.editor.setModelMarkers(model, this.markerOwner, diagnostics.map((diag) => ({
+                severity: this.markerSeverity(diag.severity),
+                startLineNumber: diag.range.start.line + 1,
+                startColumn: diag.range.start.character + 1,
+                endLineNumber: diag.range.end.line + 1,
+                endColumn: diag.range.end.character + 1,
+                message: diag.message,
+                source: diag.source || this.markerOwner,
+            })));
+        }
+
+        const filePath = fileUriToFsPath(uri);
+        this.diagnosticPaths.add(filePath);
+        useDiagnosticsStore.getState().setDiagnostics(filePath, diagnostics.map((diag, index) => ({
+            id: `${this.markerOwner}:${filePath}:${diag.range.start.line}:${diag.range.start.character}:${index}`,
+            uri,
+            filePath,
+            fileName: filePath.split(/[\\/]/).pop() || filePath,
+            severity: severities[diag.severity] ?? 'info',
+            message: diag.message,
+            code: diag.code,
+            source: diag.source || this.markerOwner,
+            range: {
+                startLine: diag.range.start.line + 1,
+                startColumn: diag.range.start.character + 1,
+                endLine: diag.range.end.line + 1,
+                endColumn: diag.range.end.character + 1,
+            },
+        })));
+    }
+
+    private clearDiagnostics(): void {
+        this.monaco.editor.getModels().forEach((model) => {
+            this.monaco.editor.setModelMarkers(model, this.markerOwner, []);
+        });
+        const store = useDiagnosticsStore.getState();
+        this.diagnosticPaths.forEach((filePath) => store.clearDiagnostics(filePath));
+        this.diagnosticPaths.clear();
+    }
+
+    private markerSeverity(severity: number): Monaco.MarkerSeverity {
+        switch (severity) {
+            case 1: return this.monaco.MarkerSeverity.Error;
+            case 2: return this.monaco.MarkerSeverity.Warning;
+            case 4: return this.monaco.MarkerSeverity.Hint;
+            default: return this.monaco.MarkerSeverity.Info;
+        }
+    }
+}
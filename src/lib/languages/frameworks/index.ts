@@ -0,0 +1,6 @@
+/**
+ * Vue, Svelte, and Astro language support
+ */
+
+export * from './FrameworkLSPClient';
+export * from './FrameworkProvider';
@@ -8,6 +8,8 @@ export * from './base';
 // Language providers
 export * from './typescript';
 export * from './csharp';
+export * from './frameworks';
+export * from './schemas';
 
 // Registry
 export * from './registry';
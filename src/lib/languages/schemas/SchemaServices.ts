@@ -0,0 +1,227 @@
+import { invoke } from '@tauri-apps/api/core';
+import type * as Monaco from 'monaco-editor';
+import type { MonacoInstance } from '../base/types';
+import { fileUriToFsPath, fsPathToLspUri } from '../base/fileUris';
+import { useDiagnosticsStore, type Diagnostic } from '@/stores/diagnostics';
+
+/** Languages validated and completed against schemas by the backend */
+export const SCHEMA_LANGUAGES = ['json', 'yaml', 'toml'];
+
+const MARKER_OWNER = 'schema';
+const VALIDATE_DELAY_MS = 400;
+
+interface SchemaRange {
+    startLine: number;
+    startColumn: number;
+    endLine: number;
+    endColumn: number;
+}
+
+/** Diagnostic as returned by `schema_validate` */
+interface SchemaDiagnostic {
+    path: string;
+    range: SchemaRange;
+    severity: Diagnostic['severity'];
+    message: string;
+    code: string | null;
+    source: string;
+}
+
+interface SchemaCompletion {
+    label: string;
+    detail: string | null;
+    documentation: string | null;
+    insertText: string;
+    required: boolean;
+    deprecated: boolean;
+}
+
+interface SchemaCompletionList {
+    items: SchemaCompletion[];
+    range: SchemaRange;
+}
+
+let registration: Monaco.IDisposable | null = null;
+
+function modelPath(model: Monaco.editor.ITextModel): string | null {
+    const uri = model.uri.toString();
+    return uri.startsWith('file://') ? fileUriToFsPath(uri) : null;
+}
+
+function markerSeverity(monaco: MonacoInstance, severity: Diagnostic['severity']): Monaco.MarkerSeverity {
+    switch (severity) {
+        case 'error': return monaco.MarkerSeverity.Error;
+        case 'warning': return monaco.MarkerSeverity.Warning;
+        case 'hint': return monaco.MarkerSeverity.Hint;
+        default: return monaco.MarkerSeverity.Info;
+    }
+}
+
+/**
+ * Register schema validation and key completion for JSON, YAML, and TOML.
+ * Files with a known schema (package.json, tsconfig, tauri.conf.json, GitHub
+ * workflows, ...) or a `$schema` reference are checked as they are edited;
+ * every file gets syntax errors. Registration happens once per app.
+ */
+export function registerSchemaServices(monaco: MonacoInstance): Monaco.IDisposable {
+    if (registration) {
+        return registration;
+    }
+
+    // Monaco has no TOML language built in
+    if (!monaco.languages.getLanguages().some((language) => language.id === 'toml')) {
+        monaco.languages.register({ id: 'toml', extensions: ['.toml'] });
+    }
+
+    const disposables: Monaco.IDisposable[] = [];
+    const tracked = new Map<string, { listener: Monaco.IDisposable; timer?: ReturnType<typeof setTimeout> }>();
+    const diagnosticPaths = new Set<string>();
+
+    const showDiagnostics = (model: Monaco.editor.ITextModel, filePath: string, diagnostics: SchemaDiagnostic[]) => {
+        monaco.editor.setModelMarkers(model, MARKER_OWNER, diagnostics.map((diag) => ({
+            severity: markerSeverity(monaco, diag.severity),
+            startLineNumber: diag.range.startLine,
+            startColumn: diag.range.startColumn,
+            endLineNumber: diag.range.endLine,
+            endColumn: diag.range.endColumn,
+            message: diag.message,
+            source: diag.source,
+        })));
+
+        const store = useDiagnosticsStore.getState();
+        if (diagnostics.length === 0) {
+            if (diagnosticPaths.delete(filePath)) {
+                store.clearDiagnostics(filePath);
+            }
+            return;
+        }
+        diagnosticPaths.add(filePath);
+        store.setDiagnostics(filePath, diagnostics.map((diag, index) => ({
+            id: `${MARKER_OWNER}:${filePath}:${diag.range.startLine}:${diag.range.startColumn}:${index}`,
+            uri: fsPathToLspUri(filePath),
+            filePath,
+            fileName: filePath.split(/[\\/]/).pop() || filePath,
+            severity: diag.severity,
+            message: diag.message,
+            code: diag.code ?? undefined,
+            source: diag.source,
+            range: diag.range,
+        })));
+    };
+
+    const validate = async (model: Monaco.editor.ITextModel, filePath: string) => {
+        const version = model.getVersionId();
+        try {
+            const diagnostics = await invoke<SchemaDiagnostic[]>('schema_validate', {
+                path: filePath,
+                content: model.getValue(),
+            });
+            // Results for an outdated version would flicker stale markers
+            if (!model.isDisposed() && model.getVersionId() === version) {
+                showDiagnostics(model, filePath, diagnostics);
+            }
+        } catch (error) {
+            console.error('[Schemas] Validation failed:', error);
+        }
+    };
+
+    const track = (model: Monaco.editor.ITextModel) => {
+        const filePath = modelPath(model);
+        const key = model.uri.toString();
+        if (!filePath || tracked.has(key) || !SCHEMA_LANGUAGES.includes(model.getLanguageId())) {
+            return;
+        }
+
+        const entry: { listener: Monaco.IDisposable; timer?: ReturnType<typeof setTimeout> } = {
+            listener: model.onDidChangeContent(() => {
+                clearTimeout(entry.timer);
+                entry.timer = setTimeout(() => void validate(model, filePath), VALIDATE_DELAY_MS);
+            }),
+        };
+        tracked.set(key, entry);
+        void validate(model, filePath);
+    };
+
+    const untrack = (model: Monaco.editor.ITextModel) => {
+        const key = model.uri.toString();
+        const entry = tracked.get(key);
+        if (!entry) {
+            return;
+        }
+
+        clearTimeout(entry.timer);
+        entry.listener.dispose();
+        tracked.delete(key);
+        monaco.editor.setModelMarkers(model, MARKER_OWNER, []);
+        const filePath = modelPath(model);
+        if (filePath && diagnosticPaths.delete(filePath)) {
+            useDiagnosticsStore.getState().clearDiagnostics(filePath);
+        }
+    };
+
+    monaco.editor.getModels().forEach(track);
+    disposables.push(monaco.editor.onDidCreateModel(track));
+    disposables.push(monaco.editor.onWillDisposeModel(untrack));
+    disposables.push(monaco.editor.onDidChangeModelLanguage(({ model }) => {
+        untrack(model);
+        track(model);
+    }));
+
+    for (const languageId of SCHEMA_LANGUAGES) {
+        disposables.push(monaco.languages.registerCompletionItemProvider(languageId, {
+            triggerCharacters: languageId === 'json' ? ['"'] : [],
+            provideCompletionItems: async (model, position) => {
+                const filePath = modelPath(model);
+                if (!filePath) {
+                    return { suggestions: [] };
+                }
+
+                try {
+                    const result = await invoke<SchemaCompletionList | null>('schema_complete', {
+                        path: filePath,
+                        content: model.getValue(),
+                        lineNumber: position.lineNumber,
+                        column: position.column,
+                    });
+                    if (!result) {
+                        return { suggestions: [] };
+                    }
+
+                    const range = {
+                        startLineNumber: result.range.startLine,
+                        startColumn: result.range.startColumn,
+                        endLineNumber: result.range.endLine,
+                        endColumn: result.range.endColumn,
+                    };
+                    return {
+                        suggestions: result.items.map((item) => ({
+                            label: item.label,
+                            kind: monaco.languages.CompletionItemKind.Property,
+                            detail: item.detail ?? undefined,
+                            documentation: item.documentation ? { value: item.documentation } : undefined,
+                            insertText: item.insertText,
+                            insertTextRules: monaco.languages.CompletionItemInsertTextRule.InsertAsSnippet,
+                            // Required keys first
+                            sortText: `${item.required ? '0' : '1'}${item.label}`,
+                            filterText: languageId === 'json' ? `"${item.label}"` : item.label,
+                            tags: item.deprecated ? [monaco.languages.CompletionItemTag.Deprecated] : undefined,
+                            range,
+                        })),
+                    };
+                } catch (error) {
+                    console.error('[Schemas] Completion failed:', error);
+                    return { suggestions: [] };
+                }
+            },
+        }));
+    }
+
+    registration = {
+        dispose: () => {
+            disposables.forEach((disposable) => disposable.dispose());
+            monaco.editor.getModels().forEach(untrack);
+            registration = null;
+        },
+    };
+    return registration;
+}
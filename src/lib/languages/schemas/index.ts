@@ -0,0 +1,5 @@
+/**
+ * Schema-aware JSON, YAML, and TOML support
+ */
+
+export * from './SchemaServices';
@@ -7,8 +7,7 @@
 
 import { readTextFile, readDir } from '@tauri-apps/plugin-fs';
 import type * as Monaco from 'monaco-editor';
-import { discoverTypingsForPackages } from '../../services';
-import { batchReadFiles } from '../../services';
+import { bundleTypingsForPackages } from '../../services';
 import { useTypeLoadingStore } from '@/stores';
 
 // Type alias for Monaco instance
@@ -514,52 +513,42 @@ async function loadResolverTypings(
     let totalFiles = 0;
 
     try {
-        const responses = await discoverTypingsForPackages(packageNames, projectRoot);
-
-        // Collect all files to read in one batch
-        const allFilesToRead: string[] = [];
-        const fileToPackage = new Map<string, string>();
+        // Each bundle follows references from the package's entry .d.ts and
+        // carries the file contents, so no separate read is needed
+        const responses = await bundleTypingsForPackages(packageNames, projectRoot, {
+            include_content: true,
+        });
 
         for (const res of responses) {
-            if (res.files.length > 0) {
-                loadedPackages++;
-                for (const file of res.files) {
-                    const normalizedFile = normalizePath(file);
-                    allFilesToRead.push(normalizedFile);
-                    fileToPackage.set(normalizedFile, res.package_name);
-                }
+            if (res.files.length === 0) continue;
+            loadedPackages++;
+            if (res.truncated) {
+                console.warn(`[TypeLoader] Typings of ${res.package_name} truncated at ${res.files.length} files`);
             }
-        }
-
-        // Batch read all files in one IPC call
-        if (allFilesToRead.length > 0) {
-            const fileContents = await batchReadFiles(allFilesToRead);
 
-            // Register all types using the queue-based system
-            for (const [filePath, content] of Object.entries(fileContents)) {
-                const virtualPath = toFileUri(filePath);
+            for (const file of res.files) {
+                if (file.content === null) continue;
+                const virtualPath = toFileUri(normalizePath(file.path));
 
                 // Skip if already loaded or use queue system which handles this
                 if (loadedTypeUris.has(virtualPath)) continue;
 
                 // Use queue-based system for controlled memory usage
-                const queued = queueExtraLib(content as string, virtualPath, monaco);
-                if (queued) {
+                if (queueExtraLib(file.content, virtualPath, monaco)) {
                     totalFiles++;
-
-                    // Collect references from first few files
-                    if (collectReferences && totalFiles <= 50) {
-                        for (const root of extractBarePackageRoots(content as string)) {
-                            referencedPackages.add(root);
-                        }
-                    }
                 }
             }
 
-            // Wait for the batch to be processed
-            await waitForPendingLibs(monaco);
+            if (collectReferences) {
+                for (const pkg of res.packages) {
+                    referencedPackages.add(pkg);
+                }
+            }
         }
 
+        // Wait for the batch to be processed
+        await waitForPendingLibs(monaco);
+
         // Ensure TS can read package.json for modern packages with exports/types
         for (const res of responses) {
             await addPackageJsonForPackage(projectRoot, res.package_name, monaco);
@@ -970,31 +959,6 @@ async function addPackageJsonForPackage(
     }
 }
 
-/**
- * Extract bare package roots referenced in a typings file.
- * Example: "@tanstack/query-core/foo" -> "@tanstack/query-core"
- *          "react/jsx-runtime" -> "react"
- */
-function extractBarePackageRoots(content: string): string[] {
-    const roots: string[] = [];
-    const regex = /(?:from|import)\s*["']([^"']+)["']|require\(\s*["']([^"']+)["']\s*\)/g;
-    let match: RegExpExecArray | null;
-    while ((match = regex.exec(content))) {
-        const spec = match[1] || match[2];
-        if (!spec) continue;
-        if (spec.startsWith('.') || spec.startsWith('/') || spec.startsWith('node:')) continue;
-        let root = spec;
-        if (root.startsWith('@')) {
-            const parts = root.split('/');
-            if (parts.length >= 2) root = `${parts[0]}/${parts[1]}`;
-        } else {
-            root = root.split('/')[0];
-        }
-        roots.push(root);
-    }
-    return roots;
-}
-
 /**
  * Main function to load all type definitions for a project
  */
@@ -15,5 +15,6 @@ export {
     type MinimaxChatRequest,
     type MinimaxToolDefinition,
     type MinimaxMessage,
+    type MinimaxContentPart,
 } from './minimaxClient';
 
@@ -48,9 +48,14 @@ export interface MinimaxToolCall {
     id?: string;
 }
 
+export type MinimaxContentPart =
+    | { type: 'text'; text: string }
+    | { type: 'image_url'; image_url: { url: string } };
+
 export interface MinimaxMessage {
     role: string;
-    content?: string;
+    /** Plain text, or text and image parts for vision models */
+    content?: string | MinimaxContentPart[];
     tool_calls?: MinimaxToolCall[];
     tool_call_id?: string;
     name?: string;
@@ -62,6 +67,17 @@ export interface MinimaxChatRequest {
     maxTokens?: number;
     temperature?: number;
     tools?: MinimaxToolDefinition[];
+    /** JSON Schema the reply must follow */
+    responseSchema?: Record<string, unknown>;
+    /**
+     * Optional sampling parameters. The backend rejects any the model does not
+     * support instead of sending them.
+     */
+    stop?: string[];
+    topP?: number;
+    seed?: number;
+    frequencyPenalty?: number;
+    presencePenalty?: number;
 }
 
 /**
@@ -136,6 +152,12 @@ export async function* streamMinimaxChat(
             max_tokens: request.maxTokens,
             temperature: request.temperature,
             tools: request.tools,
+            response_schema: request.responseSchema,
+            stop: request.stop,
+            top_p: request.topP,
+            seed: request.seed,
+            frequency_penalty: request.frequencyPenalty,
+            presence_penalty: request.presencePenalty,
         };
 
         console.log('[MiniMax] Invoking minimax_chat_stream command...');
@@ -203,6 +225,12 @@ export async function chatMinimax(
         max_tokens: request.maxTokens,
         temperature: request.temperature,
         tools: request.tools,
+        response_schema: request.responseSchema,
+        stop: request.stop,
+        top_p: request.topP,
+        seed: request.seed,
+        frequency_penalty: request.frequencyPenalty,
+        presence_penalty: request.presencePenalty,
     };
 
     const response = await invoke<any>('minimax_chat', {
@@ -16,6 +16,7 @@ describe('ProjectManager - C# Configuration Loading Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
     javascript: {
       root_path: '/test/js-project',
@@ -23,6 +24,7 @@ describe('ProjectManager - C# Configuration Loading Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixedWithCsproj: {
       root_path: '/test/mixed-project',
@@ -30,6 +32,7 @@ describe('ProjectManager - C# Configuration Loading Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixedWithoutCsproj: {
       root_path: '/test/mixed-project-no-cs',
@@ -37,6 +40,7 @@ describe('ProjectManager - C# Configuration Loading Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: true, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     unknown: {
       root_path: '/test/unknown',
@@ -44,6 +48,7 @@ describe('ProjectManager - C# Configuration Loading Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
   };
 
@@ -90,6 +95,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
     javascriptWithTsconfig: {
       root_path: '/test/js-project',
@@ -97,6 +103,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: true, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     javascriptWithJsconfig: {
       root_path: '/test/js-project-no-ts',
@@ -104,6 +111,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: true, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     javascriptNoConfig: {
       root_path: '/test/js-no-config',
@@ -111,6 +119,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: null, project_path: null },
       node: { has_package_json: false, has_tsconfig: false, has_jsconfig: false, package_manager: null },
       build_system_hint: null,
+      wsl: null,
     },
     mixedWithTs: {
       root_path: '/test/mixed-project',
@@ -118,6 +127,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: true, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixedWithJs: {
       root_path: '/test/mixed-project-no-ts',
@@ -125,6 +135,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: true, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
     mixedNoTs: {
       root_path: '/test/mixed-no-ts',
@@ -132,6 +143,7 @@ describe('ProjectManager - TypeScript Workspace Hydration Logic', () => {
       dotnet: { solution_path: '/test.sln', project_path: '/test.csproj' },
       node: { has_package_json: true, has_tsconfig: false, has_jsconfig: false, package_manager: 'bun' },
       build_system_hint: null,
+      wsl: null,
     },
   };
 
@@ -27,14 +27,13 @@ export interface BuildResult {
  * Convert a BuildDiagnostic from the Rust backend to the Diagnostic format used by the store.
  */
 function convertBuildDiagnosticToStoreDiagnostic(
-    diagnostic: BuildDiagnostic,
-    index: number
+    diagnostic: BuildDiagnostic
 ): Diagnostic {
     // Extract file name from path
     const fileName = diagnostic.file_path.split(/[/\\]/).pop() || diagnostic.file_path;
 
     return {
-        id: `build-${diagnostic.code}-${diagnostic.file_path}-${diagnostic.line}-${diagnostic.column}-${index}`,
+        id: `build-${diagnostic.id}`,
         uri: `file://${diagnostic.file_path}`,
         filePath: diagnostic.file_path,
         fileName,
@@ -240,9 +239,7 @@ async function executeDotNetBuild(projectRoot: string): Promise<BuildResult> {
         const result = await buildCSharpProject(projectRoot, resolvedConfiguration);
 
         // Convert build diagnostics to store format and update the diagnostics store
-        const storeDiagnostics = result.diagnostics.map((diag, index) =>
-            convertBuildDiagnosticToStoreDiagnostic(diag, index)
-        );
+        const storeDiagnostics = result.diagnostics.map(convertBuildDiagnosticToStoreDiagnostic);
 
         // Update the diagnostics store with build diagnostics
         const { setBuildDiagnostics } = useDiagnosticsStore.getState();
@@ -23,5 +23,10 @@ export { PerformanceBenchmark } from './profiling';
 export { executeBuild, executeTypeCheck, type BuildOptions, type BuildResult } from './build';
 export { openWorkspace, closeWorkspace, initializeProjectOrchestrator, shouldLoadCSharpConfigurations, shouldHydrateTypeScriptWorkspace } from './workspace';
 export { batchReadFiles, batchDiscoverTypings, countPackageTypeFiles } from './tauri';
-export { resolveNodeModule, discoverPackageTypings, discoverTypingsForPackages, analyzeModuleGraph } from './tauri';
+export { resolveNodeModule, discoverPackageTypings, discoverTypingsForPackages, bundleTypingsForPackages, analyzeModuleGraph } from './tauri';
+export { getPackageInfo, type PackageInfo } from './tauri';
+export { createFile, duplicatePath, copyRelativePath, copyPosixPath } from './tauri';
+export { listFileTemplates, saveFileTemplates, renderFileTemplate } from './tauri';
+export { setDirtyFiles, checkDirtyFiles } from './tauri';
+export { recordLocalHistory, listLocalHistory, readLocalHistoryVersion, restoreLocalHistoryVersion, type HistoryVersion } from './tauri';
 export { ProcessManager, registerProcess, unregisterProcess, killAllProcesses, getTrackedCount, isTracked } from './tauri';
@@ -14,6 +14,9 @@ import type {
     FrontendSpanInput,
     SessionReport,
     ExportFormat,
+    SamplingConfig,
+    SpanFilter,
+    StartupReport,
 } from '@/types/profiling';
 
 /**
@@ -119,6 +122,14 @@ export async function setEnabled(enabled: boolean): Promise<void> {
     await invoke('profiler_set_enabled', { enabled });
 }
 
+/**
+ * Configure span sampling (1-in-N span trees, always keeping slow and error spans).
+ */
+export async function setSampling(config: SamplingConfig): Promise<void> {
+    if (!await checkProfilingAvailable()) return;
+    await invoke('profiler_set_sampling', { config });
+}
+
 /**
  * Get current profiler status.
  */
@@ -128,11 +139,15 @@ export async function getStatus(): Promise<ProfilerStatus | null> {
 }
 
 /**
- * Get recent spans.
+ * Get recent spans, optionally only those of one workspace and/or file.
  */
-export async function getRecentSpans(limit = 100): Promise<SpanSummary[]> {
+export async function getRecentSpans(limit = 100, filter?: SpanFilter): Promise<SpanSummary[]> {
     if (!await checkProfilingAvailable()) return [];
-    return invoke<SpanSummary[]>('profiler_get_recent_spans', { limit });
+    return invoke<SpanSummary[]>('profiler_get_recent_spans', {
+        limit,
+        workspace: filter?.workspace ?? null,
+        file: filter?.file ?? null,
+    });
 }
 
 /**
@@ -167,9 +182,34 @@ export async function startSession(name: string): Promise<string | null> {
 /**
  * End a profiling session and get the report.
  */
-export async function endSession(sessionId: string): Promise<SessionReport | null> {
+export async function endSession(sessionId: string, filter?: SpanFilter): Promise<SessionReport | null> {
+    if (!await checkProfilingAvailable()) return null;
+    return invoke<SessionReport>('profiler_end_session', {
+        sessionId,
+        workspace: filter?.workspace ?? null,
+        file: filter?.file ?? null,
+    });
+}
+
+// =============================================================================
+// Startup
+// =============================================================================
+
+/**
+ * Finalize the startup session once the frontend is ready.
+ * Later calls return the first report.
+ */
+export async function finishStartup(): Promise<StartupReport | null> {
+    if (!await checkProfilingAvailable()) return null;
+    return invoke<StartupReport | null>('profiler_finish_startup');
+}
+
+/**
+ * Get the cold start breakdown, or null before the frontend signaled ready.
+ */
+export async function getStartupReport(): Promise<StartupReport | null> {
     if (!await checkProfilingAvailable()) return null;
-    return invoke<SessionReport>('profiler_end_session', { sessionId });
+    return invoke<StartupReport | null>('profiler_get_startup_report');
 }
 
 // =============================================================================
@@ -221,6 +261,30 @@ export async function exportData(
     }
 }
 
+/**
+ * Render the span tree under a root span as a flamegraph SVG and write it to `path`.
+ */
+export async function exportFlamegraph(rootSpanId: string, path: string): Promise<void> {
+    if (!await checkProfilingAvailable()) return;
+    await invoke('profiler_export_flamegraph', { rootSpanId, path });
+}
+
+/**
+ * Ask where to save a span tree's flamegraph SVG and write it there.
+ * @returns Whether the file was written (false if the dialog was cancelled)
+ */
+export async function downloadFlamegraph(rootSpanId: string, spanName: string): Promise<boolean> {
+    const safeName = spanName.replace(/[^\w.-]+/g, '_');
+    const filePath = await save({
+        defaultPath: `fluxel-flamegraph-${safeName}-${Date.now()}.svg`,
+        filters: [{ name: 'SVG', extensions: ['svg'] }],
+        title: 'Save Flamegraph',
+    });
+    if (!filePath) return false;
+    await exportFlamegraph(rootSpanId, filePath);
+    return true;
+}
+
 // =============================================================================
 // Utility Functions
 // =============================================================================
@@ -295,6 +359,7 @@ export async function downloadExport(
 export const ProfilerService = {
     // Basic commands
     setEnabled,
+    setSampling,
     getStatus,
     getRecentSpans,
     getAttribution,
@@ -304,12 +369,18 @@ export const ProfilerService = {
     startSession,
     endSession,
 
+    // Startup
+    finishStartup,
+    getStartupReport,
+
     // Frontend span recording
     recordFrontendSpan,
 
     // Export
     exportData,
     downloadExport,
+    exportFlamegraph,
+    downloadFlamegraph,
 
     // Utilities
     isAvailable,
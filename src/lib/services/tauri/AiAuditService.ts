@@ -0,0 +1,91 @@
+/**
+ * AI Audit Service
+ *
+ * TypeScript wrapper for the opt-in, per-workspace log of what was sent to
+ * AI providers. Secrets are redacted by the backend before anything is
+ * written, and recording is a no-op until enabled for the workspace.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AiAuditRecord {
+    provider: string;
+    model: string;
+    /** Feature that made the request, e.g. `chat` or `commitMessage` */
+    feature: string;
+    /** Workspace files whose content was included */
+    files?: string[];
+    /** Text sent to the provider */
+    snippets?: string[];
+    /** Estimated from the snippets when omitted */
+    promptTokens?: number;
+    completionTokens?: number;
+}
+
+export interface AiAuditEntry {
+    id: string;
+    /** Request time (unix milliseconds) */
+    timestamp: number;
+    provider: string;
+    model: string;
+    feature: string;
+    files: string[];
+    /** Redacted and truncated snippets */
+    snippets: string[];
+    promptTokens: number;
+    completionTokens: number | null;
+    /** Secrets replaced across all snippets */
+    redactions: number;
+}
+
+export interface AiAuditQuery {
+    provider?: string;
+    feature?: string;
+    /** Only entries at or after this time (unix milliseconds) */
+    since?: number;
+    /** Only entries before this time (unix milliseconds) */
+    until?: number;
+    limit?: number;
+}
+
+/**
+ * Whether AI requests are recorded for a workspace
+ */
+export async function getAiAuditEnabled(workspaceRoot: string): Promise<boolean> {
+    return invoke<boolean>('get_ai_audit_enabled', { workspaceRoot });
+}
+
+/**
+ * Turn recording on or off; existing entries are kept either way
+ */
+export async function setAiAuditEnabled(workspaceRoot: string, enabled: boolean): Promise<void> {
+    return invoke<void>('set_ai_audit_enabled', { workspaceRoot, enabled });
+}
+
+/**
+ * Record a request sent to an AI provider. Returns null when the log is disabled.
+ */
+export async function recordAiAuditEntry(
+    workspaceRoot: string,
+    record: AiAuditRecord
+): Promise<AiAuditEntry | null> {
+    return invoke<AiAuditEntry | null>('record_ai_audit_entry', { workspaceRoot, record });
+}
+
+/**
+ * List recorded requests, most recent first
+ */
+export async function queryAiAuditLog(
+    workspaceRoot: string,
+    filter?: AiAuditQuery
+): Promise<AiAuditEntry[]> {
+    return invoke<AiAuditEntry[]>('query_ai_audit_log', { workspaceRoot, filter: filter ?? null });
+}
+
+/**
+ * Delete recorded requests, optionally only those older than `before`.
+ * Returns how many were removed.
+ */
+export async function purgeAiAuditLog(workspaceRoot: string, before?: number): Promise<number> {
+    return invoke<number>('purge_ai_audit_log', { workspaceRoot, before: before ?? null });
+}
@@ -0,0 +1,56 @@
+/**
+ * AI Context Service
+ *
+ * TypeScript wrapper for the Rust context assembler, which picks the code
+ * around the cursor, definitions of nearby symbols, imported modules, and
+ * uncommitted changes for an AI prompt within a token budget.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type ContextBlockKind = 'focus' | 'symbol' | 'import' | 'change';
+
+export interface ContextBlock {
+    kind: ContextBlockKind;
+    /** Path relative to the workspace root */
+    path: string;
+    /** First line of `content` (1-based); null for diffs and outlines */
+    startLine: number | null;
+    endLine: number | null;
+    /** Symbol a `symbol` block defines */
+    symbol?: string;
+    content: string;
+    /** Estimated tokens of `content` */
+    tokens: number;
+    /** Cut short to fit the budget */
+    truncated: boolean;
+}
+
+export interface AiContext {
+    blocks: ContextBlock[];
+    totalTokens: number;
+    budgetTokens: number;
+    /** Candidate blocks left out for lack of budget */
+    omitted: number;
+    /** The blocks formatted as a prompt section */
+    rendered: string;
+}
+
+/**
+ * Gather the code most relevant to a cursor position for an AI prompt
+ */
+export async function assembleAiContext(
+    workspaceRoot: string,
+    focusFile: string,
+    cursorLine: number,
+    budgetTokens?: number,
+    content?: string
+): Promise<AiContext> {
+    return invoke<AiContext>('assemble_ai_context', {
+        workspaceRoot,
+        focusFile,
+        cursor: { line: cursorLine },
+        budgetTokens: budgetTokens ?? null,
+        content: content ?? null,
+    });
+}
@@ -0,0 +1,89 @@
+/**
+ * AI Edit Service
+ *
+ * TypeScript wrapper for validating and applying AI-proposed file edits.
+ * Edits are checked against the current file contents and previewed as
+ * diffs; applying writes every file or none of them.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface SearchReplaceBlock {
+    search: string;
+    replace: string;
+}
+
+export type AiFileEdit =
+    /** Each `search` must occur exactly once; an empty search on a missing file creates it */
+    | { kind: 'searchReplace'; path: string; blocks: SearchReplaceBlock[] }
+    /** Unified diff for one or more files, paths relative to the workspace */
+    | { kind: 'unifiedDiff'; diff: string };
+
+export type AiEditStatus = 'modified' | 'created' | 'deleted';
+
+export interface AiEditFilePreview {
+    /** Path relative to the workspace root */
+    path: string;
+    status: AiEditStatus;
+    /** Unified diff from the current contents to the edited ones */
+    diff: string;
+    additions: number;
+    deletions: number;
+    /** Hash of the current contents, to detect changes after the preview */
+    originalHash: string | null;
+}
+
+export interface AiEditError {
+    path: string;
+    message: string;
+}
+
+export interface AiEditPreview {
+    /** Whether every edit applies to the current contents */
+    applicable: boolean;
+    files: AiEditFilePreview[];
+    errors: AiEditError[];
+}
+
+export interface AiEditApplyResult {
+    /** Whether every file was written; nothing is written otherwise */
+    success: boolean;
+    files: AiEditFilePreview[];
+    errors: AiEditError[];
+}
+
+/**
+ * Check edits against the current files and return the resulting diffs
+ */
+export async function previewAiEdits(
+    workspaceRoot: string,
+    edits: AiFileEdit[]
+): Promise<AiEditPreview> {
+    return invoke<AiEditPreview>('preview_ai_edits', { workspaceRoot, edits });
+}
+
+/**
+ * Apply edits all or nothing. Pass the preview to refuse files that changed
+ * since it was taken. Rejects with a `dirtyFiles` JSON error
+ * when a file has unsaved changes, unless `force` is set.
+ */
+export async function applyAiEdits(
+    workspaceRoot: string,
+    edits: AiFileEdit[],
+    preview?: AiEditPreview,
+    force = false
+): Promise<AiEditApplyResult> {
+    const expectedHashes = preview
+        ? Object.fromEntries(
+              preview.files
+                  .filter((file) => file.originalHash !== null)
+                  .map((file) => [file.path, file.originalHash as string])
+          )
+        : null;
+    return invoke<AiEditApplyResult>('apply_ai_edits', {
+        workspaceRoot,
+        edits,
+        expectedHashes,
+        force,
+    });
+}
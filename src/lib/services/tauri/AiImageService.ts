@@ -0,0 +1,38 @@
+/**
+ * AI Image Service
+ *
+ * TypeScript wrapper for preparing workspace images as vision inputs. The
+ * backend downscales and re-encodes images that exceed the provider's limits.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AiImage {
+    path: string;
+    /** `image/png` or `image/jpeg` */
+    mimeType: string;
+    /** Base64 without a data URL prefix */
+    data: string;
+    width: number;
+    height: number;
+    originalWidth: number;
+    originalHeight: number;
+    /** Encoded size before base64 */
+    bytes: number;
+    /** Whether the image was downscaled or re-encoded */
+    converted: boolean;
+}
+
+/** Extensions the backend can decode */
+export const AI_IMAGE_EXTENSIONS = ['png', 'jpg', 'jpeg', 'gif', 'webp', 'bmp', 'tiff', 'ico'];
+
+/**
+ * Read a workspace image and fit it to the provider's vision input limits
+ */
+export async function prepareAiImage(
+    workspaceRoot: string,
+    path: string,
+    provider?: string
+): Promise<AiImage> {
+    return invoke<AiImage>('prepare_ai_image', { workspaceRoot, path, provider: provider ?? null });
+}
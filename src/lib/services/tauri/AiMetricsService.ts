@@ -0,0 +1,70 @@
+/**
+ * AI Metrics Service
+ *
+ * TypeScript wrapper for per provider/model latency and throughput of AI
+ * requests. Backend features record their own requests; streams run in the
+ * frontend report a sample once they finish.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AiRequestSample {
+    provider: string;
+    model: string;
+    /** Feature that made the request, e.g. `chat` */
+    feature: string;
+    /** Omitted when nothing was received */
+    timeToFirstTokenMs?: number;
+    durationMs: number;
+    outputTokens: number;
+    success: boolean;
+}
+
+export interface AiMetricSample {
+    /** Completion time (unix milliseconds) */
+    timestamp: number;
+    provider: string;
+    model: string;
+    feature: string;
+    timeToFirstTokenMs: number | null;
+    durationMs: number;
+    outputTokens: number;
+    /** Output tokens per second after the first token */
+    tokensPerSecond: number | null;
+    success: boolean;
+}
+
+export interface AiProviderMetrics {
+    provider: string;
+    model: string;
+    count: number;
+    errors: number;
+    averageTtftMs: number | null;
+    p50TtftMs: number | null;
+    p95TtftMs: number | null;
+    averageDurationMs: number;
+    averageTokensPerSecond: number | null;
+    outputTokens: number;
+    lastUsed: number;
+}
+
+export interface AiMetrics {
+    /** Aggregates per provider/model, most used first */
+    providers: AiProviderMetrics[];
+    /** Latest requests, newest first */
+    recent: AiMetricSample[];
+}
+
+/**
+ * Record a finished request that ran in the frontend
+ */
+export async function recordAiMetrics(sample: AiRequestSample): Promise<void> {
+    return invoke<void>('record_ai_metrics', { sample });
+}
+
+/**
+ * Latency and throughput per provider/model since startup
+ */
+export async function getAiMetrics(): Promise<AiMetrics> {
+    return invoke<AiMetrics>('ai_get_metrics');
+}
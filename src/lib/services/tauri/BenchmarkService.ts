@@ -0,0 +1,80 @@
+/**
+ * Benchmark Service
+ *
+ * TypeScript wrapper for the Rust benchmark runner: BenchmarkDotNet,
+ * `cargo bench`, and `vitest bench` suites with normalized results and
+ * comparisons against the previous run.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type BenchmarkFramework = 'benchmarkDotNet' | 'cargo' | 'vitest';
+
+export interface BenchmarkSuite {
+    /** Stable identifier, e.g. `dotnet:Benchmarks/Benchmarks.csproj` */
+    id: string;
+    framework: BenchmarkFramework;
+    label: string;
+    /** Project file for BenchmarkDotNet suites */
+    project: string | null;
+}
+
+export interface BenchmarkResult {
+    name: string;
+    meanNs: number;
+    stddevNs: number | null;
+    /** Bytes allocated per operation, where the framework measures it */
+    allocatedBytes: number | null;
+}
+
+export interface BenchmarkRun {
+    suiteId: string;
+    /** Start time (unix milliseconds) */
+    startedAt: number;
+    durationMs: number;
+    results: BenchmarkResult[];
+}
+
+export interface BenchmarkDelta {
+    name: string;
+    meanNs: number;
+    /** Mean in the previous run; null for new benchmarks */
+    previousMeanNs: number | null;
+    /** Relative change of the mean; positive is slower */
+    changePercent: number | null;
+    allocatedBytes: number | null;
+    previousAllocatedBytes: number | null;
+}
+
+export interface BenchmarkReport {
+    success: boolean;
+    run: BenchmarkRun;
+    deltas: BenchmarkDelta[];
+    /** Raw output of the benchmark process */
+    output: string;
+}
+
+/**
+ * List the benchmark suites in a workspace
+ */
+export async function discoverBenchmarks(workspaceRoot: string): Promise<BenchmarkSuite[]> {
+    return invoke<BenchmarkSuite[]>('discover_benchmarks', { workspaceRoot });
+}
+
+/**
+ * Run a suite, record its results, and compare them with the previous run
+ */
+export async function runBenchmarks(
+    workspaceRoot: string,
+    suiteId: string,
+    filter?: string
+): Promise<BenchmarkReport> {
+    return invoke<BenchmarkReport>('run_benchmarks', { workspaceRoot, suiteId, filter });
+}
+
+/**
+ * Recorded runs, most recent first
+ */
+export async function getBenchmarkHistory(workspaceRoot: string, suiteId?: string): Promise<BenchmarkRun[]> {
+    return invoke<BenchmarkRun[]>('get_benchmark_history', { workspaceRoot, suiteId });
+}
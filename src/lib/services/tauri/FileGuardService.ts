@@ -0,0 +1,23 @@
+/**
+ * File Guard Service
+ *
+ * TypeScript wrapper for the backend's record of files with unsaved editor
+ * changes. Destructive file operations and git checkouts that would touch one
+ * of them are refused with a `dirtyFiles` JSON error unless forced.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Replace the set of files with unsaved editor changes
+ */
+export async function setDirtyFiles(paths: string[]): Promise<void> {
+    return invoke('set_dirty_files', { paths });
+}
+
+/**
+ * Dirty files an operation on `paths` (files or directories) would affect
+ */
+export async function checkDirtyFiles(paths: string[]): Promise<string[]> {
+    return invoke<string[]>('check_dirty_files', { paths });
+}
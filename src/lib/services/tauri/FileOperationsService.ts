@@ -0,0 +1,70 @@
+/**
+ * File Operations Service
+ *
+ * TypeScript wrapper for explorer actions that go beyond the fs plugin:
+ * creating files from file templates, duplicating files and folders with the
+ * platform's copy names, and the path
+ * forms put on the clipboard by "Copy Relative Path" and "Copy POSIX Path",
+ * which work the same for drive paths and WSL shares.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+/** Copy naming convention: `name copy`, `name - Copy` or `name (copy)` */
+export type CopyNaming = 'macos' | 'windows' | 'linux';
+
+/** `native` uses `/` inside WSL workspaces and on Unix, `\` for other Windows paths */
+export type PathStyle = 'native' | 'posix' | 'windows';
+
+export interface CreatedFile {
+    path: string;
+    /** Pattern of the file template the file was populated from */
+    template: string | null;
+}
+
+export interface CreateFileOptions {
+    workspaceRoot?: string | null;
+    /** Initial content; takes precedence over templates */
+    content?: string;
+    /** Populate the file from its file template when no content is given */
+    applyTemplate?: boolean;
+}
+
+/**
+ * Create a new file. Fails if the file already exists.
+ */
+export async function createFile(path: string, options: CreateFileOptions = {}): Promise<CreatedFile> {
+    return invoke<CreatedFile>('create_file', {
+        path,
+        workspaceRoot: options.workspaceRoot ?? null,
+        content: options.content ?? null,
+        applyTemplate: options.applyTemplate ?? null,
+    });
+}
+
+/**
+ * Duplicate a file or folder next to itself and return the copy's path.
+ * Copies are named like the current platform's file manager names them.
+ */
+export async function duplicatePath(path: string, naming?: CopyNaming): Promise<string> {
+    return invoke<string>('duplicate_path', { path, naming: naming ?? null });
+}
+
+/**
+ * Path of a file relative to the workspace root
+ */
+export async function copyRelativePath(
+    path: string,
+    workspaceRoot: string,
+    style?: PathStyle
+): Promise<string> {
+    return invoke<string>('copy_relative_path', { path, workspaceRoot, style: style ?? null });
+}
+
+/**
+ * Path in POSIX form: `/mnt/<drive>/...` for drive paths, the path inside the
+ * distro for WSL shares
+ */
+export async function copyPosixPath(path: string): Promise<string> {
+    return invoke<string>('copy_posix_path', { path });
+}
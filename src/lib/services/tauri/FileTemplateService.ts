@@ -0,0 +1,62 @@
+/**
+ * File Template Service
+ *
+ * TypeScript wrapper for new-file boilerplate stored in
+ * `~/.fluxel/file-templates.json` and `<workspace>/.fluxel/file-templates.json`.
+ * Templates match an extension (`cs`) or a glob (`*.test.ts`, `src/**\/*.rs`)
+ * and fill in `{{filename}}`, `{{name}}`, `{{extension}}`, `{{namespace}}`,
+ * `{{date}}`, `{{year}}`, `{{author}}` and `{{email}}`.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type FileTemplateScope = 'user' | 'workspace';
+
+export interface FileTemplate {
+    /** Extension without the dot, or a glob; globs with a `/` match the workspace-relative path */
+    pattern: string;
+    description?: string | null;
+    content: string;
+}
+
+export interface FileTemplateSet {
+    workspace: FileTemplate[];
+    user: FileTemplate[];
+}
+
+export interface RenderedFileTemplate {
+    pattern: string;
+    scope: FileTemplateScope;
+    content: string;
+}
+
+/**
+ * List the workspace and user file templates, each in match order
+ */
+export async function listFileTemplates(workspaceRoot?: string | null): Promise<FileTemplateSet> {
+    return invoke<FileTemplateSet>('list_file_templates', { workspaceRoot: workspaceRoot ?? null });
+}
+
+/**
+ * Replace the file templates of one scope
+ */
+export async function saveFileTemplates(
+    scope: FileTemplateScope,
+    templates: FileTemplate[],
+    workspaceRoot?: string | null
+): Promise<void> {
+    return invoke('save_file_templates', { scope, templates, workspaceRoot: workspaceRoot ?? null });
+}
+
+/**
+ * Render the template a new file at `path` would be created with, if any
+ */
+export async function renderFileTemplate(
+    path: string,
+    workspaceRoot?: string | null
+): Promise<RenderedFileTemplate | null> {
+    return invoke<RenderedFileTemplate | null>('render_file_template', {
+        path,
+        workspaceRoot: workspaceRoot ?? null,
+    });
+}
@@ -0,0 +1,41 @@
+/**
+ * Grammar Check Service
+ *
+ * TypeScript wrapper for the optional AI spelling and grammar pass over commit
+ * messages and markdown. Suggestions come back in the diagnostics store's shape,
+ * with the replacement text attached.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { Diagnostic } from '@/stores/diagnostics/useDiagnosticsStore';
+import type { InlineProviderConfig } from './InlineCompletionService';
+
+export type GrammarTextKind = 'commitMessage' | 'markdown';
+
+export interface GrammarCheckRequest {
+    text: string;
+    kind: GrammarTextKind;
+    provider?: InlineProviderConfig;
+    /** Workspace whose dictionary terms are left alone */
+    workspaceRoot?: string;
+    /** File the text belongs to; defaults to the repository's COMMIT_EDITMSG */
+    filePath?: string;
+    /** 1-based position of `text` in the file, when checking a selection */
+    startLine?: number;
+    startColumn?: number;
+}
+
+export interface GrammarSuggestion extends Diagnostic {
+    source: 'ai-grammar';
+    code: 'spelling' | 'grammar' | 'style';
+    /** Text to replace the range with */
+    replacement: string;
+}
+
+/**
+ * Check a commit message or markdown selection. Resolves to an empty list for
+ * blank text; rejects when the provider fails or the text is too long.
+ */
+export async function checkGrammar(request: GrammarCheckRequest): Promise<GrammarSuggestion[]> {
+    return invoke<GrammarSuggestion[]>('ai_grammar_check', { request });
+}
@@ -0,0 +1,104 @@
+/**
+ * Health Service
+ *
+ * TypeScript wrapper for the editor health snapshot: memory of the app and its
+ * child processes, language server metrics, watchers, and cache sizes in one
+ * payload for the diagnostics page and bug reports.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { SpanSummary } from '@/types/profiling';
+
+export interface ProcessHealth {
+    pid: number;
+    /** Resident memory; null if the process exited or can't be read */
+    memoryBytes: number | null;
+}
+
+export interface LspMethodMetrics {
+    method: string;
+    count: number;
+    /** Requests answered with an error (including cancellations) */
+    errors: number;
+    averageMs: number;
+    /** Percentiles over the last 100 requests */
+    p50Ms: number;
+    p95Ms: number;
+    maxMs: number;
+    totalMs: number;
+}
+
+export interface LspServerMetrics {
+    server: string;
+    running: boolean;
+    pid: number | null;
+    uptimeMs: number | null;
+    memoryBytes: number | null;
+    totalRequests: number;
+    /** Requests sent and not yet answered */
+    pendingRequests: number;
+    oldestPendingMs: number | null;
+    /** Per-method statistics, by total time spent */
+    methods: LspMethodMetrics[];
+}
+
+export interface WatcherHealth {
+    /** Directories with an OS file watcher, shared by all subscribers */
+    watchedRoots: number;
+    moduleGraphWatches: number;
+    buildWatches: number;
+}
+
+export interface SearchIndexStatus {
+    root: string;
+    /** False while the initial build runs */
+    ready: boolean;
+    fileCount: number;
+    trigramCount: number;
+}
+
+export interface CacheHealth {
+    gitignoreWorkspaces: number;
+    buildConfigWorkspaces: number;
+    tsProjectGraphs: number;
+    searchIndexes: SearchIndexStatus[];
+}
+
+export interface HealthSnapshot {
+    /** Unix milliseconds */
+    generatedAt: number;
+    appVersion: string;
+    os: string;
+    arch: string;
+    process: ProcessHealth;
+    childProcesses: ProcessHealth[];
+    languageServers: LspServerMetrics[];
+    watchers: WatcherHealth;
+    caches: CacheHealth;
+    /** Outermost spans slower than 100ms, most recent first; profiling builds only */
+    slowOperations?: SpanSummary[];
+}
+
+/** One run of the app */
+export interface SessionRecord {
+    /** Unix milliseconds */
+    startedAt: number;
+    /** Unix milliseconds; null if the app didn't shut down cleanly */
+    endedAt: number | null;
+    /** Shutdown steps that ran out of time */
+    incompleteSteps: string[];
+}
+
+/**
+ * Snapshot of the running editor's health
+ */
+export async function getHealthSnapshot(): Promise<HealthSnapshot> {
+    return invoke<HealthSnapshot>('get_health_snapshot');
+}
+
+/**
+ * The previous run of the app, to tell whether it crashed or its shutdown timed out
+ */
+export async function getPreviousSession(): Promise<SessionRecord | null> {
+    return invoke<SessionRecord | null>('get_previous_session');
+}
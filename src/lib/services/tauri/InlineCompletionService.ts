@@ -0,0 +1,49 @@
+/**
+ * Inline Completion Service
+ *
+ * TypeScript wrapper for the Rust fill-in-the-middle endpoint used for ghost
+ * text. The backend debounces requests, cancels superseded ones, and caches
+ * results by context, so callers can invoke it on every keystroke.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type InlineProvider = 'ollama' | 'minimax';
+
+export interface InlineProviderConfig {
+    provider?: InlineProvider;
+    /** Ollama server URL or MiniMax API base */
+    endpoint?: string;
+    model?: string;
+    /** Required for MiniMax */
+    apiKey?: string;
+}
+
+export interface InlineCompletion {
+    /** Text to insert at the cursor; empty when there is no suggestion */
+    text: string;
+    /** Served from the context cache without a request */
+    cached: boolean;
+    /** A newer request superseded this one before it finished */
+    cancelled: boolean;
+    durationMs: number;
+}
+
+/**
+ * Complete the code between `prefix` and `suffix`
+ */
+export async function completeInline(
+    prefix: string,
+    suffix: string,
+    language: string,
+    maxTokens?: number,
+    provider?: InlineProviderConfig
+): Promise<InlineCompletion> {
+    return invoke<InlineCompletion>('ai_complete_inline', {
+        prefix,
+        suffix,
+        language,
+        maxTokens: maxTokens ?? null,
+        provider: provider ?? null,
+    });
+}
@@ -0,0 +1,60 @@
+/**
+ * Local History Service
+ *
+ * TypeScript wrapper for snapshots taken before files are overwritten, kept in
+ * `<workspace>/.fluxel/history/` so edits can be recovered without git.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface HistoryVersion {
+    id: string;
+    /** SHA-256 of the content */
+    hash: string;
+    size: number;
+    /** Snapshot time (unix milliseconds) */
+    timestamp: number;
+    /** What produced the snapshot, e.g. `"save"` or `"restore"` */
+    source: string;
+}
+
+/**
+ * Snapshot a file's current on-disk content. Call before overwriting it; resolves
+ * to `null` when the file doesn't exist yet, or its content is unchanged since the
+ * last snapshot or too large to keep.
+ */
+export async function recordLocalHistory(workspaceRoot: string, filePath: string): Promise<HistoryVersion | null> {
+    return invoke<HistoryVersion | null>('record_local_history', { workspaceRoot, filePath });
+}
+
+/**
+ * List the snapshots of a file, newest first
+ */
+export async function listLocalHistory(workspaceRoot: string, filePath: string): Promise<HistoryVersion[]> {
+    return invoke<HistoryVersion[]>('list_local_history', { workspaceRoot, filePath });
+}
+
+/**
+ * Read the content of a snapshot
+ */
+export async function readLocalHistoryVersion(
+    workspaceRoot: string,
+    filePath: string,
+    versionId: string
+): Promise<string> {
+    return invoke<string>('read_local_history_version', { workspaceRoot, filePath, versionId });
+}
+
+/**
+ * Overwrite a file with a snapshot. The replaced content is snapshotted first,
+ * so a restore can itself be undone. Refused for files with unsaved editor
+ * changes unless `force` is set.
+ */
+export async function restoreLocalHistoryVersion(
+    workspaceRoot: string,
+    filePath: string,
+    versionId: string,
+    force = false
+): Promise<HistoryVersion> {
+    return invoke<HistoryVersion>('restore_local_history_version', { workspaceRoot, filePath, versionId, force });
+}
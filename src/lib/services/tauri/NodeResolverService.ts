@@ -1,19 +1,50 @@
 import { invoke } from "@tauri-apps/api/core";
 
-export type ModuleFormat = "Esm" | "CommonJs" | "TypeDefinition" | "Unknown";
+/** `NodeBuiltin` modules (`fs`, `node:path`) have no resolved path and no package typings */
+export type ModuleFormat = "Esm" | "CommonJs" | "TypeDefinition" | "NodeBuiltin" | "Unknown";
 
 export interface ResolveOptions {
     conditions?: string[];
     extensions?: string[];
     preferCjs?: boolean;
+    /** `Browser` applies package.json `browser` field mappings, as bundlers do */
+    platform?: "Browser" | "Node";
+    /** Record the steps taken in `ResolveResponse.trace`, to explain a result */
+    trace?: boolean;
 }
 
+/** One step of a traced resolution, e.g. `{ File: { path, found } }` */
+export type ResolutionStep =
+    | { PackageDir: { path: string; found: boolean } }
+    | { File: { path: string; found: boolean } }
+    | { Entry: { field: "exports" | "imports"; subpath: string; matched: boolean } }
+    | { Condition: { name: string; matched: boolean } }
+    | { Field: { name: string; value: string } }
+    | { Fallback: { reason: string } };
+
 export interface ResolveResponse {
     resolved_path: string | null;
     format: ModuleFormat;
     matched_export: string | null;
     package_json: string | null;
     warnings: string[];
+    /** Set when resolved through a Yarn Plug'n'Play manifest */
+    pnp?: PnpResolution | null;
+    /** The browser field maps the module to `false`; bundlers substitute an empty module */
+    disabled?: boolean;
+    /** Steps taken, in order; empty unless `trace` was requested */
+    trace?: ResolutionStep[];
+}
+
+export interface PnpResolution {
+    manifest: string;
+    package_name: string;
+    reference: string;
+    package_location: string;
+    /** Cache archive holding the package; unset for unplugged packages */
+    zip_path: string | null;
+    /** Path of the requested module inside zip_path */
+    inner_path: string | null;
 }
 
 export interface TypingsResponse {
@@ -22,12 +53,138 @@ export interface TypingsResponse {
     package_json: string | null;
 }
 
+export interface BundleOptions {
+    /** Bundle the typings of one entry point, e.g. `auth` for `firebase/auth` */
+    subpath?: string | null;
+    /** Return each file's source along with its path */
+    include_content?: boolean;
+    /** Also return every file's source concatenated, in bundle order */
+    concatenate?: boolean;
+    /** Stop following references after this many files (default 2000) */
+    max_files?: number;
+}
+
+export interface TypingsFile {
+    path: string;
+    content: string | null;
+}
+
+export interface TypingsBundle {
+    package_name: string;
+    /** The declaration file the package's types start from */
+    entry: string | null;
+    /** Entry files first, then the files they reference, each once */
+    files: TypingsFile[];
+    /** Other packages the typings import or reference */
+    packages: string[];
+    package_json: string | null;
+    /** `max_files` was reached before every reference was followed */
+    truncated: boolean;
+    /** Every file's source under a `// <path>` header, with `concatenate` */
+    bundle: string | null;
+}
+
 export interface AnalyzeResponse {
     imports: string[];
     exports: string[];
     transformed: string;
 }
 
+export type ImportKind = "Import" | "ReExport" | "DynamicImport" | "Require";
+
+export interface GraphNode {
+    /** Resolved file path, or the specifier of a Node.js built-in */
+    id: string;
+    format: ModuleFormat;
+    /** Import hops from the entry file */
+    depth: number;
+    /** Inside node_modules or a Plug'n'Play cache; not walked unless `followPackages` is set */
+    external: boolean;
+    /** The file could not be read or parsed */
+    error: string | null;
+}
+
+export interface GraphEdge {
+    from: string;
+    to: string;
+    specifier: string;
+    kind: ImportKind;
+    type_only: boolean;
+}
+
+export interface UnresolvedImport {
+    importer: string;
+    specifier: string;
+    kind: ImportKind;
+    warnings: string[];
+}
+
+export interface ModuleGraph {
+    /** The entry file first, then in the order they were reached */
+    nodes: GraphNode[];
+    edges: GraphEdge[];
+    unresolved: UnresolvedImport[];
+    /** The depth or file limit stopped the walk before every file was parsed */
+    truncated: boolean;
+}
+
+export type DtsSymbolKind =
+    | "Interface"
+    | "TypeAlias"
+    | "Class"
+    | "Function"
+    | "Namespace"
+    | "Enum"
+    | "Variable"
+    | "AmbientModule";
+
+export interface DtsSymbol {
+    name: string;
+    kind: DtsSymbolKind;
+    /** Dotted path of the enclosing namespaces or ambient module */
+    container: string | null;
+    /** Visible to importers, or global */
+    exported: boolean;
+    /** Byte offset of the name */
+    start: number;
+}
+
+export interface UnusedExport {
+    /** Relative to the project root, `/`-separated */
+    file: string;
+    /** 1-based line of the exported name */
+    line: number;
+    name: string;
+    typeOnly: boolean;
+    /** Forwarded from another file with `export ... from` */
+    reExport: boolean;
+}
+
+export interface UnusedExportsReport {
+    filesAnalyzed: number;
+    /** Files whose exports count as used, e.g. package entries, configs and tests */
+    entryPoints: string[];
+    unused: UnusedExport[];
+    /** Files that couldn't be parsed */
+    errors: string[];
+}
+
+export interface TransformOptions {
+    /** Remove type annotations, interfaces and other TypeScript-only syntax */
+    strip_types?: boolean;
+    /** Module system to convert to; the module's own is kept when unset */
+    module?: "Esm" | "CommonJs" | null;
+}
+
+export interface GraphWalkOptions {
+    /** How many imports deep to follow (default 10) */
+    maxDepth?: number;
+    /** How many files to parse at most (default 500) */
+    maxFiles?: number;
+    followPackages?: boolean;
+    platform?: "Browser" | "Node";
+}
+
 export async function resolveNodeModule(
     specifier: string,
     importer: string,
@@ -42,19 +199,27 @@ export async function resolveNodeModule(
         conditions: options?.conditions,
         extensions: options?.extensions,
         preferCjs: options?.preferCjs,
+        platform: options?.platform,
+        trace: options?.trace,
     };
 
     return invoke<ResolveResponse>("resolve_node_module", payload);
 }
 
+/**
+ * Find the .d.ts files of a package, or of one of its entry points when `subpath`
+ * is given (e.g. `auth` for `firebase/auth`).
+ */
 export async function discoverPackageTypings(
     packageName: string,
-    projectRoot: string
+    projectRoot: string,
+    subpath?: string
 ): Promise<TypingsResponse> {
     // Tauri v2 expects camelCase from JS (auto-converts to snake_case in Rust)
     return invoke<TypingsResponse>("discover_package_typings", {
         packageName,
         projectRoot,
+        subpath: subpath ?? null,
     });
 }
 
@@ -66,6 +231,64 @@ export async function discoverTypingsForPackages(
     return Promise.all(tasks);
 }
 
-export async function analyzeModuleGraph(path: string): Promise<AnalyzeResponse> {
-    return invoke<AnalyzeResponse>("analyze_module_graph", { path });
+/**
+ * Collect the declaration files of a package by following `/// <reference>`
+ * directives and relative imports from its entry `.d.ts`
+ */
+export async function bundlePackageTypings(
+    packageName: string,
+    projectRoot: string,
+    options?: BundleOptions
+): Promise<TypingsBundle> {
+    return invoke<TypingsBundle>("bundle_typings", {
+        packageName,
+        projectRoot,
+        options: options ?? null,
+    });
+}
+
+export async function bundleTypingsForPackages(
+    packageNames: string[],
+    projectRoot: string,
+    options?: BundleOptions
+): Promise<TypingsBundle[]> {
+    const tasks = packageNames.map((pkg) => bundlePackageTypings(pkg, projectRoot, options));
+    return Promise.all(tasks);
+}
+
+/**
+ * Analyze the imports and exports of `path`. With `transform`, `transformed` is
+ * the module with types stripped or converted between ESM and CommonJS.
+ */
+export async function analyzeModuleGraph(
+    path: string,
+    transform?: TransformOptions
+): Promise<AnalyzeResponse> {
+    return invoke<AnalyzeResponse>("analyze_module_graph", { path, transform: transform ?? null });
+}
+
+/** Walk the transitive dependency graph of `path`, for rendering it */
+export async function walkModuleGraph(
+    path: string,
+    projectRoot?: string,
+    options?: GraphWalkOptions
+): Promise<ModuleGraph> {
+    return invoke<ModuleGraph>("walk_module_graph", {
+        path,
+        projectRoot: projectRoot ?? null,
+        maxDepth: options?.maxDepth,
+        maxFiles: options?.maxFiles,
+        followPackages: options?.followPackages,
+        platform: options?.platform,
+    });
+}
+
+/** Symbols declared in a .d.ts file, for priming completions */
+export async function extractDtsSymbols(path: string): Promise<DtsSymbol[]> {
+    return invoke<DtsSymbol[]>("extract_declaration_symbols", { path });
+}
+
+/** Exports no file in the project imports, following re-exports and path aliases */
+export async function analyzeUnusedExports(projectRoot: string): Promise<UnusedExportsReport> {
+    return invoke<UnusedExportsReport>("analyze_unused_exports", { projectRoot });
 }
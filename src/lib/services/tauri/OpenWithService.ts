@@ -0,0 +1,48 @@
+/**
+ * Open With Service
+ *
+ * TypeScript wrapper for revealing files in Explorer/Finder/the file manager
+ * and opening them with an external application. The handler chosen for an
+ * extension is remembered in the settings store (`openWithHandlers`).
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface OpenWithHandler {
+    /** Opaque ID to pass to `openWithHandler` */
+    id: string;
+    name: string;
+    /** The application the system opens the file with by default */
+    isDefault: boolean;
+}
+
+/**
+ * Show a file in the system file manager, selected
+ */
+export async function revealInFileManager(path: string): Promise<void> {
+    return invoke('reveal_in_file_manager', { path });
+}
+
+/**
+ * List the applications registered to open a file, the default one first
+ */
+export async function listOpenWithHandlers(path: string): Promise<OpenWithHandler[]> {
+    return invoke<OpenWithHandler[]>('list_open_with_handlers', { path });
+}
+
+/**
+ * Open a file with a handler from `listOpenWithHandlers`, or with the system
+ * default when `handlerId` is omitted
+ */
+export async function openWithHandler(path: string, handlerId?: string | null): Promise<void> {
+    return invoke('open_with_handler', { path, handlerId: handlerId ?? null });
+}
+
+/**
+ * The settings key for a file's extension, as used by `openWithHandlers`
+ */
+export function openWithExtension(path: string): string {
+    const name = path.split(/[\\/]/).pop() ?? '';
+    const dot = name.lastIndexOf('.');
+    return dot > 0 ? name.slice(dot + 1).toLowerCase() : '';
+}
@@ -0,0 +1,37 @@
+/**
+ * Package Info Service
+ *
+ * TypeScript wrapper for local npm package metadata shown in hover tooltips
+ * on import lines: installed version, license, description, homepage,
+ * declared dependencies, and whether the lockfile pins a newer version than
+ * the one in node_modules. Nothing is fetched from the registry.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PackageInfo {
+    name: string;
+    /** Version installed in node_modules */
+    version: string | null;
+    license: string | null;
+    description: string | null;
+    /** `homepage`, or the repository URL when there is none */
+    homepage: string | null;
+    /** Range the project's package.json asks for */
+    requested: string | null;
+    dependencies: Record<string, string>;
+    peerDependencies: Record<string, string>;
+    /** Versions pinned by the lockfile, oldest first */
+    lockedVersions: string[];
+    /** Newest locked version, when it is newer than the installed one */
+    newerVersion: string | null;
+    installPath: string | null;
+}
+
+/**
+ * Get what is known locally about a package. Accepts bare import specifiers
+ * such as `lodash/fp` or `@scope/pkg/sub`.
+ */
+export async function getPackageInfo(packageName: string, projectRoot: string): Promise<PackageInfo> {
+    return invoke<PackageInfo>('get_package_info', { packageName, projectRoot });
+}
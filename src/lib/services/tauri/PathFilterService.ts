@@ -0,0 +1,29 @@
+/**
+ * Path Filter Service
+ *
+ * TypeScript wrapper for per-folder workspace exclusions. The backend applies
+ * them to text search, the search index, file watchers, and repository stats,
+ * on top of a built-in list of dependency and build output directories
+ * (`node_modules`, `dist`, `target`, ...).
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PathFilterConfig {
+    /** `.gitignore`-style patterns to skip */
+    exclude: string[];
+    /** Patterns to scan even though a built-in directory or `exclude` covers them */
+    include: string[];
+    /** Skip what `.gitignore` files ignore (default: true) */
+    respectGitignore: boolean;
+}
+
+/**
+ * Set the exclusions of a workspace folder
+ */
+export async function setPathFilterConfig(
+    workspaceRoot: string,
+    config: PathFilterConfig
+): Promise<void> {
+    return invoke('set_path_filter_config', { workspaceRoot, config });
+}
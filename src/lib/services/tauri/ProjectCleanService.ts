@@ -0,0 +1,55 @@
+/**
+ * Project Clean Service
+ *
+ * TypeScript wrapper for the Rust project clean command, which removes build
+ * output (`bin/`, `obj/`, `target/`) and tool caches for the ecosystems
+ * detected in a workspace.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type CleanTarget = 'dotnet' | 'node' | 'cargo';
+
+export interface CleanOptions {
+    /** Ecosystems to clean; every detected one when omitted */
+    targets?: CleanTarget[];
+    /** Only report what would be removed */
+    dryRun?: boolean;
+}
+
+export interface CleanEntry {
+    target: CleanTarget;
+    path: string;
+    bytes: number;
+    removed: boolean;
+}
+
+export interface CleanReport {
+    dryRun: boolean;
+    entries: CleanEntry[];
+    /** Reclaimable space in a dry run, freed space otherwise */
+    totalBytes: number;
+    dotnetCleanOutput: string | null;
+    errors: string[];
+}
+
+/**
+ * Clean build output and caches, or report what a clean would remove.
+ */
+export async function cleanProject(workspaceRoot: string, options: CleanOptions = {}): Promise<CleanReport> {
+    return invoke<CleanReport>('clean_project', { workspaceRoot, options });
+}
+
+/**
+ * Human-readable size, e.g. `1.4 GB`.
+ */
+export function formatCleanSize(bytes: number): string {
+    const units = ['B', 'KB', 'MB', 'GB', 'TB'];
+    let value = bytes;
+    let unit = 0;
+    while (value >= 1024 && unit < units.length - 1) {
+        value /= 1024;
+        unit++;
+    }
+    return `${unit === 0 ? value : value.toFixed(1)} ${units[unit]}`;
+}
@@ -0,0 +1,52 @@
+/**
+ * Run Configuration Service
+ *
+ * TypeScript wrapper for the workspace run configurations stored in
+ * `.fluxel/launch.json`. Launching runs the configuration's pre-launch task
+ * chain before starting the program in a terminal.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface RunConfiguration {
+    name: string;
+    command: string;
+    args: string[];
+    /** Relative to the workspace root */
+    cwd?: string | null;
+    /** Extra environment variables; values may use `${secret:NAME}` */
+    env: Record<string, string>;
+    /** Task to run first, by label or runnable id */
+    preLaunchTask?: string | null;
+}
+
+export type PreLaunchTaskPhase = 'started' | 'output' | 'succeeded' | 'failed';
+
+/** Payload of `run-config://task` events */
+export interface PreLaunchTaskEvent {
+    configuration: string;
+    task: string;
+    phase: PreLaunchTaskPhase;
+    /** Output line, on `output` */
+    data: string | null;
+    stderr: boolean;
+    /** Exit code, on `succeeded` and `failed` */
+    code: number | null;
+}
+
+/**
+ * List the run configurations of a workspace
+ */
+export async function listRunConfigurations(workspaceRoot: string): Promise<RunConfiguration[]> {
+    return invoke<RunConfiguration[]>('list_run_configurations', { workspaceRoot });
+}
+
+/**
+ * Replace the run configurations of a workspace
+ */
+export async function saveRunConfigurations(
+    workspaceRoot: string,
+    configurations: RunConfiguration[]
+): Promise<void> {
+    await invoke('save_run_configurations', { workspaceRoot, configurations });
+}
@@ -0,0 +1,43 @@
+/**
+ * Runnables Service
+ *
+ * TypeScript wrapper for the Rust runnables catalog: package.json scripts,
+ * `.fluxel/tasks.json` tasks, .NET projects, Cargo targets, and framework
+ * dev-server presets in one list for the "Run…" palette.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type RunnableSource = 'script' | 'task' | 'dotnet' | 'cargo' | 'preset';
+
+export type RunnableGroup = 'build' | 'test' | 'run' | 'other';
+
+export interface Runnable {
+    /** Stable across refreshes, e.g. `script:dev` */
+    id: string;
+    source: RunnableSource;
+    group: RunnableGroup;
+    label: string;
+    detail: string | null;
+    command: string;
+    args: string[];
+    /** Absolute working directory */
+    cwd: string;
+}
+
+/**
+ * List everything runnable in the workspace.
+ */
+export async function listRunnables(workspaceRoot: string): Promise<Runnable[]> {
+    return invoke<Runnable[]>('list_runnables', { workspaceRoot });
+}
+
+/**
+ * Command line for running a runnable in the terminal, with arguments
+ * containing spaces quoted.
+ */
+export function runnableCommandLine(runnable: Runnable): string {
+    return [runnable.command, ...runnable.args]
+        .map((part) => (/\s/.test(part) ? `"${part}"` : part))
+        .join(' ');
+}
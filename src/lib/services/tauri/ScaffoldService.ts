@@ -0,0 +1,74 @@
+/**
+ * Scaffold Service
+ *
+ * TypeScript wrapper for creating new projects from templates: built-in ones
+ * that run `dotnet new`, `bun create vite` or `cargo new`, and user templates
+ * kept in `~/.fluxel/templates/<id>/`, whose `{{variable}}` placeholders are
+ * filled in as the files are copied.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { ProjectProfile } from '@/types/project';
+
+export type TemplateSource = 'builtin' | 'user';
+
+export interface TemplateVariable {
+    name: string;
+    description?: string | null;
+    /** Value used when none is given; variables without one are required */
+    default?: string | null;
+}
+
+export interface ProjectTemplate {
+    id: string;
+    name: string;
+    description: string | null;
+    source: TemplateSource;
+    variables: TemplateVariable[];
+}
+
+export interface ScaffoldOutput {
+    /** Destination of the project being created */
+    dest: string;
+    /** `stdout`/`stderr` for generator output, `fluxel` for copied files */
+    stream: 'stdout' | 'stderr' | 'fluxel';
+    line: string;
+}
+
+export interface ScaffoldResult {
+    /** Profile detected for the new project */
+    profile: ProjectProfile;
+    durationMs: number;
+}
+
+/**
+ * List the built-in templates followed by the user templates
+ */
+export async function listTemplates(): Promise<ProjectTemplate[]> {
+    return invoke<ProjectTemplate[]>('list_templates');
+}
+
+/**
+ * Create a project in `dest`, which must not exist or be empty. The `name`
+ * variable defaults to the folder's name.
+ */
+export async function createProject(
+    template: string,
+    dest: string,
+    variables?: Record<string, string>,
+    onOutput?: (output: ScaffoldOutput) => void
+): Promise<ScaffoldResult> {
+    const unlisten = onOutput
+        ? await listen<ScaffoldOutput>('scaffold://output', (event) => {
+              if (event.payload.dest === dest) {
+                  onOutput(event.payload);
+              }
+          })
+        : null;
+    try {
+        return await invoke<ScaffoldResult>('create_project', { template, dest, variables });
+    } finally {
+        unlisten?.();
+    }
+}
@@ -0,0 +1,46 @@
+/**
+ * Structured Output Service
+ *
+ * TypeScript wrapper for chat requests whose reply must be JSON matching a
+ * schema. The backend uses each provider's structured-output mechanism,
+ * validates the reply, and asks the model to repair invalid answers.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { InlineProviderConfig } from './InlineCompletionService';
+
+export interface ChatTurn {
+    role: 'system' | 'user' | 'assistant';
+    content: string;
+}
+
+export interface StructuredChatRequest {
+    provider?: InlineProviderConfig;
+    messages: ChatTurn[];
+    /** JSON Schema the reply must satisfy */
+    responseSchema: Record<string, unknown>;
+    /** Repair prompts sent after invalid replies (default 2, at most 5) */
+    maxRepairs?: number;
+    temperature?: number;
+    maxTokens?: number;
+}
+
+export interface StructuredChatResponse<T = unknown> {
+    /** The validated reply */
+    value: T;
+    /** Reply text the value was parsed from */
+    raw: string;
+    /** Requests made, including repairs */
+    attempts: number;
+    model: string;
+}
+
+/**
+ * Run a chat request and return its reply parsed and validated against the schema.
+ * Rejects when the model can't produce a valid reply within the repair budget.
+ */
+export async function structuredChat<T = unknown>(
+    request: StructuredChatRequest
+): Promise<StructuredChatResponse<T>> {
+    return invoke<StructuredChatResponse<T>>('ai_structured_chat', { request });
+}
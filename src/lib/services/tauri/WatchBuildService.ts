@@ -0,0 +1,74 @@
+/**
+ * Watch Build Service
+ *
+ * TypeScript wrapper for the Rust build watcher, which rebuilds the workspace
+ * when its sources change and cancels builds made stale by newer changes.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { BuildDiagnostic } from '@/lib/languages/csharp';
+
+export type WatchBuildSystem = 'dotnet' | 'node' | 'cargo';
+
+export type WatchBuildPhase = 'started' | 'cancelled' | 'finished' | 'failed';
+
+export interface WatchBuildEvent {
+    watchId: string;
+    phase: WatchBuildPhase;
+    /** Changed files that triggered the build */
+    changedPaths: string[];
+    success: boolean | null;
+    durationMs: number | null;
+    /** Build output, on `finished` */
+    output: string | null;
+    /** Parsed diagnostics, on `finished` (.NET builds only) */
+    diagnostics: BuildDiagnostic[];
+    error: string | null;
+}
+
+let active: { watchId: string; unlisten: UnlistenFn } | null = null;
+
+/**
+ * Whether a build watch is running
+ */
+export function isWatchingBuild(): boolean {
+    return active !== null;
+}
+
+/**
+ * Rebuild `workspaceRoot` on every source change, replacing any running watch.
+ */
+export async function startWatchBuild(
+    workspaceRoot: string,
+    buildSystem: WatchBuildSystem,
+    onEvent: (event: WatchBuildEvent) => void
+): Promise<string> {
+    await stopWatchBuild();
+
+    let watchId: string | null = null;
+    const unlisten = await listen<WatchBuildEvent>('watch-build://lifecycle', (event) => {
+        if (event.payload.watchId === watchId) {
+            onEvent(event.payload);
+        }
+    });
+    try {
+        watchId = await invoke<string>('watch_build', { workspaceRoot, buildSystem });
+    } catch (error) {
+        unlisten();
+        throw error;
+    }
+    active = { watchId, unlisten };
+    return watchId;
+}
+
+/**
+ * Stop the running build watch, cancelling its build
+ */
+export async function stopWatchBuild(): Promise<void> {
+    if (!active) return;
+    const { watchId, unlisten } = active;
+    active = null;
+    unlisten();
+    await invoke('unwatch_build', { watchId });
+}
@@ -0,0 +1,70 @@
+/**
+ * Workspace Archive Service
+ *
+ * TypeScript wrapper for packing a workspace folder into a `.zip` or `.tar.gz`,
+ * for "Share project" and bug reproductions. The folder's path filter decides
+ * what goes in, so dependencies, build output and ignored files stay out.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export interface ArchiveOptions {
+    /** Pack the `.git` directory too (default: false) */
+    includeGit?: boolean;
+    /** `.gitignore`-style patterns to leave out on top of the folder's exclusions */
+    extraExcludes?: string[];
+}
+
+export interface ArchiveEstimate {
+    files: number;
+    /** Total size before compression */
+    bytes: number;
+}
+
+export interface ArchiveProgress {
+    filesWritten: number;
+    totalFiles: number;
+    bytesWritten: number;
+    totalBytes: number;
+}
+
+export interface ArchiveResult {
+    path: string;
+    files: number;
+    /** Total size before compression */
+    bytes: number;
+    archiveBytes: number;
+}
+
+/**
+ * Count the files an export would pack and their size before compression
+ */
+export async function estimateWorkspaceArchive(
+    root: string,
+    options?: ArchiveOptions
+): Promise<ArchiveEstimate> {
+    return invoke<ArchiveEstimate>('estimate_workspace_archive', { root, options });
+}
+
+/**
+ * Pack `root` into `dest`, whose extension (`.zip`, `.tar.gz` or `.tgz`) picks
+ * the format
+ */
+export async function exportWorkspaceArchive(
+    root: string,
+    dest: string,
+    options?: ArchiveOptions,
+    onProgress?: (progress: ArchiveProgress) => void
+): Promise<ArchiveResult> {
+    const unlisten = onProgress
+        ? await listen<ArchiveProgress>('workspace://archive-progress', (event) => {
+              onProgress(event.payload);
+          })
+        : null;
+    try {
+        return await invoke<ArchiveResult>('export_workspace_archive', { root, dest, options });
+    } finally {
+        unlisten?.();
+    }
+}
@@ -13,14 +13,93 @@ export {
     resolveNodeModule,
     discoverPackageTypings,
     discoverTypingsForPackages,
+    bundlePackageTypings,
+    bundleTypingsForPackages,
     analyzeModuleGraph,
+    analyzeUnusedExports,
     type ModuleFormat,
     type ResolveOptions,
     type ResolveResponse,
+    type ResolutionStep,
+    type PnpResolution,
     type AnalyzeResponse,
+    type TransformOptions,
+    type BundleOptions,
+    type TypingsBundle,
+    type TypingsFile,
+    type UnusedExport,
+    type UnusedExportsReport,
 } from './NodeResolverService';
 
 // Child process lifecycle management
 export * from './ProcessManager';
 export { ProcessManager, default as ProcessManagerDefault } from './ProcessManager';
 
+// Runnable scripts, tasks, and project targets
+export * from './RunnablesService';
+
+// Build output and cache cleanup
+export * from './ProjectCleanService';
+
+// Rebuild on source changes
+export * from './WatchBuildService';
+
+// Run configurations with pre-launch tasks
+export * from './RunConfigService';
+
+// Benchmark suites and run history
+export * from './BenchmarkService';
+
+// Fill-in-the-middle ghost text completions
+export * from './InlineCompletionService';
+
+// Opt-in audit log of AI requests
+export * from './AiAuditService';
+
+// Relevant code for AI prompts
+export * from './AiContextService';
+
+// Validated, all-or-nothing AI edits
+export * from './AiEditService';
+
+// AI request latency and throughput
+export * from './AiMetricsService';
+
+// Vision inputs for AI providers
+export * from './AiImageService';
+
+// Schema-validated AI replies
+export * from './StructuredOutputService';
+
+// AI spelling and grammar suggestions
+export * from './GrammarCheckService';
+
+// Editor health snapshot for diagnostics
+export * from './HealthService';
+
+// Workspace exclusions for search, indexing, watchers, and stats
+export * from './PathFilterService';
+
+// Zip or tar.gz export of a workspace folder
+export * from './WorkspaceArchiveService';
+
+// New projects from built-in and user templates
+export * from './ScaffoldService';
+
+// Reveal in file manager and open with external applications
+export * from './OpenWithService';
+
+// New files, duplicates and copyable path forms
+export * from './FileOperationsService';
+
+// Per-extension and per-glob boilerplate for new files
+export * from './FileTemplateService';
+
+// Installed and locked package metadata for import hovers
+export * from './PackageInfoService';
+
+// Snapshots of saved files
+export * from './LocalHistoryService';
+
+// Unsaved-changes guard for destructive operations
+export * from './FileGuardService';
@@ -30,6 +30,7 @@ describe('new file templates', () => {
                     package_manager: null,
                 },
                 build_system_hint: 'dotnet',
+                wsl: null,
             },
             detectedProjects: [],
         });
@@ -24,6 +24,8 @@ export interface ChatMessage {
     toolResults?: ToolResult[];
     isStreaming?: boolean;
     thinking?: string; // MiniMax thinking blocks
+    servedBy?: FailoverEntry; // Provider/model that produced this response
+    images?: string[]; // Paths of images attached to a user message
 }
 
 export interface AgentConversation {
@@ -53,6 +55,7 @@ export interface AgentState {
     isGenerating: boolean;
     streamingContent: string;
     streamingThinking: string; // MiniMax thinking stream
+    streamingStats: StreamingStats | null; // Live throughput of the current stream
     attachedContext: FileContext[];
 
     // Settings
@@ -72,6 +75,7 @@ export interface AgentState {
     providerConfigs: Record<string, ProviderConfig>;
     models: ModelConfig[];
     activeModelId: string;
+    failoverChain: FailoverEntry[]; // Tried in order after the active model
 }
 
 export interface ModelConfig {
@@ -81,6 +85,17 @@ export interface ModelConfig {
     enabled: boolean;
 }
 
+export interface StreamingStats {
+    timeToFirstTokenMs?: number;
+    tokensPerSecond?: number;
+    outputTokens: number;
+}
+
+export interface FailoverEntry {
+    provider: ProviderType;
+    model: string;
+}
+
 export interface ProviderConfig {
     id: string; // "ollama", "minimax"
     name: string;
@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
-import type { AgentState, ChatMessage, FileContext, AgentConversation, ProviderType, ModelConfig, ProviderConfig } from './types';
+import type { AgentState, ChatMessage, FileContext, AgentConversation, ProviderType, ModelConfig, ProviderConfig, FailoverEntry, StreamingStats } from './types';
 import { getAvailableModels } from '@/lib/ollama/ollamaChatClient';
 import { getAvailableMinimaxModels } from '@/lib/minimax';
 
@@ -26,6 +26,7 @@ interface AgentActions {
     setStreamingContent: (content: string) => void;
     appendStreamingThinking: (content: string) => void;
     setStreamingThinking: (content: string) => void;
+    setStreamingStats: (stats: StreamingStats | null) => void;
 
     // Context
     attachFile: (file: FileContext) => void;
@@ -45,6 +46,7 @@ interface AgentActions {
     toggleSettings: (section?: string) => void;
     updateProviderConfig: (providerId: string, config: Partial<ProviderConfig>) => void;
     toggleModelEnabled: (modelId: string, enabled: boolean) => void;
+    setFailoverChain: (chain: FailoverEntry[]) => void;
 }
 
 const generateId = () => Math.random().toString(36).substring(2, 11);
@@ -66,6 +68,7 @@ export const useAgentStore = create<AgentState & AgentActions>()(
             isGenerating: false,
             streamingContent: '',
             streamingThinking: '',
+            streamingStats: null,
             attachedContext: [],
 
             // Legacy/Compat
@@ -82,6 +85,7 @@ export const useAgentStore = create<AgentState & AgentActions>()(
             },
             models: [],
             activeModelId: 'qwen2.5-coder:1.5b',
+            failoverChain: [],
 
             temperature: 0.7,
             maxTurns: 15,
@@ -108,6 +112,8 @@ export const useAgentStore = create<AgentState & AgentActions>()(
                 models: state.models.map(m => m.id === modelId ? { ...m, enabled } : m)
             })),
 
+            setFailoverChain: (chain) => set({ failoverChain: chain }),
+
             // Model actions
             fetchModels: async () => {
                 const { providerConfigs } = get();
@@ -255,8 +261,12 @@ export const useAgentStore = create<AgentState & AgentActions>()(
                 set({ streamingThinking: content });
             },
 
+            setStreamingStats: (stats: StreamingStats | null) => {
+                set({ streamingStats: stats });
+            },
+
             clearStreaming: () => {
-                set({ streamingContent: '', streamingThinking: '' });
+                set({ streamingContent: '', streamingThinking: '', streamingStats: null });
             },
 
             // Context actions
@@ -316,6 +326,7 @@ export const useAgentStore = create<AgentState & AgentActions>()(
                 providerConfigs: state.providerConfigs,
                 models: state.models,
                 activeModelId: state.activeModelId,
+                failoverChain: state.failoverChain,
                 activeConversationId: state.activeConversationId,
                 conversations: state.conversations,
                 temperature: state.temperature,
@@ -1,28 +1,71 @@
 import { create } from 'zustand';
+import { listen } from '@tauri-apps/api/event';
 import { getProjectConfigurations, BuildConfiguration } from '@/lib/languages/csharp';
 import { getCSharpLSPClient } from '@/lib/languages/csharp/CSharpLSPClient';
-import { choosePreferredBuildConfiguration } from '@/lib/languages/csharp/workspaceConfiguration';
+import {
+    choosePreferredBuildConfiguration,
+    choosePreferredSolution,
+    listWorkspaceSolutions,
+} from '@/lib/languages/csharp/workspaceConfiguration';
 import { FrontendProfiler } from '@/lib/services';
 import { useProjectSettingsStore } from '@/stores/project/useProjectSettingsStore';
 
+interface SolutionChanged {
+    workspaceRoot: string;
+    paths: string[];
+}
+
 interface CSharpStore {
     configurations: BuildConfiguration[];
     selectedConfiguration: string | null;
     lastLoadedWorkspace: string | null;
     /** Loading state for configuration fetching */
     isLoadingConfigs: boolean;
+    /** Solutions in the workspace, or its projects when it has none */
+    solutions: string[];
+    selectedSolution: string | null;
+    listenersInitialized: boolean;
 
     setConfigurations: (configs: BuildConfiguration[]) => void;
     setSelectedConfiguration: (config: string | null) => void;
+    setSelectedSolution: (solution: string) => void;
     loadProjectConfigurations: (workspaceRoot: string) => Promise<void>;
+    loadSolutions: (workspaceRoot: string) => Promise<void>;
+    initListeners: () => Promise<void>;
     reset: () => void;
 }
 
+function normalizeRoot(path: string): string {
+    return path.replace(/\\/g, '/').replace(/\/+$/, '');
+}
+
+/**
+ * Restart the C# language server if it is running for the workspace, so it
+ * picks up a changed configuration or solution
+ */
+async function reloadLanguageServer(workspaceRoot: string, reason: string): Promise<void> {
+    const lspClient = getCSharpLSPClient();
+    if (lspClient.getWorkspaceRoot() !== workspaceRoot || !lspClient.getIsStarted()) {
+        return;
+    }
+
+    try {
+        await lspClient.stop();
+        await lspClient.start(workspaceRoot);
+        await lspClient.initialize(workspaceRoot);
+    } catch (error) {
+        console.error(`[CSharp] Failed to reload LSP for ${reason}:`, error);
+    }
+}
+
 export const useCSharpStore = create<CSharpStore>((set, get) => ({
     configurations: [],
     selectedConfiguration: null,
     lastLoadedWorkspace: null,
     isLoadingConfigs: false,
+    solutions: [],
+    selectedSolution: null,
+    listenersInitialized: false,
 
     setConfigurations: (configs) => set({ configurations: configs }),
 
@@ -38,20 +81,22 @@ export const useCSharpStore = create<CSharpStore>((set, get) => ({
             selectedBuildConfiguration: config,
         });
 
-        const lspClient = getCSharpLSPClient();
-        if (!config || lspClient.getWorkspaceRoot() !== workspaceRoot || !lspClient.getIsStarted()) {
+        if (config) {
+            void reloadLanguageServer(workspaceRoot, 'configuration change');
+        }
+    },
+
+    setSelectedSolution: (solution) => {
+        const workspaceRoot = get().lastLoadedWorkspace;
+        if (!workspaceRoot || solution === get().selectedSolution) {
             return;
         }
 
-        void (async () => {
-            try {
-                await lspClient.stop();
-                await lspClient.start(workspaceRoot);
-                await lspClient.initialize(workspaceRoot);
-            } catch (error) {
-                console.error('[CSharp] Failed to reload LSP for configuration change:', error);
-            }
-        })();
+        set({ selectedSolution: solution });
+        useProjectSettingsStore.getState().setSettings(workspaceRoot, {
+            selectedSolution: solution,
+        });
+        void reloadLanguageServer(workspaceRoot, 'solution change');
     },
 
     loadProjectConfigurations: async (workspaceRoot) => {
@@ -65,6 +110,8 @@ export const useCSharpStore = create<CSharpStore>((set, get) => ({
 
         // Set loading state
         set({ isLoadingConfigs: true });
+        void get().initListeners();
+        void get().loadSolutions(workspaceRoot);
 
         const span = FrontendProfiler.startSpan('load_project_configurations', 'frontend_network');
 
@@ -153,10 +200,53 @@ export const useCSharpStore = create<CSharpStore>((set, get) => ({
         }
     },
 
+    loadSolutions: async (workspaceRoot) => {
+        try {
+            const solutions = await listWorkspaceSolutions(workspaceRoot);
+            const savedSolution = useProjectSettingsStore.getState()
+                .getSettings(workspaceRoot)
+                .selectedSolution;
+            const selectedSolution = choosePreferredSolution(solutions, savedSolution);
+
+            set({ solutions, selectedSolution });
+            if (selectedSolution !== savedSolution) {
+                useProjectSettingsStore.getState().setSettings(workspaceRoot, { selectedSolution });
+            }
+        } catch (error) {
+            console.error('[CSharp] Failed to list solutions:', error);
+            set({ solutions: [], selectedSolution: null });
+        }
+    },
+
+    initListeners: async () => {
+        if (get().listenersInitialized) return;
+        set({ listenersInitialized: true });
+
+        // The backend reports when the loaded solution or the set of solutions
+        // and projects changes on disk, e.g. after switching branches
+        await listen<SolutionChanged>('csharp://solution-changed', async (event) => {
+            const workspaceRoot = get().lastLoadedWorkspace;
+            if (!workspaceRoot || normalizeRoot(workspaceRoot) !== normalizeRoot(event.payload.workspaceRoot)) {
+                return;
+            }
+
+            console.log('[CSharp] Solution files changed, reloading language server:', event.payload.paths);
+            await get().loadSolutions(workspaceRoot);
+            await reloadLanguageServer(workspaceRoot, 'solution files change');
+        });
+    },
+
     reset: () => {
         if (import.meta.env.DEV) {
             console.log('[CSharp] Resetting store');
         }
-        set({ configurations: [], selectedConfiguration: null, lastLoadedWorkspace: null, isLoadingConfigs: false });
+        set({
+            configurations: [],
+            selectedConfiguration: null,
+            lastLoadedWorkspace: null,
+            isLoadingConfigs: false,
+            solutions: [],
+            selectedSolution: null,
+        });
     },
 }));
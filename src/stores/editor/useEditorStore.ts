@@ -1,7 +1,7 @@
 import { create } from 'zustand';
 import { readTextFile, writeTextFile } from '@tauri-apps/plugin-fs';
 import { getFileExtension, getFileName, getLanguageFromExtension } from '@/types/fs';
-import { FrontendProfiler } from '@/lib/services';
+import { FrontendProfiler, recordLocalHistory, setDirtyFiles } from '@/lib/services';
 import { useWorkbenchStore } from '../workbench/useWorkbenchStore';
 
 export interface EditorTab {
@@ -335,6 +335,15 @@ export const useEditorStore = create<EditorState>((set, get) => ({
             if (!tab) return;
 
             try {
+                // Snapshot the content about to be overwritten for local history
+                const { useFileSystemStore } = await import('./useFileSystemStore');
+                const { rootPath } = useFileSystemStore.getState();
+                if (rootPath) {
+                    await recordLocalHistory(rootPath, tab.path).catch((error) => {
+                        console.error('Failed to record local history:', tab.path, error);
+                    });
+                }
+
                 await FrontendProfiler.profileAsync('writeTextFile', 'file_io', async () => {
                     await writeTextFile(tab.path, tab.content);
                 }, { path: tab.path, size: tab.content.length.toString() });
@@ -389,3 +398,20 @@ export const useEditorStore = create<EditorState>((set, get) => ({
 
     clearPendingAction: () => set({ pendingAction: null }),
 }));
+
+// Keep the backend's unsaved-changes guard in sync with the dirty tabs
+let syncedDirtyPaths: string | null = null;
+useEditorStore.subscribe((state, prevState) => {
+    if (state.tabs === prevState.tabs) return;
+
+    const dirtyPaths = [...new Set(
+        state.tabs.filter((t) => t.content !== t.originalContent).map((t) => t.path)
+    )];
+    const key = dirtyPaths.join('\n');
+    if (key === syncedDirtyPaths) return;
+    syncedDirtyPaths = key;
+
+    setDirtyFiles(dirtyPaths).catch((error) => {
+        console.error('Failed to update dirty files:', error);
+    });
+});
@@ -1,9 +1,9 @@
 import { invoke } from '@tauri-apps/api/core';
 import { create } from 'zustand';
-import { readDir, readTextFile, writeTextFile, remove, mkdir, rename, copyFile } from '@tauri-apps/plugin-fs';
+import { readDir, readTextFile, remove, mkdir, rename, copyFile } from '@tauri-apps/plugin-fs';
 import type { FileEntry } from '@/types/fs';
 import { GitignoreManager } from '@/lib/utils/GitIgnore';
-import { FrontendProfiler } from '@/lib/services';
+import { FrontendProfiler, createFile as createFileOnDisk } from '@/lib/services';
 
 type BackendDirEntry = {
     name: string;
@@ -41,7 +41,7 @@ interface FileSystemState {
     _pathToEntryMap: Map<string, FileEntry> | null;
 
     // File operations
-    /** Create a new file at the given path */
+    /** Create a new file at the given path; without content, its file template is applied */
     createFile: (parentPath: string, fileName: string, content?: string) => Promise<string | null>;
     /** Create a new folder at the given path */
     createFolder: (parentPath: string, folderName: string) => Promise<string | null>;
@@ -493,8 +493,12 @@ export const useFileSystemStore = create<FileSystemState>((set, get) => ({
             const normalizedParent = parentPath.replace(/\\/g, '/');
             const filePath = `${normalizedParent}/${fileName}`;
             
-            // Create file with optional template content
-            await writeTextFile(filePath, content);
+            // Blank files get the user's file template for their name, if any
+            await createFileOnDisk(filePath, {
+                workspaceRoot: get().rootPath,
+                content: content || undefined,
+                applyTemplate: !content,
+            });
             
             // Refresh the parent folder to show the new file
             await get().refreshFolder(normalizedParent);
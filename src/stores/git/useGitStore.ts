@@ -9,6 +9,8 @@ export interface GitFileStatus {
 export interface GitStatusResult {
     branch: string;
     files: GitFileStatus[];
+    /** History is truncated by a shallow clone or fetch */
+    shallow: boolean;
 }
 
 interface GitState {
@@ -37,12 +37,14 @@ store.migrateSettings();
 // Settings have correct shape
 const buildConfig: string | null = settings.selectedBuildConfiguration;
 const startupProject: string | null = settings.selectedStartupProject;
+const solution: string | null = settings.selectedSolution;
 const excludedPaths: string[] = settings.excludedPaths;
 
 console.log('✅ useProjectSettingsStore type verification passed!', {
 	settings,
 	settingsAliasCheck,
 	buildConfig,
+	solution,
 	startupProject,
 	excludedPaths,
 });
@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import { setPathFilterConfig } from '@/lib/services/tauri/PathFilterService';
 
 /**
  * Per-project settings for C# and other project-specific configuration.
@@ -11,10 +12,16 @@ export interface ProjectSettings {
 	selectedBuildConfiguration: string | null;
 	/** Selected startup project path for solutions with multiple projects */
 	selectedStartupProject: string | null;
+	/** Solution (or project, when there is no solution) loaded by the language server */
+	selectedSolution: string | null;
 
 	/** General per-project settings */
 	/** Paths to exclude from file tree and search */
 	excludedPaths: string[];
+	/** Paths to scan even though a built-in directory (e.g. `dist`) or `excludedPaths` covers them */
+	includedPaths: string[];
+	/** Skip what `.gitignore` files ignore in search, indexing, and stats */
+	respectGitignore: boolean;
 }
 
 interface ProjectSettingsState {
@@ -59,7 +66,10 @@ interface ProjectSettingsState {
 const DEFAULT_PROJECT_SETTINGS: ProjectSettings = {
 	selectedBuildConfiguration: null,
 	selectedStartupProject: null,
+	selectedSolution: null,
 	excludedPaths: [],
+	includedPaths: [],
+	respectGitignore: true,
 };
 
 /**
@@ -70,6 +80,23 @@ function normalizeWorkspaceRoot(path: string): string {
 	return path.replace(/\\/g, '/').trim();
 }
 
+/** Settings that feed the backend's path filter */
+const PATH_FILTER_KEYS: (keyof ProjectSettings)[] = ['excludedPaths', 'includedPaths', 'respectGitignore'];
+
+/**
+ * Send a workspace's exclusions to the backend, which applies them to search,
+ * indexing, file watchers, and repository stats.
+ */
+function syncPathFilter(workspaceRoot: string, settings: ProjectSettings): void {
+	setPathFilterConfig(workspaceRoot, {
+		exclude: settings.excludedPaths,
+		include: settings.includedPaths,
+		respectGitignore: settings.respectGitignore,
+	}).catch((error) => {
+		console.error('[ProjectSettings] Failed to sync path filter:', error);
+	});
+}
+
 export const useProjectSettingsStore = create<ProjectSettingsState>()(
 	persist(
 		(set, get) => ({
@@ -106,6 +133,10 @@ export const useProjectSettingsStore = create<ProjectSettingsState>()(
 						...settings,
 					};
 
+					if (PATH_FILTER_KEYS.some((key) => key in settings)) {
+						syncPathFilter(normalized, updated);
+					}
+
 					return {
 						settingsByProject: {
 							...state.settingsByProject,
@@ -117,6 +148,7 @@ export const useProjectSettingsStore = create<ProjectSettingsState>()(
 
 			clearSettings: (workspaceRoot: string) => {
 				const normalized = normalizeWorkspaceRoot(workspaceRoot);
+				syncPathFilter(normalized, DEFAULT_PROJECT_SETTINGS);
 
 				set((state) => {
 					const { [normalized]: _, ...rest } = state.settingsByProject;
@@ -174,6 +206,9 @@ export const useProjectSettingsStore = create<ProjectSettingsState>()(
 			onRehydrateStorage: () => (state) => {
 				if (state) {
 					state.migrateSettings();
+					for (const [root, settings] of Object.entries(useProjectSettingsStore.getState().settingsByProject)) {
+						syncPathFilter(root, settings);
+					}
 				}
 			},
 		}
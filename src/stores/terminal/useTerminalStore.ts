@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import type { PreLaunchTaskEvent } from '@/lib/services/tauri/RunConfigService';
 
 // ============================================================================
 // Types
@@ -8,16 +9,52 @@ import { listen } from '@tauri-apps/api/event';
 
 export type TerminalEntryType = 'command' | 'output' | 'error' | 'info';
 
+/**
+ * Clickable file location or URL in an entry, found by the backend
+ */
+export interface TerminalLink {
+    /** Offset of the link text in the entry content */
+    start: number;
+    /** End offset (exclusive) of the link text */
+    end: number;
+    kind: 'file' | 'url';
+    /** Absolute file path or URL */
+    target: string;
+    line: number | null;
+    column: number | null;
+}
+
+/**
+ * Run of output text sharing one ANSI style. Colors are theme names
+ * (`red`, `brightBlue`, ...) or `#rrggbb`.
+ */
+export interface TerminalSegment {
+    text: string;
+    fg?: string;
+    bg?: string;
+    bold?: boolean;
+    dim?: boolean;
+    italic?: boolean;
+    underline?: boolean;
+    strikethrough?: boolean;
+}
+
 export interface TerminalEntry {
     id: string;
     type: TerminalEntryType;
     content: string;
     timestamp: number;
+    links?: TerminalLink[];
+    /** Styling of `content`, for output that used ANSI colors */
+    segments?: TerminalSegment[];
 }
 
 interface TerminalOutput {
     pid: number;
     data: string;
+    /** Redraw of the previous line of the same stream, such as a progress bar */
+    replace: boolean;
+    segments?: TerminalSegment[];
 }
 
 interface TerminalExit {
@@ -25,6 +62,19 @@ interface TerminalExit {
     code: number | null;
 }
 
+/**
+ * Command recorded by the backend for the workspace
+ */
+export interface TerminalHistoryEntry {
+    id: string;
+    command: string;
+    cwd: string | null;
+    /** Unix milliseconds */
+    startedAt: number;
+    finishedAt: number | null;
+    exitCode: number | null;
+}
+
 export type TerminalColor = 'default' | 'red' | 'green' | 'blue' | 'yellow' | 'purple' | 'cyan';
 
 export interface TerminalInstance {
@@ -38,6 +88,16 @@ export interface TerminalInstance {
     currentCommand: string | null;
     activePid: number | null;
     createdAt: number;
+    /** WSL distro commands run in, or null for the host shell */
+    wslDistro: string | null;
+    /** SSH host alias (from ~/.ssh/config) commands run on, or null for local */
+    sshHost: string | null;
+}
+
+/** Where a new terminal runs its commands; local when empty */
+export interface TerminalTarget {
+    wslDistro?: string;
+    sshHost?: string;
 }
 
 export type TerminalLayout = 'single' | 'split-horizontal' | 'split-vertical';
@@ -53,7 +113,7 @@ export interface TerminalState {
     listenersInitialized: boolean;
     
     // Terminal management
-    createTerminal: (name?: string) => string;
+    createTerminal: (name?: string, target?: TerminalTarget) => string;
     closeTerminal: (id: string) => void;
     setActiveTerminal: (id: string) => void;
     renameTerminal: (id: string, name: string) => void;
@@ -64,11 +124,17 @@ export interface TerminalState {
     setSplitTerminal: (id: string | null) => void;
     
     // Terminal operations (operate on active terminal)
-    addEntry: (terminalId: string, type: TerminalEntryType, content: string) => void;
+    addEntry: (terminalId: string, type: TerminalEntryType, content: string, segments?: TerminalSegment[]) => void;
     clearTerminal: (terminalId?: string) => void;
     executeCommand: (terminalId: string, commandString: string) => Promise<void>;
+    /** Run a configuration's pre-launch tasks, then its program, in a terminal */
+    launchRunConfiguration: (terminalId: string, name: string) => Promise<void>;
     killProcess: (terminalId?: string) => Promise<void>;
     setHistoryIndex: (terminalId: string, index: number) => void;
+    /** Seed a terminal's up-arrow history with the workspace's past commands */
+    loadHistory: (terminalId: string) => Promise<void>;
+    /** Search the workspace's command history, most recent first */
+    searchHistory: (filter?: string, limit?: number) => Promise<TerminalHistoryEntry[]>;
     
     // Legacy compatibility
     initListeners: () => Promise<void>;
@@ -106,7 +172,83 @@ function parseCommand(input: string): string[] {
     return args;
 }
 
-function createTerminalInstance(name?: string): TerminalInstance {
+/** Progress redraws replace their line; colors arrive as segments */
+const OUTPUT_OPTIONS = { coalesceCarriageReturns: true, ansi: 'parse' } as const;
+
+/** Past commands loaded into a new terminal's up-arrow history */
+const HISTORY_SEED_LIMIT = 200;
+
+/** Output lines are sent for link detection in batches collected over this delay */
+const LINK_DETECTION_DELAY_MS = 100;
+
+/** Cheap check for text that might contain a path or URL */
+const LINK_HINT = /[\\/]|\(\d|:\d/;
+
+const pendingLinkEntries = new Map<string, { id: string; content: string }[]>();
+let linkDetectionTimer: ReturnType<typeof setTimeout> | null = null;
+
+function queueLinkDetection(terminalId: string, entryId: string, content: string) {
+    if (!LINK_HINT.test(content)) return;
+
+    const pending = pendingLinkEntries.get(terminalId) ?? [];
+    pending.push({ id: entryId, content });
+    pendingLinkEntries.set(terminalId, pending);
+    linkDetectionTimer ??= setTimeout(() => void detectPendingLinks(), LINK_DETECTION_DELAY_MS);
+}
+
+/**
+ * Detect links for the queued entries, one backend call per terminal
+ */
+async function detectPendingLinks() {
+    linkDetectionTimer = null;
+    const batches = [...pendingLinkEntries];
+    pendingLinkEntries.clear();
+
+    const { useFileSystemStore } = await import('../editor/useFileSystemStore');
+    const cwd = useFileSystemStore.getState().rootPath;
+    if (!cwd) return;
+
+    for (const [terminalId, entries] of batches) {
+        try {
+            const links = await invoke<TerminalLink[]>('detect_terminal_links', {
+                text: entries.map((entry) => entry.content).join('\n'),
+                cwd,
+            });
+            if (links.length === 0) continue;
+
+            // Split the links back onto the entries they fall in
+            const linksByEntry = new Map<string, TerminalLink[]>();
+            let offset = 0;
+            for (const entry of entries) {
+                const end = offset + entry.content.length;
+                const entryLinks = links
+                    .filter((link) => link.start >= offset && link.end <= end)
+                    .map((link) => ({ ...link, start: link.start - offset, end: link.end - offset }));
+                if (entryLinks.length > 0) {
+                    linksByEntry.set(entry.id, entryLinks);
+                }
+                offset = end + 1;
+            }
+
+            useTerminalStore.setState((state) => ({
+                terminals: state.terminals.map(t =>
+                    t.id === terminalId
+                        ? {
+                            ...t,
+                            entries: t.entries.map(e =>
+                                linksByEntry.has(e.id) ? { ...e, links: linksByEntry.get(e.id) } : e
+                            ),
+                        }
+                        : t
+                ),
+            }));
+        } catch (error) {
+            console.error('[Terminal] Link detection failed:', error);
+        }
+    }
+}
+
+function createTerminalInstance(name?: string, target?: TerminalTarget): TerminalInstance {
     return {
         id: crypto.randomUUID(),
         name: name || 'Terminal',
@@ -118,6 +260,8 @@ function createTerminalInstance(name?: string): TerminalInstance {
         currentCommand: null,
         activePid: null,
         createdAt: Date.now(),
+        wslDistro: target?.wslDistro ?? null,
+        sshHost: target?.sshHost ?? null,
     };
 }
 
@@ -136,20 +280,21 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
     // Terminal Management
     // ========================================================================
     
-    createTerminal: (name?: string) => {
-        const terminal = createTerminalInstance(name);
+    createTerminal: (name?: string, target?: TerminalTarget) => {
+        const terminal = createTerminalInstance(name, target);
         const { terminals } = get();
         
         // Auto-name with number if not provided
         if (!name) {
             const count = terminals.length + 1;
-            terminal.name = `Terminal ${count}`;
+            terminal.name = `${target?.sshHost ?? target?.wslDistro ?? 'Terminal'} ${count}`;
         }
         
         set((state) => ({
             terminals: [...state.terminals, terminal],
             activeTerminalId: terminal.id,
         }));
+        void get().loadHistory(terminal.id);
         
         return terminal.id;
     },
@@ -239,7 +384,8 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
     // Terminal Operations
     // ========================================================================
 
-    addEntry: (terminalId: string, type: TerminalEntryType, content: string) => {
+    addEntry: (terminalId: string, type: TerminalEntryType, content: string, segments?: TerminalSegment[]) => {
+        const id = crypto.randomUUID();
         set((state) => ({
             terminals: state.terminals.map(t =>
                 t.id === terminalId
@@ -248,16 +394,21 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
                         entries: [
                             ...t.entries,
                             {
-                                id: crypto.randomUUID(),
+                                id,
                                 type,
                                 content,
                                 timestamp: Date.now(),
+                                segments,
                             },
                         ],
                     }
                     : t
             ),
         }));
+
+        if (type === 'output' || type === 'error') {
+            queueLinkDetection(terminalId, id, content);
+        }
     },
 
     clearTerminal: (terminalId?: string) => {
@@ -279,6 +430,44 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         }));
     },
 
+    loadHistory: async (terminalId: string) => {
+        const entries = await get().searchHistory(undefined, HISTORY_SEED_LIMIT);
+        if (entries.length === 0) return;
+
+        // Oldest first, without immediate repeats
+        const past: string[] = [];
+        for (const entry of [...entries].reverse()) {
+            if (past[past.length - 1] !== entry.command) {
+                past.push(entry.command);
+            }
+        }
+
+        set((state) => ({
+            terminals: state.terminals.map(t =>
+                t.id === terminalId
+                    ? { ...t, history: [...past, ...t.history], historyIndex: -1 }
+                    : t
+            ),
+        }));
+    },
+
+    searchHistory: async (filter?: string, limit?: number) => {
+        const { useFileSystemStore } = await import('../editor/useFileSystemStore');
+        const workspaceRoot = useFileSystemStore.getState().rootPath;
+        if (!workspaceRoot) return [];
+
+        try {
+            return await invoke<TerminalHistoryEntry[]>('get_terminal_history', {
+                workspaceRoot,
+                filter: filter ?? null,
+                limit: limit ?? null,
+            });
+        } catch (error) {
+            console.error('[Terminal] Failed to load command history:', error);
+            return [];
+        }
+    },
+
     killProcess: async (terminalId?: string) => {
         const id = terminalId || get().activeTerminalId;
         if (!id) return;
@@ -354,6 +543,11 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
                 command: commandName,
                 args: commandArgs,
                 cwd: projectRoot,
+                // Lets ${secret:NAME} references resolve against the workspace's secrets
+                workspaceRoot: projectRoot,
+                output: OUTPUT_OPTIONS,
+                wslDistro: terminal.wslDistro,
+                sshHost: terminal.sshHost,
             });
 
             set((state) => ({
@@ -373,6 +567,59 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
         }
     },
 
+    launchRunConfiguration: async (terminalId: string, name: string) => {
+        const { addEntry, initListeners, terminals } = get();
+        if (!terminals.some(t => t.id === terminalId)) return;
+
+        const { useFileSystemStore } = await import('../editor/useFileSystemStore');
+        const workspaceRoot = useFileSystemStore.getState().rootPath;
+        if (!workspaceRoot) return;
+
+        await initListeners();
+        set((state) => ({
+            terminals: state.terminals.map(t =>
+                t.id === terminalId ? { ...t, isRunning: true, currentCommand: name } : t
+            ),
+        }));
+        addEntry(terminalId, 'command', `Launch: ${name}`);
+
+        const unlisten = await listen<PreLaunchTaskEvent>('run-config://task', (event) => {
+            const task = event.payload;
+            if (task.configuration !== name) return;
+            switch (task.phase) {
+                case 'started':
+                    addEntry(terminalId, 'info', `> Pre-launch task: ${task.task}`);
+                    break;
+                case 'output':
+                    addEntry(terminalId, task.stderr ? 'error' : 'output', task.data ?? '');
+                    break;
+                case 'failed':
+                    addEntry(terminalId, 'error', `Task '${task.task}' exited with code ${task.code ?? 'unknown'}`);
+                    break;
+            }
+        });
+
+        try {
+            const pid = await invoke<number>('launch_run_configuration', { workspaceRoot, name });
+            set((state) => ({
+                terminals: state.terminals.map(t =>
+                    t.id === terminalId ? { ...t, activePid: pid } : t
+                ),
+            }));
+        } catch (error) {
+            set((state) => ({
+                terminals: state.terminals.map(t =>
+                    t.id === terminalId
+                        ? { ...t, isRunning: false, currentCommand: null, activePid: null }
+                        : t
+                ),
+            }));
+            addEntry(terminalId, 'error', `Launch failed: ${error instanceof Error ? error.message : String(error)}`);
+        } finally {
+            unlisten();
+        }
+    },
+
     // ========================================================================
     // Listeners
     // ========================================================================
@@ -380,22 +627,41 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
     initListeners: async () => {
         if (get().listenersInitialized) return;
 
-        await listen<TerminalOutput>('terminal://output', (event) => {
-            const { pid, data } = event.payload;
-            const { terminals, addEntry } = get();
-            const terminal = terminals.find(t => t.activePid === pid);
-            if (terminal) {
-                addEntry(terminal.id, 'output', data);
+        const handleOutput = (type: 'output' | 'error', output: TerminalOutput) => {
+            const terminal = get().terminals.find(t => t.activePid === output.pid);
+            if (!terminal) return;
+
+            const previous = output.replace
+                ? [...terminal.entries].reverse().find(e => e.type === type)
+                : undefined;
+            if (!previous) {
+                get().addEntry(terminal.id, type, output.data, output.segments);
+                return;
             }
+
+            set((state) => ({
+                terminals: state.terminals.map(t =>
+                    t.id === terminal.id
+                        ? {
+                            ...t,
+                            entries: t.entries.map(e =>
+                                e.id === previous.id
+                                    ? { ...e, content: output.data, segments: output.segments, links: undefined }
+                                    : e
+                            ),
+                        }
+                        : t
+                ),
+            }));
+            queueLinkDetection(terminal.id, previous.id, output.data);
+        };
+
+        await listen<TerminalOutput>('terminal://output', (event) => {
+            handleOutput('output', event.payload);
         });
 
         await listen<TerminalOutput>('terminal://stderr', (event) => {
-            const { pid, data } = event.payload;
-            const { terminals, addEntry } = get();
-            const terminal = terminals.find(t => t.activePid === pid);
-            if (terminal) {
-                addEntry(terminal.id, 'error', data);
-            }
+            handleOutput('error', event.payload);
         });
 
         await listen<TerminalExit>('terminal://exit', (event) => {
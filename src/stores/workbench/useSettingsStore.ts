@@ -375,6 +375,13 @@ export interface SettingsState {
     // Version Control
     githubToken: string;
 
+    // Language Servers: settings per language ID, sent as initializationOptions
+    // and workspace/didChangeConfiguration
+    serverSettings: Record<string, Record<string, unknown>>;
+
+    // Open With: external application handler ID per file extension (without the dot)
+    openWithHandlers: Record<string, string>;
+
     // Setters - Appearance
     setTheme: (theme: Theme) => void;
     setAccentColor: (color: AccentColor) => void;
@@ -441,6 +448,12 @@ export interface SettingsState {
     setBuildSystem: (system: BuildSystem) => void;
     setCustomBuildCommand: (command: string) => void;
     setGithubToken: (token: string) => void;
+
+    // Setters - Language Servers
+    setServerSettings: (languageId: string, settings: Record<string, unknown>) => void;
+
+    // Setters - Open With (null forgets the choice)
+    setOpenWithHandler: (extension: string, handlerId: string | null) => void;
     initAppearance: () => void;
 }
 
@@ -518,6 +531,12 @@ export const useSettingsStore = create<SettingsState>()(
             // VC defaults
             githubToken: '',
 
+            // Language server defaults
+            serverSettings: {},
+
+            // Open With defaults
+            openWithHandlers: {},
+
             // Setters - Appearance
             setTheme: (theme) => {
                 const root = window.document.documentElement;
@@ -603,6 +622,18 @@ export const useSettingsStore = create<SettingsState>()(
             setCustomBuildCommand: (customBuildCommand) => set({ customBuildCommand }),
             setGithubToken: (githubToken) => set({ githubToken }),
 
+            // Setters - Language Servers
+            setServerSettings: (languageId, settings) => set((state) => ({
+                serverSettings: { ...state.serverSettings, [languageId]: settings },
+            })),
+
+            // Setters - Open With
+            setOpenWithHandler: (extension, handlerId) => set((state) => {
+                const key = extension.replace(/^\./, '').toLowerCase();
+                const { [key]: _, ...rest } = state.openWithHandlers;
+                return { openWithHandlers: handlerId ? { ...rest, [key]: handlerId } : rest };
+            }),
+
             initAppearance: () => {
                 const state = get();
                 const root = window.document.documentElement;
@@ -100,6 +100,7 @@ export function getLanguageFromExtension(extension: string): string {
         'gql': 'graphql',
         'vue': 'vue',
         'svelte': 'svelte',
+        'astro': 'astro',
         'go': 'go',
         'java': 'java',
         'kt': 'kotlin',
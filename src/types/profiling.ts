@@ -25,6 +25,7 @@ export type SpanCategory =
     | 'frontend_interaction'
     | 'frontend_network'
     | 'backend_operation'
+    | 'ai_request'
     | 'other';
 
 /**
@@ -95,6 +96,45 @@ export interface ProfilerStatus {
     bufferCapacity: number;
     /** Active session ID, if any */
     activeSessionId: string | null;
+    /** Span sampling configuration */
+    sampling: SamplingConfig;
+    /** Sampling counters since the last clear */
+    samplingStats: SamplingStats;
+}
+
+/**
+ * Restricts span queries to one workspace and/or file.
+ * Spans inherit the `workspace_root` and `file` fields of their ancestors.
+ */
+export interface SpanFilter {
+    /** Workspace root path */
+    workspace?: string;
+    /** File path, absolute or workspace-relative */
+    file?: string;
+}
+
+/**
+ * Span sampling configuration, to bound overhead in long sessions.
+ */
+export interface SamplingConfig {
+    /** Whether sampling is applied; when off every span is recorded */
+    enabled: boolean;
+    /** Record 1 in N span trees below the slow threshold */
+    sampleRate: number;
+    /** Spans at least this long are always recorded */
+    slowThresholdMs: number;
+}
+
+/**
+ * What sampling did since the last clear.
+ */
+export interface SamplingStats {
+    /** Spans dropped because they were neither sampled, slow, nor errors */
+    droppedSpans: number;
+    /** Unsampled spans kept because they were slow */
+    keptSlow: number;
+    /** Unsampled spans kept because they recorded an error */
+    keptError: number;
 }
 
 /**
@@ -143,6 +183,68 @@ export interface AttributionReport {
     hotspots: SpanSummary[];
     /** Hierarchical tree structure for flame graph visualization */
     tree: SpanTreeNode | null;
+    /** Time and tokens spent in AI requests, if the operation made any */
+    aiUsage: AiUsageSummary | null;
+    /** Top spans by bytes read and written, then by files touched */
+    ioHotspots: SpanIo[];
+    /** Spans that held the IPC thread or an async worker past the blocking threshold, longest first */
+    blockingSpans: BlockingSpan[];
+}
+
+/**
+ * A span that blocked a thread other commands depend on, typically synchronous
+ * I/O in an async command.
+ */
+export interface BlockingSpan {
+    span: SpanSummary;
+    /** Name of the blocked thread */
+    thread: string;
+    threadKind: 'ipc' | 'async_worker';
+    /** Longest stretch the span held the thread; for async spans, the longest poll */
+    longestPollMs: number;
+    busyMs: number;
+}
+
+/**
+ * I/O recorded on a span through its bytes_* / files_* fields.
+ */
+export interface SpanIo {
+    span: SpanSummary;
+    bytesRead: number;
+    bytesWritten: number;
+    filesRead: number;
+    filesWritten: number;
+    /** Self time, to compare with the I/O volume */
+    selfTimeMs: number;
+    /** Low throughput on a slow span points at CPU rather than disk */
+    throughputBytesPerSec: number;
+}
+
+/**
+ * Time and token usage of one provider/model.
+ */
+export interface AiModelUsage {
+    provider: string;
+    model: string;
+    requestCount: number;
+    totalTimeMs: number;
+    inputTokens: number;
+    outputTokens: number;
+}
+
+/**
+ * How much of an operation's latency and token cost went to AI requests.
+ */
+export interface AiUsageSummary {
+    requestCount: number;
+    /** Wall-clock time spent waiting on models */
+    totalTimeMs: number;
+    /** Share of the operation's total time */
+    percentage: number;
+    inputTokens: number;
+    outputTokens: number;
+    /** Per provider/model breakdown, slowest first */
+    models: AiModelUsage[];
 }
 
 // =============================================================================
@@ -187,6 +289,32 @@ export interface SessionReport {
     topSpans: SpanSummary[];
     /** Total duration of all spans (may exceed session duration due to overlap) */
     totalSpanTimeMs: number;
+    /** Time and tokens spent in AI requests during the session, if any */
+    aiUsage: AiUsageSummary | null;
+}
+
+/**
+ * Cold start breakdown, finalized when the frontend signals ready.
+ */
+export interface StartupReport {
+    /** Time from profiler installation until the frontend signaled ready */
+    totalMs: number;
+    /** Phases that were recorded, in the order they started */
+    phases: StartupPhase[];
+    /** Aggregates over every span recorded during startup */
+    session: SessionReport;
+}
+
+/**
+ * A timed part of startup: state_init, tauri_setup, first_workspace_scan or first_lsp_start.
+ */
+export interface StartupPhase {
+    name: string;
+    /** The span that timed the phase, for attribution */
+    spanId: string;
+    /** Start time relative to the beginning of startup */
+    startMs: number;
+    durationMs: number;
 }
 
 // =============================================================================
@@ -211,10 +339,12 @@ export interface ProfilerApi {
     // Basic commands
     /** Enable or disable profiling */
     setEnabled: (enabled: boolean) => Promise<void>;
+    /** Configure span sampling */
+    setSampling: (config: SamplingConfig) => Promise<void>;
     /** Get current profiler status */
     getStatus: () => Promise<ProfilerStatus>;
     /** Get recent spans */
-    getRecentSpans: (limit?: number) => Promise<SpanSummary[]>;
+    getRecentSpans: (limit?: number, filter?: SpanFilter) => Promise<SpanSummary[]>;
     /** Get attribution report for a span tree */
     getAttribution: (rootSpanId: string) => Promise<AttributionReport>;
     /** Clear all stored spans */
@@ -224,7 +354,13 @@ export interface ProfilerApi {
     /** Start a named profiling session */
     startSession: (name: string) => Promise<string>;
     /** End a session and get the report */
-    endSession: (sessionId: string) => Promise<SessionReport>;
+    endSession: (sessionId: string, filter?: SpanFilter) => Promise<SessionReport>;
+
+    // Startup
+    /** Finalize the startup session once the frontend is ready */
+    finishStartup: () => Promise<StartupReport | null>;
+    /** Get the cold start breakdown */
+    getStartupReport: () => Promise<StartupReport | null>;
 
     // Frontend span recording
     /** Record a span from the frontend */
@@ -14,11 +14,20 @@ export interface NodeInfo {
   package_manager: PackageManager | null;
 }
 
+/** Workspace opened from a WSL distro share (`\\wsl.localhost\<distro>\...`) */
+export interface WslWorkspace {
+  distro: string;
+  /** Workspace root inside the distro */
+  linux_root: string;
+}
+
 export interface ProjectProfile {
   root_path: string;
   kind: ProjectKind;
   dotnet: DotnetInfo;
   node: NodeInfo;
   build_system_hint: string | null;
+  /** Set for WSL-rooted workspaces; terminals and builds run inside the distro */
+  wsl: WslWorkspace | null;
 }
 